@@ -0,0 +1,282 @@
+//! Performance benchmarks on generated synthetic documents.
+//!
+//! Run with `cargo bench --all-features`.
+//! Benchmarks can be filtered by name (e.g. `cargo bench -- load`).
+//!
+//! The following environment variables configure the generated document:
+//!
+//! * `FBXCEL_BENCH_MODELS`: number of mesh models (default: 500).
+//! * `FBXCEL_BENCH_GRID`: number of quads per mesh side (default: 8).
+//! * `FBXCEL_BENCH_FRAMES`: number of animation keys per curve (default: 60).
+//!
+//! To check regressions, save a baseline with
+//! `cargo bench --all-features -- --save-baseline <name>` and compare with it
+//! later with `cargo bench --all-features -- --baseline <name>`.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use fbxcel_dom::{
+    fbxcel::tree::v7400::Tree,
+    v7400::{
+        data::mesh::{layer::MappingMode, LayerElementCache, PolygonVertexIndex, PolygonVertices},
+        edit::{
+            CurveBuilder, DocumentBuilder, Interpolation, MeshBuilder, ModelKind, KTIME_PER_SECOND,
+        },
+        object::{geometry::TypedGeometryHandle, TypedObjectHandle},
+        Document, Loader,
+    },
+};
+
+/// Synthetic document parameters.
+#[derive(Debug, Clone, Copy)]
+struct Params {
+    /// Number of mesh models.
+    models: usize,
+    /// Number of quads per mesh side.
+    grid: usize,
+    /// Number of animation keys per curve.
+    frames: usize,
+}
+
+impl Params {
+    /// Loads the parameters from the environment variables.
+    fn from_env() -> Self {
+        Self {
+            models: env_or("FBXCEL_BENCH_MODELS", 500),
+            grid: env_or("FBXCEL_BENCH_GRID", 8),
+            frames: env_or("FBXCEL_BENCH_FRAMES", 60),
+        }
+    }
+}
+
+/// Returns the parsed environment variable, or the default value.
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    match std::env::var(name) {
+        Ok(v) => v
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid value for {}: {:?}", name, v)),
+        Err(_) => default,
+    }
+}
+
+/// Generates a synthetic document tree.
+///
+/// Models form a hierarchy where each model has up to four children, and
+/// each model has a grid mesh with normals and UVs, and a translation
+/// animation.
+fn generate(params: Params) -> Tree {
+    let mut builder = DocumentBuilder::new();
+    let stop = (params.frames.max(1) as i64 - 1) * KTIME_PER_SECOND / 30;
+    let stack = builder
+        .add_animation_stack("Take", 0, stop)
+        .expect("Failed to add animation stack");
+    let layer = builder.add_animation_layer("Layer", stack);
+    let mesh = grid_mesh(params.grid);
+
+    let mut models = Vec::with_capacity(params.models);
+    for i in 0..params.models {
+        let parent = if i == 0 {
+            None
+        } else {
+            Some(models[(i - 1) / 4])
+        };
+        let model = builder.add_model(&format!("Model{}", i), ModelKind::Mesh, parent);
+        builder
+            .set_local_transform(model, [i as f64, 0.0, 0.0], [0.0, 15.0, 0.0], [1.0; 3])
+            .expect("Failed to set transform");
+        builder
+            .add_mesh_with(&format!("Mesh{}", i), &mesh, Some(model))
+            .expect("Failed to add mesh");
+        let values: Vec<f32> = (0..params.frames).map(|f| (f + i) as f32).collect();
+        let curve = CurveBuilder::new().baked(30.0, &values, Interpolation::Linear);
+        builder
+            .add_animation_curve_node(
+                layer,
+                model,
+                "Lcl Translation",
+                &[("X", &curve), ("Y", &curve), ("Z", &curve)],
+            )
+            .expect("Failed to add animation");
+        models.push(model);
+    }
+
+    builder.build_tree()
+}
+
+/// Creates a grid mesh with the given number of quads per side.
+fn grid_mesh(grid: usize) -> MeshBuilder {
+    let side = grid + 1;
+    let positions = (0..side * side)
+        .map(|i| [(i % side) as f64, (i / side) as f64, 0.0])
+        .collect();
+    let mut mesh = MeshBuilder::new(positions);
+    let mut pv_count = 0;
+    for y in 0..grid {
+        for x in 0..grid {
+            let i = (y * side + x) as u32;
+            let side = side as u32;
+            mesh = mesh.polygon(&[i, i + 1, i + side + 1, i + side]);
+            pv_count += 4;
+        }
+    }
+    let uvs = (0..pv_count)
+        .map(|i| [(i % 2) as f64, (i / 2 % 2) as f64])
+        .collect();
+    mesh.normals(
+        MappingMode::ByPolygonVertex,
+        vec![[0.0, 0.0, 1.0]; pv_count],
+        None,
+    )
+    .uv("UVMap", MappingMode::ByPolygonVertex, uvs, None)
+}
+
+/// Triangulates the polygon as a fan.
+fn triangulate(
+    _: &PolygonVertices<'_>,
+    poly: &[PolygonVertexIndex],
+    results: &mut Vec<[PolygonVertexIndex; 3]>,
+) -> Result<(), anyhow::Error> {
+    for i in 2..poly.len() {
+        results.push([poly[0], poly[i - 1], poly[i]]);
+    }
+    Ok(())
+}
+
+/// Returns geometry meshes of the document.
+fn meshes(doc: &Document) -> Vec<fbxcel_dom::v7400::object::geometry::MeshHandle<'_>> {
+    doc.objects()
+        .filter_map(|obj| match obj.get_typed() {
+            TypedObjectHandle::Geometry(TypedGeometryHandle::Mesh(mesh)) => Some(mesh),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Runs the benchmarks.
+fn perf(c: &mut Criterion) {
+    let params = Params::from_env();
+    println!("{:?}", params);
+
+    let tree = generate(params);
+    c.bench_function("load_tree", |b| {
+        b.iter_batched(
+            || tree.clone(),
+            |tree| Loader::new().load_from_tree(tree).expect("Failed to load"),
+            BatchSize::LargeInput,
+        )
+    });
+    #[cfg(feature = "writer")]
+    {
+        let data = fbxcel_dom::v7400::write::Writer::new(fbxcel_dom::fbxcel::low::FbxVersion::V7_4)
+            .write_tree(&tree, std::io::Cursor::new(Vec::new()))
+            .expect("Failed to write")
+            .into_inner();
+        c.bench_function("load_binary", |b| {
+            b.iter_with_large_drop(|| {
+                Loader::new()
+                    .load_from_slice(&data)
+                    .expect("Failed to load")
+            })
+        });
+        // Compares serial and parallel array decompression.
+        #[cfg(feature = "rayon")]
+        {
+            let mut group = c.benchmark_group("load_decompression_threads");
+            for threads in [1, 4] {
+                group.bench_with_input(
+                    criterion::BenchmarkId::from_parameter(threads),
+                    &threads,
+                    |b, &threads| {
+                        b.iter_with_large_drop(|| {
+                            Loader::new()
+                                .strict(false)
+                                .decompression_threads(threads)
+                                .load_from_slice(&data)
+                                .expect("Failed to load")
+                        })
+                    },
+                );
+            }
+            group.finish();
+        }
+    }
+
+    let doc = Loader::new().load_from_tree(tree).expect("Failed to load");
+    c.bench_function("connections", |b| {
+        b.iter(|| {
+            doc.objects()
+                .map(|obj| obj.source_objects().count() + obj.destination_objects().count())
+                .sum::<usize>()
+        })
+    });
+
+    let meshes = meshes(&doc);
+    c.bench_function("triangulate", |b| {
+        b.iter(|| {
+            meshes
+                .iter()
+                .map(|mesh| {
+                    mesh.polygon_vertices()
+                        .and_then(|pvs| pvs.triangulate_each(triangulate))
+                        .expect("Failed to triangulate")
+                        .len()
+                })
+                .sum::<usize>()
+        })
+    });
+    c.bench_function("layer_elements", |b| {
+        b.iter_with_large_drop(|| {
+            let mut cache = LayerElementCache::<f32>::default();
+            for mesh in &meshes {
+                cache
+                    .resolved(mesh)
+                    .expect("Failed to resolve layer elements");
+            }
+            cache
+        })
+    });
+
+    let curves: Vec<_> = doc
+        .objects()
+        .filter_map(|obj| match obj.get_typed() {
+            TypedObjectHandle::AnimationCurve(curve) => Some(curve),
+            _ => None,
+        })
+        .collect();
+    c.bench_function("animation_sampling", |b| {
+        b.iter(|| {
+            let step = KTIME_PER_SECOND / 30;
+            let mut sum = 0.0;
+            for curve in &curves {
+                let times = curve.key_times().expect("Failed to get key times");
+                let (start, stop) = (times[0], times[times.len() - 1]);
+                let mut time = start;
+                while time <= stop {
+                    sum += curve
+                        .evaluate(time)
+                        .expect("Failed to evaluate")
+                        .unwrap_or(0.0);
+                    time += step;
+                }
+            }
+            sum
+        })
+    });
+
+    #[cfg(feature = "gltf-export")]
+    c.bench_function("gltf_export", |b| {
+        b.iter_with_large_drop(|| {
+            fbxcel_dom::v7400::gltf::Exporter::new()
+                .export(&doc)
+                .expect("Failed to export")
+        })
+    });
+}
+
+criterion_group! {
+    name = benches;
+    // Each iteration processes the whole document, so keep the number of
+    // samples small.
+    config = Criterion::default().sample_size(10);
+    targets = perf
+}
+criterion_main!(benches);
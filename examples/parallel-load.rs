@@ -0,0 +1,46 @@
+//! Compares the loading time with and without parallel array decompression.
+//!
+//! Run with `--features rayon`; without the feature, arrays are always
+//! decompressed on the loading thread.
+
+use std::{path::PathBuf, time::Instant};
+
+use fbxcel_dom::v7400::Loader;
+
+pub fn main() {
+    env_logger::init();
+
+    let mut args = std::env::args_os().skip(1);
+    let path = match args.next() {
+        Some(v) => PathBuf::from(v),
+        None => {
+            eprintln!("Usage: parallel-load <FBX_FILE> [THREADS]");
+            std::process::exit(1);
+        }
+    };
+    let threads = args
+        .next()
+        .map(|v| {
+            v.to_str()
+                .and_then(|v| v.parse().ok())
+                .expect("Invalid threads count")
+        })
+        .unwrap_or(4);
+    let data = std::fs::read(path).expect("Failed to read file");
+    println!("File size: {} bytes", data.len());
+
+    for threads in [1, threads] {
+        let start = Instant::now();
+        let doc = Loader::new()
+            .strict(false)
+            .decompression_threads(threads)
+            .load_from_slice(&data)
+            .expect("Failed to load document");
+        println!(
+            "Loaded with {} decompression threads in {:?} ({} objects)",
+            threads,
+            start.elapsed(),
+            doc.objects().count()
+        );
+    }
+}
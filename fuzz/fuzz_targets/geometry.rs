@@ -0,0 +1,57 @@
+//! Loads arbitrary data as an FBX document, and reads the geometries.
+#![no_main]
+
+use fbxcel_dom::v7400::{
+    data::mesh::{PolygonVertexIndex, PolygonVertices, TriangleVertices},
+    Loader, ResourceLimits,
+};
+use libfuzzer_sys::fuzz_target;
+
+/// Triangulates polygons as fans.
+fn triangulate(
+    _: &PolygonVertices<'_>,
+    pvis: &[PolygonVertexIndex],
+    results: &mut Vec<[PolygonVertexIndex; 3]>,
+) -> anyhow::Result<()> {
+    for i in 1..pvis.len().saturating_sub(1) {
+        results.push([pvis[0], pvis[i], pvis[i + 1]]);
+    }
+    Ok(())
+}
+
+/// Reads all the data of the triangles.
+fn read_triangles(tris: &TriangleVertices<'_>) {
+    for tri_vi in tris.triangle_vertex_indices() {
+        let _ = tris.control_point(tri_vi);
+        let _ = tris.polygon_vertex_index(tri_vi);
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let limits = ResourceLimits::new()
+        .max_nodes(100_000)
+        .max_array_bytes(16 * 1024 * 1024);
+    let doc = match Loader::new()
+        .strict(false)
+        .resource_limits(limits)
+        .load_from_slice(data)
+    {
+        Ok(v) => v,
+        Err(_) => return,
+    };
+
+    for mesh in doc.meshes() {
+        let _ = mesh
+            .layers()
+            .flat_map(|layer| layer.layer_element_entries())
+            .count();
+        let pvs = match mesh.polygon_vertices() {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if let Ok(tris) = pvs.triangulate_each(triangulate) {
+            read_triangles(&tris);
+        }
+    }
+    let _ = fbxcel_dom::v7400::scene::extract_all(&doc);
+});
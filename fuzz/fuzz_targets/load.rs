@@ -0,0 +1,30 @@
+//! Loads arbitrary data as an FBX document.
+#![no_main]
+
+use fbxcel_dom::v7400::{Loader, ResourceLimits};
+use libfuzzer_sys::fuzz_target;
+
+/// Limits small enough not to exhaust the fuzzer memory.
+fn limits() -> ResourceLimits {
+    ResourceLimits::new()
+        .max_nodes(100_000)
+        .max_array_bytes(16 * 1024 * 1024)
+}
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Loader::new()
+        .resource_limits(limits())
+        .load_from_slice(data);
+    if let Ok(mut doc) = Loader::new()
+        .strict(false)
+        .lazy_arrays(true)
+        .resource_limits(limits())
+        .load_from_slice(data)
+    {
+        let _ = doc.preload_all();
+    }
+    let _ = Loader::new()
+        .strict(false)
+        .resource_limits(limits())
+        .load_from_reader(std::io::Cursor::new(data));
+});
@@ -0,0 +1,121 @@
+/*
+ * C API of fbxcel-dom (`capi` feature).
+ *
+ * See the documentation of the `fbxcel_dom::capi` module for the
+ * conventions.
+ */
+
+#ifndef FBXCEL_DOM_H
+#define FBXCEL_DOM_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Loaded document (opaque). */
+typedef struct FbxDocument FbxDocument;
+
+/* Extracted scene (opaque). */
+typedef struct FbxScene FbxScene;
+
+/* Scene node. */
+typedef struct FbxNode {
+    /* Name (NUL-terminated UTF-8). */
+    const char *name;
+    /* Parent node index, or -1 for root nodes. */
+    int64_t parent;
+    /* Index of the attached mesh (in the scene meshes), or -1. */
+    int64_t mesh;
+    /* Global (world) transform, column-major. */
+    float global_transform[16];
+} FbxNode;
+
+/* Triangulated mesh buffers. */
+typedef struct FbxMesh {
+    /* Number of vertices. */
+    size_t vertex_count;
+    /* Positions (3 floats per vertex). */
+    const float *positions;
+    /* Normals (3 floats per vertex), or NULL. */
+    const float *normals;
+    /* Texture coordinates (2 floats per vertex), or NULL. */
+    const float *uvs;
+    /* Tangents (4 floats per vertex), or NULL. */
+    const float *tangents;
+    /* Joint indices (4 per vertex), or NULL. */
+    const uint16_t *joints;
+    /* Joint weights (4 floats per vertex), or NULL. */
+    const float *weights;
+    /* Number of indices. */
+    size_t index_count;
+    /* Triangle vertex indices. */
+    const uint32_t *indices;
+    /* Number of skin joints, or 0 if the mesh has no skin. */
+    size_t skin_joint_count;
+    /* Node indices of the skin joints. */
+    const size_t *skin_joints;
+    /* Inverse bind matrices of the skin joints (16 floats per joint). */
+    const float *inverse_bind_matrices;
+} FbxMesh;
+
+/* Baked animation. */
+typedef struct FbxAnimation {
+    /* Name (NUL-terminated UTF-8). */
+    const char *name;
+    /* Number of frames. */
+    size_t frame_count;
+    /* Frame times in seconds. */
+    const float *times;
+    /* Number of animated nodes. */
+    size_t node_count;
+} FbxAnimation;
+
+/* Baked animation of a node. */
+typedef struct FbxNodeAnimation {
+    /* Node index. */
+    size_t node;
+    /* Local transforms for each frame (16 floats per frame). */
+    const float *local_matrices;
+} FbxNodeAnimation;
+
+/* Returns the last error message on the calling thread, or NULL. */
+const char *fbxcel_last_error(void);
+
+/* Loads a document from the FBX data in memory. Returns NULL on failure. */
+FbxDocument *fbxcel_document_load(const uint8_t *data, size_t len);
+
+/* Frees the document. Does nothing for NULL. */
+void fbxcel_document_free(FbxDocument *doc);
+
+/*
+ * Extracts the scene with the worker threads, baking animations at the
+ * sample rate (frames per second). Returns NULL on failure.
+ */
+FbxScene *fbxcel_scene_extract(const FbxDocument *doc, uint32_t threads, double sample_rate);
+
+/* Frees the scene. Does nothing for NULL. */
+void fbxcel_scene_free(FbxScene *scene);
+
+size_t fbxcel_scene_node_count(const FbxScene *scene);
+bool fbxcel_scene_node(const FbxScene *scene, size_t index, FbxNode *out);
+
+size_t fbxcel_scene_mesh_count(const FbxScene *scene);
+bool fbxcel_scene_mesh(const FbxScene *scene, size_t index, FbxMesh *out);
+
+size_t fbxcel_scene_animation_count(const FbxScene *scene);
+bool fbxcel_scene_animation(const FbxScene *scene, size_t index, FbxAnimation *out);
+bool fbxcel_scene_animation_node(
+    const FbxScene *scene,
+    size_t index,
+    size_t node_index,
+    FbxNodeAnimation *out);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FBXCEL_DOM_H */
@@ -3,19 +3,32 @@
 //! To see how to use `AnyDocument`, see [crate-level
 //! documentation](../index.html).
 
-use std::io::{Read, Seek};
+#[cfg(feature = "ascii")]
+use std::convert::TryFrom;
+use std::io::{Cursor, Read, Seek, SeekFrom};
 
-use fbxcel::{low::FbxVersion, tree::any::AnyTree};
+use fbxcel::{
+    low::{FbxHeader, FbxVersion},
+    tree::any::AnyTree,
+};
 
 pub use self::error::{Error, Result};
 
 mod error;
 
+/// Length of the binary FBX header.
+const HEADER_LEN: usize = 27;
+
 /// FBX tree type with any supported version.
 #[non_exhaustive]
 pub enum AnyDocument {
     /// FBX 7.4 or later.
     V7400(FbxVersion, Box<crate::v7400::Document>),
+    /// FBX 6.x, converted into the FBX 7.4 structure.
+    ///
+    /// See [`v6100`][`crate::v6100`] module for the conversion.
+    #[cfg(feature = "v6100")]
+    V6100(FbxVersion, Box<crate::v7400::Document>),
 }
 
 impl AnyDocument {
@@ -24,10 +37,20 @@ impl AnyDocument {
     /// This works for seekable readers (which implement `std::io::Seek`), but
     /// `from_seekable_reader` should be used for them, because it is more
     /// efficent.
-    pub fn from_reader(reader: impl Read) -> Result<Self> {
+    ///
+    /// With `ascii` feature, ASCII FBX is also detected and loaded.
+    pub fn from_reader(mut reader: impl Read) -> Result<Self> {
+        let header = read_header(&mut reader)?;
+        if let Some(doc) = Self::from_non_v7400(&header, &mut reader)? {
+            return Ok(doc);
+        }
+        let reader = Cursor::new(header).chain(reader);
         match AnyTree::from_reader(reader)? {
-            AnyTree::V7400(fbx_version, tree, _footer) => {
-                let doc = crate::v7400::Loader::new().load_from_tree(tree)?;
+            AnyTree::V7400(fbx_version, tree, footer) => {
+                let footer = footer.ok().map(|v| *v);
+                let doc = crate::v7400::Loader::new()
+                    .fbx_version(fbx_version)
+                    .load_from_tree_with_footer(tree, footer)?;
                 Ok(AnyDocument::V7400(fbx_version, Box::new(doc)))
             }
             tree => Err(Error::UnsupportedVersion(tree.fbx_version())),
@@ -35,20 +58,100 @@ impl AnyDocument {
     }
 
     /// Loads a document from the given seekable reader.
-    pub fn from_seekable_reader(reader: impl Read + Seek) -> Result<Self> {
+    ///
+    /// With `ascii` feature, ASCII FBX is also detected and loaded.
+    pub fn from_seekable_reader(mut reader: impl Read + Seek) -> Result<Self> {
+        let header = read_header(&mut reader)?;
+        if let Some(doc) = Self::from_non_v7400(&header, &mut reader)? {
+            return Ok(doc);
+        }
+        reader.seek(SeekFrom::Current(-(header.len() as i64)))?;
         match AnyTree::from_seekable_reader(reader)? {
-            AnyTree::V7400(fbx_version, tree, _footer) => {
-                let doc = crate::v7400::Loader::new().load_from_tree(tree)?;
+            AnyTree::V7400(fbx_version, tree, footer) => {
+                let footer = footer.ok().map(|v| *v);
+                let doc = crate::v7400::Loader::new()
+                    .fbx_version(fbx_version)
+                    .load_from_tree_with_footer(tree, footer)?;
                 Ok(AnyDocument::V7400(fbx_version, Box::new(doc)))
             }
             tree => Err(Error::UnsupportedVersion(tree.fbx_version())),
         }
     }
 
+    /// Loads a document from the given data in memory.
+    ///
+    /// This does not touch the filesystem, so it works on targets without
+    /// one (such as `wasm32-unknown-unknown`) as well.
+    ///
+    /// With `ascii` feature, ASCII FBX is also detected and loaded.
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        Self::from_seekable_reader(Cursor::new(data))
+    }
+
+    /// Loads a document if the data is not binary FBX 7.x.
+    ///
+    /// `header` is the bytes already read from `reader`.
+    /// Returns `Ok(None)` if the data should be loaded as binary FBX 7.x.
+    fn from_non_v7400(header: &[u8], reader: &mut impl Read) -> Result<Option<Self>> {
+        match FbxHeader::load(header) {
+            #[cfg(feature = "v6100")]
+            Ok(fbx_header) if crate::v6100::is_v6(fbx_header.version()) => {
+                let (fbx_version, tree) = crate::v6100::load_tree(header.chain(reader))?;
+                let doc = crate::v7400::Loader::new()
+                    .fbx_version(fbx_version)
+                    .load_from_tree(crate::v6100::convert_tree(&tree))?;
+                Ok(Some(AnyDocument::V6100(fbx_version, Box::new(doc))))
+            }
+            Ok(_) => Ok(None),
+            #[cfg(feature = "ascii")]
+            Err(_) => Self::from_ascii_reader(header.chain(reader)).map(Some),
+            #[cfg(not(feature = "ascii"))]
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Loads a document from the given ASCII FBX reader.
+    ///
+    /// Invalid UTF-8 sequences are replaced with `U+FFFD`.
+    #[cfg(feature = "ascii")]
+    fn from_ascii_reader(mut reader: impl Read) -> Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let text = String::from_utf8_lossy(&bytes);
+        let tree = crate::v7400::ascii::parse_tree(&text)?;
+        let raw_version = crate::v7400::version::header_version(&tree)
+            .and_then(|v| u32::try_from(v).ok())
+            .unwrap_or(7400);
+        let fbx_version = crate::v7400::version::from_raw(raw_version);
+        let loader = crate::v7400::Loader::new().fbx_version(fbx_version);
+        #[cfg(feature = "v6100")]
+        if crate::v6100::is_v6(fbx_version) {
+            let doc = loader.load_from_tree(crate::v6100::convert_tree(&tree))?;
+            return Ok(AnyDocument::V6100(fbx_version, Box::new(doc)));
+        }
+        #[cfg(not(feature = "v6100"))]
+        if fbx_version.major() < 7 {
+            return Err(Error::UnsupportedVersion(fbx_version));
+        }
+        let doc = loader.load_from_tree(tree)?;
+        Ok(AnyDocument::V7400(fbx_version, Box::new(doc)))
+    }
+
     /// Returns the FBX version of the loaded document.
     pub fn fbx_version(&self) -> FbxVersion {
         match self {
             Self::V7400(ver, _) => *ver,
+            #[cfg(feature = "v6100")]
+            Self::V6100(ver, _) => *ver,
         }
     }
 }
+
+/// Reads the bytes to detect the FBX format and version.
+///
+/// Returns fewer bytes than the binary header if the reader reaches the end.
+fn read_header(reader: &mut impl Read) -> Result<Vec<u8>> {
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    reader.take(HEADER_LEN as u64).read_to_end(&mut header)?;
+    Ok(header)
+}
@@ -16,8 +16,13 @@ pub enum Error {
     /// This means that the FBX version may be supported by the backend parser, but the backend
     /// parser used to load the document is unsupported by fbxcel-dom crate.
     UnsupportedVersion(FbxVersion),
+    /// I/O error.
+    Io(std::io::Error),
     /// Tree load error.
     Tree(tree::any::Error),
+    /// ASCII FBX parse error.
+    #[cfg(feature = "ascii")]
+    Ascii(crate::v7400::ascii::Error),
     /// DOM load error.
     Dom(Box<dyn error::Error + Send + Sync + 'static>),
 }
@@ -25,7 +30,10 @@ pub enum Error {
 impl error::Error for Error {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self {
+            Error::Io(e) => Some(e),
             Error::Tree(e) => Some(e),
+            #[cfg(feature = "ascii")]
+            Error::Ascii(e) => Some(e),
             Error::Dom(e) => Some(&**e),
             Error::UnsupportedVersion(..) => None,
         }
@@ -35,19 +43,35 @@ impl error::Error for Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Error::Io(e) => write!(f, "I/O error: {}", e),
             Error::Tree(e) => write!(f, "Tree load error: {}", e),
+            #[cfg(feature = "ascii")]
+            Error::Ascii(e) => write!(f, "ASCII FBX load error: {}", e),
             Error::Dom(e) => write!(f, "DOM document load error: {}", e),
             Error::UnsupportedVersion(ver) => write!(f, "Unsupported FBX version: {:?}", ver),
         }
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
 impl From<tree::any::Error> for Error {
     fn from(e: tree::any::Error) -> Self {
         Error::Tree(e)
     }
 }
 
+#[cfg(feature = "ascii")]
+impl From<crate::v7400::ascii::Error> for Error {
+    fn from(e: crate::v7400::ascii::Error) -> Self {
+        Error::Ascii(e)
+    }
+}
+
 impl From<crate::v7400::LoadError> for Error {
     fn from(e: crate::v7400::LoadError) -> Self {
         Error::Dom(e.into())
@@ -0,0 +1,499 @@
+//! C API.
+//!
+//! This module (with `capi` feature) exposes document loading, mesh buffer
+//! extraction, and animation baking (see
+//! [`Extractor`][`crate::v7400::scene::Extractor`]) through a C ABI, so that
+//! C and C++ pipelines can use this crate.
+//! The declarations are in `include/fbxcel_dom.h`.
+//!
+//! To build a shared library, run:
+//!
+//! ```sh
+//! cargo rustc --release --features capi --crate-type cdylib
+//! ```
+//!
+//! # Conventions
+//!
+//! * Objects are created by `fbxcel_*_load` / `fbxcel_*_extract` functions
+//!   and must be released by the corresponding `fbxcel_*_free` functions.
+//! * Functions returning pointers return null on failure, and functions
+//!   returning `bool` return false on failure. The error message is then
+//!   available from [`fbxcel_last_error()`] on the same thread.
+//! * Pointers in output structs borrow from the scene object, and are valid
+//!   until the scene is freed. Optional arrays are null if absent.
+//! * Matrices are column-major arrays of 16 `float`s.
+
+use std::{
+    cell::RefCell,
+    ffi::CString,
+    os::raw::c_char,
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use anyhow::{bail, format_err, Error};
+
+use crate::{
+    any::AnyDocument,
+    v7400::{
+        data::scalar,
+        scene::{Extractor, SceneExtract},
+        Document,
+    },
+};
+
+thread_local! {
+    /// Last error message on the thread.
+    static LAST_ERROR: RefCell<Option<CString>> = RefCell::new(None);
+}
+
+/// Loaded document.
+///
+/// Opaque to C.
+pub struct FbxDocument(Document);
+
+/// Extracted scene.
+///
+/// Opaque to C.
+pub struct FbxScene {
+    /// Extracted scene.
+    extract: SceneExtract,
+    /// Node names.
+    node_names: Vec<CString>,
+    /// Animation names.
+    animation_names: Vec<CString>,
+    /// Global transforms of the nodes.
+    global_transforms: Vec<[f32; 16]>,
+}
+
+/// Scene node.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FbxNode {
+    /// Name (NUL-terminated UTF-8).
+    pub name: *const c_char,
+    /// Parent node index, or -1 for root nodes.
+    pub parent: i64,
+    /// Index of the attached mesh (in the scene meshes), or -1.
+    pub mesh: i64,
+    /// Global (world) transform.
+    pub global_transform: [f32; 16],
+}
+
+/// Triangulated mesh buffers.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FbxMesh {
+    /// Number of vertices.
+    pub vertex_count: usize,
+    /// Positions (3 `float`s per vertex).
+    pub positions: *const f32,
+    /// Normals (3 `float`s per vertex), or null.
+    pub normals: *const f32,
+    /// Texture coordinates (2 `float`s per vertex), or null.
+    pub uvs: *const f32,
+    /// Tangents (4 `float`s per vertex), or null.
+    pub tangents: *const f32,
+    /// Joint indices (4 `uint16_t`s per vertex), or null.
+    pub joints: *const u16,
+    /// Joint weights (4 `float`s per vertex), or null.
+    pub weights: *const f32,
+    /// Number of indices.
+    pub index_count: usize,
+    /// Triangle vertex indices.
+    pub indices: *const u32,
+    /// Number of skin joints, or 0 if the mesh has no skin.
+    pub skin_joint_count: usize,
+    /// Node indices of the skin joints.
+    pub skin_joints: *const usize,
+    /// Inverse bind matrices of the skin joints.
+    pub inverse_bind_matrices: *const f32,
+}
+
+/// Baked animation.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FbxAnimation {
+    /// Name (NUL-terminated UTF-8).
+    pub name: *const c_char,
+    /// Number of frames.
+    pub frame_count: usize,
+    /// Frame times in seconds.
+    pub times: *const f32,
+    /// Number of animated nodes.
+    pub node_count: usize,
+}
+
+/// Baked animation of a node.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FbxNodeAnimation {
+    /// Node index.
+    pub node: usize,
+    /// Local transforms for each frame.
+    pub local_matrices: *const f32,
+}
+
+/// Returns the last error message on the calling thread, or null if there
+/// is no error.
+///
+/// The message is valid until the next API call on the thread.
+#[no_mangle]
+pub extern "C" fn fbxcel_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |e| e.as_ptr()))
+}
+
+/// Loads a document from the FBX data in memory.
+///
+/// Binary FBX 6.x and 7.x (and ASCII FBX with `ascii` feature) are
+/// supported. Returns null on failure.
+///
+/// # Safety
+///
+/// `data` must be null or point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn fbxcel_document_load(data: *const u8, len: usize) -> *mut FbxDocument {
+    ffi_call(ptr::null_mut(), || {
+        if data.is_null() {
+            bail!("Data is null");
+        }
+        let data = slice::from_raw_parts(data, len);
+        let doc = match AnyDocument::from_bytes(data)? {
+            AnyDocument::V7400(_, doc) => doc,
+            #[cfg(feature = "v6100")]
+            AnyDocument::V6100(_, doc) => doc,
+        };
+        Ok(Box::into_raw(Box::new(FbxDocument(*doc))))
+    })
+}
+
+/// Frees the document.
+///
+/// Does nothing if `doc` is null.
+///
+/// # Safety
+///
+/// `doc` must be null or a document returned by [`fbxcel_document_load()`]
+/// and not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn fbxcel_document_free(doc: *mut FbxDocument) {
+    if !doc.is_null() {
+        drop(Box::from_raw(doc));
+    }
+}
+
+/// Extracts the scene (node hierarchy, triangulated meshes, and animations
+/// baked at `sample_rate` frames per second) with `threads` worker threads.
+///
+/// The document can be freed after the extraction. Returns null on failure.
+///
+/// # Safety
+///
+/// `doc` must be null or a valid document.
+#[no_mangle]
+pub unsafe extern "C" fn fbxcel_scene_extract(
+    doc: *const FbxDocument,
+    threads: u32,
+    sample_rate: f64,
+) -> *mut FbxScene {
+    ffi_call(ptr::null_mut(), || {
+        let doc = doc
+            .as_ref()
+            .ok_or_else(|| format_err!("Document is null"))?;
+        let extract = Extractor::new()
+            .threads(threads as usize)
+            .sample_rate(sample_rate)
+            .extract(&doc.0)?;
+        let node_names = extract
+            .graph
+            .nodes()
+            .iter()
+            .map(|node| c_string(&node.name))
+            .collect();
+        let animation_names = extract
+            .animations
+            .iter()
+            .map(|anim| c_string(&anim.name))
+            .collect();
+        let global_transforms = extract
+            .graph
+            .nodes()
+            .iter()
+            .map(|node| matrix(scalar::matrix4(node.global_transform)))
+            .collect();
+        Ok(Box::into_raw(Box::new(FbxScene {
+            extract,
+            node_names,
+            animation_names,
+            global_transforms,
+        })))
+    })
+}
+
+/// Frees the scene.
+///
+/// Does nothing if `scene` is null.
+///
+/// # Safety
+///
+/// `scene` must be null or a scene returned by [`fbxcel_scene_extract()`]
+/// and not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn fbxcel_scene_free(scene: *mut FbxScene) {
+    if !scene.is_null() {
+        drop(Box::from_raw(scene));
+    }
+}
+
+/// Returns the number of nodes, or 0 if `scene` is null.
+///
+/// # Safety
+///
+/// `scene` must be null or a valid scene.
+#[no_mangle]
+pub unsafe extern "C" fn fbxcel_scene_node_count(scene: *const FbxScene) -> usize {
+    scene
+        .as_ref()
+        .map_or(0, |scene| scene.extract.graph.nodes().len())
+}
+
+/// Writes the node at the given index to `out`.
+///
+/// # Safety
+///
+/// `scene` must be null or a valid scene, and `out` must be null or
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn fbxcel_scene_node(
+    scene: *const FbxScene,
+    index: usize,
+    out: *mut FbxNode,
+) -> bool {
+    ffi_call(false, || {
+        let scene = scene_ref(scene)?;
+        let node = scene
+            .extract
+            .graph
+            .nodes()
+            .get(index)
+            .ok_or_else(|| format_err!("Node index out of range: {}", index))?;
+        let mesh = node.geometry.and_then(|geometry| {
+            scene
+                .extract
+                .meshes
+                .iter()
+                .position(|mesh| mesh.geometry == geometry)
+        });
+        write_out(
+            out,
+            FbxNode {
+                name: scene.node_names[index].as_ptr(),
+                parent: node.parent.map_or(-1, |parent| parent.to_usize() as i64),
+                mesh: mesh.map_or(-1, |mesh| mesh as i64),
+                global_transform: scene.global_transforms[index],
+            },
+        )
+    })
+}
+
+/// Returns the number of meshes, or 0 if `scene` is null.
+///
+/// # Safety
+///
+/// `scene` must be null or a valid scene.
+#[no_mangle]
+pub unsafe extern "C" fn fbxcel_scene_mesh_count(scene: *const FbxScene) -> usize {
+    scene.as_ref().map_or(0, |scene| scene.extract.meshes.len())
+}
+
+/// Writes the mesh buffers at the given index to `out`.
+///
+/// # Safety
+///
+/// `scene` must be null or a valid scene, and `out` must be null or
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn fbxcel_scene_mesh(
+    scene: *const FbxScene,
+    index: usize,
+    out: *mut FbxMesh,
+) -> bool {
+    ffi_call(false, || {
+        let scene = scene_ref(scene)?;
+        let mesh = scene
+            .extract
+            .meshes
+            .get(index)
+            .ok_or_else(|| format_err!("Mesh index out of range: {}", index))?;
+        let skin = mesh.skin.as_ref();
+        // Vertex attributes are contiguous arrays of their components.
+        write_out(
+            out,
+            FbxMesh {
+                vertex_count: mesh.positions.len(),
+                positions: mesh.positions.as_ptr() as *const f32,
+                normals: mesh
+                    .normals
+                    .as_ref()
+                    .map_or(ptr::null(), |v| v.as_ptr() as *const f32),
+                uvs: mesh
+                    .uvs
+                    .as_ref()
+                    .map_or(ptr::null(), |v| v.as_ptr() as *const f32),
+                tangents: mesh
+                    .tangents
+                    .as_ref()
+                    .map_or(ptr::null(), |v| v.as_ptr() as *const f32),
+                joints: mesh
+                    .joints
+                    .as_ref()
+                    .map_or(ptr::null(), |v| v.as_ptr() as *const u16),
+                weights: mesh
+                    .weights
+                    .as_ref()
+                    .map_or(ptr::null(), |v| v.as_ptr() as *const f32),
+                index_count: mesh.indices.len(),
+                indices: mesh.indices.as_ptr(),
+                skin_joint_count: skin.map_or(0, |skin| skin.joints.len()),
+                // `NodeIndex` is a newtype of `usize`.
+                skin_joints: skin.map_or(ptr::null(), |skin| skin.joints.as_ptr() as *const usize),
+                inverse_bind_matrices: skin.map_or(ptr::null(), |skin| {
+                    skin.inverse_bind_matrices.as_ptr() as *const f32
+                }),
+            },
+        )
+    })
+}
+
+/// Returns the number of animations, or 0 if `scene` is null.
+///
+/// # Safety
+///
+/// `scene` must be null or a valid scene.
+#[no_mangle]
+pub unsafe extern "C" fn fbxcel_scene_animation_count(scene: *const FbxScene) -> usize {
+    scene
+        .as_ref()
+        .map_or(0, |scene| scene.extract.animations.len())
+}
+
+/// Writes the animation at the given index to `out`.
+///
+/// # Safety
+///
+/// `scene` must be null or a valid scene, and `out` must be null or
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn fbxcel_scene_animation(
+    scene: *const FbxScene,
+    index: usize,
+    out: *mut FbxAnimation,
+) -> bool {
+    ffi_call(false, || {
+        let scene = scene_ref(scene)?;
+        let anim = scene
+            .extract
+            .animations
+            .get(index)
+            .ok_or_else(|| format_err!("Animation index out of range: {}", index))?;
+        write_out(
+            out,
+            FbxAnimation {
+                name: scene.animation_names[index].as_ptr(),
+                frame_count: anim.times.len(),
+                times: anim.times.as_ptr(),
+                node_count: anim.nodes.len(),
+            },
+        )
+    })
+}
+
+/// Writes the animation of the node at `node_index` in the animation at
+/// `index` to `out`.
+///
+/// # Safety
+///
+/// `scene` must be null or a valid scene, and `out` must be null or
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn fbxcel_scene_animation_node(
+    scene: *const FbxScene,
+    index: usize,
+    node_index: usize,
+    out: *mut FbxNodeAnimation,
+) -> bool {
+    ffi_call(false, || {
+        let scene = scene_ref(scene)?;
+        let node = scene
+            .extract
+            .animations
+            .get(index)
+            .ok_or_else(|| format_err!("Animation index out of range: {}", index))?
+            .nodes
+            .get(node_index)
+            .ok_or_else(|| format_err!("Animated node index out of range: {}", node_index))?;
+        write_out(
+            out,
+            FbxNodeAnimation {
+                node: node.node.to_usize(),
+                local_matrices: node.local_matrices.as_ptr() as *const f32,
+            },
+        )
+    })
+}
+
+/// Calls the function, recording the error (or panic) message and returning
+/// `on_error` on failure.
+fn ffi_call<T>(on_error: T, f: impl FnOnce() -> Result<T, Error>) -> T {
+    let result = panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Unknown panic".to_owned());
+        Err(format_err!("Panicked: {}", message))
+    });
+    let (value, error) = match result {
+        Ok(v) => (v, None),
+        Err(e) => (on_error, Some(c_string(&format!("{:#}", e)))),
+    };
+    LAST_ERROR.with(|e| *e.borrow_mut() = error);
+    value
+}
+
+/// Returns the reference to the scene.
+///
+/// # Safety
+///
+/// `scene` must be null or a valid scene.
+unsafe fn scene_ref<'a>(scene: *const FbxScene) -> Result<&'a FbxScene, Error> {
+    scene.as_ref().ok_or_else(|| format_err!("Scene is null"))
+}
+
+/// Writes the value to the output pointer.
+///
+/// # Safety
+///
+/// `out` must be null or writable.
+unsafe fn write_out<T>(out: *mut T, value: T) -> Result<bool, Error> {
+    if out.is_null() {
+        bail!("Output pointer is null");
+    }
+    out.write(value);
+    Ok(true)
+}
+
+/// Returns the C string, with NUL characters removed.
+fn c_string(s: &str) -> CString {
+    CString::new(s.replace('\0', "")).expect("Should never fail: NUL characters are removed")
+}
+
+/// Returns the matrix as a column-major array.
+fn matrix(m: mint::ColumnMatrix4<f32>) -> [f32; 16] {
+    let cols: [[f32; 4]; 4] = m.into();
+    let mut array = [0.0; 16];
+    for (i, col) in cols.iter().enumerate() {
+        array[i * 4..i * 4 + 4].copy_from_slice(col);
+    }
+    array
+}
@@ -1,5 +1,20 @@
 //! FBX DOM library.
 //!
+//! # Quick start
+//!
+//! To get meshes, materials, textures, skeleton, and animation clips of a
+//! file at once, use [`prelude::SimpleScene`].
+//!
+//! ```no_run
+//! use fbxcel_dom::prelude::*;
+//!
+//! let scene = SimpleScene::load("sample.fbx")?;
+//! for mesh in &scene.meshes {
+//!     println!("{} vertices", mesh.mesh.positions.len());
+//! }
+//! # Ok::<_, anyhow::Error>(())
+//! ```
+//!
 //! # Creating DOM
 //!
 //! ## Easy setup (recommended)
@@ -42,11 +57,21 @@
 //! For detail, see documents of loaders.
 //!
 //! [`any`]: any/index.html
-#![forbid(unsafe_code)]
+//! [`prelude::SimpleScene`]: v7400/scene/struct.SimpleScene.html
+#![cfg_attr(not(feature = "capi"), forbid(unsafe_code))]
+#![cfg_attr(feature = "capi", deny(unsafe_code))]
 #![warn(missing_docs)]
 #![warn(clippy::missing_docs_in_private_items)]
 
 pub use fbxcel;
+#[cfg(feature = "glam")]
+pub use glam;
 
 pub mod any;
+#[cfg(feature = "capi")]
+#[allow(unsafe_code)]
+pub mod capi;
+pub mod prelude;
+#[cfg(feature = "v6100")]
+pub mod v6100;
 pub mod v7400;
@@ -0,0 +1,23 @@
+//! Commonly used types.
+//!
+//! ```no_run
+//! use fbxcel_dom::prelude::*;
+//!
+//! let scene = SimpleScene::load("sample.fbx")?;
+//! println!("{} meshes, {} materials", scene.meshes.len(), scene.materials.len());
+//! # Ok::<_, anyhow::Error>(())
+//! ```
+
+#[cfg(feature = "edit")]
+pub use crate::v7400::edit::{DocumentBuilder, DocumentMut, ModelKind};
+pub use crate::{
+    any::AnyDocument,
+    v7400::{
+        object::{model::TypedModelHandle, ObjectHandle, ObjectId, TypedObjectHandle},
+        scene::{
+            extract_all, ExtractOptions, Extractor, SceneExtract, SceneGraph, SceneNodeKind,
+            SimpleMaterial, SimpleMesh, SimpleScene, SimpleTexture,
+        },
+        Document, Loader,
+    },
+};
@@ -0,0 +1,100 @@
+//! FBX DOM utils for FBX 6.x.
+//!
+//! FBX 6.x documents are not loaded directly.
+//! Instead, the FBX 6.x tree is converted into the FBX 7.4 tree structure,
+//! and loaded as [`v7400::Document`][`crate::v7400::Document`].
+//!
+//! # Conversion
+//!
+//! * Objects are identified by names in FBX 6.x, and they get new object
+//!   IDs.
+//!   Object names (`Class::Name`) are converted into the FBX 7.4 form.
+//! * `Connect` nodes are converted into `C` nodes with the object IDs.
+//!   Connections to `Model::Scene` are connected to the scene root.
+//! * `Properties60` are converted into `Properties70`, and the property
+//!   values are converted into the types expected by the property loaders.
+//! * Mesh models are split into models and geometries, and deformers
+//!   attached to mesh models are attached to the geometries.
+//! * `GlobalSettings` is moved to the toplevel, and `Documents` and
+//!   `Definitions` are generated.
+//! * Pose nodes referring to models by names are converted to use the IDs.
+//!
+//! Other nodes are copied as is.
+//! Especially, the FBX 6.x animations (`Takes` node) are not converted into
+//! animation stacks, layers and curves.
+//!
+//! # Loading
+//!
+//! Binary FBX 6.x files are read with the FBX 7.4 parser of `fbxcel`, since
+//! the node record layout is the same.
+//! [`AnyDocument`][`crate::any::AnyDocument`] detects and loads them
+//! automatically (and ASCII FBX 6.x with `ascii` feature).
+//!
+//! ```no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let file = std::io::BufReader::new(std::fs::File::open("sample.fbx")?);
+//! let (fbx_version, tree) = fbxcel_dom::v6100::load_tree(file)?;
+//! let tree = fbxcel_dom::v6100::convert_tree(&tree);
+//! let doc = fbxcel_dom::v7400::Loader::new().load_from_tree(tree)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::Read;
+
+use fbxcel::{
+    low::{FbxHeader, FbxVersion},
+    pull_parser::v7400::from_reader,
+    tree::v7400::{Loader as TreeLoader, Tree},
+};
+use log::{trace, warn};
+
+use crate::v7400::LoadError;
+
+pub use self::convert::convert_tree;
+
+mod convert;
+
+/// Length of the binary FBX header.
+const HEADER_LEN: usize = 27;
+
+/// Raw FBX version to read the FBX 6.x binary with.
+const PARSER_FBX_VERSION: u32 = 7400;
+
+/// Returns true if the FBX version is 6.x.
+pub(crate) fn is_v6(fbx_version: FbxVersion) -> bool {
+    fbx_version.major() == 6
+}
+
+/// Reads the binary FBX 6.x data (including the header) as a raw FBX 6.x
+/// tree.
+///
+/// Returns an error if the data is not binary FBX 6.x.
+/// The footer of the source is ignored.
+pub fn load_tree(mut reader: impl Read) -> Result<(FbxVersion, Tree), LoadError> {
+    let mut header = [0; HEADER_LEN];
+    reader
+        .read_exact(&mut header)
+        .map_err(|e| LoadError::new(format!("Failed to read FBX header: {}", e)))?;
+    let fbx_version = FbxHeader::load(&header[..])
+        .map_err(LoadError::new)?
+        .version();
+    if !is_v6(fbx_version) {
+        return Err(LoadError::new(format!(
+            "Expected FBX 6.x but got {:?}",
+            fbx_version
+        )));
+    }
+    trace!("Loading FBX 6.x binary: version={:?}", fbx_version);
+
+    header[HEADER_LEN - 4..].copy_from_slice(&PARSER_FBX_VERSION.to_le_bytes());
+    let parser_header = FbxHeader::load(&header[..])
+        .expect("Should never fail: the header is already loaded successfully");
+    let mut parser = from_reader(parser_header, reader).map_err(LoadError::new)?;
+    let (tree, footer) = TreeLoader::new().load(&mut parser)?;
+    if let Err(e) = footer {
+        warn!("Ignoring unreadable FBX 6.x footer: {}", e);
+    }
+
+    Ok((fbx_version, tree))
+}
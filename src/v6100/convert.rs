@@ -0,0 +1,539 @@
+//! FBX 6.x to FBX 7.4 tree conversion.
+
+use std::collections::{HashMap, HashSet};
+
+use fbxcel::{
+    low::v7400::AttributeValue,
+    tree::v7400::{NodeHandle, NodeId, Tree},
+};
+use log::warn;
+
+use crate::v7400::edit::{copy_node, DocumentMut, TemplateSource};
+
+/// Object ID of the implicit scene root model.
+const ROOT_MODEL_ID: i64 = 0;
+
+/// Name of the implicit scene root model in FBX 6.x.
+const ROOT_MODEL_NAME: &str = "Model::Scene";
+
+/// Child nodes of mesh models moved to geometries.
+const GEOMETRY_CHILDREN: &[&str] = &[
+    "Vertices",
+    "PolygonVertexIndex",
+    "Edges",
+    "GeometryVersion",
+    "Layer",
+];
+
+/// Array nodes with `f64` elements.
+const F64_ARRAYS: &[&str] = &[
+    "Vertices",
+    "Normals",
+    "Binormals",
+    "Tangents",
+    "UV",
+    "Colors",
+    "Weights",
+    "Transform",
+    "TransformLink",
+    "Matrix",
+    "Points",
+    "KnotVector",
+    "KnotVectorU",
+    "KnotVectorV",
+];
+
+/// Array nodes with `i32` elements.
+const I32_ARRAYS: &[&str] = &[
+    "PolygonVertexIndex",
+    "Edges",
+    "NormalsIndex",
+    "BinormalsIndex",
+    "TangentsIndex",
+    "UVIndex",
+    "ColorIndex",
+    "Materials",
+    "TextureId",
+    "Smoothing",
+    "Indexes",
+];
+
+/// Property data types whose values are `i32`.
+const I32_PROPERTY_TYPES: &[&str] = &["bool", "Bool", "int", "Integer", "enum", "Enum"];
+
+/// Property data types whose values are `i64`.
+const I64_PROPERTY_TYPES: &[&str] = &["KTime", "Time"];
+
+/// Property data types whose values are strings.
+const STRING_PROPERTY_TYPES: &[&str] = &["KString", "charptr", "object", "Url", "XRefUrl"];
+
+/// Converts the FBX 6.x tree into the FBX 7.4 tree structure.
+///
+/// See [module documentation](index.html) for the conversion details.
+///
+/// ```
+/// use fbxcel_dom::{
+///     fbxcel::{
+///         low::v7400::AttributeValue,
+///         tree::v7400::{NodeHandle, Tree},
+///     },
+///     v6100::convert_tree,
+/// };
+///
+/// fn strings(values: &[&str]) -> Vec<AttributeValue> {
+///     values.iter().map(|v| AttributeValue::String((*v).to_owned())).collect()
+/// }
+/// fn child<'a>(node: NodeHandle<'a>, name: &str) -> NodeHandle<'a> {
+///     node.first_child_by_name(name).expect("should exist")
+/// }
+///
+/// // FBX 6.x objects are identified by names.
+/// let mut src = Tree::default();
+/// let root = src.root().node_id();
+/// let objects = src.append_new(root, "Objects");
+/// let model = src.append_new(objects, "Model");
+/// src.set_attributes_vec(model, strings(&["Model::Cube", "Mesh"]));
+/// let vertices = src.append_new(model, "Vertices");
+/// src.append_attribute(vertices, AttributeValue::ArrF64(vec![0.0; 9]));
+/// let indices = src.append_new(model, "PolygonVertexIndex");
+/// src.append_attribute(indices, AttributeValue::ArrI32(vec![0, 1, -3]));
+/// let material = src.append_new(objects, "Material");
+/// src.set_attributes_vec(material, strings(&["Material::Red", ""]));
+/// let skin = src.append_new(objects, "Deformer");
+/// src.set_attributes_vec(skin, strings(&["Deformer::Skin", "Skin"]));
+/// let connections = src.append_new(root, "Connections");
+/// for &(from, to) in &[
+///     ("Model::Cube", "Model::Scene"),
+///     ("Material::Red", "Model::Cube"),
+///     ("Deformer::Skin", "Model::Cube"),
+/// ] {
+///     let connect = src.append_new(connections, "Connect");
+///     src.set_attributes_vec(connect, strings(&["OO", from, to]));
+/// }
+///
+/// let tree = convert_tree(&src);
+///
+/// // Objects get new IDs, and mesh models are split into models and
+/// // geometries.
+/// let objects = child(tree.root(), "Objects");
+/// let ids: Vec<(&str, i64, &str)> = objects
+///     .children()
+///     .map(|obj| {
+///         let attrs = obj.attributes();
+///         let id = attrs[0].get_i64().expect("should be `i64`");
+///         let name = attrs[1].get_string().expect("should be string");
+///         (obj.name(), id, name)
+///     })
+///     .collect();
+/// let names: Vec<_> = ids.iter().map(|&(class, _, name)| (class, name)).collect();
+/// assert_eq!(
+///     names,
+///     [
+///         ("Model", "Cube\u{0}\u{1}Model"),
+///         ("Geometry", "Cube\u{0}\u{1}Geometry"),
+///         ("Material", "Red\u{0}\u{1}Material"),
+///         ("Deformer", "Skin\u{0}\u{1}Deformer"),
+///     ]
+/// );
+/// let (cube, geometry, red, skin) = (ids[0].1, ids[1].1, ids[2].1, ids[3].1);
+/// let mut unique = vec![0, cube, geometry, red, skin];
+/// unique.sort_unstable();
+/// unique.dedup();
+/// assert_eq!(unique.len(), 5, "IDs should be unique and non-zero: {:?}", ids);
+///
+/// assert!(child(objects, "Model").first_child_by_name("Vertices").is_none());
+/// let geometry_node = child(objects, "Geometry");
+/// assert!(geometry_node.first_child_by_name("Vertices").is_some());
+/// assert!(geometry_node.first_child_by_name("PolygonVertexIndex").is_some());
+///
+/// // Connections refer to the new IDs, the scene to the root (ID 0), and
+/// // deformers to the geometries.
+/// let connections: Vec<_> = child(tree.root(), "Connections")
+///     .children_by_name("C")
+///     .map(|c| c.attributes().to_vec())
+///     .collect();
+/// let c = |from: i64, to: i64| {
+///     vec![
+///         AttributeValue::String("OO".to_owned()),
+///         AttributeValue::I64(from),
+///         AttributeValue::I64(to),
+///     ]
+/// };
+/// assert_eq!(
+///     connections,
+///     [c(cube, 0), c(red, cube), c(skin, geometry), c(geometry, cube)]
+/// );
+/// ```
+pub fn convert_tree(src: &Tree) -> Tree {
+    let mut converter = Converter {
+        src,
+        dest: Tree::default(),
+        ids: HashMap::new(),
+        geometries: Vec::new(),
+        next_id: ROOT_MODEL_ID + 1,
+    };
+    converter.convert();
+
+    let mut doc = DocumentMut::from_tree(converter.dest);
+    doc.update_definitions(TemplateSource::Existing);
+    doc.into_tree()
+}
+
+/// Converter state.
+struct Converter<'a> {
+    /// Source FBX 6.x tree.
+    src: &'a Tree,
+    /// Destination tree.
+    dest: Tree,
+    /// Object IDs by the names in `Class::Name` form.
+    ids: HashMap<String, i64>,
+    /// Mesh model IDs and their geometry IDs.
+    geometries: Vec<(i64, i64)>,
+    /// Next object ID to assign.
+    next_id: i64,
+}
+
+impl Converter<'_> {
+    /// Returns a new object ID.
+    fn new_id(&mut self) -> i64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// Converts the toplevel nodes.
+    fn convert(&mut self) {
+        let src_root = self.src.root();
+        let src_objects = src_root.first_child_by_name("Objects");
+        for object in src_objects.iter().flat_map(|objects| objects.children()) {
+            if let Some(name) = object_name(object) {
+                if self.ids.contains_key(&name) {
+                    warn!("Ignoring duplicate object name: {:?}", name);
+                    continue;
+                }
+                let id = self.new_id();
+                self.ids.insert(name, id);
+            }
+        }
+
+        let root = self.dest.root().node_id();
+        let mut has_objects = false;
+        let mut has_connections = false;
+        for node in src_root.children() {
+            match node.name() {
+                "Document" | "Definitions" | "Relations" => {}
+                "Objects" => {
+                    self.convert_objects_section(src_objects);
+                    has_objects = true;
+                }
+                "Connections" => {
+                    self.convert_connections(node);
+                    has_connections = true;
+                }
+                _ => {
+                    copy_node(node, &mut self.dest, root);
+                }
+            }
+        }
+        if !has_objects {
+            self.convert_objects_section(None);
+        }
+        if !has_connections {
+            self.dest.append_new(root, "Connections");
+        }
+    }
+
+    /// Writes `GlobalSettings`, `Documents` and `Objects` nodes.
+    fn convert_objects_section(&mut self, src_objects: Option<NodeHandle<'_>>) {
+        let root = self.dest.root().node_id();
+        let settings =
+            src_objects.and_then(|objects| objects.first_child_by_name("GlobalSettings"));
+        if let Some(settings) = settings {
+            self.convert_node(settings, root, "");
+        }
+
+        let document_id = self.new_id();
+        let documents = self.dest.append_new(root, "Documents");
+        let count = self.dest.append_new(documents, "Count");
+        self.dest.append_attribute(count, 1i32);
+        let document = self.dest.append_new(documents, "Document");
+        self.dest.set_attributes_vec(
+            document,
+            vec![
+                AttributeValue::I64(document_id),
+                "Scene".to_owned().into(),
+                "Scene".to_owned().into(),
+            ],
+        );
+        let root_node = self.dest.append_new(document, "RootNode");
+        self.dest.append_attribute(root_node, ROOT_MODEL_ID);
+
+        let objects = self.dest.append_new(root, "Objects");
+        let mut converted = HashSet::new();
+        for object in src_objects.iter().flat_map(|objects| objects.children()) {
+            if object.name() == "GlobalSettings" {
+                continue;
+            }
+            match object_name(object) {
+                Some(name) if converted.insert(name.clone()) => {
+                    self.convert_object(object, &name, objects)
+                }
+                Some(_) => {}
+                None => warn!(
+                    "Skipping object node without name: node={:?}",
+                    object.name()
+                ),
+            }
+        }
+    }
+
+    /// Converts the object node.
+    fn convert_object(&mut self, src: NodeHandle<'_>, name: &str, objects: NodeId) {
+        let id = self.ids[name];
+        let subclass = src
+            .attributes()
+            .get(1)
+            .and_then(AttributeValue::get_string)
+            .unwrap_or_default()
+            .to_owned();
+        let (class, short_name) = split_name(name);
+
+        let node = self.dest.append_new(objects, src.name());
+        self.dest.set_attributes_vec(
+            node,
+            vec![
+                AttributeValue::I64(id),
+                format!("{}\u{0}\u{1}{}", short_name, class).into(),
+                subclass.clone().into(),
+            ],
+        );
+        let is_mesh = src.name() == "Model" && subclass == "Mesh";
+        let geometry = if is_mesh {
+            let geometry_id = self.new_id();
+            let geometry = self.dest.append_new(objects, "Geometry");
+            self.dest.set_attributes_vec(
+                geometry,
+                vec![
+                    AttributeValue::I64(geometry_id),
+                    format!("{}\u{0}\u{1}Geometry", short_name).into(),
+                    "Mesh".to_owned().into(),
+                ],
+            );
+            self.geometries.push((id, geometry_id));
+            Some(geometry)
+        } else {
+            None
+        };
+
+        for child in src.children() {
+            let is_geometry_child = GEOMETRY_CHILDREN.contains(&child.name())
+                || child.name().starts_with("LayerElement");
+            match geometry {
+                Some(geometry) if is_geometry_child => {
+                    self.convert_node(child, geometry, "Geometry")
+                }
+                _ => self.convert_node(child, node, src.name()),
+            }
+        }
+    }
+
+    /// Converts the non-object node and its descendants.
+    fn convert_node(&mut self, src: NodeHandle<'_>, parent: NodeId, parent_name: &str) {
+        let name = match src.name() {
+            "Properties60" => "Properties70",
+            "Property" if parent_name == "Properties60" => "P",
+            name => name,
+        };
+        let node = self.dest.append_new(parent, name);
+        let attrs = match name {
+            "P" => convert_property(src.attributes()),
+            "Node" if parent_name == "PoseNode" => self.convert_pose_node(src.attributes()),
+            _ => convert_array(src.name(), src.attributes())
+                .map_or_else(|| src.attributes().to_vec(), |array| vec![array]),
+        };
+        self.dest.set_attributes_vec(node, attrs);
+        for child in src.children() {
+            self.convert_node(child, node, src.name());
+        }
+    }
+
+    /// Converts the model name of the pose node into the ID.
+    fn convert_pose_node(&self, attrs: &[AttributeValue]) -> Vec<AttributeValue> {
+        match attrs.first().and_then(AttributeValue::get_string) {
+            Some(name) => match self.ids.get(&ascii_name(name)) {
+                Some(&id) => vec![AttributeValue::I64(id)],
+                None => {
+                    warn!("Unknown model in pose node: {:?}", name);
+                    attrs.to_vec()
+                }
+            },
+            None => attrs.to_vec(),
+        }
+    }
+
+    /// Converts `Connect` nodes into `C` nodes.
+    fn convert_connections(&mut self, src: NodeHandle<'_>) {
+        let root = self.dest.root().node_id();
+        let connections = self.dest.append_new(root, "Connections");
+        for connect in src.children_by_name("Connect") {
+            let attrs = connect.attributes();
+            let ty = attrs.first().and_then(AttributeValue::get_string);
+            let names = (
+                attrs.get(1).and_then(AttributeValue::get_string),
+                attrs.get(2).and_then(AttributeValue::get_string),
+            );
+            let (ty, src_name, dest_name) = match (ty, names) {
+                (Some(ty), (Some(src_name), Some(dest_name))) => {
+                    (ty, ascii_name(src_name), ascii_name(dest_name))
+                }
+                _ => {
+                    warn!("Skipping invalid connection: attrs={:?}", attrs);
+                    continue;
+                }
+            };
+            let src_id = self.ids.get(&src_name).copied();
+            let dest_id = match self.ids.get(&dest_name) {
+                Some(&id) => Some(id),
+                None if dest_name == ROOT_MODEL_NAME => Some(ROOT_MODEL_ID),
+                None => None,
+            };
+            let (src_id, mut dest_id) = match (src_id, dest_id) {
+                (Some(src_id), Some(dest_id)) => (src_id, dest_id),
+                _ => {
+                    warn!(
+                        "Skipping connection with unknown objects: src={:?}, dest={:?}",
+                        src_name, dest_name
+                    );
+                    continue;
+                }
+            };
+            if split_name(&src_name).0 == "Deformer" {
+                if let Some(&(_, geometry)) =
+                    self.geometries.iter().find(|(model, _)| *model == dest_id)
+                {
+                    dest_id = geometry;
+                }
+            }
+
+            let c = self.dest.append_new(connections, "C");
+            let mut new_attrs = vec![
+                ty.to_owned().into(),
+                AttributeValue::I64(src_id),
+                AttributeValue::I64(dest_id),
+            ];
+            new_attrs.extend(attrs.get(3).cloned());
+            self.dest.set_attributes_vec(c, new_attrs);
+        }
+        for &(model, geometry) in &self.geometries {
+            let c = self.dest.append_new(connections, "C");
+            self.dest.set_attributes_vec(
+                c,
+                vec![
+                    "OO".to_owned().into(),
+                    AttributeValue::I64(geometry),
+                    AttributeValue::I64(model),
+                ],
+            );
+        }
+    }
+}
+
+/// Returns the object name in `Class::Name` form.
+fn object_name(node: NodeHandle<'_>) -> Option<String> {
+    node.attributes()
+        .first()
+        .and_then(AttributeValue::get_string)
+        .map(ascii_name)
+}
+
+/// Converts the object name into `Class::Name` form.
+///
+/// Binary FBX may use `Name\u{0}\u{1}Class` form.
+fn ascii_name(name: &str) -> String {
+    match name.find("\u{0}\u{1}") {
+        Some(pos) => format!("{}::{}", &name[pos + 2..], &name[..pos]),
+        None => name.to_owned(),
+    }
+}
+
+/// Splits the `Class::Name` into the class and the name.
+fn split_name(name: &str) -> (&str, &str) {
+    match name.find("::") {
+        Some(pos) => (&name[..pos], &name[pos + 2..]),
+        None => ("", name),
+    }
+}
+
+/// Converts `Property` attributes into `P` attributes.
+fn convert_property(attrs: &[AttributeValue]) -> Vec<AttributeValue> {
+    let data_type = match attrs.get(1).and_then(AttributeValue::get_string) {
+        Some("charptr") => "KString",
+        Some("Real") => "double",
+        Some(v) => v,
+        None => return attrs.to_vec(),
+    };
+    let mut new = vec![
+        attrs[0].clone(),
+        data_type.to_owned().into(),
+        String::new().into(),
+    ];
+    new.extend(attrs.get(2).cloned());
+    for value in attrs.iter().skip(3) {
+        let converted = if STRING_PROPERTY_TYPES.contains(&data_type) {
+            None
+        } else if I32_PROPERTY_TYPES.contains(&data_type) {
+            to_f64(value).map(|v| AttributeValue::I32(v as i32))
+        } else if I64_PROPERTY_TYPES.contains(&data_type) {
+            match value {
+                AttributeValue::I32(v) => Some(AttributeValue::I64(i64::from(*v))),
+                _ => None,
+            }
+        } else {
+            to_f64(value).map(AttributeValue::F64)
+        };
+        new.push(converted.unwrap_or_else(|| value.clone()));
+    }
+    new
+}
+
+/// Converts the attributes of the known array node into an array, or
+/// returns `None` if the node is not an array node or not numeric.
+fn convert_array(name: &str, attrs: &[AttributeValue]) -> Option<AttributeValue> {
+    let is_f64 = F64_ARRAYS.contains(&name);
+    if !is_f64 && !I32_ARRAYS.contains(&name) {
+        return None;
+    }
+    match attrs {
+        [attr @ AttributeValue::ArrF64(_)] if is_f64 => return Some(attr.clone()),
+        [attr @ AttributeValue::ArrI32(_)] if !is_f64 => return Some(attr.clone()),
+        _ => {}
+    }
+    let mut values = Vec::new();
+    for attr in attrs {
+        match attr {
+            AttributeValue::ArrF32(v) => values.extend(v.iter().map(|&v| f64::from(v))),
+            AttributeValue::ArrF64(v) => values.extend_from_slice(v),
+            AttributeValue::ArrI32(v) => values.extend(v.iter().map(|&v| f64::from(v))),
+            AttributeValue::ArrI64(v) => values.extend(v.iter().map(|&v| v as f64)),
+            v => values.push(to_f64(v)?),
+        }
+    }
+    Some(if is_f64 {
+        AttributeValue::ArrF64(values)
+    } else {
+        AttributeValue::ArrI32(values.into_iter().map(|v| v as i32).collect())
+    })
+}
+
+/// Converts the numeric scalar value into `f64`.
+fn to_f64(value: &AttributeValue) -> Option<f64> {
+    Some(match *value {
+        AttributeValue::Bool(v) => f64::from(u8::from(v)),
+        AttributeValue::I16(v) => f64::from(v),
+        AttributeValue::I32(v) => f64::from(v),
+        AttributeValue::I64(v) => v as f64,
+        AttributeValue::F32(v) => f64::from(v),
+        AttributeValue::F64(v) => v,
+        _ => return None,
+    })
+}
@@ -52,18 +52,137 @@
 //!
 //! For detail, see [module documentation of `object`](object/index.html).
 //!
+//! ## Iteration order
+//!
+//! Iterators of the document (such as [`Document::objects`],
+//! [`Document::connections`], and properties of objects) return items in the
+//! order of the nodes in the tree, which is the order in the source file.
+//! Warnings ([`Document::take_warnings`]) are returned in the order they are
+//! recorded.
+//! No iteration order depends on hashing, so the same input always gives
+//! the same output.
+//!
+//! ```
+//! # #[cfg(feature = "edit")]
+//! # fn main() -> Result<(), anyhow::Error> {
+//! use fbxcel_dom::v7400::{
+//!     edit::{DocumentBuilder, ModelKind, SkinBuilder},
+//!     scene::Extractor,
+//! };
+//!
+//! const IDENTITY: [f64; 16] = [
+//!     1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+//! ];
+//!
+//! let mut builder = DocumentBuilder::new();
+//! let mut skins = Vec::new();
+//! for i in 0..4 {
+//!     let model = builder.add_model(&format!("Mesh{}", i), ModelKind::Mesh, None);
+//!     let positions = [[0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+//!     let mesh = builder.add_mesh("", &positions, &[&[0, 1, 2]], Some(model));
+//!     // Clusters with singular bind matrices, recorded as warnings.
+//!     let bone = builder.add_model(&format!("Bone{}", i), ModelKind::LimbNode, None);
+//!     let skin = SkinBuilder::new().cluster(bone, vec![0], vec![1.0], IDENTITY, [0.0; 16]);
+//!     skins.push((mesh, skin));
+//! }
+//! for (mesh, skin) in &skins {
+//!     builder.add_skin("", *mesh, skin)?;
+//! }
+//! let doc = builder.build()?;
+//!
+//! // Objects are in the file order.
+//! let models: Vec<_> = doc
+//!     .objects_by_class("Model", None)
+//!     .filter_map(|obj| obj.name())
+//!     .collect();
+//! assert_eq!(
+//!     models,
+//!     ["Mesh0", "Bone0", "Mesh1", "Bone1", "Mesh2", "Bone2", "Mesh3", "Bone3"]
+//! );
+//!
+//! // Warnings are in the same order with any number of threads.
+//! Extractor::new().threads(1).extract(&doc)?;
+//! let sequential = doc.take_warnings();
+//! assert_eq!(sequential.len(), 4);
+//! for _ in 0..8 {
+//!     Extractor::new().threads(3).extract(&doc)?;
+//!     assert_eq!(doc.take_warnings(), sequential);
+//! }
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "edit"))]
+//! # fn main() {}
+//! ```
+//!
+//! ## Panics
+//!
+//! Broken or hostile FBX data should not cause panics: loading, accessors,
+//! and extraction return errors (or record warnings) instead, and panics on
+//! such data are bugs.
+//! Accessors of mesh and layer element data return `Option` or `Result` for
+//! out-of-range indices.
+//!
+//! Panics are only caused by misuse of the API, such as using an index or
+//! a node ID from another document or scene graph, and they are documented
+//! in the "Panics" section of such methods.
+//! Most of them have fallible alternatives (such as
+//! [`object::ObjectNodeId::get_object_handle`] and
+//! [`scene::SceneGraph::get_node`]).
+//!
 //! [`Document`]: struct.Document.html
 //! [`object::ObjectHandle`]: object/struct.ObjectHandle.html
 //! [`object::ObjectNodeId`]: object/struct.ObjectNodeId.html
 
 pub use self::{
-    document::{Document, Loader},
-    error::LoadError,
+    document::{
+        AttributeMemory, Document, DocumentSummary, IncrementalLoader, Loader, MemoryReport,
+        NameSanitizer, ResourceLimits, Statistics,
+    },
+    error::{Error, ErrorCode, ErrorLocation, LoadError, LoadWarning, Warning, Warnings},
 };
 
+#[cfg(feature = "ascii")]
+pub mod ascii;
+#[cfg(feature = "borrowed")]
+pub mod borrowed;
 pub(crate) mod connection;
 pub mod data;
-mod definition;
+pub mod definition;
+#[cfg(feature = "diff")]
+pub mod diff;
 mod document;
+#[cfg(feature = "edit")]
+pub mod edit;
 pub(crate) mod error;
+#[cfg(feature = "obj-export")]
+pub mod export;
+#[cfg(feature = "gltf-export")]
+pub mod gltf;
+pub mod graph;
+#[cfg(feature = "lint")]
+pub mod lint;
 pub mod object;
+#[cfg(feature = "query")]
+pub mod query;
+pub mod scene;
+#[cfg(feature = "symbol")]
+pub mod symbol;
+#[cfg(not(feature = "symbol"))]
+pub(crate) mod symbol;
+pub mod version;
+#[cfg(feature = "walk")]
+pub mod walk;
+#[cfg(feature = "writer")]
+pub mod write;
+
+/// Returns the number of worker threads to use for the requested number.
+///
+/// `wasm32` targets (such as `wasm32-unknown-unknown`) cannot spawn threads
+/// with `std`, so the work is done on the calling thread there.
+pub(crate) fn available_threads(requested: usize) -> usize {
+    if cfg!(target_arch = "wasm32") {
+        requested.min(1)
+    } else {
+        requested
+    }
+}
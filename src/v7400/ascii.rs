@@ -0,0 +1,73 @@
+//! ASCII FBX support.
+//!
+//! ASCII FBX files are parsed into the same `fbxcel::tree::v7400::Tree` as
+//! binary files, so the loaded documents can be used in the same way.
+//!
+//! ASCII FBX does not record attribute types.
+//! They are inferred from the node names, the property data types and the
+//! values, so that the resulting tree can be loaded with the loaders for
+//! binary FBX.
+//! Object names are converted from `Class::Name` into the binary form.
+//!
+//! Trees can also be written as ASCII FBX with [`write_tree()`] (or
+//! `write::Writer::write_ascii_tree()` with `writer` feature).
+//! Scalar `f32` and `i16` attributes are written as numbers without types,
+//! so they are loaded back as `f64` and `i32`.
+//!
+//! ```no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let text = std::fs::read_to_string("sample.fbx")?;
+//! let doc = fbxcel_dom::v7400::Loader::new().load_from_ascii(&text)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::Write;
+
+use fbxcel::tree::v7400::Tree;
+
+use crate::v7400::ResourceLimits;
+
+pub use self::error::Error;
+
+pub(crate) use self::writer::write_nodes;
+
+mod error;
+mod parser;
+mod types;
+mod writer;
+
+/// Parses ASCII FBX text into an FBX data tree.
+///
+/// The text is parsed with the default [`ResourceLimits`].
+pub fn parse_tree(text: &str) -> Result<Tree, Error> {
+    parse_tree_with_limits(text, &ResourceLimits::default())
+}
+
+/// Parses ASCII FBX text into an FBX data tree with the given resource
+/// limits.
+///
+/// ```
+/// use fbxcel_dom::v7400::{ascii, ResourceLimits};
+///
+/// let limits = ResourceLimits::new().max_depth(2);
+/// let text = "A: {\n B: {\n  C: 1\n }\n}\n";
+/// assert!(ascii::parse_tree_with_limits(text, &limits).is_err());
+/// ```
+pub fn parse_tree_with_limits(text: &str, limits: &ResourceLimits) -> Result<Tree, Error> {
+    parser::parse(text, limits)
+}
+
+/// Writes the FBX data tree as ASCII FBX, and returns the sink.
+///
+/// The version in the header comment is taken from `FBXHeaderExtension`.
+pub fn write_tree<W: Write>(tree: &Tree, sink: W) -> Result<W, Error> {
+    let version = crate::v7400::version::header_version(tree)
+        .unwrap_or(7400)
+        .max(0) as u32;
+    write_nodes(
+        tree.root().children(),
+        (version / 1000, version % 1000 / 100),
+        sink,
+    )
+}
@@ -0,0 +1,76 @@
+//! ASCII FBX error.
+
+use std::{error, fmt, io};
+
+use crate::v7400::error::LimitError;
+
+/// ASCII FBX error.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// I/O error.
+    Io(io::Error),
+    /// Syntax error.
+    Syntax {
+        /// Line number (1-based).
+        line: usize,
+        /// Error message.
+        message: String,
+    },
+    /// Resource limit exceeded.
+    ///
+    /// See [`ResourceLimits`][`crate::v7400::ResourceLimits`].
+    Limit {
+        /// Line number (1-based).
+        line: usize,
+        /// Error message.
+        message: String,
+    },
+}
+
+impl Error {
+    /// Creates a new syntax error.
+    pub(crate) fn syntax(line: usize, message: impl Into<String>) -> Self {
+        Error::Syntax {
+            line,
+            message: message.into(),
+        }
+    }
+
+    /// Creates a new resource limit error.
+    pub(crate) fn limit(line: usize, e: LimitError) -> Self {
+        Error::Limit {
+            line,
+            message: e.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::Syntax { line, message } => {
+                write!(f, "ASCII FBX syntax error at line {}: {}", line, message)
+            }
+            Error::Limit { line, message } => {
+                write!(f, "ASCII FBX error at line {}: {}", line, message)
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Syntax { .. } | Error::Limit { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
@@ -0,0 +1,433 @@
+//! ASCII FBX parser.
+//!
+//! Comments (including the header comment) are skipped, nodes are nested by
+//! braces, and `*N { a: ... }` arrays are checked against the declared
+//! length.
+//!
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use fbxcel_dom::{fbxcel::low::v7400::AttributeValue, v7400::ascii};
+//!
+//! let text = "; FBX 7.4.0 project file
+//! ; ----------------------------------------------------
+//!
+//! FBXHeaderExtension:  {
+//!     FBXVersion: 7400 ; trailing comment
+//! }
+//! Objects:  {
+//!     Geometry: 100, \"Geometry::Quad\", \"Mesh\" {
+//!         Vertices: *6 {
+//!             a: 0,0,0,
+//!             1,0.5,-2e3
+//!         }
+//!         PolygonVertexIndex: *3 {
+//!             a: 0,1,-3
+//!         }
+//!         Custom: *2 {
+//!             a: 1,3000000000
+//!         }
+//!     }
+//! }
+//! ";
+//! let tree = ascii::parse_tree(text)?;
+//!
+//! let toplevel: Vec<_> = tree.root().children().map(|node| node.name()).collect();
+//! assert_eq!(toplevel, ["FBXHeaderExtension", "Objects"]);
+//!
+//! let header = tree.root().first_child_by_name("FBXHeaderExtension").unwrap();
+//! let version = header.first_child_by_name("FBXVersion").unwrap();
+//! assert_eq!(version.attributes(), [AttributeValue::I32(7400)]);
+//!
+//! let objects = tree.root().first_child_by_name("Objects").unwrap();
+//! let geometry = objects.first_child_by_name("Geometry").unwrap();
+//! assert_eq!(geometry.attributes()[0], AttributeValue::I64(100));
+//! let children: Vec<_> = geometry.children().map(|node| node.attributes()).collect();
+//! assert_eq!(
+//!     children,
+//!     [
+//!         [AttributeValue::ArrF64(vec![0.0, 0.0, 0.0, 1.0, 0.5, -2000.0])],
+//!         [AttributeValue::ArrI32(vec![0, 1, -3])],
+//!         [AttributeValue::ArrI64(vec![1, 3_000_000_000])],
+//!     ]
+//! );
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Syntax errors report the line of the offending token.
+//!
+//! ```
+//! use fbxcel_dom::v7400::ascii;
+//!
+//! fn error_line(text: &str) -> usize {
+//!     match ascii::parse_tree(text) {
+//!         Err(ascii::Error::Syntax { line, .. }) => line,
+//!         Err(e) => panic!("Expected syntax error but got {}", e),
+//!         Ok(_) => panic!("Expected syntax error"),
+//!     }
+//! }
+//!
+//! // Unterminated string, reported at the opening quote.
+//! assert_eq!(error_line("A: 1\nB: \"open\n\n"), 2);
+//! // Unbalanced braces.
+//! assert_eq!(error_line("A: {\n}\n}\n"), 3);
+//! assert_eq!(error_line("A: {\n B: 1\n"), 3);
+//! // Token which is neither a node name nor a value.
+//! assert_eq!(error_line("A: 1\n\n*\n"), 3);
+//! // Array length mismatch, reported at the declared length.
+//! assert_eq!(error_line("A: {\n Vertices: *3 {\n  a: 0,1\n }\n}\n"), 2);
+//! assert_eq!(error_line("Vertices: *1 {\n a: 0,1\n}\n"), 1);
+//! // Missing array length and invalid array values.
+//! assert_eq!(error_line("Vertices: * {\n a: 0\n}\n"), 1);
+//! assert_eq!(error_line("\nVertices: *1 {\n a: x\n}\n"), 2);
+//! ```
+
+use fbxcel::tree::v7400::{NodeId, Tree};
+
+use crate::v7400::{
+    ascii::{
+        types::{array_kind, array_value, attribute_values, Kind, RawValue},
+        Error,
+    },
+    document::memory::attribute_heap_size,
+    ResourceLimits,
+};
+
+/// Token.
+#[derive(Debug, Clone, PartialEq)]
+enum Token<'a> {
+    /// Node name followed by a colon.
+    Key(&'a str),
+    /// Quoted string.
+    Str(&'a str),
+    /// Unquoted word (number or symbol).
+    Word(&'a str),
+    /// `,`.
+    Comma,
+    /// `*`.
+    Star,
+    /// `{`.
+    Open,
+    /// `}`.
+    Close,
+}
+
+/// Tokenizer.
+///
+/// Tokens are read one at a time while parsing, so the text is never split
+/// into tokens all at once.
+struct Tokenizer<'a> {
+    /// Source text.
+    text: &'a str,
+    /// Current byte position.
+    pos: usize,
+    /// Current line number.
+    line: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    /// Creates a new `Tokenizer`.
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            pos: 0,
+            line: 1,
+        }
+    }
+
+    /// Reads the next token and its line number.
+    fn next_token(&mut self) -> Result<Option<(Token<'a>, usize)>, Error> {
+        let text = self.text;
+        let bytes = text.as_bytes();
+        while self.pos < bytes.len() {
+            let c = bytes[self.pos];
+            match c {
+                b'\n' => {
+                    self.line += 1;
+                    self.pos += 1;
+                }
+                c if c.is_ascii_whitespace() => self.pos += 1,
+                b';' => {
+                    while self.pos < bytes.len() && bytes[self.pos] != b'\n' {
+                        self.pos += 1;
+                    }
+                }
+                b'"' => {
+                    let line = self.line;
+                    let start = self.pos + 1;
+                    let end = text[start..]
+                        .find('"')
+                        .map(|len| start + len)
+                        .ok_or_else(|| Error::syntax(line, "Unterminated string"))?;
+                    let s = &text[start..end];
+                    self.line += s.matches('\n').count();
+                    self.pos = end + 1;
+                    return Ok(Some((Token::Str(s), line)));
+                }
+                b',' | b'*' | b'{' | b'}' => {
+                    let token = match c {
+                        b',' => Token::Comma,
+                        b'*' => Token::Star,
+                        b'{' => Token::Open,
+                        _ => Token::Close,
+                    };
+                    self.pos += 1;
+                    return Ok(Some((token, self.line)));
+                }
+                _ => {
+                    let start = self.pos;
+                    while self.pos < bytes.len()
+                        && !bytes[self.pos].is_ascii_whitespace()
+                        && !b",*{}:;\"".contains(&bytes[self.pos])
+                    {
+                        self.pos += 1;
+                    }
+                    if self.pos == start {
+                        return Err(Error::syntax(
+                            self.line,
+                            format!("Unexpected character {:?}", text[start..].chars().next()),
+                        ));
+                    }
+                    let word = &text[start..self.pos];
+                    if bytes.get(self.pos) == Some(&b':') {
+                        self.pos += 1;
+                        return Ok(Some((Token::Key(word), self.line)));
+                    }
+                    return Ok(Some((Token::Word(word), self.line)));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Parser state.
+struct Parser<'a> {
+    /// Tokenizer.
+    tokenizer: Tokenizer<'a>,
+    /// Next token and its line number, if already read.
+    peeked: Option<Option<(Token<'a>, usize)>>,
+    /// Resource limits.
+    limits: &'a ResourceLimits,
+    /// Number of parsed nodes.
+    nodes: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Returns the next token without consuming it.
+    fn peek(&mut self) -> Result<Option<&Token<'a>>, Error> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.tokenizer.next_token()?);
+        }
+        Ok(self
+            .peeked
+            .as_ref()
+            .and_then(Option::as_ref)
+            .map(|(token, _)| token))
+    }
+
+    /// Returns the line number of the next token, or the last line at the
+    /// end of the input.
+    fn line(&mut self) -> Result<usize, Error> {
+        self.peek()?;
+        Ok(match &self.peeked {
+            Some(Some((_, line))) => *line,
+            _ => self.tokenizer.line,
+        })
+    }
+
+    /// Consumes the next token.
+    fn next(&mut self) -> Result<Option<Token<'a>>, Error> {
+        self.peek()?;
+        Ok(self.peeked.take().flatten().map(|(token, _)| token))
+    }
+
+    /// Consumes the next token and checks that it is the expected one.
+    fn expect(&mut self, expected: Token<'_>) -> Result<(), Error> {
+        let line = self.line()?;
+        match self.next()? {
+            Some(token) if token == expected => Ok(()),
+            token => Err(Error::syntax(
+                line,
+                format!("Expected {:?} but got {:?}", expected, token),
+            )),
+        }
+    }
+
+    /// Parses nodes until the end of the input.
+    ///
+    /// Nodes are parsed with an explicit stack, so deeply nested text does
+    /// not overflow the call stack.
+    fn parse_nodes(&mut self, tree: &mut Tree) -> Result<(), Error> {
+        // Open nodes and their names, the implicit root first.
+        let mut stack = vec![(tree.root().node_id(), "")];
+        loop {
+            let line = self.line()?;
+            match self.next()? {
+                None if stack.len() > 1 => {
+                    return Err(Error::syntax(
+                        line,
+                        format!("Expected {:?} but got None", Token::Close),
+                    ))
+                }
+                None => return Ok(()),
+                Some(Token::Close) if stack.len() > 1 => {
+                    stack.pop();
+                }
+                Some(Token::Close) => return Err(Error::syntax(line, "Unexpected `}`")),
+                Some(Token::Key(name)) => {
+                    let (parent, parent_name) = stack[stack.len() - 1];
+                    self.nodes += 1;
+                    self.limits
+                        .check_nodes(self.nodes)
+                        .map_err(|e| Error::limit(line, e))?;
+                    self.limits
+                        .check_depth("", stack.len())
+                        .map_err(|e| Error::limit(line, e.with_node(node_path(&stack, name))))?;
+                    let path = || node_path(&stack, name);
+                    if let Some(node) = self.parse_node(tree, parent, parent_name, name, path)? {
+                        stack.push((node, name));
+                    }
+                }
+                Some(token) => {
+                    return Err(Error::syntax(
+                        line,
+                        format!("Expected node name but got {:?}", token),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Checks the size of an attribute of the node at `path` in bytes.
+    fn check_size(&self, line: usize, path: impl Fn() -> String, bytes: u64) -> Result<(), Error> {
+        self.limits
+            .check_array("", bytes)
+            .map_err(|e| Error::limit(line, e.with_node(path())))
+    }
+
+    /// Parses the attributes of the node.
+    ///
+    /// Returns the node if its children follow, with `{` consumed.
+    fn parse_node(
+        &mut self,
+        tree: &mut Tree,
+        parent: NodeId,
+        parent_name: &str,
+        name: &'a str,
+        path: impl Fn() -> String,
+    ) -> Result<Option<NodeId>, Error> {
+        let node = tree.append_new(parent, name);
+
+        if self.peek()? == Some(&Token::Star) {
+            self.next()?;
+            let line = self.line()?;
+            let len = match self.next()? {
+                Some(Token::Word(len)) => len.parse::<usize>().ok(),
+                _ => None,
+            }
+            .ok_or_else(|| Error::syntax(line, "Expected array length after `*`"))?;
+            // Check the declared length before reading the values.
+            let elem_size = match array_kind(name) {
+                Some(Kind::I64) | Some(Kind::F64) => 8,
+                _ => 4,
+            };
+            self.check_size(line, &path, (len as u64).saturating_mul(elem_size))?;
+            self.expect(Token::Open)?;
+            self.expect(Token::Key("a"))?;
+            let values = self.parse_values(Some(len))?;
+            if values.len() > len {
+                return Err(Error::syntax(
+                    line,
+                    format!(
+                        "Array length mismatch for `{}`: declared {} but got more",
+                        name, len
+                    ),
+                ));
+            }
+            self.expect(Token::Close)?;
+            if values.len() != len {
+                return Err(Error::syntax(
+                    line,
+                    format!(
+                        "Array length mismatch for `{}`: declared {} but got {}",
+                        name,
+                        len,
+                        values.len()
+                    ),
+                ));
+            }
+            let value =
+                array_value(name, &values).map_err(|message| Error::syntax(line, message))?;
+            self.check_size(line, &path, attribute_heap_size(&value) as u64)?;
+            tree.append_attribute(node, value);
+            return Ok(None);
+        }
+
+        let line = self.line()?;
+        let values = self.parse_values(None)?;
+        let attrs = attribute_values(parent_name, name, values)
+            .map_err(|message| Error::syntax(line, message))?;
+        for attr in &attrs {
+            self.check_size(line, &path, attribute_heap_size(attr) as u64)?;
+        }
+        tree.set_attributes_vec(node, attrs);
+
+        if self.peek()? == Some(&Token::Open) {
+            self.next()?;
+            return Ok(Some(node));
+        }
+
+        Ok(None)
+    }
+
+    /// Parses comma-separated values.
+    ///
+    /// Empty values (such as leading commas) are skipped.
+    /// If there are more than `max` values, stops after reading one extra
+    /// value.
+    fn parse_values(&mut self, max: Option<usize>) -> Result<Vec<RawValue<'a>>, Error> {
+        let mut values = Vec::new();
+        loop {
+            if max.map_or(false, |max| values.len() > max) {
+                return Ok(values);
+            }
+            match self.peek()? {
+                Some(&Token::Str(s)) => values.push(RawValue::Str(s)),
+                Some(&Token::Word(w)) => values.push(RawValue::Word(w)),
+                Some(Token::Comma) => {}
+                _ => return Ok(values),
+            }
+            self.next()?;
+        }
+    }
+}
+
+/// Returns the path of the node.
+///
+/// `stack` is the parsing stack of the parent node, and `name` is the name
+/// of the node.
+fn node_path(stack: &[(NodeId, &str)], name: &str) -> String {
+    let mut path = stack
+        .iter()
+        .skip(1)
+        .map(|&(_, name)| name)
+        .collect::<Vec<_>>();
+    path.push(name);
+    path.join("/")
+}
+
+/// Parses ASCII FBX text into an FBX data tree.
+pub(crate) fn parse(text: &str, limits: &ResourceLimits) -> Result<Tree, Error> {
+    let mut parser = Parser {
+        tokenizer: Tokenizer::new(text),
+        peeked: None,
+        limits,
+        nodes: 0,
+    };
+    let mut tree = Tree::default();
+    parser.parse_nodes(&mut tree)?;
+
+    Ok(tree)
+}
@@ -0,0 +1,401 @@
+//! Attribute type inference for ASCII FBX.
+//!
+//! ASCII FBX does not record attribute types, so they are inferred from the
+//! node names and the property data types, to be compatible with the trees
+//! loaded from binary FBX.
+//!
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use fbxcel_dom::{
+//!     fbxcel::{low::v7400::AttributeValue, tree::v7400::NodeHandle},
+//!     v7400::ascii,
+//! };
+//!
+//! fn child<'a>(node: NodeHandle<'a>, name: &str) -> NodeHandle<'a> {
+//!     node.first_child_by_name(name).expect("should exist")
+//! }
+//!
+//! let text = r#"
+//! Objects:  {
+//!     Model: 200, "Model::Say &quot;Hi&quot;", "Mesh" {
+//!         Properties70:  {
+//!             P: "Visibility", "Visibility", "", "A",1
+//!             P: "Lcl Translation", "Lcl Translation", "", "A",1,2,3
+//!             P: "Time", "KTime", "Time", "",46186158000
+//!             P: "Path", "KString", "", "", "C:\dir\a,b.png"
+//!         }
+//!         Shading: T
+//!         Culling: "CullingOff"
+//!     }
+//!     Video: 300, "Video::Clip", "Clip" {
+//!         Content: , "aGVs",
+//!             "bG8="
+//!     }
+//! }
+//! Connections:  {
+//!     C: "OO",200,0
+//! }
+//! "#;
+//! let tree = ascii::parse_tree(text)?;
+//! let root = tree.root();
+//!
+//! let objects = child(root, "Objects");
+//! let model = child(objects, "Model");
+//! // IDs are `i64`, and object names are converted into the binary form,
+//! // with `&quot;` unescaped.
+//! assert_eq!(model.attributes()[0], AttributeValue::I64(200));
+//! assert_eq!(
+//!     model.attributes()[1],
+//!     AttributeValue::String("Say \"Hi\"\u{0}\u{1}Model".to_owned())
+//! );
+//!
+//! // Property values are typed by the property data types.
+//! let props: Vec<_> = child(model, "Properties70")
+//!     .children()
+//!     .map(|p| p.attributes()[4..].to_vec())
+//!     .collect();
+//! assert_eq!(
+//!     props,
+//!     [
+//!         vec![AttributeValue::F64(1.0)],
+//!         vec![
+//!             AttributeValue::F64(1.0),
+//!             AttributeValue::F64(2.0),
+//!             AttributeValue::F64(3.0),
+//!         ],
+//!         vec![AttributeValue::I64(46_186_158_000)],
+//!         vec![AttributeValue::String(r"C:\dir\a,b.png".to_owned())],
+//!     ]
+//! );
+//! assert_eq!(child(model, "Shading").attributes(), [AttributeValue::Bool(true)]);
+//! assert_eq!(
+//!     child(model, "Culling").attributes(),
+//!     [AttributeValue::String("CullingOff".to_owned())]
+//! );
+//!
+//! // Base64 content may be split into multiple strings.
+//! let video = child(objects, "Video");
+//! assert_eq!(
+//!     child(video, "Content").attributes(),
+//!     [AttributeValue::Binary(b"hello".to_vec())]
+//! );
+//!
+//! let connection = child(child(root, "Connections"), "C");
+//! assert_eq!(
+//!     connection.attributes(),
+//!     [
+//!         AttributeValue::String("OO".to_owned()),
+//!         AttributeValue::I64(200),
+//!         AttributeValue::I64(0),
+//!     ]
+//! );
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Values which cannot be converted are reported as syntax errors.
+//!
+//! ```
+//! use fbxcel_dom::v7400::ascii;
+//!
+//! // Strings in numeric arrays.
+//! assert!(ascii::parse_tree("Vertices: *1 {\n a: \"x\"\n}\n").is_err());
+//! // Non-integer object IDs.
+//! assert!(ascii::parse_tree("Objects: {\n Model: 1.5, \"Model::A\", \"Null\"\n}\n").is_err());
+//! // Broken base64 content.
+//! assert!(ascii::parse_tree("Content: \"a*b\"\n").is_err());
+//! ```
+
+use std::convert::TryFrom;
+
+use fbxcel::low::v7400::AttributeValue;
+
+/// Raw attribute value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum RawValue<'a> {
+    /// Quoted string.
+    Str(&'a str),
+    /// Unquoted word.
+    Word(&'a str),
+}
+
+/// Attribute type to convert into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    /// `i32`.
+    I32,
+    /// `i64`.
+    I64,
+    /// `f32`.
+    F32,
+    /// `f64`.
+    F64,
+}
+
+/// Array node names and their element types.
+pub(crate) const ARRAY_KINDS: &[(&str, Kind)] = &[
+    ("Vertices", Kind::F64),
+    ("Normals", Kind::F64),
+    ("NormalsW", Kind::F64),
+    ("Binormals", Kind::F64),
+    ("BinormalsW", Kind::F64),
+    ("Tangents", Kind::F64),
+    ("TangentsW", Kind::F64),
+    ("UV", Kind::F64),
+    ("Colors", Kind::F64),
+    ("Weights", Kind::F64),
+    ("FullWeights", Kind::F64),
+    ("Transform", Kind::F64),
+    ("TransformLink", Kind::F64),
+    ("TransformAssociateModel", Kind::F64),
+    ("Matrix", Kind::F64),
+    ("Points", Kind::F64),
+    ("KnotVector", Kind::F64),
+    ("KnotVectorU", Kind::F64),
+    ("KnotVectorV", Kind::F64),
+    ("EdgeCrease", Kind::F64),
+    ("VertexCrease", Kind::F64),
+    ("KeyValueFloat", Kind::F32),
+    ("KeyAttrDataFloat", Kind::F32),
+    ("KeyTime", Kind::I64),
+    ("PolygonVertexIndex", Kind::I32),
+    ("Edges", Kind::I32),
+    ("Materials", Kind::I32),
+    ("Smoothing", Kind::I32),
+    ("NormalsIndex", Kind::I32),
+    ("BinormalsIndex", Kind::I32),
+    ("TangentsIndex", Kind::I32),
+    ("UVIndex", Kind::I32),
+    ("ColorIndex", Kind::I32),
+    ("Indexes", Kind::I32),
+    ("KeyAttrFlags", Kind::I32),
+    ("KeyAttrRefCount", Kind::I32),
+    ("TextureId", Kind::I32),
+];
+
+/// Scalar node names and their types.
+///
+/// Numbers of other nodes are loaded as `i32` (or `i64` if too large) for
+/// integers, and `f64` otherwise.
+pub(crate) const SCALAR_KINDS: &[(&str, Kind)] = &[
+    ("Default", Kind::F64),
+    ("Link_DeformAcuracy", Kind::F64),
+    ("DeformPercent", Kind::F64),
+    ("FullWeights", Kind::F64),
+    ("Node", Kind::I64),
+    ("RootNode", Kind::I64),
+];
+
+/// Property data types whose numeric values are `i32`.
+pub(crate) const I32_PROPERTY_TYPES: &[&str] = &[
+    "bool",
+    "Bool",
+    "int",
+    "Integer",
+    "enum",
+    "Enum",
+    "Visibility Inheritance",
+];
+
+/// Property data types whose numeric values are `i64`.
+pub(crate) const I64_PROPERTY_TYPES: &[&str] = &["KTime", "Time", "LongLong", "ULongLong"];
+
+/// Returns the array kind for the node.
+pub(crate) fn array_kind(name: &str) -> Option<Kind> {
+    ARRAY_KINDS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|&(_, kind)| kind)
+}
+
+/// Converts the array values.
+pub(crate) fn array_value(name: &str, values: &[RawValue<'_>]) -> Result<AttributeValue, String> {
+    let words = values
+        .iter()
+        .map(|v| match v {
+            RawValue::Word(w) => Ok(*w),
+            RawValue::Str(s) => Err(format!("Unexpected string in array `{}`: {:?}", name, s)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let kind = array_kind(name).unwrap_or_else(|| {
+        if words.iter().any(|w| is_float(w)) {
+            Kind::F64
+        } else if words.iter().any(|w| w.parse::<i32>().is_err()) {
+            Kind::I64
+        } else {
+            Kind::I32
+        }
+    });
+    let err = |w: &str| format!("Invalid number in array `{}`: {:?}", name, w);
+    Ok(match kind {
+        Kind::I32 => AttributeValue::ArrI32(
+            words
+                .iter()
+                .map(|w| w.parse().map_err(|_| err(w)))
+                .collect::<Result<_, _>>()?,
+        ),
+        Kind::I64 => AttributeValue::ArrI64(
+            words
+                .iter()
+                .map(|w| w.parse().map_err(|_| err(w)))
+                .collect::<Result<_, _>>()?,
+        ),
+        Kind::F32 => AttributeValue::ArrF32(
+            words
+                .iter()
+                .map(|w| w.parse().map_err(|_| err(w)))
+                .collect::<Result<_, _>>()?,
+        ),
+        Kind::F64 => AttributeValue::ArrF64(
+            words
+                .iter()
+                .map(|w| parse_float(w).ok_or_else(|| err(w)))
+                .collect::<Result<_, _>>()?,
+        ),
+    })
+}
+
+/// Converts the attribute values of the node.
+pub(crate) fn attribute_values(
+    parent_name: &str,
+    name: &str,
+    values: Vec<RawValue<'_>>,
+) -> Result<Vec<AttributeValue>, String> {
+    if name == "Content" && !values.is_empty() {
+        let mut encoded = String::new();
+        for value in &values {
+            match value {
+                RawValue::Str(s) => encoded.push_str(s),
+                RawValue::Word(w) => return Err(format!("Unexpected word in `Content`: {:?}", w)),
+            }
+        }
+        let decoded =
+            decode_base64(&encoded).ok_or_else(|| "Invalid base64 data in `Content`".to_owned())?;
+        return Ok(vec![AttributeValue::Binary(decoded)]);
+    }
+
+    let property_kind = if name == "P" {
+        let data_type = match values.get(1) {
+            Some(RawValue::Str(s)) => *s,
+            _ => "",
+        };
+        Some(if I32_PROPERTY_TYPES.contains(&data_type) {
+            Kind::I32
+        } else if I64_PROPERTY_TYPES.contains(&data_type) {
+            Kind::I64
+        } else {
+            Kind::F64
+        })
+    } else {
+        None
+    };
+    let scalar_kind = SCALAR_KINDS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|&(_, kind)| kind);
+
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let word = match value {
+                RawValue::Str(s) => {
+                    let s = s.replace("&quot;", "\"");
+                    return Ok(AttributeValue::String(
+                        if parent_name == "Objects" && i == 1 {
+                            binary_object_name(&s)
+                        } else {
+                            s
+                        },
+                    ));
+                }
+                RawValue::Word(w) => w,
+            };
+            let is_id = match (parent_name, name) {
+                ("Objects", _) | (_, "Document") => i == 0,
+                (_, "C") => i == 1 || i == 2,
+                _ => false,
+            };
+            let kind = if is_id {
+                Some(Kind::I64)
+            } else {
+                property_kind.or(scalar_kind)
+            };
+            scalar_value(word, kind)
+                .ok_or_else(|| format!("Invalid value for `{}`: {:?}", name, word))
+        })
+        .collect()
+}
+
+/// Converts the scalar word into an attribute value.
+fn scalar_value(word: &str, kind: Option<Kind>) -> Option<AttributeValue> {
+    match word {
+        "T" | "Y" => return Some(AttributeValue::Bool(true)),
+        "F" | "N" => return Some(AttributeValue::Bool(false)),
+        _ => {}
+    }
+    let kind = match kind {
+        Some(v) => v,
+        None if is_float(word) => Kind::F64,
+        None => match word.parse::<i64>() {
+            Ok(v) if i32::try_from(v).is_ok() => Kind::I32,
+            Ok(_) => Kind::I64,
+            Err(_) => return Some(AttributeValue::String(word.to_owned())),
+        },
+    };
+    Some(match kind {
+        Kind::I32 => AttributeValue::I32(word.parse().ok()?),
+        Kind::I64 => AttributeValue::I64(word.parse().ok()?),
+        Kind::F32 => AttributeValue::F32(parse_float(word)? as f32),
+        Kind::F64 => AttributeValue::F64(parse_float(word)?),
+    })
+}
+
+/// Returns true if the word looks like a floating point number.
+fn is_float(word: &str) -> bool {
+    word.contains(|c| c == '.' || c == 'e' || c == 'E') && parse_float(word).is_some()
+        || word.eq_ignore_ascii_case("nan")
+        || word.eq_ignore_ascii_case("inf")
+        || word.eq_ignore_ascii_case("-inf")
+}
+
+/// Parses the floating point number, including integers.
+fn parse_float(word: &str) -> Option<f64> {
+    word.parse().ok()
+}
+
+/// Converts the ASCII object name (`Class::Name`) into the binary form
+/// (`Name\u{0}\u{1}Class`).
+pub(crate) fn binary_object_name(name: &str) -> String {
+    match name.find("::") {
+        Some(pos) => format!("{}\u{0}\u{1}{}", &name[pos + 2..], &name[..pos]),
+        None => name.to_owned(),
+    }
+}
+
+/// Decodes base64 data, ignoring whitespaces.
+fn decode_base64(s: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(s.len() * 3 / 4);
+    let mut buf = 0u32;
+    let mut bits = 0;
+    for c in s.bytes() {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            c if c.is_ascii_whitespace() => continue,
+            _ => return None,
+        };
+        buf = (buf << 6) | u32::from(v);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buf >> bits) as u8);
+        }
+    }
+
+    Some(out)
+}
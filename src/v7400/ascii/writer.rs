@@ -0,0 +1,262 @@
+//! ASCII FBX writer.
+//!
+//! Trees written as ASCII FBX are parsed back into the same trees, as long
+//! as the attribute types are the ones inferred by the parser.
+//!
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use fbxcel_dom::{
+//!     fbxcel::{
+//!         low::v7400::AttributeValue,
+//!         tree::v7400::{NodeHandle, Tree},
+//!     },
+//!     v7400::ascii,
+//! };
+//!
+//! fn assert_same_nodes(expected: NodeHandle<'_>, actual: NodeHandle<'_>) {
+//!     assert_eq!(expected.name(), actual.name());
+//!     assert_eq!(expected.attributes(), actual.attributes(), "node {:?}", expected.name());
+//!     let expected: Vec<_> = expected.children().collect();
+//!     let actual: Vec<_> = actual.children().collect();
+//!     assert_eq!(expected.len(), actual.len());
+//!     for (expected, actual) in expected.into_iter().zip(actual) {
+//!         assert_same_nodes(expected, actual);
+//!     }
+//! }
+//!
+//! let mut tree = Tree::default();
+//! let root = tree.root().node_id();
+//! let header = tree.append_new(root, "FBXHeaderExtension");
+//! let version = tree.append_new(header, "FBXVersion");
+//! tree.append_attribute(version, AttributeValue::I32(7400));
+//!
+//! let objects = tree.append_new(root, "Objects");
+//! let geometry = tree.append_new(objects, "Geometry");
+//! tree.append_attribute(geometry, AttributeValue::I64(100));
+//! tree.append_attribute(geometry, AttributeValue::String("Quad \"1\"\u{0}\u{1}Geometry".into()));
+//! tree.append_attribute(geometry, AttributeValue::String("Mesh".into()));
+//! let props = tree.append_new(geometry, "Properties70");
+//! let prop = tree.append_new(props, "P");
+//! for s in &["Path", "KString", "", ""] {
+//!     tree.append_attribute(prop, AttributeValue::String((*s).into()));
+//! }
+//! tree.append_attribute(prop, AttributeValue::String(r"C:\dir\a,b.png".into()));
+//! let prop = tree.append_new(props, "P");
+//! for s in &["Weight", "double", "Number", "A"] {
+//!     tree.append_attribute(prop, AttributeValue::String((*s).into()));
+//! }
+//! tree.append_attribute(prop, AttributeValue::F64(0.1));
+//! // Long arrays are wrapped into multiple lines.
+//! let vertices = (0..100).map(|i| f64::from(i) / 3.0).collect();
+//! let node = tree.append_new(geometry, "Vertices");
+//! tree.append_attribute(node, AttributeValue::ArrF64(vertices));
+//! let node = tree.append_new(geometry, "PolygonVertexIndex");
+//! tree.append_attribute(node, AttributeValue::ArrI32(vec![0, 1, -3]));
+//! let node = tree.append_new(geometry, "Custom");
+//! tree.append_attribute(node, AttributeValue::ArrI64(vec![1, i64::MAX]));
+//! let node = tree.append_new(geometry, "Smooth");
+//! tree.append_attribute(node, AttributeValue::Bool(true));
+//! let node = tree.append_new(geometry, "Content");
+//! tree.append_attribute(node, AttributeValue::Binary((0..=255).collect()));
+//!
+//! let connections = tree.append_new(root, "Connections");
+//! let connection = tree.append_new(connections, "C");
+//! tree.append_attribute(connection, AttributeValue::String("OO".into()));
+//! tree.append_attribute(connection, AttributeValue::I64(100));
+//! tree.append_attribute(connection, AttributeValue::I64(0));
+//!
+//! let text = String::from_utf8(ascii::write_tree(&tree, Vec::new())?)?;
+//! assert!(text.starts_with("; FBX 7.4.0 project file\n"));
+//! let parsed = ascii::parse_tree(&text)?;
+//! assert_same_nodes(tree.root(), parsed.root());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::Write;
+
+use fbxcel::{low::v7400::AttributeValue, tree::v7400::NodeHandle};
+
+use crate::v7400::ascii::Error;
+
+/// Maximum length of array lines before wrapping.
+const ARRAY_LINE_WIDTH: usize = 100;
+
+/// Base64 alphabet.
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Writes the toplevel nodes as ASCII FBX, and returns the sink.
+///
+/// `version` is the major and minor FBX version written to the header
+/// comment.
+pub(crate) fn write_nodes<'a, W: Write>(
+    nodes: impl IntoIterator<Item = NodeHandle<'a>>,
+    version: (u32, u32),
+    mut sink: W,
+) -> Result<W, Error> {
+    writeln!(sink, "; FBX {}.{}.0 project file", version.0, version.1)?;
+    writeln!(
+        sink,
+        "; ----------------------------------------------------"
+    )?;
+    for node in nodes {
+        writeln!(sink)?;
+        write_node(&mut sink, node, "", 0)?;
+    }
+    sink.flush()?;
+
+    Ok(sink)
+}
+
+/// Writes the node and its descendants.
+fn write_node<W: Write>(
+    sink: &mut W,
+    node: NodeHandle<'_>,
+    parent_name: &str,
+    depth: usize,
+) -> Result<(), Error> {
+    let indent = "\t".repeat(depth);
+    write!(sink, "{}{}: ", indent, node.name())?;
+
+    let attrs = node.attributes();
+    let has_children = node.children().next().is_some();
+    if let [attr] = attrs {
+        if !has_children {
+            if let Some(len) = array_len(attr) {
+                writeln!(sink, "*{} {{", len)?;
+                write!(sink, "{}\ta: ", indent)?;
+                write_array(sink, attr, &indent)?;
+                writeln!(sink)?;
+                writeln!(sink, "{}}} ", indent)?;
+                return Ok(());
+            }
+        }
+    }
+
+    for (i, attr) in attrs.iter().enumerate() {
+        if i != 0 {
+            sink.write_all(b", ")?;
+        }
+        let is_object_name = parent_name == "Objects" && i == 1;
+        write_attribute(sink, attr, is_object_name)?;
+    }
+    if has_children {
+        writeln!(sink, " {{")?;
+        for child in node.children() {
+            write_node(sink, child, node.name(), depth + 1)?;
+        }
+        write!(sink, "{}}}", indent)?;
+    }
+    writeln!(sink)?;
+
+    Ok(())
+}
+
+/// Returns the number of elements if the attribute is an array.
+fn array_len(attr: &AttributeValue) -> Option<usize> {
+    match attr {
+        AttributeValue::ArrBool(v) => Some(v.len()),
+        AttributeValue::ArrI32(v) => Some(v.len()),
+        AttributeValue::ArrI64(v) => Some(v.len()),
+        AttributeValue::ArrF32(v) => Some(v.len()),
+        AttributeValue::ArrF64(v) => Some(v.len()),
+        _ => None,
+    }
+}
+
+/// Writes the elements of the array attribute, wrapping long lines.
+fn write_array<W: Write>(sink: &mut W, attr: &AttributeValue, indent: &str) -> Result<(), Error> {
+    let values: Vec<String> = match attr {
+        AttributeValue::ArrBool(v) => v.iter().map(|&v| (v as i32).to_string()).collect(),
+        AttributeValue::ArrI32(v) => v.iter().map(ToString::to_string).collect(),
+        AttributeValue::ArrI64(v) => v.iter().map(ToString::to_string).collect(),
+        AttributeValue::ArrF32(v) => v.iter().map(|&v| format_f32(v)).collect(),
+        AttributeValue::ArrF64(v) => v.iter().map(|&v| format_f64(v)).collect(),
+        _ => unreachable!("Should never fail: the attribute is checked to be an array"),
+    };
+    let mut line_len = 0;
+    for (i, value) in values.iter().enumerate() {
+        if i != 0 {
+            sink.write_all(b",")?;
+            line_len += 1;
+            if line_len + value.len() > ARRAY_LINE_WIDTH {
+                write!(sink, "\n{}\t", indent)?;
+                line_len = 0;
+            }
+        }
+        sink.write_all(value.as_bytes())?;
+        line_len += value.len();
+    }
+
+    Ok(())
+}
+
+/// Writes the non-array attribute.
+///
+/// Arrays which are not the only attribute of the node are written as
+/// comma-separated scalars.
+fn write_attribute<W: Write>(
+    sink: &mut W,
+    attr: &AttributeValue,
+    is_object_name: bool,
+) -> Result<(), Error> {
+    match attr {
+        AttributeValue::Bool(v) => sink.write_all(if *v { b"T" } else { b"F" })?,
+        AttributeValue::I16(v) => write!(sink, "{}", v)?,
+        AttributeValue::I32(v) => write!(sink, "{}", v)?,
+        AttributeValue::I64(v) => write!(sink, "{}", v)?,
+        AttributeValue::F32(v) => sink.write_all(format_f32(*v).as_bytes())?,
+        AttributeValue::F64(v) => sink.write_all(format_f64(*v).as_bytes())?,
+        AttributeValue::String(v) => {
+            let escaped = v.replace('"', "&quot;");
+            if is_object_name {
+                write!(sink, "\"{}\"", ascii_object_name(&escaped))?;
+            } else {
+                write!(sink, "\"{}\"", escaped)?;
+            }
+        }
+        AttributeValue::Binary(v) => write!(sink, ", \"{}\"", encode_base64(v))?,
+        _ => write_array(sink, attr, "")?,
+    }
+
+    Ok(())
+}
+
+/// Formats the `f64` value so that it is parsed back as a float.
+fn format_f64(v: f64) -> String {
+    format!("{:?}", v)
+}
+
+/// Formats the `f32` value so that it is parsed back as a float.
+fn format_f32(v: f32) -> String {
+    format!("{:?}", v)
+}
+
+/// Converts the binary object name (`Name\u{0}\u{1}Class`) into the ASCII
+/// form (`Class::Name`).
+fn ascii_object_name(name: &str) -> String {
+    match name.find("\u{0}\u{1}") {
+        Some(pos) => format!("{}::{}", &name[pos + 2..], &name[..pos]),
+        None => name.to_owned(),
+    }
+}
+
+/// Encodes the data into base64.
+fn encode_base64(data: &[u8]) -> String {
+    let mut out = String::with_capacity((data.len() + 2) / 3 * 4);
+    for chunk in data.chunks(3) {
+        let buf = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |buf, (i, &b)| buf | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_CHARS[(buf >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+
+    out
+}
@@ -0,0 +1,776 @@
+//! Zero-copy view of binary FBX data.
+//!
+//! [`BorrowedTree`] indexes the node records of binary FBX data in memory
+//! (such as a memory-mapped file) without copying the attribute values.
+//! Strings, binaries and uncompressed arrays are borrowed from the data, and
+//! compressed arrays are decoded only when their values are requested.
+//!
+//! This is useful to read large arrays (such as vertices of huge meshes)
+//! with low peak memory usage.
+//! To use the DOM APIs, load a [`Document`][`crate::v7400::Document`] with
+//! [`Loader::load_from_slice()`][`crate::v7400::Loader::load_from_slice`]
+//! instead, which does not copy the input but keeps owned attribute values.
+//!
+//! ```no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use fbxcel_dom::v7400::borrowed::{BorrowedAttribute, BorrowedTree};
+//!
+//! // Memory-mapped file can be used instead.
+//! let data = std::fs::read("sample.fbx")?;
+//! let tree = BorrowedTree::parse(&data)?;
+//! let objects = tree.root().first_child_by_name("Objects").ok_or("no objects")?;
+//! for geometry in objects.children_by_name("Geometry") {
+//!     let vertices = match geometry.first_child_by_name("Vertices") {
+//!         Some(v) => v,
+//!         None => continue,
+//!     };
+//!     if let Some(BorrowedAttribute::ArrF64(arr)) = vertices.attributes().first() {
+//!         // Borrowed from `data` if the array is not compressed.
+//!         let sum: f64 = arr.values()?.sum();
+//!         println!("{} values, sum={}", arr.len(), sum);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{borrow::Cow, convert::TryFrom, io::Read, marker::PhantomData, str};
+
+use fbxcel::low::{v7400::AttributeValue, FbxHeader, FbxVersion};
+use log::trace;
+
+use crate::v7400::{
+    document::{
+        recovery::{read_bool, read_u32, read_u64},
+        MAX_PREALLOCATION,
+    },
+    error::LimitError,
+    version::Capabilities,
+    LoadError, ResourceLimits,
+};
+
+/// Length of the binary FBX header.
+const HEADER_LEN: usize = 27;
+
+/// Zero-copy tree of binary FBX node records.
+#[derive(Debug, Clone)]
+pub struct BorrowedTree<'a> {
+    /// FBX version.
+    fbx_version: FbxVersion,
+    /// Nodes, the implicit root at index 0.
+    nodes: Vec<NodeRecord<'a>>,
+}
+
+/// Node record.
+#[derive(Debug, Clone)]
+struct NodeRecord<'a> {
+    /// Node name.
+    name: &'a str,
+    /// Node attributes.
+    attributes: Vec<BorrowedAttribute<'a>>,
+    /// Indices of child nodes.
+    children: Vec<usize>,
+}
+
+impl<'a> BorrowedTree<'a> {
+    /// Parses the binary FBX 7.x data (including the header) with the
+    /// default resource limits.
+    ///
+    /// The footer is not read.
+    ///
+    /// The parsed nodes and attributes are the same as the `fbxcel` parser
+    /// reads:
+    ///
+    /// ```
+    /// # #[cfg(feature = "writer")]
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::io::Cursor;
+    ///
+    /// use fbxcel_dom::{
+    ///     fbxcel::{
+    ///         low::{v7400::AttributeValue, FbxVersion},
+    ///         tree::{
+    ///             any::AnyTree,
+    ///             v7400::{NodeHandle, Tree},
+    ///         },
+    ///     },
+    ///     v7400::{
+    ///         borrowed::{BorrowedAttribute, BorrowedNode, BorrowedTree},
+    ///         write::Writer,
+    ///     },
+    /// };
+    ///
+    /// fn assert_same(borrowed: BorrowedNode<'_, '_>, node: NodeHandle<'_>) {
+    ///     assert_eq!(borrowed.name(), node.name());
+    ///     let attrs: Vec<_> = borrowed
+    ///         .attributes()
+    ///         .iter()
+    ///         .map(|attr| attr.to_attribute_value().expect("should be readable"))
+    ///         .collect();
+    ///     assert_eq!(attrs, node.attributes());
+    ///     assert_eq!(borrowed.children().count(), node.children().count());
+    ///     for (borrowed, node) in borrowed.children().zip(node.children()) {
+    ///         assert_same(borrowed, node);
+    ///     }
+    /// }
+    ///
+    /// let mut tree = Tree::default();
+    /// let root = tree.root().node_id();
+    /// let outer = tree.append_new(root, "Outer");
+    /// tree.append_attribute(outer, AttributeValue::String("Name\u{0}\u{1}Class".into()));
+    /// tree.append_attribute(outer, AttributeValue::Binary(vec![0, 1, 2]));
+    /// let inner = tree.append_new(outer, "Inner");
+    /// tree.append_attribute(inner, AttributeValue::I64(-42));
+    /// tree.append_attribute(inner, AttributeValue::ArrF64((0..100).map(f64::from).collect()));
+    /// tree.append_attribute(inner, AttributeValue::ArrI32(vec![1, -2, 3]));
+    /// tree.append_new(inner, "Leaf");
+    ///
+    /// // Raw and zlib-compressed arrays.
+    /// for compression in [None, Some(1)] {
+    ///     let data = Writer::new(FbxVersion::V7_4)
+    ///         .array_compression(compression)
+    ///         .write_tree(&tree, Cursor::new(Vec::new()))?
+    ///         .into_inner();
+    ///     let parsed = match AnyTree::from_seekable_reader(Cursor::new(&data))? {
+    ///         AnyTree::V7400(_, tree, _) => tree,
+    ///         _ => panic!("should be FBX 7.4"),
+    ///     };
+    ///     let borrowed = BorrowedTree::parse(&data)?;
+    ///     assert_same(borrowed.root(), parsed.root());
+    ///
+    ///     let inner = borrowed
+    ///         .root()
+    ///         .first_child_by_name("Outer")
+    ///         .and_then(|node| node.first_child_by_name("Inner"))
+    ///         .expect("should exist");
+    ///     match &inner.attributes()[1] {
+    ///         BorrowedAttribute::ArrF64(arr) => {
+    ///             assert_eq!(arr.is_compressed(), compression.is_some());
+    ///             assert_eq!(arr.as_le_bytes().is_some(), compression.is_none());
+    ///         }
+    ///         attr => panic!("unexpected attribute: {:?}", attr),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "writer"))]
+    /// # fn main() {}
+    /// ```
+    pub fn parse(data: &'a [u8]) -> Result<Self, LoadError> {
+        Self::parse_with_limits(data, &ResourceLimits::default())
+    }
+
+    /// Parses the binary FBX 7.x data (including the header) with the given
+    /// resource limits.
+    ///
+    /// The depth and the number of the nodes, and the declared sizes of the
+    /// arrays, strings, and binaries are checked.
+    /// The footer is not read.
+    ///
+    /// ```
+    /// use fbxcel_dom::v7400::{borrowed::BorrowedTree, ResourceLimits};
+    ///
+    /// // Header, and 1000 nested `A` nodes ending at the same offset.
+    /// let mut data = b"Kaydara FBX Binary  \x00\x1a\x00".to_vec();
+    /// data.extend_from_slice(&7400_u32.to_le_bytes());
+    /// let end = (data.len() + 1000 * 14) as u32;
+    /// for _ in 0..1000 {
+    ///     data.extend_from_slice(&end.to_le_bytes());
+    ///     data.extend_from_slice(&[0; 8]);
+    ///     data.extend_from_slice(b"\x01A");
+    /// }
+    /// data.extend_from_slice(&[0; 13]);
+    ///
+    /// assert!(BorrowedTree::parse(&data).is_err());
+    /// let limits = ResourceLimits::new().max_depth(1000);
+    /// assert!(BorrowedTree::parse_with_limits(&data, &limits).is_ok());
+    /// ```
+    pub fn parse_with_limits(data: &'a [u8], limits: &ResourceLimits) -> Result<Self, LoadError> {
+        let fbx_version = FbxHeader::load(data.get(..HEADER_LEN).unwrap_or(data))
+            .map_err(LoadError::new)?
+            .version();
+        if !Capabilities::new(fbx_version).is_native() {
+            return Err(LoadError::new(format!(
+                "Expected FBX 7.x but got {:?}",
+                fbx_version
+            )));
+        }
+        trace!("Parsing borrowed FBX tree: version={:?}", fbx_version);
+
+        let mut parser = RecordParser {
+            data,
+            large: Capabilities::new(fbx_version).has_64bit_offsets(),
+            limits: *limits,
+            nodes: vec![NodeRecord {
+                name: "",
+                attributes: Vec::new(),
+                children: Vec::new(),
+            }],
+        };
+        parser.parse_nodes(HEADER_LEN, data.len())?;
+
+        Ok(Self {
+            fbx_version,
+            nodes: parser.nodes,
+        })
+    }
+
+    /// Returns the FBX version.
+    pub fn fbx_version(&self) -> FbxVersion {
+        self.fbx_version
+    }
+
+    /// Returns the implicit root node.
+    pub fn root(&self) -> BorrowedNode<'a, '_> {
+        BorrowedNode {
+            tree: self,
+            index: 0,
+        }
+    }
+}
+
+/// Node handle of [`BorrowedTree`].
+#[derive(Debug, Clone, Copy)]
+pub struct BorrowedNode<'a, 't> {
+    /// Tree.
+    tree: &'t BorrowedTree<'a>,
+    /// Node index.
+    index: usize,
+}
+
+impl<'a, 't> BorrowedNode<'a, 't> {
+    /// Returns the node record.
+    fn record(&self) -> &'t NodeRecord<'a> {
+        &self.tree.nodes[self.index]
+    }
+
+    /// Returns the node name.
+    ///
+    /// The implicit root node has an empty name.
+    pub fn name(&self) -> &'a str {
+        self.record().name
+    }
+
+    /// Returns the node attributes.
+    pub fn attributes(&self) -> &'t [BorrowedAttribute<'a>] {
+        &self.record().attributes
+    }
+
+    /// Returns an iterator of the child nodes.
+    pub fn children(&self) -> impl Iterator<Item = BorrowedNode<'a, 't>> + 't {
+        let tree = self.tree;
+        self.record()
+            .children
+            .iter()
+            .map(move |&index| BorrowedNode { tree, index })
+    }
+
+    /// Returns an iterator of the child nodes with the given name.
+    pub fn children_by_name<'n>(
+        &self,
+        name: &'n str,
+    ) -> impl Iterator<Item = BorrowedNode<'a, 't>> + 'n
+    where
+        't: 'n,
+    {
+        self.children().filter(move |child| child.name() == name)
+    }
+
+    /// Returns the first child node with the given name.
+    pub fn first_child_by_name(&self, name: &str) -> Option<BorrowedNode<'a, 't>> {
+        self.children().find(|child| child.name() == name)
+    }
+}
+
+/// Node attribute borrowing the data.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BorrowedAttribute<'a> {
+    /// Single `bool`.
+    Bool(bool),
+    /// Single `i16`.
+    I16(i16),
+    /// Single `i32`.
+    I32(i32),
+    /// Single `i64`.
+    I64(i64),
+    /// Single `f32`.
+    F32(f32),
+    /// Single `f64`.
+    F64(f64),
+    /// Array of `bool`.
+    ArrBool(BorrowedArray<'a, bool>),
+    /// Array of `i32`.
+    ArrI32(BorrowedArray<'a, i32>),
+    /// Array of `i64`.
+    ArrI64(BorrowedArray<'a, i64>),
+    /// Array of `f32`.
+    ArrF32(BorrowedArray<'a, f32>),
+    /// Array of `f64`.
+    ArrF64(BorrowedArray<'a, f64>),
+    /// Binary.
+    Binary(&'a [u8]),
+    /// String.
+    ///
+    /// This is the raw bytes, and may be invalid UTF-8.
+    String(&'a [u8]),
+}
+
+impl<'a> BorrowedAttribute<'a> {
+    /// Returns the string value if the attribute is a valid UTF-8 string.
+    pub fn get_str(&self) -> Option<&'a str> {
+        match *self {
+            BorrowedAttribute::String(v) => str::from_utf8(v).ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the `i64` value if the attribute is an `i64`.
+    pub fn get_i64(&self) -> Option<i64> {
+        match *self {
+            BorrowedAttribute::I64(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the attribute as an owned `fbxcel` attribute value.
+    ///
+    /// Compressed arrays are decoded.
+    /// Returns an error if an array is broken or a string is not valid UTF-8,
+    /// as the `fbxcel` parser does.
+    pub fn to_attribute_value(&self) -> Result<AttributeValue, LoadError> {
+        Ok(match *self {
+            BorrowedAttribute::Bool(v) => AttributeValue::Bool(v),
+            BorrowedAttribute::I16(v) => AttributeValue::I16(v),
+            BorrowedAttribute::I32(v) => AttributeValue::I32(v),
+            BorrowedAttribute::I64(v) => AttributeValue::I64(v),
+            BorrowedAttribute::F32(v) => AttributeValue::F32(v),
+            BorrowedAttribute::F64(v) => AttributeValue::F64(v),
+            BorrowedAttribute::ArrBool(ref arr) => AttributeValue::ArrBool(arr.to_vec()?),
+            BorrowedAttribute::ArrI32(ref arr) => AttributeValue::ArrI32(arr.to_vec()?),
+            BorrowedAttribute::ArrI64(ref arr) => AttributeValue::ArrI64(arr.to_vec()?),
+            BorrowedAttribute::ArrF32(ref arr) => AttributeValue::ArrF32(arr.to_vec()?),
+            BorrowedAttribute::ArrF64(ref arr) => AttributeValue::ArrF64(arr.to_vec()?),
+            BorrowedAttribute::Binary(v) => AttributeValue::Binary(v.to_vec()),
+            BorrowedAttribute::String(v) => AttributeValue::String(
+                str::from_utf8(v)
+                    .map_err(|e| LoadError::new(format!("Invalid UTF-8 string: {}", e)))?
+                    .to_owned(),
+            ),
+        })
+    }
+}
+
+/// Array element type of [`BorrowedArray`].
+pub trait ArrayElement: private::Sealed + Copy {
+    /// Size of an element in bytes.
+    #[doc(hidden)]
+    const SIZE: usize;
+
+    /// Reads an element from the little endian bytes.
+    #[doc(hidden)]
+    fn from_le_bytes(bytes: &[u8]) -> Self;
+}
+
+/// Sealed trait.
+mod private {
+    /// Sealed trait.
+    pub trait Sealed {}
+
+    impl Sealed for bool {}
+    impl Sealed for i32 {}
+    impl Sealed for i64 {}
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+impl ArrayElement for bool {
+    const SIZE: usize = 1;
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        read_bool(bytes[0])
+    }
+}
+
+impl ArrayElement for i32 {
+    const SIZE: usize = 4;
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        read_u32(bytes) as i32
+    }
+}
+
+impl ArrayElement for i64 {
+    const SIZE: usize = 8;
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        read_u64(bytes) as i64
+    }
+}
+
+impl ArrayElement for f32 {
+    const SIZE: usize = 4;
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        f32::from_bits(read_u32(bytes))
+    }
+}
+
+impl ArrayElement for f64 {
+    const SIZE: usize = 8;
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        f64::from_bits(read_u64(bytes))
+    }
+}
+
+/// Array attribute borrowing the data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BorrowedArray<'a, T> {
+    /// Number of elements.
+    len: usize,
+    /// Whether the data is zlib-compressed.
+    compressed: bool,
+    /// Raw (possibly compressed) data.
+    data: &'a [u8],
+    /// Element type.
+    _element: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: ArrayElement> BorrowedArray<'a, T> {
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the array has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if the data is compressed.
+    ///
+    /// Compressed arrays are decoded into a new buffer by
+    /// [`values()`][`Self::values`].
+    pub fn is_compressed(&self) -> bool {
+        self.compressed
+    }
+
+    /// Returns the little endian bytes of the elements if the array is not
+    /// compressed.
+    pub fn as_le_bytes(&self) -> Option<&'a [u8]> {
+        if self.compressed {
+            None
+        } else {
+            Some(self.data)
+        }
+    }
+
+    /// Returns an iterator of the elements.
+    ///
+    /// This borrows the data if the array is not compressed, and decodes the
+    /// data otherwise.
+    pub fn values(&self) -> Result<ArrayValues<'a, T>, LoadError> {
+        let expected = array_bytes::<T>(self.len);
+        let bytes = if self.compressed {
+            let mut decoded =
+                Vec::with_capacity(std::cmp::min(expected, MAX_PREALLOCATION as u64) as usize);
+            // Read one more byte than expected to detect excess data without
+            // inflating all of it.
+            libflate::zlib::Decoder::new(self.data)
+                .and_then(|decoder| decoder.take(expected + 1).read_to_end(&mut decoded))
+                .map_err(|e| LoadError::new(format!("Failed to decode array: {}", e)))?;
+            Cow::Owned(decoded)
+        } else {
+            Cow::Borrowed(self.data)
+        };
+        if bytes.len() as u64 != expected {
+            return Err(LoadError::new(format!(
+                "Array data length mismatch: expected {} elements, got {} bytes",
+                self.len,
+                bytes.len()
+            )));
+        }
+
+        Ok(ArrayValues {
+            bytes,
+            pos: 0,
+            _element: PhantomData,
+        })
+    }
+
+    /// Returns the elements as a vector.
+    pub fn to_vec(&self) -> Result<Vec<T>, LoadError> {
+        Ok(self.values()?.collect())
+    }
+}
+
+/// Iterator of [`BorrowedArray`] elements.
+#[derive(Debug, Clone)]
+pub struct ArrayValues<'a, T> {
+    /// Little endian bytes of the elements.
+    bytes: Cow<'a, [u8]>,
+    /// Position of the next element.
+    pos: usize,
+    /// Element type.
+    _element: PhantomData<fn() -> T>,
+}
+
+impl<T: ArrayElement> Iterator for ArrayValues<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.bytes.get(self.pos..self.pos + T::SIZE)?;
+        self.pos += T::SIZE;
+        Some(T::from_le_bytes(bytes))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.bytes.len() - self.pos) / T::SIZE;
+        (len, Some(len))
+    }
+}
+
+impl<T: ArrayElement> ExactSizeIterator for ArrayValues<'_, T> {}
+
+/// Node records parser.
+struct RecordParser<'a> {
+    /// Whole FBX data.
+    data: &'a [u8],
+    /// Whether the node headers use 64-bit fields.
+    large: bool,
+    /// Resource limits.
+    limits: ResourceLimits,
+    /// Parsed nodes.
+    nodes: Vec<NodeRecord<'a>>,
+}
+
+impl<'a> RecordParser<'a> {
+    /// Returns the bytes in `pos..pos+len`, or an error if they are not
+    /// before `end`.
+    fn bytes(&self, pos: usize, len: usize, end: usize) -> Result<&'a [u8], LoadError> {
+        let data = self.data;
+        pos.checked_add(len)
+            .filter(|&stop| stop <= end)
+            .and_then(|stop| data.get(pos..stop))
+            .ok_or_else(|| LoadError::new(format!("Unexpected end of node data at {}", pos)))
+    }
+
+    /// Reads a node header field, and returns it as an offset or length.
+    fn header_field(&self, bytes: &[u8]) -> Result<usize, LoadError> {
+        let v = if self.large {
+            read_u64(bytes)
+        } else {
+            u64::from(read_u32(bytes))
+        };
+        usize::try_from(v).map_err(LoadError::new)
+    }
+
+    /// Parses node records in `pos..end` as children of the implicit root.
+    ///
+    /// Nodes are parsed with an explicit stack, so deeply nested data does
+    /// not overflow the call stack.
+    fn parse_nodes(&mut self, pos: usize, end: usize) -> Result<(), LoadError> {
+        let field_len = if self.large { 8 } else { 4 };
+        let header_len = field_len * 3 + 1;
+        // Parent node, and the range of its remaining child records.
+        let mut stack = vec![(0, pos, end)];
+        while let Some(&mut (parent, ref mut pos, end)) = stack.last_mut() {
+            if *pos >= end {
+                stack.pop();
+                continue;
+            }
+            let node_pos = *pos;
+            let header = self.bytes(node_pos, header_len, end)?;
+            let node_end = self.header_field(&header[..field_len])?;
+            let num_attrs = self.header_field(&header[field_len..])?;
+            let attrs_len = self.header_field(&header[field_len * 2..])?;
+            let name_len = usize::from(header[field_len * 3]);
+            if node_end == 0 {
+                // Node end marker.
+                stack.pop();
+                continue;
+            }
+
+            let name_start = node_pos + header_len;
+            let name = str::from_utf8(self.bytes(name_start, name_len, end)?)
+                .map_err(|e| LoadError::new(format!("Invalid node name at {}: {}", node_pos, e)))?;
+            let attrs_start = name_start + name_len;
+            let attrs_end = attrs_start.saturating_add(attrs_len);
+            if node_end <= node_pos || node_end > end || attrs_end > node_end {
+                return Err(LoadError::new(format!(
+                    "Invalid node length for {:?} at {}",
+                    name, node_pos
+                )));
+            }
+            *pos = node_end;
+
+            let depth = stack.len();
+            self.limits.check_nodes(self.nodes.len())?;
+            self.limits
+                .check_depth("", depth)
+                .map_err(|e| e.with_node(self.node_path(&stack, name)))?;
+            let attributes = self
+                .parse_attributes(attrs_start, attrs_end, num_attrs)
+                .map_err(|e| match e {
+                    AttributeError::Limit(e) => e.with_node(self.node_path(&stack, name)).into(),
+                    AttributeError::Load(e) => e,
+                })?;
+            let index = self.nodes.len();
+            self.nodes.push(NodeRecord {
+                name,
+                attributes,
+                children: Vec::new(),
+            });
+            self.nodes[parent].children.push(index);
+            if attrs_end < node_end {
+                stack.push((index, attrs_end, node_end));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the path of the node.
+    ///
+    /// `stack` is the parsing stack of the parent node, and `name` is the
+    /// name of the node.
+    fn node_path(&self, stack: &[(usize, usize, usize)], name: &str) -> String {
+        let mut path = stack
+            .iter()
+            .skip(1)
+            .map(|&(parent, _, _)| self.nodes[parent].name)
+            .collect::<Vec<_>>();
+        path.push(name);
+        path.join("/")
+    }
+
+    /// Parses node attributes in `pos..end`.
+    fn parse_attributes(
+        &self,
+        mut pos: usize,
+        end: usize,
+        count: usize,
+    ) -> Result<Vec<BorrowedAttribute<'a>>, AttributeError> {
+        let mut attrs = Vec::with_capacity(std::cmp::min(count, MAX_PREALLOCATION));
+        for _ in 0..count {
+            let type_code = self.bytes(pos, 1, end)?[0];
+            pos += 1;
+            let attr = match type_code {
+                b'C' => BorrowedAttribute::Bool(read_bool(self.bytes(pos, 1, end)?[0])),
+                b'Y' => {
+                    let bytes = self.bytes(pos, 2, end)?;
+                    BorrowedAttribute::I16(i16::from_le_bytes([bytes[0], bytes[1]]))
+                }
+                b'I' => BorrowedAttribute::I32(read_u32(self.bytes(pos, 4, end)?) as i32),
+                b'F' => BorrowedAttribute::F32(f32::from_bits(read_u32(self.bytes(pos, 4, end)?))),
+                b'L' => BorrowedAttribute::I64(read_u64(self.bytes(pos, 8, end)?) as i64),
+                b'D' => BorrowedAttribute::F64(f64::from_bits(read_u64(self.bytes(pos, 8, end)?))),
+                b'b' => BorrowedAttribute::ArrBool(self.parse_array(pos, end)?),
+                b'i' => BorrowedAttribute::ArrI32(self.parse_array(pos, end)?),
+                b'l' => BorrowedAttribute::ArrI64(self.parse_array(pos, end)?),
+                b'f' => BorrowedAttribute::ArrF32(self.parse_array(pos, end)?),
+                b'd' => BorrowedAttribute::ArrF64(self.parse_array(pos, end)?),
+                b'S' => BorrowedAttribute::String(self.parse_special(pos, end)?),
+                b'R' => BorrowedAttribute::Binary(self.parse_special(pos, end)?),
+                _ => {
+                    return Err(LoadError::new(format!(
+                        "Invalid attribute type code {:#04x} at {}",
+                        type_code,
+                        pos - 1
+                    ))
+                    .into())
+                }
+            };
+            pos += attribute_len(&attr);
+            attrs.push(attr);
+        }
+
+        Ok(attrs)
+    }
+
+    /// Parses an array attribute value.
+    fn parse_array<T: ArrayElement>(
+        &self,
+        pos: usize,
+        end: usize,
+    ) -> Result<BorrowedArray<'a, T>, AttributeError> {
+        let header = self.bytes(pos, 12, end)?;
+        let len = read_u32(&header[0..4]) as usize;
+        self.limits.check_array("", array_bytes::<T>(len))?;
+        let compressed = match read_u32(&header[4..8]) {
+            0 => false,
+            1 => true,
+            v => {
+                return Err(
+                    LoadError::new(format!("Invalid array encoding {} at {}", v, pos)).into(),
+                )
+            }
+        };
+        let data = self.bytes(pos + 12, read_u32(&header[8..12]) as usize, end)?;
+        if !compressed && data.len() as u64 != array_bytes::<T>(len) {
+            return Err(LoadError::new(format!(
+                "Array data length mismatch at {}: expected {} elements, got {} bytes",
+                pos,
+                len,
+                data.len()
+            ))
+            .into());
+        }
+
+        Ok(BorrowedArray {
+            len,
+            compressed,
+            data,
+            _element: PhantomData,
+        })
+    }
+
+    /// Parses a string or binary attribute value.
+    fn parse_special(&self, pos: usize, end: usize) -> Result<&'a [u8], AttributeError> {
+        let len = read_u32(self.bytes(pos, 4, end)?) as usize;
+        self.limits.check_array("", len as u64)?;
+        Ok(self.bytes(pos + 4, len, end)?)
+    }
+}
+
+/// Error on parsing node attributes.
+enum AttributeError {
+    /// Resource limit error, without the node path.
+    Limit(LimitError),
+    /// Other load error.
+    Load(LoadError),
+}
+
+impl From<LimitError> for AttributeError {
+    fn from(e: LimitError) -> Self {
+        AttributeError::Limit(e)
+    }
+}
+
+impl From<LoadError> for AttributeError {
+    fn from(e: LoadError) -> Self {
+        AttributeError::Load(e)
+    }
+}
+
+/// Returns the size of the array elements in bytes.
+fn array_bytes<T: ArrayElement>(len: usize) -> u64 {
+    (len as u64).saturating_mul(T::SIZE as u64)
+}
+
+/// Returns the length of the attribute value in bytes, excluding the type
+/// code.
+fn attribute_len(attr: &BorrowedAttribute<'_>) -> usize {
+    match attr {
+        BorrowedAttribute::Bool(_) => 1,
+        BorrowedAttribute::I16(_) => 2,
+        BorrowedAttribute::I32(_) | BorrowedAttribute::F32(_) => 4,
+        BorrowedAttribute::I64(_) | BorrowedAttribute::F64(_) => 8,
+        BorrowedAttribute::ArrBool(v) => 12 + v.data.len(),
+        BorrowedAttribute::ArrI32(v) => 12 + v.data.len(),
+        BorrowedAttribute::ArrI64(v) => 12 + v.data.len(),
+        BorrowedAttribute::ArrF32(v) => 12 + v.data.len(),
+        BorrowedAttribute::ArrF64(v) => 12 + v.data.len(),
+        BorrowedAttribute::Binary(v) | BorrowedAttribute::String(v) => 4 + v.len(),
+    }
+}
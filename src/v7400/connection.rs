@@ -1,32 +1,14 @@
 //! Objects connections.
 
-use string_interner::symbol::SymbolU32;
-
-use crate::v7400::object::ObjectId;
+use crate::v7400::{object::ObjectId, symbol::Symbol};
 
 pub(crate) use self::cache::ConnectionsCache;
 
 mod cache;
 
-/// Symbol for interned connection label.
-// This is an opaque-typedef pattern.
-// `string_interner::Sym` has efficient implementation, so use it internally.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub(crate) struct ConnectionLabelSym(SymbolU32);
-
-impl string_interner::Symbol for ConnectionLabelSym {
-    fn try_from_usize(v: usize) -> Option<Self> {
-        SymbolU32::try_from_usize(v).map(Self)
-    }
-
-    fn to_usize(self) -> usize {
-        self.0.to_usize()
-    }
-}
-
 /// Type of a connected node.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub(crate) enum ConnectedNodeType {
+pub enum ConnectedNodeType {
     /// Object.
     Object,
     /// Property.
@@ -61,7 +43,7 @@ pub(crate) struct Connection {
     /// Destination node type.
     destination_type: ConnectedNodeType,
     /// Label.
-    label: Option<ConnectionLabelSym>,
+    label: Option<Symbol>,
     /// Connection node index.
     index: ConnectionIndex,
 }
@@ -73,7 +55,7 @@ impl Connection {
         source_type: ConnectedNodeType,
         destination_id: ObjectId,
         destination_type: ConnectedNodeType,
-        label: Option<ConnectionLabelSym>,
+        label: Option<Symbol>,
         index: ConnectionIndex,
     ) -> Self {
         Self {
@@ -91,13 +73,23 @@ impl Connection {
         self.source_id
     }
 
+    /// Returns source node type.
+    pub(crate) fn source_type(&self) -> ConnectedNodeType {
+        self.source_type
+    }
+
     /// Returns destination ID.
     pub(crate) fn destination_id(&self) -> ObjectId {
         self.destination_id
     }
 
+    /// Returns destination node type.
+    pub(crate) fn destination_type(&self) -> ConnectedNodeType {
+        self.destination_type
+    }
+
     /// Returns label symbol.
-    pub(crate) fn label_sym(&self) -> Option<ConnectionLabelSym> {
+    pub(crate) fn label_sym(&self) -> Option<Symbol> {
         self.label
     }
 
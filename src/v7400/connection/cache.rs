@@ -7,15 +7,16 @@ use fbxcel::{
     tree::v7400::{NodeHandle, NodeId, Tree},
 };
 use log::trace;
-use string_interner::{DefaultBackend, StringInterner};
 
 use crate::v7400::{
-    connection::{ConnectedNodeType, Connection, ConnectionIndex, ConnectionLabelSym},
+    connection::{ConnectedNodeType, Connection, ConnectionIndex},
+    document::memory,
     error::{
         connection::ConnectionError,
         load::{LoadError, StructureError},
     },
     object::ObjectId,
+    symbol::{Symbol, SymbolTable},
 };
 
 /// Connections cache.
@@ -23,32 +24,29 @@ use crate::v7400::{
 pub(crate) struct ConnectionsCache {
     /// Connections.
     connections: Vec<Connection>,
-    /// Connection label interner.
-    labels: StringInterner<DefaultBackend<ConnectionLabelSym>>,
     /// Connection indices by source object ID.
     conn_indices_by_src: HashMap<ObjectId, Vec<ConnectionIndex>>,
     /// Connection indices by destination object ID.
     conn_indices_by_dest: HashMap<ObjectId, Vec<ConnectionIndex>>,
+    /// Connection indices by source and destination object IDs.
+    conn_indices_by_pair: HashMap<(ObjectId, ObjectId), Vec<ConnectionIndex>>,
 }
 
 impl ConnectionsCache {
-    /// Creates a new `ConnectionsCache` from the given FBX data tree.
-    pub(crate) fn from_tree(tree: &Tree) -> Result<Self, LoadError> {
-        ConnectionsCacheBuilder::default().load(tree)
+    /// Creates a new `ConnectionsCache` from the given FBX data tree,
+    /// interning the labels into the symbol table.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "connections_cache", skip_all)
+    )]
+    pub(crate) fn from_tree(tree: &Tree, symbols: &mut SymbolTable) -> Result<Self, LoadError> {
+        ConnectionsCacheBuilder::new(symbols).load(tree)
     }
 
-    /// Returns corresponding label.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the given symbol is not registered.
-    pub(crate) fn resolve_label(&self, sym: ConnectionLabelSym) -> &str {
-        self.labels.resolve(sym).unwrap_or_else(|| {
-            panic!(
-                "The given connection label symbol is not registered: sym={:?}",
-                sym
-            )
-        })
+    /// Returns an iterator of all connections, in the order of the nodes in
+    /// the tree.
+    pub(crate) fn connections(&self) -> impl Iterator<Item = &Connection> {
+        self.connections.iter()
     }
 
     /// Returns an iterator of outgoing connections.
@@ -74,26 +72,61 @@ impl ConnectionsCache {
             .flatten()
             .map(move |index| &self.connections[index.value()])
     }
+
+    /// Returns an iterator of connections from the source to the destination.
+    pub(crate) fn connections_between(
+        &self,
+        source: ObjectId,
+        destination: ObjectId,
+    ) -> impl Iterator<Item = &Connection> {
+        self.conn_indices_by_pair
+            .get(&(source, destination))
+            .into_iter()
+            .flatten()
+            .map(move |index| &self.connections[index.value()])
+    }
+
+    /// Returns the estimated heap size.
+    pub(crate) fn heap_size(&self) -> usize {
+        memory::vec_heap_size(&self.connections)
+            + memory::multimap_heap_size(&self.conn_indices_by_src)
+            + memory::multimap_heap_size(&self.conn_indices_by_dest)
+            + memory::multimap_heap_size(&self.conn_indices_by_pair)
+    }
 }
 
 /// Connections cache.
 #[derive(Debug)]
-struct ConnectionsCacheBuilder {
+struct ConnectionsCacheBuilder<'a> {
     /// Connections data and node IDs.
     connections: Vec<(NodeId, Connection)>,
-    /// Connection label interner.
-    labels: StringInterner<DefaultBackend<ConnectionLabelSym>>,
+    /// Symbol table for labels.
+    symbols: &'a mut SymbolTable,
     /// Connection indices by source object ID.
     conn_indices_by_src: HashMap<ObjectId, Vec<ConnectionIndex>>,
     /// Connection indices by destination object ID.
     conn_indices_by_dest: HashMap<ObjectId, Vec<ConnectionIndex>>,
+    /// Connection indices by source and destination object IDs.
+    conn_indices_by_pair: HashMap<(ObjectId, ObjectId), Vec<ConnectionIndex>>,
     /// Connections set to check duplicates.
     ///
     /// Contains `(source, destination, label)`s.
-    conn_set: HashSet<(ObjectId, ObjectId, Option<ConnectionLabelSym>)>,
+    conn_set: HashSet<(ObjectId, ObjectId, Option<Symbol>)>,
 }
 
-impl ConnectionsCacheBuilder {
+impl<'a> ConnectionsCacheBuilder<'a> {
+    /// Creates a new builder interning labels into the given symbol table.
+    fn new(symbols: &'a mut SymbolTable) -> Self {
+        Self {
+            connections: Default::default(),
+            symbols,
+            conn_indices_by_src: Default::default(),
+            conn_indices_by_dest: Default::default(),
+            conn_indices_by_pair: Default::default(),
+            conn_set: Default::default(),
+        }
+    }
+
     /// Loads the connectinos from the tree.
     fn load(mut self, tree: &Tree) -> Result<ConnectionsCache, LoadError> {
         let connections_node = tree
@@ -118,26 +151,18 @@ impl ConnectionsCacheBuilder {
             .insert((conn.source_id(), conn.destination_id(), conn.label_sym()))
         {
             let old_conn = self
-                .conn_indices_by_src
-                .get(&conn.source_id())
+                .conn_indices_by_pair
+                .get(&(conn.source_id(), conn.destination_id()))
                 .expect("Should never fail: entry should exist")
                 .iter()
                 .map(|index| &self.connections[index.value()])
-                .find(|(_, old_conn)| {
-                    old_conn.destination_id() == conn.destination_id()
-                        && old_conn.label_sym() == conn.label_sym()
-                })
+                .find(|(_, old_conn)| old_conn.label_sym() == conn.label_sym())
                 .expect("Should never fail: entry should exist");
             return Err(ConnectionError::DuplicateConnection(
                 conn.source_id(),
                 conn.destination_id(),
                 conn.label_sym()
-                    .map(|sym| {
-                        self.labels.resolve(sym).expect(
-                            "Should never fail: connection label symbol should be registered",
-                        )
-                    })
-                    .map(ToOwned::to_owned),
+                    .map(|sym| self.symbols.resolve_registered(sym).to_owned()),
                 old_conn.0,
                 old_conn.1.index(),
                 node.node_id(),
@@ -147,11 +172,15 @@ impl ConnectionsCacheBuilder {
         self.connections.push((node.node_id(), conn));
         self.conn_indices_by_src
             .entry(conn.source_id())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(index);
         self.conn_indices_by_dest
             .entry(conn.destination_id())
-            .or_insert_with(Vec::new)
+            .or_default()
+            .push(index);
+        self.conn_indices_by_pair
+            .entry((conn.source_id(), conn.destination_id()))
+            .or_default()
             .push(index);
 
         assert_eq!(
@@ -184,7 +213,7 @@ impl ConnectionsCacheBuilder {
             .ok_or_else(|| ConnectionError::MissingNodeTypes(node.node_id(), index))?
             .get_string_or_type()
             .map_err(|ty| ConnectionError::InvalidNodeTypesType(node.node_id(), index, ty))?;
-        let (destination_type, source_type) = match nodes_ty_str {
+        let (source_type, destination_type) = match nodes_ty_str {
             "OO" => (ConnectedNodeType::Object, ConnectedNodeType::Object),
             "OP" => (ConnectedNodeType::Object, ConnectedNodeType::Property),
             "PO" => (ConnectedNodeType::Property, ConnectedNodeType::Object),
@@ -214,7 +243,7 @@ impl ConnectionsCacheBuilder {
             .map(AttributeValue::get_string_or_type)
             .transpose()
             .map_err(|ty| ConnectionError::InvalidLabelType(node.node_id(), index, ty))?;
-        let label_sym = label.map(|s| self.labels.get_or_intern(s));
+        let label_sym = label.map(|s| self.symbols.intern(s));
         trace!(
             "Successfully loaded connection: node_id={:?}, index={:?}, \
              dst_type={:?}, src_type={:?}, src_id={:?}, dest_id={:?}, label={:?}",
@@ -244,21 +273,9 @@ impl ConnectionsCacheBuilder {
                 .into_iter()
                 .map(|(_node_id, conn)| conn)
                 .collect(),
-            labels: self.labels,
             conn_indices_by_src: self.conn_indices_by_src,
             conn_indices_by_dest: self.conn_indices_by_dest,
-        }
-    }
-}
-
-impl Default for ConnectionsCacheBuilder {
-    fn default() -> Self {
-        Self {
-            connections: Default::default(),
-            labels: StringInterner::new(),
-            conn_indices_by_src: Default::default(),
-            conn_indices_by_dest: Default::default(),
-            conn_set: Default::default(),
+            conn_indices_by_pair: self.conn_indices_by_pair,
         }
     }
 }
@@ -1,5 +1,13 @@
 //! Data types.
 
+pub mod cache;
+pub mod camera;
+pub mod datetime;
+pub mod light;
 pub mod material;
 pub mod mesh;
+pub mod model;
+pub mod morph;
+pub mod scalar;
 pub mod texture;
+pub mod transform;
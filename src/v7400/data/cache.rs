@@ -0,0 +1,55 @@
+//! Geometry cache data.
+
+use std::convert::TryFrom;
+
+use anyhow::{bail, Error};
+
+use crate::v7400::object::property::{loaders::PrimitiveLoader, LoadProperty, PropertyHandle};
+
+/// Cache file format.
+///
+/// See
+/// <http://help.autodesk.com/cloudhelp/2019/ENU/FBX-Developer-Help/cpp_ref/class_fbx_cache.html>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CacheFileType {
+    /// Unknown format.
+    Unknown,
+    /// 3ds Max point cache (PC2).
+    MaxPointCacheV2,
+    /// Maya cache (MC and MCX, described by an XML file).
+    MayaCache,
+    /// Alembic.
+    Alembic,
+}
+
+impl TryFrom<i32> for CacheFileType {
+    type Error = Error;
+
+    fn try_from(v: i32) -> Result<Self, Self::Error> {
+        match v {
+            0 => Ok(CacheFileType::Unknown),
+            1 => Ok(CacheFileType::MaxPointCacheV2),
+            2 => Ok(CacheFileType::MayaCache),
+            3 => Ok(CacheFileType::Alembic),
+            v => bail!("Unexpected `CacheFileType` value: {:?}", v),
+        }
+    }
+}
+
+/// `CacheFileType` property loader.
+#[derive(Default, Debug, Clone, Copy)]
+pub(crate) struct CacheFileTypeLoader;
+
+impl<'a> LoadProperty<'a> for CacheFileTypeLoader {
+    type Value = CacheFileType;
+    type Error = Error;
+
+    fn expecting(&self) -> String {
+        "`i32` value as cache file type".into()
+    }
+
+    fn load(self, node: &PropertyHandle<'a>) -> Result<Self::Value, Self::Error> {
+        node.load_value(PrimitiveLoader::<i32>::new())
+            .and_then(TryFrom::try_from)
+    }
+}
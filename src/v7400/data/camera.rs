@@ -0,0 +1,10 @@
+//! Camera data.
+
+pub use self::normalize::{
+    distance_in_meters, film_aspect_ratio, film_size_mm, vertical_fov, MM_PER_INCH,
+};
+pub use self::primitive::StereoMode;
+pub(crate) use self::primitive::StereoModeLoader;
+
+mod normalize;
+mod primitive;
@@ -0,0 +1,57 @@
+//! Camera unit normalization.
+//!
+//! FBX stores the film (sensor) size in inches, the focal length in
+//! millimeters, and the clip planes in scene units.
+//! The helpers here map them to values which renderers usually expect:
+//! millimeters for the sensor, radians for the field of view, and meters for
+//! the clip planes.
+
+/// Millimeters per inch.
+pub const MM_PER_INCH: f64 = 25.4;
+
+/// Returns the film size (`FilmWidth` and `FilmHeight`, in inches) in
+/// millimeters.
+pub fn film_size_mm(film_width: f64, film_height: f64) -> [f64; 2] {
+    [film_width * MM_PER_INCH, film_height * MM_PER_INCH]
+}
+
+/// Returns the vertical field of view in radians.
+///
+/// `focal_length` is the focal length in millimeters (`FocalLength`), and
+/// `film_height` is the film height in inches (`FilmHeight`).
+///
+/// Returns `None` if the focal length is not positive.
+///
+/// ```
+/// use fbxcel_dom::v7400::data::camera::vertical_fov;
+///
+/// // 35mm full frame (24mm high) with 24mm lens: 2 * atan(0.5).
+/// let fov = vertical_fov(24.0, 24.0 / 25.4).unwrap();
+/// assert!((fov - 2.0 * 0.5_f64.atan()).abs() < 1e-12);
+/// assert_eq!(vertical_fov(0.0, 0.612), None);
+/// ```
+pub fn vertical_fov(focal_length: f64, film_height: f64) -> Option<f64> {
+    if focal_length <= 0.0 {
+        return None;
+    }
+    Some(2.0 * (film_height * MM_PER_INCH / (2.0 * focal_length)).atan())
+}
+
+/// Returns the aspect ratio (width / height) of the film.
+///
+/// Returns `None` if the film height is not positive.
+pub fn film_aspect_ratio(film_width: f64, film_height: f64) -> Option<f64> {
+    if film_height <= 0.0 {
+        return None;
+    }
+    Some(film_width / film_height)
+}
+
+/// Returns the distance in scene units converted to meters.
+///
+/// `unit_scale_factor` is the `UnitScaleFactor` of `GlobalSettings`, i.e.
+/// centimeters per scene unit.
+/// This is intended for `NearPlane` and `FarPlane`.
+pub fn distance_in_meters(distance: f64, unit_scale_factor: f64) -> f64 {
+    distance * unit_scale_factor / 100.0
+}
@@ -0,0 +1,67 @@
+//! Primitive types.
+
+use std::convert::TryFrom;
+
+use anyhow::{bail, Error};
+
+use crate::v7400::{
+    object::property::{loaders::PrimitiveLoader, LoadProperty, PropertyHandle},
+    Error as DomError,
+};
+
+/// Stereo camera rig mode.
+///
+/// See
+/// <http://help.autodesk.com/cloudhelp/2019/ENU/FBX-Developer-Help/cpp_ref/class_fbx_camera_stereo.html>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StereoMode {
+    /// Disabled.
+    None,
+    /// Converged (toe-in) cameras.
+    Converged,
+    /// Off-axis cameras.
+    OffAxis,
+    /// Parallel cameras.
+    Parallel,
+}
+
+impl TryFrom<i32> for StereoMode {
+    type Error = Error;
+
+    fn try_from(v: i32) -> Result<Self, Self::Error> {
+        match v {
+            0 => Ok(StereoMode::None),
+            1 => Ok(StereoMode::Converged),
+            2 => Ok(StereoMode::OffAxis),
+            3 => Ok(StereoMode::Parallel),
+            v => bail!("Unexpected `StereoMode` value: {:?}", v),
+        }
+    }
+}
+
+/// `StereoMode` property loader.
+#[derive(Default, Debug, Clone, Copy)]
+pub(crate) struct StereoModeLoader;
+
+impl<'a> LoadProperty<'a> for StereoModeLoader {
+    type Value = StereoMode;
+    type Error = Error;
+
+    fn expecting(&self) -> String {
+        "`i32` value as stereo mode".into()
+    }
+
+    fn load(self, node: &PropertyHandle<'a>) -> Result<Self::Value, Self::Error> {
+        let data_type = node.data_type()?;
+        if data_type != "enum" {
+            return Err(DomError::type_mismatch(
+                node.error_location(),
+                "`enum` data type",
+                data_type,
+            )
+            .into());
+        }
+        node.load_value(PrimitiveLoader::<i32>::new())
+            .and_then(TryFrom::try_from)
+    }
+}
@@ -0,0 +1,286 @@
+//! Date and time data.
+
+use std::{convert::TryFrom, fmt, str::FromStr};
+
+use anyhow::{bail, format_err, Error};
+use fbxcel::tree::v7400::NodeHandle;
+
+use crate::v7400::{
+    object::property::{loaders::BorrowedStringLoader, LoadProperty, PropertyHandle},
+    Error as DomError, ErrorLocation,
+};
+
+/// Plain date and time, without time zone.
+///
+/// This is used by `DateTime` typed properties (such as `DateTime_GMT`) and
+/// the `CreationTimeStamp` node of the FBX header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    /// Year.
+    pub year: i32,
+    /// Month (1-12).
+    pub month: u8,
+    /// Day of month (1-31).
+    pub day: u8,
+    /// Hour (0-23).
+    pub hour: u8,
+    /// Minute (0-59).
+    pub minute: u8,
+    /// Second (0-59, 60 for leap second).
+    pub second: u8,
+    /// Millisecond (0-999).
+    pub millisecond: u16,
+}
+
+impl DateTime {
+    /// Creates a new `DateTime` after checking the field ranges.
+    pub fn new(
+        year: i32,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+        millisecond: u16,
+    ) -> Result<Self, Error> {
+        if !(1..=12).contains(&month)
+            || !(1..=31).contains(&day)
+            || hour > 23
+            || minute > 59
+            || second > 60
+            || millisecond > 999
+        {
+            bail!(
+                "Date time field out of range: \
+                 {:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+                year,
+                month,
+                day,
+                hour,
+                minute,
+                second,
+                millisecond
+            );
+        }
+
+        Ok(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            millisecond,
+        })
+    }
+
+    /// Reads the date time from a node with `Year`, `Month`, `Day`, `Hour`,
+    /// `Minute`, `Second` and `Millisecond` children (such as
+    /// `CreationTimeStamp`).
+    pub(crate) fn from_children(node: NodeHandle<'_>) -> Result<Self, Error> {
+        let get = |name: &str| -> Result<i32, Error> {
+            let child = node
+                .first_child_by_name(name)
+                .ok_or_else(|| DomError::missing_node(&node, name))?;
+            child
+                .attributes()
+                .first()
+                .ok_or_else(|| DomError::missing_attribute(&child, 0))?
+                .get_i32_or_type()
+                .map_err(|ty| {
+                    DomError::type_mismatch(ErrorLocation::node(&child), "`i32`", ty).into()
+                })
+        };
+        let narrow = |name: &str| -> Result<u8, Error> {
+            let v = get(name)?;
+            u8::try_from(v).map_err(|_| format_err!("`{}` out of range: {}", name, v))
+        };
+        let millisecond = {
+            let v = get("Millisecond")?;
+            u16::try_from(v).map_err(|_| format_err!("`Millisecond` out of range: {}", v))?
+        };
+
+        Self::new(
+            get("Year")?,
+            narrow("Month")?,
+            narrow("Day")?,
+            narrow("Hour")?,
+            narrow("Minute")?,
+            narrow("Second")?,
+            millisecond,
+        )
+    }
+}
+
+impl fmt::Display for DateTime {
+    /// Formats in the same way as FBX `DateTime` properties
+    /// (`DD/MM/YYYY hh:mm:ss.mmm`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}/{:02}/{:04} {:02}:{:02}:{:02}.{:03}",
+            self.day, self.month, self.year, self.hour, self.minute, self.second, self.millisecond
+        )
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = Error;
+
+    /// Parses `DD/MM/YYYY hh:mm:ss.mmm` string.
+    ///
+    /// Time part and milliseconds are optional.
+    ///
+    /// ```
+    /// use fbxcel_dom::v7400::data::datetime::DateTime;
+    ///
+    /// let parsed: DateTime = "25/12/2020 13:45:30.123".parse()?;
+    /// assert_eq!(parsed, DateTime::new(2020, 12, 25, 13, 45, 30, 123)?);
+    /// assert_eq!(parsed.to_string(), "25/12/2020 13:45:30.123");
+    /// assert_eq!(
+    ///     " 1/2/2003  4:05:06 ".parse::<DateTime>()?,
+    ///     DateTime::new(2003, 2, 1, 4, 5, 6, 0)?
+    /// );
+    /// assert_eq!("01/02/2003".parse::<DateTime>()?, DateTime::new(2003, 2, 1, 0, 0, 0, 0)?);
+    /// // Leap second.
+    /// assert_eq!("31/12/2016 23:59:60.000".parse::<DateTime>()?.second, 60);
+    ///
+    /// // Out-of-range fields.
+    /// for s in &[
+    ///     "01/13/2020",
+    ///     "00/01/2020",
+    ///     "32/01/2020",
+    ///     "01/300/2020",
+    ///     "01/01/2020 24:00:00",
+    ///     "01/01/2020 00:60:00",
+    ///     "01/01/2020 00:00:61",
+    ///     "01/01/2020 00:00:00.1000",
+    ///     "01/01/2020 99999:00:00",
+    /// ] {
+    ///     assert!(s.parse::<DateTime>().is_err(), "{:?} should be rejected", s);
+    /// }
+    ///
+    /// // Malformed strings.
+    /// for s in &[
+    ///     "",
+    ///     "2020-01-01",
+    ///     "01/01",
+    ///     "01/01/2020/01",
+    ///     "aa/01/2020",
+    ///     "-1/01/2020",
+    ///     "01/01/2020 12:00",
+    ///     "01/01/2020 12:00:00:00",
+    ///     "01/01/2020 12:00:00.",
+    ///     "01/01/2020T12:00:00",
+    /// ] {
+    ///     assert!(s.parse::<DateTime>().is_err(), "{:?} should be rejected", s);
+    /// }
+    /// # Ok::<_, anyhow::Error>(())
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || format_err!("Invalid date time string: {:?}", s);
+        let parse = |v: &str| v.trim().parse::<u16>().map_err(|_| err());
+
+        let s = s.trim();
+        let (date, time) = match s.find(' ') {
+            Some(pos) => (&s[..pos], s[pos..].trim()),
+            None => (s, ""),
+        };
+
+        let mut date = date.split('/');
+        let day = parse(date.next().ok_or_else(err)?)?;
+        let month = parse(date.next().ok_or_else(err)?)?;
+        let year = date
+            .next()
+            .ok_or_else(err)?
+            .trim()
+            .parse::<i32>()
+            .map_err(|_| err())?;
+        if date.next().is_some() {
+            return Err(err());
+        }
+
+        let (hour, minute, second, millisecond) = if time.is_empty() {
+            (0, 0, 0, 0)
+        } else {
+            let (hms, ms) = match time.find('.') {
+                Some(pos) => (&time[..pos], parse(&time[(pos + 1)..])?),
+                None => (time, 0),
+            };
+            let mut hms = hms.split(':');
+            let hour = parse(hms.next().ok_or_else(err)?)?;
+            let minute = parse(hms.next().ok_or_else(err)?)?;
+            let second = parse(hms.next().ok_or_else(err)?)?;
+            if hms.next().is_some() {
+                return Err(err());
+            }
+            (hour, minute, second, ms)
+        };
+
+        let narrow = |v: u16| u8::try_from(v).map_err(|_| err());
+        Self::new(
+            year,
+            narrow(month)?,
+            narrow(day)?,
+            narrow(hour)?,
+            narrow(minute)?,
+            narrow(second)?,
+            millisecond,
+        )
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<DateTime> for chrono::NaiveDateTime {
+    type Error = Error;
+
+    fn try_from(v: DateTime) -> Result<Self, Self::Error> {
+        chrono::NaiveDate::from_ymd_opt(v.year, u32::from(v.month), u32::from(v.day))
+            .and_then(|date| {
+                date.and_hms_milli_opt(
+                    u32::from(v.hour),
+                    u32::from(v.minute),
+                    u32::from(v.second),
+                    u32::from(v.millisecond),
+                )
+            })
+            .ok_or_else(|| format_err!("Invalid date time: {}", v))
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<DateTime> for time::PrimitiveDateTime {
+    type Error = Error;
+
+    fn try_from(v: DateTime) -> Result<Self, Self::Error> {
+        let month = time::Month::try_from(v.month)?;
+        let date = time::Date::from_calendar_date(v.year, month, v.day)?;
+        let time = time::Time::from_hms_milli(v.hour, v.minute, v.second, v.millisecond)?;
+        Ok(time::PrimitiveDateTime::new(date, time))
+    }
+}
+
+/// `DateTime` property loader.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTimeLoader;
+
+impl DateTimeLoader {
+    /// Creates a new loader.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<'a> LoadProperty<'a> for DateTimeLoader {
+    type Value = DateTime;
+    type Error = Error;
+
+    fn expecting(&self) -> String {
+        "string value as date time".into()
+    }
+
+    fn load(self, node: &PropertyHandle<'a>) -> Result<Self::Value, Self::Error> {
+        node.load_value(BorrowedStringLoader::new())
+            .and_then(str::parse)
+    }
+}
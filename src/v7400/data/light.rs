@@ -0,0 +1,10 @@
+//! Light data.
+
+pub(crate) use self::primitive::{DecayTypeLoader, LightTypeLoader};
+pub use self::{
+    intensity::{normalize_intensity, FBX_INTENSITY_SCALE},
+    primitive::{DecayType, LightType},
+};
+
+mod intensity;
+mod primitive;
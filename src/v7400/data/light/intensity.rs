@@ -0,0 +1,56 @@
+//! Light intensity normalization.
+//!
+//! FBX stores light intensity as DCC-specific numbers (`100.0` means "full
+//! intensity" in most exporters), and attenuation as a decay type without any
+//! explicit range.
+//! The helpers here map them to values which physically based renderers can
+//! use as a starting point.
+//!
+//! These are heuristics, not exact conversions: FBX has no physical units for
+//! lights, so the result should be treated as a sensible default which users
+//! may tweak.
+
+use crate::v7400::data::light::DecayType;
+
+/// Intensity value which FBX exporters use as "full intensity".
+pub const FBX_INTENSITY_SCALE: f64 = 100.0;
+
+/// Returns the intensity normalized so that `1.0` is the exporter's "full
+/// intensity".
+///
+/// For point and spot lights, the result can be used as candela-like luminous
+/// intensity; for directional lights, as lux-like illuminance.
+pub fn normalize_intensity(intensity: f64) -> f64 {
+    intensity / FBX_INTENSITY_SCALE
+}
+
+impl DecayType {
+    /// Returns the estimated distance where the light contribution falls
+    /// below `cutoff`.
+    ///
+    /// `intensity` should be the normalized intensity (see
+    /// [`normalize_intensity`]), and `decay_start` the distance where the
+    /// decay starts.
+    ///
+    /// The estimate solves `intensity / d^n = cutoff` where `n` is the decay
+    /// exponent (1 for linear, 2 for quadratic, 3 for cubic), and adds
+    /// `decay_start` to the result.
+    ///
+    /// Returns `None` if the light does not decay (i.e. has infinite range),
+    /// or if `cutoff` is not positive.
+    ///
+    /// [`normalize_intensity`]: fn.normalize_intensity.html
+    pub fn attenuation_radius(self, intensity: f64, decay_start: f64, cutoff: f64) -> Option<f64> {
+        if cutoff <= 0.0 {
+            return None;
+        }
+        let ratio = intensity.abs() / cutoff;
+        let radius = match self {
+            DecayType::None => return None,
+            DecayType::Linear => ratio,
+            DecayType::Quadratic => ratio.sqrt(),
+            DecayType::Cubic => ratio.cbrt(),
+        };
+        Some(radius + decay_start.max(0.0))
+    }
+}
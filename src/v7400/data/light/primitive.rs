@@ -0,0 +1,124 @@
+//! Primitive types.
+
+use std::convert::TryFrom;
+
+use anyhow::{bail, Error};
+
+use crate::v7400::{
+    object::property::{loaders::PrimitiveLoader, LoadProperty, PropertyHandle},
+    Error as DomError,
+};
+
+/// Light type.
+///
+/// See
+/// <http://help.autodesk.com/cloudhelp/2019/ENU/FBX-Developer-Help/cpp_ref/class_fbx_light.html>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LightType {
+    /// Point light.
+    Point,
+    /// Directional light.
+    Directional,
+    /// Spot light.
+    Spot,
+    /// Area light.
+    Area,
+    /// Volume light.
+    Volume,
+}
+
+impl TryFrom<i32> for LightType {
+    type Error = Error;
+
+    fn try_from(v: i32) -> Result<Self, Self::Error> {
+        match v {
+            0 => Ok(LightType::Point),
+            1 => Ok(LightType::Directional),
+            2 => Ok(LightType::Spot),
+            3 => Ok(LightType::Area),
+            4 => Ok(LightType::Volume),
+            v => bail!("Unexpected `LightType` value: {:?}", v),
+        }
+    }
+}
+
+/// `LightType` property loader.
+#[derive(Default, Debug, Clone, Copy)]
+pub(crate) struct LightTypeLoader;
+
+impl<'a> LoadProperty<'a> for LightTypeLoader {
+    type Value = LightType;
+    type Error = Error;
+
+    fn expecting(&self) -> String {
+        "`i32` value as light type".into()
+    }
+
+    fn load(self, node: &PropertyHandle<'a>) -> Result<Self::Value, Self::Error> {
+        let data_type = node.data_type()?;
+        if data_type != "enum" {
+            return Err(DomError::type_mismatch(
+                node.error_location(),
+                "`enum` data type",
+                data_type,
+            )
+            .into());
+        }
+        node.load_value(PrimitiveLoader::<i32>::new())
+            .and_then(TryFrom::try_from)
+    }
+}
+
+/// Light decay type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DecayType {
+    /// No decay.
+    None,
+    /// Linear decay.
+    Linear,
+    /// Quadratic decay.
+    Quadratic,
+    /// Cubic decay.
+    Cubic,
+}
+
+impl TryFrom<i32> for DecayType {
+    type Error = Error;
+
+    fn try_from(v: i32) -> Result<Self, Self::Error> {
+        match v {
+            0 => Ok(DecayType::None),
+            1 => Ok(DecayType::Linear),
+            2 => Ok(DecayType::Quadratic),
+            3 => Ok(DecayType::Cubic),
+            v => bail!("Unexpected `DecayType` value: {:?}", v),
+        }
+    }
+}
+
+/// `DecayType` property loader.
+#[derive(Default, Debug, Clone, Copy)]
+pub(crate) struct DecayTypeLoader;
+
+impl<'a> LoadProperty<'a> for DecayTypeLoader {
+    type Value = DecayType;
+    type Error = Error;
+
+    fn expecting(&self) -> String {
+        "`i32` value as decay type".into()
+    }
+
+    fn load(self, node: &PropertyHandle<'a>) -> Result<Self::Value, Self::Error> {
+        let data_type = node.data_type()?;
+        if data_type != "enum" {
+            return Err(DomError::type_mismatch(
+                node.error_location(),
+                "`enum` data type",
+                data_type,
+            )
+            .into());
+        }
+        node.load_value(PrimitiveLoader::<i32>::new())
+            .and_then(TryFrom::try_from)
+    }
+}
@@ -6,6 +6,9 @@ use anyhow::{bail, Error};
 
 use crate::v7400::object::property::{loaders::BorrowedStringLoader, LoadProperty, PropertyHandle};
 
+#[cfg(feature = "pbr")]
+pub mod pbr;
+
 /// Shading model.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ShadingModel {
@@ -0,0 +1,96 @@
+//! Metallic-roughness material heuristics.
+//!
+//! FBX materials are Lambert / Phong materials, while glTF and USD expect
+//! physically based metallic-roughness materials.
+//! [`PbrMaterial`] guesses the latter from the former.
+
+use crate::v7400::{object::material::MaterialHandle, Warning};
+
+/// Metallic-roughness material parameters guessed from a Lambert / Phong
+/// material.
+///
+/// The parameters are converted as below:
+///
+/// * base color is diffuse color multiplied by diffuse factor, and alpha is
+///   `1 - transparency factor`,
+/// * metallic factor is zero, and roughness is guessed from shininess (see
+///   [`roughness_from_shininess()`]),
+/// * emissive color is emissive color multiplied by emissive factor.
+///
+/// Colors are clamped to `[0, 1]`. Non-finite property values are kept as
+/// is, so that users can reject them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PbrMaterial {
+    /// Base color (RGBA).
+    pub base_color: [f64; 4],
+    /// Metallic factor.
+    pub metallic: f64,
+    /// Roughness factor.
+    pub roughness: f64,
+    /// Emissive color (RGB).
+    pub emissive: [f64; 3],
+}
+
+impl PbrMaterial {
+    /// Guesses the metallic-roughness parameters of the given material.
+    ///
+    /// Properties which cannot be read are defaulted, and a
+    /// [`Warning::DefaultedValue`] is recorded for the diffuse color.
+    pub fn from_material(material: &MaterialHandle<'_>) -> Self {
+        let props = material.properties();
+
+        let diffuse = props.diffuse_color_or_default().unwrap_or_else(|e| {
+            material.document().warn(Warning::DefaultedValue {
+                object: material.object_id(),
+                name: "DiffuseColor".to_owned(),
+                cause: Some(format!("{:#}", e)),
+            });
+            rgb::RGB::from([0.8; 3])
+        });
+        let diffuse_factor = props.diffuse_factor_or_default().unwrap_or(1.0);
+        let alpha = 1.0 - props.transparency_factor_or_default().unwrap_or(0.0);
+        let shininess = props.shininess_or_default().unwrap_or(20.0);
+        let emissive = props
+            .emissive_color_or_default()
+            .unwrap_or_else(|_| rgb::RGB::from([0.0; 3]));
+        let emissive_factor = props.emissive_factor_or_default().unwrap_or(1.0);
+
+        Self {
+            base_color: [
+                clamp01(diffuse.r * diffuse_factor),
+                clamp01(diffuse.g * diffuse_factor),
+                clamp01(diffuse.b * diffuse_factor),
+                clamp01(alpha),
+            ],
+            metallic: 0.0,
+            roughness: roughness_from_shininess(shininess),
+            emissive: [
+                clamp01(emissive.r * emissive_factor),
+                clamp01(emissive.g * emissive_factor),
+                clamp01(emissive.b * emissive_factor),
+            ],
+        }
+    }
+}
+
+/// Converts the Blinn-Phong shininess exponent into roughness.
+///
+/// This is the inverse of the Phong-Beckmann approximation
+/// `shininess = 2 / roughness^2 - 2`. Negative shininess is treated as zero.
+///
+/// ```
+/// use fbxcel_dom::v7400::data::material::pbr::roughness_from_shininess;
+///
+/// assert_eq!(roughness_from_shininess(0.0), 1.0);
+/// assert_eq!(roughness_from_shininess(-1.0), 1.0);
+/// assert!((roughness_from_shininess(6.0) - 0.5).abs() < 1e-12);
+/// ```
+pub fn roughness_from_shininess(shininess: f64) -> f64 {
+    (2.0 / (shininess.max(0.0) + 2.0)).sqrt()
+}
+
+/// Clamps the value to `[0, 1]`.
+fn clamp01(v: f64) -> f64 {
+    v.clamp(0.0, 1.0)
+}
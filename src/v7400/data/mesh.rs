@@ -5,6 +5,7 @@ pub use self::{
     polygon_vertex_index::{
         IntoCpiWithPolyVerts, PolygonIndex, PolygonVertex, PolygonVertexIndex, PolygonVertices,
     },
+    resolved::{LayerElementCache, ResolvedLayerElements},
     triangle_vertex_index::{
         IntoCpiWithTriVerts, IntoPvWithTriVerts, TriangleIndex, TriangleVertexIndex,
         TriangleVertices,
@@ -15,4 +16,5 @@ pub(crate) use self::{control_point::ControlPoints, polygon_vertex_index::RawPol
 mod control_point;
 pub mod layer;
 mod polygon_vertex_index;
+mod resolved;
 mod triangle_vertex_index;
@@ -39,11 +39,15 @@ impl<'a> ControlPoints<'a> {
 
     /// Returns a control point at the given index.
     pub(crate) fn get(&self, index: ControlPointIndex) -> Option<Point3<f64>> {
-        let i3 = index.to_u32() as usize * 3;
-        if self.data.len() < i3 + 2 {
-            return None;
-        }
-        Some(Point3::from_slice(&self.data[i3..]))
+        let i3 = (index.to_u32() as usize).checked_mul(3)?;
+        self.data
+            .get(i3..i3.checked_add(3)?)
+            .map(Point3::from_slice)
+    }
+
+    /// Returns the number of control points.
+    pub(crate) fn len(&self) -> usize {
+        self.data.len() / 3
     }
 
     /// Returns an iterator through the control points
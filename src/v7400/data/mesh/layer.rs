@@ -2,10 +2,12 @@
 
 use std::convert::{TryFrom, TryInto};
 
-use anyhow::{bail, format_err, Error};
+use anyhow::{format_err, Error};
 
 use fbxcel::{low::v7400::AttributeValue, tree::v7400::NodeHandle};
 
+use crate::v7400::{Document, Error as DomError, ErrorLocation};
+
 pub(crate) use self::common::LayerContentIndex;
 pub use self::{
     color::LayerElementColorHandle,
@@ -26,12 +28,14 @@ pub mod uv;
 pub struct LayerHandle<'a> {
     /// `Layer` node under `Geometry`.
     node: NodeHandle<'a>,
+    /// Document.
+    doc: &'a Document,
 }
 
 impl<'a> LayerHandle<'a> {
     /// Creates a new `LayerHandle`.
-    pub(crate) fn new(node: NodeHandle<'a>) -> Self {
-        Self { node }
+    pub(crate) fn new(doc: &'a Document, node: NodeHandle<'a>) -> Self {
+        Self { node, doc }
     }
 
     /// Get layer index.
@@ -40,14 +44,18 @@ impl<'a> LayerHandle<'a> {
             .node
             .attributes()
             .get(0)
-            .ok_or_else(|| format_err!("Attributes not found for `Layer` element"))?
+            .ok_or_else(|| DomError::missing_attribute(&self.node, 0))?
             .get_i32_or_type()
-            .map_err(|ty| format_err!("Expected `i32` as layer index, but got {:?}", ty))?;
+            .map_err(|ty| DomError::type_mismatch(ErrorLocation::node(&self.node), "`i32`", ty))?;
         if raw < 0 {
-            bail!(
-                "Expected non-negative integer as layer index, but got {:?}",
-                raw
-            );
+            return Err(DomError::invalid_value(
+                ErrorLocation::node(&self.node),
+                format_args!(
+                    "Expected non-negative integer as layer index, but got {:?}",
+                    raw
+                ),
+            )
+            .into());
         }
 
         Ok(LayerIndex::new(raw as u32))
@@ -55,8 +63,9 @@ impl<'a> LayerHandle<'a> {
 
     /// Returns an iterator of layer element entries.
     pub fn layer_element_entries(&self) -> impl Iterator<Item = LayerElementEntryHandle<'a>> {
+        let doc = self.doc;
         self.children_by_name("LayerElement")
-            .map(LayerElementEntryHandle::new)
+            .map(move |node| LayerElementEntryHandle::new(doc, node))
     }
 }
 
@@ -98,24 +107,27 @@ impl LayerIndex {
 pub struct LayerElementEntryHandle<'a> {
     /// `LayerElement` node under `Layer`.
     node: NodeHandle<'a>,
+    /// Document.
+    doc: &'a Document,
 }
 
 impl<'a> LayerElementEntryHandle<'a> {
     /// Creates a new `LayerElementEntryHandle` from the given node handle.
-    fn new(node: NodeHandle<'a>) -> Self {
-        Self { node }
+    fn new(doc: &'a Document, node: NodeHandle<'a>) -> Self {
+        Self { node, doc }
     }
 
     /// Returns layer element type string.
     pub fn type_str(&self) -> Result<&'a str, Error> {
-        self.children_by_name("Type")
+        let node = self
+            .children_by_name("Type")
             .next()
-            .ok_or_else(|| format_err!("Child node `Type` not found for `LayerElement`"))?
-            .attributes()
+            .ok_or_else(|| DomError::missing_node(&self.node, "Type"))?;
+        node.attributes()
             .get(0)
-            .ok_or_else(|| format_err!("Attributes not found for `Type`"))?
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?
             .get_string_or_type()
-            .map_err(|ty| format_err!("Expected string but got {:?}", ty))
+            .map_err(|ty| DomError::type_mismatch(ErrorLocation::node(&node), "string", ty).into())
     }
 
     /// Returns layer element type.
@@ -125,20 +137,25 @@ impl<'a> LayerElementEntryHandle<'a> {
 
     /// Returns the layer element index in the same type.
     pub fn typed_index(&self) -> Result<LayerElementIndex, Error> {
-        let raw = self
+        let node = self
             .children_by_name("TypedIndex")
             .next()
-            .ok_or_else(|| format_err!("Child node `TypedIndex` not found for `LayerElement`"))?
+            .ok_or_else(|| DomError::missing_node(&self.node, "TypedIndex"))?;
+        let raw = node
             .attributes()
             .get(0)
-            .ok_or_else(|| format_err!("Attributes not found for `TypedIndex`"))?
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?
             .get_i32_or_type()
-            .map_err(|ty| format_err!("Expected `i32` but got {:?}", ty))?;
+            .map_err(|ty| DomError::type_mismatch(ErrorLocation::node(&node), "`i32`", ty))?;
         if raw < 0 {
-            bail!(
-                "Expected non-negative integer as layer element index, but got {:?}",
-                raw
-            );
+            return Err(DomError::invalid_value(
+                ErrorLocation::node(&node),
+                format_args!(
+                    "Expected non-negative integer as layer element index, but got {:?}",
+                    raw
+                ),
+            )
+            .into());
         }
 
         Ok(LayerElementIndex::new(raw as u32))
@@ -163,13 +180,12 @@ impl<'a> LayerElementEntryHandle<'a> {
                     .map_or(false, |v| v == index.to_u32() as i32)
             })
             .ok_or_else(|| {
-                format_err!(
-                    "Layer element node not found: type={:?}, index={:?}",
-                    ty,
-                    index
+                DomError::missing_node(
+                    &geometry_node,
+                    &format!("{}[{}]", ty.type_name(), index.to_u32()),
                 )
             })
-            .map(|node| TypedLayerElementHandle::new(ty, node))
+            .map(|node| TypedLayerElementHandle::new(self.doc, ty, node))
     }
 }
 
@@ -265,8 +281,8 @@ pub enum TypedLayerElementHandle<'a> {
 
 impl<'a> TypedLayerElementHandle<'a> {
     /// Creates a new `TypedLayerElementHandle`.
-    fn new(ty: LayerElementType, node: NodeHandle<'a>) -> Self {
-        let base = LayerElementHandle::new(node);
+    fn new(doc: &'a Document, ty: LayerElementType, node: NodeHandle<'a>) -> Self {
+        let base = LayerElementHandle::new(doc, node);
         match ty {
             LayerElementType::Color => {
                 TypedLayerElementHandle::Color(LayerElementColorHandle::new(base))
@@ -1,12 +1,15 @@
 //! Color.
 
-use anyhow::{format_err, Error};
+use anyhow::Error;
 
-use crate::v7400::data::mesh::{
-    layer::{
-        LayerContentIndex, LayerElementHandle, MappingMode, ReferenceInformation, ReferenceMode,
+use crate::v7400::{
+    data::mesh::{
+        layer::{
+            LayerContentIndex, LayerElementHandle, MappingMode, ReferenceInformation, ReferenceMode,
+        },
+        PolygonIndex, PolygonVertexIndex, PolygonVertices, TriangleVertexIndex, TriangleVertices,
     },
-    TriangleVertexIndex, TriangleVertices,
+    Error as DomError, ErrorLocation,
 };
 
 /// Layer element node handle.
@@ -29,14 +32,15 @@ impl<'a> LayerElementColorHandle<'a> {
 
     /// Returns reference to the colors slice.
     fn colors_slice(&self) -> Result<&'a [f64], Error> {
-        self.children_by_name("Colors")
+        let node = self
+            .children_by_name("Colors")
             .next()
-            .ok_or_else(|| format_err!("No `Colors` found for `LayerElementColor` node"))?
-            .attributes()
-            .get(0)
-            .ok_or_else(|| format_err!("No attributes found for `Colors` node"))?
+            .ok_or_else(|| DomError::missing_node(self.node(), "Colors"))?;
+        self.document()
+            .attribute(&node, 0)?
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?
             .get_arr_f64_or_type()
-            .map_err(|ty| format_err!("Expected `[f64]` as colors, but got {:?}", ty))
+            .map_err(|ty| DomError::type_mismatch(ErrorLocation::node(&node), "`[f64]`", ty).into())
     }
 
     /// Returns reference to the colors index slice.
@@ -44,14 +48,15 @@ impl<'a> LayerElementColorHandle<'a> {
     // it would be better to implement this rather than rejecting `ColorsIndex`
     // (if it exists).
     fn colors_index_slice(&self) -> Result<&'a [i32], Error> {
-        self.children_by_name("ColorsIndex")
+        let node = self
+            .children_by_name("ColorsIndex")
             .next()
-            .ok_or_else(|| format_err!("No `ColorsIndex` found for `LayerElementColor` node"))?
-            .attributes()
-            .get(0)
-            .ok_or_else(|| format_err!("No attributes found for `ColorsIndex` node"))?
+            .ok_or_else(|| DomError::missing_node(self.node(), "ColorsIndex"))?;
+        self.document()
+            .attribute(&node, 0)?
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?
             .get_arr_i32_or_type()
-            .map_err(|ty| format_err!("Expected `[i32]` as color indices, but got {:?}", ty))
+            .map_err(|ty| DomError::type_mismatch(ErrorLocation::node(&node), "`[i32]`", ty).into())
     }
 }
 
@@ -108,12 +113,36 @@ impl<'a> Colors<'a> {
             self.colors.len() / 4,
             tri_vi,
         )?;
+        Ok(self.color_at(i))
+    }
+
+    /// Returns `[f64; 4]` color corresponding to the given polygon vertex of
+    /// the given polygon.
+    pub(crate) fn color_by_polygon_vertex(
+        &self,
+        pvs: &PolygonVertices<'a>,
+        pvi: PolygonVertexIndex,
+        poly_i: PolygonIndex,
+    ) -> Result<[f64; 4], Error> {
+        let i = LayerContentIndex::control_point_data_from_polygon_vertices(
+            self.reference_info,
+            self.mapping_mode,
+            pvs,
+            self.colors.len() / 4,
+            pvi,
+            poly_i,
+        )?;
+        Ok(self.color_at(i))
+    }
+
+    /// Returns the color at the given content index.
+    fn color_at(&self, i: LayerContentIndex) -> [f64; 4] {
         let i4 = i.get() * 4;
-        Ok([
+        [
             self.colors[i4],
             self.colors[i4 + 1],
             self.colors[i4 + 2],
             self.colors[i4 + 3],
-        ])
+        ]
     }
 }
@@ -2,11 +2,17 @@
 
 use std::convert::{TryFrom, TryInto};
 
-use anyhow::{bail, format_err, Error};
+use anyhow::{bail, Error};
 
 use crate::{
     fbxcel::tree::v7400::NodeHandle,
-    v7400::data::mesh::{layer::LayerElementIndex, TriangleVertexIndex, TriangleVertices},
+    v7400::{
+        data::mesh::{
+            layer::LayerElementIndex, PolygonIndex, PolygonVertexIndex, PolygonVertices,
+            TriangleVertexIndex, TriangleVertices,
+        },
+        Document, Error as DomError, ErrorLocation,
+    },
 };
 
 /// Layer element node handle.
@@ -14,12 +20,14 @@ use crate::{
 pub struct LayerElementHandle<'a> {
     /// `LayerElement*` node under `Geometry`.
     node: NodeHandle<'a>,
+    /// Document.
+    doc: &'a Document,
 }
 
 impl<'a> LayerElementHandle<'a> {
     /// Creates a new `LayerElementHandle`.
-    pub(crate) fn new(node: NodeHandle<'a>) -> Self {
-        Self { node }
+    pub(crate) fn new(doc: &'a Document, node: NodeHandle<'a>) -> Self {
+        Self { node, doc }
     }
 
     /// Returns a reference to the node handle.
@@ -27,20 +35,29 @@ impl<'a> LayerElementHandle<'a> {
         &self.node
     }
 
+    /// Returns the document.
+    pub(crate) fn document(&self) -> &'a Document {
+        self.doc
+    }
+
     /// Returns type-local layer element index.
     pub fn typed_index(&self) -> Result<LayerElementIndex, Error> {
         let raw = self
             .node()
             .attributes()
             .get(0)
-            .ok_or_else(|| format_err!("No attributes found for `{}` node", self.node().name()))?
+            .ok_or_else(|| DomError::missing_attribute(self.node(), 0))?
             .get_i32_or_type()
-            .map_err(|ty| format_err!("Expected `i32` as layer element index, but got {:?}", ty))?;
+            .map_err(|ty| DomError::type_mismatch(ErrorLocation::node(self.node()), "`i32`", ty))?;
         if raw < 0 {
-            bail!(
-                "Expected non-negative integer as layer element index, but got {:?}",
-                raw
-            );
+            return Err(DomError::invalid_value(
+                ErrorLocation::node(self.node()),
+                format_args!(
+                    "Expected non-negative integer as layer element index, but got {:?}",
+                    raw
+                ),
+            )
+            .into());
         }
 
         Ok(LayerElementIndex::new(raw as u32))
@@ -52,55 +69,36 @@ impl<'a> LayerElementHandle<'a> {
     /// If you want to get node name, do `obj.node().name()` instead of
     /// `obj.name()`.
     pub fn name(&self) -> Result<&'a str, Error> {
-        self.children_by_name("Name")
-            .next()
-            .ok_or_else(|| {
-                format_err!(
-                    "Child node `Name` not found for `{}` node",
-                    self.node().name()
-                )
-            })?
-            .attributes()
-            .get(0)
-            .ok_or_else(|| format_err!("No attributes found for `{}` node", self.node().name()))?
-            .get_string_or_type()
-            .map_err(|ty| format_err!("Expected string as layer element name, but got {:?}", ty))
+        self.string_child("Name").map(|(_, s)| s)
     }
 
     /// Returns mapping mode.
     pub fn mapping_mode(&self) -> Result<MappingMode, Error> {
-        self.children_by_name("MappingInformationType")
-            .next()
-            .ok_or_else(|| {
-                format_err!(
-                    "Child node `MappingInformationType` not found for `{}` node",
-                    self.node().name()
-                )
-            })?
-            .attributes()
-            .get(0)
-            .ok_or_else(|| format_err!("No attributes found for `{}` node", self.node().name()))?
-            .get_string_or_type()
-            .map_err(|ty| format_err!("Expected string as layer element name, but got {:?}", ty))
-            .and_then(str::parse)
+        let (node, s) = self.string_child("MappingInformationType")?;
+        s.parse()
+            .map_err(|e| DomError::invalid_value(ErrorLocation::node(&node), e).into())
     }
 
     /// Returns reference mode.
     pub fn reference_mode(&self) -> Result<ReferenceMode, Error> {
-        self.children_by_name("ReferenceInformationType")
+        let (node, s) = self.string_child("ReferenceInformationType")?;
+        s.parse()
+            .map_err(|e| DomError::invalid_value(ErrorLocation::node(&node), e).into())
+    }
+
+    /// Returns the child node with the given name and its string attribute.
+    fn string_child(&self, name: &str) -> Result<(NodeHandle<'a>, &'a str), Error> {
+        let child = self
+            .children_by_name(name)
             .next()
-            .ok_or_else(|| {
-                format_err!(
-                    "Child node `ReferenceInformationType` not found for `{}` node",
-                    self.node().name()
-                )
-            })?
+            .ok_or_else(|| DomError::missing_node(self.node(), name))?;
+        let s = child
             .attributes()
             .get(0)
-            .ok_or_else(|| format_err!("No attributes found for `{}` node", self.node().name()))?
+            .ok_or_else(|| DomError::missing_attribute(&child, 0))?
             .get_string_or_type()
-            .map_err(|ty| format_err!("Expected string as layer element name, but got {:?}", ty))
-            .and_then(str::parse)
+            .map_err(|ty| DomError::type_mismatch(ErrorLocation::node(&child), "string", ty))?;
+        Ok((child, s))
     }
 }
 
@@ -139,7 +137,7 @@ impl TryFrom<&str> for MappingMode {
             "ByPolygon" => Ok(MappingMode::ByPolygon),
             "ByEdge" => Ok(MappingMode::ByEdge),
             "AllSame" => Ok(MappingMode::AllSame),
-            s => Err(format_err!("Failed to parse mapping mode: got {:?}", s)),
+            s => Err(DomError::parse_error(ErrorLocation::default(), "mapping mode", s).into()),
         }
     }
 }
@@ -168,7 +166,7 @@ impl TryFrom<&str> for ReferenceMode {
         match s {
             "Direct" => Ok(ReferenceMode::Direct),
             "IndexToDirect" => Ok(ReferenceMode::IndexToDirect),
-            s => Err(format_err!("Failed to parse reference mode: got {:?}", s)),
+            s => Err(DomError::parse_error(ErrorLocation::default(), "reference mode", s).into()),
         }
     }
 }
@@ -197,10 +195,11 @@ impl ReferenceInformation<'_> {
             ReferenceInformation::Direct => Ok(LayerContentIndex::new(i)),
             ReferenceInformation::IndexToDirect(indices) => {
                 let direct = indices.get(i).cloned().ok_or_else(|| {
-                    format_err!(
-                        "Index out of range: indices.len()={:?}, i={:?}",
+                    DomError::corrupt_index(
+                        ErrorLocation::default(),
+                        "Layer element index",
+                        i64::try_from(i).unwrap_or(i64::MAX),
                         indices.len(),
-                        i
                     )
                 })?;
                 let direct = if direct < 0 {
@@ -248,42 +247,99 @@ impl LayerContentIndex {
         layer_element_array_len: usize,
         tri_vi: TriangleVertexIndex,
     ) -> Result<LayerContentIndex, Error> {
-        let index = match mapping_mode {
-            MappingMode::None | MappingMode::ByEdge => {
-                bail!("Unsupported mapping mode: {:?}", mapping_mode)
-            }
-            MappingMode::ByControlPoint => {
-                let cpi = triangle_vertices
+        let corrupt = || {
+            DomError::corrupt_index(
+                ErrorLocation::default(),
+                "Triangle vertex index",
+                i64::try_from(tri_vi.to_usize()).unwrap_or(i64::MAX),
+                triangle_vertices.len(),
+            )
+        };
+        Self::from_source_indices(
+            reference_info,
+            mapping_mode,
+            layer_element_array_len,
+            || {
+                triangle_vertices
                     .control_point_index(tri_vi)
-                    .ok_or_else(|| {
-                        format_err!("Failed to get control point index: tri_vi={:?}", tri_vi)
-                    })?;
-                reference_info.get_direct(cpi.to_u32() as usize)?
-            }
-            MappingMode::ByPolygonVertex => {
-                let pvi = triangle_vertices
+                    .map(|cpi| cpi.to_u32() as usize)
+                    .ok_or_else(|| corrupt().into())
+            },
+            || {
+                triangle_vertices
                     .polygon_vertex_index(tri_vi)
-                    .ok_or_else(|| {
-                        format_err!("Failed to get polygon vertex index: tri_vi={:?}", tri_vi)
-                    })?;
-                reference_info.get_direct(pvi.to_usize() as usize)?
-            }
-            MappingMode::ByPolygon => {
-                let poly_i = triangle_vertices
+                    .map(PolygonVertexIndex::to_usize)
+                    .ok_or_else(|| corrupt().into())
+            },
+            || {
+                triangle_vertices
                     .polygon_index(tri_vi.triangle_index())
+                    .map(PolygonIndex::to_usize)
+                    .ok_or_else(|| corrupt().into())
+            },
+        )
+    }
+
+    /// Returns the layer content index for the given polygon vertex of the
+    /// given polygon.
+    pub(crate) fn control_point_data_from_polygon_vertices(
+        reference_info: ReferenceInformation<'_>,
+        mapping_mode: MappingMode,
+        polygon_vertices: &PolygonVertices<'_>,
+        layer_element_array_len: usize,
+        pvi: PolygonVertexIndex,
+        poly_i: PolygonIndex,
+    ) -> Result<LayerContentIndex, Error> {
+        Self::from_source_indices(
+            reference_info,
+            mapping_mode,
+            layer_element_array_len,
+            || {
+                polygon_vertices
+                    .polygon_vertex(pvi)
+                    .map(|pv| pv.to_u32() as usize)
                     .ok_or_else(|| {
-                        format_err!("Failed to get polygon vertex index: tri_vi={:?}", tri_vi)
-                    })?;
-                reference_info.get_direct(poly_i.to_usize())?
+                        DomError::corrupt_index(
+                            ErrorLocation::default(),
+                            "Polygon vertex index",
+                            i64::try_from(pvi.to_usize()).unwrap_or(i64::MAX),
+                            polygon_vertices.raw_polygon_vertices().len(),
+                        )
+                        .into()
+                    })
+            },
+            || Ok(pvi.to_usize()),
+            || Ok(poly_i.to_usize()),
+        )
+    }
+
+    /// Returns the layer content index for the control point index, polygon
+    /// vertex index, or polygon index, depending on the mapping mode.
+    fn from_source_indices(
+        reference_info: ReferenceInformation<'_>,
+        mapping_mode: MappingMode,
+        layer_element_array_len: usize,
+        cpi: impl FnOnce() -> Result<usize, Error>,
+        pvi: impl FnOnce() -> Result<usize, Error>,
+        poly_i: impl FnOnce() -> Result<usize, Error>,
+    ) -> Result<LayerContentIndex, Error> {
+        let index = match mapping_mode {
+            MappingMode::None | MappingMode::ByEdge => {
+                bail!("Unsupported mapping mode: {:?}", mapping_mode)
             }
+            MappingMode::ByControlPoint => reference_info.get_direct(cpi()?)?,
+            MappingMode::ByPolygonVertex => reference_info.get_direct(pvi()?)?,
+            MappingMode::ByPolygon => reference_info.get_direct(poly_i()?)?,
             MappingMode::AllSame => reference_info.get_direct(0)?,
         };
         if index.get() >= layer_element_array_len {
-            bail!(
-                "Calculated index out of range: index={:?}, array_len={:?}",
-                index,
-                layer_element_array_len
-            );
+            return Err(DomError::corrupt_index(
+                ErrorLocation::default(),
+                "Calculated layer element content index",
+                i64::try_from(index.get()).unwrap_or(i64::MAX),
+                layer_element_array_len,
+            )
+            .into());
         }
 
         Ok(index)
@@ -1,12 +1,15 @@
 //! Material.
 
-use anyhow::{bail, format_err, Error};
-
-use crate::v7400::data::mesh::{
-    layer::{
-        LayerContentIndex, LayerElementHandle, MappingMode, ReferenceInformation, ReferenceMode,
+use anyhow::{bail, Error};
+
+use crate::v7400::{
+    data::mesh::{
+        layer::{
+            LayerContentIndex, LayerElementHandle, MappingMode, ReferenceInformation, ReferenceMode,
+        },
+        PolygonIndex, PolygonVertexIndex, PolygonVertices, TriangleVertexIndex, TriangleVertices,
     },
-    TriangleVertexIndex, TriangleVertices,
+    Error as DomError, ErrorLocation,
 };
 
 /// Layer element node handle.
@@ -29,14 +32,15 @@ impl<'a> LayerElementMaterialHandle<'a> {
 
     /// Returns material indices slice.
     fn material_indices_slice(&self) -> Result<&'a [i32], Error> {
-        self.children_by_name("Materials")
+        let node = self
+            .children_by_name("Materials")
             .next()
-            .ok_or_else(|| format_err!("No `Materials` found for `LayerElementMaterial` node"))?
-            .attributes()
-            .get(0)
-            .ok_or_else(|| format_err!("No attributes found for `Materials` node"))?
+            .ok_or_else(|| DomError::missing_node(self.node(), "Materials"))?;
+        self.document()
+            .attribute(&node, 0)?
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?
             .get_arr_i32_or_type()
-            .map_err(|ty| format_err!("Expected `[i32]` as material indices, but got {:?}", ty))
+            .map_err(|ty| DomError::type_mismatch(ErrorLocation::node(&node), "`[i32]`", ty).into())
     }
 }
 
@@ -89,6 +93,30 @@ impl<'a> Materials<'a> {
             self.indices.len(),
             tri_vi,
         )?;
+        self.material_index_at(i)
+    }
+
+    /// Returns material index corresponding to the given polygon vertex of
+    /// the given polygon.
+    pub fn material_index_by_polygon_vertex(
+        &self,
+        pvs: &PolygonVertices<'a>,
+        pvi: PolygonVertexIndex,
+        poly_i: PolygonIndex,
+    ) -> Result<MaterialIndex, Error> {
+        let i = LayerContentIndex::control_point_data_from_polygon_vertices(
+            ReferenceInformation::Direct,
+            self.mapping_mode,
+            pvs,
+            self.indices.len(),
+            pvi,
+            poly_i,
+        )?;
+        self.material_index_at(i)
+    }
+
+    /// Returns material index at the given layer content index.
+    fn material_index_at(&self, i: LayerContentIndex) -> Result<MaterialIndex, Error> {
         let material_index_index = self.indices[i.get()];
         if material_index_index < 0 {
             bail!(
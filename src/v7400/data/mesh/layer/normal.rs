@@ -1,13 +1,16 @@
 //! Normal.
 
-use anyhow::{bail, format_err, Error};
+use anyhow::{bail, Error};
 use mint::Vector3;
 
-use crate::v7400::data::mesh::{
-    layer::{
-        LayerContentIndex, LayerElementHandle, MappingMode, ReferenceInformation, ReferenceMode,
+use crate::v7400::{
+    data::mesh::{
+        layer::{
+            LayerContentIndex, LayerElementHandle, MappingMode, ReferenceInformation, ReferenceMode,
+        },
+        PolygonIndex, PolygonVertexIndex, PolygonVertices, TriangleVertexIndex, TriangleVertices,
     },
-    TriangleVertexIndex, TriangleVertices,
+    Error as DomError, ErrorLocation,
 };
 
 /// Layer element node handle.
@@ -30,14 +33,15 @@ impl<'a> LayerElementNormalHandle<'a> {
 
     /// Returns reference to the normals (xyz) slice.
     fn normals_vec3_slice(&self) -> Result<&'a [f64], Error> {
-        self.children_by_name("Normals")
+        let node = self
+            .children_by_name("Normals")
             .next()
-            .ok_or_else(|| format_err!("No `Normals` found for `LayerElementNormal` node"))?
-            .attributes()
-            .get(0)
-            .ok_or_else(|| format_err!("No attributes found for `Normals` node"))?
+            .ok_or_else(|| DomError::missing_node(self.node(), "Normals"))?;
+        self.document()
+            .attribute(&node, 0)?
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?
             .get_arr_f64_or_type()
-            .map_err(|ty| format_err!("Expected `[f64]` as normals, but got {:?}", ty))
+            .map_err(|ty| DomError::type_mismatch(ErrorLocation::node(&node), "`[f64]`", ty).into())
     }
 
     /// Returns reference to the normals norms (w = `sqrt(x*x + y*y + z*z)`)
@@ -50,13 +54,14 @@ impl<'a> LayerElementNormalHandle<'a> {
             Some(v) => v,
             None => return Ok(None),
         };
-        normals_w_node
-            .attributes()
-            .get(0)
-            .ok_or_else(|| format_err!("No attributes found for `NormalsW` node"))?
+        self.document()
+            .attribute(&normals_w_node, 0)?
+            .ok_or_else(|| DomError::missing_attribute(&normals_w_node, 0))?
             .get_arr_f64_or_type()
             .map(Some)
-            .map_err(|ty| format_err!("Expected `[f64]` as normals W, but got {:?}", ty))
+            .map_err(|ty| {
+                DomError::type_mismatch(ErrorLocation::node(&normals_w_node), "`[f64]`", ty).into()
+            })
     }
 }
 
@@ -116,4 +121,23 @@ impl<'a> Normals<'a> {
         )?;
         Ok(Vector3::from_slice(&self.normals[(i.get() * 3)..]))
     }
+
+    /// Returns `[f64; 3]` normal corresponding to the given polygon vertex of
+    /// the given polygon.
+    pub(crate) fn normal_by_polygon_vertex(
+        &self,
+        pvs: &PolygonVertices<'a>,
+        pvi: PolygonVertexIndex,
+        poly_i: PolygonIndex,
+    ) -> Result<Vector3<f64>, Error> {
+        let i = LayerContentIndex::control_point_data_from_polygon_vertices(
+            ReferenceInformation::Direct,
+            self.mapping_mode,
+            pvs,
+            self.normals.len() / 3,
+            pvi,
+            poly_i,
+        )?;
+        Ok(Vector3::from_slice(&self.normals[(i.get() * 3)..]))
+    }
 }
@@ -1,13 +1,16 @@
 //! UV.
 
-use anyhow::{format_err, Error};
+use anyhow::Error;
 use mint::Point2;
 
-use crate::v7400::data::mesh::{
-    layer::{
-        LayerContentIndex, LayerElementHandle, MappingMode, ReferenceInformation, ReferenceMode,
+use crate::v7400::{
+    data::mesh::{
+        layer::{
+            LayerContentIndex, LayerElementHandle, MappingMode, ReferenceInformation, ReferenceMode,
+        },
+        PolygonIndex, PolygonVertexIndex, PolygonVertices, TriangleVertexIndex, TriangleVertices,
     },
-    TriangleVertexIndex, TriangleVertices,
+    Error as DomError, ErrorLocation,
 };
 
 /// Layer element node handle.
@@ -30,26 +33,28 @@ impl<'a> LayerElementUvHandle<'a> {
 
     /// Returns reference to the uv slice.
     fn uv_slice(&self) -> Result<&'a [f64], Error> {
-        self.children_by_name("UV")
+        let node = self
+            .children_by_name("UV")
             .next()
-            .ok_or_else(|| format_err!("No `UV` found for `LayerElementUV` node"))?
-            .attributes()
-            .get(0)
-            .ok_or_else(|| format_err!("No attributes found for `UV` node"))?
+            .ok_or_else(|| DomError::missing_node(self.node(), "UV"))?;
+        self.document()
+            .attribute(&node, 0)?
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?
             .get_arr_f64_or_type()
-            .map_err(|ty| format_err!("Expected `[f64]` as UVs, but got {:?}", ty))
+            .map_err(|ty| DomError::type_mismatch(ErrorLocation::node(&node), "`[f64]`", ty).into())
     }
 
     /// Returns reference to the uv index slice.
     fn uv_index_slice(&self) -> Result<&'a [i32], Error> {
-        self.children_by_name("UVIndex")
+        let node = self
+            .children_by_name("UVIndex")
             .next()
-            .ok_or_else(|| format_err!("No `UVIndex` found for `LayerElementUV` node"))?
-            .attributes()
-            .get(0)
-            .ok_or_else(|| format_err!("No attributes found for `UVIndex` node"))?
+            .ok_or_else(|| DomError::missing_node(self.node(), "UVIndex"))?;
+        self.document()
+            .attribute(&node, 0)?
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?
             .get_arr_i32_or_type()
-            .map_err(|ty| format_err!("Expected `[i32]` as UV indices, but got {:?}", ty))
+            .map_err(|ty| DomError::type_mismatch(ErrorLocation::node(&node), "`[i32]`", ty).into())
     }
 }
 
@@ -107,4 +112,23 @@ impl<'a> Uv<'a> {
         )?;
         Ok(Point2::from_slice(&self.uv[(i.get() * 2)..]))
     }
+
+    /// Returns `[f64; 2]` uv corresponding to the given polygon vertex of the
+    /// given polygon.
+    pub(crate) fn uv_by_polygon_vertex(
+        &self,
+        pvs: &PolygonVertices<'a>,
+        pvi: PolygonVertexIndex,
+        poly_i: PolygonIndex,
+    ) -> Result<Point2<f64>, Error> {
+        let i = LayerContentIndex::control_point_data_from_polygon_vertices(
+            self.reference_info,
+            self.mapping_mode,
+            pvs,
+            self.uv.len() / 2,
+            pvi,
+            poly_i,
+        )?;
+        Ok(Point2::from_slice(&self.uv[(i.get() * 2)..]))
+    }
 }
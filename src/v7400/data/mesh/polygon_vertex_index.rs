@@ -3,7 +3,10 @@
 use anyhow::{bail, Error};
 use mint::Point3;
 
-use crate::v7400::data::mesh::{ControlPointIndex, ControlPoints, TriangleVertices};
+use crate::v7400::data::{
+    mesh::{ControlPointIndex, ControlPoints, TriangleVertices},
+    scalar,
+};
 
 /// Polygon vertex index.
 ///
@@ -36,6 +39,11 @@ impl<'a> RawPolygonVertices<'a> {
         Self { data }
     }
 
+    /// Returns the polygon vertices (control point indices).
+    pub(crate) fn data(&self) -> &'a [i32] {
+        self.data
+    }
+
     /// Returns a polygon vertex at the given index.
     pub(crate) fn get(&self, pvi: PolygonVertexIndex) -> Option<PolygonVertex> {
         self.data
@@ -71,6 +79,12 @@ impl<'a> PolygonVertices<'a> {
         self.control_points.iter()
     }
 
+    /// Returns the raw control points converted to `f32`.
+    pub fn raw_control_points_f32(&self) -> anyhow::Result<impl Iterator<Item = Point3<f32>> + 'a> {
+        self.raw_control_points()
+            .map(|points| points.map(scalar::point3))
+    }
+
     /// Returns a slice of the raw polygon vertices (indices).
     pub fn raw_polygon_vertices(&self) -> &[i32] {
         self.polygon_vertices.data
@@ -88,7 +102,19 @@ impl<'a> PolygonVertices<'a> {
             .and_then(|cpi| self.control_points.get(cpi))
     }
 
+    /// Returns a control point at the given index, converted to `f32`.
+    pub fn control_point_f32(&self, i: impl Into<IntoCpiWithPolyVerts>) -> Option<Point3<f32>> {
+        self.control_point(i).map(scalar::point3)
+    }
+
     /// Triangulates the polygons and returns indices map.
+    ///
+    /// Fails if the triangulator returns polygon vertex indices not given for
+    /// the polygon.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "triangulate", skip_all)
+    )]
     pub fn triangulate_each<F>(&self, mut triangulator: F) -> Result<TriangleVertices<'a>, Error>
     where
         F: FnMut(
@@ -126,6 +152,18 @@ impl<'a> PolygonVertices<'a> {
             current_poly_pvis
                 .extend((pv_index_start..pv_index_next_start).map(PolygonVertexIndex::new));
             triangulator(self, &current_poly_pvis, &mut tri_results)?;
+            if let Some(pvi) = tri_results
+                .iter()
+                .flatten()
+                .find(|pvi| !(pv_index_start..pv_index_next_start).contains(&pvi.to_usize()))
+            {
+                bail!(
+                    "Triangulator returned a polygon vertex outside of the polygon: \
+                     pvi={:?}, polygon={}",
+                    pvi,
+                    current_poly_index
+                );
+            }
             tri_pv_indices.extend(tri_results.iter().flatten());
             tri_poly_indices
                 .extend((0..tri_results.len()).map(|_| PolygonIndex::new(current_poly_index)));
@@ -133,6 +171,12 @@ impl<'a> PolygonVertices<'a> {
             pv_index_start = pv_index_next_start;
             current_poly_index += 1;
         }
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            polygons = current_poly_index,
+            triangles = tri_poly_indices.len(),
+            "Triangulated"
+        );
 
         Ok(TriangleVertices::new(
             *self,
@@ -193,7 +237,7 @@ pub struct PolygonIndex(usize);
 
 impl PolygonIndex {
     /// Creates a new `PolygonIndex`.
-    fn new(v: usize) -> Self {
+    pub(crate) fn new(v: usize) -> Self {
         Self(v)
     }
 
@@ -0,0 +1,191 @@
+//! Resolved layer elements.
+
+use std::collections::{hash_map::Entry, HashMap};
+
+use anyhow::Error;
+use mint::{Point2, Vector3};
+
+use crate::v7400::{
+    data::{
+        mesh::{
+            layer::{MappingMode, TypedLayerElementHandle},
+            PolygonIndex, PolygonVertexIndex,
+        },
+        scalar::{self, Scalar},
+    },
+    object::{geometry::MeshHandle, ObjectId},
+    Warning,
+};
+
+/// Layer element values of a mesh resolved for each polygon vertex.
+///
+/// The first normal, UV and color layer elements of the mesh are used.
+/// Values are indexed by polygon vertex indices, so mapping modes and
+/// reference modes need not be resolved on every access.
+///
+/// Values are converted to the scalar type `T` on resolving, so use `f32` to
+/// avoid keeping `f64` copies if they are not necessary.
+#[derive(Default, Debug, Clone)]
+pub struct ResolvedLayerElements<T: Scalar = f64> {
+    /// Normals.
+    normals: Option<Vec<Vector3<T>>>,
+    /// UVs.
+    uvs: Option<Vec<Point2<T>>>,
+    /// Colors.
+    colors: Option<Vec<[T; 4]>>,
+}
+
+impl<T: Scalar> ResolvedLayerElements<T> {
+    /// Resolves the layer elements of the given mesh.
+    ///
+    /// Layer elements with unsupported mapping modes (`ByEdge` and none) are
+    /// ignored with warnings recorded in the document.
+    pub fn from_mesh(mesh: &MeshHandle<'_>) -> Result<Self, Error> {
+        let pvs = mesh.polygon_vertices()?;
+
+        let mut normals = None;
+        let mut uvs = None;
+        let mut colors = None;
+        for entry in mesh
+            .layers()
+            .flat_map(|layer| layer.layer_element_entries())
+        {
+            let element = match entry.typed_layer_element() {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            let base = match &element {
+                TypedLayerElementHandle::Normal(h) if normals.is_none() => **h,
+                TypedLayerElementHandle::Uv(h) if uvs.is_none() => **h,
+                TypedLayerElementHandle::Color(h) if colors.is_none() => **h,
+                _ => continue,
+            };
+            let mapping_mode = base.mapping_mode()?;
+            if let MappingMode::None | MappingMode::ByEdge = mapping_mode {
+                mesh.document().warn(Warning::UnsupportedMappingMode {
+                    geometry: mesh.object_id(),
+                    element: base.node().name().to_owned(),
+                    mapping_mode,
+                });
+                continue;
+            }
+            match element {
+                TypedLayerElementHandle::Normal(h) => normals = Some(h.normals()?),
+                TypedLayerElementHandle::Uv(h) => uvs = Some(h.uv()?),
+                TypedLayerElementHandle::Color(h) => colors = Some(h.color()?),
+                _ => {}
+            }
+        }
+
+        let len = pvs.raw_polygon_vertices().len();
+        let mut resolved = Self {
+            normals: normals.map(|_| Vec::with_capacity(len)),
+            uvs: uvs.map(|_| Vec::with_capacity(len)),
+            colors: colors.map(|_| Vec::with_capacity(len)),
+        };
+        let mut poly_i = 0;
+        for (i, &raw) in pvs.raw_polygon_vertices().iter().enumerate() {
+            let pvi = PolygonVertexIndex::new(i);
+            let polygon = PolygonIndex::new(poly_i);
+            if let (Some(src), Some(dest)) = (&normals, &mut resolved.normals) {
+                dest.push(scalar::vector3(
+                    src.normal_by_polygon_vertex(&pvs, pvi, polygon)?,
+                ));
+            }
+            if let (Some(src), Some(dest)) = (&uvs, &mut resolved.uvs) {
+                dest.push(scalar::point2(
+                    src.uv_by_polygon_vertex(&pvs, pvi, polygon)?,
+                ));
+            }
+            if let (Some(src), Some(dest)) = (&colors, &mut resolved.colors) {
+                dest.push(scalar::array4(
+                    src.color_by_polygon_vertex(&pvs, pvi, polygon)?,
+                ));
+            }
+            if raw < 0 {
+                poly_i += 1;
+            }
+        }
+
+        Ok(resolved)
+    }
+
+    /// Returns normals by polygon vertex index, if the mesh has normals.
+    pub fn normals(&self) -> Option<&[Vector3<T>]> {
+        self.normals.as_deref()
+    }
+
+    /// Returns UVs by polygon vertex index, if the mesh has UVs.
+    pub fn uvs(&self) -> Option<&[Point2<T>]> {
+        self.uvs.as_deref()
+    }
+
+    /// Returns colors by polygon vertex index, if the mesh has colors.
+    pub fn colors(&self) -> Option<&[[T; 4]]> {
+        self.colors.as_deref()
+    }
+
+    /// Returns the normal of the given polygon vertex.
+    pub fn normal(&self, pvi: PolygonVertexIndex) -> Option<Vector3<T>> {
+        self.normals()?.get(pvi.to_usize()).copied()
+    }
+
+    /// Returns the UV of the given polygon vertex.
+    pub fn uv(&self, pvi: PolygonVertexIndex) -> Option<Point2<T>> {
+        self.uvs()?.get(pvi.to_usize()).copied()
+    }
+
+    /// Returns the color of the given polygon vertex.
+    pub fn color(&self, pvi: PolygonVertexIndex) -> Option<[T; 4]> {
+        self.colors()?.get(pvi.to_usize()).copied()
+    }
+}
+
+/// Cache of resolved layer elements of meshes.
+///
+/// When the same mesh is queried many times (for example, building vertex
+/// buffers and then computing bounds, or exporting a mesh shared by many
+/// models), use this cache to resolve the layer elements only once.
+///
+/// The cache is keyed by object IDs, so it should be used only with a single
+/// document.
+/// Cached values are not updated automatically; call
+/// [`invalidate`][`Self::invalidate`] or [`clear`][`Self::clear`] when the
+/// meshes are modified.
+///
+/// To cache `f32` values, use `LayerElementCache::<f32>::default()`.
+#[derive(Default, Debug, Clone)]
+pub struct LayerElementCache<T: Scalar = f64> {
+    /// Resolved layer elements by geometry object ID.
+    meshes: HashMap<ObjectId, ResolvedLayerElements<T>>,
+}
+
+impl LayerElementCache {
+    /// Creates a new empty `LayerElementCache` for `f64` values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T: Scalar> LayerElementCache<T> {
+    /// Removes all cached values.
+    pub fn clear(&mut self) {
+        self.meshes.clear();
+    }
+
+    /// Removes the cached values of the given geometry.
+    ///
+    /// Returns true if the values were cached.
+    pub fn invalidate(&mut self, geometry: ObjectId) -> bool {
+        self.meshes.remove(&geometry).is_some()
+    }
+
+    /// Returns the resolved layer elements of the given mesh, resolving them
+    /// on first use.
+    pub fn resolved(&mut self, mesh: &MeshHandle<'_>) -> Result<&ResolvedLayerElements<T>, Error> {
+        match self.meshes.entry(mesh.object_id()) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => Ok(entry.insert(ResolvedLayerElements::from_mesh(mesh)?)),
+        }
+    }
+}
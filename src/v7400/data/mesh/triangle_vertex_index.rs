@@ -2,8 +2,9 @@
 
 use mint::Point3;
 
-use crate::v7400::data::mesh::{
-    ControlPointIndex, PolygonIndex, PolygonVertex, PolygonVertexIndex, PolygonVertices,
+use crate::v7400::data::{
+    mesh::{ControlPointIndex, PolygonIndex, PolygonVertex, PolygonVertexIndex, PolygonVertices},
+    scalar,
 };
 
 /// Triange vertex index.
@@ -89,6 +90,12 @@ impl<'a> TriangleVertices<'a> {
             .and_then(|cpi| self.polygon_vertices.control_point(cpi))
     }
 
+    /// Returns control point corresponding to the given triangle vertex,
+    /// converted to `f32`.
+    pub fn control_point_f32(&self, i: impl Into<IntoCpiWithTriVerts>) -> Option<Point3<f32>> {
+        self.control_point(i).map(scalar::point3)
+    }
+
     /// Returns the number of triangle vertices.
     pub fn len(&self) -> usize {
         self.tri_pv_indices.len()
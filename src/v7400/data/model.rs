@@ -0,0 +1,65 @@
+//! Model data.
+
+use std::convert::{TryFrom, TryInto};
+
+use anyhow::{bail, Error};
+use mint::Point3;
+
+/// Face culling mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Culling {
+    /// No culling.
+    Off,
+    /// Cull counter-clockwise faces.
+    OnCcw,
+    /// Cull clockwise faces.
+    OnCw,
+}
+
+impl Default for Culling {
+    fn default() -> Self {
+        Culling::Off
+    }
+}
+
+impl TryFrom<&str> for Culling {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "CullingOff" => Ok(Culling::Off),
+            "CullingOnCCW" => Ok(Culling::OnCcw),
+            "CullingOnCW" => Ok(Culling::OnCw),
+            s => bail!("Unexpected `Culling` value: {:?}", s),
+        }
+    }
+}
+
+impl std::str::FromStr for Culling {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.try_into()
+    }
+}
+
+/// Rest-pose segment of a bone, in global (world) coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoneSegment {
+    /// Position of the joint of the bone.
+    pub head: Point3<f64>,
+    /// Position of the end of the bone.
+    pub tail: Point3<f64>,
+}
+
+impl BoneSegment {
+    /// Returns the length of the segment.
+    pub fn length(&self) -> f64 {
+        let d = [
+            self.tail.x - self.head.x,
+            self.tail.y - self.head.y,
+            self.tail.z - self.head.z,
+        ];
+        (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt()
+    }
+}
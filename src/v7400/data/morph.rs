@@ -0,0 +1,312 @@
+//! Morph target naming conventions.
+//!
+//! Blend shape channels are named differently by each DCC tool and rig
+//! (`eyeBlinkLeft`, `EyeBlink_L`, `Face:eye_blink_left`, ...), while avatar
+//! pipelines expect the names of a fixed convention.
+//! [`MorphNameMap`] maps channel names to the canonical names of the
+//! conventions, with user-defined aliases.
+//!
+//! Names are compared after normalization: namespaces (`Face:` and
+//! `blendShape1.`) and a leading `viseme` word are removed, words are split at
+//! separators and camel case boundaries, `L` and `R` words are expanded to
+//! `Left` and `Right`, and case is ignored.
+//!
+//! ```
+//! use fbxcel_dom::v7400::data::morph::{MorphConvention, MorphNameMap};
+//!
+//! let map = MorphNameMap::new()
+//!     .with_convention(MorphConvention::ArKit)
+//!     .with_convention(MorphConvention::Viseme)
+//!     .alias("Blink_L", "eyeBlinkLeft");
+//!
+//! assert_eq!(map.canonical_name("EyeBlink_R"), Some("eyeBlinkRight"));
+//! assert_eq!(map.canonical_name("Face:mouth_smile_left"), Some("mouthSmileLeft"));
+//! assert_eq!(map.canonical_name("viseme_aa"), Some("aa"));
+//! assert_eq!(map.canonical_name("Blink_L"), Some("eyeBlinkLeft"));
+//! assert_eq!(map.convention("Blink_L"), Some(MorphConvention::ArKit));
+//! assert_eq!(map.canonical_name("Frown"), None);
+//! ```
+//!
+//! [`MorphNameMap`]: struct.MorphNameMap.html
+
+use std::collections::{HashMap, HashSet};
+
+use log::warn;
+
+use crate::v7400::object::deformer::BlendShapeChannelHandle;
+
+/// ARKit blend shape names.
+const ARKIT_NAMES: [&str; 52] = [
+    "browDownLeft",
+    "browDownRight",
+    "browInnerUp",
+    "browOuterUpLeft",
+    "browOuterUpRight",
+    "cheekPuff",
+    "cheekSquintLeft",
+    "cheekSquintRight",
+    "eyeBlinkLeft",
+    "eyeBlinkRight",
+    "eyeLookDownLeft",
+    "eyeLookDownRight",
+    "eyeLookInLeft",
+    "eyeLookInRight",
+    "eyeLookOutLeft",
+    "eyeLookOutRight",
+    "eyeLookUpLeft",
+    "eyeLookUpRight",
+    "eyeSquintLeft",
+    "eyeSquintRight",
+    "eyeWideLeft",
+    "eyeWideRight",
+    "jawForward",
+    "jawLeft",
+    "jawOpen",
+    "jawRight",
+    "mouthClose",
+    "mouthDimpleLeft",
+    "mouthDimpleRight",
+    "mouthFrownLeft",
+    "mouthFrownRight",
+    "mouthFunnel",
+    "mouthLeft",
+    "mouthLowerDownLeft",
+    "mouthLowerDownRight",
+    "mouthPressLeft",
+    "mouthPressRight",
+    "mouthPucker",
+    "mouthRight",
+    "mouthRollLower",
+    "mouthRollUpper",
+    "mouthShrugLower",
+    "mouthShrugUpper",
+    "mouthSmileLeft",
+    "mouthSmileRight",
+    "mouthStretchLeft",
+    "mouthStretchRight",
+    "mouthUpperUpLeft",
+    "mouthUpperUpRight",
+    "noseSneerLeft",
+    "noseSneerRight",
+    "tongueOut",
+];
+
+/// Viseme names.
+const VISEME_NAMES: [&str; 15] = [
+    "sil", "PP", "FF", "TH", "DD", "kk", "CH", "SS", "nn", "RR", "aa", "E", "ih", "oh", "ou",
+];
+
+/// Morph target naming convention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MorphConvention {
+    /// The 52 blend shapes of Apple ARKit face tracking.
+    ///
+    /// See
+    /// <https://developer.apple.com/documentation/arkit/arfaceanchor/blendshapelocation>.
+    ArKit,
+    /// The 15 visemes of Meta (Oculus) Lipsync.
+    ///
+    /// See
+    /// <https://developer.oculus.com/documentation/unity/audio-ovrlipsync-viseme-reference/>.
+    Viseme,
+}
+
+impl MorphConvention {
+    /// Returns the canonical names of the convention.
+    pub fn names(self) -> &'static [&'static str] {
+        match self {
+            MorphConvention::ArKit => &ARKIT_NAMES,
+            MorphConvention::Viseme => &VISEME_NAMES,
+        }
+    }
+}
+
+/// Table from morph target names to canonical names.
+#[derive(Default, Debug, Clone)]
+pub struct MorphNameMap {
+    /// Canonical names and conventions by normalized names.
+    names: HashMap<String, (String, Option<MorphConvention>)>,
+}
+
+impl MorphNameMap {
+    /// Creates a new empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the canonical names of the given convention.
+    ///
+    /// Names already registered are not overwritten.
+    pub fn with_convention(mut self, convention: MorphConvention) -> Self {
+        for &name in convention.names() {
+            self.names
+                .entry(normalize(name))
+                .or_insert_with(|| (name.to_owned(), Some(convention)));
+        }
+        self
+    }
+
+    /// Adds an alias.
+    ///
+    /// If the canonical name belongs to a convention already added, the
+    /// alias is mapped to the convention.
+    /// Existing entry for the alias is overwritten.
+    pub fn alias(mut self, alias: &str, canonical: &str) -> Self {
+        let convention = self
+            .names
+            .get(&normalize(canonical))
+            .and_then(|(_, convention)| *convention);
+        self.names
+            .insert(normalize(alias), (canonical.to_owned(), convention));
+        self
+    }
+
+    /// Returns the canonical name for the given name, if available.
+    pub fn canonical_name(&self, name: &str) -> Option<&str> {
+        self.names
+            .get(&normalize(name))
+            .map(|(canonical, _)| canonical.as_str())
+    }
+
+    /// Returns the convention of the given name, if available.
+    pub fn convention(&self, name: &str) -> Option<MorphConvention> {
+        self.names
+            .get(&normalize(name))
+            .and_then(|(_, convention)| *convention)
+    }
+
+    /// Returns the normalized morph targets of the given blend shape
+    /// channels, in the given order.
+    ///
+    /// If multiple channels are mapped to the same canonical name, only the
+    /// first one gets the canonical name and the others are warned.
+    ///
+    /// ```
+    /// # #[cfg(feature = "edit")]
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// use fbxcel_dom::v7400::{
+    ///     data::morph::{MorphConvention, MorphNameMap},
+    ///     edit::DocumentBuilder,
+    /// };
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let mesh = builder.add_mesh("Face", &[[0.0; 3]; 3], &[&[0, 1, 2]], None);
+    /// let edit = builder.edit_mut();
+    /// let blendshape = edit.add_object("Deformer", "Face", "Deformer", "BlendShape");
+    /// edit.connect(blendshape, mesh);
+    /// for &name in &["JawOpen", "Face:jaw_open", "Smirk"] {
+    ///     let channel = edit.add_object("Deformer", name, "SubDeformer", "BlendShapeChannel");
+    ///     edit.connect(channel, blendshape);
+    /// }
+    /// let doc = builder.build()?;
+    ///
+    /// let map = MorphNameMap::new().with_convention(MorphConvention::ArKit);
+    /// let channels = doc
+    ///     .meshes()
+    ///     .flat_map(|mesh| mesh.blendshapes())
+    ///     .flat_map(|blendshape| blendshape.blendshape_channels());
+    /// let names: Vec<_> = map
+    ///     .morph_targets(channels)
+    ///     .iter()
+    ///     .map(|target| target.normalized_name().to_owned())
+    ///     .collect();
+    /// assert_eq!(names, ["jawOpen", "Face:jaw_open", "Smirk"]);
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "edit"))]
+    /// # fn main() {}
+    /// ```
+    pub fn morph_targets<'a>(
+        &self,
+        channels: impl IntoIterator<Item = BlendShapeChannelHandle<'a>>,
+    ) -> Vec<MorphTarget<'a>> {
+        let mut used = HashSet::new();
+        channels
+            .into_iter()
+            .map(|channel| {
+                let name = channel.name().unwrap_or_default();
+                let mut canonical = self.names.get(&normalize(name)).cloned();
+                if let Some((canonical_name, _)) = &canonical {
+                    if !used.insert(canonical_name.clone()) {
+                        warn!(
+                            "Multiple blend shape channels are mapped to the same morph target \
+                             {:?}: object_id={:?}, name={:?}",
+                            canonical_name,
+                            channel.object_id(),
+                            name
+                        );
+                        canonical = None;
+                    }
+                }
+                let (canonical, convention) = match canonical {
+                    Some((canonical, convention)) => (Some(canonical), convention),
+                    None => (None, None),
+                };
+                MorphTarget {
+                    channel,
+                    name,
+                    canonical,
+                    convention,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Blend shape channel with the normalized name.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct MorphTarget<'a> {
+    /// Blend shape channel.
+    pub channel: BlendShapeChannelHandle<'a>,
+    /// Original name of the channel.
+    pub name: &'a str,
+    /// Canonical name, if the name is known.
+    pub canonical: Option<String>,
+    /// Convention of the canonical name, if available.
+    pub convention: Option<MorphConvention>,
+}
+
+impl MorphTarget<'_> {
+    /// Returns the canonical name if available, or the original name.
+    pub fn normalized_name(&self) -> &str {
+        self.canonical.as_deref().unwrap_or(self.name)
+    }
+}
+
+/// Returns the normalized name used as a table key.
+fn normalize(name: &str) -> String {
+    let name = name.rsplit(|c| c == ':' || c == '.').next().unwrap_or(name);
+
+    let mut words: Vec<String> = Vec::new();
+    let mut prev: Option<char> = None;
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            prev = None;
+            continue;
+        }
+        let boundary = match prev {
+            None => true,
+            Some(p) => c.is_uppercase() && (p.is_lowercase() || p.is_numeric()),
+        };
+        if boundary {
+            words.push(String::new());
+        }
+        if let Some(word) = words.last_mut() {
+            word.extend(c.to_lowercase());
+        }
+        prev = Some(c);
+    }
+
+    if words.len() > 1 && words[0] == "viseme" {
+        words.remove(0);
+    }
+    words
+        .iter()
+        .map(|word| match word.as_str() {
+            "l" => "left",
+            "r" => "right",
+            word => word,
+        })
+        .collect()
+}
@@ -0,0 +1,111 @@
+//! Scalar types of the output data.
+//!
+//! FBX data usually stores coordinates as `f64`, but many applications only
+//! need `f32`.
+//! Some APIs take the scalar type as a parameter, and convert the values
+//! once during extraction.
+//!
+//! With `glam` feature, transforms, bounding boxes, and skinning matrices
+//! can also be returned as `glam` types (`*_glam()` methods).
+
+use mint::{ColumnMatrix4, Point2, Point3, Vector3};
+
+/// Scalar type of the output data.
+///
+/// This is implemented for `f32` and `f64`.
+pub trait Scalar: private::Sealed + Copy + Default + PartialEq + std::fmt::Debug {
+    /// Converts the `f64` value.
+    #[doc(hidden)]
+    fn from_f64(v: f64) -> Self;
+}
+
+/// Sealed trait.
+mod private {
+    /// Sealed trait.
+    pub trait Sealed {}
+
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+impl Scalar for f32 {
+    fn from_f64(v: f64) -> Self {
+        v as f32
+    }
+}
+
+impl Scalar for f64 {
+    fn from_f64(v: f64) -> Self {
+        v
+    }
+}
+
+/// Converts the `f64` point.
+pub(crate) fn point2<T: Scalar>(v: Point2<f64>) -> Point2<T> {
+    Point2 {
+        x: T::from_f64(v.x),
+        y: T::from_f64(v.y),
+    }
+}
+
+/// Converts the `f64` point.
+pub(crate) fn point3<T: Scalar>(v: Point3<f64>) -> Point3<T> {
+    Point3 {
+        x: T::from_f64(v.x),
+        y: T::from_f64(v.y),
+        z: T::from_f64(v.z),
+    }
+}
+
+/// Converts the `f64` vector.
+pub(crate) fn vector3<T: Scalar>(v: Vector3<f64>) -> Vector3<T> {
+    Vector3 {
+        x: T::from_f64(v.x),
+        y: T::from_f64(v.y),
+        z: T::from_f64(v.z),
+    }
+}
+
+/// Converts the `f64` array.
+pub(crate) fn array4<T: Scalar>(v: [f64; 4]) -> [T; 4] {
+    [
+        T::from_f64(v[0]),
+        T::from_f64(v[1]),
+        T::from_f64(v[2]),
+        T::from_f64(v[3]),
+    ]
+}
+
+/// Converts the `f64` matrix.
+pub(crate) fn matrix4<T: Scalar>(m: ColumnMatrix4<f64>) -> ColumnMatrix4<T> {
+    let m: [[f64; 4]; 4] = m.into();
+    ColumnMatrix4::from([array4(m[0]), array4(m[1]), array4(m[2]), array4(m[3])])
+}
+
+/// Converts the `f64` matrix into `glam::Mat4`.
+#[cfg(feature = "glam")]
+pub(crate) fn glam_mat4(m: ColumnMatrix4<f64>) -> glam::Mat4 {
+    let cols: [[f32; 4]; 4] = matrix4(m).into();
+    glam::Mat4::from_cols_array_2d(&cols)
+}
+
+/// Converts the `f64` matrix into `glam::Affine3A`.
+///
+/// The last row of the matrix is ignored.
+#[cfg(feature = "glam")]
+pub(crate) fn glam_affine3a(m: ColumnMatrix4<f64>) -> glam::Affine3A {
+    glam::Affine3A::from_mat4(glam_mat4(m))
+}
+
+/// Converts the `f64` vector or point into `glam::Vec3`.
+#[cfg(feature = "glam")]
+pub(crate) fn glam_vec3(v: impl Into<[f64; 3]>) -> glam::Vec3 {
+    let [x, y, z] = v.into();
+    glam::Vec3::new(x as f32, y as f32, z as f32)
+}
+
+/// Converts the `f64` quaternion into `glam::Quat`.
+#[cfg(feature = "glam")]
+pub(crate) fn glam_quat(q: mint::Quaternion<f64>) -> glam::Quat {
+    glam::Quat::from_xyzw(q.v.x as f32, q.v.y as f32, q.v.z as f32, q.s as f32)
+}
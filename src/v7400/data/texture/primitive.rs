@@ -4,7 +4,10 @@ use std::convert::TryFrom;
 
 use anyhow::{bail, Error};
 
-use crate::v7400::object::property::{loaders::PrimitiveLoader, LoadProperty, PropertyHandle};
+use crate::v7400::{
+    object::property::{loaders::PrimitiveLoader, LoadProperty, PropertyHandle},
+    Error as DomError,
+};
 
 /// Texture wrap mode.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -40,11 +43,14 @@ impl<'a> LoadProperty<'a> for WrapModeLoader {
     }
 
     fn load(self, node: &PropertyHandle<'a>) -> Result<Self::Value, Self::Error> {
-        if node.data_type()? != "enum" {
-            bail!(
-                "Unexpected data type: expected \"enum\", but got {:?}",
-                node.data_type()
-            );
+        let data_type = node.data_type()?;
+        if data_type != "enum" {
+            return Err(DomError::type_mismatch(
+                node.error_location(),
+                "`enum` data type",
+                data_type,
+            )
+            .into());
         }
         node.load_value(PrimitiveLoader::<i32>::new())
             .and_then(TryFrom::try_from)
@@ -97,11 +103,14 @@ impl<'a> LoadProperty<'a> for BlendModeLoader {
     }
 
     fn load(self, node: &PropertyHandle<'a>) -> Result<Self::Value, Self::Error> {
-        if node.data_type()? != "enum" {
-            bail!(
-                "Unexpected data type: expected \"enum\", but got {:?}",
-                node.data_type()
-            );
+        let data_type = node.data_type()?;
+        if data_type != "enum" {
+            return Err(DomError::type_mismatch(
+                node.error_location(),
+                "`enum` data type",
+                data_type,
+            )
+            .into());
         }
         node.load_value(PrimitiveLoader::<i32>::new())
             .and_then(TryFrom::try_from)
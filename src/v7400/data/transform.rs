@@ -0,0 +1,21 @@
+//! Transform data.
+
+pub(crate) use self::{
+    axis::AxisSystem,
+    primitive::{InheritTypeLoader, RotationOrderLoader},
+};
+pub use self::{
+    bake::{bake_pivots, BakedTransform},
+    cache::TransformCache,
+    euler::EulerRotation,
+    local::LocalTransform,
+    primitive::{InheritType, RotationOrder},
+};
+
+mod axis;
+mod bake;
+mod cache;
+mod euler;
+mod local;
+pub(crate) mod math;
+mod primitive;
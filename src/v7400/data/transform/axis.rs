@@ -0,0 +1,86 @@
+//! Axis system.
+
+use fbxcel::{low::v7400::AttributeValue, tree::v7400::Tree};
+
+use crate::v7400::data::transform::math::Mat4;
+
+/// Axis system and unit in `GlobalSettings`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct AxisSystem {
+    /// Up axis index and sign.
+    up: (i32, i32),
+    /// Front axis index and sign.
+    front: (i32, i32),
+    /// Coord (right) axis index and sign.
+    coord: (i32, i32),
+    /// Centimeters per unit.
+    pub(crate) unit_scale: f64,
+}
+
+impl AxisSystem {
+    /// Loads the axis system from the tree, using Y-up right-handed
+    /// centimeter for missing values.
+    pub(crate) fn from_tree(tree: &Tree) -> Self {
+        let props: Vec<_> = tree
+            .root()
+            .first_child_by_name("GlobalSettings")
+            .and_then(|node| node.first_child_by_name("Properties70"))
+            .into_iter()
+            .flat_map(|node| node.children_by_name("P"))
+            .collect();
+        let get = |name: &str| {
+            props
+                .iter()
+                .find(|p| p.attributes().first().and_then(AttributeValue::get_string) == Some(name))
+                .and_then(|p| p.attributes().get(4))
+        };
+        let int = |name: &str, default: i32| {
+            get(name)
+                .and_then(AttributeValue::get_i32)
+                .unwrap_or(default)
+        };
+        Self {
+            up: (int("UpAxis", 1), int("UpAxisSign", 1)),
+            front: (int("FrontAxis", 2), int("FrontAxisSign", 1)),
+            coord: (int("CoordAxis", 0), int("CoordAxisSign", 1)),
+            unit_scale: get("UnitScaleFactor")
+                .and_then(AttributeValue::get_f64)
+                .unwrap_or(1.0),
+        }
+    }
+
+    /// Creates an axis system from the up, front, and coord axes (index and
+    /// sign) and the unit scale.
+    pub(crate) fn new(
+        up: (i32, i32),
+        front: (i32, i32),
+        coord: (i32, i32),
+        unit_scale: f64,
+    ) -> Self {
+        Self {
+            up,
+            front,
+            coord,
+            unit_scale,
+        }
+    }
+
+    /// Returns the up axis index and sign.
+    #[cfg(feature = "usd")]
+    pub(crate) fn up_axis(&self) -> (i32, i32) {
+        self.up
+    }
+
+    /// Returns the matrix whose columns are the coord, up and front axes.
+    pub(crate) fn matrix(&self) -> Option<Mat4> {
+        let mut m = Mat4::IDENTITY;
+        for (col, &(axis, sign)) in [self.coord, self.up, self.front].iter().enumerate() {
+            if !(0..3).contains(&axis) {
+                return None;
+            }
+            m.0[col] = [0.0; 4];
+            m.0[col][axis as usize] = if sign < 0 { -1.0 } else { 1.0 };
+        }
+        Some(m)
+    }
+}
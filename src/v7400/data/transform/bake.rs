@@ -0,0 +1,95 @@
+//! Pivot baking.
+
+use mint::{ColumnMatrix4, Vector3};
+
+use crate::v7400::data::transform::{math::Mat4, EulerRotation, LocalTransform};
+
+/// Plain translation-rotation-scaling transform.
+///
+/// This is created by [`bake_pivots`].
+///
+/// [`bake_pivots`]: fn.bake_pivots.html
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BakedTransform {
+    /// Translation.
+    pub translation: Vector3<f64>,
+    /// Rotation, with pre-rotation and post-rotation folded in.
+    pub rotation: EulerRotation,
+    /// Scaling.
+    pub scaling: Vector3<f64>,
+    /// Residual transform which could not be represented by the plain TRS.
+    ///
+    /// The original local transform is `T * R * S * residual`.
+    /// This is (almost) identity unless the rotation is degenerate (for
+    /// example, the rotation is not orthonormal due to broken data).
+    pub residual: ColumnMatrix4<f64>,
+}
+
+impl BakedTransform {
+    /// Returns the matrix of the plain TRS part (`T * R * S`).
+    pub fn to_matrix(&self) -> ColumnMatrix4<f64> {
+        self.trs_matrix().into()
+    }
+
+    /// Returns the plain TRS part as `glam` scaling, rotation, and
+    /// translation.
+    ///
+    /// The order of the values is the same as
+    /// `glam::Affine3A::from_scale_rotation_translation()`.
+    #[cfg(feature = "glam")]
+    pub fn to_glam_scale_rotation_translation(&self) -> (glam::Vec3, glam::Quat, glam::Vec3) {
+        use crate::v7400::data::scalar;
+
+        (
+            scalar::glam_vec3(self.scaling),
+            self.rotation.to_glam_quat(),
+            scalar::glam_vec3(self.translation),
+        )
+    }
+
+    /// Returns the matrix of the plain TRS part.
+    fn trs_matrix(&self) -> Mat4 {
+        Mat4::from_translation(self.translation)
+            * self.rotation.matrix()
+            * Mat4::from_scaling(self.scaling)
+    }
+
+    /// Returns the geometric transform with the residual pushed into it.
+    ///
+    /// Note that the residual would affect child models in the original
+    /// transform, but geometric transform does not.
+    /// This is exact only when the residual is identity or the model has no
+    /// children.
+    pub fn geometric_with_residual(&self, geometric: ColumnMatrix4<f64>) -> ColumnMatrix4<f64> {
+        (Mat4::from(self.residual) * Mat4::from(geometric)).into()
+    }
+}
+
+/// Converts a local transform with pivots and offsets into a plain TRS.
+///
+/// For static scenes, rotation/scaling pivots and offsets only affect the
+/// translation, and pre/post rotations can be folded into the rotation, so
+/// the local transform `T * Roff * Rp * Rpre * R * Rpost^-1 * Rp^-1 * Soff *
+/// Sp * S * Sp^-1` is converted to `T' * R' * S`.
+/// This matches the behavior of the FBX SDK's
+/// `FbxNode::ConvertPivotAnimationRecursive` for static scenes.
+///
+/// The rotation is decomposed in the effective rotation order of the model.
+pub fn bake_pivots(local: &LocalTransform) -> BakedTransform {
+    let matrix = local.matrix();
+    let rotation_matrix = local.rotation_matrix();
+    let rotation = EulerRotation::new(
+        rotation_matrix.euler_degrees(local.euler_rotation().order),
+        local.euler_rotation().order,
+    );
+    let mut baked = BakedTransform {
+        translation: matrix.translation(),
+        rotation,
+        scaling: local.scaling,
+        residual: Mat4::IDENTITY.into(),
+    };
+    if let Some(trs_inv) = baked.trs_matrix().inverse() {
+        baked.residual = (trs_inv * matrix).into();
+    }
+    baked
+}
@@ -0,0 +1,145 @@
+//! Global transform cache.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Error};
+use mint::{ColumnMatrix4, Vector3};
+
+use crate::v7400::{
+    data::{
+        scalar,
+        transform::{math::Mat4, InheritType, LocalTransform},
+    },
+    object::{model::ModelHandle, ObjectId},
+};
+
+/// Cache of global (world) transforms of models.
+///
+/// Computing the global transform of a model requires the global transforms
+/// of all its ancestors.
+/// When querying many models in the same scene, use this cache to share the
+/// computation.
+///
+/// The cache is keyed by object IDs, so it should be used only with a single
+/// document.
+#[derive(Default, Debug, Clone)]
+pub struct TransformCache {
+    /// Global transforms and local scalings by object ID.
+    globals: HashMap<ObjectId, (Mat4, Vector3<f64>)>,
+}
+
+impl TransformCache {
+    /// Creates a new empty `TransformCache`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes all cached transforms.
+    pub fn clear(&mut self) {
+        self.globals.clear();
+    }
+
+    /// Returns the global transform of the given model.
+    pub fn global_transform(
+        &mut self,
+        model: &ModelHandle<'_>,
+    ) -> Result<ColumnMatrix4<f64>, Error> {
+        self.global(model).map(Into::into)
+    }
+
+    /// Returns the global transform of the given model, converted to `f32`.
+    ///
+    /// Transforms are composed and cached in `f64`.
+    pub fn global_transform_f32(
+        &mut self,
+        model: &ModelHandle<'_>,
+    ) -> Result<ColumnMatrix4<f32>, Error> {
+        self.global_transform(model).map(scalar::matrix4)
+    }
+
+    /// Returns the global transform of the given model as `glam::Affine3A`.
+    ///
+    /// Transforms are composed and cached in `f64`.
+    #[cfg(feature = "glam")]
+    pub fn global_transform_glam(
+        &mut self,
+        model: &ModelHandle<'_>,
+    ) -> Result<glam::Affine3A, Error> {
+        self.global_transform(model).map(scalar::glam_affine3a)
+    }
+
+    /// Returns the global transform matrix of the given model.
+    pub(crate) fn global(&mut self, model: &ModelHandle<'_>) -> Result<Mat4, Error> {
+        if let Some((global, _)) = self.globals.get(&model.object_id()) {
+            return Ok(*global);
+        }
+
+        // Collect uncached ancestors, from the model to the root.
+        let mut chain = vec![*model];
+        let mut parent_entry = None;
+        while let Some(parent) = chain[chain.len() - 1].parent_model() {
+            if let Some(&entry) = self.globals.get(&parent.object_id()) {
+                parent_entry = Some(entry);
+                break;
+            }
+            if chain.iter().any(|m| m.object_id() == parent.object_id()) {
+                bail!(
+                    "Cyclic model hierarchy detected: object_id={:?}",
+                    parent.object_id()
+                );
+            }
+            chain.push(*parent);
+        }
+
+        for model in chain.iter().rev() {
+            let local = model.local_transform()?;
+            let inherit_type = model.properties().inherit_type_or_default()?;
+            let global = compose_global(&local, inherit_type, parent_entry);
+            let entry = (global, local.scaling);
+            self.globals.insert(model.object_id(), entry);
+            parent_entry = Some(entry);
+        }
+
+        Ok(parent_entry
+            .expect("Should never fail: at least one model is processed")
+            .0)
+    }
+}
+
+/// Computes the global transform from the local transform and the parent.
+///
+/// `parent` is the global transform and the local scaling of the parent.
+///
+/// This follows the formula used by the FBX SDK (see `CalculateGlobalTransform`
+/// in the SDK samples).
+fn compose_global(
+    local: &LocalTransform,
+    inherit_type: InheritType,
+    parent: Option<(Mat4, Vector3<f64>)>,
+) -> Mat4 {
+    let local_matrix = local.matrix();
+    let (parent_global, parent_local_scaling) = match parent {
+        Some(v) => v,
+        None => return local_matrix,
+    };
+
+    let local_rot = local.rotation_matrix();
+    let local_scaling = Mat4::from_scaling(local.scaling);
+    let parent_rot = parent_global.rotation_part();
+    // Shear and scaling of the parent.
+    let parent_scaling = parent_rot.transpose() * parent_global.without_translation();
+
+    let global_rs = match inherit_type {
+        InheritType::RrSs => parent_rot * local_rot * parent_scaling * local_scaling,
+        InheritType::RSrs => parent_rot * parent_scaling * local_rot * local_scaling,
+        InheritType::Rrs => {
+            let parent_local_scaling_inv = Mat4::from_scaling(parent_local_scaling)
+                .inverse()
+                .unwrap_or(Mat4::IDENTITY);
+            parent_rot * local_rot * parent_scaling * parent_local_scaling_inv * local_scaling
+        }
+    };
+    let translation = parent_global.transform_point(local_matrix.translation());
+
+    Mat4::from_translation(translation) * global_rs
+}
@@ -0,0 +1,188 @@
+//! Euler rotation.
+
+use mint::{ColumnMatrix4, Quaternion, Vector3};
+
+use crate::v7400::data::transform::{math::Mat4, RotationOrder};
+
+/// Euler rotation with rotation order.
+///
+/// FBX Euler angles are in degrees, and the first axis of the rotation order
+/// is applied first.
+/// For example, `EulerXyz` means `Rz * Ry * Rx` for column vectors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EulerRotation {
+    /// Rotation angles around X, Y, and Z axes in degrees.
+    pub angles: Vector3<f64>,
+    /// Rotation order.
+    pub order: RotationOrder,
+}
+
+impl EulerRotation {
+    /// Creates a new `EulerRotation`.
+    pub fn new(angles: Vector3<f64>, order: RotationOrder) -> Self {
+        Self { angles, order }
+    }
+
+    /// Decomposes the rotation matrix into Euler angles with the given
+    /// rotation order.
+    ///
+    /// The matrix should be a pure rotation matrix.
+    /// The angle of the second axis in the rotation order is in `[-90, 90]`.
+    /// At the singular points (the second angle is ±90 degrees, i.e. gimbal
+    /// lock), the whole rotation around the first and the last axes is
+    /// assigned to the first axis.
+    /// `SphericXyz` is treated as `EulerXyz`.
+    ///
+    /// ```
+    /// use fbxcel_dom::v7400::data::transform::{EulerRotation, RotationOrder};
+    ///
+    /// let orders = [
+    ///     (RotationOrder::EulerXyz, [0, 1, 2]),
+    ///     (RotationOrder::EulerXzy, [0, 2, 1]),
+    ///     (RotationOrder::EulerYzx, [1, 2, 0]),
+    ///     (RotationOrder::EulerYxz, [1, 0, 2]),
+    ///     (RotationOrder::EulerZxy, [2, 0, 1]),
+    ///     (RotationOrder::EulerZyx, [2, 1, 0]),
+    ///     (RotationOrder::SphericXyz, [0, 1, 2]),
+    /// ];
+    /// // Angles around the first, the second, and the last axes.
+    /// let cases = [
+    ///     [30.0, 45.0, -60.0],
+    ///     [170.0, -20.0, 5.0],
+    ///     // Gimbal lock.
+    ///     [30.0, 90.0, -60.0],
+    ///     [30.0, -90.0, -60.0],
+    /// ];
+    /// for &(order, axes) in &orders {
+    ///     for case in &cases {
+    ///         let mut angles = [0.0; 3];
+    ///         for (&axis, &angle) in axes.iter().zip(case) {
+    ///             angles[axis] = angle;
+    ///         }
+    ///         let rotation = EulerRotation::new(angles.into(), order);
+    ///         let decomposed = EulerRotation::from_matrix(rotation.to_matrix(), order);
+    ///         assert_eq!(decomposed.order, order);
+    ///
+    ///         // Compose again.
+    ///         let expected: [[f64; 4]; 4] = rotation.to_matrix().into();
+    ///         let actual: [[f64; 4]; 4] = decomposed.to_matrix().into();
+    ///         for (e, a) in expected.iter().flatten().zip(actual.iter().flatten()) {
+    ///             assert!((e - a).abs() < 1e-9, "{:?}: {:?}", order, case);
+    ///         }
+    ///         // Angles are preserved unless gimbal locked.
+    ///         if case[1] != 90.0 && case[1] != -90.0 {
+    ///             let actual: [f64; 3] = decomposed.angles.into();
+    ///             for (e, a) in angles.iter().zip(&actual) {
+    ///                 assert!((e - a).abs() < 1e-9, "{:?}: {:?}", order, case);
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn from_matrix(matrix: ColumnMatrix4<f64>, order: RotationOrder) -> Self {
+        Self::new(Mat4::from(matrix).euler_degrees(order), order)
+    }
+
+    /// Returns the rotation matrix.
+    ///
+    /// `SphericXyz` is treated as `EulerXyz`.
+    pub fn to_matrix(&self) -> ColumnMatrix4<f64> {
+        self.matrix().into()
+    }
+
+    /// Returns the rotation matrix.
+    pub(crate) fn matrix(&self) -> Mat4 {
+        Mat4::from_euler_degrees(self.angles, self.order)
+    }
+
+    /// Returns the rotation as a unit quaternion.
+    ///
+    /// `SphericXyz` is treated as `EulerXyz`.
+    pub fn to_quaternion(&self) -> Quaternion<f64> {
+        let x = axis_quat(self.angles.x, [1.0, 0.0, 0.0]);
+        let y = axis_quat(self.angles.y, [0.0, 1.0, 0.0]);
+        let z = axis_quat(self.angles.z, [0.0, 0.0, 1.0]);
+        match self.order {
+            RotationOrder::EulerXyz | RotationOrder::SphericXyz => mul_quat(mul_quat(z, y), x),
+            RotationOrder::EulerXzy => mul_quat(mul_quat(y, z), x),
+            RotationOrder::EulerYzx => mul_quat(mul_quat(x, z), y),
+            RotationOrder::EulerYxz => mul_quat(mul_quat(z, x), y),
+            RotationOrder::EulerZxy => mul_quat(mul_quat(y, x), z),
+            RotationOrder::EulerZyx => mul_quat(mul_quat(x, y), z),
+        }
+    }
+
+    /// Returns the rotation as `glam::Quat`.
+    ///
+    /// `SphericXyz` is treated as `EulerXyz`.
+    #[cfg(feature = "glam")]
+    pub fn to_glam_quat(&self) -> glam::Quat {
+        crate::v7400::data::scalar::glam_quat(self.to_quaternion())
+    }
+}
+
+impl From<EulerRotation> for ColumnMatrix4<f64> {
+    fn from(v: EulerRotation) -> Self {
+        v.to_matrix()
+    }
+}
+
+impl From<EulerRotation> for Quaternion<f64> {
+    fn from(v: EulerRotation) -> Self {
+        v.to_quaternion()
+    }
+}
+
+impl From<ColumnMatrix4<f64>> for EulerRotation {
+    /// Decomposes the rotation matrix with `EulerXyz` order (see
+    /// [`EulerRotation::from_matrix()`]).
+    fn from(m: ColumnMatrix4<f64>) -> Self {
+        Self::from_matrix(m, RotationOrder::EulerXyz)
+    }
+}
+
+impl From<Quaternion<f64>> for EulerRotation {
+    /// Decomposes the unit quaternion with `EulerXyz` order.
+    ///
+    /// ```
+    /// use fbxcel_dom::v7400::data::transform::{EulerRotation, RotationOrder};
+    ///
+    /// let rotation = EulerRotation::new([30.0, 45.0, -60.0].into(), RotationOrder::EulerXyz);
+    /// let decomposed = EulerRotation::from(rotation.to_quaternion());
+    /// assert_eq!(decomposed.order, RotationOrder::EulerXyz);
+    /// let angles: [f64; 3] = decomposed.angles.into();
+    /// for (e, a) in [30.0, 45.0, -60.0].iter().zip(&angles) {
+    ///     assert!((e - a).abs() < 1e-9);
+    /// }
+    /// ```
+    fn from(q: Quaternion<f64>) -> Self {
+        let order = RotationOrder::EulerXyz;
+        Self::new(Mat4::from_quaternion(q).euler_degrees(order), order)
+    }
+}
+
+/// Returns a quaternion rotating around the given axis by the given angle in
+/// degrees.
+fn axis_quat(degrees: f64, axis: [f64; 3]) -> Quaternion<f64> {
+    let (s, c) = (degrees.to_radians() * 0.5).sin_cos();
+    Quaternion {
+        v: Vector3 {
+            x: axis[0] * s,
+            y: axis[1] * s,
+            z: axis[2] * s,
+        },
+        s: c,
+    }
+}
+
+/// Returns the Hamilton product `a * b`.
+fn mul_quat(a: Quaternion<f64>, b: Quaternion<f64>) -> Quaternion<f64> {
+    Quaternion {
+        v: Vector3 {
+            x: a.s * b.v.x + a.v.x * b.s + a.v.y * b.v.z - a.v.z * b.v.y,
+            y: a.s * b.v.y - a.v.x * b.v.z + a.v.y * b.s + a.v.z * b.v.x,
+            z: a.s * b.v.z + a.v.x * b.v.y - a.v.y * b.v.x + a.v.z * b.s,
+        },
+        s: a.s * b.s - a.v.x * b.v.x - a.v.y * b.v.y - a.v.z * b.v.z,
+    }
+}
@@ -0,0 +1,234 @@
+//! Local transform.
+
+use mint::{ColumnMatrix4, Vector3};
+
+use crate::v7400::data::{
+    scalar,
+    transform::{math::Mat4, EulerRotation, RotationOrder},
+};
+
+/// Decomposed local transform of a model.
+///
+/// Rotations are Euler angles in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocalTransform {
+    /// Translation (`Lcl Translation`).
+    pub translation: Vector3<f64>,
+    /// Rotation (`Lcl Rotation`).
+    pub rotation: Vector3<f64>,
+    /// Scaling (`Lcl Scaling`).
+    pub scaling: Vector3<f64>,
+    /// Rotation order.
+    pub rotation_order: RotationOrder,
+    /// Pre-rotation.
+    pub pre_rotation: Vector3<f64>,
+    /// Post-rotation.
+    pub post_rotation: Vector3<f64>,
+    /// Rotation pivot.
+    pub rotation_pivot: Vector3<f64>,
+    /// Rotation offset.
+    pub rotation_offset: Vector3<f64>,
+    /// Scaling pivot.
+    pub scaling_pivot: Vector3<f64>,
+    /// Scaling offset.
+    pub scaling_offset: Vector3<f64>,
+    /// Whether the rotation order, pre-rotation, and post-rotation are
+    /// active.
+    pub rotation_active: bool,
+}
+
+impl Default for LocalTransform {
+    fn default() -> Self {
+        /// Zero vector.
+        const ZERO: Vector3<f64> = Vector3 {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+
+        Self {
+            translation: ZERO,
+            rotation: ZERO,
+            scaling: Vector3 {
+                x: 1.0,
+                y: 1.0,
+                z: 1.0,
+            },
+            rotation_order: RotationOrder::default(),
+            pre_rotation: ZERO,
+            post_rotation: ZERO,
+            rotation_pivot: ZERO,
+            rotation_offset: ZERO,
+            scaling_pivot: ZERO,
+            scaling_offset: ZERO,
+            rotation_active: false,
+        }
+    }
+}
+
+impl LocalTransform {
+    /// Returns the local rotation (`Lcl Rotation`) with its effective
+    /// rotation order.
+    ///
+    /// If `rotation_active` is false, `EulerXyz` is used as rotation order,
+    /// as the FBX SDK does.
+    pub fn euler_rotation(&self) -> EulerRotation {
+        let order = if self.rotation_active {
+            self.rotation_order
+        } else {
+            RotationOrder::EulerXyz
+        };
+        EulerRotation::new(self.rotation, order)
+    }
+
+    /// Returns the pre-rotation.
+    ///
+    /// Pre-rotation always uses `EulerXyz` order.
+    pub fn pre_euler_rotation(&self) -> EulerRotation {
+        EulerRotation::new(self.pre_rotation, RotationOrder::EulerXyz)
+    }
+
+    /// Returns the post-rotation.
+    ///
+    /// Post-rotation always uses `EulerXyz` order.
+    pub fn post_euler_rotation(&self) -> EulerRotation {
+        EulerRotation::new(self.post_rotation, RotationOrder::EulerXyz)
+    }
+
+    /// Returns the local rotation matrix (`Rpre * R * Rpost^-1`).
+    ///
+    /// If `rotation_active` is false, pre-rotation and post-rotation are
+    /// ignored and `EulerXyz` is used as rotation order, as the FBX SDK does.
+    pub(crate) fn rotation_matrix(&self) -> Mat4 {
+        let rot = self.euler_rotation().matrix();
+        if !self.rotation_active {
+            return rot;
+        }
+        let pre = self.pre_euler_rotation().matrix();
+        let post = self.post_euler_rotation().matrix();
+        pre * rot * post.transpose()
+    }
+
+    /// Returns the local transform matrix.
+    pub(crate) fn matrix(&self) -> Mat4 {
+        let t = Mat4::from_translation(self.translation);
+        let roff = Mat4::from_translation(self.rotation_offset);
+        let rp = Mat4::from_translation(self.rotation_pivot);
+        let rp_inv = Mat4::from_translation(neg(self.rotation_pivot));
+        let soff = Mat4::from_translation(self.scaling_offset);
+        let sp = Mat4::from_translation(self.scaling_pivot);
+        let sp_inv = Mat4::from_translation(neg(self.scaling_pivot));
+        let s = Mat4::from_scaling(self.scaling);
+        t * roff * rp * self.rotation_matrix() * rp_inv * soff * sp * s * sp_inv
+    }
+
+    /// Returns the local transform matrix relative to the parent.
+    ///
+    /// This is `T * Roff * Rp * Rpre * R * Rpost^-1 * Rp^-1 * Soff * Sp * S *
+    /// Sp^-1`.
+    /// Note that this does not take `InheritType` into account; use
+    /// [`ModelHandle::global_transform`] to get the world transform.
+    ///
+    /// [`ModelHandle::global_transform`]:
+    ///     ../../object/model/struct.ModelHandle.html#method.global_transform
+    pub fn to_matrix(&self) -> ColumnMatrix4<f64> {
+        self.matrix().into()
+    }
+
+    /// Returns the local transform matrix converted to `f32`.
+    pub fn to_matrix_f32(&self) -> ColumnMatrix4<f32> {
+        scalar::matrix4(self.to_matrix())
+    }
+
+    /// Returns the local transform matrix as `glam::Mat4`.
+    #[cfg(feature = "glam")]
+    pub fn to_glam_mat4(&self) -> glam::Mat4 {
+        scalar::glam_mat4(self.to_matrix())
+    }
+
+    /// Returns the local transform matrix as `glam::Affine3A`.
+    ///
+    /// ```
+    /// use fbxcel_dom::{glam::Vec3, v7400::data::transform::LocalTransform};
+    ///
+    /// let local = LocalTransform {
+    ///     translation: [1.0, 2.0, 3.0].into(),
+    ///     scaling: [2.0, 2.0, 2.0].into(),
+    ///     ..Default::default()
+    /// };
+    /// let affine = local.to_glam_affine3a();
+    /// assert_eq!(affine.transform_point3(Vec3::X), Vec3::new(3.0, 2.0, 3.0));
+    /// ```
+    #[cfg(feature = "glam")]
+    pub fn to_glam_affine3a(&self) -> glam::Affine3A {
+        scalar::glam_affine3a(self.to_matrix())
+    }
+}
+
+impl From<LocalTransform> for ColumnMatrix4<f64> {
+    fn from(v: LocalTransform) -> Self {
+        v.to_matrix()
+    }
+}
+
+impl From<LocalTransform> for ColumnMatrix4<f32> {
+    fn from(v: LocalTransform) -> Self {
+        v.to_matrix_f32()
+    }
+}
+
+impl From<ColumnMatrix4<f64>> for LocalTransform {
+    /// Decomposes the affine transform into translation, rotation (in
+    /// `EulerXyz` order), and scaling.
+    ///
+    /// Shear is not supported, and negative scaling is assigned to the X
+    /// axis.
+    /// Pivots, offsets, pre-rotation, and post-rotation are zero.
+    ///
+    /// ```
+    /// use fbxcel_dom::v7400::data::transform::LocalTransform;
+    ///
+    /// let local = LocalTransform {
+    ///     translation: [1.0, 2.0, 3.0].into(),
+    ///     rotation: [30.0, 45.0, -60.0].into(),
+    ///     scaling: [-2.0, 1.0, 0.5].into(),
+    ///     ..Default::default()
+    /// };
+    /// let decomposed = LocalTransform::from(local.to_matrix());
+    /// let expected: [[f64; 4]; 4] = local.to_matrix().into();
+    /// let actual: [[f64; 4]; 4] = decomposed.to_matrix().into();
+    /// for (e, a) in expected.iter().flatten().zip(actual.iter().flatten()) {
+    ///     assert!((e - a).abs() < 1e-9);
+    /// }
+    /// let translation: [f64; 3] = decomposed.translation.into();
+    /// assert_eq!(translation, [1.0, 2.0, 3.0]);
+    /// ```
+    fn from(m: ColumnMatrix4<f64>) -> Self {
+        let m = Mat4::from(m);
+        Self {
+            translation: m.translation(),
+            rotation: m.rotation_part().euler_degrees(RotationOrder::EulerXyz),
+            scaling: m.scaling(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<ColumnMatrix4<f32>> for LocalTransform {
+    /// Decomposes the affine transform (see the conversion from
+    /// `ColumnMatrix4<f64>`).
+    fn from(m: ColumnMatrix4<f32>) -> Self {
+        let m: [[f32; 4]; 4] = m.into();
+        Self::from(ColumnMatrix4::from(m.map(|col| col.map(f64::from))))
+    }
+}
+
+/// Returns the negated vector.
+fn neg(v: Vector3<f64>) -> Vector3<f64> {
+    Vector3 {
+        x: -v.x,
+        y: -v.y,
+        z: -v.z,
+    }
+}
@@ -0,0 +1,314 @@
+//! Minimal matrix math for transform computation.
+
+use mint::{ColumnMatrix4, Quaternion, Vector3};
+
+use crate::v7400::data::transform::RotationOrder;
+
+/// Column-major 4x4 matrix.
+///
+/// `self.0[col][row]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Mat4(pub(crate) [[f64; 4]; 4]);
+
+impl Mat4 {
+    /// Identity matrix.
+    pub(crate) const IDENTITY: Self = Self([
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+
+    /// Creates a translation matrix.
+    pub(crate) fn from_translation(v: Vector3<f64>) -> Self {
+        let mut m = Self::IDENTITY;
+        m.0[3] = [v.x, v.y, v.z, 1.0];
+        m
+    }
+
+    /// Creates a scaling matrix.
+    pub(crate) fn from_scaling(v: Vector3<f64>) -> Self {
+        let mut m = Self::IDENTITY;
+        m.0[0][0] = v.x;
+        m.0[1][1] = v.y;
+        m.0[2][2] = v.z;
+        m
+    }
+
+    /// Creates a rotation matrix around the X axis.
+    fn from_rotation_x(rad: f64) -> Self {
+        let (s, c) = rad.sin_cos();
+        let mut m = Self::IDENTITY;
+        m.0[1] = [0.0, c, s, 0.0];
+        m.0[2] = [0.0, -s, c, 0.0];
+        m
+    }
+
+    /// Creates a rotation matrix around the Y axis.
+    fn from_rotation_y(rad: f64) -> Self {
+        let (s, c) = rad.sin_cos();
+        let mut m = Self::IDENTITY;
+        m.0[0] = [c, 0.0, -s, 0.0];
+        m.0[2] = [s, 0.0, c, 0.0];
+        m
+    }
+
+    /// Creates a rotation matrix around the Z axis.
+    fn from_rotation_z(rad: f64) -> Self {
+        let (s, c) = rad.sin_cos();
+        let mut m = Self::IDENTITY;
+        m.0[0] = [c, s, 0.0, 0.0];
+        m.0[1] = [-s, c, 0.0, 0.0];
+        m
+    }
+
+    /// Creates a rotation matrix from the unit quaternion.
+    pub(crate) fn from_quaternion(q: Quaternion<f64>) -> Self {
+        let (x, y, z, w) = (q.v.x, q.v.y, q.v.z, q.s);
+        let mut m = Self::IDENTITY;
+        m.0[0] = [
+            1.0 - 2.0 * (y * y + z * z),
+            2.0 * (x * y + z * w),
+            2.0 * (x * z - y * w),
+            0.0,
+        ];
+        m.0[1] = [
+            2.0 * (x * y - z * w),
+            1.0 - 2.0 * (x * x + z * z),
+            2.0 * (y * z + x * w),
+            0.0,
+        ];
+        m.0[2] = [
+            2.0 * (x * z + y * w),
+            2.0 * (y * z - x * w),
+            1.0 - 2.0 * (x * x + y * y),
+            0.0,
+        ];
+        m
+    }
+
+    /// Creates a rotation matrix from Euler angles in degrees.
+    ///
+    /// For `EulerXyz`, the rotation around X is applied first, i.e. the
+    /// result is `Rz * Ry * Rx`.
+    pub(crate) fn from_euler_degrees(v: Vector3<f64>, order: RotationOrder) -> Self {
+        let x = Self::from_rotation_x(v.x.to_radians());
+        let y = Self::from_rotation_y(v.y.to_radians());
+        let z = Self::from_rotation_z(v.z.to_radians());
+        match order {
+            RotationOrder::EulerXyz | RotationOrder::SphericXyz => z * y * x,
+            RotationOrder::EulerXzy => y * z * x,
+            RotationOrder::EulerYzx => x * z * y,
+            RotationOrder::EulerYxz => z * x * y,
+            RotationOrder::EulerZxy => y * x * z,
+            RotationOrder::EulerZyx => x * y * z,
+        }
+    }
+
+    /// Returns the translation part.
+    pub(crate) fn translation(&self) -> Vector3<f64> {
+        Vector3 {
+            x: self.0[3][0],
+            y: self.0[3][1],
+            z: self.0[3][2],
+        }
+    }
+
+    /// Returns the matrix without translation.
+    pub(crate) fn without_translation(&self) -> Self {
+        let mut m = *self;
+        m.0[3] = [0.0, 0.0, 0.0, 1.0];
+        m
+    }
+
+    /// Returns the scaling part of the affine transform.
+    ///
+    /// Shear is not supported, and negative scaling is assigned to the X
+    /// axis.
+    pub(crate) fn scaling(&self) -> Vector3<f64> {
+        let mut scale = [0.0; 3];
+        for (col, s) in scale.iter_mut().enumerate() {
+            let c = &self.0[col];
+            *s = (c[0] * c[0] + c[1] * c[1] + c[2] * c[2]).sqrt();
+        }
+        if self.determinant() < 0.0 {
+            scale[0] = -scale[0];
+        }
+        Vector3::from(scale)
+    }
+
+    /// Returns the rotation part of the affine transform.
+    ///
+    /// Shear is not supported, and negative scaling is assigned to the X
+    /// axis.
+    pub(crate) fn rotation_part(&self) -> Self {
+        let mut m = Self::IDENTITY;
+        for col in 0..3 {
+            let c = &self.0[col];
+            let len = (c[0] * c[0] + c[1] * c[1] + c[2] * c[2]).sqrt();
+            if len > 0.0 {
+                m.0[col][0] = c[0] / len;
+                m.0[col][1] = c[1] / len;
+                m.0[col][2] = c[2] / len;
+            }
+        }
+        if m.determinant() < 0.0 {
+            for v in &mut m.0[0][0..3] {
+                *v = -*v;
+            }
+        }
+        m
+    }
+
+    /// Returns the transposed matrix.
+    pub(crate) fn transpose(&self) -> Self {
+        let mut m = Self::IDENTITY;
+        for (col, m_col) in m.0.iter_mut().enumerate() {
+            for (row, v) in m_col.iter_mut().enumerate() {
+                *v = self.0[row][col];
+            }
+        }
+        m
+    }
+
+    /// Returns the determinant.
+    pub(crate) fn determinant(&self) -> f64 {
+        let m = &self.0;
+        let s0 = m[0][0] * m[1][1] - m[1][0] * m[0][1];
+        let s1 = m[0][0] * m[1][2] - m[1][0] * m[0][2];
+        let s2 = m[0][0] * m[1][3] - m[1][0] * m[0][3];
+        let s3 = m[0][1] * m[1][2] - m[1][1] * m[0][2];
+        let s4 = m[0][1] * m[1][3] - m[1][1] * m[0][3];
+        let s5 = m[0][2] * m[1][3] - m[1][2] * m[0][3];
+        let c5 = m[2][2] * m[3][3] - m[3][2] * m[2][3];
+        let c4 = m[2][1] * m[3][3] - m[3][1] * m[2][3];
+        let c3 = m[2][1] * m[3][2] - m[3][1] * m[2][2];
+        let c2 = m[2][0] * m[3][3] - m[3][0] * m[2][3];
+        let c1 = m[2][0] * m[3][2] - m[3][0] * m[2][2];
+        let c0 = m[2][0] * m[3][1] - m[3][0] * m[2][1];
+        s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0
+    }
+
+    /// Returns the inverse matrix, or `None` if the matrix is singular.
+    pub(crate) fn inverse(&self) -> Option<Self> {
+        let m = &self.0;
+        let s0 = m[0][0] * m[1][1] - m[1][0] * m[0][1];
+        let s1 = m[0][0] * m[1][2] - m[1][0] * m[0][2];
+        let s2 = m[0][0] * m[1][3] - m[1][0] * m[0][3];
+        let s3 = m[0][1] * m[1][2] - m[1][1] * m[0][2];
+        let s4 = m[0][1] * m[1][3] - m[1][1] * m[0][3];
+        let s5 = m[0][2] * m[1][3] - m[1][2] * m[0][3];
+        let c5 = m[2][2] * m[3][3] - m[3][2] * m[2][3];
+        let c4 = m[2][1] * m[3][3] - m[3][1] * m[2][3];
+        let c3 = m[2][1] * m[3][2] - m[3][1] * m[2][2];
+        let c2 = m[2][0] * m[3][3] - m[3][0] * m[2][3];
+        let c1 = m[2][0] * m[3][2] - m[3][0] * m[2][2];
+        let c0 = m[2][0] * m[3][1] - m[3][0] * m[2][1];
+        let det = s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let inv = 1.0 / det;
+        Some(Self([
+            [
+                (m[1][1] * c5 - m[1][2] * c4 + m[1][3] * c3) * inv,
+                (-m[0][1] * c5 + m[0][2] * c4 - m[0][3] * c3) * inv,
+                (m[3][1] * s5 - m[3][2] * s4 + m[3][3] * s3) * inv,
+                (-m[2][1] * s5 + m[2][2] * s4 - m[2][3] * s3) * inv,
+            ],
+            [
+                (-m[1][0] * c5 + m[1][2] * c2 - m[1][3] * c1) * inv,
+                (m[0][0] * c5 - m[0][2] * c2 + m[0][3] * c1) * inv,
+                (-m[3][0] * s5 + m[3][2] * s2 - m[3][3] * s1) * inv,
+                (m[2][0] * s5 - m[2][2] * s2 + m[2][3] * s1) * inv,
+            ],
+            [
+                (m[1][0] * c4 - m[1][1] * c2 + m[1][3] * c0) * inv,
+                (-m[0][0] * c4 + m[0][1] * c2 - m[0][3] * c0) * inv,
+                (m[3][0] * s4 - m[3][1] * s2 + m[3][3] * s0) * inv,
+                (-m[2][0] * s4 + m[2][1] * s2 - m[2][3] * s0) * inv,
+            ],
+            [
+                (-m[1][0] * c3 + m[1][1] * c1 - m[1][2] * c0) * inv,
+                (m[0][0] * c3 - m[0][1] * c1 + m[0][2] * c0) * inv,
+                (-m[3][0] * s3 + m[3][1] * s1 - m[3][2] * s0) * inv,
+                (m[2][0] * s3 - m[2][1] * s1 + m[2][2] * s0) * inv,
+            ],
+        ]))
+    }
+
+    /// Transforms the given point.
+    pub(crate) fn transform_point(&self, p: Vector3<f64>) -> Vector3<f64> {
+        let m = &self.0;
+        Vector3 {
+            x: m[0][0] * p.x + m[1][0] * p.y + m[2][0] * p.z + m[3][0],
+            y: m[0][1] * p.x + m[1][1] * p.y + m[2][1] * p.z + m[3][1],
+            z: m[0][2] * p.x + m[1][2] * p.y + m[2][2] * p.z + m[3][2],
+        }
+    }
+}
+
+impl std::ops::Mul for Mat4 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let mut out = [[0.0; 4]; 4];
+        for (col, out_col) in out.iter_mut().enumerate() {
+            for (row, v) in out_col.iter_mut().enumerate() {
+                *v = (0..4).map(|k| self.0[k][row] * rhs.0[col][k]).sum();
+            }
+        }
+        Self(out)
+    }
+}
+
+impl From<Mat4> for ColumnMatrix4<f64> {
+    fn from(m: Mat4) -> Self {
+        ColumnMatrix4::from(m.0)
+    }
+}
+
+impl From<ColumnMatrix4<f64>> for Mat4 {
+    fn from(m: ColumnMatrix4<f64>) -> Self {
+        Self(m.into())
+    }
+}
+
+impl Mat4 {
+    /// Decomposes the rotation matrix into Euler angles in degrees.
+    ///
+    /// `SphericXyz` is treated as `EulerXyz`.
+    /// The matrix should be a pure rotation matrix.
+    pub(crate) fn euler_degrees(&self, order: RotationOrder) -> Vector3<f64> {
+        // Axis indices in the order of application, and parity.
+        let (a, b, c, parity) = match order {
+            RotationOrder::EulerXyz | RotationOrder::SphericXyz => (0, 1, 2, 1.0),
+            RotationOrder::EulerYzx => (1, 2, 0, 1.0),
+            RotationOrder::EulerZxy => (2, 0, 1, 1.0),
+            RotationOrder::EulerXzy => (0, 2, 1, -1.0),
+            RotationOrder::EulerYxz => (1, 0, 2, -1.0),
+            RotationOrder::EulerZyx => (2, 1, 0, -1.0),
+        };
+        // Row-major accessor.
+        let r = |row: usize, col: usize| self.0[col][row];
+
+        let sin_b = (-parity * r(c, a)).clamp(-1.0, 1.0);
+        let angle_b = sin_b.asin();
+        let (angle_a, angle_c) = if sin_b.abs() < 1.0 - 1e-12 {
+            (
+                (parity * r(c, b)).atan2(r(c, c)),
+                (parity * r(b, a)).atan2(r(a, a)),
+            )
+        } else {
+            // Gimbal lock: only the sum or difference of `a` and `c` is
+            // determined, so assign all of it to `a`.
+            ((-parity * r(b, c)).atan2(r(b, b)), 0.0)
+        };
+
+        let mut angles = [0.0; 3];
+        angles[a] = angle_a.to_degrees();
+        angles[b] = angle_b.to_degrees();
+        angles[c] = angle_c.to_degrees();
+        Vector3::from(angles)
+    }
+}
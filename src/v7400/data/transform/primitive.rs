@@ -0,0 +1,144 @@
+//! Primitive types.
+
+use std::convert::TryFrom;
+
+use anyhow::{bail, Error};
+
+use crate::v7400::{
+    object::property::{loaders::PrimitiveLoader, LoadProperty, PropertyHandle},
+    Error as DomError,
+};
+
+/// Euler rotation order.
+///
+/// See
+/// <http://help.autodesk.com/cloudhelp/2019/ENU/FBX-Developer-Help/cpp_ref/fbxmath_8h.html>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum RotationOrder {
+    /// X, then Y, then Z.
+    EulerXyz,
+    /// X, then Z, then Y.
+    EulerXzy,
+    /// Y, then Z, then X.
+    EulerYzx,
+    /// Y, then X, then Z.
+    EulerYxz,
+    /// Z, then X, then Y.
+    EulerZxy,
+    /// Z, then Y, then X.
+    EulerZyx,
+    /// Spheric XYZ.
+    SphericXyz,
+}
+
+impl Default for RotationOrder {
+    fn default() -> Self {
+        RotationOrder::EulerXyz
+    }
+}
+
+impl TryFrom<i32> for RotationOrder {
+    type Error = Error;
+
+    fn try_from(v: i32) -> Result<Self, Self::Error> {
+        match v {
+            0 => Ok(RotationOrder::EulerXyz),
+            1 => Ok(RotationOrder::EulerXzy),
+            2 => Ok(RotationOrder::EulerYzx),
+            3 => Ok(RotationOrder::EulerYxz),
+            4 => Ok(RotationOrder::EulerZxy),
+            5 => Ok(RotationOrder::EulerZyx),
+            6 => Ok(RotationOrder::SphericXyz),
+            v => bail!("Unexpected `RotationOrder` value: {:?}", v),
+        }
+    }
+}
+
+/// `RotationOrder` property loader.
+#[derive(Default, Debug, Clone, Copy)]
+pub(crate) struct RotationOrderLoader;
+
+impl<'a> LoadProperty<'a> for RotationOrderLoader {
+    type Value = RotationOrder;
+    type Error = Error;
+
+    fn expecting(&self) -> String {
+        "`i32` value as rotation order".into()
+    }
+
+    fn load(self, node: &PropertyHandle<'a>) -> Result<Self::Value, Self::Error> {
+        let data_type = node.data_type()?;
+        if data_type != "enum" {
+            return Err(DomError::type_mismatch(
+                node.error_location(),
+                "`enum` data type",
+                data_type,
+            )
+            .into());
+        }
+        node.load_value(PrimitiveLoader::<i32>::new())
+            .and_then(TryFrom::try_from)
+    }
+}
+
+/// Transform inheritance type.
+///
+/// `r` and `s` are parent rotation and scaling, `R` and `S` are local
+/// rotation and scaling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum InheritType {
+    /// Parent rotation, local rotation, parent scaling, local scaling.
+    RrSs,
+    /// Parent rotation and scaling, then local rotation and scaling.
+    RSrs,
+    /// Parent rotation, local rotation, local scaling (parent scaling is
+    /// ignored).
+    Rrs,
+}
+
+impl Default for InheritType {
+    fn default() -> Self {
+        InheritType::RSrs
+    }
+}
+
+impl TryFrom<i32> for InheritType {
+    type Error = Error;
+
+    fn try_from(v: i32) -> Result<Self, Self::Error> {
+        match v {
+            0 => Ok(InheritType::RrSs),
+            1 => Ok(InheritType::RSrs),
+            2 => Ok(InheritType::Rrs),
+            v => bail!("Unexpected `InheritType` value: {:?}", v),
+        }
+    }
+}
+
+/// `InheritType` property loader.
+#[derive(Default, Debug, Clone, Copy)]
+pub(crate) struct InheritTypeLoader;
+
+impl<'a> LoadProperty<'a> for InheritTypeLoader {
+    type Value = InheritType;
+    type Error = Error;
+
+    fn expecting(&self) -> String {
+        "`i32` value as inherit type".into()
+    }
+
+    fn load(self, node: &PropertyHandle<'a>) -> Result<Self::Value, Self::Error> {
+        let data_type = node.data_type()?;
+        if data_type != "enum" {
+            return Err(DomError::type_mismatch(
+                node.error_location(),
+                "`enum` data type",
+                data_type,
+            )
+            .into());
+        }
+        node.load_value(PrimitiveLoader::<i32>::new())
+            .and_then(TryFrom::try_from)
+    }
+}
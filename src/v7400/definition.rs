@@ -1,21 +1,57 @@
 //! Object template definitions.
+//!
+//! FBX files have a `Definitions` node which declares the object types used
+//! in the file, the number of objects of each type, and the default property
+//! values (`PropertyTemplate`) for each native type such as `FbxMesh` and
+//! `FbxSurfacePhong`.
+//!
+//! Object properties fall back to these templates when a property is not
+//! set directly on the object, so they are useful to answer questions like
+//! "what is the default `DiffuseColor` for `FbxSurfacePhong` in this file?".
 
 use std::collections::HashMap;
 
-use anyhow::{format_err, Error};
+use anyhow::Error;
 use fbxcel::tree::v7400::{NodeHandle, Tree};
 use log::warn;
 
-use crate::v7400::object::property::PropertiesNodeId;
+use crate::v7400::{
+    document::memory,
+    object::property::{PropertiesHandle, PropertiesNodeId, PropertyHandle},
+    Document, Error as DomError, ErrorLocation,
+};
 
 /// Object template definitions cache.
 #[derive(Default, Debug, Clone)]
 pub(crate) struct DefinitionsCache {
-    /// Node IDs of property templates.
+    /// Object types, in the order of the nodes in the tree.
+    object_types: Vec<ObjectTypeEntry>,
+    /// A map from object type name to the index of `object_types`.
+    indices: HashMap<String, usize>,
+}
+
+/// Cached `ObjectType` definition.
+#[derive(Debug, Clone)]
+struct ObjectTypeEntry {
+    /// Object type (object node name), such as `Model` and `Material`.
+    name: String,
+    /// Declared object count.
+    count: Option<i32>,
+    /// Native types and node IDs of property templates.
     ///
-    /// Inner key is (maybe) the native node type in FBX SDK, such as
+    /// Native type is (maybe) the native node type in FBX SDK, such as
     /// `FbxCamera`, `FbxMesh`, and `FbxSurfacePhong`.
-    templates: HashMap<String, HashMap<String, PropertiesNodeId>>,
+    templates: Vec<(String, PropertiesNodeId)>,
+}
+
+impl ObjectTypeEntry {
+    /// Returns the properties node ID of the template for the native type.
+    fn template(&self, native_type: &str) -> Option<PropertiesNodeId> {
+        self.templates
+            .iter()
+            .find(|(ty, _)| ty == native_type)
+            .map(|&(_, id)| id)
+    }
 }
 
 impl DefinitionsCache {
@@ -25,10 +61,61 @@ impl DefinitionsCache {
         obj_node_name: &str,
         native_type: &str,
     ) -> Option<PropertiesNodeId> {
-        self.templates.get(obj_node_name)?.get(native_type).cloned()
+        self.object_type(obj_node_name)?.template(native_type)
+    }
+
+    /// Returns the object type entry.
+    fn object_type(&self, obj_type: &str) -> Option<&ObjectTypeEntry> {
+        self.indices
+            .get(obj_type)
+            .map(|&index| &self.object_types[index])
+    }
+
+    /// Returns the mutable object type entry, creating it if necessary.
+    fn object_type_mut(&mut self, obj_type: &str) -> &mut ObjectTypeEntry {
+        let index = match self.indices.get(obj_type) {
+            Some(&index) => index,
+            None => {
+                let index = self.object_types.len();
+                self.object_types.push(ObjectTypeEntry {
+                    name: obj_type.to_owned(),
+                    count: None,
+                    templates: Vec::new(),
+                });
+                self.indices.insert(obj_type.to_owned(), index);
+                index
+            }
+        };
+        &mut self.object_types[index]
+    }
+
+    /// Returns the estimated heap size.
+    pub(crate) fn heap_size(&self) -> usize {
+        let entries = self
+            .object_types
+            .iter()
+            .map(|entry| {
+                entry.name.capacity()
+                    + memory::vec_heap_size(&entry.templates)
+                    + entry
+                        .templates
+                        .iter()
+                        .map(|(ty, _)| ty.capacity())
+                        .sum::<usize>()
+            })
+            .sum::<usize>();
+        let indices = self.indices.keys().map(String::capacity).sum::<usize>();
+        memory::vec_heap_size(&self.object_types)
+            + entries
+            + memory::map_heap_size(&self.indices)
+            + indices
     }
 
     /// Creates a new `DefinitionsCache` from the given FBX data tree.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "definitions_cache", skip_all)
+    )]
     pub(crate) fn from_tree(tree: &Tree) -> Self {
         let mut this = Self::default();
 
@@ -58,11 +145,26 @@ impl DefinitionsCache {
         let obj_type = node
             .attributes()
             .get(0)
-            .ok_or_else(|| format_err!("No attributes found"))?
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?
             .get_string_or_type()
-            .map_err(|ty| {
-                format_err!("Expected string as the first attribute, but got {:?}", ty)
-            })?;
+            .map_err(|ty| DomError::type_mismatch(ErrorLocation::node(&node), "string", ty))?;
+        let count = match node.children_by_name("Count").next() {
+            Some(count_node) => Some(
+                count_node
+                    .attributes()
+                    .first()
+                    .ok_or_else(|| DomError::missing_attribute(&count_node, 0))?
+                    .get_i32_or_type()
+                    .map_err(|ty| {
+                        DomError::type_mismatch(ErrorLocation::node(&count_node), "`i32`", ty)
+                    })?,
+            ),
+            None => None,
+        };
+        let entry = self.object_type_mut(obj_type);
+        if count.is_some() {
+            entry.count = count;
+        }
         for property_template_node in node.children_by_name("PropertyTemplate") {
             if let Err(e) = self.load_property_template(property_template_node, obj_type) {
                 warn!(
@@ -86,22 +188,142 @@ impl DefinitionsCache {
         let native_type = node
             .attributes()
             .get(0)
-            .ok_or_else(|| format_err!("No attributes found"))?
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?
             .get_string_or_type()
-            .map_err(|ty| {
-                format_err!("Expected string as the first attribute, but got {:?}", ty)
-            })?;
+            .map_err(|ty| DomError::type_mismatch(ErrorLocation::node(&node), "string", ty))?;
         let properties_node = match node.children_by_name("Properties70").next() {
             Some(v) => v,
             None => return Ok(()),
         };
-        self.templates
-            .entry(obj_type.into())
-            .or_insert_with(Default::default)
-            .insert(
-                native_type.into(),
-                PropertiesNodeId::new(properties_node.node_id()),
-            );
+        let node_id = PropertiesNodeId::new(properties_node.node_id());
+        let entry = self.object_type_mut(obj_type);
+        match entry.templates.iter_mut().find(|(ty, _)| ty == native_type) {
+            Some((_, id)) => *id = node_id,
+            None => entry.templates.push((native_type.into(), node_id)),
+        }
         Ok(())
     }
 }
+
+/// Object template definitions of a document.
+#[derive(Debug, Clone, Copy)]
+pub struct Definitions<'a> {
+    /// Document.
+    doc: &'a Document,
+}
+
+impl<'a> Definitions<'a> {
+    /// Creates a new `Definitions`.
+    pub(crate) fn new(doc: &'a Document) -> Self {
+        Self { doc }
+    }
+
+    /// Returns an iterator of declared object types, in the order of the
+    /// nodes in the tree.
+    pub fn object_types(&self) -> impl Iterator<Item = ObjectTypeDefinition<'a>> {
+        let doc = self.doc;
+        doc.definitions_cache()
+            .object_types
+            .iter()
+            .map(move |entry| ObjectTypeDefinition { entry, doc })
+    }
+
+    /// Returns the declared object type with the given name (such as `Model`
+    /// and `Material`).
+    pub fn object_type(&self, obj_type: &str) -> Option<ObjectTypeDefinition<'a>> {
+        let doc = self.doc;
+        doc.definitions_cache()
+            .object_type(obj_type)
+            .map(|entry| ObjectTypeDefinition { entry, doc })
+    }
+
+    /// Returns the property template for the object type and the native
+    /// type.
+    pub fn property_template(
+        &self,
+        obj_type: &str,
+        native_type: &str,
+    ) -> Option<PropertyTemplate<'a>> {
+        self.object_type(obj_type)?.property_template(native_type)
+    }
+}
+
+/// Object type definition (provided by `ObjectType` node).
+#[derive(Debug, Clone, Copy)]
+pub struct ObjectTypeDefinition<'a> {
+    /// Cached entry.
+    entry: &'a ObjectTypeEntry,
+    /// Document.
+    doc: &'a Document,
+}
+
+impl<'a> ObjectTypeDefinition<'a> {
+    /// Returns the object type (object node name), such as `Model` and
+    /// `Material`.
+    pub fn name(&self) -> &'a str {
+        &self.entry.name
+    }
+
+    /// Returns the declared object count, if available.
+    ///
+    /// Note that this is not guaranteed to match the actual number of
+    /// objects in the document.
+    pub fn count(&self) -> Option<i32> {
+        self.entry.count
+    }
+
+    /// Returns an iterator of property templates, in the order of the nodes
+    /// in the tree.
+    pub fn property_templates(&self) -> impl Iterator<Item = PropertyTemplate<'a>> {
+        let entry = self.entry;
+        let doc = self.doc;
+        entry
+            .templates
+            .iter()
+            .map(move |(native_type, node_id)| PropertyTemplate {
+                object_type: &entry.name,
+                native_type,
+                properties: PropertiesHandle::new(*node_id, doc),
+            })
+    }
+
+    /// Returns the property template for the native type (such as `FbxMesh`
+    /// and `FbxSurfacePhong`).
+    pub fn property_template(&self, native_type: &str) -> Option<PropertyTemplate<'a>> {
+        self.property_templates()
+            .find(|template| template.native_type() == native_type)
+    }
+}
+
+/// Property template (provided by `PropertyTemplate` node).
+#[derive(Debug, Clone, Copy)]
+pub struct PropertyTemplate<'a> {
+    /// Object type.
+    object_type: &'a str,
+    /// Native type.
+    native_type: &'a str,
+    /// Default properties.
+    properties: PropertiesHandle<'a>,
+}
+
+impl<'a> PropertyTemplate<'a> {
+    /// Returns the object type (object node name), such as `Model`.
+    pub fn object_type(&self) -> &'a str {
+        self.object_type
+    }
+
+    /// Returns the native type, such as `FbxNode`.
+    pub fn native_type(&self) -> &'a str {
+        self.native_type
+    }
+
+    /// Returns the default properties.
+    pub fn properties(&self) -> PropertiesHandle<'a> {
+        self.properties
+    }
+
+    /// Returns the default property with the given name.
+    pub fn get_property(&self, name: &str) -> Option<PropertyHandle<'a>> {
+        self.properties.get_property(name)
+    }
+}
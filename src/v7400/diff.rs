@@ -0,0 +1,707 @@
+//! Comparison between two documents.
+//!
+//! [`Differ`] (and [`diff()`]) reports the differences between an old and a
+//! new document, for validating exporter upgrades and writer round-trips:
+//!
+//! * objects added, removed, and renamed,
+//! * property (`Properties70`) value changes,
+//! * connections added and removed,
+//! * array attribute (such as vertices and animation keys) changes, with a
+//!   tolerance for floating point values.
+//!
+//! Objects are matched by object ID first, and the remaining objects are
+//! matched by class, subclass, and name if they are unique in both
+//! documents, since exporters may regenerate object IDs.
+//!
+//! ```
+//! # #[cfg(feature = "edit")]
+//! # fn main() -> Result<(), anyhow::Error> {
+//! use fbxcel_dom::v7400::{
+//!     diff::{ArrayDelta, Differ},
+//!     edit::{DocumentBuilder, ModelKind},
+//!     Document,
+//! };
+//!
+//! let build = |name: &str, positions: &[[f64; 3]]| -> anyhow::Result<Document> {
+//!     let mut builder = DocumentBuilder::new();
+//!     let model = builder.add_model(name, ModelKind::Mesh, None);
+//!     builder.add_mesh("Quad", positions, &[&[0, 1, 2, 3]], Some(model));
+//!     Ok(builder.build()?)
+//! };
+//! let mut positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+//! let old = build("Box", &positions)?;
+//! // The first move is within the tolerance, and the second is not.
+//! positions[1][0] += 5e-5;
+//! positions[2][1] += 0.25;
+//! let new = build("Lid", &positions)?;
+//!
+//! let report = Differ::new().tolerance(1e-4).diff(&old, &new);
+//! assert!(report.added_objects.is_empty() && report.removed_objects.is_empty());
+//! assert_eq!(report.renamed_objects.len(), 1);
+//! assert_eq!(report.renamed_objects[0].old_name, "Box");
+//! assert_eq!(report.renamed_objects[0].object.name, "Lid");
+//! assert_eq!(report.array_changes.len(), 1);
+//! let change = &report.array_changes[0];
+//! assert_eq!((change.object.name.as_str(), change.path.as_str()), ("Quad", "Vertices"));
+//! assert_eq!(
+//!     change.delta,
+//!     ArrayDelta {
+//!         old_len: 12,
+//!         new_len: 12,
+//!         mismatches: 1,
+//!         max_delta: 0.25,
+//!     }
+//! );
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "edit"))]
+//! # fn main() {}
+//! ```
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use fbxcel::{low::v7400::AttributeValue, tree::v7400::NodeHandle};
+use log::warn;
+
+use crate::v7400::{
+    object::{
+        property::{OwnedPropertyValue, PropertyValue},
+        ObjectHandle, ObjectId,
+    },
+    Document,
+};
+
+/// Compares two documents with the default options.
+///
+/// This is same as `Differ::new().diff(old, new)`.
+///
+/// ```
+/// # #[cfg(feature = "edit")]
+/// # fn main() -> Result<(), anyhow::Error> {
+/// use fbxcel_dom::v7400::{
+///     diff::{diff, DiffValue},
+///     edit::{DocumentBuilder, ModelKind},
+///     object::property::OwnedPropertyValue,
+///     Document,
+/// };
+///
+/// let build = |visibility: f64| -> anyhow::Result<Document> {
+///     let mut builder = DocumentBuilder::new();
+///     let model = builder.add_model("Door", ModelKind::Null, None);
+///     let value = OwnedPropertyValue::F64(visibility);
+///     builder
+///         .edit_mut()
+///         .insert_property(model, "Visibility", "Visibility", "", "A", value)?;
+///     Ok(builder.build()?)
+/// };
+///
+/// let report = diff(&build(1.0)?, &build(0.0)?);
+/// assert!(report.added_objects.is_empty() && report.removed_objects.is_empty());
+/// assert!(report.array_changes.is_empty());
+/// assert_eq!(report.property_changes.len(), 1);
+/// let change = &report.property_changes[0];
+/// assert_eq!(change.object.name, "Door");
+/// assert_eq!(change.property, "Visibility");
+/// assert_eq!(change.old, Some(DiffValue::Value(OwnedPropertyValue::F64(1.0))));
+/// assert_eq!(change.new, Some(DiffValue::Value(OwnedPropertyValue::F64(0.0))));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "edit"))]
+/// # fn main() {}
+/// ```
+pub fn diff(old: &Document, new: &Document) -> DiffReport {
+    Differ::new().diff(old, new)
+}
+
+/// Document comparison options.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Differ {
+    /// Absolute tolerance for floating point values.
+    tolerance: f64,
+}
+
+impl Differ {
+    /// Creates a new differ with the default options.
+    ///
+    /// The default tolerance is `1e-6`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the absolute tolerance for floating point values.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Compares the documents.
+    pub fn diff(&self, old: &Document, new: &Document) -> DiffReport {
+        let matching = Matching::new(old, new);
+        let mut report = DiffReport::default();
+
+        let mut old_objects: Vec<_> = old.objects().collect();
+        old_objects.sort_by_key(|obj| obj.object_id());
+        for old_obj in &old_objects {
+            let new_obj = match matching.new_id(old_obj.object_id()) {
+                Some(id) => id
+                    .to_object_handle(new)
+                    .expect("Should never fail: matched objects exist"),
+                None => {
+                    report.removed_objects.push(ObjectSummary::new(old_obj));
+                    continue;
+                }
+            };
+            let pair = ObjectPair::new(old_obj, &new_obj);
+            if old_obj.name() != new_obj.name() {
+                report.renamed_objects.push(ObjectRename {
+                    object: pair.clone(),
+                    old_name: old_obj.name().unwrap_or_default().to_owned(),
+                });
+            }
+            self.diff_properties(&mut report, &pair, old_obj, &new_obj);
+            self.diff_arrays(&mut report, &pair, old_obj, &new_obj);
+        }
+
+        let mut new_objects: Vec<_> = new
+            .objects()
+            .filter(|obj| matching.old_id(obj.object_id()).is_none())
+            .collect();
+        new_objects.sort_by_key(|obj| obj.object_id());
+        report.added_objects = new_objects.iter().map(ObjectSummary::new).collect();
+
+        // Compare connections with the new object IDs mapped to the old ones.
+        let old_conns: HashSet<_> = old
+            .connections()
+            .map(|conn| {
+                (
+                    ConnectionEnd::Old(conn.source_id()),
+                    ConnectionEnd::Old(conn.destination_id()),
+                    conn.label().map(ToOwned::to_owned),
+                )
+            })
+            .collect();
+        let map_new = |id: ObjectId| match matching.old_id(id) {
+            Some(old_id) => ConnectionEnd::Old(old_id),
+            None if id.to_object_handle(new).is_some() => ConnectionEnd::New(id),
+            // Scene roots and other non-object IDs.
+            None => ConnectionEnd::Old(id),
+        };
+        let new_conns: HashSet<_> = new
+            .connections()
+            .map(|conn| {
+                (
+                    map_new(conn.source_id()),
+                    map_new(conn.destination_id()),
+                    conn.label().map(ToOwned::to_owned),
+                )
+            })
+            .collect();
+        report.removed_connections = old
+            .connections()
+            .filter(|conn| {
+                !new_conns.contains(&(
+                    ConnectionEnd::Old(conn.source_id()),
+                    ConnectionEnd::Old(conn.destination_id()),
+                    conn.label().map(ToOwned::to_owned),
+                ))
+            })
+            .map(|conn| ConnectionSummary {
+                source: conn.source_id(),
+                destination: conn.destination_id(),
+                label: conn.label().map(ToOwned::to_owned),
+            })
+            .collect();
+        report.added_connections = new
+            .connections()
+            .filter(|conn| {
+                !old_conns.contains(&(
+                    map_new(conn.source_id()),
+                    map_new(conn.destination_id()),
+                    conn.label().map(ToOwned::to_owned),
+                ))
+            })
+            .map(|conn| ConnectionSummary {
+                source: conn.source_id(),
+                destination: conn.destination_id(),
+                label: conn.label().map(ToOwned::to_owned),
+            })
+            .collect();
+
+        report
+    }
+
+    /// Compares the direct properties of the objects.
+    fn diff_properties(
+        &self,
+        report: &mut DiffReport,
+        pair: &ObjectPair,
+        old: &ObjectHandle<'_>,
+        new: &ObjectHandle<'_>,
+    ) {
+        let old_props = property_values(old);
+        let mut new_props = property_values(new);
+        for (name, old_value) in old_props {
+            let new_value = new_props.remove(&name);
+            let changed = match (&old_value, &new_value) {
+                (DiffValue::Value(old_value), Some(DiffValue::Value(new_value))) => {
+                    !self.values_eq(old_value.as_value(), new_value.as_value())
+                }
+                (
+                    DiffValue::Unreadable { raw: old_raw, .. },
+                    Some(DiffValue::Unreadable { raw: new_raw, .. }),
+                ) => old_raw != new_raw,
+                _ => true,
+            };
+            if changed {
+                report.property_changes.push(PropertyChange {
+                    object: pair.clone(),
+                    property: name,
+                    old: Some(old_value),
+                    new: new_value,
+                });
+            }
+        }
+        for (name, new_value) in new_props {
+            report.property_changes.push(PropertyChange {
+                object: pair.clone(),
+                property: name,
+                old: None,
+                new: Some(new_value),
+            });
+        }
+    }
+
+    /// Returns true if the property values are equal within the tolerance.
+    fn values_eq(&self, a: PropertyValue<'_>, b: PropertyValue<'_>) -> bool {
+        let close = |a: &[f64], b: &[f64]| a.iter().zip(b).all(|(a, b)| self.close(*a, *b));
+        match (a, b) {
+            (PropertyValue::F32(a), PropertyValue::F32(b)) => self.close(a.into(), b.into()),
+            (PropertyValue::F64(a), PropertyValue::F64(b)) => self.close(a, b),
+            (PropertyValue::F64x2(a), PropertyValue::F64x2(b)) => close(&a, &b),
+            (PropertyValue::F64x3(a), PropertyValue::F64x3(b)) => close(&a, &b),
+            (PropertyValue::F64x4(a), PropertyValue::F64x4(b)) => close(&a, &b),
+            (a, b) => a == b,
+        }
+    }
+
+    /// Returns true if the values are equal within the tolerance.
+    fn close(&self, a: f64, b: f64) -> bool {
+        a == b || (a - b).abs() <= self.tolerance
+    }
+
+    /// Compares the array attributes of the object nodes and their
+    /// descendants, except for the properties.
+    fn diff_arrays(
+        &self,
+        report: &mut DiffReport,
+        pair: &ObjectPair,
+        old: &ObjectHandle<'_>,
+        new: &ObjectHandle<'_>,
+    ) {
+        let old_arrays = arrays(old.document(), old.node());
+        let mut new_arrays = arrays(new.document(), new.node());
+        for ((path, index), old_value) in old_arrays {
+            let new_value = new_arrays.remove(&(path.clone(), index));
+            let change = match new_value {
+                Some(new_value) => self.compare_arrays(old_value, new_value),
+                None => Some(ArrayDelta {
+                    old_len: array_values(old_value).len(),
+                    new_len: 0,
+                    mismatches: 0,
+                    max_delta: 0.0,
+                }),
+            };
+            if let Some(delta) = change {
+                report.array_changes.push(ArrayChange {
+                    object: pair.clone(),
+                    path,
+                    attribute: index,
+                    delta,
+                });
+            }
+        }
+        for ((path, index), new_value) in new_arrays {
+            report.array_changes.push(ArrayChange {
+                object: pair.clone(),
+                path,
+                attribute: index,
+                delta: ArrayDelta {
+                    old_len: 0,
+                    new_len: array_values(new_value).len(),
+                    mismatches: 0,
+                    max_delta: 0.0,
+                },
+            });
+        }
+    }
+
+    /// Compares the array attributes, and returns the delta if they differ.
+    fn compare_arrays(&self, old: &AttributeValue, new: &AttributeValue) -> Option<ArrayDelta> {
+        let a = array_values(old);
+        let b = array_values(new);
+        let mut mismatches = 0;
+        let mut max_delta: f64 = 0.0;
+        for (a, b) in a.iter().zip(&b) {
+            if !self.close(*a, *b) {
+                mismatches += 1;
+            }
+            if a != b {
+                max_delta = max_delta.max((a - b).abs());
+            }
+        }
+        if mismatches == 0 && a.len() == b.len() {
+            return None;
+        }
+        Some(ArrayDelta {
+            old_len: a.len(),
+            new_len: b.len(),
+            mismatches,
+            max_delta,
+        })
+    }
+}
+
+impl Default for Differ {
+    fn default() -> Self {
+        Self { tolerance: 1e-6 }
+    }
+}
+
+/// Differences between two documents.
+///
+/// Object IDs in the report are the ones in the document they exist in:
+/// removed objects and connections have the old IDs, added ones have the
+/// new IDs, and matched objects have both.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DiffReport {
+    /// Objects only in the new document.
+    pub added_objects: Vec<ObjectSummary>,
+    /// Objects only in the old document.
+    pub removed_objects: Vec<ObjectSummary>,
+    /// Matched objects with different names.
+    pub renamed_objects: Vec<ObjectRename>,
+    /// Property value changes of matched objects.
+    pub property_changes: Vec<PropertyChange>,
+    /// Connections only in the new document.
+    pub added_connections: Vec<ConnectionSummary>,
+    /// Connections only in the old document.
+    pub removed_connections: Vec<ConnectionSummary>,
+    /// Array attribute changes of matched objects.
+    pub array_changes: Vec<ArrayChange>,
+}
+
+impl DiffReport {
+    /// Returns true if no differences are found.
+    pub fn is_empty(&self) -> bool {
+        self.added_objects.is_empty()
+            && self.removed_objects.is_empty()
+            && self.renamed_objects.is_empty()
+            && self.property_changes.is_empty()
+            && self.added_connections.is_empty()
+            && self.removed_connections.is_empty()
+            && self.array_changes.is_empty()
+    }
+}
+
+/// Object only in one of the documents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectSummary {
+    /// Object ID.
+    pub id: ObjectId,
+    /// Class.
+    pub class: String,
+    /// Subclass.
+    pub subclass: String,
+    /// Name.
+    pub name: String,
+}
+
+impl ObjectSummary {
+    /// Creates a summary of the object.
+    fn new(obj: &ObjectHandle<'_>) -> Self {
+        Self {
+            id: obj.object_id(),
+            class: obj.class().to_owned(),
+            subclass: obj.subclass().to_owned(),
+            name: obj.name().unwrap_or_default().to_owned(),
+        }
+    }
+}
+
+/// Object matched between the documents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectPair {
+    /// Object ID in the old document.
+    pub old_id: ObjectId,
+    /// Object ID in the new document.
+    pub new_id: ObjectId,
+    /// Class.
+    pub class: String,
+    /// Name in the new document.
+    pub name: String,
+}
+
+impl ObjectPair {
+    /// Creates a pair of the objects.
+    fn new(old: &ObjectHandle<'_>, new: &ObjectHandle<'_>) -> Self {
+        Self {
+            old_id: old.object_id(),
+            new_id: new.object_id(),
+            class: new.class().to_owned(),
+            name: new.name().unwrap_or_default().to_owned(),
+        }
+    }
+}
+
+/// Renamed object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectRename {
+    /// Object, with the new name.
+    pub object: ObjectPair,
+    /// Name in the old document.
+    pub old_name: String,
+}
+
+/// Property value change.
+///
+/// Values are `None` if the property is absent in the document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyChange {
+    /// Object.
+    pub object: ObjectPair,
+    /// Property name.
+    pub property: String,
+    /// Value in the old document.
+    pub old: Option<DiffValue>,
+    /// Value in the new document.
+    pub new: Option<DiffValue>,
+}
+
+/// Property value compared by the differ.
+///
+/// Unreadable values are compared by their raw attributes, and an unreadable
+/// value never equals a readable one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffValue {
+    /// Loaded value.
+    Value(OwnedPropertyValue),
+    /// Value which failed to load.
+    Unreadable {
+        /// Raw value part attributes.
+        raw: Vec<AttributeValue>,
+        /// Load error message.
+        error: String,
+    },
+}
+
+/// Connection only in one of the documents.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConnectionSummary {
+    /// Source object ID.
+    pub source: ObjectId,
+    /// Destination object ID.
+    pub destination: ObjectId,
+    /// Label (destination property name).
+    pub label: Option<String>,
+}
+
+/// Array attribute change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayChange {
+    /// Object.
+    pub object: ObjectPair,
+    /// Node path from the object node (such as `LayerElementNormal/Normals`).
+    ///
+    /// Names of nodes with same-name siblings have the index suffix (such
+    /// as `LayerElementUV[1]`).
+    pub path: String,
+    /// Attribute index in the node.
+    pub attribute: usize,
+    /// Difference.
+    pub delta: ArrayDelta,
+}
+
+/// Difference of array values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArrayDelta {
+    /// Number of elements in the old document (0 if absent).
+    pub old_len: usize,
+    /// Number of elements in the new document (0 if absent).
+    pub new_len: usize,
+    /// Number of elements differing beyond the tolerance, among the common
+    /// prefix.
+    pub mismatches: usize,
+    /// Maximum absolute difference among the common prefix.
+    pub max_delta: f64,
+}
+
+/// Connection end, with the new object IDs mapped to the old ones if
+/// possible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ConnectionEnd {
+    /// Object ID in the old document (or an unmatched non-object ID).
+    Old(ObjectId),
+    /// Object ID of an added object in the new document.
+    New(ObjectId),
+}
+
+/// Object matching between the documents.
+struct Matching {
+    /// New object IDs by old object IDs.
+    old_to_new: HashMap<ObjectId, ObjectId>,
+    /// Old object IDs by new object IDs.
+    new_to_old: HashMap<ObjectId, ObjectId>,
+}
+
+impl Matching {
+    /// Matches the objects in the documents.
+    fn new(old: &Document, new: &Document) -> Self {
+        let mut matching = Self {
+            old_to_new: HashMap::new(),
+            new_to_old: HashMap::new(),
+        };
+        for old_obj in old.objects() {
+            let id = old_obj.object_id();
+            if let Some(new_obj) = id.to_object_handle(new) {
+                if new_obj.class() == old_obj.class() {
+                    matching.insert(id, id);
+                }
+            }
+        }
+
+        /// Unmatched objects by class, subclass, and name, or `None` if the
+        /// key is not unique.
+        type ByKey<'a> = HashMap<(&'a str, &'a str, &'a str), Option<ObjectId>>;
+        fn by_key<'a>(objects: impl Iterator<Item = ObjectHandle<'a>>) -> ByKey<'a> {
+            let mut map = ByKey::new();
+            for obj in objects {
+                let key = (obj.class(), obj.subclass(), obj.name().unwrap_or_default());
+                map.entry(key)
+                    .and_modify(|v| *v = None)
+                    .or_insert_with(|| Some(obj.object_id()));
+            }
+            map
+        }
+        let old_unmatched = by_key(
+            old.objects()
+                .filter(|obj| !matching.old_to_new.contains_key(&obj.object_id())),
+        );
+        let new_unmatched = by_key(
+            new.objects()
+                .filter(|obj| !matching.new_to_old.contains_key(&obj.object_id())),
+        );
+        for (key, old_id) in old_unmatched {
+            if let (Some(old_id), Some(Some(new_id))) = (old_id, new_unmatched.get(&key)) {
+                matching.insert(old_id, *new_id);
+            }
+        }
+
+        matching
+    }
+
+    /// Registers the matched pair.
+    fn insert(&mut self, old: ObjectId, new: ObjectId) {
+        self.old_to_new.insert(old, new);
+        self.new_to_old.insert(new, old);
+    }
+
+    /// Returns the new object ID matched to the old one.
+    fn new_id(&self, old: ObjectId) -> Option<ObjectId> {
+        self.old_to_new.get(&old).copied()
+    }
+
+    /// Returns the old object ID matched to the new one.
+    fn old_id(&self, new: ObjectId) -> Option<ObjectId> {
+        self.new_to_old.get(&new).copied()
+    }
+}
+
+/// Returns the direct property values of the object by name.
+///
+/// Properties with unreadable values are kept as [`DiffValue::Unreadable`].
+fn property_values(obj: &ObjectHandle<'_>) -> BTreeMap<String, DiffValue> {
+    obj.direct_properties()
+        .into_iter()
+        .flat_map(|props| props.properties())
+        .filter_map(|prop| {
+            let name = prop.name().ok()?.to_owned();
+            let value = match prop.value() {
+                Ok(v) => DiffValue::Value(v.into()),
+                Err(e) => DiffValue::Unreadable {
+                    raw: prop.value_part().to_vec(),
+                    error: e.to_string(),
+                },
+            };
+            Some((name, value))
+        })
+        .collect()
+}
+
+/// Returns the array attributes of the node and its descendants (except
+/// `Properties70`) by node path and attribute index.
+fn arrays<'a>(
+    doc: &'a Document,
+    node: NodeHandle<'a>,
+) -> BTreeMap<(String, usize), &'a AttributeValue> {
+    let mut arrays = BTreeMap::new();
+    let mut stack = vec![(String::new(), node)];
+    while let Some((path, node)) = stack.pop() {
+        for index in 0..node.attributes().len() {
+            match doc.attribute(&node, index) {
+                Ok(Some(attr)) if is_array(attr) => {
+                    arrays.insert((path.clone(), index), attr);
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Failed to read array at {:?}: {}", path, e),
+            }
+        }
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for child in node.children() {
+            *counts.entry(child.name()).or_default() += 1;
+        }
+        let mut indices: HashMap<&str, usize> = HashMap::new();
+        for child in node.children() {
+            if child.name() == "Properties70" {
+                continue;
+            }
+            let index = indices.entry(child.name()).or_default();
+            let name = if counts[child.name()] > 1 {
+                format!("{}[{}]", child.name(), index)
+            } else {
+                child.name().to_owned()
+            };
+            *index += 1;
+            let child_path = if path.is_empty() {
+                name
+            } else {
+                format!("{}/{}", path, name)
+            };
+            stack.push((child_path, child));
+        }
+    }
+    arrays
+}
+
+/// Returns true if the attribute is an array.
+fn is_array(attr: &AttributeValue) -> bool {
+    matches!(
+        attr,
+        AttributeValue::ArrBool(_)
+            | AttributeValue::ArrI32(_)
+            | AttributeValue::ArrI64(_)
+            | AttributeValue::ArrF32(_)
+            | AttributeValue::ArrF64(_)
+    )
+}
+
+/// Returns the elements of the array attribute as `f64`.
+fn array_values(attr: &AttributeValue) -> Vec<f64> {
+    match attr {
+        AttributeValue::ArrBool(v) => v.iter().map(|&v| f64::from(u8::from(v))).collect(),
+        AttributeValue::ArrI32(v) => v.iter().map(|&v| f64::from(v)).collect(),
+        AttributeValue::ArrI64(v) => v.iter().map(|&v| v as f64).collect(),
+        AttributeValue::ArrF32(v) => v.iter().map(|&v| f64::from(v)).collect(),
+        AttributeValue::ArrF64(v) => v.clone(),
+        _ => Vec::new(),
+    }
+}
@@ -1,19 +1,77 @@
 //! FBX DOM.
 
-use fbxcel::tree::v7400::Tree;
+use std::{borrow::Cow, io::Read};
+
+use anyhow::{format_err, Error};
+use fbxcel::{
+    low::{
+        v7400::{AttributeValue, FbxFooter},
+        FbxVersion,
+    },
+    tree::v7400::{NodeHandle, Tree},
+};
+use log::warn;
 
 use crate::v7400::{
     connection::ConnectionsCache,
-    definition::DefinitionsCache,
-    object::{scene::SceneHandle, ObjectHandle, ObjectsCache},
+    data::{datetime::DateTime, model::BoneSegment, transform::TransformCache},
+    definition::{Definitions, DefinitionsCache},
+    graph::ConnectionGraph,
+    object::{
+        animation, character, collection, constraint,
+        extension::ExtensionType,
+        geometry,
+        id::Resolve,
+        material,
+        model::{self, PathOptions, TypedModelHandle},
+        plugin::PluginObjectHandle,
+        property::{PropertiesIndex, PropertyEntry},
+        reference,
+        scene::SceneHandle,
+        selection, NameMatchOptions, ObjectConnection, ObjectHandle, ObjectId, ObjectIndex,
+        ObjectsCache, TypedObjectHandle,
+    },
+    symbol::{Symbol, SymbolTable},
+    version::Capabilities,
+    LoadError, LoadWarning, Warning, Warnings,
+};
+
+pub(crate) use self::limits::MAX_PREALLOCATION;
+pub use self::{
+    incremental::IncrementalLoader,
+    limits::ResourceLimits,
+    loader::Loader,
+    memory::{AttributeMemory, MemoryReport},
+    names::NameSanitizer,
+    statistics::Statistics,
+    summary::DocumentSummary,
 };
 
-pub use self::loader::Loader;
+#[cfg(feature = "lazy-arrays")]
+use self::lazy::LazyArrays;
+#[cfg(feature = "query")]
+use crate::v7400::query::Query;
+#[cfg(feature = "walk")]
+use crate::v7400::walk::{self, Visitor, WalkOptions};
 
+mod analysis;
+mod filter;
+mod incremental;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "lazy-arrays")]
+mod lazy;
+mod limits;
 mod loader;
+mod lookup;
+pub(crate) mod memory;
+mod names;
+pub(crate) mod recovery;
+mod statistics;
+mod summary;
 
 /// FBX DOM.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Document {
     /// FBX data tree.
     tree: Tree,
@@ -23,14 +81,211 @@ pub struct Document {
     connections: ConnectionsCache,
     /// Object template definitions.
     definitions: DefinitionsCache,
+    /// Interned strings.
+    symbols: SymbolTable,
+    /// Property nodes index.
+    properties: PropertiesIndex,
+    /// Footer of the source file.
+    footer: Option<FbxFooter>,
+    /// FBX version of the source file.
+    fbx_version: Option<FbxVersion>,
+    /// User-registered extension object types.
+    extension_types: Vec<ExtensionType>,
+    /// Warnings recorded on loading in the non-strict mode.
+    load_warnings: Vec<LoadWarning>,
+    /// Warnings recorded on loading and extraction, not taken yet.
+    warnings: Warnings,
+    /// Compressed arrays not decoded into the tree yet.
+    #[cfg(feature = "lazy-arrays")]
+    lazy_arrays: LazyArrays,
 }
 
 impl Document {
+    /// Creates a new document loader.
+    ///
+    /// This is same as [`Loader::new()`].
+    pub fn loader() -> Loader {
+        Loader::new()
+    }
+
+    /// Loads a document from the given binary FBX 7.x data in memory, with
+    /// the default options.
+    ///
+    /// This does not touch the filesystem, so it works on targets without
+    /// one (such as `wasm32-unknown-unknown`) as well.
+    /// This is same as `Loader::new().load_from_slice(data)`; use
+    /// [`AnyDocument`][`crate::any::AnyDocument`] to load other versions and
+    /// ASCII FBX.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, LoadError> {
+        Loader::new().load_from_slice(data)
+    }
+
+    /// Loads a document from the given binary FBX 7.x reader, with the
+    /// default options.
+    ///
+    /// This is same as `Loader::new().load_from_reader(reader)`.
+    pub fn from_reader(reader: impl Read) -> Result<Self, LoadError> {
+        Loader::new().load_from_reader(reader)
+    }
+
     /// Returns a reference to the tree.
+    ///
+    /// Compressed arrays not decoded yet are empty in the tree (see
+    /// [`Loader::lazy_arrays()`]). Call [`preload_all()`][`Self::preload_all`]
+    /// before reading arrays from the tree directly.
     pub fn tree(&self) -> &Tree {
         &self.tree
     }
 
+    /// Consumes the document and returns the tree.
+    ///
+    /// The tree can be modified and loaded again with
+    /// [`Loader::load_from_tree()`].
+    /// Compressed arrays not decoded yet are decoded into the tree, and
+    /// broken ones are stored as far as decoded.
+    pub fn into_tree(self) -> Tree {
+        self.into_tree_and_footer().0
+    }
+
+    /// Consumes the document and returns the tree and the source footer.
+    #[cfg(feature = "lazy-arrays")]
+    pub(crate) fn into_tree_and_footer(mut self) -> (Tree, Option<FbxFooter>) {
+        if let Err(e) = self.lazy_arrays.preload(&mut self.tree, true) {
+            warn!("Failed to decode arrays: {}", e);
+        }
+        (self.tree, self.footer)
+    }
+
+    /// Consumes the document and returns the tree and the source footer.
+    #[cfg(not(feature = "lazy-arrays"))]
+    pub(crate) fn into_tree_and_footer(self) -> (Tree, Option<FbxFooter>) {
+        (self.tree, self.footer)
+    }
+
+    /// Returns true if all arrays are decoded into the tree.
+    ///
+    /// This is false for documents loaded with [`Loader::lazy_arrays()`]
+    /// until [`preload_all()`][`Self::preload_all`] is called, if they have
+    /// compressed arrays.
+    #[cfg(feature = "lazy-arrays")]
+    pub fn is_preloaded(&self) -> bool {
+        self.lazy_arrays.is_empty()
+    }
+
+    /// Decodes all compressed arrays not decoded yet into the tree.
+    ///
+    /// This restores the behavior of loading without
+    /// [`Loader::lazy_arrays()`]. Returns an error if an array is broken (in
+    /// the strict mode), without modifying the tree.
+    #[cfg(feature = "lazy-arrays")]
+    pub fn preload_all(&mut self) -> Result<(), Error> {
+        self.lazy_arrays.preload(&mut self.tree, false)
+    }
+
+    /// Drops the arrays decoded on use, to decode them again on next use.
+    ///
+    /// This reduces the memory usage after processing large arrays of a
+    /// document loaded with [`Loader::lazy_arrays()`].
+    /// Arrays decoded by [`preload_all()`][`Self::preload_all`] are kept.
+    #[cfg(feature = "lazy-arrays")]
+    pub fn release_arrays(&mut self) {
+        self.lazy_arrays.release();
+    }
+
+    /// Returns the attribute of the node, decoding the compressed array on
+    /// first use.
+    pub(crate) fn attribute<'a>(
+        &'a self,
+        node: &NodeHandle<'a>,
+        index: usize,
+    ) -> Result<Option<&'a AttributeValue>, Error> {
+        #[cfg(feature = "lazy-arrays")]
+        if !self.lazy_arrays.is_empty() {
+            if let Some(attr) = self.lazy_arrays.get(node.node_id(), index) {
+                return attr.map(Some);
+            }
+        }
+        Ok(node.attributes().get(index))
+    }
+
+    /// Returns the tree with all compressed arrays decoded.
+    ///
+    /// The tree is cloned if there are arrays not decoded yet.
+    pub(crate) fn preloaded_tree(&self) -> Result<Cow<'_, Tree>, Error> {
+        #[cfg(feature = "lazy-arrays")]
+        if !self.lazy_arrays.is_empty() {
+            let mut tree = self.tree.clone();
+            self.lazy_arrays.decode_into(&mut tree)?;
+            return Ok(Cow::Owned(tree));
+        }
+        Ok(Cow::Borrowed(&self.tree))
+    }
+
+    /// Returns the footer of the source file, if available.
+    ///
+    /// This is available when the document is loaded from a parser (or
+    /// [`AnyDocument`][`crate::any::AnyDocument`]) and the footer is
+    /// successfully read.
+    pub fn footer(&self) -> Option<&FbxFooter> {
+        self.footer.as_ref()
+    }
+
+    /// Returns the FBX version of the source file, if available.
+    ///
+    /// This is available when the document is loaded from a parser (or
+    /// [`AnyDocument`][`crate::any::AnyDocument`]), or the version is set to
+    /// the loader by [`Loader::fbx_version()`].
+    pub fn fbx_version(&self) -> Option<FbxVersion> {
+        self.fbx_version
+            .or_else(|| self.footer.map(|footer| footer.fbx_version))
+    }
+
+    /// Returns the capabilities of the FBX version of the source file, if
+    /// available.
+    pub fn capabilities(&self) -> Option<Capabilities> {
+        self.fbx_version().map(Capabilities::new)
+    }
+
+    /// Returns the problems recovered from on loading, in the order they are
+    /// found.
+    ///
+    /// This is always empty for documents loaded in the strict mode (see
+    /// [`Loader::strict()`]).
+    pub fn load_warnings(&self) -> &[LoadWarning] {
+        &self.load_warnings
+    }
+
+    /// Takes the warnings recorded on loading and extraction so far.
+    ///
+    /// Warnings are recorded when non-fatal problems are recovered from,
+    /// such as broken data in the non-strict mode (also available by
+    /// [`load_warnings()`][`Self::load_warnings`]), or layer elements and
+    /// skin influences which cannot be used as is by high-level extraction.
+    /// Taken warnings are removed, so each warning is returned only once.
+    ///
+    /// Warnings are returned in the order they are recorded, which is
+    /// deterministic for the same input and the same operations, even with
+    /// parallel extraction.
+    pub fn take_warnings(&self) -> Vec<Warning> {
+        self.warnings.take()
+    }
+
+    /// Records the warning.
+    pub(crate) fn warn(&self, warning: impl Into<Warning>) {
+        self.warnings.push(warning);
+    }
+
+    /// Records the warnings already logged.
+    pub(crate) fn extend_warnings(&self, warnings: Vec<Warning>) {
+        self.warnings.extend(warnings);
+    }
+
+    /// Runs the function, and returns the warnings recorded to the document
+    /// on the current thread during the call instead of recording them.
+    pub(crate) fn capture_warnings<T>(&self, f: impl FnOnce() -> T) -> (T, Vec<Warning>) {
+        self.warnings.capture(f)
+    }
+
     /// Returns a reference to the objects cache.
     pub(crate) fn objects_cache(&self) -> &ObjectsCache {
         &self.objects
@@ -46,20 +301,585 @@ impl Document {
         &self.definitions
     }
 
-    /// Returns an iterator of all object nodes.
+    /// Returns a reference to the property nodes index.
+    pub(crate) fn properties_index(&self) -> &PropertiesIndex {
+        &self.properties
+    }
+
+    /// Returns the symbol of the given string, if it is interned in the
+    /// document.
+    ///
+    /// Object names, classes and subclasses, connection labels, and property
+    /// names and type names are interned on loading.
+    /// If this returns `None`, no such names are in the document.
+    /// See [`symbol`](../symbol/index.html) module.
+    #[cfg(feature = "symbol")]
+    pub fn symbol(&self, s: &str) -> Option<Symbol> {
+        self.lookup_symbol(s)
+    }
+
+    /// Returns the string for the symbol, if it is interned in the document.
+    #[cfg(feature = "symbol")]
+    pub fn resolve_symbol(&self, sym: Symbol) -> Option<&str> {
+        self.symbols.resolve(sym)
+    }
+
+    /// Returns the symbol of the given string, if it is interned in the
+    /// document.
+    pub(crate) fn lookup_symbol(&self, s: &str) -> Option<Symbol> {
+        self.symbols.get(s)
+    }
+
+    /// Returns the string for the symbol registered in the document.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given symbol is not registered.
+    pub(crate) fn resolve_registered(&self, sym: Symbol) -> &str {
+        self.symbols.resolve_registered(sym)
+    }
+
+    /// Returns the user-registered extension object types.
+    pub(crate) fn extension_types(&self) -> &[ExtensionType] {
+        &self.extension_types
+    }
+
+    /// Returns the creation time stamp in the FBX header extension.
+    ///
+    /// Returns `Ok(None)` if the `CreationTimeStamp` node does not exist.
+    pub fn creation_time_stamp(&self) -> Result<Option<DateTime>, Error> {
+        self.tree
+            .root()
+            .first_child_by_name("FBXHeaderExtension")
+            .and_then(|ext| ext.first_child_by_name("CreationTimeStamp"))
+            .map(|node| {
+                DateTime::from_children(node)
+                    .map_err(|e| e.context("Failed to load creation time stamp"))
+            })
+            .transpose()
+    }
+
+    /// Returns the object template definitions.
+    pub fn definitions(&self) -> Definitions<'_> {
+        Definitions::new(self)
+    }
+
+    /// Returns an iterator of all object nodes, in the order of the nodes in
+    /// the tree.
     pub fn objects(&self) -> impl Iterator<Item = ObjectHandle<'_>> {
         self.objects
-            .object_node_ids()
-            .map(move |id| id.to_object_handle(self))
+            .object_indices()
+            .map(move |index| ObjectHandle::from_index(index, self))
+    }
+
+    /// Returns an iterator of all connections, in the order of the nodes in
+    /// the tree.
+    pub fn connections(&self) -> impl Iterator<Item = ObjectConnection<'_>> {
+        self.connections
+            .connections()
+            .map(move |conn| ObjectConnection::new(conn, self))
+    }
+
+    /// Returns an owned graph of all objects and connections.
+    ///
+    /// This is intended for debugging. See [`ConnectionGraph`].
+    pub fn connection_graph(&self) -> ConnectionGraph {
+        ConnectionGraph::from_document(self)
+    }
+
+    /// Returns an iterator of all properties of all objects, with the owner
+    /// objects.
+    ///
+    /// Only the properties directly associated to the objects are returned,
+    /// and default values from the property templates are not.
+    /// Properties whose names, flags, or values cannot be read are yielded
+    /// as errors, and the iteration can continue after them.
+    ///
+    /// ```
+    /// # #[cfg(feature = "edit")]
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// use fbxcel_dom::v7400::{
+    ///     edit::{DocumentBuilder, ModelKind},
+    ///     object::property::{OwnedPropertyValue, PropertyValue},
+    /// };
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let model = builder.add_model("Cube", ModelKind::Null, None);
+    /// let value = OwnedPropertyValue::String("textures/cube.png".to_owned());
+    /// builder
+    ///     .edit_mut()
+    ///     .insert_property(model, "TexturePath", "KString", "", "U", value)?;
+    /// let doc = builder.build()?;
+    ///
+    /// let entries = doc.iter_all_properties().collect::<Result<Vec<_>, _>>()?;
+    /// let user_props: Vec<_> = entries
+    ///     .into_iter()
+    ///     .filter(|entry| entry.flags.is_user_defined())
+    ///     .map(|entry| (entry.object.name(), entry.name, entry.value))
+    ///     .collect();
+    /// assert_eq!(
+    ///     user_props,
+    ///     [(Some("Cube"), "TexturePath", PropertyValue::String("textures/cube.png"))]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "edit"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// `Visibility` (stored as `f64`) and properties without values are also
+    /// read:
+    ///
+    /// ```
+    /// # #[cfg(feature = "edit")]
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// use fbxcel_dom::v7400::{
+    ///     edit::{DocumentBuilder, ModelKind},
+    ///     object::property::{OwnedPropertyValue, PropertyValue},
+    /// };
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let model = builder.add_model("Hidden", ModelKind::Null, None);
+    /// let edit = builder.edit_mut();
+    /// let visibility = OwnedPropertyValue::F64(0.0);
+    /// edit.insert_property(model, "Visibility", "Visibility", "", "A", visibility)?;
+    /// let target = OwnedPropertyValue::Empty;
+    /// edit.insert_property(model, "LookAtProperty", "object", "", "", target)?;
+    /// let doc = builder.build()?;
+    ///
+    /// let entries = doc.iter_all_properties().collect::<Result<Vec<_>, _>>()?;
+    /// let value = |name: &str| {
+    ///     entries
+    ///         .iter()
+    ///         .find(|entry| entry.name == name)
+    ///         .map(|entry| entry.value)
+    /// };
+    /// assert_eq!(value("Visibility"), Some(PropertyValue::F64(0.0)));
+    /// assert_eq!(value("LookAtProperty"), Some(PropertyValue::Empty));
+    /// let model = doc.model_by_name("Hidden").expect("Model should exist");
+    /// assert!(!model.properties().is_visible_or_default()?);
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "edit"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// Unreadable properties are reported without stopping the iteration:
+    ///
+    /// ```
+    /// # #[cfg(feature = "edit")]
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// use fbxcel_dom::v7400::{
+    ///     edit::{DocumentBuilder, ModelKind},
+    ///     object::property::OwnedPropertyValue,
+    /// };
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let model = builder.add_model("Broken", ModelKind::Null, None);
+    /// let edit = builder.edit_mut();
+    /// // `KString` property with an integer value.
+    /// edit.insert_property(model, "Path", "KString", "", "U", OwnedPropertyValue::I32(1))?;
+    /// edit.insert_property(model, "Count", "int", "", "U", OwnedPropertyValue::I32(2))?;
+    /// let doc = builder.build()?;
+    ///
+    /// let (ok, err): (Vec<_>, Vec<_>) = doc.iter_all_properties().partition(Result::is_ok);
+    /// assert_eq!(err.len(), 1);
+    /// assert!(ok.iter().flatten().any(|entry| entry.name == "Count"));
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "edit"))]
+    /// # fn main() {}
+    /// ```
+    pub fn iter_all_properties(&self) -> impl Iterator<Item = Result<PropertyEntry<'_>, Error>> {
+        self.objects().flat_map(|object| {
+            object
+                .direct_properties()
+                .into_iter()
+                .flat_map(|props| props.properties())
+                .map(move |property| {
+                    Ok(PropertyEntry {
+                        object,
+                        property,
+                        name: property.name()?,
+                        value: property.value()?,
+                        flags: property.flags()?,
+                    })
+                })
+        })
+    }
+
+    /// Resolves the object ID or the typed ID to the handle.
+    ///
+    /// Returns `None` if the object is not found or has a different type.
+    /// See [`object::id`](object/id/index.html) module for detail.
+    pub fn resolve<'a, I: Resolve<'a>>(&'a self, id: I) -> Option<I::Handle> {
+        id.resolve(self)
+    }
+
+    /// Returns a query matching all objects.
+    ///
+    /// See [`query`](query/index.html) module for detail.
+    #[cfg(feature = "query")]
+    pub fn query(&self) -> Query<'_> {
+        Query::new(self)
+    }
+
+    /// Walks the object graph with the default options.
+    ///
+    /// See [`walk`](walk/index.html) module for detail.
+    #[cfg(feature = "walk")]
+    pub fn walk<'a>(&'a self, visitor: &mut impl Visitor<'a>) {
+        self.walk_with(&WalkOptions::new(), visitor)
+    }
+
+    /// Walks the object graph with the given options.
+    ///
+    /// See [`walk`](walk/index.html) module for detail.
+    #[cfg(feature = "walk")]
+    pub fn walk_with<'a>(&'a self, options: &WalkOptions, visitor: &mut impl Visitor<'a>) {
+        walk::walk(self, options, visitor)
     }
 
     /// Returns `Document` object nodes, which have root object ID of scenes.
+    ///
+    /// Scenes are returned in the order of the nodes in the tree.
     pub fn scenes(&self) -> impl Iterator<Item = SceneHandle<'_>> {
-        self.objects.document_nodes().iter().map(move |obj_id| {
-            SceneHandle::new(obj_id.to_object_handle(self))
+        self.objects.document_nodes().iter().map(move |&index| {
+            SceneHandle::new(ObjectHandle::from_index(index, self))
                 .expect("Should never fail: Actually using `Document` objects")
         })
     }
+
+    /// Returns the object with the given raw FBX object ID.
+    ///
+    /// See [`ObjectHandle::fbx_id`].
+    pub fn object_by_fbx_id(&self, id: i64) -> Option<ObjectHandle<'_>> {
+        ObjectId::from_raw(id).to_object_handle(self)
+    }
+
+    /// Returns an iterator of objects with the given class and subclass.
+    ///
+    /// If `subclass` is `None`, objects with any subclass are returned.
+    /// Objects are returned in the order of the nodes in the tree.
+    ///
+    /// This uses an index built at load time, and does not scan the whole
+    /// document.
+    pub fn objects_by_class<'a>(
+        &'a self,
+        class: &str,
+        subclass: Option<&str>,
+    ) -> impl Iterator<Item = ObjectHandle<'a>> {
+        let indices = match (
+            self.lookup_symbol(class),
+            subclass.map(|v| self.lookup_symbol(v)),
+        ) {
+            (Some(class), None) => self.objects.object_indices_by_class(class, None),
+            (Some(class), Some(Some(subclass))) => {
+                self.objects.object_indices_by_class(class, Some(subclass))
+            }
+            _ => &[],
+        };
+        indices
+            .iter()
+            .map(move |&index| ObjectHandle::from_index(index, self))
+    }
+
+    /// Returns an iterator of objects with the given name.
+    ///
+    /// `name` is the object name without the class part following the
+    /// `"\u{0}\u{1}"` separator.
+    /// Objects are returned in the order of the nodes in the tree, and
+    /// [`ObjectHandle::class`] and [`ObjectHandle::subclass`] can be used to
+    /// tell them apart.
+    pub fn objects_by_name<'a>(&'a self, name: &str) -> impl Iterator<Item = ObjectHandle<'a>> {
+        self.objects_by_name_with_options(name, NameMatchOptions::new())
+    }
+
+    /// Returns an iterator of objects with the given name, compared with the
+    /// given options.
+    ///
+    /// If namespaces are not ignored, this uses an index built at load time.
+    /// Otherwise, all objects are scanned.
+    ///
+    /// ```
+    /// # #[cfg(feature = "edit")]
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// use fbxcel_dom::v7400::{
+    ///     edit::{DocumentBuilder, ModelKind},
+    ///     object::NameMatchOptions,
+    /// };
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let hips = builder.add_model("Rig:Hips", ModelKind::Null, None);
+    /// let doc = builder.build()?;
+    ///
+    /// assert_eq!(doc.objects_by_name("Hips").count(), 0);
+    /// let options = NameMatchOptions::new().ignore_namespace(true);
+    /// let found: Vec<_> = doc
+    ///     .objects_by_name_with_options("Hips", options)
+    ///     .map(|obj| obj.object_id())
+    ///     .collect();
+    /// assert_eq!(found, [hips]);
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "edit"))]
+    /// # fn main() {}
+    /// ```
+    pub fn objects_by_name_with_options<'a>(
+        &'a self,
+        name: &str,
+        options: NameMatchOptions,
+    ) -> impl Iterator<Item = ObjectHandle<'a>> {
+        let indexed: &[ObjectIndex] = if options.ignores_namespace() {
+            &[]
+        } else {
+            self.object_indices_by_name(name)
+        };
+        let scanned = if options.ignores_namespace() {
+            let name = name.to_owned();
+            Some(self.objects().filter(move |obj| {
+                obj.name_parts()
+                    .map_or(false, |parts| parts.matches(&name, options))
+            }))
+        } else {
+            None
+        };
+        indexed
+            .iter()
+            .map(move |&index| ObjectHandle::from_index(index, self))
+            .chain(scanned.into_iter().flatten())
+    }
+
+    /// Returns object indices with the given name.
+    fn object_indices_by_name(&self, name: &str) -> &[ObjectIndex] {
+        self.lookup_symbol(name)
+            .map_or(&[], |name| self.objects.object_indices_by_name(name))
+    }
+
+    /// Returns the only object with the given name.
+    ///
+    /// Returns `Ok(None)` if there are no such objects, and returns an error
+    /// listing the candidates if the name is ambiguous.
+    pub fn unique_object_by_name(&self, name: &str) -> Result<Option<ObjectHandle<'_>>, Error> {
+        self.unique_object_by_name_with_options(name, NameMatchOptions::new())
+    }
+
+    /// Returns the only object with the given name, compared with the given
+    /// options.
+    ///
+    /// See [`unique_object_by_name`](#method.unique_object_by_name) and
+    /// [`objects_by_name_with_options`](#method.objects_by_name_with_options).
+    pub fn unique_object_by_name_with_options(
+        &self,
+        name: &str,
+        options: NameMatchOptions,
+    ) -> Result<Option<ObjectHandle<'_>>, Error> {
+        let objects: Vec<_> = self.objects_by_name_with_options(name, options).collect();
+        match objects[..] {
+            [] => Ok(None),
+            [obj] => Ok(Some(obj)),
+            _ => {
+                let candidates = objects
+                    .iter()
+                    .map(|obj| {
+                        format!(
+                            "{} (id={}, class={:?}, subclass={:?})",
+                            obj.name().unwrap_or(name),
+                            obj.object_id().raw(),
+                            obj.class(),
+                            obj.subclass()
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                Err(format_err!(
+                    "Object name {:?} is ambiguous: {} objects found: {}",
+                    name,
+                    objects.len(),
+                    candidates.join(", ")
+                ))
+            }
+        }
+    }
+
+    /// Returns an iterator of geometry mesh objects.
+    pub fn meshes(&self) -> impl Iterator<Item = geometry::MeshHandle<'_>> {
+        self.objects_by_class("Geometry", Some("Mesh"))
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Geometry(geometry::TypedGeometryHandle::Mesh(o)) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns an iterator of material objects.
+    pub fn materials(&self) -> impl Iterator<Item = material::MaterialHandle<'_>> {
+        self.objects_by_class("Material", None)
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Material(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns an iterator of camera model objects.
+    pub fn cameras(&self) -> impl Iterator<Item = model::CameraHandle<'_>> {
+        self.objects_by_class("Model", Some("Camera"))
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Model(model::TypedModelHandle::Camera(o)) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns an iterator of light model objects.
+    pub fn lights(&self) -> impl Iterator<Item = model::LightHandle<'_>> {
+        self.objects_by_class("Model", Some("Light"))
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Model(model::TypedModelHandle::Light(o)) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns an iterator of animation stack objects.
+    ///
+    /// ```
+    /// use fbxcel_dom::{
+    ///     fbxcel::{low::v7400::AttributeValue, tree::v7400::Tree},
+    ///     v7400::Loader,
+    /// };
+    ///
+    /// let mut tree = Tree::default();
+    /// let root = tree.root().node_id();
+    /// tree.append_new(root, "Documents");
+    /// tree.append_new(root, "Connections");
+    /// let objects = tree.append_new(root, "Objects");
+    /// let stack = tree.append_new(objects, "AnimationStack");
+    /// tree.append_attribute(stack, AttributeValue::I64(42));
+    /// tree.append_attribute(stack, AttributeValue::String("Take 001\u{0}\u{1}AnimStack".into()));
+    /// tree.append_attribute(stack, AttributeValue::String(String::new()));
+    /// let doc = Loader::new().load_from_tree(tree)?;
+    ///
+    /// let stacks: Vec<_> = doc.anim_stacks().collect();
+    /// assert_eq!(stacks.len(), 1);
+    /// assert_eq!(stacks[0].name(), Some("Take 001"));
+    /// # Ok::<_, fbxcel_dom::v7400::LoadError>(())
+    /// ```
+    pub fn anim_stacks(&self) -> impl Iterator<Item = animation::AnimationStackHandle<'_>> {
+        self.objects_by_class("AnimStack", None)
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::AnimationStack(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns an iterator of character objects.
+    pub fn characters(&self) -> impl Iterator<Item = character::CharacterHandle<'_>> {
+        self.objects_by_class("Character", None)
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Character(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns an iterator of display layer objects.
+    pub fn display_layers(
+        &self,
+    ) -> impl Iterator<Item = collection::CollectionExclusiveHandle<'_>> {
+        self.objects_by_class("DisplayLayer", None)
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::CollectionExclusive(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns an iterator of constraint objects.
+    pub fn constraints(&self) -> impl Iterator<Item = constraint::TypedConstraintHandle<'_>> {
+        self.objects_by_class("Constraint", None)
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Constraint(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns an iterator of objects with plugin-defined classes.
+    ///
+    /// See [`PluginObjectHandle::from_object`] for the objects included.
+    pub fn plugin_objects(&self) -> impl Iterator<Item = PluginObjectHandle<'_>> {
+        self.objects().filter_map(PluginObjectHandle::from_object)
+    }
+
+    /// Returns an iterator of scene reference objects.
+    pub fn scene_references(&self) -> impl Iterator<Item = reference::SceneReferenceHandle<'_>> {
+        self.objects_by_class("SceneReference", None)
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::SceneReference(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns an iterator of selection set objects.
+    pub fn selection_sets(&self) -> impl Iterator<Item = selection::SelectionSetHandle<'_>> {
+        self.objects_by_class("SelectionSet", None)
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::SelectionSet(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns an iterator of models which have no parent model.
+    pub fn model_roots(&self) -> impl Iterator<Item = TypedModelHandle<'_>> {
+        self.objects()
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Model(o) => Some(o),
+                _ => None,
+            })
+            .filter(|model| model.parent_model().is_none())
+    }
+
+    /// Returns the rest-pose segments of all limb nodes.
+    ///
+    /// Bones whose segments cannot be computed are skipped with warnings.
+    /// See [`LimbNodeHandle::segment`] for detail.
+    ///
+    /// [`LimbNodeHandle::segment`]:
+    /// object/model/struct.LimbNodeHandle.html#method.segment
+    pub fn bone_segments(&self) -> Vec<(model::LimbNodeHandle<'_>, BoneSegment)> {
+        let mut cache = TransformCache::new();
+        self.objects_by_class("Model", Some("LimbNode"))
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Model(TypedModelHandle::LimbNode(o)) => Some(o),
+                _ => None,
+            })
+            .filter_map(|bone| match bone.segment_with(&mut cache) {
+                Ok(segment) => Some((bone, segment)),
+                Err(e) => {
+                    warn!(
+                        "Failed to compute bone segment: object_id={:?}, error={}",
+                        bone.object_id(),
+                        e
+                    );
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the model with the given slash-separated path, such as
+    /// `"Root/Hips/Spine"`.
+    ///
+    /// If multiple models match, the first one found is returned.
+    pub fn model_by_path(&self, path: &str) -> Option<TypedModelHandle<'_>> {
+        self.model_by_path_with_options(path, PathOptions::default())
+    }
+
+    /// Returns the model with the given slash-separated path using the given
+    /// options.
+    ///
+    /// If multiple models match, the first one found is returned.
+    pub fn model_by_path_with_options(
+        &self,
+        path: &str,
+        options: PathOptions,
+    ) -> Option<TypedModelHandle<'_>> {
+        model::find_by_path(self, path, options)
+    }
 }
 
 impl AsRef<Tree> for Document {
@@ -0,0 +1,83 @@
+//! Document consistency analysis.
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::v7400::{
+    object::{ObjectConnection, ObjectHandle, ObjectId},
+    Document,
+};
+
+impl Document {
+    /// Returns objects which are not reachable from the scene roots.
+    ///
+    /// Reachability is checked by following connections in both directions,
+    /// starting from the root objects of the scenes (or the object ID `0` if
+    /// no scene root is available).
+    /// This means objects indirectly attached to the scene (such as animation
+    /// stacks connected through layers and curve nodes) are not reported.
+    ///
+    /// `Document` objects are never reported.
+    /// Note that some valid objects (such as bind poses, which reference
+    /// nodes by properties rather than by connections) may have no
+    /// connections at all.
+    ///
+    /// Objects are returned in ascending order of object IDs.
+    pub fn orphan_objects(&self) -> Vec<ObjectHandle<'_>> {
+        let mut roots = self
+            .scenes()
+            .filter_map(|scene| scene.root_object_id().ok())
+            .collect::<Vec<_>>();
+        if roots.is_empty() {
+            roots.push(ObjectId::new(0));
+        }
+
+        let mut visited = roots.iter().cloned().collect::<HashSet<_>>();
+        let mut queue = roots.into_iter().collect::<VecDeque<_>>();
+        while let Some(id) = queue.pop_front() {
+            let neighbors = id
+                .source_connections(self)
+                .map(|conn| conn.source_id())
+                .chain(
+                    id.destination_connections(self)
+                        .map(|conn| conn.destination_id()),
+                );
+            for neighbor in neighbors {
+                if visited.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        let documents = self
+            .objects_cache()
+            .document_nodes()
+            .iter()
+            .map(|&index| self.objects_cache().meta().object_id(index))
+            .collect::<HashSet<_>>();
+        let mut orphans = self
+            .objects()
+            .filter(|obj| {
+                !visited.contains(&obj.object_id()) && !documents.contains(&obj.object_id())
+            })
+            .collect::<Vec<_>>();
+        orphans.sort_by_key(|obj| obj.object_id());
+        orphans
+    }
+
+    /// Returns an iterator of connections referencing nonexistent objects.
+    ///
+    /// Connections to the scene root objects (which usually have no
+    /// corresponding object nodes) are not reported.
+    pub fn dangling_connections(&self) -> impl Iterator<Item = ObjectConnection<'_>> {
+        let mut roots = self
+            .scenes()
+            .filter_map(|scene| scene.root_object_id().ok())
+            .collect::<HashSet<_>>();
+        roots.insert(ObjectId::new(0));
+        self.connections().filter(move |conn| {
+            let is_missing =
+                |id: ObjectId| !roots.contains(&id) && id.to_object_handle(self).is_none();
+            is_missing(conn.source_id()) || is_missing(conn.destination_id())
+        })
+    }
+}
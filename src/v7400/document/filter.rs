@@ -0,0 +1,196 @@
+//! Load-time node filtering.
+
+use std::collections::HashSet;
+
+use fbxcel::{
+    low::v7400::{AttributeValue, FbxFooter},
+    pull_parser::{
+        v7400::{Event, Parser},
+        Error as ParserError, ParserSource,
+    },
+    tree::v7400::{LoadError as TreeLoadError, NodeHandle, NodeId, Tree},
+};
+use log::{debug, trace};
+
+use crate::v7400::LoadError;
+
+use super::limits::{LimitedLoader, ResourceLimits};
+
+/// Paths of the animation nodes.
+pub(crate) const ANIMATION_PATHS: &[&str] = &[
+    "Takes",
+    "Objects/AnimationStack",
+    "Objects/AnimationLayer",
+    "Objects/AnimationCurveNode",
+    "Objects/AnimationCurve",
+];
+
+/// Paths of the embedded content nodes.
+pub(crate) const EMBEDDED_CONTENT_PATHS: &[&str] = &["Objects/Video/Content"];
+
+/// Node filter to skip subtrees on loading.
+#[derive(Default, Debug, Clone)]
+pub(crate) struct NodeFilter {
+    /// Paths of the nodes to skip, such as `Objects/AnimationCurve`.
+    paths: Vec<String>,
+}
+
+impl NodeFilter {
+    /// Adds a path of the nodes to skip.
+    pub(crate) fn add(&mut self, path: &str) {
+        let path = path.trim_matches('/');
+        if !self.paths.iter().any(|v| v == path) {
+            self.paths.push(path.to_owned());
+        }
+    }
+
+    /// Returns true if no nodes are skipped.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Returns true if the node with the given path should be skipped.
+    ///
+    /// `path` is the names of the node and its ancestors, from the toplevel.
+    pub(crate) fn matches<S: AsRef<str>>(&self, path: &[S]) -> bool {
+        self.paths
+            .iter()
+            .any(|v| v.split('/').eq(path.iter().map(AsRef::as_ref)))
+    }
+
+    /// Returns true if the node with the given slash-separated path should
+    /// be skipped.
+    pub(crate) fn matches_path(&self, path: &str) -> bool {
+        self.paths.iter().any(|v| v == path)
+    }
+
+    /// Loads a tree from the given parser, skipping the filtered nodes.
+    ///
+    /// Returns an error if a resource limit is exceeded.
+    #[allow(clippy::type_complexity)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "load_tree", skip_all))]
+    pub(crate) fn load_tree<R: ParserSource>(
+        &self,
+        parser: &mut Parser<R>,
+        limits: &ResourceLimits,
+    ) -> Result<(Tree, Result<Box<FbxFooter>, ParserError>), LoadError> {
+        debug!("Loading FBX data tree with filter: paths={:?}", self.paths);
+        if parser.is_used() {
+            return Err(TreeLoadError::BadParser.into());
+        }
+
+        let mut tree = Tree::default();
+        let mut open_nodes = vec![tree.root().node_id()];
+        let mut path: Vec<String> = Vec::new();
+        let mut nodes = 0;
+        let footer = loop {
+            match parser.next_event().map_err(LoadError::new)? {
+                Event::StartNode(start) => {
+                    path.push(start.name().to_owned());
+                    if self.matches(&path) {
+                        trace!("Skipping node: path={:?}", path);
+                        path.pop();
+                        parser.skip_current_node().map_err(LoadError::new)?;
+                        continue;
+                    }
+                    nodes += 1;
+                    limits.check_nodes(nodes)?;
+                    let joined = path.join("/");
+                    limits.check_depth(&joined, path.len())?;
+                    let parent = *open_nodes
+                        .last()
+                        .expect("Should never fail: open nodes stack should not be empty");
+                    let loader = LimitedLoader {
+                        path: &joined,
+                        limits,
+                    };
+                    let attributes = start
+                        .attributes()
+                        .into_iter(std::iter::repeat(loader))
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(LoadError::new)?;
+                    let current = tree.append_new(
+                        parent,
+                        path.last().expect("Should never fail: path is just pushed"),
+                    );
+                    tree.set_attributes_vec(current, attributes);
+                    open_nodes.push(current);
+                }
+                Event::EndNode => {
+                    path.pop();
+                    open_nodes.pop();
+                }
+                Event::EndFbx(footer) => break footer,
+            }
+        };
+
+        Ok((tree, footer))
+    }
+
+    /// Removes the filtered nodes from the tree.
+    ///
+    /// Connections to the removed objects are also removed.
+    pub(crate) fn apply(&self, tree: &mut Tree) {
+        let mut removed = Vec::new();
+        let mut path = Vec::new();
+        collect_matches(self, tree.root(), &mut path, &mut removed);
+        for node_id in removed {
+            tree.detach(node_id);
+        }
+        remove_dangling_connections(tree);
+    }
+}
+
+/// Collects the descendant nodes to be removed.
+fn collect_matches<'a>(
+    filter: &NodeFilter,
+    node: NodeHandle<'a>,
+    path: &mut Vec<&'a str>,
+    removed: &mut Vec<NodeId>,
+) {
+    for child in node.children() {
+        path.push(child.name());
+        if filter.matches(path) {
+            removed.push(child.node_id());
+        } else {
+            collect_matches(filter, child, path, removed);
+        }
+        path.pop();
+    }
+}
+
+/// Removes connections from or to objects not in the tree.
+///
+/// Connections to the scene root (object ID `0`) are kept.
+fn remove_dangling_connections(tree: &mut Tree) {
+    let root = tree.root();
+    let mut obj_ids: HashSet<i64> = root
+        .children_by_name("Objects")
+        .flat_map(|node| node.children())
+        .chain(
+            root.children_by_name("Documents")
+                .flat_map(|node| node.children_by_name("Document")),
+        )
+        .filter_map(|node| node.attributes().first().and_then(AttributeValue::get_i64))
+        .collect();
+    obj_ids.insert(0);
+
+    let dangling: Vec<_> = root
+        .children_by_name("Connections")
+        .flat_map(|node| node.children_by_name("C"))
+        .filter(|node| {
+            let attrs = node.attributes();
+            [attrs.get(1), attrs.get(2)]
+                .iter()
+                .filter_map(|v| v.and_then(AttributeValue::get_i64))
+                .any(|id| !obj_ids.contains(&id))
+        })
+        .map(|node| node.node_id())
+        .collect();
+    if !dangling.is_empty() {
+        trace!("Removing {} connections to skipped objects", dangling.len());
+    }
+    for node_id in dangling {
+        tree.detach(node_id);
+    }
+}
@@ -0,0 +1,486 @@
+//! Resumable loader for chunked binary FBX data.
+
+use std::{convert::TryFrom, str};
+
+use fbxcel::{
+    low::{v7400::FbxFooter, FbxHeader, FbxVersion},
+    tree::v7400::{NodeId, Tree},
+};
+use log::{trace, warn};
+
+use crate::v7400::{
+    version::{self, Capabilities},
+    Document, LoadError, Loader,
+};
+
+use super::recovery::{self, read_u32, read_u64};
+
+/// Length of the FBX header.
+const HEADER_LEN: usize = 27;
+/// Length of the FBX footer without padding.
+const FOOTER_LEN: usize = 160;
+
+/// Resumable loader for binary FBX data arriving in chunks.
+///
+/// The loader does not read by itself: the data is passed by
+/// [`feed()`][`Self::feed`] as it arrives, and the complete node records are
+/// parsed immediately, so the caller (such as an async task reading an
+/// upload) never blocks on I/O inside the loader.
+/// Only the incomplete node record at the end of the fed data is buffered.
+///
+/// Created by [`Loader::incremental()`].
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let mut reader = std::io::empty();
+/// use std::io::Read;
+/// use fbxcel_dom::v7400::Loader;
+///
+/// let mut loader = Loader::new().incremental();
+/// let mut buf = [0; 8192];
+/// loop {
+///     let len = reader.read(&mut buf)?;
+///     if len == 0 {
+///         break;
+///     }
+///     loader.feed(&buf[..len])?;
+/// }
+/// # if false {
+/// let doc = loader.finish()?;
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// In the non-strict mode (see [`Loader::strict()`]), broken data cannot be
+/// recovered from in the middle of the stream, so the fed data is buffered
+/// and loaded on [`finish()`][`Self::finish`].
+#[derive(Debug)]
+pub struct IncrementalLoader {
+    /// Document loader.
+    loader: Loader,
+    /// Parsing state.
+    state: State,
+    /// Unconsumed data.
+    buf: Vec<u8>,
+    /// Position of the first unconsumed byte in `buf`.
+    pos: usize,
+    /// Offset of `buf[0]` in the whole data.
+    offset: u64,
+    /// FBX version in the header.
+    fbx_version: Option<FbxVersion>,
+    /// Whether the node headers use 64-bit fields.
+    large: bool,
+    /// Tree being built.
+    tree: Tree,
+    /// Number of nodes read.
+    nodes: usize,
+    /// Nodes whose children are being read.
+    open_nodes: Vec<OpenNode>,
+    /// End offset of the node being skipped.
+    skip_until: Option<u64>,
+}
+
+/// Parsing state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Reading the header.
+    Header,
+    /// Reading node records.
+    Nodes,
+    /// Node records are read, and reading the footer.
+    Footer,
+    /// Failed with an error.
+    Failed,
+}
+
+/// Node whose children are being read.
+#[derive(Debug, Clone)]
+struct OpenNode {
+    /// Node ID.
+    id: NodeId,
+    /// End offset of the node.
+    end: u64,
+    /// Path of the node.
+    path: String,
+}
+
+impl IncrementalLoader {
+    /// Creates a new incremental loader.
+    pub(super) fn new(loader: Loader) -> Self {
+        Self {
+            loader,
+            state: State::Header,
+            buf: Vec::new(),
+            pos: 0,
+            offset: 0,
+            fbx_version: None,
+            large: false,
+            tree: Tree::default(),
+            nodes: 0,
+            open_nodes: Vec::new(),
+            skip_until: None,
+        }
+    }
+
+    /// Parses the next chunk of the data.
+    ///
+    /// Returns an error if the data is broken, and fails all following calls
+    /// after that.
+    ///
+    /// The chunks may split the data at any position, such as in the middle
+    /// of a node header or a compressed array:
+    ///
+    /// ```
+    /// # #[cfg(feature = "writer")]
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::io::Cursor;
+    ///
+    /// use fbxcel_dom::{
+    ///     fbxcel::low::FbxVersion,
+    ///     v7400::{
+    ///         edit::{DocumentBuilder, ModelKind},
+    ///         write::Writer,
+    ///         Document, Loader,
+    ///     },
+    /// };
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let model = builder.add_model("Quad", ModelKind::Mesh, None);
+    /// let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+    /// builder.add_mesh("Quad", &positions, &[&[0, 1, 2, 3]], Some(model));
+    /// let doc = builder.build()?;
+    /// // Compress all arrays.
+    /// let writer = Writer::new(FbxVersion::V7_4).array_compression(Some(1));
+    /// let write = |doc: &Document| -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    ///     Ok(writer.write_document(doc, Cursor::new(Vec::new()))?.into_inner())
+    /// };
+    /// let data = write(&doc)?;
+    /// let whole = Loader::new().load_from_slice(&data)?;
+    ///
+    /// // Feeding a byte at a time splits every node header and array.
+    /// for chunk_len in [1, 7, 64] {
+    ///     let mut loader = Loader::new().incremental();
+    ///     for chunk in data.chunks(chunk_len) {
+    ///         loader.feed(chunk)?;
+    ///     }
+    ///     assert!(loader.is_complete());
+    ///     assert_eq!(loader.bytes_fed(), data.len() as u64);
+    ///     let chunked = loader.finish()?;
+    ///
+    ///     assert_eq!(chunked.objects().count(), whole.objects().count());
+    ///     let vertices = |doc: &Document| -> anyhow::Result<Vec<_>> {
+    ///         let mesh = doc.meshes().next().expect("should exist");
+    ///         Ok(mesh.polygon_vertices()?.raw_control_points()?.collect())
+    ///     };
+    ///     assert_eq!(vertices(&chunked)?, vertices(&whole)?);
+    ///     assert_eq!(write(&chunked)?, write(&whole)?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "writer"))]
+    /// # fn main() {}
+    /// ```
+    pub fn feed(&mut self, data: &[u8]) -> Result<(), LoadError> {
+        if self.state == State::Failed {
+            return Err(LoadError::new(
+                "Incremental loader has already failed with an error",
+            ));
+        }
+        if self.loader.tolerant {
+            self.buf.extend_from_slice(data);
+            return Ok(());
+        }
+
+        let data = self.skip(data);
+        self.buf.extend_from_slice(data);
+        let res = self.parse_available();
+        // Drop the consumed data.
+        self.buf.drain(..self.pos);
+        self.offset += self.pos as u64;
+        self.pos = 0;
+        if res.is_err() {
+            self.state = State::Failed;
+        }
+
+        res
+    }
+
+    /// Returns the number of bytes fed so far.
+    pub fn bytes_fed(&self) -> u64 {
+        self.offset + self.buf.len() as u64
+    }
+
+    /// Returns true if all node records are read.
+    ///
+    /// Only the footer may follow after this.
+    /// This is always false in the non-strict mode, since the data is loaded
+    /// on [`finish()`][`Self::finish`].
+    pub fn is_complete(&self) -> bool {
+        self.state == State::Footer
+    }
+
+    /// Finishes loading and returns the document.
+    ///
+    /// Returns an error if the node records are incomplete (in the strict
+    /// mode).
+    /// The footer is loaded if available.
+    ///
+    /// ```
+    /// # #[cfg(feature = "writer")]
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::io::Cursor;
+    ///
+    /// use fbxcel_dom::{
+    ///     fbxcel::low::FbxVersion,
+    ///     v7400::{
+    ///         edit::{DocumentBuilder, ModelKind},
+    ///         write::Writer,
+    ///         Loader,
+    ///     },
+    /// };
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// builder.add_model("Cube", ModelKind::Null, None);
+    /// let doc = builder.build()?;
+    /// let data = Writer::new(FbxVersion::V7_4)
+    ///     .write_document(&doc, Cursor::new(Vec::new()))?
+    ///     .into_inner();
+    ///
+    /// // Stop after the header and a part of the first node.
+    /// let mut loader = Loader::new().incremental();
+    /// loader.feed(&data[..64])?;
+    /// assert!(!loader.is_complete());
+    /// let err = loader.finish().expect_err("should fail");
+    /// assert!(err.to_string().contains("Unexpected end of FBX data"), "{}", err);
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "writer"))]
+    /// # fn main() {}
+    /// ```
+    pub fn finish(self) -> Result<Document, LoadError> {
+        if self.loader.tolerant {
+            return self.loader.load_tolerant(&self.buf);
+        }
+        match self.state {
+            State::Footer => {}
+            State::Failed => {
+                return Err(LoadError::new(
+                    "Incremental loader has already failed with an error",
+                ))
+            }
+            _ => {
+                return Err(LoadError::new(format!(
+                    "Unexpected end of FBX data at offset {}",
+                    self.bytes_fed()
+                )))
+            }
+        }
+        let fbx_version = self
+            .fbx_version
+            .expect("Should never fail: the header is read before node records");
+        let footer = load_footer(&self.buf[self.pos..], fbx_version);
+        if footer.is_none() {
+            warn!("Failed to load FBX footer");
+        }
+
+        self.loader
+            .fbx_version(fbx_version)
+            .load_impl(self.tree, footer, Vec::new())
+    }
+
+    /// Consumes the data of the node being skipped, and returns the rest.
+    fn skip<'a>(&mut self, data: &'a [u8]) -> &'a [u8] {
+        let skip_until = match self.skip_until {
+            Some(v) => v,
+            None => return data,
+        };
+        // Nothing is buffered while skipping.
+        debug_assert_eq!(self.pos, self.buf.len());
+        let current = self.bytes_fed();
+        let len = std::cmp::min(skip_until - current, data.len() as u64) as usize;
+        self.offset += len as u64;
+        if self.offset + self.buf.len() as u64 == skip_until {
+            self.skip_until = None;
+        }
+        &data[len..]
+    }
+
+    /// Parses the buffered data as far as possible.
+    fn parse_available(&mut self) -> Result<(), LoadError> {
+        loop {
+            let progressed = match self.state {
+                State::Header => self.read_header()?,
+                State::Nodes => self.read_node()?,
+                State::Footer | State::Failed => false,
+            };
+            if !progressed {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Reads the header.
+    ///
+    /// Returns `Ok(false)` if more data is needed.
+    fn read_header(&mut self) -> Result<bool, LoadError> {
+        let header = match self.buf.get(self.pos..self.pos + HEADER_LEN) {
+            Some(v) => v,
+            None => return Ok(false),
+        };
+        let fbx_version = FbxHeader::load(header).map_err(LoadError::new)?.version();
+        trace!("Loading FBX DOM incrementally: version={:?}", fbx_version);
+        self.fbx_version = Some(fbx_version);
+        self.large = Capabilities::new(fbx_version).has_64bit_offsets();
+        self.pos += HEADER_LEN;
+        self.state = State::Nodes;
+        Ok(true)
+    }
+
+    /// Reads a node record (without children) or a node end marker.
+    ///
+    /// Returns `Ok(false)` if more data is needed.
+    fn read_node(&mut self) -> Result<bool, LoadError> {
+        let field_len = if self.large { 8 } else { 4 };
+        let header_len = field_len * 3 + 1;
+        let start = self.offset + self.pos as u64;
+        let data = &self.buf[self.pos..];
+        if data.len() < header_len {
+            return Ok(false);
+        }
+        let field = |i: usize| {
+            let bytes = &data[field_len * i..];
+            if field_len == 8 {
+                read_u64(bytes)
+            } else {
+                u64::from(read_u32(bytes))
+            }
+        };
+        let (end_offset, num_attrs, attrs_len) = (field(0), field(1), field(2));
+        let name_len = usize::from(data[field_len * 3]);
+
+        if end_offset == 0 {
+            if num_attrs != 0 || attrs_len != 0 || name_len != 0 {
+                return Err(LoadError::new(format!(
+                    "Broken node end marker at offset {}",
+                    start
+                )));
+            }
+            self.pos += header_len;
+            match self.open_nodes.pop() {
+                Some(node) if node.end != start + header_len as u64 => {
+                    return Err(LoadError::new(format!(
+                        "Node end marker at offset {} does not match the end of node {:?}",
+                        start, node.path
+                    )));
+                }
+                Some(_) => {}
+                None => {
+                    trace!("Loaded FBX node records incrementally");
+                    self.state = State::Footer;
+                }
+            }
+            return Ok(true);
+        }
+
+        let name = match data.get(header_len..header_len + name_len) {
+            Some(v) => str::from_utf8(v).map_err(|e| {
+                LoadError::new(format!("Invalid node name at offset {}: {}", start, e))
+            })?,
+            None => return Ok(false),
+        };
+        let parent = self.open_nodes.last();
+        let path = match parent {
+            Some(parent) => format!("{}/{}", parent.path, name),
+            None => name.to_owned(),
+        };
+        let attrs_start = start + (header_len + name_len) as u64;
+        let attrs_end = attrs_start
+            .checked_add(attrs_len)
+            .filter(|&v| v <= end_offset)
+            .ok_or_else(|| {
+                LoadError::new(format!(
+                    "Node {:?} at offset {} has attributes beyond its end",
+                    path, start
+                ))
+            })?;
+        if let Some(parent) = parent.filter(|parent| end_offset > parent.end) {
+            return Err(LoadError::new(format!(
+                "Node {:?} at offset {} exceeds the end of its parent {:?}",
+                path, start, parent.path
+            )));
+        }
+
+        if self.loader.skipped.matches_path(&path) {
+            trace!("Skipping node: path={:?}", path);
+            let available = self.buf.len() - self.pos;
+            let len = std::cmp::min(end_offset - start, available as u64) as usize;
+            self.pos += len;
+            if start + (len as u64) < end_offset {
+                self.skip_until = Some(end_offset);
+                // Wait for more data, and skip it in `skip()`.
+                debug_assert_eq!(self.pos, self.buf.len());
+                return Ok(false);
+            }
+            return Ok(true);
+        }
+
+        let record_len = usize::try_from(attrs_end - start)
+            .map_err(|_| LoadError::new(format!("Node {:?} is too large", path)))?;
+        let attrs = match data.get(header_len + name_len..record_len) {
+            Some(v) => v,
+            None => return Ok(false),
+        };
+        self.nodes += 1;
+        let limits = &self.loader.limits;
+        limits.check_nodes(self.nodes)?;
+        limits.check_depth(&path, self.open_nodes.len() + 1)?;
+        let (attrs, warnings) = recovery::read_attributes(attrs, &path, num_attrs, limits)?;
+        if let Some(warning) = warnings.into_iter().next() {
+            return Err(LoadError::new(warning));
+        }
+
+        let parent_id = parent.map_or_else(|| self.tree.root().node_id(), |parent| parent.id);
+        let node_id = self.tree.append_new(parent_id, name);
+        self.tree.set_attributes_vec(node_id, attrs);
+        if attrs_end < end_offset {
+            self.open_nodes.push(OpenNode {
+                id: node_id,
+                end: end_offset,
+                path,
+            });
+        }
+        self.pos += record_len;
+
+        Ok(true)
+    }
+}
+
+/// Loads the footer from the data following the node records.
+///
+/// Returns `None` if the footer is broken.
+fn load_footer(data: &[u8], fbx_version: FbxVersion) -> Option<FbxFooter> {
+    let padding_len = data.len().checked_sub(FOOTER_LEN)?;
+    if padding_len >= 16 {
+        return None;
+    }
+    let mut unknown1 = [0; 16];
+    unknown1.copy_from_slice(&data[..16]);
+    let rest = &data[16 + padding_len..];
+    let mut unknown2 = [0; 4];
+    unknown2.copy_from_slice(&rest[..4]);
+    let version = version::from_raw(read_u32(&rest[4..8]));
+    if version != fbx_version {
+        return None;
+    }
+    let mut unknown3 = [0; 16];
+    unknown3.copy_from_slice(&rest[rest.len() - 16..]);
+
+    Some(FbxFooter {
+        unknown1,
+        padding_len: padding_len as u8,
+        unknown2,
+        fbx_version: version,
+        unknown3,
+    })
+}
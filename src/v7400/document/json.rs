@@ -0,0 +1,126 @@
+//! JSON dump of the node tree.
+
+use anyhow::Error;
+use fbxcel::{low::v7400::AttributeValue, tree::v7400::NodeHandle};
+use serde_json::{json, Map, Value};
+
+use crate::v7400::Document;
+
+impl Document {
+    /// Returns the whole node tree as JSON.
+    ///
+    /// The result is an array of the toplevel nodes, and each node is an
+    /// object with `name`, `attributes`, and `children` (omitted if empty).
+    /// Each attribute is an object with the `type` (such as `"I32"` or
+    /// `"ArrF64"`) and the `value`.
+    /// Arrays and binaries additionally have the `len`, and only the first
+    /// `max_array_len` elements are written if given.
+    /// Binaries are written as arrays of bytes.
+    ///
+    /// Compressed arrays not decoded yet (see
+    /// [`Loader::lazy_arrays()`][`super::Loader::lazy_arrays`]) are decoded
+    /// for the dump, and an error is returned if one is broken.
+    /// Non-finite floating point values are written as `null`.
+    ///
+    /// ```
+    /// # #[cfg(feature = "edit")]
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// use fbxcel_dom::v7400::edit::{DocumentBuilder, ModelKind};
+    /// use serde_json::{json, Value};
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let model = builder.add_model("Quad", ModelKind::Mesh, None);
+    /// let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+    /// builder.add_mesh("Quad", &positions, &[&[0, 1, 2, 3]], Some(model));
+    /// let doc = builder.build()?;
+    ///
+    /// let json = doc.tree_to_json(Some(2))?;
+    /// let child = |nodes: &Value, name: &str| -> Value {
+    ///     nodes
+    ///         .as_array()
+    ///         .and_then(|nodes| nodes.iter().find(|node| node["name"] == name))
+    ///         .cloned()
+    ///         .unwrap_or_else(|| panic!("`{}` node should exist", name))
+    /// };
+    /// let objects = child(&json, "Objects");
+    /// let geometry = child(&objects["children"], "Geometry");
+    /// assert_eq!(geometry["attributes"][2], json!({ "type": "String", "value": "Mesh" }));
+    /// // Arrays are truncated, but have the original length.
+    /// assert_eq!(
+    ///     child(&geometry["children"], "Vertices"),
+    ///     json!({
+    ///         "name": "Vertices",
+    ///         "attributes": [{ "type": "ArrF64", "len": 12, "value": [0.0, 0.0] }],
+    ///     })
+    /// );
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "edit"))]
+    /// # fn main() {}
+    /// ```
+    pub fn tree_to_json(&self, max_array_len: Option<usize>) -> Result<Value, Error> {
+        self.tree
+            .root()
+            .children()
+            .map(|node| self.node_to_json(node, max_array_len))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array)
+    }
+
+    /// Returns the node and its descendants as JSON.
+    fn node_to_json(
+        &self,
+        node: NodeHandle<'_>,
+        max_array_len: Option<usize>,
+    ) -> Result<Value, Error> {
+        let mut attributes = Vec::with_capacity(node.attributes().len());
+        for index in 0..node.attributes().len() {
+            if let Some(attr) = self.attribute(&node, index)? {
+                attributes.push(attribute_to_json(attr, max_array_len));
+            }
+        }
+
+        let mut map = Map::new();
+        map.insert("name".to_owned(), Value::from(node.name()));
+        map.insert("attributes".to_owned(), Value::Array(attributes));
+        let children = node
+            .children()
+            .map(|child| self.node_to_json(child, max_array_len))
+            .collect::<Result<Vec<_>, _>>()?;
+        if !children.is_empty() {
+            map.insert("children".to_owned(), Value::Array(children));
+        }
+
+        Ok(Value::Object(map))
+    }
+}
+
+/// Returns the attribute as JSON.
+fn attribute_to_json(attr: &AttributeValue, max_array_len: Option<usize>) -> Value {
+    /// Returns the truncated array.
+    fn array<T: Clone + Into<Value>>(ty: &str, v: &[T], max_len: Option<usize>) -> Value {
+        let len = max_len.map_or(v.len(), |max| v.len().min(max));
+        let values = v[..len]
+            .iter()
+            .cloned()
+            .map(Into::into)
+            .collect::<Vec<Value>>();
+        json!({ "type": ty, "len": v.len(), "value": values })
+    }
+
+    match attr {
+        AttributeValue::Bool(v) => json!({ "type": "Bool", "value": v }),
+        AttributeValue::I16(v) => json!({ "type": "I16", "value": v }),
+        AttributeValue::I32(v) => json!({ "type": "I32", "value": v }),
+        AttributeValue::I64(v) => json!({ "type": "I64", "value": v }),
+        AttributeValue::F32(v) => json!({ "type": "F32", "value": v }),
+        AttributeValue::F64(v) => json!({ "type": "F64", "value": v }),
+        AttributeValue::ArrBool(v) => array("ArrBool", v, max_array_len),
+        AttributeValue::ArrI32(v) => array("ArrI32", v, max_array_len),
+        AttributeValue::ArrI64(v) => array("ArrI64", v, max_array_len),
+        AttributeValue::ArrF32(v) => array("ArrF32", v, max_array_len),
+        AttributeValue::ArrF64(v) => array("ArrF64", v, max_array_len),
+        AttributeValue::String(v) => json!({ "type": "String", "value": v }),
+        AttributeValue::Binary(v) => array("Binary", v, max_array_len),
+    }
+}
@@ -0,0 +1,207 @@
+//! Lazily decoded array attributes.
+
+use std::collections::HashMap;
+
+use anyhow::{format_err, Error};
+use fbxcel::{
+    low::v7400::AttributeValue,
+    tree::v7400::{NodeId, Tree},
+};
+use log::warn;
+use once_cell::sync::OnceCell;
+
+use crate::v7400::LoadWarning;
+
+use super::{
+    memory,
+    recovery::{self, DeferredArray},
+};
+
+/// Compressed array attributes decoded on first use.
+#[derive(Default, Debug, Clone)]
+pub(crate) struct LazyArrays {
+    /// Arrays by the node and the attribute index.
+    arrays: HashMap<(NodeId, usize), LazyArray>,
+    /// Whether to use the broken arrays as far as decoded instead of failing.
+    tolerant: bool,
+}
+
+/// Compressed array attribute.
+#[derive(Debug, Clone)]
+struct LazyArray {
+    /// Path of the node.
+    path: String,
+    /// Type code of the attribute.
+    type_code: u8,
+    /// Compressed data.
+    raw: Vec<u8>,
+    /// Declared elements count.
+    declared: usize,
+    /// Whether the data is truncated.
+    truncated: bool,
+    /// Decoded value and the warning if the data is broken.
+    decoded: OnceCell<(AttributeValue, Option<LoadWarning>)>,
+    /// Sequence number in the order the arrays are read from the file.
+    order: usize,
+    /// Maximum decompressed size in bytes.
+    max_bytes: usize,
+}
+
+impl LazyArray {
+    /// Returns the decoded value and the warning, decoding it on first use.
+    fn decoded(&self) -> &(AttributeValue, Option<LoadWarning>) {
+        self.decoded.get_or_init(|| self.decode())
+    }
+
+    /// Decodes the value without caching it, and returns it with the warning.
+    fn decode(&self) -> (AttributeValue, Option<LoadWarning>) {
+        recovery::decode_array(
+            &self.path,
+            self.type_code,
+            1,
+            &self.raw,
+            self.declared,
+            self.truncated,
+            self.max_bytes,
+        )
+    }
+}
+
+impl LazyArrays {
+    /// Sets whether to use the broken arrays as far as decoded instead of
+    /// failing.
+    pub(super) fn set_tolerant(&mut self, tolerant: bool) {
+        self.tolerant = tolerant;
+    }
+
+    /// Returns the arrays in the order they are read from the file, so that
+    /// errors and warnings are reported deterministically.
+    fn in_file_order(&self) -> Vec<(&(NodeId, usize), &LazyArray)> {
+        let mut arrays: Vec<_> = self.arrays.iter().collect();
+        arrays.sort_by_key(|(_, array)| array.order);
+        arrays
+    }
+
+    /// Adds a compressed array attribute of the node.
+    pub(super) fn insert(
+        &mut self,
+        node: NodeId,
+        path: &str,
+        array: &DeferredArray<'_>,
+        max_bytes: usize,
+    ) {
+        let order = self.arrays.len();
+        self.arrays.insert(
+            (node, array.index),
+            LazyArray {
+                path: path.to_owned(),
+                type_code: array.type_code,
+                raw: array.raw.to_vec(),
+                declared: array.declared,
+                truncated: array.truncated,
+                decoded: OnceCell::new(),
+                order,
+                max_bytes,
+            },
+        );
+    }
+
+    /// Returns the estimated heap size, including the decoded values.
+    pub(super) fn heap_size(&self) -> usize {
+        memory::map_heap_size(&self.arrays)
+            + self
+                .arrays
+                .values()
+                .map(|array| {
+                    array.path.capacity()
+                        + memory::vec_heap_size(&array.raw)
+                        + array
+                            .decoded
+                            .get()
+                            .map_or(0, |(value, _)| memory::attribute_heap_size(value))
+                })
+                .sum::<usize>()
+    }
+
+    /// Returns true if there are no arrays to decode.
+    pub(super) fn is_empty(&self) -> bool {
+        self.arrays.is_empty()
+    }
+
+    /// Returns the decoded array attribute, if the attribute is lazily loaded.
+    pub(super) fn get(&self, node: NodeId, index: usize) -> Option<Result<&AttributeValue, Error>> {
+        let array = self.arrays.get(&(node, index))?;
+        Some(self.check(array.decoded()))
+    }
+
+    /// Drops the decoded values, keeping the compressed data.
+    pub(super) fn release(&mut self) {
+        for array in self.arrays.values_mut() {
+            array.decoded = OnceCell::new();
+        }
+    }
+
+    /// Decodes all arrays into the tree.
+    ///
+    /// If `lenient` is true, broken arrays are stored as far as decoded even
+    /// in the strict mode. Otherwise, the tree is not modified on error.
+    pub(super) fn preload(&mut self, tree: &mut Tree, lenient: bool) -> Result<(), Error> {
+        if !lenient {
+            for (_, array) in self.in_file_order() {
+                self.check(array.decoded())?;
+            }
+        }
+        let mut arrays: Vec<_> = self.arrays.drain().collect();
+        arrays.sort_by_key(|(_, array)| array.order);
+        for ((node, index), mut array) in arrays {
+            let (value, warning) = match array.decoded.take() {
+                Some(v) => v,
+                None => array.decode(),
+            };
+            if let Some(warning) = warning {
+                warn!("Using broken array as far as decoded: {}", warning);
+            }
+            if let Some(attr) = tree.get_attribute_mut(node, index) {
+                *attr = value;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decodes all arrays into the given copy of the tree, without caching
+    /// the decoded values.
+    pub(super) fn decode_into(&self, tree: &mut Tree) -> Result<(), Error> {
+        for (&(node, index), array) in self.in_file_order() {
+            let value = match array.decoded.get() {
+                Some(decoded) => self.check(decoded)?.clone(),
+                None => {
+                    let decoded = array.decode();
+                    self.check(&decoded)?;
+                    decoded.0
+                }
+            };
+            if let Some(attr) = tree.get_attribute_mut(node, index) {
+                *attr = value;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the decoded value, or an error if it is broken in the strict
+    /// mode.
+    fn check<'a>(
+        &self,
+        (value, warning): &'a (AttributeValue, Option<LoadWarning>),
+    ) -> Result<&'a AttributeValue, Error> {
+        match warning {
+            Some(warning) if !self.tolerant => Err(format_err!("Broken array: {}", warning)),
+            Some(warning) => {
+                warn!("Using broken array as far as decoded: {}", warning);
+                Ok(value)
+            }
+            None => Ok(value),
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! Resource limits on loading.
+
+use std::io::{self, Read};
+
+use fbxcel::{
+    low::v7400::AttributeValue,
+    pull_parser::{
+        v7400::{attribute::loaders::DirectLoader, LoadAttribute},
+        Result as ParserResult,
+    },
+    tree::v7400::{NodeHandle, Tree},
+};
+
+use crate::v7400::error::{dom::node_path, LimitError};
+
+/// Default maximum depth of the nodes.
+const DEFAULT_MAX_DEPTH: usize = 256;
+
+/// Maximum capacity preallocated for string and binary attributes.
+///
+/// Declared lengths are not trusted for allocation, and longer values are
+/// grown as the data is actually read.
+pub(crate) const MAX_PREALLOCATION: usize = 64 * 1024;
+
+/// Resource limits on loading.
+///
+/// Broken or hostile FBX data can declare huge arrays, contain compressed
+/// arrays which inflate to huge sizes, or nest nodes deeply.
+/// Limits make loading such data fail early with an error, instead of
+/// running out of memory.
+///
+/// By default, the depth of the nodes is limited to 256, and the number of
+/// nodes and the size of arrays are not limited.
+/// Set all limits when loading untrusted data.
+///
+/// ```
+/// use fbxcel_dom::v7400::{Loader, ResourceLimits};
+///
+/// let loader = Loader::new().resource_limits(
+///     ResourceLimits::new()
+///         .max_nodes(1_000_000)
+///         .max_array_bytes(256 * 1024 * 1024),
+/// );
+/// # let _ = loader;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResourceLimits {
+    /// Maximum number of nodes.
+    max_nodes: Option<usize>,
+    /// Maximum depth of the nodes.
+    max_depth: Option<usize>,
+    /// Maximum size of an array, string, or binary attribute in bytes.
+    max_array_bytes: Option<usize>,
+}
+
+impl ResourceLimits {
+    /// Creates the default limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates limits with nothing limited.
+    pub fn unlimited() -> Self {
+        Self {
+            max_nodes: None,
+            max_depth: None,
+            max_array_bytes: None,
+        }
+    }
+
+    /// Sets the maximum number of nodes.
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Sets the maximum depth of the nodes.
+    ///
+    /// Toplevel nodes have depth 1.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Sets the maximum size of an array, string, or binary attribute in
+    /// bytes.
+    ///
+    /// For arrays, this limits both the declared size and the decompressed
+    /// size.
+    pub fn max_array_bytes(mut self, max_array_bytes: usize) -> Self {
+        self.max_array_bytes = Some(max_array_bytes);
+        self
+    }
+
+    /// Returns the maximum size of an attribute in bytes, or `usize::MAX` if
+    /// unlimited.
+    pub(crate) fn array_bytes_limit(&self) -> usize {
+        self.max_array_bytes.unwrap_or(usize::MAX)
+    }
+
+    /// Checks the number of nodes.
+    pub(crate) fn check_nodes(&self, nodes: usize) -> Result<(), LimitError> {
+        match self.max_nodes {
+            Some(limit) if nodes > limit => Err(LimitError::Nodes { limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks the depth of the node.
+    pub(crate) fn check_depth(&self, path: &str, depth: usize) -> Result<(), LimitError> {
+        match self.max_depth {
+            Some(limit) if depth > limit => Err(LimitError::Depth {
+                node: path.to_owned(),
+                limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks the size of an attribute in bytes.
+    pub(crate) fn check_array(&self, path: &str, bytes: u64) -> Result<(), LimitError> {
+        match self.max_array_bytes {
+            Some(limit) if bytes > limit as u64 => Err(LimitError::ArrayBytes {
+                node: path.to_owned(),
+                bytes,
+                limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks the number of nodes and the depth of the tree.
+    pub(crate) fn check_tree(&self, tree: &Tree) -> Result<(), LimitError> {
+        if self.max_nodes.is_none() && self.max_depth.is_none() {
+            return Ok(());
+        }
+        let mut nodes = 0;
+        let mut stack: Vec<(NodeHandle<'_>, usize)> =
+            tree.root().children().map(|node| (node, 1)).collect();
+        while let Some((node, depth)) = stack.pop() {
+            nodes += 1;
+            self.check_nodes(nodes)?;
+            if self.max_depth.map_or(false, |limit| depth > limit) {
+                return self.check_depth(&node_path(&node), depth);
+            }
+            stack.extend(node.children().map(|child| (child, depth + 1)));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            max_nodes: None,
+            max_depth: Some(DEFAULT_MAX_DEPTH),
+            max_array_bytes: None,
+        }
+    }
+}
+
+/// Attribute loader checking the sizes of arrays, strings, and binaries.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LimitedLoader<'a> {
+    /// Path of the node.
+    pub(crate) path: &'a str,
+    /// Limits.
+    pub(crate) limits: &'a ResourceLimits,
+}
+
+impl LimitedLoader<'_> {
+    /// Checks the size of an attribute.
+    fn check(&self, len: u64, elem_size: u64) -> io::Result<()> {
+        self.limits
+            .check_array(self.path, len.saturating_mul(elem_size))
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+
+    /// Reads the whole data without trusting the declared length for
+    /// allocation.
+    fn read_bytes(&self, mut reader: impl Read, len: u64) -> io::Result<Vec<u8>> {
+        self.check(len, 1)?;
+        let mut buf = Vec::with_capacity(std::cmp::min(len, MAX_PREALLOCATION as u64) as usize);
+        reader.read_to_end(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+impl LoadAttribute for LimitedLoader<'_> {
+    type Output = AttributeValue;
+
+    fn expecting(&self) -> String {
+        DirectLoader.expecting()
+    }
+
+    fn load_bool(self, v: bool) -> ParserResult<Self::Output> {
+        DirectLoader.load_bool(v)
+    }
+
+    fn load_i16(self, v: i16) -> ParserResult<Self::Output> {
+        DirectLoader.load_i16(v)
+    }
+
+    fn load_i32(self, v: i32) -> ParserResult<Self::Output> {
+        DirectLoader.load_i32(v)
+    }
+
+    fn load_i64(self, v: i64) -> ParserResult<Self::Output> {
+        DirectLoader.load_i64(v)
+    }
+
+    fn load_f32(self, v: f32) -> ParserResult<Self::Output> {
+        DirectLoader.load_f32(v)
+    }
+
+    fn load_f64(self, v: f64) -> ParserResult<Self::Output> {
+        DirectLoader.load_f64(v)
+    }
+
+    fn load_seq_bool(
+        self,
+        iter: impl Iterator<Item = ParserResult<bool>>,
+        len: usize,
+    ) -> ParserResult<Self::Output> {
+        self.check(len as u64, 1)?;
+        DirectLoader.load_seq_bool(iter, len)
+    }
+
+    fn load_seq_i32(
+        self,
+        iter: impl Iterator<Item = ParserResult<i32>>,
+        len: usize,
+    ) -> ParserResult<Self::Output> {
+        self.check(len as u64, 4)?;
+        DirectLoader.load_seq_i32(iter, len)
+    }
+
+    fn load_seq_i64(
+        self,
+        iter: impl Iterator<Item = ParserResult<i64>>,
+        len: usize,
+    ) -> ParserResult<Self::Output> {
+        self.check(len as u64, 8)?;
+        DirectLoader.load_seq_i64(iter, len)
+    }
+
+    fn load_seq_f32(
+        self,
+        iter: impl Iterator<Item = ParserResult<f32>>,
+        len: usize,
+    ) -> ParserResult<Self::Output> {
+        self.check(len as u64, 4)?;
+        DirectLoader.load_seq_f32(iter, len)
+    }
+
+    fn load_seq_f64(
+        self,
+        iter: impl Iterator<Item = ParserResult<f64>>,
+        len: usize,
+    ) -> ParserResult<Self::Output> {
+        self.check(len as u64, 8)?;
+        DirectLoader.load_seq_f64(iter, len)
+    }
+
+    fn load_binary(self, reader: impl Read, len: u64) -> ParserResult<Self::Output> {
+        Ok(AttributeValue::Binary(self.read_bytes(reader, len)?))
+    }
+
+    fn load_string(self, reader: impl Read, len: u64) -> ParserResult<Self::Output> {
+        let bytes = self.read_bytes(reader, len)?;
+        let value =
+            String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(AttributeValue::String(value))
+    }
+}
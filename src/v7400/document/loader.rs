@@ -1,19 +1,52 @@
 //! FBX DOM loader.
 
+#[cfg(feature = "ascii")]
+use std::convert::TryFrom;
+use std::io::{Cursor, Read};
+
 use fbxcel::{
-    pull_parser::{v7400::Parser, ParserSource},
-    tree::v7400::{Loader as TreeLoader, Tree},
+    low::{v7400::FbxFooter, FbxHeader, FbxVersion},
+    pull_parser::{
+        v7400::{from_reader, from_seekable_reader, Parser},
+        ParserSource,
+    },
+    tree::v7400::Tree,
 };
-use log::trace;
+use log::{trace, warn};
 
 use crate::v7400::{
-    connection::ConnectionsCache, definition::DefinitionsCache, object::ObjectsCache, Document,
-    LoadError,
+    connection::ConnectionsCache,
+    definition::DefinitionsCache,
+    object::{extension::ExtensionType, property::PropertiesIndex, ObjectsCache},
+    symbol::SymbolTable,
+    version::Capabilities,
+    Document, LoadError, LoadWarning, Warning, Warnings,
+};
+
+use super::{
+    filter::{self, NodeFilter},
+    limits::ResourceLimits,
+    recovery, IncrementalLoader,
 };
 
 /// FBX DOM loader.
 #[derive(Default, Debug, Clone)]
-pub struct Loader;
+pub struct Loader {
+    /// User-registered extension object types.
+    extension_types: Vec<ExtensionType>,
+    /// FBX version of the source.
+    fbx_version: Option<FbxVersion>,
+    /// Whether to recover from broken data instead of failing.
+    pub(super) tolerant: bool,
+    /// Nodes to skip.
+    pub(super) skipped: NodeFilter,
+    /// Number of threads to decompress arrays with.
+    decompression_threads: usize,
+    /// Whether to decode compressed arrays on first use.
+    lazy_arrays: bool,
+    /// Resource limits.
+    pub(super) limits: ResourceLimits,
+}
 
 impl Loader {
     /// Creates a new `Loader`.
@@ -21,28 +54,482 @@ impl Loader {
         Self::default()
     }
 
+    /// Registers a user-defined extension object type.
+    ///
+    /// Objects not supported by this crate and matching the type are
+    /// returned as `TypedObjectHandle::Extension` from
+    /// `ObjectHandle::get_typed()`.
+    /// If multiple types match an object, the first registered one is used.
+    ///
+    /// See [`object::extension`](object/extension/index.html) module.
+    pub fn register_extension(mut self, ty: ExtensionType) -> Self {
+        self.extension_types.push(ty);
+        self
+    }
+
+    /// Sets the FBX version of the source.
+    ///
+    /// This is used when the version is not available from the source, such
+    /// as loading from trees.
+    /// Loading from a parser overrides this with the version of the parser.
+    pub fn fbx_version(mut self, fbx_version: FbxVersion) -> Self {
+        self.fbx_version = Some(fbx_version);
+        self
+    }
+
+    /// Sets whether to fail on broken data.
+    ///
+    /// The loader is strict by default.
+    /// In the non-strict mode, the loader recovers from common corruptions by
+    /// repairing or skipping the broken parts, and records them as
+    /// [`Document::load_warnings()`]:
+    ///
+    /// * Objects with duplicate IDs and duplicate connections are skipped.
+    /// * With [`load_from_reader()`][`Self::load_from_reader`], truncated
+    ///   nodes and arrays are read up to the available end, arrays with wrong
+    ///   declared elements counts are read as the data is, and invalid UTF-8
+    ///   sequences in node names and strings are replaced with `U+FFFD`.
+    ///
+    /// Errors from the `fbxcel` parser are not recovered from, since the
+    /// parser stops on them. Use `load_from_reader()` to recover from broken
+    /// binary data.
+    ///
+    /// ```
+    /// use fbxcel_dom::{
+    ///     fbxcel::low::v7400::AttributeValue,
+    ///     v7400::{Document, LoadWarning, Loader},
+    /// };
+    ///
+    /// /// Appends a node record with the given raw attributes.
+    /// fn node(
+    ///     data: &mut Vec<u8>,
+    ///     name: &str,
+    ///     attrs: &[Vec<u8>],
+    ///     children: impl FnOnce(&mut Vec<u8>),
+    /// ) {
+    ///     let start = data.len();
+    ///     data.extend_from_slice(&[0; 12]);
+    ///     data.push(name.len() as u8);
+    ///     data.extend_from_slice(name.as_bytes());
+    ///     let attrs_start = data.len();
+    ///     attrs.iter().for_each(|attr| data.extend_from_slice(attr));
+    ///     let attrs_len = (data.len() - attrs_start) as u32;
+    ///     children(data);
+    ///     if data.len() > attrs_start + attrs_len as usize {
+    ///         // Node end marker.
+    ///         data.extend_from_slice(&[0; 13]);
+    ///     }
+    ///     let end = data.len() as u32;
+    ///     data[start..start + 4].copy_from_slice(&end.to_le_bytes());
+    ///     data[start + 4..start + 8].copy_from_slice(&(attrs.len() as u32).to_le_bytes());
+    ///     data[start + 8..start + 12].copy_from_slice(&attrs_len.to_le_bytes());
+    /// }
+    /// fn string(v: &str) -> Vec<u8> {
+    ///     [&b"S"[..], &(v.len() as u32).to_le_bytes(), v.as_bytes()].concat()
+    /// }
+    /// /// `f64` array of 3 elements with the given encoding and raw data.
+    /// fn array(encoding: u32, raw: &[u8]) -> Vec<u8> {
+    ///     let header = [3, encoding, raw.len() as u32];
+    ///     let header: Vec<u8> = header.iter().flat_map(|v| v.to_le_bytes()).collect();
+    ///     [&b"d"[..], &header, raw].concat()
+    /// }
+    /// /// FBX data with a mesh geometry, and the offset of the `Vertices` node.
+    /// fn fbx(vertices: Vec<u8>) -> (Vec<u8>, usize) {
+    ///     let mut data = b"Kaydara FBX Binary  \x00\x1a\x00".to_vec();
+    ///     data.extend_from_slice(&7400_u32.to_le_bytes());
+    ///     node(&mut data, "Documents", &[], |_| {});
+    ///     node(&mut data, "Connections", &[], |_| {});
+    ///     let mut offset = 0;
+    ///     node(&mut data, "Objects", &[], |data| {
+    ///         let id = [&b"L"[..], &1_i64.to_le_bytes()].concat();
+    ///         let attrs = [id, string("Cube\u{0}\u{1}Geometry"), string("Mesh")];
+    ///         node(data, "Geometry", &attrs, |data| {
+    ///             offset = data.len();
+    ///             node(data, "Vertices", &[vertices.clone()], |_| {});
+    ///         });
+    ///     });
+    ///     data.extend_from_slice(&[0; 13]);
+    ///     (data, offset)
+    /// }
+    /// fn vertices(doc: &Document) -> &AttributeValue {
+    ///     let vertices = ["Objects", "Geometry", "Vertices"]
+    ///         .iter()
+    ///         .try_fold(doc.tree().root(), |node, name| node.first_child_by_name(name))
+    ///         .expect("should exist");
+    ///     &vertices.attributes()[0]
+    /// }
+    ///
+    /// let raw: Vec<u8> = [0.0_f64, 1.0, 2.0].iter().flat_map(|v| v.to_le_bytes()).collect();
+    /// let loader = Loader::new().strict(false);
+    /// let path = "Objects/Geometry/Vertices";
+    ///
+    /// // Truncated in the middle of the last array element.
+    /// let (data, _) = fbx(array(0, &raw));
+    /// let doc = loader.clone().load_from_slice(&data[..data.len() - 3 * 13 - 4])?;
+    /// assert_eq!(
+    ///     doc.load_warnings(),
+    ///     [
+    ///         LoadWarning::TruncatedNode { node: "Objects".into() },
+    ///         LoadWarning::TruncatedNode { node: "Objects/Geometry".into() },
+    ///         LoadWarning::TruncatedNode { node: path.into() },
+    ///         LoadWarning::TruncatedArray { node: path.into(), declared: 3, actual: 2 },
+    ///     ]
+    /// );
+    /// assert_eq!(vertices(&doc), &AttributeValue::ArrF64(vec![0.0, 1.0]));
+    /// assert_eq!(doc.objects().filter(|obj| obj.name() == Some("Cube")).count(), 1);
+    ///
+    /// // The end offset of `Vertices` exceeds the end of the parent node.
+    /// let (mut data, offset) = fbx(array(0, &raw));
+    /// let len = data.len() as u32;
+    /// data[offset..offset + 4].copy_from_slice(&len.to_le_bytes());
+    /// let doc = loader.clone().load_from_slice(&data)?;
+    /// assert_eq!(doc.load_warnings(), [LoadWarning::TruncatedNode { node: path.into() }]);
+    /// assert_eq!(vertices(&doc), &AttributeValue::ArrF64(vec![0.0, 1.0, 2.0]));
+    ///
+    /// // Corrupt zlib data.
+    /// let (data, _) = fbx(array(1, b"broken"));
+    /// let doc = loader.load_from_slice(&data)?;
+    /// assert_eq!(
+    ///     doc.load_warnings(),
+    ///     [LoadWarning::TruncatedArray { node: path.into(), declared: 3, actual: 0 }]
+    /// );
+    /// assert_eq!(vertices(&doc), &AttributeValue::ArrF64(Vec::new()));
+    /// assert_eq!(doc.objects().filter(|obj| obj.name() == Some("Cube")).count(), 1);
+    /// # Ok::<_, fbxcel_dom::v7400::LoadError>(())
+    /// ```
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.tolerant = !strict;
+        self
+    }
+
+    /// Skips the nodes at the given path on loading.
+    ///
+    /// The path is the node names from the toplevel separated by `/`, such
+    /// as `Takes`, `Objects/AnimationCurve` or `Objects/Video/Content`.
+    /// The matching nodes and their descendants are not loaded, and
+    /// connections from or to the skipped objects are removed.
+    ///
+    /// Skipping `Objects`, `Documents` or `Connections` makes loading fail
+    /// (except in the non-strict mode, see [`strict()`][`Self::strict`]).
+    pub fn skip_nodes(mut self, path: &str) -> Self {
+        self.skipped.add(path);
+        self
+    }
+
+    /// Skips the animation nodes on loading.
+    ///
+    /// This skips `Takes` and the animation stacks, layers, curve nodes and
+    /// curves.
+    pub fn skip_animations(mut self) -> Self {
+        for path in filter::ANIMATION_PATHS {
+            self.skipped.add(path);
+        }
+        self
+    }
+
+    /// Skips the embedded media content on loading.
+    ///
+    /// This skips `Content` nodes of `Video` objects, which contain the
+    /// embedded texture files.
+    pub fn skip_embedded_content(mut self) -> Self {
+        for path in filter::EMBEDDED_CONTENT_PATHS {
+            self.skipped.add(path);
+        }
+        self
+    }
+
+    /// Sets the number of threads to decompress the array attributes with.
+    ///
+    /// Most of the loading time of large files is spent on inflating the
+    /// zlib-compressed arrays (such as vertices and animation keys).
+    /// With two or more threads, the compressed arrays are decompressed in
+    /// parallel on a `rayon` thread pool after the node records are read.
+    ///
+    /// The default is 1, which decompresses the arrays on the loading thread.
+    /// This has effect only on loading binary data in the non-strict mode
+    /// (see [`strict()`][`Self::strict`]) with the `rayon` feature enabled.
+    /// The strict mode always uses the `fbxcel` parser, which decompresses
+    /// the arrays on the loading thread.
+    /// This has no effect on `wasm32` targets.
+    pub fn decompression_threads(mut self, threads: usize) -> Self {
+        self.decompression_threads = crate::v7400::available_threads(threads);
+        self
+    }
+
+    /// Sets whether to decode the compressed arrays on first use.
+    ///
+    /// If enabled, compressed array attributes (such as vertices and
+    /// animation keys) are kept compressed on loading, and decoded when they
+    /// are accessed through the object handles.
+    /// Tools reading only metadata (such as object names and properties)
+    /// then do not pay for decoding the geometry.
+    /// Broken arrays are reported on first use instead of on loading.
+    ///
+    /// The undecoded arrays are empty in [`Document::tree()`] until
+    /// [`Document::preload_all()`] is called. See also
+    /// [`Document::release_arrays()`].
+    ///
+    /// Like [`decompression_threads()`][`Self::decompression_threads`], this
+    /// has effect only on loading binary data in the non-strict mode.
+    /// Disabled by default.
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "writer", feature = "lazy-arrays"))]
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::io::Cursor;
+    ///
+    /// use fbxcel_dom::{
+    ///     fbxcel::low::FbxVersion,
+    ///     v7400::{
+    ///         data::material::ShadingModel,
+    ///         edit::{DocumentBuilder, ModelKind},
+    ///         object::{property::PropertyValue, ObjectId},
+    ///         write::Writer,
+    ///         Document, Loader,
+    ///     },
+    /// };
+    ///
+    /// fn objects(doc: &Document) -> Vec<(ObjectId, Option<&str>, &str)> {
+    ///     doc.objects()
+    ///         .map(|obj| (obj.object_id(), obj.name(), obj.class()))
+    ///         .collect()
+    /// }
+    /// fn properties(doc: &Document) -> Vec<(ObjectId, &str, PropertyValue<'_>)> {
+    ///     doc.iter_all_properties()
+    ///         .map(|entry| entry.expect("should be readable"))
+    ///         .map(|entry| (entry.object.object_id(), entry.name, entry.value))
+    ///         .collect()
+    /// }
+    /// fn vertices(doc: &Document) -> anyhow::Result<Vec<mint::Point3<f64>>> {
+    ///     let mesh = doc.meshes().next().expect("should exist");
+    ///     Ok(mesh.polygon_vertices()?.raw_control_points()?.collect())
+    /// }
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let model = builder.add_model("Quad", ModelKind::Mesh, None);
+    /// let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+    /// builder.add_mesh("Quad", &positions, &[&[0, 1, 2, 3]], Some(model));
+    /// builder.add_material("Red", ShadingModel::Lambert, [1.0, 0.0, 0.0], Some(model))?;
+    /// let data = Writer::new(FbxVersion::V7_4)
+    ///     .array_compression(Some(1))
+    ///     .write_document(&builder.build()?, Cursor::new(Vec::new()))?
+    ///     .into_inner();
+    ///
+    /// let eager = Loader::new().load_from_slice(&data)?;
+    /// let mut lazy = Loader::new()
+    ///     .strict(false)
+    ///     .lazy_arrays(true)
+    ///     .load_from_slice(&data)?;
+    /// assert!(eager.is_preloaded());
+    /// assert!(!lazy.is_preloaded());
+    ///
+    /// assert_eq!(objects(&lazy), objects(&eager));
+    /// assert_eq!(properties(&lazy), properties(&eager));
+    /// // Arrays are decoded on use.
+    /// assert_eq!(vertices(&lazy)?, vertices(&eager)?);
+    ///
+    /// lazy.preload_all()?;
+    /// assert!(lazy.is_preloaded());
+    /// assert_eq!(vertices(&lazy)?, vertices(&eager)?);
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(all(feature = "writer", feature = "lazy-arrays")))]
+    /// # fn main() {}
+    /// ```
+    #[cfg(feature = "lazy-arrays")]
+    pub fn lazy_arrays(mut self, lazy: bool) -> Self {
+        self.lazy_arrays = lazy;
+        self
+    }
+
+    /// Sets the resource limits.
+    ///
+    /// Loading fails if the data exceeds the limits, even in the non-strict
+    /// mode.
+    /// Compressed arrays inflating to more than the size limit are treated
+    /// as broken (truncated) arrays.
+    /// See [`ResourceLimits`] for the defaults.
+    pub fn resource_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Loads a document from the given binary FBX 7.x reader.
+    ///
+    /// In the non-strict mode (see [`strict()`][`Self::strict`]), the whole
+    /// data is read and broken node records are recovered from.
+    /// The footer is not loaded in the non-strict mode.
+    pub fn load_from_reader(self, mut reader: impl Read) -> Result<Document, LoadError> {
+        if self.tolerant {
+            trace!("Loading FBX DOM from a reader as a whole");
+            let mut data = Vec::new();
+            reader
+                .read_to_end(&mut data)
+                .map_err(|e| LoadError::new(format!("Failed to read FBX data: {}", e)))?;
+            return self.load_from_slice(&data);
+        }
+        trace!("Loading FBX DOM from a reader");
+        let header = FbxHeader::load(&mut reader).map_err(LoadError::new)?;
+        let mut parser = from_reader(header, reader).map_err(LoadError::new)?;
+        self.load_from_parser(&mut parser)
+    }
+
+    /// Loads a document from the given binary FBX 7.x data in memory.
+    ///
+    /// The data (such as a memory-mapped file) is read without being copied
+    /// as a whole, even in the non-strict mode.
+    /// To read attribute values without copying them, see
+    /// [`borrowed`](../borrowed/index.html) module.
+    pub fn load_from_slice(self, data: &[u8]) -> Result<Document, LoadError> {
+        if self.tolerant {
+            trace!("Loading FBX DOM from a slice in non-strict mode");
+            return self.load_tolerant(data);
+        }
+        trace!("Loading FBX DOM from a slice");
+        let mut reader = Cursor::new(data);
+        let header = FbxHeader::load(&mut reader).map_err(LoadError::new)?;
+        let mut parser = from_seekable_reader(header, reader).map_err(LoadError::new)?;
+        self.load_from_parser(&mut parser)
+    }
+
+    /// Returns a resumable loader to feed the binary FBX 7.x data in chunks.
+    ///
+    /// See [`IncrementalLoader`].
+    pub fn incremental(self) -> IncrementalLoader {
+        IncrementalLoader::new(self)
+    }
+
+    /// Loads a document from the given binary FBX data in the non-strict
+    /// mode.
+    pub(super) fn load_tolerant(self, data: &[u8]) -> Result<Document, LoadError> {
+        let loaded = recovery::load_tree(
+            data,
+            &self.skipped,
+            &self.limits,
+            self.decompression_threads,
+            self.lazy_arrays,
+        )?;
+        let doc =
+            self.fbx_version(loaded.fbx_version)
+                .load_impl(loaded.tree, None, loaded.warnings)?;
+        #[cfg(feature = "lazy-arrays")]
+        let doc = {
+            let mut doc = doc;
+            doc.lazy_arrays = loaded.lazy_arrays;
+            doc.lazy_arrays.set_tolerant(true);
+            doc
+        };
+        Ok(doc)
+    }
+
     /// Loads a document from the given FBX parser.
     pub fn load_from_parser<R: ParserSource>(
         self,
         parser: &mut Parser<R>,
     ) -> Result<Document, LoadError> {
         trace!("Loading FBX DOM from a parser");
-        let (tree, _) = TreeLoader::new().load(parser)?;
-        self.load_from_tree(tree)
+        let loader = self.fbx_version(parser.fbx_version());
+        let (tree, footer) = loader.skipped.load_tree(parser, &loader.limits)?;
+        let footer = match footer {
+            Ok(v) => Some(*v),
+            Err(e) => {
+                warn!("Failed to load FBX footer: {}", e);
+                None
+            }
+        };
+        loader.load_from_tree_with_footer(tree, footer)
+    }
+
+    /// Loads a document from the given ASCII FBX text.
+    ///
+    /// See [`ascii`](../ascii/index.html) module.
+    #[cfg(feature = "ascii")]
+    pub fn load_from_ascii(self, text: &str) -> Result<Document, LoadError> {
+        trace!("Loading FBX DOM from ASCII FBX");
+        let tree = crate::v7400::ascii::parse_tree_with_limits(text, &self.limits)?;
+        let loader = match crate::v7400::version::header_version(&tree)
+            .and_then(|v| u32::try_from(v).ok())
+        {
+            Some(v) => self.fbx_version(crate::v7400::version::from_raw(v)),
+            None => self,
+        };
+        loader.load_from_tree(tree)
     }
 
     /// Loads a document from the given FBX data tree.
     pub fn load_from_tree(self, tree: Tree) -> Result<Document, LoadError> {
+        self.load_from_tree_with_footer(tree, None)
+    }
+
+    /// Loads a document from the given FBX data tree and the footer of the
+    /// source file.
+    ///
+    /// The footer is kept in the document, and used by the writer to
+    /// reproduce the source file (see [`Document::footer()`]).
+    pub fn load_from_tree_with_footer(
+        self,
+        tree: Tree,
+        footer: Option<FbxFooter>,
+    ) -> Result<Document, LoadError> {
+        self.load_impl(tree, footer, Vec::new())
+    }
+
+    /// Loads a document from the given FBX data tree with the warnings
+    /// already recorded.
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "load_dom", skip_all))]
+    pub(super) fn load_impl(
+        self,
+        mut tree: Tree,
+        footer: Option<FbxFooter>,
+        mut load_warnings: Vec<LoadWarning>,
+    ) -> Result<Document, LoadError> {
         trace!("Loading FBX DOM from an FBX data tree");
-        let objects = ObjectsCache::from_tree(&tree)?;
-        let connections = ConnectionsCache::from_tree(&tree)?;
+        let fbx_version = self
+            .fbx_version
+            .or_else(|| footer.map(|footer| footer.fbx_version));
+        if let Some(fbx_version) = fbx_version {
+            let capabilities = Capabilities::new(fbx_version);
+            if capabilities.is_newer_than_known() {
+                warn!(
+                    "FBX version {:?} is newer than the known versions, and may be misread",
+                    fbx_version
+                );
+            } else if capabilities.is_native() && !capabilities.is_known() {
+                warn!(
+                    "FBX version {:?} is not explicitly supported, and may be misread",
+                    fbx_version
+                );
+            }
+        }
+        self.limits.check_tree(&tree)?;
+        if !self.skipped.is_empty() {
+            self.skipped.apply(&mut tree);
+        }
+        if self.tolerant {
+            recovery::repair_tree(&mut tree, &mut load_warnings);
+        }
+        let mut symbols = SymbolTable::default();
+        let objects = ObjectsCache::from_tree(&tree, &mut symbols)?;
+        let connections = ConnectionsCache::from_tree(&tree, &mut symbols)?;
         let definitions = DefinitionsCache::from_tree(&tree);
+        let properties = PropertiesIndex::from_tree(&tree, &mut symbols);
+        let warnings =
+            Warnings::with_recorded(load_warnings.iter().cloned().map(Warning::Load).collect());
         trace!("Loaded FBX DOM successfully");
         Ok(Document {
             tree,
             objects,
             connections,
             definitions,
+            symbols,
+            properties,
+            footer,
+            fbx_version,
+            extension_types: self.extension_types,
+            load_warnings,
+            warnings,
+            #[cfg(feature = "lazy-arrays")]
+            lazy_arrays: Default::default(),
         })
     }
 }
@@ -0,0 +1,248 @@
+//! Name-based lookups across connected objects.
+
+use crate::v7400::{
+    object::{deformer, geometry, material, model, texture, NameMatchOptions, TypedObjectHandle},
+    Document,
+};
+
+impl Document {
+    /// Returns the first model with the given name.
+    ///
+    /// See [`objects_by_name`](#method.objects_by_name) for the name format.
+    pub fn model_by_name(&self, name: &str) -> Option<model::TypedModelHandle<'_>> {
+        self.model_by_name_with_options(name, NameMatchOptions::new())
+    }
+
+    /// Returns the first model with the given name, compared with the given
+    /// options.
+    ///
+    /// ```
+    /// # #[cfg(feature = "edit")]
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// use fbxcel_dom::v7400::{
+    ///     edit::{DocumentBuilder, ModelKind},
+    ///     object::NameMatchOptions,
+    /// };
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let hips = builder.add_model("Rig:Hips", ModelKind::Null, None);
+    /// let doc = builder.build()?;
+    ///
+    /// assert!(doc.model_by_name("Hips").is_none());
+    /// let options = NameMatchOptions::new().ignore_namespace(true);
+    /// let model = doc.model_by_name_with_options("Hips", options);
+    /// assert_eq!(model.map(|model| model.object_id()), Some(hips));
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "edit"))]
+    /// # fn main() {}
+    /// ```
+    pub fn model_by_name_with_options(
+        &self,
+        name: &str,
+        options: NameMatchOptions,
+    ) -> Option<model::TypedModelHandle<'_>> {
+        self.objects_by_name_with_options(name, options)
+            .find_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Model(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns the first mesh model with the given name.
+    pub fn mesh_model_by_name(&self, name: &str) -> Option<model::MeshHandle<'_>> {
+        self.mesh_model_by_name_with_options(name, NameMatchOptions::new())
+    }
+
+    /// Returns the first mesh model with the given name, compared with the
+    /// given options.
+    pub fn mesh_model_by_name_with_options(
+        &self,
+        name: &str,
+        options: NameMatchOptions,
+    ) -> Option<model::MeshHandle<'_>> {
+        self.objects_by_name_with_options(name, options)
+            .find_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Model(model::TypedModelHandle::Mesh(o)) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns the first material with the given name.
+    pub fn material_by_name(&self, name: &str) -> Option<material::MaterialHandle<'_>> {
+        self.material_by_name_with_options(name, NameMatchOptions::new())
+    }
+
+    /// Returns the first material with the given name, compared with the
+    /// given options.
+    pub fn material_by_name_with_options(
+        &self,
+        name: &str,
+        options: NameMatchOptions,
+    ) -> Option<material::MaterialHandle<'_>> {
+        self.objects_by_name_with_options(name, options)
+            .find_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Material(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns the first texture with the given name.
+    pub fn texture_by_name(&self, name: &str) -> Option<texture::TextureHandle<'_>> {
+        self.texture_by_name_with_options(name, NameMatchOptions::new())
+    }
+
+    /// Returns the first texture with the given name, compared with the given
+    /// options.
+    pub fn texture_by_name_with_options(
+        &self,
+        name: &str,
+        options: NameMatchOptions,
+    ) -> Option<texture::TextureHandle<'_>> {
+        self.objects_by_name_with_options(name, options)
+            .find_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Texture(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns the geometry mesh of the first mesh model with the given name.
+    ///
+    /// Like the other `*_for_model_name` lookups, only the first mesh model
+    /// with the name (see [`mesh_model_by_name`](#method.mesh_model_by_name))
+    /// is used, even if other mesh models have the same name.
+    ///
+    /// ```
+    /// # #[cfg(feature = "edit")]
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// use fbxcel_dom::v7400::{
+    ///     data::material::ShadingModel,
+    ///     edit::{DocumentBuilder, ModelKind},
+    /// };
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let model = builder.add_model("Quad", ModelKind::Mesh, None);
+    /// let positions = [[0.0; 3], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+    /// let mesh = builder.add_mesh("QuadMesh", &positions, &[&[0, 1, 2, 3]], Some(model));
+    /// builder.add_material("Red", ShadingModel::Lambert, [1.0, 0.0, 0.0], Some(model))?;
+    /// let doc = builder.build()?;
+    ///
+    /// let geometry = doc.geometry_for_model_name("Quad").expect("should exist");
+    /// assert_eq!(geometry.object_id(), mesh);
+    /// let materials: Vec<_> = doc.materials_for_model_name("Quad").collect();
+    /// assert_eq!(materials.len(), 1);
+    /// assert_eq!(materials[0].name(), Some("Red"));
+    /// let models: Vec<_> = doc.models_for_material_name("Red").collect();
+    /// assert_eq!(models[0].object_id(), model);
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "edit"))]
+    /// # fn main() {}
+    /// ```
+    pub fn geometry_for_model_name(&self, name: &str) -> Option<geometry::MeshHandle<'_>> {
+        self.geometry_for_model_name_with_options(name, NameMatchOptions::new())
+    }
+
+    /// Returns the geometry mesh of the first mesh model with the given name,
+    /// compared with the given options.
+    pub fn geometry_for_model_name_with_options(
+        &self,
+        name: &str,
+        options: NameMatchOptions,
+    ) -> Option<geometry::MeshHandle<'_>> {
+        self.mesh_model_by_name_with_options(name, options)
+            .and_then(|model| model.geometry().ok())
+    }
+
+    /// Returns an iterator of materials of the first mesh model with the given
+    /// name.
+    ///
+    /// Only the first mesh model with the name is used.
+    pub fn materials_for_model_name<'a>(
+        &'a self,
+        name: &str,
+    ) -> impl Iterator<Item = material::MaterialHandle<'a>> {
+        self.materials_for_model_name_with_options(name, NameMatchOptions::new())
+    }
+
+    /// Returns an iterator of materials of the first mesh model with the given
+    /// name, compared with the given options.
+    pub fn materials_for_model_name_with_options<'a>(
+        &'a self,
+        name: &str,
+        options: NameMatchOptions,
+    ) -> impl Iterator<Item = material::MaterialHandle<'a>> {
+        self.mesh_model_by_name_with_options(name, options)
+            .into_iter()
+            .flat_map(|model| model.materials())
+    }
+
+    /// Returns an iterator of skins of the geometry of the first mesh model
+    /// with the given name.
+    ///
+    /// Only the first mesh model with the name is used.
+    pub fn skins_for_model_name<'a>(
+        &'a self,
+        name: &str,
+    ) -> impl Iterator<Item = deformer::SkinHandle<'a>> {
+        self.skins_for_model_name_with_options(name, NameMatchOptions::new())
+    }
+
+    /// Returns an iterator of skins of the geometry of the first mesh model
+    /// with the given name, compared with the given options.
+    pub fn skins_for_model_name_with_options<'a>(
+        &'a self,
+        name: &str,
+        options: NameMatchOptions,
+    ) -> impl Iterator<Item = deformer::SkinHandle<'a>> {
+        self.geometry_for_model_name_with_options(name, options)
+            .into_iter()
+            .flat_map(|geometry| geometry.skins())
+    }
+
+    /// Returns an iterator of mesh models using the first geometry mesh with
+    /// the given name.
+    pub fn models_for_geometry_name<'a>(
+        &'a self,
+        name: &str,
+    ) -> impl Iterator<Item = model::MeshHandle<'a>> {
+        self.models_for_geometry_name_with_options(name, NameMatchOptions::new())
+    }
+
+    /// Returns an iterator of mesh models using the first geometry mesh with
+    /// the given name, compared with the given options.
+    pub fn models_for_geometry_name_with_options<'a>(
+        &'a self,
+        name: &str,
+        options: NameMatchOptions,
+    ) -> impl Iterator<Item = model::MeshHandle<'a>> {
+        self.objects_by_name_with_options(name, options)
+            .find_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Geometry(geometry::TypedGeometryHandle::Mesh(o)) => Some(o),
+                _ => None,
+            })
+            .into_iter()
+            .flat_map(|geometry| geometry.models())
+    }
+
+    /// Returns an iterator of mesh models using the first material with the
+    /// given name.
+    pub fn models_for_material_name<'a>(
+        &'a self,
+        name: &str,
+    ) -> impl Iterator<Item = model::MeshHandle<'a>> {
+        self.models_for_material_name_with_options(name, NameMatchOptions::new())
+    }
+
+    /// Returns an iterator of mesh models using the first material with the
+    /// given name, compared with the given options.
+    pub fn models_for_material_name_with_options<'a>(
+        &'a self,
+        name: &str,
+        options: NameMatchOptions,
+    ) -> impl Iterator<Item = model::MeshHandle<'a>> {
+        self.material_by_name_with_options(name, options)
+            .into_iter()
+            .flat_map(|material| material.meshes())
+    }
+}
@@ -0,0 +1,262 @@
+//! Memory usage report.
+
+use std::{collections::HashMap, mem};
+
+use fbxcel::{low::v7400::AttributeValue, tree::v7400::NodeHandle};
+
+use crate::v7400::Document;
+
+/// Estimated per-node overhead of the tree, other than the attributes.
+///
+/// This is the node name symbol, the attributes vector header, and the
+/// parent, sibling and child links in the arena.
+const NODE_OVERHEAD: usize = mem::size_of::<Vec<AttributeValue>>() + 6 * mem::size_of::<usize>();
+
+/// Returns the estimated heap size of the vector, excluding the heap memory
+/// owned by the elements.
+pub(crate) fn vec_heap_size<T>(vec: &Vec<T>) -> usize {
+    vec.capacity() * mem::size_of::<T>()
+}
+
+/// Returns the estimated heap size of the hash map, excluding the heap memory
+/// owned by the entries.
+pub(crate) fn map_heap_size<K, V, S>(map: &HashMap<K, V, S>) -> usize {
+    // One control byte per bucket.
+    map.capacity() * (mem::size_of::<(K, V)>() + 1)
+}
+
+/// Returns the estimated heap size of the hash map with vector values.
+pub(crate) fn multimap_heap_size<K, V, S>(map: &HashMap<K, Vec<V>, S>) -> usize {
+    map_heap_size(map) + map.values().map(vec_heap_size).sum::<usize>()
+}
+
+/// Returns the heap size of the attribute value, excluding the attribute
+/// itself.
+pub(crate) fn attribute_heap_size(value: &AttributeValue) -> usize {
+    match value {
+        AttributeValue::ArrBool(v) => vec_heap_size(v),
+        AttributeValue::ArrI32(v) => vec_heap_size(v),
+        AttributeValue::ArrI64(v) => vec_heap_size(v),
+        AttributeValue::ArrF32(v) => vec_heap_size(v),
+        AttributeValue::ArrF64(v) => vec_heap_size(v),
+        AttributeValue::String(v) => v.capacity(),
+        AttributeValue::Binary(v) => vec_heap_size(v),
+        _ => 0,
+    }
+}
+
+/// Estimated memory usage of a document, in bytes.
+///
+/// Sizes are estimated from the lengths and capacities of the containers,
+/// so allocator overhead is not included and the values are approximate.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MemoryReport {
+    /// Number of tree nodes.
+    node_count: usize,
+    /// Tree nodes, excluding the attributes.
+    tree_nodes: usize,
+    /// Attributes.
+    attributes: AttributeMemory,
+    /// Compressed arrays not decoded into the tree yet.
+    lazy_arrays: usize,
+    /// Objects cache.
+    objects: usize,
+    /// Connections cache.
+    connections: usize,
+    /// Object template definitions cache.
+    definitions: usize,
+    /// Interned strings.
+    symbols: usize,
+    /// Property nodes index.
+    properties: usize,
+}
+
+impl MemoryReport {
+    /// Returns the number of tree nodes.
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    /// Returns the size of the tree nodes, excluding the attributes.
+    pub fn tree_nodes(&self) -> usize {
+        self.tree_nodes
+    }
+
+    /// Returns the sizes of the attributes in the tree.
+    pub fn attributes(&self) -> &AttributeMemory {
+        &self.attributes
+    }
+
+    /// Returns the size of the compressed arrays not decoded into the tree,
+    /// including the values decoded on access.
+    ///
+    /// See [`Loader::lazy_arrays()`][`super::Loader::lazy_arrays`].
+    pub fn lazy_arrays(&self) -> usize {
+        self.lazy_arrays
+    }
+
+    /// Returns the size of the objects cache.
+    pub fn objects(&self) -> usize {
+        self.objects
+    }
+
+    /// Returns the size of the connections cache.
+    pub fn connections(&self) -> usize {
+        self.connections
+    }
+
+    /// Returns the size of the object template definitions cache.
+    pub fn definitions(&self) -> usize {
+        self.definitions
+    }
+
+    /// Returns the size of the interned strings.
+    pub fn symbols(&self) -> usize {
+        self.symbols
+    }
+
+    /// Returns the size of the property nodes index.
+    pub fn properties(&self) -> usize {
+        self.properties
+    }
+
+    /// Returns the total size of the tree, including the attributes.
+    pub fn tree(&self) -> usize {
+        self.tree_nodes + self.attributes.total()
+    }
+
+    /// Returns the total size of the caches and indices.
+    pub fn caches(&self) -> usize {
+        self.objects + self.connections + self.definitions + self.symbols + self.properties
+    }
+
+    /// Returns the total size.
+    pub fn total(&self) -> usize {
+        self.tree() + self.lazy_arrays + self.caches()
+    }
+}
+
+/// Estimated memory usage of attributes by type, in bytes.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AttributeMemory {
+    /// Attribute values themselves, including scalar attributes.
+    values: usize,
+    /// Boolean arrays.
+    arr_bool: usize,
+    /// `i32` arrays.
+    arr_i32: usize,
+    /// `i64` arrays.
+    arr_i64: usize,
+    /// `f32` arrays.
+    arr_f32: usize,
+    /// `f64` arrays.
+    arr_f64: usize,
+    /// Strings.
+    string: usize,
+    /// Binaries.
+    binary: usize,
+}
+
+impl AttributeMemory {
+    /// Adds the attribute.
+    fn add(&mut self, value: &AttributeValue) {
+        self.values += mem::size_of::<AttributeValue>();
+        let heap = attribute_heap_size(value);
+        match value {
+            AttributeValue::ArrBool(_) => self.arr_bool += heap,
+            AttributeValue::ArrI32(_) => self.arr_i32 += heap,
+            AttributeValue::ArrI64(_) => self.arr_i64 += heap,
+            AttributeValue::ArrF32(_) => self.arr_f32 += heap,
+            AttributeValue::ArrF64(_) => self.arr_f64 += heap,
+            AttributeValue::String(_) => self.string += heap,
+            AttributeValue::Binary(_) => self.binary += heap,
+            _ => {}
+        }
+    }
+
+    /// Returns the size of the attribute values themselves.
+    ///
+    /// This includes scalar attributes and the headers of the array, string
+    /// and binary attributes.
+    pub fn values(&self) -> usize {
+        self.values
+    }
+
+    /// Returns the size of the boolean array contents.
+    pub fn arr_bool(&self) -> usize {
+        self.arr_bool
+    }
+
+    /// Returns the size of the `i32` array contents.
+    pub fn arr_i32(&self) -> usize {
+        self.arr_i32
+    }
+
+    /// Returns the size of the `i64` array contents.
+    pub fn arr_i64(&self) -> usize {
+        self.arr_i64
+    }
+
+    /// Returns the size of the `f32` array contents.
+    pub fn arr_f32(&self) -> usize {
+        self.arr_f32
+    }
+
+    /// Returns the size of the `f64` array contents.
+    pub fn arr_f64(&self) -> usize {
+        self.arr_f64
+    }
+
+    /// Returns the size of the string contents.
+    pub fn string(&self) -> usize {
+        self.string
+    }
+
+    /// Returns the size of the binary contents.
+    pub fn binary(&self) -> usize {
+        self.binary
+    }
+
+    /// Returns the total size of the array contents.
+    pub fn arrays(&self) -> usize {
+        self.arr_bool + self.arr_i32 + self.arr_i64 + self.arr_f32 + self.arr_f64
+    }
+
+    /// Returns the total size.
+    pub fn total(&self) -> usize {
+        self.values + self.arrays() + self.string + self.binary
+    }
+}
+
+impl Document {
+    /// Returns the estimated memory usage of the document.
+    ///
+    /// This walks the whole tree, so it is not cheap for large documents.
+    pub fn memory_report(&self) -> MemoryReport {
+        let mut report = MemoryReport::default();
+        let mut stack = vec![self.tree.root()];
+        while let Some(node) = stack.pop() {
+            add_node(&mut report, node);
+            stack.extend(node.children());
+        }
+        #[cfg(feature = "lazy-arrays")]
+        {
+            report.lazy_arrays = self.lazy_arrays.heap_size();
+        }
+        report.objects = self.objects.heap_size();
+        report.connections = self.connections.heap_size();
+        report.definitions = self.definitions.heap_size();
+        report.symbols = self.symbols.heap_size();
+        report.properties = self.properties.heap_size();
+        report
+    }
+}
+
+/// Adds the node to the report.
+fn add_node(report: &mut MemoryReport, node: NodeHandle<'_>) {
+    report.node_count += 1;
+    report.tree_nodes += NODE_OVERHEAD;
+    for attr in node.attributes() {
+        report.attributes.add(attr);
+    }
+}
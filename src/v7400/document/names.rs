@@ -0,0 +1,190 @@
+//! Unique object names.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::v7400::{
+    object::{Name, ObjectId},
+    Document,
+};
+
+/// Options for generating unique object names.
+///
+/// This is used by [`Document::unique_names`].
+///
+/// [`Document::unique_names`]: struct.Document.html#method.unique_names
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NameSanitizer {
+    /// Whether to strip namespaces.
+    strip_namespace: bool,
+    /// Whether to replace characters other than ASCII alphanumerics.
+    ascii: bool,
+    /// Separator between the name and the deduplication suffix.
+    separator: String,
+    /// Whether names are unique only among objects of the same class.
+    per_class: bool,
+    /// Whether names are compared case-insensitively.
+    case_insensitive: bool,
+}
+
+impl Default for NameSanitizer {
+    fn default() -> Self {
+        Self {
+            strip_namespace: true,
+            ascii: false,
+            separator: "_".to_owned(),
+            per_class: false,
+            case_insensitive: false,
+        }
+    }
+}
+
+impl NameSanitizer {
+    /// Creates a new default `NameSanitizer`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether to strip namespaces (such as `"Namespace:"`).
+    ///
+    /// Default is `true`.
+    pub fn strip_namespace(mut self, strip: bool) -> Self {
+        self.strip_namespace = strip;
+        self
+    }
+
+    /// Sets whether to slugify names to ASCII.
+    ///
+    /// If enabled, every run of characters other than ASCII alphanumerics and
+    /// `_` is replaced with a single `_`, and names starting with a digit are
+    /// prefixed with `_`.
+    ///
+    /// Default is `false`.
+    pub fn ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+
+    /// Sets the separator between the name and the deduplication suffix.
+    ///
+    /// Default is `"_"`, i.e. the duplicates of `"Cube"` are named
+    /// `"Cube_1"`, `"Cube_2"`, and so on.
+    pub fn separator(mut self, separator: impl Into<String>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Sets whether names are unique only among objects of the same class.
+    ///
+    /// Default is `false`, i.e. names are unique in the whole document.
+    pub fn per_class(mut self, per_class: bool) -> Self {
+        self.per_class = per_class;
+        self
+    }
+
+    /// Sets whether names are compared case-insensitively.
+    ///
+    /// This is useful for engines with case-insensitive asset names.
+    ///
+    /// Default is `false`.
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Returns the sanitized name, without deduplication.
+    ///
+    /// Empty names are replaced with `fallback`.
+    pub fn sanitize(&self, name: &str, fallback: &str) -> String {
+        let name = Name::new(name);
+        let name = if self.strip_namespace {
+            name.clean_name()
+        } else {
+            name.name()
+        };
+        let name = if name.is_empty() { fallback } else { name };
+        if !self.ascii {
+            return name.to_owned();
+        }
+
+        let mut slug = String::with_capacity(name.len());
+        for c in name.chars() {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                slug.push(c);
+            } else if !slug.ends_with('_') {
+                slug.push('_');
+            }
+        }
+        if slug.is_empty() || slug == "_" {
+            slug = fallback
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+                .collect();
+        }
+        if slug.is_empty() {
+            slug.push('_');
+        }
+        if slug.starts_with(|c: char| c.is_ascii_digit()) {
+            slug.insert(0, '_');
+        }
+        slug
+    }
+
+    /// Returns the key to detect collisions.
+    fn key(&self, class: &str, name: &str) -> (String, String) {
+        let class = if self.per_class { class } else { "" };
+        let name = if self.case_insensitive {
+            name.to_lowercase()
+        } else {
+            name.to_owned()
+        };
+        (class.to_owned(), name)
+    }
+}
+
+impl Document {
+    /// Returns unique sanitized names for all objects.
+    ///
+    /// Objects are processed in the document order, so the first object with
+    /// a name keeps it and later ones get numbered suffixes. Objects without
+    /// names are named after their class.
+    ///
+    /// ```
+    /// # #[cfg(feature = "edit")]
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// use fbxcel_dom::v7400::{
+    ///     edit::{DocumentBuilder, ModelKind},
+    ///     NameSanitizer,
+    /// };
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let first = builder.add_model("Rig:Cube", ModelKind::Null, None);
+    /// let second = builder.add_model("Cube", ModelKind::Null, None);
+    /// let third = builder.add_model("Left Arm (1)", ModelKind::Null, None);
+    /// let doc = builder.build()?;
+    ///
+    /// let names = doc.unique_names(&NameSanitizer::new().ascii(true));
+    /// assert_eq!(names[&first], "Cube");
+    /// assert_eq!(names[&second], "Cube_1");
+    /// assert_eq!(names[&third], "Left_Arm_1_");
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "edit"))]
+    /// # fn main() {}
+    /// ```
+    pub fn unique_names(&self, sanitizer: &NameSanitizer) -> HashMap<ObjectId, String> {
+        let mut used = HashSet::new();
+        let mut names = HashMap::new();
+        for obj in self.objects() {
+            let class = obj.class();
+            let base = sanitizer.sanitize(obj.name().unwrap_or_default(), class);
+            let mut name = base.clone();
+            let mut suffix = 1_usize;
+            while !used.insert(sanitizer.key(class, &name)) {
+                name = format!("{}{}{}", base, sanitizer.separator, suffix);
+                suffix += 1;
+            }
+            names.insert(obj.object_id(), name);
+        }
+        names
+    }
+}
@@ -0,0 +1,794 @@
+//! Error recovery for the non-strict loading.
+
+use std::{collections::HashSet, convert::TryFrom, io::Read};
+
+use fbxcel::{
+    low::{v7400::AttributeValue, FbxHeader, FbxVersion},
+    tree::v7400::{NodeId, Tree},
+};
+use log::{trace, warn};
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::v7400::{error::LimitError, object::ObjectId, version, LoadError, LoadWarning};
+
+#[cfg(feature = "lazy-arrays")]
+use super::lazy::LazyArrays;
+use super::{filter::NodeFilter, limits::ResourceLimits};
+
+/// Length of the binary FBX header.
+const HEADER_LEN: usize = 27;
+
+/// Binary FBX node records read into a tree.
+pub(crate) struct LoadedTree {
+    /// FBX version in the header.
+    pub(crate) fbx_version: FbxVersion,
+    /// Tree of the node records.
+    pub(crate) tree: Tree,
+    /// Recorded warnings.
+    pub(crate) warnings: Vec<LoadWarning>,
+    /// Offset of the data following the node records, if the node records
+    /// are terminated properly.
+    pub(crate) end: Option<usize>,
+    /// Compressed arrays to decode on first use.
+    #[cfg(feature = "lazy-arrays")]
+    pub(crate) lazy_arrays: LazyArrays,
+}
+
+/// Reads the binary FBX data (including the header) into a tree, recovering
+/// from broken node records and attributes.
+///
+/// If `threads` is more than 1 and the `rayon` feature is enabled,
+/// compressed arrays are decompressed in parallel with that many threads
+/// after reading the node records.
+/// If `lazy` is true and the `lazy-arrays` feature is enabled, compressed
+/// arrays are left empty in the tree and returned as
+/// [`LoadedTree::lazy_arrays`] instead.
+///
+/// Returns an error only if the header is unreadable or a resource limit is
+/// exceeded.
+/// The footer is ignored.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "load_tree",
+        skip_all,
+        fields(threads = threads, lazy = lazy)
+    )
+)]
+pub(crate) fn load_tree(
+    data: &[u8],
+    filter: &NodeFilter,
+    limits: &ResourceLimits,
+    threads: usize,
+    lazy: bool,
+) -> Result<LoadedTree, LoadError> {
+    let fbx_version = FbxHeader::load(data.get(..HEADER_LEN).unwrap_or(data))
+        .map_err(LoadError::new)?
+        .version();
+    trace!("Loading FBX tree tolerantly: version={:?}", fbx_version);
+
+    let mut tree_reader = TreeReader {
+        data,
+        large: version::Capabilities::new(fbx_version).has_64bit_offsets(),
+        filter,
+        limits,
+        tree: Tree::default(),
+        nodes: 0,
+        warnings: Vec::new(),
+        limit_error: None,
+        deferred: if (cfg!(feature = "rayon") && threads > 1)
+            || (cfg!(feature = "lazy-arrays") && lazy)
+        {
+            Some(Vec::new())
+        } else {
+            None
+        },
+        pending_arrays: Vec::new(),
+    };
+    let root_id = tree_reader.tree.root().node_id();
+    let end = tree_reader.read_children(root_id, "", 1, HEADER_LEN, data.len());
+    if let Some(e) = tree_reader.limit_error {
+        return Err(e.into());
+    }
+    #[cfg(feature = "lazy-arrays")]
+    let mut lazy_arrays = LazyArrays::default();
+    match tree_reader.deferred.take() {
+        #[cfg(feature = "lazy-arrays")]
+        Some(deferred) if lazy => {
+            let max_bytes = limits.array_bytes_limit();
+            for node in deferred {
+                for array in &node.arrays {
+                    lazy_arrays.insert(node.node, &node.path, array, max_bytes);
+                }
+                tree_reader.tree.set_attributes_vec(node.node, node.attrs);
+            }
+        }
+        #[cfg(feature = "rayon")]
+        Some(deferred) => {
+            let max_bytes = limits.array_bytes_limit();
+            decode_deferred(&mut tree_reader, deferred, threads, max_bytes)
+        }
+        _ => {}
+    }
+
+    Ok(LoadedTree {
+        fbx_version,
+        tree: tree_reader.tree,
+        warnings: tree_reader.warnings,
+        end,
+        #[cfg(feature = "lazy-arrays")]
+        lazy_arrays,
+    })
+}
+
+/// Reads the attributes of a node from the given attribute records.
+///
+/// Broken attributes are recovered from and recorded as warnings.
+/// Returns an error if a resource limit is exceeded.
+pub(crate) fn read_attributes(
+    data: &[u8],
+    path: &str,
+    count: u64,
+    limits: &ResourceLimits,
+) -> Result<(Vec<AttributeValue>, Vec<LoadWarning>), LimitError> {
+    let filter = NodeFilter::default();
+    let mut tree_reader = TreeReader {
+        data,
+        large: false,
+        filter: &filter,
+        limits,
+        tree: Tree::default(),
+        nodes: 0,
+        warnings: Vec::new(),
+        limit_error: None,
+        deferred: None,
+        pending_arrays: Vec::new(),
+    };
+    let attrs = tree_reader.read_attributes(path, 0, data.len(), count);
+    match tree_reader.limit_error {
+        Some(e) => Err(e),
+        None => Ok((attrs, tree_reader.warnings)),
+    }
+}
+
+/// Tolerant binary FBX node records reader.
+struct TreeReader<'a> {
+    /// Whole FBX data.
+    data: &'a [u8],
+    /// Whether the node headers use 64-bit fields.
+    large: bool,
+    /// Filter of the nodes to skip.
+    filter: &'a NodeFilter,
+    /// Resource limits.
+    limits: &'a ResourceLimits,
+    /// Tree being built.
+    tree: Tree,
+    /// Number of nodes read.
+    nodes: usize,
+    /// Recorded warnings.
+    warnings: Vec<LoadWarning>,
+    /// Exceeded resource limit, which stops reading.
+    limit_error: Option<LimitError>,
+    /// Nodes with compressed arrays to decode after reading the node
+    /// records, or `None` to decode them immediately.
+    deferred: Option<Vec<DeferredNode<'a>>>,
+    /// Compressed arrays in the attributes being read.
+    pending_arrays: Vec<DeferredArray<'a>>,
+}
+
+/// Node with compressed arrays to decode.
+#[cfg_attr(
+    not(any(feature = "rayon", feature = "lazy-arrays")),
+    allow(dead_code) // Nodes are never deferred without these features.
+)]
+struct DeferredNode<'a> {
+    /// Node ID.
+    node: NodeId,
+    /// Path of the node.
+    path: String,
+    /// Attributes, with placeholders for the compressed arrays.
+    attrs: Vec<AttributeValue>,
+    /// Compressed arrays.
+    arrays: Vec<DeferredArray<'a>>,
+}
+
+/// Compressed array to decode.
+#[cfg_attr(
+    not(any(feature = "rayon", feature = "lazy-arrays")),
+    allow(dead_code) // Arrays are never deferred without these features.
+)]
+pub(super) struct DeferredArray<'a> {
+    /// Index of the attribute.
+    pub(super) index: usize,
+    /// Type code of the attribute.
+    pub(super) type_code: u8,
+    /// Compressed data.
+    pub(super) raw: &'a [u8],
+    /// Declared elements count.
+    pub(super) declared: usize,
+    /// Whether the data is truncated.
+    pub(super) truncated: bool,
+}
+
+impl<'a> TreeReader<'a> {
+    /// Records the exceeded limit if any, and returns true if reading should
+    /// stop.
+    fn limit(&mut self, result: Result<(), LimitError>) -> bool {
+        if let Err(e) = result {
+            self.limit_error = Some(e);
+        }
+        self.limit_error.is_some()
+    }
+
+    /// Records a warning.
+    fn warn(&mut self, warning: LoadWarning) {
+        warn!("Recovering from broken FBX data: {}", warning);
+        self.warnings.push(warning);
+    }
+
+    /// Returns the bytes in `pos..pos+len` if they are before `end`.
+    fn bytes(&self, pos: usize, len: usize, end: usize) -> Option<&[u8]> {
+        let stop = pos.checked_add(len)?;
+        if stop > end {
+            return None;
+        }
+        self.data.get(pos..stop)
+    }
+
+    /// Reads an unsigned integer of 4 bytes, or 8 bytes for large headers.
+    fn read_header_field(&self, pos: usize) -> u64 {
+        if self.large {
+            let mut buf = [0; 8];
+            buf.copy_from_slice(&self.data[pos..pos + 8]);
+            u64::from_le_bytes(buf)
+        } else {
+            u64::from(read_u32(&self.data[pos..pos + 4]))
+        }
+    }
+
+    /// Reads node records in `pos..end` as children of `parent`.
+    ///
+    /// `depth` is the depth of the children, starting from 1 for the
+    /// toplevel nodes.
+    /// Returns the position after the node end marker, if found.
+    fn read_children(
+        &mut self,
+        parent: NodeId,
+        parent_path: &str,
+        depth: usize,
+        mut pos: usize,
+        end: usize,
+    ) -> Option<usize> {
+        let field_len = if self.large { 8 } else { 4 };
+        let header_len = field_len * 3 + 1;
+        while pos < end {
+            if self.limit_error.is_some() {
+                return None;
+            }
+            if self.bytes(pos, header_len, end).is_none() {
+                self.warn(LoadWarning::TruncatedNode {
+                    node: parent_path.to_owned(),
+                });
+                return None;
+            }
+            let end_offset = self.read_header_field(pos);
+            let num_attrs = self.read_header_field(pos + field_len);
+            let attrs_len = self.read_header_field(pos + field_len * 2);
+            let name_len = usize::from(self.data[pos + field_len * 3]);
+            if end_offset == 0 {
+                // Node end marker.
+                return Some(pos + header_len);
+            }
+
+            let name_start = pos + header_len;
+            let name = match self.bytes(name_start, name_len, end) {
+                Some(v) => String::from_utf8_lossy(v).into_owned(),
+                None => {
+                    self.warn(LoadWarning::TruncatedNode {
+                        node: parent_path.to_owned(),
+                    });
+                    return None;
+                }
+            };
+            let path = if parent_path.is_empty() {
+                name.clone()
+            } else {
+                format!("{}/{}", parent_path, name)
+            };
+            if name.contains('\u{FFFD}') {
+                self.warn(LoadWarning::InvalidUtf8 { node: path.clone() });
+            }
+
+            let attrs_start = name_start + name_len;
+            let node_end = usize::try_from(end_offset)
+                .ok()
+                .filter(|&v| v >= attrs_start && v <= end);
+            let attrs_end = usize::try_from(attrs_len)
+                .ok()
+                .and_then(|len| attrs_start.checked_add(len))
+                .filter(|&v| v <= node_end.unwrap_or(end));
+            let (node_end, attrs_end) = match (node_end, attrs_end) {
+                (Some(node_end), Some(attrs_end)) => (node_end, attrs_end),
+                (node_end, attrs_end) => {
+                    self.warn(LoadWarning::TruncatedNode { node: path.clone() });
+                    let node_end = node_end.unwrap_or(end);
+                    (node_end, attrs_end.unwrap_or(node_end))
+                }
+            };
+
+            if self.filter.matches_path(&path) {
+                trace!("Skipping node: path={:?}", path);
+                pos = node_end;
+                continue;
+            }
+            self.nodes += 1;
+            let checked = self
+                .limits
+                .check_nodes(self.nodes)
+                .and_then(|_| self.limits.check_depth(&path, depth));
+            if self.limit(checked) {
+                return None;
+            }
+            let node_id = self.tree.append_new(parent, &name);
+            let attrs = self.read_attributes(&path, attrs_start, attrs_end, num_attrs);
+            match &mut self.deferred {
+                Some(deferred) if !self.pending_arrays.is_empty() => {
+                    deferred.push(DeferredNode {
+                        node: node_id,
+                        path: path.clone(),
+                        attrs,
+                        arrays: std::mem::take(&mut self.pending_arrays),
+                    });
+                }
+                _ => self.tree.set_attributes_vec(node_id, attrs),
+            }
+            if attrs_end < node_end {
+                self.read_children(node_id, &path, depth + 1, attrs_end, node_end);
+            }
+            pos = node_end;
+        }
+
+        None
+    }
+
+    /// Reads node attributes in `pos..end`.
+    fn read_attributes(
+        &mut self,
+        path: &str,
+        mut pos: usize,
+        end: usize,
+        count: u64,
+    ) -> Vec<AttributeValue> {
+        let mut attrs = Vec::new();
+        while (attrs.len() as u64) < count {
+            let type_code = match self.bytes(pos, 1, end) {
+                Some(v) => v[0],
+                None => break,
+            };
+            pos += 1;
+            let read = match type_code {
+                b'C' | b'Y' | b'I' | b'L' | b'F' | b'D' => self.read_scalar(type_code, pos, end),
+                b'b' | b'i' | b'l' | b'f' | b'd' => {
+                    self.read_array(path, attrs.len(), type_code, pos, end)
+                }
+                b'S' | b'R' => self.read_special(path, type_code, pos, end),
+                _ => {
+                    self.warn(LoadWarning::InvalidAttributeType {
+                        node: path.to_owned(),
+                        type_code,
+                    });
+                    return attrs;
+                }
+            };
+            match read {
+                Some((value, next_pos)) => {
+                    attrs.push(value);
+                    pos = next_pos;
+                }
+                None if self.limit_error.is_some() => return attrs,
+                None => break,
+            }
+        }
+        if (attrs.len() as u64) < count {
+            self.warn(LoadWarning::MissingAttributes {
+                node: path.to_owned(),
+                declared: count,
+                actual: attrs.len(),
+            });
+        }
+
+        attrs
+    }
+
+    /// Reads a scalar attribute value, and returns it with the end position.
+    fn read_scalar(
+        &self,
+        type_code: u8,
+        pos: usize,
+        end: usize,
+    ) -> Option<(AttributeValue, usize)> {
+        let len = match type_code {
+            b'C' => 1,
+            b'Y' => 2,
+            b'I' | b'F' => 4,
+            _ => 8,
+        };
+        let bytes = self.bytes(pos, len, end)?;
+        let value = match type_code {
+            b'C' => AttributeValue::Bool(read_bool(bytes[0])),
+            b'Y' => AttributeValue::I16(i16::from_le_bytes([bytes[0], bytes[1]])),
+            b'I' => AttributeValue::I32(read_u32(bytes) as i32),
+            b'F' => AttributeValue::F32(f32::from_bits(read_u32(bytes))),
+            b'L' => AttributeValue::I64(read_u64(bytes) as i64),
+            _ => AttributeValue::F64(f64::from_bits(read_u64(bytes))),
+        };
+        Some((value, pos + len))
+    }
+
+    /// Reads an array attribute value, and returns it with the end position.
+    ///
+    /// Returns `None` if the following attributes cannot be read.
+    fn read_array(
+        &mut self,
+        path: &str,
+        attr_index: usize,
+        type_code: u8,
+        pos: usize,
+        end: usize,
+    ) -> Option<(AttributeValue, usize)> {
+        let header = self.bytes(pos, 12, end)?;
+        let declared = read_u32(&header[0..4]) as usize;
+        let encoding = read_u32(&header[4..8]);
+        let bytelen = read_u32(&header[8..12]) as usize;
+        let checked = self
+            .limits
+            .check_array(path, declared as u64 * elem_size(type_code) as u64)
+            .and_then(|_| self.limits.check_array(path, bytelen as u64));
+        if self.limit(checked) {
+            return None;
+        }
+        let data_start = pos + 12;
+        let data_end = data_start.saturating_add(bytelen);
+        let truncated = data_end > end;
+        let data = self.data;
+        let raw = &data[data_start..std::cmp::min(data_end, end)];
+
+        let value = match encoding {
+            1 if self.deferred.is_some() => {
+                self.pending_arrays.push(DeferredArray {
+                    index: attr_index,
+                    type_code,
+                    raw,
+                    declared,
+                    truncated,
+                });
+                array_value(type_code, &[])
+            }
+            0 | 1 => {
+                let (value, warning) = decode_array(
+                    path,
+                    type_code,
+                    encoding,
+                    raw,
+                    declared,
+                    truncated,
+                    self.limits.array_bytes_limit(),
+                );
+                if let Some(warning) = warning {
+                    self.warn(warning);
+                }
+                value
+            }
+            _ => {
+                self.warn(LoadWarning::InvalidArrayEncoding {
+                    node: path.to_owned(),
+                    encoding,
+                });
+                return None;
+            }
+        };
+        // Following attributes are unavailable if the data is truncated.
+        Some((value, std::cmp::min(data_end, end)))
+    }
+
+    /// Reads a string or binary attribute value, and returns it with the end
+    /// position.
+    fn read_special(
+        &mut self,
+        path: &str,
+        type_code: u8,
+        pos: usize,
+        end: usize,
+    ) -> Option<(AttributeValue, usize)> {
+        let len = read_u32(self.bytes(pos, 4, end)?) as usize;
+        if self.limit(self.limits.check_array(path, len as u64)) {
+            return None;
+        }
+        let data_start = pos + 4;
+        let data_end = data_start.saturating_add(len);
+        let bytes = &self.data[data_start..std::cmp::min(data_end, end)];
+        let value = if type_code == b'S' {
+            let value = String::from_utf8_lossy(bytes).into_owned();
+            if std::str::from_utf8(bytes).is_err() {
+                self.warn(LoadWarning::InvalidUtf8 {
+                    node: path.to_owned(),
+                });
+            }
+            AttributeValue::String(value)
+        } else {
+            AttributeValue::Binary(bytes.to_vec())
+        };
+        if data_end > end {
+            self.warn(LoadWarning::TruncatedNode {
+                node: path.to_owned(),
+            });
+            return Some((value, end));
+        }
+        Some((value, data_end))
+    }
+}
+
+/// Decodes an array attribute value from the raw (possibly compressed)
+/// data, and returns it with the warning if the data is broken.
+///
+/// Compressed data inflating to more than `max_bytes` is treated as
+/// truncated at `max_bytes`.
+pub(super) fn decode_array(
+    path: &str,
+    type_code: u8,
+    encoding: u32,
+    raw: &[u8],
+    declared: usize,
+    mut truncated: bool,
+    max_bytes: usize,
+) -> (AttributeValue, Option<LoadWarning>) {
+    let decoded = if encoding == 1 {
+        let mut decoded = Vec::new();
+        let limit = u64::try_from(max_bytes)
+            .unwrap_or(u64::MAX)
+            .saturating_add(1);
+        let res = libflate::zlib::Decoder::new(raw)
+            .and_then(|decoder| decoder.take(limit).read_to_end(&mut decoded));
+        if let Err(e) = res {
+            trace!("Failed to decode array: node={:?}, error={}", path, e);
+            truncated = true;
+        }
+        if decoded.len() > max_bytes {
+            trace!(
+                "Decompressed array exceeds the limit: node={:?}, limit={}",
+                path,
+                max_bytes
+            );
+            decoded.truncate(max_bytes);
+            truncated = true;
+        }
+        decoded
+    } else {
+        raw.to_vec()
+    };
+
+    let chunks = decoded.chunks_exact(elem_size(type_code));
+    let actual = chunks.len();
+    let warning = if truncated {
+        Some(LoadWarning::TruncatedArray {
+            node: path.to_owned(),
+            declared,
+            actual,
+        })
+    } else if actual != declared || !chunks.remainder().is_empty() {
+        Some(LoadWarning::ArrayCountMismatch {
+            node: path.to_owned(),
+            declared,
+            actual,
+        })
+    } else {
+        None
+    };
+
+    (array_value(type_code, &decoded), warning)
+}
+
+/// Returns the size of an element of the array type.
+fn elem_size(type_code: u8) -> usize {
+    match type_code {
+        b'b' => 1,
+        b'i' | b'f' => 4,
+        _ => 8,
+    }
+}
+
+/// Returns the array attribute value of the given type from the decoded
+/// data.
+fn array_value(type_code: u8, decoded: &[u8]) -> AttributeValue {
+    match type_code {
+        b'b' => AttributeValue::ArrBool(decoded.iter().map(|&b| read_bool(b)).collect()),
+        b'i' => AttributeValue::ArrI32(
+            decoded
+                .chunks_exact(4)
+                .map(|c| read_u32(c) as i32)
+                .collect(),
+        ),
+        b'f' => AttributeValue::ArrF32(
+            decoded
+                .chunks_exact(4)
+                .map(|c| f32::from_bits(read_u32(c)))
+                .collect(),
+        ),
+        b'l' => AttributeValue::ArrI64(
+            decoded
+                .chunks_exact(8)
+                .map(|c| read_u64(c) as i64)
+                .collect(),
+        ),
+        _ => AttributeValue::ArrF64(
+            decoded
+                .chunks_exact(8)
+                .map(|c| f64::from_bits(read_u64(c)))
+                .collect(),
+        ),
+    }
+}
+
+/// Decodes the deferred compressed arrays in parallel on a `rayon` thread
+/// pool with the given number of threads, and sets the attributes of the
+/// nodes.
+///
+/// The warnings for the arrays are recorded in the document order, after the
+/// warnings for the node records.
+#[cfg(feature = "rayon")]
+fn decode_deferred(
+    reader: &mut TreeReader<'_>,
+    mut deferred: Vec<DeferredNode<'_>>,
+    threads: usize,
+    max_bytes: usize,
+) {
+    let arrays: Vec<(usize, &str, &DeferredArray<'_>)> = deferred
+        .iter()
+        .enumerate()
+        .flat_map(|(node, v)| v.arrays.iter().map(move |array| (node, &*v.path, array)))
+        .collect();
+    trace!(
+        "Decompressing {} arrays with {} threads",
+        arrays.len(),
+        threads
+    );
+    let decode = |(node, path, array): (usize, &str, &DeferredArray<'_>)| {
+        let (value, warning) = decode_array(
+            path,
+            array.type_code,
+            1,
+            array.raw,
+            array.declared,
+            array.truncated,
+            max_bytes,
+        );
+        (node, array.index, value, warning)
+    };
+    let results: Vec<_> = match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+        Ok(pool) => pool.install(|| arrays.into_par_iter().map(decode).collect()),
+        Err(e) => {
+            warn!("Failed to create decompression threads: {}", e);
+            arrays.into_iter().map(decode).collect()
+        }
+    };
+
+    for (node, index, value, warning) in results {
+        if let Some(warning) = warning {
+            reader.warn(warning);
+        }
+        deferred[node].attrs[index] = value;
+    }
+    for node in deferred {
+        reader.tree.set_attributes_vec(node.node, node.attrs);
+    }
+}
+
+/// Toplevel nodes required to load a document.
+const REQUIRED_TOPLEVEL_NODES: &[&str] = &["Objects", "Documents", "Connections"];
+
+/// Repairs the tree which would make the document unloadable, and records
+/// the warnings.
+///
+/// Missing required toplevel nodes are added, and objects with duplicate IDs
+/// and duplicate connections are removed, keeping the first ones.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "repair_tree", skip_all)
+)]
+pub(crate) fn repair_tree(tree: &mut Tree, warnings: &mut Vec<LoadWarning>) {
+    for &name in REQUIRED_TOPLEVEL_NODES {
+        if tree.root().first_child_by_name(name).is_none() {
+            let warning = LoadWarning::MissingNode {
+                node: name.to_owned(),
+            };
+            warn!("Adding an empty node in non-strict mode: {}", warning);
+            warnings.push(warning);
+            let root_id = tree.root().node_id();
+            tree.append_new(root_id, name);
+        }
+    }
+
+    let mut removed = Vec::new();
+    let mut record = |warning: LoadWarning, node_id: NodeId| {
+        warn!("Skipping a node in non-strict mode: {}", warning);
+        warnings.push(warning);
+        removed.push(node_id);
+    };
+
+    let root = tree.root();
+    let objects = root
+        .children_by_name("Objects")
+        .flat_map(|node| node.children());
+    let documents = root
+        .children_by_name("Documents")
+        .flat_map(|node| node.children_by_name("Document"));
+    let mut obj_ids = HashSet::new();
+    for node in objects.chain(documents) {
+        let id = match node.attributes().first().and_then(AttributeValue::get_i64) {
+            Some(v) => v,
+            None => continue,
+        };
+        if !obj_ids.insert(id) {
+            record(
+                LoadWarning::DuplicateObjectId(ObjectId::new(id)),
+                node.node_id(),
+            );
+        }
+    }
+
+    let mut conns = HashSet::new();
+    let conn_nodes = root
+        .children_by_name("Connections")
+        .flat_map(|node| node.children_by_name("C"));
+    for node in conn_nodes {
+        let attrs = node.attributes();
+        let (source, destination) = match (
+            attrs.get(1).and_then(AttributeValue::get_i64),
+            attrs.get(2).and_then(AttributeValue::get_i64),
+        ) {
+            (Some(src), Some(dest)) => (src, dest),
+            _ => continue,
+        };
+        let label = attrs
+            .get(3)
+            .and_then(AttributeValue::get_string)
+            .map(ToOwned::to_owned);
+        if !conns.insert((source, destination, label.clone())) {
+            record(
+                LoadWarning::DuplicateConnection {
+                    source: ObjectId::new(source),
+                    destination: ObjectId::new(destination),
+                    label,
+                },
+                node.node_id(),
+            );
+        }
+    }
+
+    for node_id in removed {
+        tree.detach(node_id);
+    }
+}
+
+/// Reads a little endian `u32`.
+pub(crate) fn read_u32(bytes: &[u8]) -> u32 {
+    let mut buf = [0; 4];
+    buf.copy_from_slice(&bytes[..4]);
+    u32::from_le_bytes(buf)
+}
+
+/// Reads a little endian `u64`.
+pub(crate) fn read_u64(bytes: &[u8]) -> u64 {
+    let mut buf = [0; 8];
+    buf.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(buf)
+}
+
+/// Reads a boolean value.
+///
+/// `b'Y'` is true and `b'T'` is false, and some exporters use `0x01` and
+/// `0x00` instead.
+pub(crate) fn read_bool(v: u8) -> bool {
+    (v & 1) != 0
+}
@@ -0,0 +1,219 @@
+//! Document statistics.
+
+use std::collections::{BTreeMap, HashSet};
+
+use log::warn;
+
+use crate::v7400::{
+    object::{geometry, video, TypedObjectHandle},
+    Document,
+};
+
+/// Statistics summary of a document.
+///
+/// Data which cannot be read (such as a mesh with broken vertices) is not
+/// counted, and a warning is logged.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Statistics {
+    /// Number of objects by class.
+    objects_by_class: BTreeMap<String, usize>,
+    /// Number of geometry meshes.
+    meshes: usize,
+    /// Total number of control points of the meshes.
+    control_points: usize,
+    /// Total number of polygon vertices of the meshes.
+    polygon_vertices: usize,
+    /// Total number of polygons of the meshes.
+    polygons: usize,
+    /// Total number of triangles of the meshes.
+    triangles: usize,
+    /// Number of animation curves.
+    animation_curves: usize,
+    /// Total number of keys of the animation curves.
+    animation_keys: usize,
+    /// Total size of the embedded media contents, in bytes.
+    embedded_media_bytes: usize,
+    /// Depth of the deepest model hierarchy.
+    max_hierarchy_depth: usize,
+}
+
+impl Statistics {
+    /// Returns the number of objects by class (such as `Model` and
+    /// `Geometry`), sorted by the class name.
+    pub fn objects_by_class(&self) -> &BTreeMap<String, usize> {
+        &self.objects_by_class
+    }
+
+    /// Returns the number of objects of the given class.
+    pub fn object_count(&self, class: &str) -> usize {
+        self.objects_by_class.get(class).copied().unwrap_or(0)
+    }
+
+    /// Returns the total number of objects.
+    pub fn objects(&self) -> usize {
+        self.objects_by_class.values().sum()
+    }
+
+    /// Returns the number of geometry meshes.
+    pub fn meshes(&self) -> usize {
+        self.meshes
+    }
+
+    /// Returns the total number of control points of the meshes.
+    pub fn control_points(&self) -> usize {
+        self.control_points
+    }
+
+    /// Returns the total number of polygon vertices of the meshes.
+    pub fn polygon_vertices(&self) -> usize {
+        self.polygon_vertices
+    }
+
+    /// Returns the total number of polygons of the meshes.
+    pub fn polygons(&self) -> usize {
+        self.polygons
+    }
+
+    /// Returns the total number of triangles of the meshes.
+    ///
+    /// A polygon with `n` vertices is counted as `n - 2` triangles, which is
+    /// the number of triangles any triangulation gives.
+    pub fn triangles(&self) -> usize {
+        self.triangles
+    }
+
+    /// Returns the number of animation curves.
+    pub fn animation_curves(&self) -> usize {
+        self.animation_curves
+    }
+
+    /// Returns the total number of keys of the animation curves.
+    pub fn animation_keys(&self) -> usize {
+        self.animation_keys
+    }
+
+    /// Returns the total size of the media contents embedded in video
+    /// objects, in bytes.
+    pub fn embedded_media_bytes(&self) -> usize {
+        self.embedded_media_bytes
+    }
+
+    /// Returns the depth of the deepest model hierarchy.
+    ///
+    /// Models without parent models have depth 1, and this is 0 if the
+    /// document has no models.
+    pub fn max_hierarchy_depth(&self) -> usize {
+        self.max_hierarchy_depth
+    }
+
+    /// Adds the geometry mesh.
+    fn add_mesh(&mut self, mesh: &geometry::MeshHandle<'_>) {
+        self.meshes += 1;
+        match mesh.control_points() {
+            Ok(v) => self.control_points += v.len(),
+            Err(e) => warn!(
+                "Skipping control points of {:?} in statistics: {:#}",
+                mesh.object_id(),
+                e
+            ),
+        }
+        match mesh.raw_polygon_vertices() {
+            Ok(v) => {
+                let mut vertices: usize = 0;
+                for pv in v.data() {
+                    vertices += 1;
+                    if *pv < 0 {
+                        self.polygons += 1;
+                        self.triangles += vertices.saturating_sub(2);
+                        vertices = 0;
+                    }
+                }
+                self.polygon_vertices += v.data().len();
+            }
+            Err(e) => warn!(
+                "Skipping polygons of {:?} in statistics: {:#}",
+                mesh.object_id(),
+                e
+            ),
+        }
+    }
+}
+
+impl Document {
+    /// Returns the statistics summary of the document.
+    ///
+    /// This reads all objects (and decodes arrays of meshes and animation
+    /// curves if lazily loaded), so it is not cheap for large documents.
+    ///
+    /// ```
+    /// # #[cfg(feature = "edit")]
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// use fbxcel_dom::v7400::edit::{DocumentBuilder, ModelKind};
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let root = builder.add_model("Root", ModelKind::Null, None);
+    /// let model = builder.add_model("Quad", ModelKind::Mesh, Some(root));
+    /// let positions = [[0.0; 3], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+    /// builder.add_mesh("Quad", &positions, &[&[0, 1, 2, 3]], Some(model));
+    /// let doc = builder.build()?;
+    ///
+    /// let stats = doc.statistics();
+    /// assert_eq!(stats.object_count("Model"), 2);
+    /// assert_eq!(stats.control_points(), 4);
+    /// assert_eq!(stats.polygons(), 1);
+    /// assert_eq!(stats.triangles(), 2);
+    /// assert_eq!(stats.max_hierarchy_depth(), 2);
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "edit"))]
+    /// # fn main() {}
+    /// ```
+    pub fn statistics(&self) -> Statistics {
+        let mut stats = Statistics::default();
+        for obj in self.objects() {
+            *stats
+                .objects_by_class
+                .entry(obj.class().to_owned())
+                .or_insert(0) += 1;
+            match obj.get_typed() {
+                TypedObjectHandle::Geometry(geometry::TypedGeometryHandle::Mesh(mesh)) => {
+                    stats.add_mesh(&mesh)
+                }
+                TypedObjectHandle::AnimationCurve(curve) => {
+                    stats.animation_curves += 1;
+                    match curve.key_times() {
+                        Ok(v) => stats.animation_keys += v.len(),
+                        Err(e) => warn!(
+                            "Skipping keys of {:?} in statistics: {:#}",
+                            curve.object_id(),
+                            e
+                        ),
+                    }
+                }
+                TypedObjectHandle::Video(video::TypedVideoHandle::Clip(clip)) => {
+                    stats.embedded_media_bytes += clip.content().map_or(0, <[u8]>::len);
+                }
+                _ => {}
+            }
+        }
+        stats.max_hierarchy_depth = self.max_hierarchy_depth();
+        stats
+    }
+
+    /// Returns the depth of the deepest model hierarchy.
+    ///
+    /// Models in cyclic hierarchies are visited only once.
+    fn max_hierarchy_depth(&self) -> usize {
+        let mut visited = HashSet::new();
+        let mut stack: Vec<_> = self.model_roots().map(|model| (model, 1)).collect();
+        let mut max_depth = 0;
+        while let Some((model, depth)) = stack.pop() {
+            if !visited.insert(model.object_id()) {
+                continue;
+            }
+            max_depth = max_depth.max(depth);
+            stack.extend(model.child_models().map(|child| (child, depth + 1)));
+        }
+        max_depth
+    }
+}
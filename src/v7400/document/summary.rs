@@ -0,0 +1,250 @@
+//! Document summary.
+
+use std::fmt;
+
+use fbxcel::{low::v7400::AttributeValue, tree::v7400::NodeHandle};
+
+use crate::v7400::Document;
+
+/// Default maximum number of array elements to print.
+const DEFAULT_MAX_ARRAY_LEN: usize = 8;
+/// Default maximum number of child nodes to print for each node.
+const DEFAULT_MAX_CHILDREN: usize = 32;
+
+/// Human-readable summary of a document.
+///
+/// This is created by [`Document::summary`], and printed with `Display`.
+/// The summary has the number of objects by class and the node tree, with
+/// long arrays and long child node lists truncated.
+///
+/// [`Document::summary`]: struct.Document.html#method.summary
+#[derive(Debug, Clone, Copy)]
+pub struct DocumentSummary<'a> {
+    /// Document.
+    doc: &'a Document,
+    /// Maximum number of array elements to print.
+    max_array_len: usize,
+    /// Maximum number of child nodes to print for each node.
+    max_children: usize,
+    /// Maximum depth of nodes to print.
+    max_depth: Option<usize>,
+}
+
+impl<'a> DocumentSummary<'a> {
+    /// Sets the maximum number of array elements to print.
+    ///
+    /// The default is 8.
+    pub fn max_array_len(mut self, len: usize) -> Self {
+        self.max_array_len = len;
+        self
+    }
+
+    /// Sets the maximum number of child nodes to print for each node.
+    ///
+    /// The default is 32.
+    pub fn max_children(mut self, count: usize) -> Self {
+        self.max_children = count;
+        self
+    }
+
+    /// Sets the maximum depth of nodes to print.
+    ///
+    /// Top-level nodes are depth 0. The default is unlimited.
+    pub fn max_depth(mut self, depth: Option<usize>) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Writes the node and its descendants.
+    fn fmt_node(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        node: NodeHandle<'a>,
+        depth: usize,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{:indent$}{}:",
+            "",
+            node.name(),
+            indent = (depth + 1) * 2
+        )?;
+        for index in 0..node.attributes().len() {
+            f.write_str(if index == 0 { " " } else { ", " })?;
+            match self.doc.attribute(&node, index) {
+                Ok(Some(attr)) => self.fmt_attribute(f, attr)?,
+                Ok(None) => f.write_str("<missing>")?,
+                Err(e) => write!(f, "<error: {}>", e)?,
+            }
+        }
+        writeln!(f)?;
+
+        let count = node.children().count();
+        if count == 0 {
+            return Ok(());
+        }
+        if self.max_depth.map_or(false, |max| depth >= max) {
+            return writeln!(
+                f,
+                "{:indent$}... {} child nodes",
+                "",
+                count,
+                indent = (depth + 2) * 2
+            );
+        }
+        for child in node.children().take(self.max_children) {
+            self.fmt_node(f, child, depth + 1)?;
+        }
+        if count > self.max_children {
+            writeln!(
+                f,
+                "{:indent$}... {} more nodes",
+                "",
+                count - self.max_children,
+                indent = (depth + 2) * 2
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the attribute.
+    fn fmt_attribute(&self, f: &mut fmt::Formatter<'_>, attr: &AttributeValue) -> fmt::Result {
+        match attr {
+            AttributeValue::Bool(v) => write!(f, "{}", v),
+            AttributeValue::I16(v) => write!(f, "{}", v),
+            AttributeValue::I32(v) => write!(f, "{}", v),
+            AttributeValue::I64(v) => write!(f, "{}", v),
+            AttributeValue::F32(v) => write!(f, "{:?}", v),
+            AttributeValue::F64(v) => write!(f, "{:?}", v),
+            AttributeValue::ArrBool(v) => self.fmt_array(f, "bool", v),
+            AttributeValue::ArrI32(v) => self.fmt_array(f, "i32", v),
+            AttributeValue::ArrI64(v) => self.fmt_array(f, "i64", v),
+            AttributeValue::ArrF32(v) => self.fmt_array(f, "f32", v),
+            AttributeValue::ArrF64(v) => self.fmt_array(f, "f64", v),
+            AttributeValue::String(v) => write!(f, "{:?}", v),
+            AttributeValue::Binary(v) => write!(f, "<binary: {} bytes>", v.len()),
+        }
+    }
+
+    /// Writes the array with the type and the length, truncating elements.
+    fn fmt_array<T: fmt::Debug>(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        ty: &str,
+        values: &[T],
+    ) -> fmt::Result {
+        write!(f, "[{}; {}] [", ty, values.len())?;
+        for (i, v) in values.iter().take(self.max_array_len).enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{:?}", v)?;
+        }
+        if values.len() > self.max_array_len {
+            f.write_str(if self.max_array_len == 0 {
+                "..."
+            } else {
+                ", ..."
+            })?;
+        }
+        f.write_str("]")
+    }
+}
+
+impl fmt::Display for DocumentSummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let doc = self.doc;
+        match doc.fbx_version() {
+            Some(version) => write!(f, "FBX {}.{} document", version.major(), version.minor())?,
+            None => f.write_str("FBX document")?,
+        }
+        writeln!(
+            f,
+            ": {} objects, {} connections",
+            doc.objects().count(),
+            doc.connections().count()
+        )?;
+
+        let stats = doc.statistics();
+        for (class, count) in stats.objects_by_class() {
+            writeln!(f, "  {:?}: {}", class, count)?;
+        }
+
+        writeln!(f, "Nodes:")?;
+        for node in doc.tree().root().children() {
+            self.fmt_node(f, node, 0)?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints the summarized document instead of the whole tree.
+///
+/// Handles holding a reference to the document also print it in short.
+///
+/// ```
+/// # #[cfg(feature = "edit")]
+/// # fn main() -> Result<(), anyhow::Error> {
+/// use fbxcel_dom::v7400::edit::{DocumentBuilder, ModelKind};
+///
+/// let mut builder = DocumentBuilder::new();
+/// builder.add_model("Hips", ModelKind::Null, None);
+/// let doc = builder.build()?;
+///
+/// let debug = format!("{:?}", doc);
+/// assert!(debug.starts_with("Document {"));
+/// assert!(debug.contains("objects: 1"));
+/// let model = doc.model_by_path("Hips").expect("Model should exist");
+/// assert_eq!(model.to_string(), format!("Model(\"Hips\", id={})", model.object_id()));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "edit"))]
+/// # fn main() {}
+/// ```
+impl fmt::Debug for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Document")
+            .field("fbx_version", &self.fbx_version())
+            .field("objects", &self.objects().count())
+            .field("connections", &self.connections().count())
+            .field("load_warnings", &self.load_warnings.len())
+            .field("warnings", &self.warnings.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Document {
+    /// Returns the human-readable summary of the document.
+    ///
+    /// ```
+    /// # #[cfg(feature = "edit")]
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// use fbxcel_dom::v7400::edit::{DocumentBuilder, ModelKind};
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let model = builder.add_model("Quad", ModelKind::Mesh, None);
+    /// let positions = [[0.0; 3], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+    /// builder.add_mesh("Quad", &positions, &[&[0, 1, 2, 3]], Some(model));
+    /// let doc = builder.build()?;
+    ///
+    /// let summary = doc.summary().max_array_len(3).to_string();
+    /// assert!(summary.contains("\"Model\": 1"));
+    /// assert!(summary.contains("Vertices: [f64; 12] [0.0, 0.0, 0.0, ...]"));
+    ///
+    /// // Top-level nodes are depth 0.
+    /// let summary = doc.summary().max_depth(Some(0)).to_string();
+    /// assert!(summary.contains("\n  Objects:\n    ... 2 child nodes\n"));
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "edit"))]
+    /// # fn main() {}
+    /// ```
+    pub fn summary(&self) -> DocumentSummary<'_> {
+        DocumentSummary {
+            doc: self,
+            max_array_len: DEFAULT_MAX_ARRAY_LEN,
+            max_children: DEFAULT_MAX_CHILDREN,
+            max_depth: None,
+        }
+    }
+}
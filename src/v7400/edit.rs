@@ -0,0 +1,576 @@
+//! Mutable document editing.
+//!
+//! [`DocumentMut`] edits the FBX data tree of a loaded document: adding and
+//! removing objects, editing connections, and setting properties.
+//! Edits are applied directly to the owned tree, and the caches of
+//! [`Document`] are rebuilt by [`DocumentMut::into_document()`].
+
+use std::{collections::HashSet, convert::TryFrom};
+
+use anyhow::{bail, format_err, Error};
+use fbxcel::{
+    low::{
+        v7400::{AttributeType, AttributeValue, FbxFooter},
+        FbxVersion,
+    },
+    tree::v7400::{NodeHandle, NodeId, Tree},
+};
+
+use crate::v7400::{
+    object::{
+        property::{OwnedPropertyValue, PropertyEdit, PropertyEdits},
+        ObjectId,
+    },
+    Document, Error as DomError, ErrorLocation, LoadError, Loader,
+};
+
+#[cfg(feature = "animation")]
+pub use self::anim::CurveBuilder;
+pub use self::{
+    append::AppendOptions,
+    builder::{DocumentBuilder, ModelKind},
+    definitions::TemplateSource,
+    mesh::MeshBuilder,
+    reference::{SceneReference, MAX_REFERENCE_DEPTH},
+    skin::{SkinBuilder, IDENTITY_MATRIX},
+};
+pub use crate::v7400::object::animation::{Interpolation, KTIME_PER_SECOND};
+
+mod anim;
+mod append;
+mod builder;
+pub(crate) mod definitions;
+mod media;
+mod mesh;
+mod reference;
+mod skin;
+
+/// Mutable FBX document.
+#[derive(Debug, Clone)]
+pub struct DocumentMut {
+    /// FBX data tree.
+    tree: Tree,
+    /// Footer of the source file.
+    footer: Option<FbxFooter>,
+    /// FBX version of the source file.
+    fbx_version: Option<FbxVersion>,
+}
+
+impl DocumentMut {
+    /// Creates a new `DocumentMut` from the given document.
+    ///
+    /// The footer and the FBX version of the source file are kept, and
+    /// passed to the document loaded by
+    /// [`into_document()`][`Self::into_document`].
+    pub fn from_document(doc: Document) -> Self {
+        let fbx_version = doc.fbx_version();
+        let (tree, footer) = doc.into_tree_and_footer();
+        Self {
+            tree,
+            footer,
+            fbx_version,
+        }
+    }
+
+    /// Creates a new `DocumentMut` from the given tree.
+    pub fn from_tree(tree: Tree) -> Self {
+        Self {
+            tree,
+            footer: None,
+            fbx_version: None,
+        }
+    }
+
+    /// Returns a reference to the tree.
+    pub fn tree(&self) -> &Tree {
+        &self.tree
+    }
+
+    /// Returns the tree.
+    pub fn into_tree(self) -> Tree {
+        self.tree
+    }
+
+    /// Loads a document from the edited tree.
+    pub fn into_document(self) -> Result<Document, LoadError> {
+        self.into_document_with(Loader::new())
+    }
+
+    /// Loads a document from the edited tree with the given loader.
+    ///
+    /// The FBX version of the source document overrides the version set to
+    /// the loader.
+    pub fn into_document_with(self, loader: Loader) -> Result<Document, LoadError> {
+        let loader = match self.fbx_version {
+            Some(fbx_version) => loader.fbx_version(fbx_version),
+            None => loader,
+        };
+        loader.load_from_tree_with_footer(self.tree, self.footer)
+    }
+
+    /// Returns the node ID of the toplevel node with the given name.
+    fn toplevel(&self, name: &str) -> Result<NodeId, Error> {
+        self.tree
+            .root()
+            .first_child_by_name(name)
+            .map(|node| node.node_id())
+            .ok_or_else(|| DomError::missing_node(&self.tree.root(), name).into())
+    }
+
+    /// Returns the node ID of the toplevel node with the given name, creating
+    /// it if absent.
+    fn toplevel_or_insert(&mut self, name: &str) -> NodeId {
+        match self.toplevel(name) {
+            Ok(v) => v,
+            Err(_) => {
+                let root = self.tree.root().node_id();
+                self.tree.append_new(root, name)
+            }
+        }
+    }
+
+    /// Returns the object node with the given ID.
+    fn object_node(&self, id: ObjectId) -> Result<NodeHandle<'_>, Error> {
+        let objects = self.toplevel("Objects")?;
+        objects
+            .to_handle(&self.tree)
+            .children()
+            .find(|node| {
+                node.attributes().first().and_then(AttributeValue::get_i64) == Some(id.raw())
+            })
+            .ok_or_else(|| DomError::missing_object(id).into())
+    }
+
+    /// Returns an unused object ID.
+    ///
+    /// IDs of `Document` nodes are also taken into account.
+    fn new_object_id(&self) -> ObjectId {
+        self.object_id_allocator().allocate()
+    }
+
+    /// Returns an allocator of unused object IDs.
+    ///
+    /// IDs of `Document` nodes are also taken into account.
+    fn object_id_allocator(&self) -> ObjectIdAllocator {
+        let documents = self
+            .toplevel("Documents")
+            .ok()
+            .into_iter()
+            .flat_map(|documents| documents.to_handle(&self.tree).children_by_name("Document"));
+        let used = self
+            .toplevel("Objects")
+            .ok()
+            .into_iter()
+            .flat_map(|objects| objects.to_handle(&self.tree).children())
+            .chain(documents)
+            .filter_map(|node| node.attributes().first().and_then(AttributeValue::get_i64))
+            .collect();
+        ObjectIdAllocator::new(used)
+    }
+
+    /// Adds a new object and returns its ID.
+    ///
+    /// `node_name` is the name of the object node (such as `Model` or
+    /// `Material`), and `class` is the class name written after the object
+    /// name (usually same as `node_name`).
+    ///
+    /// The new ID is greater than all the existing IDs if possible, and an
+    /// unused positive ID otherwise.
+    ///
+    /// ```
+    /// use fbxcel_dom::{
+    ///     fbxcel::{low::v7400::AttributeValue, tree::v7400::Tree},
+    ///     v7400::edit::DocumentMut,
+    /// };
+    ///
+    /// let mut tree = Tree::default();
+    /// let root = tree.root().node_id();
+    /// let objects = tree.append_new(root, "Objects");
+    /// for &id in &[1, i64::MAX] {
+    ///     let model = tree.append_new(objects, "Model");
+    ///     tree.append_attribute(model, AttributeValue::I64(id));
+    /// }
+    ///
+    /// let mut doc = DocumentMut::from_tree(tree);
+    /// let id = doc.add_object("Model", "Cube", "Model", "Mesh");
+    /// assert_eq!(id.raw(), 2);
+    /// ```
+    pub fn add_object(
+        &mut self,
+        node_name: &str,
+        name: &str,
+        class: &str,
+        subclass: &str,
+    ) -> ObjectId {
+        let id = self.new_object_id();
+        let objects = self.toplevel_or_insert("Objects");
+        let node = self.tree.append_new(objects, node_name);
+        self.tree.append_attribute(node, id.raw());
+        self.tree
+            .append_attribute(node, format!("{}\u{0}\u{1}{}", name, class));
+        self.tree.append_attribute(node, subclass.to_owned());
+        id
+    }
+
+    /// Removes the object and all connections from or to it.
+    pub fn remove_object(&mut self, id: ObjectId) -> Result<(), Error> {
+        let node_id = self.object_node(id)?.node_id();
+        self.tree.detach(node_id);
+        self.remove_connections(|src, dest, _| src == id || dest == id);
+        Ok(())
+    }
+
+    /// Renames the object, keeping its class.
+    pub fn set_object_name(&mut self, id: ObjectId, name: &str) -> Result<(), Error> {
+        let node = self.object_node(id)?;
+        let name_class = node
+            .attributes()
+            .get(1)
+            .ok_or_else(|| DomError::missing_attribute(&node, 1))?
+            .get_string_or_type()
+            .map_err(|ty| {
+                DomError::type_mismatch(ErrorLocation::node(&node).with_object(id), "string", ty)
+            })?;
+        let new = match name_class.find("\u{0}\u{1}") {
+            Some(pos) => format!("{}{}", name, &name_class[pos..]),
+            None => name.to_owned(),
+        };
+        let node_id = node.node_id();
+        if let Some(attr) = self.tree.get_attribute_mut(node_id, 1) {
+            *attr = AttributeValue::String(new);
+        }
+        Ok(())
+    }
+
+    /// Adds an object-object connection.
+    pub fn connect(&mut self, source: ObjectId, destination: ObjectId) {
+        let connections = self.toplevel_or_insert("Connections");
+        let node = self.tree.append_new(connections, "C");
+        self.tree.append_attribute(node, "OO".to_owned());
+        self.tree.append_attribute(node, source.raw());
+        self.tree.append_attribute(node, destination.raw());
+    }
+
+    /// Adds an object-property connection with the given property name.
+    pub fn connect_to_property(&mut self, source: ObjectId, destination: ObjectId, property: &str) {
+        let connections = self.toplevel_or_insert("Connections");
+        let node = self.tree.append_new(connections, "C");
+        self.tree.append_attribute(node, "OP".to_owned());
+        self.tree.append_attribute(node, source.raw());
+        self.tree.append_attribute(node, destination.raw());
+        self.tree.append_attribute(node, property.to_owned());
+    }
+
+    /// Removes all connections from `source` to `destination`.
+    pub fn disconnect(&mut self, source: ObjectId, destination: ObjectId) {
+        self.remove_connections(|src, dest, _| src == source && dest == destination);
+    }
+
+    /// Removes object-property connections from `source` to the property of
+    /// `destination`.
+    pub fn disconnect_property(&mut self, source: ObjectId, destination: ObjectId, property: &str) {
+        self.remove_connections(|src, dest, label| {
+            src == source && dest == destination && label == Some(property)
+        });
+    }
+
+    /// Removes connections matching the predicate.
+    ///
+    /// The predicate takes source, destination and label.
+    fn remove_connections(
+        &mut self,
+        mut pred: impl FnMut(ObjectId, ObjectId, Option<&str>) -> bool,
+    ) {
+        let connections = match self.toplevel("Connections") {
+            Ok(v) => v,
+            Err(_) => return,
+        };
+        let targets: Vec<NodeId> = connections
+            .to_handle(&self.tree)
+            .children_by_name("C")
+            .filter(|node| {
+                let attrs = node.attributes();
+                let src = attrs.get(1).and_then(AttributeValue::get_i64);
+                let dest = attrs.get(2).and_then(AttributeValue::get_i64);
+                let label = attrs.get(3).and_then(AttributeValue::get_string);
+                match (src, dest) {
+                    (Some(src), Some(dest)) => {
+                        pred(ObjectId::from_raw(src), ObjectId::from_raw(dest), label)
+                    }
+                    _ => false,
+                }
+            })
+            .map(|node| node.node_id())
+            .collect();
+        for node_id in targets {
+            self.tree.detach(node_id);
+        }
+    }
+
+    /// Returns the `P` node of the object property with the given name.
+    fn property_node(&self, id: ObjectId, name: &str) -> Result<Option<NodeId>, Error> {
+        Ok(self
+            .object_node(id)?
+            .first_child_by_name("Properties70")
+            .and_then(|props| {
+                props.children_by_name("P").find(|p| {
+                    p.attributes().first().and_then(AttributeValue::get_string) == Some(name)
+                })
+            })
+            .map(|p| p.node_id()))
+    }
+
+    /// Sets the value of an existing direct property of the object.
+    ///
+    /// The value is converted to the attribute types of the current value,
+    /// and an error is returned if they are incompatible (for example,
+    /// setting a string to a `Vector3D` property).
+    /// Properties only present in the class template should be added by
+    /// [`insert_property`][`Self::insert_property`].
+    pub fn set_property(
+        &mut self,
+        id: ObjectId,
+        name: &str,
+        value: impl Into<OwnedPropertyValue>,
+    ) -> Result<(), Error> {
+        let node_id = self
+            .property_node(id, name)?
+            .ok_or_else(|| DomError::missing_property(id, name))?;
+        let mut attrs = self.tree.take_attributes_vec(node_id);
+        let result = match attrs.get(4..) {
+            Some(current) => encode_like(current, &value.into()),
+            None => Err(DomError::missing_attribute(&node_id.to_handle(&self.tree), 4).into()),
+        };
+        match result {
+            Ok(new) => {
+                attrs.truncate(4);
+                attrs.extend(new);
+                self.tree.set_attributes_vec(node_id, attrs);
+                Ok(())
+            }
+            Err(e) => {
+                self.tree.set_attributes_vec(node_id, attrs);
+                Err(e.context(format!(
+                    "Failed to set property: id={:?}, name={:?}",
+                    id, name
+                )))
+            }
+        }
+    }
+
+    /// Inserts a new direct property to the object, replacing the existing
+    /// one with the same name.
+    pub fn insert_property(
+        &mut self,
+        id: ObjectId,
+        name: &str,
+        data_type: &str,
+        label: &str,
+        flags: &str,
+        value: impl Into<OwnedPropertyValue>,
+    ) -> Result<(), Error> {
+        let object = self.object_node(id)?.node_id();
+        if let Some(existing) = self.property_node(id, name)? {
+            self.tree.detach(existing);
+        }
+        let props = match object
+            .to_handle(&self.tree)
+            .first_child_by_name("Properties70")
+        {
+            Some(v) => v.node_id(),
+            None => self.tree.append_new(object, "Properties70"),
+        };
+        let node = self.tree.append_new(props, "P");
+        let mut attrs = vec![
+            AttributeValue::String(name.to_owned()),
+            AttributeValue::String(data_type.to_owned()),
+            AttributeValue::String(label.to_owned()),
+            AttributeValue::String(flags.to_owned()),
+        ];
+        attrs.extend(encode(value.into()));
+        self.tree.set_attributes_vec(node, attrs);
+        Ok(())
+    }
+
+    /// Removes the direct property of the object.
+    ///
+    /// Returns `Ok(false)` if the property does not exist.
+    pub fn remove_property(&mut self, id: ObjectId, name: &str) -> Result<bool, Error> {
+        match self.property_node(id, name)? {
+            Some(node_id) => {
+                self.tree.detach(node_id);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Applies the recorded property edits in order.
+    ///
+    /// Inserted properties are written with empty label and flags.
+    pub fn apply_edits(&mut self, edits: &PropertyEdits) -> Result<(), Error> {
+        for record in edits.journal() {
+            match &record.edit {
+                PropertyEdit::Set(value) => {
+                    self.set_property(record.object, &record.name, value.clone())?
+                }
+                PropertyEdit::Insert { data_type, value } => self.insert_property(
+                    record.object,
+                    &record.name,
+                    data_type,
+                    "",
+                    "",
+                    value.clone(),
+                )?,
+                PropertyEdit::Delete => {
+                    self.remove_property(record.object, &record.name)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<Document> for DocumentMut {
+    fn from(doc: Document) -> Self {
+        Self::from_document(doc)
+    }
+}
+
+/// Copies the node and its descendants to `parent` in `dest`, and returns
+/// the new node.
+pub(crate) fn copy_node(src: NodeHandle<'_>, dest: &mut Tree, parent: NodeId) -> NodeId {
+    let node = dest.append_new(parent, src.name());
+    dest.set_attributes_vec(node, src.attributes().to_vec());
+    for child in src.children() {
+        copy_node(child, dest, node);
+    }
+    node
+}
+
+/// Scalar component of a property value.
+#[derive(Debug, Clone)]
+enum Component {
+    /// Integer.
+    Int(i64),
+    /// Float.
+    Float(f64),
+    /// String.
+    String(String),
+    /// Binary.
+    Binary(Vec<u8>),
+}
+
+/// Splits the value into scalar components.
+fn components(value: &OwnedPropertyValue) -> Vec<Component> {
+    match value {
+        OwnedPropertyValue::Bool(v) => vec![Component::Int(i64::from(*v))],
+        OwnedPropertyValue::I32(v) => vec![Component::Int(i64::from(*v))],
+        OwnedPropertyValue::I64(v) | OwnedPropertyValue::Time(v) => vec![Component::Int(*v)],
+        OwnedPropertyValue::F32(v) => vec![Component::Float(f64::from(*v))],
+        OwnedPropertyValue::F64(v) => vec![Component::Float(*v)],
+        OwnedPropertyValue::F64x2(v) => v.iter().map(|&v| Component::Float(v)).collect(),
+        OwnedPropertyValue::F64x3(v) => v.iter().map(|&v| Component::Float(v)).collect(),
+        OwnedPropertyValue::F64x4(v) => v.iter().map(|&v| Component::Float(v)).collect(),
+        OwnedPropertyValue::String(v) => vec![Component::String(v.clone())],
+        OwnedPropertyValue::Binary(v) => vec![Component::Binary(v.clone())],
+        OwnedPropertyValue::Empty => Vec::new(),
+    }
+}
+
+/// Encodes the value into attributes with the natural types.
+fn encode(value: OwnedPropertyValue) -> Vec<AttributeValue> {
+    match value {
+        OwnedPropertyValue::Bool(v) => vec![AttributeValue::I32(i32::from(v))],
+        OwnedPropertyValue::I32(v) => vec![AttributeValue::I32(v)],
+        OwnedPropertyValue::I64(v) | OwnedPropertyValue::Time(v) => vec![AttributeValue::I64(v)],
+        OwnedPropertyValue::F32(v) => vec![AttributeValue::F32(v)],
+        OwnedPropertyValue::F64(v) => vec![AttributeValue::F64(v)],
+        OwnedPropertyValue::F64x2(v) => v.iter().map(|&v| AttributeValue::F64(v)).collect(),
+        OwnedPropertyValue::F64x3(v) => v.iter().map(|&v| AttributeValue::F64(v)).collect(),
+        OwnedPropertyValue::F64x4(v) => v.iter().map(|&v| AttributeValue::F64(v)).collect(),
+        OwnedPropertyValue::String(v) => vec![AttributeValue::String(v)],
+        OwnedPropertyValue::Binary(v) => vec![AttributeValue::Binary(v)],
+        OwnedPropertyValue::Empty => Vec::new(),
+    }
+}
+
+/// Encodes the value into attributes with the same types as `current`.
+fn encode_like(
+    current: &[AttributeValue],
+    value: &OwnedPropertyValue,
+) -> Result<Vec<AttributeValue>, Error> {
+    let components = components(value);
+    if components.len() != current.len() {
+        bail!(
+            "Value length mismatch: expected {} attributes but got {} ({})",
+            current.len(),
+            components.len(),
+            value.as_value().kind_name()
+        );
+    }
+
+    current
+        .iter()
+        .zip(components)
+        .map(|(current, component)| {
+            let ty = current.type_();
+            let converted = match (ty, component) {
+                (AttributeType::Bool, Component::Int(v)) => Some(AttributeValue::Bool(v != 0)),
+                (AttributeType::I16, Component::Int(v)) => {
+                    i16::try_from(v).ok().map(AttributeValue::I16)
+                }
+                (AttributeType::I32, Component::Int(v)) => {
+                    i32::try_from(v).ok().map(AttributeValue::I32)
+                }
+                (AttributeType::I64, Component::Int(v)) => Some(AttributeValue::I64(v)),
+                (AttributeType::F32, Component::Float(v)) => Some(AttributeValue::F32(v as f32)),
+                (AttributeType::F64, Component::Float(v)) => Some(AttributeValue::F64(v)),
+                (AttributeType::String, Component::String(v)) => Some(AttributeValue::String(v)),
+                (AttributeType::Binary, Component::Binary(v)) => Some(AttributeValue::Binary(v)),
+                _ => None,
+            };
+            converted.ok_or_else(|| {
+                format_err!(
+                    "Incompatible value: cannot store {} into {:?} attribute",
+                    value.as_value().kind_name(),
+                    ty
+                )
+            })
+        })
+        .collect()
+}
+
+/// Allocator of unused object IDs.
+#[derive(Debug, Clone)]
+struct ObjectIdAllocator {
+    /// Used IDs.
+    used: HashSet<i64>,
+    /// Next ID candidate.
+    next: i64,
+}
+
+impl ObjectIdAllocator {
+    /// Creates a new allocator avoiding the given used IDs.
+    fn new(used: HashSet<i64>) -> Self {
+        let next = used
+            .iter()
+            .max()
+            .and_then(|&max| max.max(0).checked_add(1))
+            .unwrap_or(1);
+        Self { used, next }
+    }
+
+    /// Returns an unused positive ID, and marks it as used.
+    ///
+    /// IDs are allocated upward from the greatest used ID, and from 1 after
+    /// reaching `i64::MAX`.
+    fn allocate(&mut self) -> ObjectId {
+        while self.used.contains(&self.next) {
+            self.next = self.next.checked_add(1).unwrap_or(1);
+        }
+        let id = self.next;
+        self.used.insert(id);
+        ObjectId::from_raw(id)
+    }
+}
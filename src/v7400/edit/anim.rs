@@ -0,0 +1,143 @@
+//! Animation curve builder.
+
+#[cfg(feature = "animation")]
+use anyhow::{bail, Error};
+#[cfg(feature = "animation")]
+use fbxcel::{
+    low::v7400::AttributeValue,
+    tree::v7400::{NodeId, Tree},
+};
+
+#[cfg(feature = "animation")]
+use crate::v7400::object::animation::{
+    Interpolation, FLAG_CONSTANT, FLAG_LINEAR, KTIME_PER_SECOND,
+};
+
+/// Key attribute flag for cubic interpolation.
+#[cfg(feature = "animation")]
+const FLAG_CUBIC: i32 = 0x0000_0008;
+/// Key attribute flag for auto tangent.
+#[cfg(feature = "animation")]
+const FLAG_TANGENT_AUTO: i32 = 0x0000_0100;
+/// Default tangent weights (`1/3` for both sides), packed as in FBX files.
+#[cfg(feature = "animation")]
+const DEFAULT_WEIGHTS: u32 = 0x0D05_0D05;
+
+/// Returns the key attribute flags of the interpolation.
+#[cfg(feature = "animation")]
+fn key_flags(interpolation: Interpolation) -> i32 {
+    match interpolation {
+        Interpolation::Constant => FLAG_CONSTANT,
+        Interpolation::Linear => FLAG_LINEAR,
+        Interpolation::Cubic => FLAG_CUBIC | FLAG_TANGENT_AUTO,
+    }
+}
+
+/// Builder of animation curve data.
+///
+/// Pass the builder to
+/// [`DocumentBuilder::add_animation_curve_node()`][`super::DocumentBuilder::add_animation_curve_node`].
+#[cfg(feature = "animation")]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CurveBuilder {
+    /// Key times in `KTime` ticks.
+    times: Vec<i64>,
+    /// Key values.
+    values: Vec<f32>,
+    /// Key interpolations.
+    interpolations: Vec<Interpolation>,
+}
+
+#[cfg(feature = "animation")]
+impl CurveBuilder {
+    /// Creates a new empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a key at the time in `KTime` ticks.
+    pub fn key(mut self, time: i64, value: f32, interpolation: Interpolation) -> Self {
+        self.times.push(time);
+        self.values.push(value);
+        self.interpolations.push(interpolation);
+        self
+    }
+
+    /// Adds a key at the time in seconds.
+    pub fn key_seconds(self, seconds: f64, value: f32, interpolation: Interpolation) -> Self {
+        let time = (seconds * KTIME_PER_SECOND as f64).round() as i64;
+        self.key(time, value, interpolation)
+    }
+
+    /// Adds keys sampled at a fixed frame rate, starting at time zero.
+    pub fn baked(mut self, fps: f64, values: &[f32], interpolation: Interpolation) -> Self {
+        for (frame, &value) in values.iter().enumerate() {
+            self = self.key_seconds(frame as f64 / fps, value, interpolation);
+        }
+        self
+    }
+
+    /// Returns the value of the first key, if available.
+    pub(crate) fn default_value(&self) -> Option<f32> {
+        self.values.first().copied()
+    }
+
+    /// Checks the consistency of the data.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.times.is_empty() {
+            bail!("Animation curve has no keys");
+        }
+        if let Some(i) = self.times.windows(2).position(|w| w[0] >= w[1]) {
+            bail!(
+                "Key times are not strictly increasing: key {} at {} but key {} at {}",
+                i,
+                self.times[i],
+                i + 1,
+                self.times[i + 1]
+            );
+        }
+        if let Some(i) = self.values.iter().position(|v| !v.is_finite()) {
+            bail!("Invalid value at key {}: {}", i, self.values[i]);
+        }
+
+        Ok(())
+    }
+
+    /// Writes the key data as children of the given animation curve node.
+    ///
+    /// Key attributes are run-length encoded: consecutive keys with the same
+    /// interpolation share one entry of `KeyAttrFlags` and
+    /// `KeyAttrDataFloat`, and `KeyAttrRefCount` stores the run lengths.
+    ///
+    /// The data should be validated in advance.
+    pub(crate) fn write(&self, tree: &mut Tree, curve: NodeId) {
+        let mut flags = Vec::new();
+        let mut ref_counts: Vec<i32> = Vec::new();
+        for interpolation in &self.interpolations {
+            let flag = key_flags(interpolation);
+            match (flags.last(), ref_counts.last_mut()) {
+                (Some(&last), Some(count)) if last == flag => *count += 1,
+                _ => {
+                    flags.push(flag);
+                    ref_counts.push(1);
+                }
+            }
+        }
+        // Right slope, next left slope, packed weights, and packed velocity.
+        let attr_data = [0.0, 0.0, f32::from_bits(DEFAULT_WEIGHTS), 0.0].repeat(flags.len());
+
+        let default = f64::from(self.default_value().unwrap_or(0.0));
+        for (name, value) in [
+            ("Default", AttributeValue::F64(default)),
+            ("KeyVer", AttributeValue::I32(4009)),
+            ("KeyTime", AttributeValue::ArrI64(self.times.clone())),
+            ("KeyValueFloat", AttributeValue::ArrF32(self.values.clone())),
+            ("KeyAttrFlags", AttributeValue::ArrI32(flags)),
+            ("KeyAttrDataFloat", AttributeValue::ArrF32(attr_data)),
+            ("KeyAttrRefCount", AttributeValue::ArrI32(ref_counts)),
+        ] {
+            let node = tree.append_new(curve, name);
+            tree.append_attribute(node, value);
+        }
+    }
+}
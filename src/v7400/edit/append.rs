@@ -0,0 +1,247 @@
+//! Scene appending.
+
+use std::collections::HashMap;
+
+use anyhow::Error;
+use fbxcel::{
+    low::v7400::AttributeValue,
+    tree::v7400::{NodeHandle, NodeId, Tree},
+};
+use log::warn;
+use mint::Vector3;
+
+use crate::v7400::{
+    data::transform::{AxisSystem, RotationOrder},
+    edit::{copy_node, DocumentMut},
+    object::{property::OwnedPropertyValue, ObjectId},
+    Document,
+};
+
+/// Object ID of the implicit scene root model.
+const ROOT_MODEL_ID: i64 = 0;
+
+/// Options for [`DocumentMut::append()`].
+#[derive(Default, Debug, Clone)]
+pub struct AppendOptions {
+    /// Namespace prefixed to the object names.
+    namespace: Option<String>,
+    /// Model to attach the appended root models to.
+    parent: Option<ObjectId>,
+    /// Whether to convert the axis system and the unit.
+    convert_axis_system: bool,
+}
+
+impl AppendOptions {
+    /// Creates a new default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the namespace.
+    ///
+    /// Appended objects are renamed to `<namespace>:<name>`.
+    pub fn namespace(mut self, namespace: &str) -> Self {
+        self.namespace = Some(namespace.to_owned());
+        self
+    }
+
+    /// Sets the model to attach the appended root models to.
+    ///
+    /// If not set, the root models are attached to the scene root.
+    pub fn parent(mut self, parent: ObjectId) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Sets whether to convert the axis system and the unit of the appended
+    /// document into those of the destination document.
+    ///
+    /// If enabled and the `GlobalSettings` differ, the appended root models
+    /// are attached to a new `Null` model with the conversion transform.
+    /// Conversion between left-handed and right-handed axis systems is not
+    /// supported, and only the unit is converted in that case.
+    pub fn convert_axis_system(mut self, convert: bool) -> Self {
+        self.convert_axis_system = convert;
+        self
+    }
+}
+
+impl DocumentMut {
+    /// Appends objects and connections of another document.
+    ///
+    /// Object IDs are remapped to unused IDs, and the map from the original
+    /// IDs to the new IDs is returned.
+    /// Connections to the scene root of `other` are redirected to the
+    /// parent (see [`AppendOptions::parent()`]).
+    ///
+    /// `Definitions` is not updated; use
+    /// [`update_definitions()`][`Self::update_definitions`] after appending.
+    pub fn append(
+        &mut self,
+        other: &Document,
+        options: AppendOptions,
+    ) -> Result<HashMap<ObjectId, ObjectId>, Error> {
+        let other_tree = &*other.preloaded_tree()?;
+        let src_objects = match other_tree.root().first_child_by_name("Objects") {
+            Some(v) => v,
+            None => return Ok(HashMap::new()),
+        };
+
+        let mut parent = options
+            .parent
+            .unwrap_or_else(|| ObjectId::from_raw(ROOT_MODEL_ID));
+        if options.convert_axis_system {
+            let conversion = conversion(&self.tree, other_tree);
+            if let Some((rotation, scale)) = conversion {
+                let name = match &options.namespace {
+                    Some(ns) => format!("{}:ConversionRoot", ns),
+                    None => "ConversionRoot".to_owned(),
+                };
+                let root = self.add_object("Model", &name, "Model", "Null");
+                let node = self.object_node(root)?.node_id();
+                let version = self.tree.append_new(node, "Version");
+                self.tree.append_attribute(version, 232i32);
+                for &(prop, value) in &[("Lcl Rotation", rotation), ("Lcl Scaling", [scale; 3])] {
+                    self.insert_property(
+                        root,
+                        prop,
+                        prop,
+                        "",
+                        "A",
+                        OwnedPropertyValue::F64x3(value),
+                    )?;
+                }
+                self.connect(root, parent);
+                parent = root;
+            }
+        }
+
+        let mut ids = self.object_id_allocator();
+        let mut id_map = HashMap::new();
+        for node in src_objects.children() {
+            if let Some(id) = node.attributes().first().and_then(AttributeValue::get_i64) {
+                id_map.insert(ObjectId::from_raw(id), ids.allocate());
+            }
+        }
+
+        let objects = self.toplevel_or_insert("Objects");
+        for node in src_objects.children() {
+            let old_id = match node.attributes().first().and_then(AttributeValue::get_i64) {
+                Some(v) => ObjectId::from_raw(v),
+                None => {
+                    warn!(
+                        "Skipping object node without ID: node_id={:?}",
+                        node.node_id()
+                    );
+                    continue;
+                }
+            };
+            let new = copy_node(node, &mut self.tree, objects);
+            if let Some(id) = self.tree.get_attribute_mut(new, 0) {
+                *id = AttributeValue::I64(id_map[&old_id].raw());
+            }
+            if let Some(ns) = &options.namespace {
+                if let Some(AttributeValue::String(name)) = self.tree.get_attribute_mut(new, 1) {
+                    *name = format!("{}:{}", ns, name);
+                }
+            }
+            if node.name() == "Pose" {
+                remap_pose_nodes(&mut self.tree, node, new, &id_map);
+            }
+        }
+
+        let remap = |id: i64| match id_map.get(&ObjectId::from_raw(id)) {
+            Some(new) => Some(*new),
+            None if id == ROOT_MODEL_ID => Some(parent),
+            None => None,
+        };
+        let connections = other_tree
+            .root()
+            .first_child_by_name("Connections")
+            .into_iter()
+            .flat_map(|node| node.children_by_name("C"));
+        for conn in connections {
+            let attrs = conn.attributes();
+            let src = attrs.get(1).and_then(AttributeValue::get_i64);
+            let dest = attrs.get(2).and_then(AttributeValue::get_i64);
+            let (src, dest) = match (src.and_then(remap), dest.and_then(remap)) {
+                (Some(src), Some(dest)) => (src, dest),
+                _ => {
+                    warn!(
+                        "Skipping connection with unknown objects: src={:?}, dest={:?}",
+                        src, dest
+                    );
+                    continue;
+                }
+            };
+            match attrs.get(3).and_then(AttributeValue::get_string) {
+                Some(property) => self.connect_to_property(src, dest, property),
+                None => self.connect(src, dest),
+            }
+        }
+
+        Ok(id_map)
+    }
+}
+
+/// Remaps object IDs referred by `PoseNode` children of the copied pose.
+fn remap_pose_nodes(
+    tree: &mut Tree,
+    src: NodeHandle<'_>,
+    new: NodeId,
+    id_map: &HashMap<ObjectId, ObjectId>,
+) {
+    let targets: Vec<_> = new
+        .to_handle(tree)
+        .children_by_name("PoseNode")
+        .filter_map(|pose_node| pose_node.first_child_by_name("Node"))
+        .map(|node| node.node_id())
+        .collect();
+    for target in targets {
+        if let Some(AttributeValue::I64(id)) = tree.get_attribute_mut(target, 0) {
+            match id_map.get(&ObjectId::from_raw(*id)) {
+                Some(new_id) => *id = new_id.raw(),
+                None => warn!(
+                    "Unknown object in pose node: id={}, pose_node_id={:?}",
+                    id,
+                    src.node_id()
+                ),
+            }
+        }
+    }
+}
+
+/// Returns the Euler rotation (in degrees) and the uniform scale converting
+/// the axis system of `src` into that of `dest`, or `None` if they match.
+fn conversion(dest: &Tree, src: &Tree) -> Option<([f64; 3], f64)> {
+    let dest = AxisSystem::from_tree(dest);
+    let src = AxisSystem::from_tree(src);
+    if dest == src {
+        return None;
+    }
+    let scale = if dest.unit_scale > 0.0 {
+        src.unit_scale / dest.unit_scale
+    } else {
+        1.0
+    };
+    let rotation = match (dest.matrix(), src.matrix()) {
+        (Some(dest_m), Some(src_m)) => {
+            let m = dest_m * src_m.transpose();
+            if m.determinant() > 0.0 {
+                let Vector3 { x, y, z } = m.euler_degrees(RotationOrder::EulerXyz);
+                [x, y, z]
+            } else {
+                warn!(
+                    "Conversion between left-handed and right-handed axis systems is not supported"
+                );
+                [0.0; 3]
+            }
+        }
+        _ => {
+            warn!("Invalid axis system in `GlobalSettings`");
+            [0.0; 3]
+        }
+    };
+
+    Some((rotation, scale))
+}
@@ -0,0 +1,906 @@
+//! Document builder.
+
+use std::convert::TryFrom;
+
+use anyhow::{bail, format_err};
+use fbxcel::{
+    low::v7400::AttributeValue,
+    tree::v7400::{NodeId, Tree},
+};
+
+#[cfg(feature = "animation")]
+use crate::v7400::edit::CurveBuilder;
+use crate::v7400::{
+    data::{cache::CacheFileType, datetime::DateTime, material::ShadingModel},
+    edit::{
+        definitions::TemplateSource,
+        media::relative_filename,
+        mesh::{encode_polygons, MeshBuilder},
+        skin::{validate_matrix, SkinBuilder},
+        DocumentMut,
+    },
+    object::{property::OwnedPropertyValue, ObjectId},
+    Document, Error as DomError, ErrorLocation, LoadError, Loader,
+};
+
+/// FBX version written to the header.
+const FBX_VERSION: i32 = 7400;
+
+/// Object ID of the implicit scene root model.
+const ROOT_MODEL_ID: i64 = 0;
+
+/// Model kind to create by [`DocumentBuilder::add_model()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ModelKind {
+    /// Null (transform only).
+    Null,
+    /// Mesh.
+    Mesh,
+    /// Skeleton limb node.
+    LimbNode,
+}
+
+impl ModelKind {
+    /// Returns the subclass name.
+    fn subclass(self) -> &'static str {
+        match self {
+            ModelKind::Null => "Null",
+            ModelKind::Mesh => "Mesh",
+            ModelKind::LimbNode => "LimbNode",
+        }
+    }
+}
+
+/// Builder of a valid FBX document without an input file.
+///
+/// This creates `FBXHeaderExtension`, `GlobalSettings`, `Documents`,
+/// `Definitions` (with object counts computed on build), `Objects` and
+/// `Connections` nodes.
+///
+/// Use [`edit_mut()`][`Self::edit_mut`] for edits not covered by the typed
+/// helpers.
+#[derive(Debug, Clone)]
+pub struct DocumentBuilder {
+    /// Document being built.
+    doc: DocumentMut,
+    /// Creation time stamp.
+    creation_time: Option<DateTime>,
+    /// Creator name.
+    creator: String,
+}
+
+impl DocumentBuilder {
+    /// Creates a new builder with the toplevel node skeleton.
+    pub fn new() -> Self {
+        let mut tree = Tree::default();
+        let root = tree.root().node_id();
+        for name in &[
+            "FBXHeaderExtension",
+            "GlobalSettings",
+            "Documents",
+            "References",
+            "Definitions",
+            "Objects",
+            "Connections",
+        ] {
+            tree.append_new(root, name);
+        }
+
+        Self {
+            doc: DocumentMut::from_tree(tree),
+            creation_time: None,
+            creator: concat!("fbxcel-dom ", env!("CARGO_PKG_VERSION")).to_owned(),
+        }
+    }
+
+    /// Sets the creation time stamp written to the header.
+    pub fn creation_time(mut self, time: DateTime) -> Self {
+        self.creation_time = Some(time);
+        self
+    }
+
+    /// Sets the creator name written to the header.
+    pub fn creator(mut self, creator: &str) -> Self {
+        self.creator = creator.to_owned();
+        self
+    }
+
+    /// Returns the underlying mutable document.
+    pub fn edit_mut(&mut self) -> &mut DocumentMut {
+        &mut self.doc
+    }
+
+    /// Adds a model and returns its ID.
+    ///
+    /// The model is attached to `parent`, or to the scene root if `None`.
+    /// For [`ModelKind::LimbNode`], a skeleton node attribute is also created
+    /// and attached to the model.
+    pub fn add_model(&mut self, name: &str, kind: ModelKind, parent: Option<ObjectId>) -> ObjectId {
+        let id = self.doc.add_object("Model", name, "Model", kind.subclass());
+        self.append_child(id, "Version", AttributeValue::I32(232));
+        self.doc.connect(
+            id,
+            parent.unwrap_or_else(|| ObjectId::from_raw(ROOT_MODEL_ID)),
+        );
+        if kind == ModelKind::LimbNode {
+            let attr = self
+                .doc
+                .add_object("NodeAttribute", name, "NodeAttribute", "LimbNode");
+            self.append_child(
+                attr,
+                "TypeFlags",
+                AttributeValue::String("Skeleton".to_owned()),
+            );
+            self.doc.connect(attr, id);
+        }
+        id
+    }
+
+    /// Sets the local transform of the model.
+    ///
+    /// `rotation` is Euler angles in degrees.
+    pub fn set_local_transform(
+        &mut self,
+        model: ObjectId,
+        translation: [f64; 3],
+        rotation: [f64; 3],
+        scaling: [f64; 3],
+    ) -> Result<(), anyhow::Error> {
+        for &(name, value) in &[
+            ("Lcl Translation", translation),
+            ("Lcl Rotation", rotation),
+            ("Lcl Scaling", scaling),
+        ] {
+            self.doc.insert_property(
+                model,
+                name,
+                name,
+                "",
+                "A",
+                OwnedPropertyValue::F64x3(value),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Adds a material and returns its ID.
+    ///
+    /// The material is attached to `model` if given.
+    pub fn add_material(
+        &mut self,
+        name: &str,
+        shading_model: ShadingModel,
+        diffuse_color: [f64; 3],
+        model: Option<ObjectId>,
+    ) -> Result<ObjectId, anyhow::Error> {
+        let shading_model = match shading_model {
+            ShadingModel::Lambert => "Lambert",
+            ShadingModel::Phong => "Phong",
+            ShadingModel::Unknown => "Unknown",
+        };
+        let id = self.doc.add_object("Material", name, "Material", "");
+        self.append_child(id, "Version", AttributeValue::I32(102));
+        self.append_child(
+            id,
+            "ShadingModel",
+            AttributeValue::String(shading_model.to_lowercase()),
+        );
+        self.doc.insert_property(
+            id,
+            "ShadingModel",
+            "KString",
+            "",
+            "",
+            OwnedPropertyValue::String(shading_model.to_owned()),
+        )?;
+        self.doc.insert_property(
+            id,
+            "DiffuseColor",
+            "Color",
+            "",
+            "A",
+            OwnedPropertyValue::F64x3(diffuse_color),
+        )?;
+        if let Some(model) = model {
+            self.doc.connect(id, model);
+        }
+        Ok(id)
+    }
+
+    /// Adds a mesh geometry and returns its ID.
+    ///
+    /// `polygons` are lists of indices into `positions`.
+    /// The geometry is attached to `model` if given.
+    pub fn add_mesh(
+        &mut self,
+        name: &str,
+        positions: &[[f64; 3]],
+        polygons: &[&[u32]],
+        model: Option<ObjectId>,
+    ) -> ObjectId {
+        let id = self.doc.add_object("Geometry", name, "Geometry", "Mesh");
+        self.append_child(id, "GeometryVersion", AttributeValue::I32(124));
+        self.append_child(
+            id,
+            "Vertices",
+            AttributeValue::ArrF64(positions.iter().flatten().copied().collect()),
+        );
+        let indices = encode_polygons(polygons);
+        self.append_child(id, "PolygonVertexIndex", AttributeValue::ArrI32(indices));
+        if let Some(model) = model {
+            self.doc.connect(id, model);
+        }
+        id
+    }
+
+    /// Adds a mesh geometry from the mesh builder and returns its ID.
+    ///
+    /// The geometry is attached to `model` if given.
+    /// Returns an error if the mesh data is inconsistent.
+    pub fn add_mesh_with(
+        &mut self,
+        name: &str,
+        mesh: &MeshBuilder,
+        model: Option<ObjectId>,
+    ) -> Result<ObjectId, anyhow::Error> {
+        mesh.validate()?;
+        let id = self.doc.add_object("Geometry", name, "Geometry", "Mesh");
+        self.append_child(id, "GeometryVersion", AttributeValue::I32(124));
+        let geometry = self.doc.object_node(id)?.node_id();
+        mesh.write(&mut self.doc.tree, geometry);
+        if let Some(model) = model {
+            self.doc.connect(id, model);
+        }
+        Ok(id)
+    }
+
+    /// Adds a skin deformer with clusters and returns the skin ID.
+    ///
+    /// The skin is attached to `geometry`, and each cluster is attached to
+    /// the skin and linked to its bone.
+    /// Returns an error if the geometry or a bone is not found, or the skin
+    /// data is inconsistent with the geometry.
+    pub fn add_skin(
+        &mut self,
+        name: &str,
+        geometry: ObjectId,
+        skin: &SkinBuilder,
+    ) -> Result<ObjectId, anyhow::Error> {
+        let num_control_points = {
+            let node = self.doc.object_node(geometry)?;
+            if node.name() != "Geometry" {
+                return Err(DomError::type_mismatch(
+                    ErrorLocation::node(&node).with_object(geometry),
+                    "`Geometry` node",
+                    node.name(),
+                )
+                .into());
+            }
+            node.first_child_by_name("Vertices")
+                .and_then(|vertices| vertices.attributes().first())
+                .and_then(AttributeValue::get_arr_f64)
+                .map_or(0, |vertices| vertices.len() / 3)
+        };
+        skin.validate(num_control_points)?;
+        for cluster in skin.clusters() {
+            let node = self.doc.object_node(cluster.bone)?;
+            if node.name() != "Model" {
+                return Err(DomError::type_mismatch(
+                    ErrorLocation::node(&node).with_object(cluster.bone),
+                    "`Model` node",
+                    node.name(),
+                )
+                .into());
+            }
+        }
+
+        let skin_id = self.doc.add_object("Deformer", name, "Deformer", "Skin");
+        self.append_child(skin_id, "Version", AttributeValue::I32(101));
+        self.append_child(skin_id, "Link_DeformAcuracy", AttributeValue::F64(50.0));
+        self.doc.connect(skin_id, geometry);
+        for cluster in skin.clusters() {
+            let bone_name = self
+                .doc
+                .object_node(cluster.bone)?
+                .attributes()
+                .get(1)
+                .and_then(AttributeValue::get_string)
+                .and_then(|name| name.split('\u{0}').next())
+                .unwrap_or("")
+                .to_owned();
+            let id = self
+                .doc
+                .add_object("Deformer", &bone_name, "SubDeformer", "Cluster");
+            self.append_child(id, "Version", AttributeValue::I32(100));
+            let user_data =
+                self.append_child(id, "UserData", AttributeValue::String(String::new()));
+            self.doc.tree.append_attribute(user_data, String::new());
+            self.append_child(
+                id,
+                "Indexes",
+                AttributeValue::ArrI32(cluster.indices.iter().map(|&i| i as i32).collect()),
+            );
+            self.append_child(
+                id,
+                "Weights",
+                AttributeValue::ArrF64(cluster.weights.clone()),
+            );
+            self.append_child(
+                id,
+                "Transform",
+                AttributeValue::ArrF64(cluster.transform.to_vec()),
+            );
+            self.append_child(
+                id,
+                "TransformLink",
+                AttributeValue::ArrF64(cluster.transform_link.to_vec()),
+            );
+            self.doc.connect(id, skin_id);
+            self.doc.connect(cluster.bone, id);
+        }
+        Ok(skin_id)
+    }
+
+    /// Adds a bind pose and returns its ID.
+    ///
+    /// `nodes` are pairs of a model and its global transform matrix (column
+    /// major) at binding time.
+    /// Returns an error if a model is not found or listed twice, or a matrix
+    /// is invalid.
+    pub fn add_bind_pose(
+        &mut self,
+        name: &str,
+        nodes: &[(ObjectId, [f64; 16])],
+    ) -> Result<ObjectId, anyhow::Error> {
+        for (i, (model, matrix)) in nodes.iter().enumerate() {
+            let node = self.doc.object_node(*model)?;
+            if node.name() != "Model" {
+                return Err(DomError::type_mismatch(
+                    ErrorLocation::node(&node).with_object(*model),
+                    "`Model` node",
+                    node.name(),
+                )
+                .into());
+            }
+            if nodes[..i].iter().any(|(other, _)| other == model) {
+                bail!("Duplicate model in bind pose: id={:?}", model);
+            }
+            validate_matrix(matrix, "bind pose")?;
+        }
+
+        let id = self.doc.add_object("Pose", name, "Pose", "BindPose");
+        self.append_child(id, "Type", AttributeValue::String("BindPose".to_owned()));
+        self.append_child(id, "Version", AttributeValue::I32(100));
+        let count = i32::try_from(nodes.len())
+            .map_err(|_| format_err!("Too many pose nodes: {}", nodes.len()))?;
+        self.append_child(id, "NbPoseNodes", AttributeValue::I32(count));
+        let pose = self.doc.object_node(id)?.node_id();
+        for (model, matrix) in nodes {
+            let pose_node = self.doc.tree.append_new(pose, "PoseNode");
+            let node = self.doc.tree.append_new(pose_node, "Node");
+            self.doc.tree.append_attribute(node, model.raw());
+            let node = self.doc.tree.append_new(pose_node, "Matrix");
+            self.doc.tree.append_attribute(node, matrix.to_vec());
+        }
+        Ok(id)
+    }
+
+    /// Adds a video clip for the media file and returns its ID.
+    ///
+    /// `RelativeFilename` is generated from `path`: relative paths are used
+    /// as is, and absolute paths are reduced to the file name.
+    /// If `content` is given, it is embedded into the document.
+    pub fn add_video(&mut self, name: &str, path: &str, content: Option<Vec<u8>>) -> ObjectId {
+        let id = self.doc.add_object("Video", name, "Video", "Clip");
+        self.append_child(id, "Type", AttributeValue::String("Clip".to_owned()));
+        self.append_child(id, "Filename", AttributeValue::String(path.to_owned()));
+        self.append_child(
+            id,
+            "RelativeFilename",
+            AttributeValue::String(relative_filename(path)),
+        );
+        if let Some(content) = content {
+            self.append_child(id, "Content", AttributeValue::Binary(content));
+        }
+        id
+    }
+
+    /// Adds an audio layer played with the animation stack and returns its
+    /// ID.
+    pub fn add_audio_layer(
+        &mut self,
+        name: &str,
+        stack: ObjectId,
+    ) -> Result<ObjectId, anyhow::Error> {
+        let node = self.doc.object_node(stack)?;
+        if node.name() != "AnimationStack" {
+            return Err(DomError::type_mismatch(
+                ErrorLocation::node(&node).with_object(stack),
+                "`AnimationStack` node",
+                node.name(),
+            )
+            .into());
+        }
+        let id = self.doc.add_object("AudioLayer", name, "AudioLayer", "");
+        self.doc.connect(id, stack);
+        Ok(id)
+    }
+
+    /// Adds an audio clip for the media file to the audio layer and returns
+    /// its ID.
+    ///
+    /// `offset` is the start time of the clip on the timeline in `KTime`
+    /// ticks.
+    /// Filenames and `content` are handled as [`add_video`] does.
+    ///
+    /// [`add_video`]: #method.add_video
+    pub fn add_audio(
+        &mut self,
+        name: &str,
+        path: &str,
+        content: Option<Vec<u8>>,
+        offset: i64,
+        layer: ObjectId,
+    ) -> Result<ObjectId, anyhow::Error> {
+        let node = self.doc.object_node(layer)?;
+        if node.name() != "AudioLayer" {
+            return Err(DomError::type_mismatch(
+                ErrorLocation::node(&node).with_object(layer),
+                "`AudioLayer` node",
+                node.name(),
+            )
+            .into());
+        }
+        let id = self.doc.add_object("Audio", name, "Audio", "");
+        self.append_child(id, "Filename", AttributeValue::String(path.to_owned()));
+        self.append_child(
+            id,
+            "RelativeFilename",
+            AttributeValue::String(relative_filename(path)),
+        );
+        if let Some(content) = content {
+            self.append_child(id, "Content", AttributeValue::Binary(content));
+        }
+        self.doc.insert_property(
+            id,
+            "Offset",
+            "KTime",
+            "Time",
+            "",
+            OwnedPropertyValue::Time(offset),
+        )?;
+        self.doc.connect(id, layer);
+        Ok(id)
+    }
+
+    /// Adds a texture using the video clip and returns its ID.
+    ///
+    /// The texture is attached to the property (such as `DiffuseColor`) of
+    /// the material if given.
+    pub fn add_texture(
+        &mut self,
+        name: &str,
+        video: ObjectId,
+        material: Option<(ObjectId, &str)>,
+    ) -> Result<ObjectId, anyhow::Error> {
+        let (filename, relative) = {
+            let node = self.doc.object_node(video)?;
+            if node.name() != "Video" {
+                return Err(DomError::type_mismatch(
+                    ErrorLocation::node(&node).with_object(video),
+                    "`Video` node",
+                    node.name(),
+                )
+                .into());
+            }
+            let get = |name: &str| {
+                node.first_child_by_name(name)
+                    .and_then(|child| child.attributes().first())
+                    .and_then(AttributeValue::get_string)
+                    .unwrap_or("")
+                    .to_owned()
+            };
+            (get("Filename"), get("RelativeFilename"))
+        };
+        let id = self.doc.add_object("Texture", name, "Texture", "");
+        self.append_child(
+            id,
+            "Type",
+            AttributeValue::String("TextureVideoClip".to_owned()),
+        );
+        self.append_child(id, "Version", AttributeValue::I32(202));
+        self.append_child(
+            id,
+            "TextureName",
+            AttributeValue::String(format!("{}\u{0}\u{1}Texture", name)),
+        );
+        self.append_child(id, "FileName", AttributeValue::String(filename));
+        self.append_child(id, "RelativeFilename", AttributeValue::String(relative));
+        self.doc.connect(video, id);
+        if let Some((material, property)) = material {
+            self.doc.connect_to_property(id, material, property);
+        }
+        Ok(id)
+    }
+
+    /// Adds a vertex cache deformer with a cache referring to the external
+    /// cache file to the mesh geometry, and returns the ID of the cache.
+    ///
+    /// `channel` is the name of the channel in the cache file.
+    /// `CacheFile` is generated from `path` as `RelativeFilename` of
+    /// [`add_video`] is.
+    ///
+    /// [`add_video`]: #method.add_video
+    pub fn add_vertex_cache(
+        &mut self,
+        name: &str,
+        mesh: ObjectId,
+        path: &str,
+        file_type: CacheFileType,
+        channel: &str,
+    ) -> Result<ObjectId, anyhow::Error> {
+        let node = self.doc.object_node(mesh)?;
+        if node.name() != "Geometry" {
+            return Err(DomError::type_mismatch(
+                ErrorLocation::node(&node).with_object(mesh),
+                "`Geometry` node",
+                node.name(),
+            )
+            .into());
+        }
+        let file_type = match file_type {
+            CacheFileType::Unknown => 0,
+            CacheFileType::MaxPointCacheV2 => 1,
+            CacheFileType::MayaCache => 2,
+            CacheFileType::Alembic => 3,
+        };
+
+        let deformer = self
+            .doc
+            .add_object("Deformer", name, "Deformer", "VertexCacheDeformer");
+        self.append_child(deformer, "Version", AttributeValue::I32(100));
+        self.doc.insert_property(
+            deformer,
+            "Channel",
+            "KString",
+            "",
+            "",
+            OwnedPropertyValue::String(channel.to_owned()),
+        )?;
+        self.doc.connect(deformer, mesh);
+
+        let id = self.doc.add_object("Cache", name, "Cache", "");
+        self.append_child(id, "Version", AttributeValue::I32(100));
+        for (prop, value) in [
+            ("CacheFile", relative_filename(path)),
+            ("CacheFileAbsolutePath", path.to_owned()),
+        ] {
+            self.doc.insert_property(
+                id,
+                prop,
+                "KString",
+                "XRefUrl",
+                "",
+                OwnedPropertyValue::String(value),
+            )?;
+        }
+        self.doc.insert_property(
+            id,
+            "CacheFileType",
+            "enum",
+            "",
+            "",
+            OwnedPropertyValue::I32(file_type),
+        )?;
+        self.doc.connect(id, deformer);
+        Ok(id)
+    }
+
+    /// Adds a display layer containing the objects and returns its ID.
+    pub fn add_display_layer(
+        &mut self,
+        name: &str,
+        color: [f64; 3],
+        objects: &[ObjectId],
+    ) -> Result<ObjectId, anyhow::Error> {
+        for &object in objects {
+            self.doc.object_node(object)?;
+        }
+        let id = self
+            .doc
+            .add_object("CollectionExclusive", name, "DisplayLayer", "DisplayLayer");
+        self.doc.insert_property(
+            id,
+            "Color",
+            "ColorRGB",
+            "Color",
+            "",
+            OwnedPropertyValue::F64x3(color),
+        )?;
+        self.doc
+            .insert_property(id, "Show", "bool", "", "", OwnedPropertyValue::Bool(true))?;
+        for &object in objects {
+            self.doc.connect(object, id);
+        }
+        Ok(id)
+    }
+
+    /// Adds a selection set containing the objects and returns its ID.
+    ///
+    /// Components of objects can be added to the set by
+    /// [`add_selection_node`](#method.add_selection_node).
+    pub fn add_selection_set(
+        &mut self,
+        name: &str,
+        objects: &[ObjectId],
+    ) -> Result<ObjectId, anyhow::Error> {
+        for &object in objects {
+            self.doc.object_node(object)?;
+        }
+        let id = self
+            .doc
+            .add_object("SelectionSet", name, "SelectionSet", "");
+        for &object in objects {
+            self.doc.connect(object, id);
+        }
+        Ok(id)
+    }
+
+    /// Adds a selection node for the components (control point, edge, and
+    /// polygon indices) of the object to the selection set, and returns its
+    /// ID.
+    pub fn add_selection_node(
+        &mut self,
+        set: ObjectId,
+        object: ObjectId,
+        vertices: &[i32],
+        edges: &[i32],
+        polygons: &[i32],
+    ) -> Result<ObjectId, anyhow::Error> {
+        let node = self.doc.object_node(set)?;
+        if node.name() != "SelectionSet" {
+            return Err(DomError::type_mismatch(
+                ErrorLocation::node(&node).with_object(set),
+                "`SelectionSet` node",
+                node.name(),
+            )
+            .into());
+        }
+        self.doc.object_node(object)?;
+        let id = self
+            .doc
+            .add_object("SelectionNode", "", "SelectionNode", "");
+        self.append_child(id, "Version", AttributeValue::I32(100));
+        self.append_child(id, "VertexIndexArray", vertices.to_vec().into());
+        self.append_child(id, "EdgeIndexArray", edges.to_vec().into());
+        self.append_child(id, "PolygonIndexArray", polygons.to_vec().into());
+        self.append_child(id, "IsTheNodeInSet", AttributeValue::I32(0));
+        self.doc.connect(object, id);
+        self.doc.connect(id, set);
+        Ok(id)
+    }
+
+    /// Adds an animation stack (take) and returns its ID.
+    ///
+    /// `start` and `stop` are times in `KTime` ticks.
+    pub fn add_animation_stack(
+        &mut self,
+        name: &str,
+        start: i64,
+        stop: i64,
+    ) -> Result<ObjectId, anyhow::Error> {
+        if start > stop {
+            bail!(
+                "Animation stack start is after stop: start={}, stop={}",
+                start,
+                stop
+            );
+        }
+        let id = self.doc.add_object("AnimationStack", name, "AnimStack", "");
+        for &(prop, value) in &[
+            ("LocalStart", start),
+            ("LocalStop", stop),
+            ("ReferenceStart", start),
+            ("ReferenceStop", stop),
+        ] {
+            self.doc.insert_property(
+                id,
+                prop,
+                "KTime",
+                "Time",
+                "",
+                OwnedPropertyValue::Time(value),
+            )?;
+        }
+        Ok(id)
+    }
+
+    /// Adds an animation layer to the stack and returns its ID.
+    pub fn add_animation_layer(&mut self, name: &str, stack: ObjectId) -> ObjectId {
+        let id = self.doc.add_object("AnimationLayer", name, "AnimLayer", "");
+        self.doc.connect(id, stack);
+        id
+    }
+
+    /// Adds an animation curve node animating the property of the target
+    /// object, and returns its ID.
+    ///
+    /// `channels` are pairs of a channel name (such as `X`) and its curve.
+    /// Each curve is connected to the curve node as `d|<channel>`.
+    /// Returns an error if the target is not found, a channel is listed
+    /// twice, or a curve is invalid.
+    #[cfg(feature = "animation")]
+    pub fn add_animation_curve_node(
+        &mut self,
+        layer: ObjectId,
+        target: ObjectId,
+        property: &str,
+        channels: &[(&str, &CurveBuilder)],
+    ) -> Result<ObjectId, anyhow::Error> {
+        self.doc.object_node(target)?;
+        for (i, (channel, curve)) in channels.iter().enumerate() {
+            if channels[..i].iter().any(|(other, _)| other == channel) {
+                bail!("Duplicate animation channel: {:?}", channel);
+            }
+            curve.validate()?;
+        }
+
+        let name = match property {
+            "Lcl Translation" => "T",
+            "Lcl Rotation" => "R",
+            "Lcl Scaling" => "S",
+            property => property,
+        };
+        let id = self
+            .doc
+            .add_object("AnimationCurveNode", name, "AnimCurveNode", "");
+        for (channel, curve) in channels {
+            let channel = format!("d|{}", channel);
+            let default = curve.default_value().unwrap_or(0.0);
+            self.doc.insert_property(
+                id,
+                &channel,
+                "Number",
+                "",
+                "A",
+                OwnedPropertyValue::F64(f64::from(default)),
+            )?;
+            let curve_id = self.doc.add_object("AnimationCurve", "", "AnimCurve", "");
+            let node = self.doc.object_node(curve_id)?.node_id();
+            curve.write(&mut self.doc.tree, node);
+            self.doc.connect_to_property(curve_id, id, &channel);
+        }
+        self.doc.connect(id, layer);
+        self.doc.connect_to_property(id, target, property);
+        Ok(id)
+    }
+
+    /// Appends a child node with a single attribute to the object.
+    fn append_child(&mut self, object: ObjectId, name: &str, value: AttributeValue) -> NodeId {
+        let object = self
+            .doc
+            .object_node(object)
+            .expect("Should never fail: the object is added by the builder")
+            .node_id();
+        let node = self.doc.tree.append_new(object, name);
+        self.doc.tree.append_attribute(node, value);
+        node
+    }
+
+    /// Fills the header, global settings, documents and definitions, and
+    /// returns the tree.
+    pub fn build_tree(mut self) -> Tree {
+        self.write_header();
+        self.write_global_settings();
+        self.write_documents();
+        self.doc.update_definitions(TemplateSource::Existing);
+        self.doc.into_tree()
+    }
+
+    /// Builds the document.
+    pub fn build(self) -> Result<Document, LoadError> {
+        Loader::new().load_from_tree(self.build_tree())
+    }
+
+    /// Fills `FBXHeaderExtension` node.
+    fn write_header(&mut self) {
+        let tree = &mut self.doc.tree;
+        let header = tree
+            .root()
+            .first_child_by_name("FBXHeaderExtension")
+            .expect("Should never fail: created by the builder")
+            .node_id();
+        let version = tree.append_new(header, "FBXHeaderVersion");
+        tree.append_attribute(version, 1003i32);
+        let version = tree.append_new(header, "FBXVersion");
+        tree.append_attribute(version, FBX_VERSION);
+        if let Some(time) = self.creation_time {
+            let stamp = tree.append_new(header, "CreationTimeStamp");
+            for &(name, value) in &[
+                ("Version", 1000),
+                ("Year", time.year),
+                ("Month", i32::from(time.month)),
+                ("Day", i32::from(time.day)),
+                ("Hour", i32::from(time.hour)),
+                ("Minute", i32::from(time.minute)),
+                ("Second", i32::from(time.second)),
+                ("Millisecond", i32::from(time.millisecond)),
+            ] {
+                let node = tree.append_new(stamp, name);
+                tree.append_attribute(node, value);
+            }
+        }
+        let creator = tree.append_new(header, "Creator");
+        tree.append_attribute(creator, self.creator.clone());
+    }
+
+    /// Fills `GlobalSettings` node with Y-up, right-handed, centimeter axis
+    /// system.
+    fn write_global_settings(&mut self) {
+        let tree = &mut self.doc.tree;
+        let settings = tree
+            .root()
+            .first_child_by_name("GlobalSettings")
+            .expect("Should never fail: created by the builder")
+            .node_id();
+        let version = tree.append_new(settings, "Version");
+        tree.append_attribute(version, 1000i32);
+        let props = tree.append_new(settings, "Properties70");
+        for &(name, value) in &[
+            ("UpAxis", 1),
+            ("UpAxisSign", 1),
+            ("FrontAxis", 2),
+            ("FrontAxisSign", 1),
+            ("CoordAxis", 0),
+            ("CoordAxisSign", 1),
+        ] {
+            let p = tree.append_new(props, "P");
+            tree.set_attributes_vec(
+                p,
+                vec![
+                    name.to_owned().into(),
+                    "int".to_owned().into(),
+                    "Integer".to_owned().into(),
+                    String::new().into(),
+                    AttributeValue::I32(value),
+                ],
+            );
+        }
+        let p = tree.append_new(props, "P");
+        tree.set_attributes_vec(
+            p,
+            vec![
+                "UnitScaleFactor".to_owned().into(),
+                "double".to_owned().into(),
+                "Number".to_owned().into(),
+                String::new().into(),
+                AttributeValue::F64(1.0),
+            ],
+        );
+    }
+
+    /// Fills `Documents` node with a scene document.
+    fn write_documents(&mut self) {
+        let id = self.doc.new_object_id();
+        let tree = &mut self.doc.tree;
+        let documents = tree
+            .root()
+            .first_child_by_name("Documents")
+            .expect("Should never fail: created by the builder")
+            .node_id();
+        let count = tree.append_new(documents, "Count");
+        tree.append_attribute(count, 1i32);
+        let document = tree.append_new(documents, "Document");
+        tree.append_attribute(document, id.raw());
+        tree.append_attribute(document, "Scene".to_owned());
+        tree.append_attribute(document, "Scene".to_owned());
+        let root_node = tree.append_new(document, "RootNode");
+        tree.append_attribute(root_node, ROOT_MODEL_ID);
+    }
+}
+
+impl Default for DocumentBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,109 @@
+//! Definitions regeneration.
+
+use fbxcel::tree::v7400::{NodeId, Tree};
+
+use crate::v7400::{
+    edit::{copy_node, DocumentMut},
+    Document,
+};
+
+/// Source of property templates for regenerated `Definitions`.
+#[derive(Debug, Clone, Copy)]
+pub enum TemplateSource<'a> {
+    /// Keeps the templates in the document being updated.
+    Existing,
+    /// Copies the templates from the given (source) document.
+    Document(&'a Document),
+}
+
+impl DocumentMut {
+    /// Regenerates the `Definitions` node from the objects in the document.
+    ///
+    /// Object counts are recomputed, object types without objects are
+    /// removed, and property templates for the remaining object types are
+    /// taken from `templates`.
+    pub fn update_definitions(&mut self, templates: TemplateSource<'_>) {
+        let mut generated = Tree::default();
+        let generated_root = generated.root().node_id();
+        build_definitions(&self.tree, templates, &mut generated, generated_root);
+        let generated = generated
+            .root()
+            .first_child_by_name("Definitions")
+            .expect("Should never fail: `Definitions` node is just created");
+
+        let root = self.tree.root();
+        let new = match root.first_child_by_name("Definitions") {
+            Some(old) => {
+                let old = old.node_id();
+                let new = self.tree.insert_new_before(old, "Definitions");
+                self.tree.detach(old);
+                new
+            }
+            None => match root.first_child_by_name("Objects") {
+                Some(objects) => {
+                    let objects = objects.node_id();
+                    self.tree.insert_new_before(objects, "Definitions")
+                }
+                None => {
+                    let root = root.node_id();
+                    self.tree.append_new(root, "Definitions")
+                }
+            },
+        };
+        for child in generated.children() {
+            copy_node(child, &mut self.tree, new);
+        }
+    }
+}
+
+/// Appends the `Definitions` node generated from the objects in `tree` to
+/// `parent` in `dest`, and returns the new node.
+pub(crate) fn build_definitions(
+    tree: &Tree,
+    templates: TemplateSource<'_>,
+    dest: &mut Tree,
+    parent: NodeId,
+) -> NodeId {
+    let mut counts: Vec<(&str, i32)> = vec![("GlobalSettings", 1)];
+    if let Some(objects) = tree.root().first_child_by_name("Objects") {
+        for object in objects.children() {
+            match counts.iter_mut().find(|(name, _)| *name == object.name()) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((object.name(), 1)),
+            }
+        }
+    }
+    let template_tree = match templates {
+        TemplateSource::Existing => tree,
+        TemplateSource::Document(doc) => doc.tree(),
+    };
+    let template_types: Vec<_> = template_tree
+        .root()
+        .first_child_by_name("Definitions")
+        .into_iter()
+        .flat_map(|definitions| definitions.children_by_name("ObjectType"))
+        .collect();
+
+    let definitions = dest.append_new(parent, "Definitions");
+    let version = dest.append_new(definitions, "Version");
+    dest.append_attribute(version, 100i32);
+    let total = dest.append_new(definitions, "Count");
+    dest.append_attribute(total, counts.iter().map(|(_, count)| count).sum::<i32>());
+    for (name, count) in counts {
+        let object_type = dest.append_new(definitions, "ObjectType");
+        dest.append_attribute(object_type, name.to_owned());
+        let count_node = dest.append_new(object_type, "Count");
+        dest.append_attribute(count_node, count);
+        let source = template_types
+            .iter()
+            .find(|node| node.attributes().first().and_then(|v| v.get_string()) == Some(name));
+        for template in source
+            .into_iter()
+            .flat_map(|node| node.children_by_name("PropertyTemplate"))
+        {
+            copy_node(template, dest, object_type);
+        }
+    }
+
+    definitions
+}
@@ -0,0 +1,184 @@
+//! Embedded media editing.
+
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{bail, format_err, Context, Error};
+use fbxcel::{
+    low::v7400::AttributeValue,
+    tree::v7400::{NodeHandle, NodeId},
+};
+use log::warn;
+
+use crate::v7400::{edit::DocumentMut, object::ObjectId, Error as DomError, ErrorLocation};
+
+impl DocumentMut {
+    /// Returns the IDs of `Video` objects.
+    fn video_objects(&self) -> Vec<ObjectId> {
+        self.toplevel("Objects")
+            .ok()
+            .into_iter()
+            .flat_map(|objects| objects.to_handle(&self.tree).children_by_name("Video"))
+            .filter_map(|node| node.attributes().first().and_then(AttributeValue::get_i64))
+            .map(ObjectId::from_raw)
+            .collect()
+    }
+
+    /// Sets the embedded content of the video object.
+    ///
+    /// `None` removes the embedded content, and the video refers to the
+    /// external file.
+    pub fn set_media_content(
+        &mut self,
+        video: ObjectId,
+        content: Option<Vec<u8>>,
+    ) -> Result<(), Error> {
+        let node = self.object_node(video)?;
+        if node.name() != "Video" {
+            return Err(DomError::type_mismatch(
+                ErrorLocation::node(&node).with_object(video),
+                "`Video` node",
+                node.name(),
+            )
+            .into());
+        }
+        let node_id = node.node_id();
+        let targets: Vec<NodeId> = node
+            .children_by_name("Content")
+            .map(|child| child.node_id())
+            .collect();
+        for target in targets {
+            self.tree.detach(target);
+        }
+        if let Some(content) = content {
+            let child = self.tree.append_new(node_id, "Content");
+            self.tree.append_attribute(child, content);
+        }
+        Ok(())
+    }
+
+    /// Embeds the external files of all video objects without content, and
+    /// returns the number of embedded files.
+    ///
+    /// Files are searched by `RelativeFilename` relative to `base_dir`, then
+    /// by `Filename`.
+    /// Videos whose files are not found are skipped with warnings.
+    pub fn embed_media(&mut self, base_dir: impl AsRef<Path>) -> Result<usize, Error> {
+        let base_dir = base_dir.as_ref();
+        let mut count = 0;
+        for video in self.video_objects() {
+            let node = self.object_node(video)?;
+            if node.first_child_by_name("Content").is_some() {
+                continue;
+            }
+            let relative = child_string(&node, "RelativeFilename")
+                .map(|relative| base_dir.join(native_path(relative)));
+            let absolute = child_string(&node, "Filename").map(native_path);
+            let path = match relative
+                .into_iter()
+                .chain(absolute)
+                .find(|path| path.is_file())
+            {
+                Some(v) => v,
+                None => {
+                    warn!("Media file not found for video object: id={:?}", video);
+                    continue;
+                }
+            };
+            let content = fs::read(&path)
+                .with_context(|| format!("Failed to read media file {}", path.display()))?;
+            self.set_media_content(video, Some(content))?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Writes the embedded contents of all video objects to external files,
+    /// removes the contents from the document, and returns the number of
+    /// written files.
+    ///
+    /// Files are written to `RelativeFilename` relative to `base_dir`.
+    /// Returns an error if a relative filename is missing, or points outside
+    /// of `base_dir`.
+    pub fn extract_media(&mut self, base_dir: impl AsRef<Path>) -> Result<usize, Error> {
+        let base_dir = base_dir.as_ref();
+        let mut count = 0;
+        for video in self.video_objects() {
+            let node = self.object_node(video)?;
+            let content = match node
+                .first_child_by_name("Content")
+                .and_then(|content| content.attributes().first())
+                .and_then(AttributeValue::get_binary)
+            {
+                Some(v) if !v.is_empty() => v,
+                _ => continue,
+            };
+            let relative = child_string(&node, "RelativeFilename").ok_or_else(|| {
+                format_err!(
+                    "`RelativeFilename` not found for video object: id={:?}",
+                    video
+                )
+            })?;
+            let relative = native_path(relative);
+            if relative
+                .components()
+                .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+            {
+                bail!(
+                    "Relative filename points outside of the base directory: {}",
+                    relative.display()
+                );
+            }
+            let path = base_dir.join(relative);
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)
+                    .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+            }
+            fs::write(&path, content)
+                .with_context(|| format!("Failed to write media file {}", path.display()))?;
+            self.set_media_content(video, None)?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+}
+
+/// Returns the relative filename to write for the given media path.
+///
+/// Relative paths are used as is, and absolute paths are reduced to the file
+/// name.
+pub(crate) fn relative_filename(path: &str) -> String {
+    let is_absolute =
+        path.starts_with('/') || path.starts_with('\\') || path.as_bytes().get(1) == Some(&b':');
+    if !is_absolute {
+        return path.to_owned();
+    }
+    path.rsplit(|c| c == '/' || c == '\\')
+        .next()
+        .unwrap_or(path)
+        .to_owned()
+}
+
+/// Converts the path in the FBX data into the native path.
+///
+/// Both slashes and backslashes are treated as separators.
+fn native_path(path: &str) -> PathBuf {
+    if cfg!(windows) {
+        PathBuf::from(path)
+    } else {
+        PathBuf::from(path.replace('\\', "/"))
+    }
+}
+
+/// Returns the string attribute of the first child node with the given name.
+fn child_string<'a>(node: &NodeHandle<'a>, name: &str) -> Option<&'a str> {
+    node.first_child_by_name(name)?
+        .attributes()
+        .first()?
+        .get_string()
+        .filter(|s| !s.is_empty())
+}
@@ -0,0 +1,374 @@
+//! Mesh builder.
+
+use anyhow::{bail, Error};
+use fbxcel::{
+    low::v7400::AttributeValue,
+    tree::v7400::{NodeId, Tree},
+};
+
+use crate::v7400::data::mesh::layer::{MappingMode, ReferenceMode};
+
+/// Layer element data.
+#[derive(Debug, Clone, PartialEq)]
+struct LayerData<T> {
+    /// Mapping mode.
+    mapping: MappingMode,
+    /// Values.
+    values: Vec<T>,
+    /// Indices into `values`, for `IndexToDirect` reference mode.
+    indices: Option<Vec<u32>>,
+}
+
+impl<T> LayerData<T> {
+    /// Returns the reference mode.
+    fn reference_mode(&self) -> ReferenceMode {
+        match self.indices {
+            Some(_) => ReferenceMode::IndexToDirect,
+            None => ReferenceMode::Direct,
+        }
+    }
+
+    /// Checks the data length against the mapping mode.
+    fn validate(&self, target: &str, builder: &MeshBuilder) -> Result<(), Error> {
+        let expected = match self.mapping {
+            MappingMode::ByControlPoint => builder.positions.len(),
+            MappingMode::ByPolygonVertex => builder.polygons.iter().map(Vec::len).sum(),
+            MappingMode::ByPolygon => builder.polygons.len(),
+            MappingMode::AllSame => 1,
+            mapping => bail!("Unsupported mapping mode for {}: {:?}", target, mapping),
+        };
+        match &self.indices {
+            Some(indices) => {
+                if indices.len() != expected {
+                    bail!(
+                        "Index count mismatch for {} ({:?}): expected {} but got {}",
+                        target,
+                        self.mapping,
+                        expected,
+                        indices.len()
+                    );
+                }
+                if let Some(&i) = indices.iter().find(|&&i| i as usize >= self.values.len()) {
+                    bail!(
+                        "Index out of range for {}: index {} but only {} values",
+                        target,
+                        i,
+                        self.values.len()
+                    );
+                }
+            }
+            None => {
+                if self.values.len() != expected {
+                    bail!(
+                        "Value count mismatch for {} ({:?}): expected {} but got {}",
+                        target,
+                        self.mapping,
+                        expected,
+                        self.values.len()
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Builder of mesh geometry data.
+///
+/// This encodes `Vertices`, `PolygonVertexIndex` (with negative-terminated
+/// polygons), and layer elements for normals, UVs and materials.
+///
+/// Pass the builder to
+/// [`DocumentBuilder::add_mesh_with()`][`super::DocumentBuilder::add_mesh_with`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshBuilder {
+    /// Control point positions.
+    positions: Vec<[f64; 3]>,
+    /// Polygons as control point indices.
+    polygons: Vec<Vec<u32>>,
+    /// Normals.
+    normals: Option<LayerData<[f64; 3]>>,
+    /// UV sets with names.
+    uvs: Vec<(String, LayerData<[f64; 2]>)>,
+    /// Material indices.
+    materials: Option<LayerData<u32>>,
+}
+
+impl MeshBuilder {
+    /// Creates a new builder with the given control point positions.
+    pub fn new(positions: Vec<[f64; 3]>) -> Self {
+        Self {
+            positions,
+            polygons: Vec::new(),
+            normals: None,
+            uvs: Vec::new(),
+            materials: None,
+        }
+    }
+
+    /// Adds a polygon by control point indices.
+    pub fn polygon(mut self, indices: &[u32]) -> Self {
+        self.polygons.push(indices.to_vec());
+        self
+    }
+
+    /// Adds polygons by control point indices.
+    pub fn polygons<'a>(mut self, polygons: impl IntoIterator<Item = &'a [u32]>) -> Self {
+        self.polygons
+            .extend(polygons.into_iter().map(<[u32]>::to_vec));
+        self
+    }
+
+    /// Adds triangles by control point indices.
+    pub fn triangles(mut self, triangles: &[[u32; 3]]) -> Self {
+        self.polygons
+            .extend(triangles.iter().map(|tri| tri.to_vec()));
+        self
+    }
+
+    /// Sets normals.
+    ///
+    /// If `indices` is `Some(_)`, `IndexToDirect` reference mode is used.
+    pub fn normals(
+        mut self,
+        mapping: MappingMode,
+        values: Vec<[f64; 3]>,
+        indices: Option<Vec<u32>>,
+    ) -> Self {
+        self.normals = Some(LayerData {
+            mapping,
+            values,
+            indices,
+        });
+        self
+    }
+
+    /// Adds a UV set.
+    ///
+    /// If `indices` is `Some(_)`, `IndexToDirect` reference mode is used.
+    pub fn uv(
+        mut self,
+        name: &str,
+        mapping: MappingMode,
+        values: Vec<[f64; 2]>,
+        indices: Option<Vec<u32>>,
+    ) -> Self {
+        self.uvs.push((
+            name.to_owned(),
+            LayerData {
+                mapping,
+                values,
+                indices,
+            },
+        ));
+        self
+    }
+
+    /// Sets per-polygon material indices.
+    ///
+    /// Indices refer to materials in the order they are connected to the
+    /// model.
+    pub fn materials(mut self, indices: Vec<u32>) -> Self {
+        let count = indices.iter().max().map_or(0, |&max| max as usize + 1);
+        self.materials = Some(LayerData {
+            mapping: MappingMode::ByPolygon,
+            values: (0..count as u32).collect(),
+            indices: Some(indices),
+        });
+        self
+    }
+
+    /// Uses a single material for all polygons.
+    pub fn single_material(mut self) -> Self {
+        self.materials = Some(LayerData {
+            mapping: MappingMode::AllSame,
+            values: vec![0],
+            indices: Some(vec![0]),
+        });
+        self
+    }
+
+    /// Checks the consistency of the data.
+    pub fn validate(&self) -> Result<(), Error> {
+        for (i, polygon) in self.polygons.iter().enumerate() {
+            if polygon.len() < 3 {
+                bail!(
+                    "Polygon {} has too few vertices: expected at least 3 but got {}",
+                    i,
+                    polygon.len()
+                );
+            }
+            if let Some(&v) = polygon
+                .iter()
+                .find(|&&v| v as usize >= self.positions.len())
+            {
+                bail!(
+                    "Control point index out of range in polygon {}: index {} but only {} control points",
+                    i,
+                    v,
+                    self.positions.len()
+                );
+            }
+        }
+        if let Some(normals) = &self.normals {
+            normals.validate("normals", self)?;
+        }
+        for (name, uv) in &self.uvs {
+            uv.validate(&format!("UV set {:?}", name), self)?;
+        }
+        if let Some(materials) = &self.materials {
+            materials.validate("materials", self)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the geometry data as children of the given geometry node.
+    ///
+    /// The data should be validated in advance.
+    pub(crate) fn write(&self, tree: &mut Tree, geometry: NodeId) {
+        append_child(
+            tree,
+            geometry,
+            "Vertices",
+            AttributeValue::ArrF64(self.positions.iter().flatten().copied().collect()),
+        );
+        append_child(
+            tree,
+            geometry,
+            "PolygonVertexIndex",
+            AttributeValue::ArrI32(encode_polygons(&self.polygons)),
+        );
+
+        let mut layer_elements: Vec<(usize, &str, i32)> = Vec::new();
+        if let Some(normals) = &self.normals {
+            let element =
+                append_layer_element(tree, geometry, "LayerElementNormal", 0, "", normals);
+            append_child(
+                tree,
+                element,
+                "Normals",
+                AttributeValue::ArrF64(normals.values.iter().flatten().copied().collect()),
+            );
+            if let Some(indices) = &normals.indices {
+                append_child(tree, element, "NormalsIndex", arr_i32(indices));
+            }
+            layer_elements.push((0, "LayerElementNormal", 0));
+        }
+        for (i, (name, uv)) in self.uvs.iter().enumerate() {
+            let element = append_layer_element(tree, geometry, "LayerElementUV", i, name, uv);
+            append_child(
+                tree,
+                element,
+                "UV",
+                AttributeValue::ArrF64(uv.values.iter().flatten().copied().collect()),
+            );
+            if let Some(indices) = &uv.indices {
+                append_child(tree, element, "UVIndex", arr_i32(indices));
+            }
+            layer_elements.push((i, "LayerElementUV", i as i32));
+        }
+        if let Some(materials) = &self.materials {
+            let element =
+                append_layer_element(tree, geometry, "LayerElementMaterial", 0, "", materials);
+            let indices = materials.indices.as_deref().unwrap_or(&[]);
+            append_child(tree, element, "Materials", arr_i32(indices));
+            layer_elements.push((0, "LayerElementMaterial", 0));
+        }
+
+        let num_layers = layer_elements.iter().map(|&(layer, ..)| layer + 1).max();
+        for layer_index in 0..num_layers.unwrap_or(0) {
+            let layer = tree.append_new(geometry, "Layer");
+            tree.append_attribute(layer, layer_index as i32);
+            append_child(tree, layer, "Version", AttributeValue::I32(100));
+            for &(_, ty, typed_index) in layer_elements.iter().filter(|e| e.0 == layer_index) {
+                let element = tree.append_new(layer, "LayerElement");
+                append_child(tree, element, "Type", AttributeValue::String(ty.to_owned()));
+                append_child(
+                    tree,
+                    element,
+                    "TypedIndex",
+                    AttributeValue::I32(typed_index),
+                );
+            }
+        }
+    }
+}
+
+/// Encodes polygons into `PolygonVertexIndex` array.
+///
+/// The last index of each polygon is stored as `!index` (i.e. `-index - 1`).
+pub(crate) fn encode_polygons<P: AsRef<[u32]>>(polygons: &[P]) -> Vec<i32> {
+    polygons
+        .iter()
+        .flat_map(|poly| {
+            let poly = poly.as_ref();
+            let last = poly.len().wrapping_sub(1);
+            poly.iter()
+                .enumerate()
+                .map(move |(i, &v)| if i == last { !(v as i32) } else { v as i32 })
+        })
+        .collect()
+}
+
+/// Converts indices into `i32` array attribute.
+fn arr_i32(indices: &[u32]) -> AttributeValue {
+    AttributeValue::ArrI32(indices.iter().map(|&i| i as i32).collect())
+}
+
+/// Appends a child node with a single attribute.
+fn append_child(tree: &mut Tree, parent: NodeId, name: &str, value: AttributeValue) -> NodeId {
+    let node = tree.append_new(parent, name);
+    tree.append_attribute(node, value);
+    node
+}
+
+/// Appends a layer element node with common children.
+fn append_layer_element<T>(
+    tree: &mut Tree,
+    geometry: NodeId,
+    node_name: &str,
+    typed_index: usize,
+    name: &str,
+    data: &LayerData<T>,
+) -> NodeId {
+    let mapping = match data.mapping {
+        MappingMode::None => "NoMappingInformation",
+        MappingMode::ByControlPoint => "ByControlPoint",
+        MappingMode::ByPolygonVertex => "ByPolygonVertex",
+        MappingMode::ByPolygon => "ByPolygon",
+        MappingMode::ByEdge => "ByEdge",
+        MappingMode::AllSame => "AllSame",
+    };
+    let reference = match data.reference_mode() {
+        ReferenceMode::Direct => "Direct",
+        ReferenceMode::IndexToDirect => "IndexToDirect",
+    };
+    let element = append_child(
+        tree,
+        geometry,
+        node_name,
+        AttributeValue::I32(typed_index as i32),
+    );
+    append_child(tree, element, "Version", AttributeValue::I32(101));
+    append_child(
+        tree,
+        element,
+        "Name",
+        AttributeValue::String(name.to_owned()),
+    );
+    append_child(
+        tree,
+        element,
+        "MappingInformationType",
+        AttributeValue::String(mapping.to_owned()),
+    );
+    append_child(
+        tree,
+        element,
+        "ReferenceInformationType",
+        AttributeValue::String(reference.to_owned()),
+    );
+    element
+}
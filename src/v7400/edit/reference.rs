@@ -0,0 +1,208 @@
+//! Scene reference resolution.
+
+use anyhow::{bail, Error};
+use fbxcel::{low::v7400::AttributeValue, tree::v7400::NodeHandle};
+
+use crate::v7400::{
+    edit::{AppendOptions, DocumentMut},
+    object::ObjectId,
+    Document,
+};
+
+/// Maximum nesting depth of scene references.
+///
+/// Deeper references are most likely cyclic.
+pub const MAX_REFERENCE_DEPTH: usize = 16;
+
+/// Scene reference passed to the resolver of
+/// [`DocumentMut::resolve_references()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SceneReference {
+    /// Object ID of the `SceneReference` object.
+    pub id: ObjectId,
+    /// Path of the referenced file, as written in the document.
+    pub file_path: String,
+    /// Namespace of the referenced objects, including the namespaces of the
+    /// outer references (empty if none).
+    pub namespace: String,
+    /// Nesting depth: `0` for references in the original document.
+    pub depth: usize,
+}
+
+impl DocumentMut {
+    /// Composes the scenes referenced by `SceneReference` objects, and
+    /// returns the number of resolved references.
+    ///
+    /// `resolver` is called for each active reference, and returns the
+    /// referenced document, or `None` to leave the reference unresolved.
+    /// The document is appended with the namespace of the reference, under
+    /// the model the reference is attached to (or the scene root), and the
+    /// reference object is removed.
+    /// References in the appended documents are resolved recursively, and an
+    /// error is returned if they are nested deeper than
+    /// [`MAX_REFERENCE_DEPTH`].
+    ///
+    /// `Definitions` is not updated; use
+    /// [`update_definitions()`][`Self::update_definitions`] after resolving.
+    ///
+    /// ```
+    /// use fbxcel_dom::v7400::{
+    ///     edit::{DocumentBuilder, DocumentMut, ModelKind},
+    ///     object::property::OwnedPropertyValue,
+    /// };
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let edit = builder.edit_mut();
+    /// let reference = edit.add_object("SceneReference", "Chair", "SceneReference", "");
+    /// for &(name, value) in &[
+    ///     ("ReferenceFilePath", "props/chair.fbx"),
+    ///     ("ReferenceNameSpace", "chair1"),
+    /// ] {
+    ///     let value = OwnedPropertyValue::String(value.to_owned());
+    ///     edit.insert_property(reference, name, "KString", "", "", value)?;
+    /// }
+    /// let mut doc = DocumentMut::from_document(builder.build()?);
+    ///
+    /// let resolved = doc.resolve_references(|reference| {
+    ///     assert_eq!(reference.file_path, "props/chair.fbx");
+    ///     let mut chair = DocumentBuilder::new();
+    ///     chair.add_model("Seat", ModelKind::Null, None);
+    ///     Ok(Some(chair.build()?))
+    /// })?;
+    /// assert_eq!(resolved, 1);
+    /// let doc = doc.into_document()?;
+    /// assert!(doc.scene_references().next().is_none());
+    /// assert!(doc.objects().any(|obj| obj.name() == Some("chair1:Seat")));
+    /// # Ok::<_, anyhow::Error>(())
+    /// ```
+    pub fn resolve_references<F>(&mut self, mut resolver: F) -> Result<usize, Error>
+    where
+        F: FnMut(&SceneReference) -> Result<Option<Document>, Error>,
+    {
+        let mut pending: Vec<(ObjectId, usize, String)> = self
+            .scene_reference_ids()
+            .into_iter()
+            .rev()
+            .map(|id| (id, 0, String::new()))
+            .collect();
+        let mut count = 0;
+        while let Some((id, depth, outer_namespace)) = pending.pop() {
+            let (reference, active) = {
+                let node = self.object_node(id)?;
+                let namespace = property_string(&node, "ReferenceNameSpace").unwrap_or("");
+                let namespace = match (outer_namespace.as_str(), namespace) {
+                    (outer, "") => outer.to_owned(),
+                    ("", inner) => inner.to_owned(),
+                    (outer, inner) => format!("{}:{}", outer, inner),
+                };
+                let reference = SceneReference {
+                    id,
+                    file_path: property_string(&node, "ReferenceFilePath")
+                        .unwrap_or("")
+                        .to_owned(),
+                    namespace,
+                    depth,
+                };
+                (
+                    reference,
+                    property_bool(&node, "ReferenceActive").unwrap_or(true),
+                )
+            };
+            if !active {
+                continue;
+            }
+            if depth >= MAX_REFERENCE_DEPTH {
+                bail!(
+                    "Scene references are nested too deep (possibly cyclic): file_path={:?}",
+                    reference.file_path
+                );
+            }
+            let other = match resolver(&reference)? {
+                Some(v) => v,
+                None => continue,
+            };
+
+            let mut options = AppendOptions::new();
+            if let Some(parent) = self.reference_parent(id) {
+                options = options.parent(parent);
+            }
+            if !reference.namespace.is_empty() {
+                options = options.namespace(&reference.namespace);
+            }
+            let id_map = self.append(&other, options)?;
+            self.remove_object(id)?;
+            count += 1;
+
+            let mut nested: Vec<ObjectId> = other
+                .objects()
+                .filter(|obj| obj.node().name() == "SceneReference")
+                .filter_map(|obj| id_map.get(&obj.object_id()).copied())
+                .collect();
+            nested.sort();
+            pending.extend(
+                nested
+                    .into_iter()
+                    .rev()
+                    .map(|id| (id, depth + 1, reference.namespace.clone())),
+            );
+        }
+
+        Ok(count)
+    }
+
+    /// Returns the IDs of `SceneReference` objects.
+    fn scene_reference_ids(&self) -> Vec<ObjectId> {
+        self.toplevel("Objects")
+            .ok()
+            .into_iter()
+            .flat_map(|objects| {
+                objects
+                    .to_handle(&self.tree)
+                    .children_by_name("SceneReference")
+            })
+            .filter_map(|node| node.attributes().first().and_then(AttributeValue::get_i64))
+            .map(ObjectId::from_raw)
+            .collect()
+    }
+
+    /// Returns the model the reference is attached to, if available.
+    fn reference_parent(&self, id: ObjectId) -> Option<ObjectId> {
+        let connections = self.toplevel("Connections").ok()?;
+        connections
+            .to_handle(&self.tree)
+            .children_by_name("C")
+            .filter_map(|node| {
+                let attrs = node.attributes();
+                if attrs.get(3).is_some() || attrs.get(1)?.get_i64()? != id.raw() {
+                    return None;
+                }
+                attrs.get(2)?.get_i64().map(ObjectId::from_raw)
+            })
+            .find(|&dest| {
+                self.object_node(dest)
+                    .map_or(false, |node| node.name() == "Model")
+            })
+    }
+}
+
+/// Returns the string value of the direct property of the object node.
+fn property_string<'a>(node: &NodeHandle<'a>, name: &str) -> Option<&'a str> {
+    property_value(node, name)?.get_string()
+}
+
+/// Returns the boolean value of the direct property of the object node.
+fn property_bool(node: &NodeHandle<'_>, name: &str) -> Option<bool> {
+    let value = property_value(node, name)?;
+    value.get_bool().or_else(|| value.get_i32().map(|v| v != 0))
+}
+
+/// Returns the first value attribute of the direct property of the object
+/// node.
+fn property_value<'a>(node: &NodeHandle<'a>, name: &str) -> Option<&'a AttributeValue> {
+    node.first_child_by_name("Properties70")?
+        .children_by_name("P")
+        .find(|p| p.attributes().first().and_then(AttributeValue::get_string) == Some(name))?
+        .attributes()
+        .get(4)
+}
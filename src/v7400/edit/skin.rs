@@ -0,0 +1,123 @@
+//! Skin builder.
+
+use anyhow::{bail, Error};
+
+use crate::v7400::object::ObjectId;
+
+/// Identity matrix (column major).
+pub const IDENTITY_MATRIX: [f64; 16] = [
+    1.0, 0.0, 0.0, 0.0, //
+    0.0, 1.0, 0.0, 0.0, //
+    0.0, 0.0, 1.0, 0.0, //
+    0.0, 0.0, 0.0, 1.0,
+];
+
+/// Cluster data.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ClusterData {
+    /// Bone (limb node model).
+    pub(crate) bone: ObjectId,
+    /// Control point indices.
+    pub(crate) indices: Vec<u32>,
+    /// Weights for the control points.
+    pub(crate) weights: Vec<f64>,
+    /// Global transform of the mesh at binding time.
+    pub(crate) transform: [f64; 16],
+    /// Global transform of the bone at binding time.
+    pub(crate) transform_link: [f64; 16],
+}
+
+/// Builder of skin deformer data.
+///
+/// Each cluster binds a bone to weighted control points of the mesh.
+/// Matrices are column major, in the same layout as FBX stores them.
+///
+/// Pass the builder to
+/// [`DocumentBuilder::add_skin()`][`super::DocumentBuilder::add_skin`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SkinBuilder {
+    /// Clusters.
+    clusters: Vec<ClusterData>,
+}
+
+impl SkinBuilder {
+    /// Creates a new empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a cluster for the bone.
+    ///
+    /// `transform` is the global transform of the mesh, and `transform_link`
+    /// is the global transform of the bone, both at binding time.
+    pub fn cluster(
+        mut self,
+        bone: ObjectId,
+        indices: Vec<u32>,
+        weights: Vec<f64>,
+        transform: [f64; 16],
+        transform_link: [f64; 16],
+    ) -> Self {
+        self.clusters.push(ClusterData {
+            bone,
+            indices,
+            weights,
+            transform,
+            transform_link,
+        });
+        self
+    }
+
+    /// Returns the clusters.
+    pub(crate) fn clusters(&self) -> &[ClusterData] {
+        &self.clusters
+    }
+
+    /// Checks the consistency of the data against the control point count.
+    pub fn validate(&self, num_control_points: usize) -> Result<(), Error> {
+        if self.clusters.is_empty() {
+            bail!("Skin has no clusters");
+        }
+        for (i, cluster) in self.clusters.iter().enumerate() {
+            if self.clusters[..i].iter().any(|c| c.bone == cluster.bone) {
+                bail!("Duplicate cluster for bone: bone={:?}", cluster.bone);
+            }
+            if cluster.indices.len() != cluster.weights.len() {
+                bail!(
+                    "Index and weight count mismatch for cluster {}: {} indices but {} weights",
+                    i,
+                    cluster.indices.len(),
+                    cluster.weights.len()
+                );
+            }
+            if let Some(&v) = cluster
+                .indices
+                .iter()
+                .find(|&&v| v as usize >= num_control_points)
+            {
+                bail!(
+                    "Control point index out of range in cluster {}: index {} but only {} control points",
+                    i,
+                    v,
+                    num_control_points
+                );
+            }
+            if let Some(&w) = cluster.weights.iter().find(|w| !w.is_finite()) {
+                bail!("Invalid weight in cluster {}: {}", i, w);
+            }
+            validate_matrix(&cluster.transform, "Transform")?;
+            validate_matrix(&cluster.transform_link, "TransformLink")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks that the matrix has no non-finite elements.
+pub(crate) fn validate_matrix(matrix: &[f64; 16], target: &str) -> Result<(), Error> {
+    if let Some(v) = matrix.iter().find(|v| !v.is_finite()) {
+        bail!("Invalid element in {} matrix: {}", target, v);
+    }
+
+    Ok(())
+}
@@ -1,7 +1,14 @@
 //! FBX DOM errors.
 
-pub use self::load::LoadError;
+pub(crate) use self::load::LimitError;
+pub use self::{
+    dom::{Error, ErrorCode, ErrorLocation},
+    load::LoadError,
+    warning::{LoadWarning, Warning, Warnings},
+};
 
 pub(crate) mod connection;
+pub(crate) mod dom;
 pub(crate) mod load;
 pub(crate) mod object;
+pub(crate) mod warning;
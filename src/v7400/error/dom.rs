@@ -0,0 +1,426 @@
+//! Structured DOM access error.
+
+use std::{error, fmt};
+
+use fbxcel::tree::v7400::NodeHandle;
+
+use crate::v7400::object::ObjectId;
+
+/// Error code, the kind of [`Error`].
+///
+/// Codes are stable, and can be used to branch on failure kinds or to be
+/// reported to users (see [`ErrorCode::as_str`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// Required child node not found.
+    MissingNode,
+    /// Required node attribute not found.
+    MissingAttribute,
+    /// Required object not found.
+    MissingObject,
+    /// Required property not found.
+    MissingProperty,
+    /// Unexpected value type (or unexpected number of values).
+    TypeMismatch,
+    /// Index (such as polygon vertex index) out of range.
+    CorruptIndex,
+    /// Value of the expected type, but invalid.
+    InvalidValue,
+    /// String value which cannot be parsed (such as unknown enum names).
+    ParseError,
+    /// Property value load failure.
+    PropertyLoad,
+}
+
+impl ErrorCode {
+    /// Returns the code as a string, such as `"missing-property"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::MissingNode => "missing-node",
+            ErrorCode::MissingAttribute => "missing-attribute",
+            ErrorCode::MissingObject => "missing-object",
+            ErrorCode::MissingProperty => "missing-property",
+            ErrorCode::TypeMismatch => "type-mismatch",
+            ErrorCode::CorruptIndex => "corrupt-index",
+            ErrorCode::InvalidValue => "invalid-value",
+            ErrorCode::ParseError => "parse-error",
+            ErrorCode::PropertyLoad => "property-load",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Location of the error in the document.
+///
+/// All fields are optional, and set if known.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ErrorLocation {
+    /// Node path from the toplevel, such as
+    /// `Objects/Geometry/LayerElementNormal/Normals`.
+    pub node_path: Option<String>,
+    /// Object ID.
+    pub object: Option<ObjectId>,
+    /// Property name.
+    pub property: Option<String>,
+}
+
+impl ErrorLocation {
+    /// Creates a location of the node.
+    pub(crate) fn node(node: &NodeHandle<'_>) -> Self {
+        Self {
+            node_path: Some(node_path(node)),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a location of the object.
+    pub(crate) fn object(object: ObjectId) -> Self {
+        Self {
+            object: Some(object),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the object ID.
+    pub(crate) fn with_object(mut self, object: ObjectId) -> Self {
+        self.object = Some(object);
+        self
+    }
+
+    /// Sets the property name.
+    pub(crate) fn with_property(mut self, property: &str) -> Self {
+        self.property = Some(property.to_owned());
+        self
+    }
+
+    /// Returns true if no location information is available.
+    fn is_empty(&self) -> bool {
+        self.node_path.is_none() && self.object.is_none() && self.property.is_none()
+    }
+}
+
+impl fmt::Display for ErrorLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut sep = "";
+        if let Some(path) = &self.node_path {
+            write!(f, "node_path={:?}", path)?;
+            sep = ", ";
+        }
+        if let Some(object) = &self.object {
+            write!(f, "{}object={:?}", sep, object)?;
+            sep = ", ";
+        }
+        if let Some(property) = &self.property {
+            write!(f, "{}property={:?}", sep, property)?;
+        }
+        Ok(())
+    }
+}
+
+/// Structured DOM access error.
+///
+/// Fallible DOM APIs return `anyhow::Error`, which may contain this error
+/// (possibly wrapped by other errors).
+/// Use [`Error::find`] to get it from the error chain.
+///
+/// ```
+/// # #[cfg(feature = "edit")]
+/// # fn main() -> Result<(), anyhow::Error> {
+/// use fbxcel_dom::v7400::{
+///     edit::{DocumentBuilder, ModelKind},
+///     object::property::OwnedPropertyValue,
+///     Error, ErrorCode,
+/// };
+///
+/// let mut builder = DocumentBuilder::new();
+/// let id = builder.add_model("Broken", ModelKind::Null, None);
+/// // Translation should be three `f64` values.
+/// let value = OwnedPropertyValue::String("far away".to_owned());
+/// builder
+///     .edit_mut()
+///     .insert_property(id, "Lcl Translation", "Lcl Translation", "", "A", value)?;
+/// let doc = builder.build()?;
+///
+/// let model = doc.model_by_path("Broken").expect("Model should exist");
+/// let e = model
+///     .properties()
+///     .translation()
+///     .expect_err("String translation should be rejected");
+///
+/// let err = Error::find(&e).expect("Structured error should be in the chain");
+/// assert_eq!(err.code(), ErrorCode::TypeMismatch);
+/// assert_eq!(err.location().property.as_deref(), Some("Lcl Translation"));
+///
+/// // The outermost error tells which object property failed to load.
+/// let outer = Error::find_outermost(&e).expect("Structured error should be in the chain");
+/// assert_eq!(outer.code(), ErrorCode::PropertyLoad);
+/// assert_eq!(outer.location().object, Some(model.object_id()));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "edit"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Required child node not found.
+    MissingNode {
+        /// Location of the parent node.
+        location: ErrorLocation,
+        /// Name of the missing child node.
+        name: String,
+    },
+    /// Required node attribute not found.
+    MissingAttribute {
+        /// Location of the node.
+        location: ErrorLocation,
+        /// Index of the missing attribute.
+        index: usize,
+    },
+    /// Required object not found.
+    MissingObject {
+        /// Location, with the object ID.
+        location: ErrorLocation,
+    },
+    /// Required property not found.
+    MissingProperty {
+        /// Location, with the property name.
+        location: ErrorLocation,
+    },
+    /// Unexpected value type (or unexpected number of values).
+    TypeMismatch {
+        /// Location of the value.
+        location: ErrorLocation,
+        /// Expected type.
+        expected: String,
+        /// Actual type.
+        actual: String,
+    },
+    /// Index out of range.
+    CorruptIndex {
+        /// Location of the index array.
+        location: ErrorLocation,
+        /// Description of the index.
+        what: String,
+        /// Index.
+        index: i64,
+        /// Length of the indexed array.
+        len: usize,
+    },
+    /// Value of the expected type, but invalid.
+    InvalidValue {
+        /// Location of the value.
+        location: ErrorLocation,
+        /// Description of the problem.
+        message: String,
+    },
+    /// String value which cannot be parsed.
+    ParseError {
+        /// Location of the value.
+        location: ErrorLocation,
+        /// Description of the expected value.
+        what: String,
+        /// Actual value.
+        value: String,
+    },
+    /// Property value load failure.
+    ///
+    /// The underlying error is available by `source()`, and is not included
+    /// in the message (use `{:#}` format of `anyhow::Error` to print the
+    /// whole chain).
+    PropertyLoad {
+        /// Location, with the object ID and the property name.
+        location: ErrorLocation,
+        /// Description of the property.
+        description: String,
+        /// Underlying error.
+        source: anyhow::Error,
+    },
+}
+
+impl Error {
+    /// Returns the error code.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Error::MissingNode { .. } => ErrorCode::MissingNode,
+            Error::MissingAttribute { .. } => ErrorCode::MissingAttribute,
+            Error::MissingObject { .. } => ErrorCode::MissingObject,
+            Error::MissingProperty { .. } => ErrorCode::MissingProperty,
+            Error::TypeMismatch { .. } => ErrorCode::TypeMismatch,
+            Error::CorruptIndex { .. } => ErrorCode::CorruptIndex,
+            Error::InvalidValue { .. } => ErrorCode::InvalidValue,
+            Error::ParseError { .. } => ErrorCode::ParseError,
+            Error::PropertyLoad { .. } => ErrorCode::PropertyLoad,
+        }
+    }
+
+    /// Returns the location.
+    pub fn location(&self) -> &ErrorLocation {
+        match self {
+            Error::MissingNode { location, .. }
+            | Error::MissingAttribute { location, .. }
+            | Error::MissingObject { location }
+            | Error::MissingProperty { location }
+            | Error::TypeMismatch { location, .. }
+            | Error::CorruptIndex { location, .. }
+            | Error::InvalidValue { location, .. }
+            | Error::ParseError { location, .. }
+            | Error::PropertyLoad { location, .. } => location,
+        }
+    }
+
+    /// Returns the innermost structured error in the error chain.
+    ///
+    /// Wrapper errors (such as [`Error::PropertyLoad`]) are skipped if they
+    /// have a structured cause, since the cause describes the failure kind
+    /// more precisely.
+    /// Use [`Error::find_outermost`] to get the wrapper.
+    pub fn find(e: &anyhow::Error) -> Option<&Error> {
+        e.chain().filter_map(|e| e.downcast_ref::<Error>()).last()
+    }
+
+    /// Returns the outermost structured error in the error chain.
+    pub fn find_outermost(e: &anyhow::Error) -> Option<&Error> {
+        e.chain().find_map(|e| e.downcast_ref::<Error>())
+    }
+
+    /// Creates a `MissingNode` error.
+    pub(crate) fn missing_node(parent: &NodeHandle<'_>, name: &str) -> Self {
+        Error::MissingNode {
+            location: ErrorLocation::node(parent),
+            name: name.to_owned(),
+        }
+    }
+
+    /// Creates a `MissingAttribute` error.
+    pub(crate) fn missing_attribute(node: &NodeHandle<'_>, index: usize) -> Self {
+        Error::MissingAttribute {
+            location: ErrorLocation::node(node),
+            index,
+        }
+    }
+
+    /// Creates a `MissingObject` error.
+    pub(crate) fn missing_object(object: ObjectId) -> Self {
+        Error::MissingObject {
+            location: ErrorLocation::object(object),
+        }
+    }
+
+    /// Creates a `MissingProperty` error.
+    pub(crate) fn missing_property(object: ObjectId, property: &str) -> Self {
+        Error::MissingProperty {
+            location: ErrorLocation::object(object).with_property(property),
+        }
+    }
+
+    /// Creates a `TypeMismatch` error.
+    pub(crate) fn type_mismatch(
+        location: ErrorLocation,
+        expected: impl fmt::Display,
+        actual: impl fmt::Debug,
+    ) -> Self {
+        Error::TypeMismatch {
+            location,
+            expected: expected.to_string(),
+            actual: format!("{:?}", actual),
+        }
+    }
+
+    /// Creates a `CorruptIndex` error.
+    pub(crate) fn corrupt_index(
+        location: ErrorLocation,
+        what: &str,
+        index: impl Into<i64>,
+        len: usize,
+    ) -> Self {
+        Error::CorruptIndex {
+            location,
+            what: what.to_owned(),
+            index: index.into(),
+            len,
+        }
+    }
+
+    /// Creates an `InvalidValue` error.
+    pub(crate) fn invalid_value(location: ErrorLocation, message: impl fmt::Display) -> Self {
+        Error::InvalidValue {
+            location,
+            message: message.to_string(),
+        }
+    }
+
+    /// Creates a `ParseError` error.
+    pub(crate) fn parse_error(location: ErrorLocation, what: &str, value: &str) -> Self {
+        Error::ParseError {
+            location,
+            what: what.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingNode { name, .. } => write!(f, "Child node `{}` not found", name)?,
+            Error::MissingAttribute { index, .. } => {
+                write!(f, "Node attribute not found: index={}", index)?
+            }
+            Error::MissingObject { .. } => f.write_str("Object not found")?,
+            Error::MissingProperty { .. } => f.write_str("Property not found")?,
+            Error::TypeMismatch {
+                expected, actual, ..
+            } => write!(
+                f,
+                "Unexpected value type: expected {} but got {}",
+                expected, actual
+            )?,
+            Error::CorruptIndex {
+                what, index, len, ..
+            } => write!(f, "{} out of range: index={}, len={}", what, index, len)?,
+            Error::InvalidValue { message, .. } => f.write_str(message)?,
+            Error::ParseError { what, value, .. } => {
+                write!(f, "Failed to parse {}: got {:?}", what, value)?
+            }
+            Error::PropertyLoad { description, .. } => write!(f, "Failed to load {}", description)?,
+        }
+        let location = self.location();
+        if !location.is_empty() {
+            write!(f, " ({})", location)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::PropertyLoad { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the path of the node from the toplevel.
+pub(crate) fn node_path(node: &NodeHandle<'_>) -> String {
+    let mut names = Vec::new();
+    let mut current = Some(*node);
+    while let Some(node) = current {
+        current = node.parent();
+        // The root node has no name.
+        if current.is_some() {
+            names.push(node.name());
+        }
+    }
+    names.reverse();
+    names.join("/")
+}
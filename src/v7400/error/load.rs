@@ -33,6 +33,13 @@ impl From<TreeLoadError> for LoadError {
     }
 }
 
+#[cfg(feature = "ascii")]
+impl From<crate::v7400::ascii::Error> for LoadError {
+    fn from(e: crate::v7400::ascii::Error) -> Self {
+        Self::new(e)
+    }
+}
+
 /// FBX DOM structure error.
 #[derive(Debug)]
 #[allow(clippy::enum_variant_names)]
@@ -66,3 +73,72 @@ impl From<StructureError> for LoadError {
         Self::new(e)
     }
 }
+
+/// Resource limit error.
+///
+/// See [`ResourceLimits`][`crate::v7400::ResourceLimits`].
+#[derive(Debug)]
+pub(crate) enum LimitError {
+    /// Too many nodes.
+    Nodes {
+        /// Maximum number of nodes.
+        limit: usize,
+    },
+    /// Too deeply nested node.
+    Depth {
+        /// Path of the node.
+        node: String,
+        /// Maximum depth.
+        limit: usize,
+    },
+    /// Too large attribute.
+    ArrayBytes {
+        /// Path of the node.
+        node: String,
+        /// Size of the attribute in bytes.
+        bytes: u64,
+        /// Maximum size in bytes.
+        limit: usize,
+    },
+}
+
+impl LimitError {
+    /// Sets the path of the node.
+    ///
+    /// This is used to build the path only when the check fails.
+    pub(crate) fn with_node(mut self, path: String) -> Self {
+        match &mut self {
+            LimitError::Depth { node, .. } | LimitError::ArrayBytes { node, .. } => *node = path,
+            LimitError::Nodes { .. } => {}
+        }
+        self
+    }
+}
+
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitError::Nodes { limit } => {
+                write!(f, "Resource limit exceeded: more than {} nodes", limit)
+            }
+            LimitError::Depth { node, limit } => write!(
+                f,
+                "Resource limit exceeded: node {:?} is nested deeper than {}",
+                node, limit
+            ),
+            LimitError::ArrayBytes { node, bytes, limit } => write!(
+                f,
+                "Resource limit exceeded: attribute of node {:?} has {} bytes (limit {})",
+                node, bytes, limit
+            ),
+        }
+    }
+}
+
+impl error::Error for LimitError {}
+
+impl From<LimitError> for LoadError {
+    fn from(e: LimitError) -> Self {
+        Self::new(e)
+    }
+}
@@ -0,0 +1,431 @@
+//! FBX DOM load warning.
+
+use std::{
+    collections::HashMap,
+    error, fmt,
+    sync::Mutex,
+    thread::{self, ThreadId},
+};
+
+use log::warn;
+
+use crate::v7400::{data::mesh::layer::MappingMode, object::ObjectId};
+
+/// Problem recovered from while loading in the non-strict mode.
+///
+/// See [`Loader::strict()`][`crate::v7400::Loader::strict`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LoadWarning {
+    /// Node record extends past the end of the data or the parent node.
+    ///
+    /// The node is read up to the available end.
+    TruncatedNode {
+        /// Path of the node (such as `Objects/Geometry/Vertices`).
+        node: String,
+    },
+    /// Node has fewer attributes than declared.
+    MissingAttributes {
+        /// Path of the node.
+        node: String,
+        /// Declared number of attributes.
+        declared: u64,
+        /// Number of attributes read.
+        actual: usize,
+    },
+    /// Unknown attribute type code.
+    ///
+    /// The attribute and the following attributes of the node are skipped.
+    InvalidAttributeType {
+        /// Path of the node.
+        node: String,
+        /// Type code.
+        type_code: u8,
+    },
+    /// Unknown array encoding.
+    ///
+    /// The attribute and the following attributes of the node are skipped.
+    InvalidArrayEncoding {
+        /// Path of the node.
+        node: String,
+        /// Encoding value.
+        encoding: u32,
+    },
+    /// Array data is truncated or corrupted.
+    ///
+    /// Elements before the broken part are kept.
+    TruncatedArray {
+        /// Path of the node.
+        node: String,
+        /// Declared number of elements.
+        declared: usize,
+        /// Number of elements read.
+        actual: usize,
+    },
+    /// Array data has a different number of elements than declared.
+    ///
+    /// All elements in the data are kept.
+    ArrayCountMismatch {
+        /// Path of the node.
+        node: String,
+        /// Declared number of elements.
+        declared: usize,
+        /// Number of elements in the data.
+        actual: usize,
+    },
+    /// Node name or string attribute is not valid UTF-8.
+    ///
+    /// Invalid sequences are replaced with `U+FFFD`.
+    InvalidUtf8 {
+        /// Path of the node.
+        node: String,
+    },
+    /// Required toplevel node is not found.
+    ///
+    /// An empty node is added.
+    MissingNode {
+        /// Node name.
+        node: String,
+    },
+    /// Multiple objects have the same ID.
+    ///
+    /// Objects other than the first one are skipped.
+    DuplicateObjectId(ObjectId),
+    /// Multiple connections have the same source, destination and label.
+    ///
+    /// Connections other than the first one are skipped.
+    DuplicateConnection {
+        /// Source object ID.
+        source: ObjectId,
+        /// Destination object ID.
+        destination: ObjectId,
+        /// Label.
+        label: Option<String>,
+    },
+}
+
+impl fmt::Display for LoadWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadWarning::TruncatedNode { node } => write!(f, "Truncated node: node={:?}", node),
+            LoadWarning::MissingAttributes {
+                node,
+                declared,
+                actual,
+            } => write!(
+                f,
+                "Missing node attributes: node={:?}, declared={}, actual={}",
+                node, declared, actual
+            ),
+            LoadWarning::InvalidAttributeType { node, type_code } => write!(
+                f,
+                "Invalid attribute type code: node={:?}, type_code={:#04x}",
+                node, type_code
+            ),
+            LoadWarning::InvalidArrayEncoding { node, encoding } => write!(
+                f,
+                "Invalid array encoding: node={:?}, encoding={}",
+                node, encoding
+            ),
+            LoadWarning::TruncatedArray {
+                node,
+                declared,
+                actual,
+            } => write!(
+                f,
+                "Truncated array: node={:?}, declared={}, actual={}",
+                node, declared, actual
+            ),
+            LoadWarning::ArrayCountMismatch {
+                node,
+                declared,
+                actual,
+            } => write!(
+                f,
+                "Array elements count mismatch: node={:?}, declared={}, actual={}",
+                node, declared, actual
+            ),
+            LoadWarning::InvalidUtf8 { node } => {
+                write!(f, "Invalid UTF-8 sequence: node={:?}", node)
+            }
+            LoadWarning::MissingNode { node } => {
+                write!(f, "Required toplevel node not found: node={:?}", node)
+            }
+            LoadWarning::DuplicateObjectId(id) => {
+                write!(f, "Duplicate object ID: object={:?}", id)
+            }
+            LoadWarning::DuplicateConnection {
+                source,
+                destination,
+                label,
+            } => write!(
+                f,
+                "Duplicate connection: source={:?}, destination={:?}, label={:?}",
+                source, destination, label
+            ),
+        }
+    }
+}
+
+impl error::Error for LoadWarning {}
+
+/// Non-fatal problem recovered from while loading or extracting data.
+///
+/// Warnings are collected in the document, and can be taken by
+/// [`Document::take_warnings()`][`crate::v7400::Document::take_warnings`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Warning {
+    /// Problem recovered from while loading in the non-strict mode.
+    Load(LoadWarning),
+    /// Layer element with an unsupported mapping mode.
+    ///
+    /// The layer element is ignored, and the next layer element of the same
+    /// type (if any) is used.
+    UnsupportedMappingMode {
+        /// Geometry object ID.
+        geometry: ObjectId,
+        /// Layer element node name (such as `LayerElementNormal`).
+        element: String,
+        /// Mapping mode.
+        mapping_mode: MappingMode,
+    },
+    /// Control points with more bone influences than supported.
+    ///
+    /// The strongest influences are kept, and the weights are renormalized.
+    ClampedInfluences {
+        /// Geometry object ID.
+        geometry: ObjectId,
+        /// Number of control points with clamped influences.
+        control_points: usize,
+        /// Maximum number of influences kept.
+        max_influences: usize,
+    },
+    /// Skin cluster without a bone in the scene.
+    ///
+    /// The cluster is skipped.
+    MissingBone {
+        /// Cluster object ID.
+        cluster: ObjectId,
+    },
+    /// Bone transform at binding time is not invertible.
+    ///
+    /// The identity matrix is used as the inverse bind matrix.
+    SingularBindMatrix {
+        /// Cluster object ID.
+        cluster: ObjectId,
+    },
+    /// Missing or unreadable value replaced with the default.
+    DefaultedValue {
+        /// Object ID.
+        object: ObjectId,
+        /// Property or node name.
+        name: String,
+        /// Cause, if the value is present but unreadable.
+        cause: Option<String>,
+    },
+    /// Object skipped since it cannot be extracted.
+    ExtractionFailed {
+        /// Object ID, if known.
+        object: Option<ObjectId>,
+        /// Description of the failure.
+        message: String,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::Load(warning) => warning.fmt(f),
+            Warning::UnsupportedMappingMode {
+                geometry,
+                element,
+                mapping_mode,
+            } => write!(
+                f,
+                "Ignoring layer element with unsupported mapping mode: geometry={:?}, \
+                 element={:?}, mapping_mode={:?}",
+                geometry, element, mapping_mode
+            ),
+            Warning::ClampedInfluences {
+                geometry,
+                control_points,
+                max_influences,
+            } => write!(
+                f,
+                "Clamped bone influences to {}: geometry={:?}, control_points={}",
+                max_influences, geometry, control_points
+            ),
+            Warning::MissingBone { cluster } => {
+                write!(f, "Skipping cluster without a bone: cluster={:?}", cluster)
+            }
+            Warning::SingularBindMatrix { cluster } => {
+                write!(f, "Bone transform is not invertible: cluster={:?}", cluster)
+            }
+            Warning::DefaultedValue {
+                object,
+                name,
+                cause,
+            } => {
+                write!(f, "Using default for {:?} of {:?}", name, object)?;
+                if let Some(cause) = cause {
+                    write!(f, ": {}", cause)?;
+                }
+                Ok(())
+            }
+            Warning::ExtractionFailed { object, message } => {
+                write!(f, "Extraction failed: object={:?}: {}", object, message)
+            }
+        }
+    }
+}
+
+impl error::Error for Warning {}
+
+impl From<LoadWarning> for Warning {
+    fn from(v: LoadWarning) -> Self {
+        Warning::Load(v)
+    }
+}
+
+/// Warnings collector.
+///
+/// Warnings can be recorded through shared references (and from multiple
+/// threads).
+/// Recorded warnings are also logged by `log::warn!`.
+///
+/// Warnings are kept in the order they are recorded.
+/// Warnings recorded by parallel extraction are reordered as if the
+/// extraction were done sequentially, so the order is deterministic for the
+/// same input.
+#[derive(Debug, Default)]
+pub struct Warnings(Mutex<WarningsInner>);
+
+/// Content of the warnings collector.
+#[derive(Debug, Default)]
+struct WarningsInner {
+    /// Recorded warnings.
+    recorded: Vec<Warning>,
+    /// Stacks of active captures for each thread.
+    captures: HashMap<ThreadId, Vec<Vec<Warning>>>,
+}
+
+impl Warnings {
+    /// Creates a new empty collector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a collector with the warnings already recorded (and logged).
+    pub(crate) fn with_recorded(warnings: Vec<Warning>) -> Self {
+        Self(Mutex::new(WarningsInner {
+            recorded: warnings,
+            captures: HashMap::new(),
+        }))
+    }
+
+    /// Records the warning.
+    pub fn push(&self, warning: impl Into<Warning>) {
+        let warning = warning.into();
+        warn!("{}", warning);
+        self.extend(Some(warning));
+    }
+
+    /// Runs the function, and returns the warnings recorded to this collector
+    /// on the current thread during the call instead of recording them.
+    ///
+    /// Captures are per collector and can be nested; warnings go to the
+    /// innermost capture.
+    /// This is used to record the warnings of parallel jobs in the job order.
+    pub(crate) fn capture<T>(&self, f: impl FnOnce() -> T) -> (T, Vec<Warning>) {
+        /// Guard to end the capture even on panic.
+        struct Capture<'a> {
+            /// Collector.
+            warnings: &'a Warnings,
+            /// Capturing thread.
+            thread: ThreadId,
+        }
+        impl Drop for Capture<'_> {
+            fn drop(&mut self) {
+                let mut inner = self.warnings.lock();
+                if let Some(stack) = inner.captures.get_mut(&self.thread) {
+                    stack.pop();
+                    if stack.is_empty() {
+                        inner.captures.remove(&self.thread);
+                    }
+                }
+            }
+        }
+
+        let thread = thread::current().id();
+        self.lock()
+            .captures
+            .entry(thread)
+            .or_default()
+            .push(Vec::new());
+        let guard = Capture {
+            warnings: self,
+            thread,
+        };
+        let value = f();
+        let warnings = self
+            .lock()
+            .captures
+            .get_mut(&thread)
+            .and_then(|stack| stack.last_mut())
+            .map(std::mem::take)
+            .unwrap_or_default();
+        drop(guard);
+        (value, warnings)
+    }
+
+    /// Records the warnings already logged.
+    ///
+    /// If capturing on the current thread, the warnings are captured instead.
+    pub(crate) fn extend(&self, warnings: impl IntoIterator<Item = Warning>) {
+        let mut inner = self.lock();
+        let inner = &mut *inner;
+        match inner
+            .captures
+            .get_mut(&thread::current().id())
+            .and_then(|stack| stack.last_mut())
+        {
+            Some(captured) => captured.extend(warnings),
+            None => inner.recorded.extend(warnings),
+        }
+    }
+
+    /// Returns the number of recorded warnings.
+    pub fn len(&self) -> usize {
+        self.lock().recorded.len()
+    }
+
+    /// Returns true if no warnings are recorded.
+    pub fn is_empty(&self) -> bool {
+        self.lock().recorded.is_empty()
+    }
+
+    /// Returns a copy of the recorded warnings.
+    pub fn to_vec(&self) -> Vec<Warning> {
+        self.lock().recorded.clone()
+    }
+
+    /// Takes the recorded warnings, leaving the collector empty.
+    pub fn take(&self) -> Vec<Warning> {
+        std::mem::take(&mut self.lock().recorded)
+    }
+
+    /// Locks the content.
+    ///
+    /// A panic while holding the lock cannot leave the content inconsistent,
+    /// so poisoning is ignored.
+    fn lock(&self) -> std::sync::MutexGuard<'_, WarningsInner> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Clone for Warnings {
+    fn clone(&self) -> Self {
+        Self::with_recorded(self.to_vec())
+    }
+}
@@ -0,0 +1,6 @@
+//! Exporters to simple interchange formats.
+//!
+//! See also the [`gltf`][`crate::v7400::gltf`] module (with `gltf-export`
+//! feature) for glTF 2.0 export.
+
+pub mod obj;
@@ -0,0 +1,446 @@
+//! Wavefront OBJ export.
+//!
+//! [`ObjExporter`] writes the mesh models of a document as Wavefront OBJ
+//! data with a companion MTL file, to check the parsing results in any 3D
+//! viewer:
+//!
+//! * each mesh model becomes an object (`o`), with the polygons kept as they
+//!   are (not triangulated),
+//! * positions, normals, and the first UV set are written, with the global
+//!   and geometric transforms applied,
+//! * polygons are grouped by material (`usemtl`), and the materials are
+//!   written with the Lambert / Phong colors and the diffuse texture file.
+//!
+//! ```
+//! # #[cfg(feature = "edit")]
+//! # fn main() -> Result<(), anyhow::Error> {
+//! use fbxcel_dom::v7400::{
+//!     data::material::ShadingModel,
+//!     edit::{DocumentBuilder, ModelKind},
+//!     export::obj::ObjExporter,
+//! };
+//!
+//! let mut builder = DocumentBuilder::new();
+//! let model = builder.add_model("Triangle", ModelKind::Mesh, None);
+//! builder.set_local_transform(model, [0.0, 0.0, 2.0], [0.0; 3], [1.0; 3])?;
+//! let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+//! builder.add_mesh("Triangle", &positions, &[&[0, 1, 2]], Some(model));
+//! builder.add_material("Red", ShadingModel::Lambert, [1.0, 0.0, 0.0], Some(model))?;
+//! let doc = builder.build()?;
+//!
+//! let export = ObjExporter::new().export(&doc)?;
+//! let obj = export.to_obj("scene.mtl");
+//! let obj: Vec<_> = obj.lines().collect();
+//! assert_eq!(
+//!     obj,
+//!     [
+//!         "mtllib scene.mtl",
+//!         "o Triangle",
+//!         "v 0 0 2",
+//!         "v 1 0 2",
+//!         "v 0 1 2",
+//!         "usemtl Red",
+//!         "f 1 2 3",
+//!     ]
+//! );
+//! assert!(export.mtl().starts_with("newmtl Red\n"));
+//! assert!(export.mtl().lines().any(|line| line == "Kd 1 0 0"));
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "edit"))]
+//! # fn main() {}
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use anyhow::{bail, format_err, Error};
+use log::warn;
+use mint::Vector3;
+
+use crate::v7400::{
+    data::{
+        mesh::{layer::TypedLayerElementHandle, PolygonIndex, PolygonVertexIndex},
+        transform::math::Mat4,
+    },
+    object::{model::TypedModelHandle, TypedObjectHandle},
+    scene::{SceneGraph, SceneNode},
+    Document,
+};
+
+/// Wavefront OBJ exporter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjExporter {
+    /// Whether to apply the global transforms of the models.
+    world_space: bool,
+}
+
+impl ObjExporter {
+    /// Creates a new exporter with the default options.
+    ///
+    /// Meshes are written in the world space.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether to apply the global transforms of the models.
+    ///
+    /// If disabled, only the geometric transforms are applied, and each mesh
+    /// is written in the local space of its model.
+    pub fn world_space(mut self, world_space: bool) -> Self {
+        self.world_space = world_space;
+        self
+    }
+
+    /// Exports the mesh models of the given document.
+    ///
+    /// Meshes which fail to be exported are skipped with warnings.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "obj_export", skip_all)
+    )]
+    pub fn export(&self, doc: &Document) -> Result<ObjExport, Error> {
+        let graph = SceneGraph::from_document(doc)?;
+        let material_names = material_names(doc, &graph);
+
+        let mut writer = ObjWriter::default();
+        for node in graph.nodes() {
+            if node.geometry.is_none() {
+                continue;
+            }
+            if let Err(e) = writer.mesh(doc, node, &material_names, self.world_space) {
+                warn!("Failed to export mesh {:?}: {:#}", node.name, e);
+            }
+        }
+
+        let mut mtl = String::new();
+        for (index, name) in material_names.iter().enumerate() {
+            write_material(&mut mtl, doc, &graph, index, name);
+        }
+
+        Ok(ObjExport {
+            obj: writer.obj,
+            mtl,
+        })
+    }
+}
+
+impl Default for ObjExporter {
+    fn default() -> Self {
+        Self { world_space: true }
+    }
+}
+
+/// Exported Wavefront OBJ data.
+#[derive(Debug, Clone)]
+pub struct ObjExport {
+    /// OBJ data without `mtllib`.
+    obj: String,
+    /// MTL data.
+    mtl: String,
+}
+
+impl ObjExport {
+    /// Returns the OBJ data referring to the MTL file with the given path.
+    ///
+    /// The MTL data should be written to the path relative to the OBJ file.
+    pub fn to_obj(&self, mtl_path: &str) -> String {
+        format!("mtllib {}\n{}", mtl_path, self.obj)
+    }
+
+    /// Returns the MTL data.
+    pub fn mtl(&self) -> &str {
+        &self.mtl
+    }
+}
+
+/// OBJ data writer.
+#[derive(Default)]
+struct ObjWriter {
+    /// OBJ data.
+    obj: String,
+    /// Number of positions written.
+    positions: usize,
+    /// Number of texture coordinates written.
+    uvs: usize,
+    /// Number of normals written.
+    normals: usize,
+}
+
+impl ObjWriter {
+    /// Writes the mesh of the given mesh node.
+    fn mesh(
+        &mut self,
+        doc: &Document,
+        node: &SceneNode,
+        material_names: &[String],
+        world_space: bool,
+    ) -> Result<(), Error> {
+        let mesh = match node.object_id.to_object_handle(doc) {
+            Some(obj) => match obj.get_typed() {
+                TypedObjectHandle::Model(TypedModelHandle::Mesh(o)) => o.geometry()?,
+                _ => bail!("Mesh node is not a mesh model"),
+            },
+            None => bail!("Mesh model not found: {:?}", node.object_id),
+        };
+        let pvs = mesh.polygon_vertices()?;
+        let layer = mesh.layers().next();
+        let element = |f: fn(&TypedLayerElementHandle<'_>) -> bool| {
+            layer
+                .iter()
+                .flat_map(|layer| layer.layer_element_entries())
+                .filter_map(|entry| entry.typed_layer_element().ok())
+                .find(f)
+        };
+        let normals = match element(|e| matches!(e, TypedLayerElementHandle::Normal(_))) {
+            Some(TypedLayerElementHandle::Normal(h)) => Some(h.normals()?),
+            _ => None,
+        };
+        let uv = match element(|e| matches!(e, TypedLayerElementHandle::Uv(_))) {
+            Some(TypedLayerElementHandle::Uv(h)) => Some(h.uv()?),
+            _ => None,
+        };
+        let materials = match element(|e| matches!(e, TypedLayerElementHandle::Material(_))) {
+            Some(TypedLayerElementHandle::Material(h)) => Some(h.materials()?),
+            _ => None,
+        };
+
+        let geometric = Mat4::from(node.geometric_transform);
+        let transform = if world_space {
+            Mat4::from(node.global_transform) * geometric
+        } else {
+            geometric
+        };
+        let normal_matrix = transform
+            .inverse()
+            .ok_or_else(|| format_err!("Mesh transform is not invertible"))?
+            .transpose()
+            .without_translation();
+
+        let mut obj = String::new();
+        let _ = writeln!(obj, "o {}", sanitize(&node.name));
+        let mut num_positions = 0;
+        for p in pvs.raw_control_points()? {
+            let p = transform.transform_point(Vector3::from([p.x, p.y, p.z]));
+            let _ = writeln!(obj, "v {} {} {}", p.x, p.y, p.z);
+            num_positions += 1;
+        }
+
+        let mut uv_indices = HashMap::new();
+        let mut normal_indices = HashMap::new();
+        let mut faces: Vec<(Option<usize>, String)> = Vec::new();
+        let mut face = String::new();
+        let mut poly_i = 0;
+        let mut poly_start = 0;
+        for (i, pv) in pvs.raw_polygon_vertices().iter().enumerate() {
+            let pv = crate::v7400::data::mesh::PolygonVertex::new(*pv);
+            let pvi = PolygonVertexIndex::new(i);
+            let poly = PolygonIndex::new(poly_i);
+            let cpi = pv.to_u32() as usize;
+            if cpi >= num_positions {
+                bail!("Control point index out of range: {}", cpi);
+            }
+            let _ = write!(face, " {}", self.positions + cpi + 1);
+
+            let vt = match &uv {
+                Some(uv) => {
+                    let v = uv.uv_by_polygon_vertex(&pvs, pvi, poly)?;
+                    let next = self.uvs + uv_indices.len() + 1;
+                    let index = *uv_indices
+                        .entry([v.x.to_bits(), v.y.to_bits()])
+                        .or_insert_with(|| {
+                            let _ = writeln!(obj, "vt {} {}", v.x, v.y);
+                            next
+                        });
+                    Some(index)
+                }
+                None => None,
+            };
+            let vn = match &normals {
+                Some(normals) => {
+                    let n = normals.normal_by_polygon_vertex(&pvs, pvi, poly)?;
+                    let n = normalize(normal_matrix.transform_point(n));
+                    let next = self.normals + normal_indices.len() + 1;
+                    let index = *normal_indices
+                        .entry([n.x.to_bits(), n.y.to_bits(), n.z.to_bits()])
+                        .or_insert_with(|| {
+                            let _ = writeln!(obj, "vn {} {} {}", n.x, n.y, n.z);
+                            next
+                        });
+                    Some(index)
+                }
+                None => None,
+            };
+            match (vt, vn) {
+                (Some(vt), Some(vn)) => {
+                    let _ = write!(face, "/{}/{}", vt, vn);
+                }
+                (Some(vt), None) => {
+                    let _ = write!(face, "/{}", vt);
+                }
+                (None, Some(vn)) => {
+                    let _ = write!(face, "//{}", vn);
+                }
+                (None, None) => {}
+            }
+
+            if pv.is_end() {
+                let material = match &materials {
+                    Some(materials) => {
+                        let pvi = PolygonVertexIndex::new(poly_start);
+                        let index = materials.material_index_by_polygon_vertex(&pvs, pvi, poly)?;
+                        node.materials.get(index.to_u32() as usize).copied()
+                    }
+                    None => node.materials.first().copied(),
+                };
+                faces.push((material.map(|m| m.to_usize()), std::mem::take(&mut face)));
+                poly_i += 1;
+                poly_start = i + 1;
+            }
+        }
+        if !face.is_empty() {
+            warn!(
+                "Skipping the last polygon without the end marker in {:?}",
+                node.name
+            );
+        }
+
+        let mut current = None;
+        for (material, face) in faces {
+            if material != current {
+                if let Some(name) = material.and_then(|m| material_names.get(m)) {
+                    let _ = writeln!(obj, "usemtl {}", name);
+                }
+                current = material;
+            }
+            let _ = writeln!(obj, "f{}", face);
+        }
+
+        self.obj.push_str(&obj);
+        self.positions += num_positions;
+        self.uvs += uv_indices.len();
+        self.normals += normal_indices.len();
+
+        Ok(())
+    }
+}
+
+/// Returns the unique names of the materials in the scene graph.
+fn material_names(doc: &Document, graph: &SceneGraph) -> Vec<String> {
+    let mut used = HashSet::new();
+    graph
+        .materials()
+        .iter()
+        .enumerate()
+        .map(|(index, id)| {
+            let name = id
+                .to_object_handle(doc)
+                .and_then(|obj| obj.name())
+                .filter(|name| !name.is_empty())
+                .map_or_else(|| format!("material{}", index), sanitize);
+            if used.insert(name.clone()) {
+                name
+            } else {
+                let name = format!("{}_{}", name, index);
+                used.insert(name.clone());
+                name
+            }
+        })
+        .collect()
+}
+
+/// Writes the material with the given scene graph material index.
+fn write_material(mtl: &mut String, doc: &Document, graph: &SceneGraph, index: usize, name: &str) {
+    let _ = writeln!(mtl, "newmtl {}", name);
+    let obj = graph.materials()[index].to_object_handle(doc);
+    let material = match obj.map(|obj| obj.get_typed()) {
+        Some(TypedObjectHandle::Material(o)) => o,
+        _ => {
+            warn!("Material object not found: index={}", index);
+            let _ = writeln!(mtl);
+            return;
+        }
+    };
+    let props = material.properties();
+    let color = |c: Option<rgb::RGB<f64>>, factor: Option<f64>| {
+        c.map(|c| {
+            let factor = factor.unwrap_or(1.0);
+            format!("{} {} {}", c.r * factor, c.g * factor, c.b * factor)
+        })
+    };
+    let entries = [
+        (
+            "Ka",
+            color(
+                props.ambient_color_or_default().ok(),
+                props.ambient_factor_or_default().ok(),
+            ),
+        ),
+        (
+            "Kd",
+            color(
+                props.diffuse_color_or_default().ok(),
+                props.diffuse_factor_or_default().ok(),
+            ),
+        ),
+        (
+            "Ks",
+            color(
+                props.specular_or_default().ok(),
+                props.specular_factor_or_default().ok(),
+            ),
+        ),
+        (
+            "Ke",
+            color(
+                props.emissive_color_or_default().ok(),
+                props.emissive_factor_or_default().ok(),
+            ),
+        ),
+        (
+            "Ns",
+            props.shininess_or_default().ok().map(|v| v.to_string()),
+        ),
+        (
+            "d",
+            props
+                .transparency_factor_or_default()
+                .ok()
+                .map(|v| (1.0 - v).to_string()),
+        ),
+    ];
+    for (key, value) in &entries {
+        if let Some(value) = value {
+            let _ = writeln!(mtl, "{} {}", key, value);
+        }
+    }
+    let _ = writeln!(mtl, "illum 2");
+    let filename = material
+        .diffuse_texture()
+        .and_then(|texture| texture.video_clip())
+        .and_then(|clip| clip.relative_filename().ok())
+        .filter(|v| !v.is_empty());
+    if let Some(filename) = filename {
+        let _ = writeln!(mtl, "map_Kd {}", filename.replace('\\', "/"));
+    }
+    let _ = writeln!(mtl);
+}
+
+/// Returns the name with whitespaces replaced by underscores.
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_whitespace() { '_' } else { c })
+        .collect()
+}
+
+/// Returns the normalized vector, or the vector itself if it is zero.
+fn normalize(v: Vector3<f64>) -> Vector3<f64> {
+    let len = (v.x * v.x + v.y * v.y + v.z * v.z).sqrt();
+    if len > 0.0 {
+        Vector3::from([v.x / len, v.y / len, v.z / len])
+    } else {
+        v
+    }
+}
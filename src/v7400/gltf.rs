@@ -0,0 +1,270 @@
+//! glTF 2.0 export.
+//!
+//! [`Exporter`] converts the scene of a document into glTF 2.0 data:
+//!
+//! * each model becomes a node, with the local transform decomposed into
+//!   translation, rotation, and scale,
+//! * each mesh model becomes a mesh with welded vertex buffers and one
+//!   primitive per material, with the geometric transform baked into the
+//!   vertices,
+//! * materials are converted into metallic-roughness materials guessed from
+//!   the Lambert / Phong parameters,
+//! * skin deformers become skins (up to four influences per vertex),
+//! * animation stacks are baked into sampled translation, rotation, and scale
+//!   channels.
+//!
+//! Cameras, lights, and blend shapes are not exported.
+//!
+//! The result can be written as a binary `.glb` file with
+//! [`GltfExport::to_glb()`], or as a `.gltf` file with a separate buffer file
+//! with [`GltfExport::to_gltf()`].
+//!
+//! The glTF document is built with the [`gltf-json`] crate.
+//! Non-finite values (such as NaN in node transforms or vertex positions)
+//! cannot be represented in glTF JSON, and make the export fail.
+//!
+//! [`gltf-json`]: https://crates.io/crates/gltf-json
+//!
+//! ```
+//! # #[cfg(feature = "edit")]
+//! # fn main() -> Result<(), anyhow::Error> {
+//! use fbxcel_dom::v7400::{
+//!     data::material::ShadingModel,
+//!     edit::{DocumentBuilder, ModelKind},
+//!     gltf::Exporter,
+//! };
+//! use gltf_json::validation::Validate;
+//!
+//! let mut builder = DocumentBuilder::new();
+//! let root = builder.add_model("Root", ModelKind::Null, None);
+//! let quad = builder.add_model("Quad", ModelKind::Mesh, Some(root));
+//! let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+//! builder.add_mesh("Quad", &positions, &[&[0, 1, 2, 3]], Some(quad));
+//! builder.add_material("Red", ShadingModel::Lambert, [1.0, 0.0, 0.0], Some(quad))?;
+//! let doc = builder.build()?;
+//!
+//! let json = Exporter::new().export(&doc)?.to_gltf("scene.bin")?;
+//! let gltf = gltf_json::Root::from_str(&json)?;
+//! let mut errors = Vec::new();
+//! gltf.validate(&gltf, gltf_json::Path::new, &mut |path, error| {
+//!     errors.push(format!("{}: {:?}", path(), error))
+//! });
+//! assert!(errors.is_empty(), "invalid glTF: {:?}", errors);
+//! assert_eq!(gltf.meshes.len(), 1);
+//! assert_eq!(gltf.materials.len(), 1);
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "edit"))]
+//! # fn main() {}
+//! ```
+//!
+//! Animations are sampled at the given frame rate, and the binary glTF
+//! starts with the GLB header:
+//!
+//! ```
+//! # #[cfg(feature = "edit")]
+//! # fn main() -> Result<(), anyhow::Error> {
+//! use fbxcel_dom::v7400::{
+//!     edit::{CurveBuilder, DocumentBuilder, Interpolation, ModelKind, KTIME_PER_SECOND},
+//!     gltf::Exporter,
+//! };
+//!
+//! let mut builder = DocumentBuilder::new();
+//! let model = builder.add_model("Mover", ModelKind::Null, None);
+//! let stack = builder.add_animation_stack("Take", 0, KTIME_PER_SECOND)?;
+//! let layer = builder.add_animation_layer("Base", stack);
+//! let curve = CurveBuilder::new()
+//!     .key_seconds(0.0, 0.0, Interpolation::Linear)
+//!     .key_seconds(1.0, 10.0, Interpolation::Linear);
+//! builder.add_animation_curve_node(layer, model, "Lcl Translation", &[("X", &curve)])?;
+//! let doc = builder.build()?;
+//!
+//! let glb = Exporter::new().sample_rate(60.0).export(&doc)?.to_glb()?;
+//! let u32_at = |offset: usize| {
+//!     let mut bytes = [0; 4];
+//!     bytes.copy_from_slice(&glb[offset..(offset + 4)]);
+//!     u32::from_le_bytes(bytes) as usize
+//! };
+//! assert_eq!(&glb[..4], b"glTF");
+//! assert_eq!(u32_at(4), 2);
+//! assert_eq!(u32_at(8), glb.len());
+//! assert_eq!(&glb[16..20], b"JSON");
+//! let json_len = u32_at(12);
+//! let gltf = gltf_json::Root::from_slice(&glb[20..(20 + json_len)])?;
+//!
+//! // One second at 60 frames per second, including both ends.
+//! let input = gltf.animations[0].samplers[0].input.value();
+//! assert_eq!(gltf.accessors[input].count, 61);
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "edit"))]
+//! # fn main() {}
+//! ```
+
+use anyhow::{bail, Error};
+use gltf_json::{Buffer, Root};
+
+use crate::v7400::Document;
+
+use self::builder::{to_u32, Builder};
+
+mod anim;
+mod builder;
+mod material;
+mod mesh;
+
+/// Magic of GLB files (`glTF`).
+const GLB_MAGIC: u32 = 0x4654_6C67;
+/// Chunk type of GLB JSON chunks.
+const GLB_CHUNK_JSON: u32 = 0x4E4F_534A;
+/// Chunk type of GLB binary chunks.
+const GLB_CHUNK_BIN: u32 = 0x004E_4942;
+
+/// glTF 2.0 exporter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exporter {
+    /// Frames per second for baking animations.
+    sample_rate: f64,
+    /// Whether to convert the axis system and unit.
+    convert_axes: bool,
+}
+
+impl Exporter {
+    /// Creates a new exporter with the default options.
+    ///
+    /// Animations are sampled at 30 frames per second, and the scene is
+    /// converted into the glTF axis system.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the frames per second for baking animations.
+    pub fn sample_rate(mut self, fps: f64) -> Self {
+        self.sample_rate = fps;
+        self
+    }
+
+    /// Sets whether to convert the scene into the glTF axis system (Y-up,
+    /// right-handed, meters).
+    ///
+    /// If enabled, a root node with the conversion matrix is added using the
+    /// axis system and the unit in `GlobalSettings`.
+    pub fn convert_axes(mut self, convert: bool) -> Self {
+        self.convert_axes = convert;
+        self
+    }
+
+    /// Exports the scene of the given document.
+    ///
+    /// Returns an error if node transforms, accessor bounds, or material
+    /// parameters are not finite.
+    ///
+    /// ```
+    /// # #[cfg(feature = "edit")]
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// use fbxcel_dom::v7400::{
+    ///     edit::{DocumentBuilder, ModelKind},
+    ///     gltf::Exporter,
+    /// };
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let model = builder.add_model("Broken", ModelKind::Null, None);
+    /// builder.set_local_transform(model, [f64::NAN, 0.0, 0.0], [0.0; 3], [1.0; 3])?;
+    /// let doc = builder.build()?;
+    ///
+    /// assert!(Exporter::new().export(&doc).is_err());
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "edit"))]
+    /// # fn main() {}
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "gltf_export", skip_all)
+    )]
+    pub fn export(&self, doc: &Document) -> Result<GltfExport, Error> {
+        if !(self.sample_rate.is_finite() && self.sample_rate > 0.0) {
+            bail!("Invalid animation sample rate: {}", self.sample_rate);
+        }
+        let (root, buffer) = Builder::new(doc, self)?.build()?;
+
+        Ok(GltfExport { root, buffer })
+    }
+}
+
+impl Default for Exporter {
+    fn default() -> Self {
+        Self {
+            sample_rate: 30.0,
+            convert_axes: true,
+        }
+    }
+}
+
+/// Exported glTF data.
+#[derive(Debug, Clone)]
+pub struct GltfExport {
+    /// glTF document without `buffers`.
+    root: Root,
+    /// Binary buffer.
+    buffer: Vec<u8>,
+}
+
+impl GltfExport {
+    /// Returns the binary buffer.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Returns the glTF JSON referring to the buffer with the given URI.
+    ///
+    /// The buffer should be written to the URI relative to the JSON file.
+    pub fn to_gltf(&self, bin_uri: &str) -> Result<String, Error> {
+        let json = self.with_buffer(Some(bin_uri))?.to_string()?;
+        Ok(json)
+    }
+
+    /// Returns the binary glTF (GLB) data.
+    pub fn to_glb(&self) -> Result<Vec<u8>, Error> {
+        let mut json = self.with_buffer(None)?.to_vec()?;
+        json.resize(padded_len(json.len()), b' ');
+        let mut bin = self.buffer.clone();
+        bin.resize(padded_len(bin.len()), 0);
+
+        let total_len = 12 + 8 + json.len() + if bin.is_empty() { 0 } else { 8 + bin.len() };
+        let mut glb = Vec::with_capacity(total_len);
+        for v in [GLB_MAGIC, 2, to_u32(total_len)?] {
+            glb.extend_from_slice(&v.to_le_bytes());
+        }
+        glb.extend_from_slice(&to_u32(json.len())?.to_le_bytes());
+        glb.extend_from_slice(&GLB_CHUNK_JSON.to_le_bytes());
+        glb.extend_from_slice(&json);
+        if !bin.is_empty() {
+            glb.extend_from_slice(&to_u32(bin.len())?.to_le_bytes());
+            glb.extend_from_slice(&GLB_CHUNK_BIN.to_le_bytes());
+            glb.extend_from_slice(&bin);
+        }
+
+        Ok(glb)
+    }
+
+    /// Returns the glTF document with the buffer of the given URI.
+    fn with_buffer(&self, uri: Option<&str>) -> Result<Root, Error> {
+        let mut root = self.root.clone();
+        if !self.buffer.is_empty() {
+            root.buffers.push(Buffer {
+                byte_length: to_u32(self.buffer.len())?,
+                name: None,
+                uri: uri.map(ToOwned::to_owned),
+                extensions: None,
+                extras: Default::default(),
+            });
+        }
+        Ok(root)
+    }
+}
+
+/// Returns the length padded to 4-byte alignment.
+fn padded_len(len: usize) -> usize {
+    (len + 3) & !3
+}
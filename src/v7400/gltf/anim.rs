@@ -0,0 +1,205 @@
+//! Animation export.
+
+use std::collections::BTreeMap;
+
+use anyhow::Error;
+use gltf_json::{
+    accessor::Type,
+    animation::{Channel, Interpolation, Property, Sampler, Target},
+    validation::Checked,
+    Animation,
+};
+use log::{debug, warn};
+use mint::Vector3;
+
+use crate::v7400::{
+    gltf::builder::{decompose, gltf_index, Builder},
+    object::animation::{AnimationCurveHandle, AnimationStackHandle, KTIME_PER_SECOND},
+    scene::{sample_times, NodeIndex},
+};
+
+/// Animation curves of a node, indexed by `[property][axis]`.
+///
+/// Properties are translation, rotation, and scaling.
+type NodeCurves<'a> = [[Option<AnimationCurveHandle<'a>>; 3]; 3];
+
+impl<'a> Builder<'a> {
+    /// Adds animations for all animation stacks.
+    pub(super) fn animations(&mut self) -> Result<(), Error> {
+        let doc = self.doc;
+        for stack in doc.anim_stacks() {
+            if let Err(e) = self.animation(&stack) {
+                warn!(
+                    "Failed to export animation stack {:?}: {:#}",
+                    stack.name(),
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds the animation for the given animation stack.
+    ///
+    /// Only the first (base) animation layer of the stack is exported, and
+    /// the local transforms of the animated nodes are sampled at the sample
+    /// rate over the range of the keys.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "bake_animation", skip_all)
+    )]
+    fn animation(&mut self, stack: &AnimationStackHandle<'a>) -> Result<(), Error> {
+        let layer = match stack.layers().next() {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+
+        let mut node_curves: BTreeMap<NodeIndex, NodeCurves<'a>> = BTreeMap::new();
+        for curve_node in layer.curve_nodes() {
+            let (target, property) = match curve_node.target() {
+                Some(v) => v,
+                None => continue,
+            };
+            let property = match property {
+                "Lcl Translation" => 0,
+                "Lcl Rotation" => 1,
+                "Lcl Scaling" => 2,
+                _ => {
+                    debug!("Skipping animated property {:?}", property);
+                    continue;
+                }
+            };
+            let node = match self.graph.node_index(target.object_id()) {
+                Some(v) => v,
+                None => continue,
+            };
+            for (curve, channel) in curve_node.curves() {
+                let axis = match channel {
+                    "d|X" => 0,
+                    "d|Y" => 1,
+                    "d|Z" => 2,
+                    _ => continue,
+                };
+                node_curves.entry(node).or_default()[property][axis] = Some(curve);
+            }
+        }
+
+        let mut range: Option<(i64, i64)> = None;
+        for curve in node_curves.values().flatten().flatten().flatten() {
+            let times = curve.key_times()?;
+            if let (Some(&first), Some(&last)) = (times.first(), times.last()) {
+                range = Some(match range {
+                    Some((start, end)) => (start.min(first), end.max(last)),
+                    None => (first, last),
+                });
+            }
+        }
+        let (start, end) = match range {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+
+        let times = sample_times(start, end, self.options.sample_rate)?;
+        let seconds: Vec<f32> = times
+            .iter()
+            .map(|&t| ((t as f64 - start as f64) / KTIME_PER_SECOND as f64) as f32)
+            .collect();
+        let input = self.push_f32(&seconds, Type::Scalar, None, true)?;
+
+        let mut samplers = Vec::new();
+        let mut channels = Vec::new();
+        for (&node, curves) in &node_curves {
+            let base = self.graph.node(node).local_transform;
+            let mut translations = Vec::with_capacity(times.len() * 3);
+            let mut rotations: Vec<f32> = Vec::with_capacity(times.len() * 4);
+            let mut scales = Vec::with_capacity(times.len() * 3);
+            for &time in &times {
+                let mut local = base;
+                for (property, axes) in curves.iter().enumerate() {
+                    let value = match property {
+                        0 => &mut local.translation,
+                        1 => &mut local.rotation,
+                        _ => &mut local.scaling,
+                    };
+                    for (axis, curve) in axes.iter().enumerate() {
+                        let curve = match curve {
+                            Some(v) => v,
+                            None => continue,
+                        };
+                        if let Some(v) = curve.evaluate(time)? {
+                            set_axis(value, axis, f64::from(v));
+                        }
+                    }
+                }
+
+                let (t, mut r, s) = decompose(local.matrix());
+                // Keep the quaternions on the same hemisphere for
+                // interpolation.
+                if let Some(prev) = rotations.rchunks_exact(4).next() {
+                    let dot = f64::from(prev[0]) * r.v.x
+                        + f64::from(prev[1]) * r.v.y
+                        + f64::from(prev[2]) * r.v.z
+                        + f64::from(prev[3]) * r.s;
+                    if dot < 0.0 {
+                        r.v = Vector3::from([-r.v.x, -r.v.y, -r.v.z]);
+                        r.s = -r.s;
+                    }
+                }
+                translations.extend_from_slice(&[t.x as f32, t.y as f32, t.z as f32]);
+                rotations.extend_from_slice(&[
+                    r.v.x as f32,
+                    r.v.y as f32,
+                    r.v.z as f32,
+                    r.s as f32,
+                ]);
+                scales.extend_from_slice(&[s.x as f32, s.y as f32, s.z as f32]);
+            }
+
+            for (path, values, type_) in [
+                (Property::Translation, translations, Type::Vec3),
+                (Property::Rotation, rotations, Type::Vec4),
+                (Property::Scale, scales, Type::Vec3),
+            ] {
+                let output = self.push_f32(&values, type_, None, false)?;
+                samplers.push(Sampler {
+                    extensions: None,
+                    extras: Default::default(),
+                    input,
+                    interpolation: Checked::Valid(Interpolation::Linear),
+                    output,
+                });
+                channels.push(Channel {
+                    sampler: gltf_index(samplers.len() - 1)?,
+                    target: Target {
+                        extensions: None,
+                        extras: Default::default(),
+                        node: gltf_index(node.to_usize())?,
+                        path: Checked::Valid(path),
+                    },
+                    extensions: None,
+                    extras: Default::default(),
+                });
+            }
+        }
+
+        self.animations.push(Animation {
+            extensions: None,
+            extras: Default::default(),
+            channels,
+            name: stack.name().map(ToOwned::to_owned),
+            samplers,
+        });
+
+        Ok(())
+    }
+}
+
+/// Sets the component of the vector.
+fn set_axis(v: &mut Vector3<f64>, axis: usize, value: f64) {
+    match axis {
+        0 => v.x = value,
+        1 => v.y = value,
+        _ => v.z = value,
+    }
+}
@@ -0,0 +1,443 @@
+//! glTF document builder.
+
+use std::{collections::HashMap, convert::TryFrom};
+
+use anyhow::{bail, format_err, Context, Error};
+use gltf_json::{
+    accessor::{self, Accessor, ComponentType, GenericComponentType},
+    buffer, scene,
+    validation::Checked,
+    Animation, Image, Index, Material, Mesh, Root, Skin, Texture,
+};
+use log::warn;
+use mint::{Quaternion, Vector3};
+
+use crate::v7400::{
+    data::{
+        mesh::LayerElementCache,
+        transform::{math::Mat4, AxisSystem},
+    },
+    gltf::Exporter,
+    object::ObjectId,
+    scene::{SceneGraph, SceneNodeKind},
+    Document,
+};
+
+/// glTF document builder.
+pub(super) struct Builder<'a> {
+    /// Source document.
+    pub(super) doc: &'a Document,
+    /// Export options.
+    pub(super) options: &'a Exporter,
+    /// Scene graph of the document.
+    pub(super) graph: SceneGraph,
+    /// Binary buffer.
+    buffer: Vec<u8>,
+    /// `bufferViews`.
+    buffer_views: Vec<buffer::View>,
+    /// `accessors`.
+    accessors: Vec<Accessor>,
+    /// `meshes`.
+    pub(super) meshes: Vec<Mesh>,
+    /// `skins`.
+    pub(super) skins: Vec<Skin>,
+    /// `materials`.
+    pub(super) materials: Vec<Material>,
+    /// `textures`.
+    pub(super) textures: Vec<Texture>,
+    /// `images`.
+    pub(super) images: Vec<Image>,
+    /// `samplers`.
+    pub(super) samplers: Vec<gltf_json::texture::Sampler>,
+    /// `animations`.
+    pub(super) animations: Vec<Animation>,
+    /// Texture indices by texture object ID.
+    pub(super) texture_indices: HashMap<ObjectId, Option<Index<Texture>>>,
+    /// Resolved layer elements of meshes.
+    pub(super) layer_elements: LayerElementCache,
+}
+
+impl<'a> Builder<'a> {
+    /// Creates a new builder.
+    pub(super) fn new(doc: &'a Document, options: &'a Exporter) -> Result<Self, Error> {
+        Ok(Self {
+            doc,
+            options,
+            graph: SceneGraph::from_document(doc)?,
+            buffer: Vec::new(),
+            buffer_views: Vec::new(),
+            accessors: Vec::new(),
+            meshes: Vec::new(),
+            skins: Vec::new(),
+            materials: Vec::new(),
+            textures: Vec::new(),
+            images: Vec::new(),
+            samplers: Vec::new(),
+            animations: Vec::new(),
+            texture_indices: HashMap::new(),
+            layer_elements: LayerElementCache::new(),
+        })
+    }
+
+    /// Builds the glTF document (without `buffers`) and the binary buffer.
+    pub(super) fn build(mut self) -> Result<(Root, Vec<u8>), Error> {
+        self.materials = (0..self.graph.materials().len())
+            .map(|i| self.material(i))
+            .collect::<Result<_, _>>()?;
+
+        let mut nodes = Vec::with_capacity(self.graph.nodes().len() + 1);
+        for index in 0..self.graph.nodes().len() {
+            let node = &self.graph.nodes()[index];
+            let name = node.name.clone();
+            let (translation, rotation, scale) = decompose(node.local_transform.matrix());
+            let children = node
+                .children
+                .iter()
+                .map(|c| gltf_index(c.to_usize()))
+                .collect::<Result<Vec<_>, _>>()?;
+            let has_mesh = node.kind == SceneNodeKind::Mesh && node.geometry.is_some();
+            let invalid = || format!("Invalid transform of node {:?}", name);
+            let mut json = scene::Node {
+                camera: None,
+                children: Some(children).filter(|v| !v.is_empty()),
+                extensions: None,
+                extras: Default::default(),
+                matrix: None,
+                mesh: None,
+                name: Some(name.clone()),
+                rotation: Some(scene::UnitQuaternion(quat(rotation).with_context(invalid)?)),
+                scale: Some(vec3(scale).with_context(invalid)?),
+                translation: Some(vec3(translation).with_context(invalid)?),
+                skin: None,
+                weights: None,
+            };
+            if has_mesh {
+                match self.mesh(index) {
+                    Ok(Some((mesh, skin))) => {
+                        json.mesh = Some(mesh);
+                        json.skin = skin;
+                    }
+                    Ok(None) => {}
+                    Err(e) => warn!("Failed to export mesh of node {:?}: {:#}", name, e),
+                }
+            }
+            nodes.push(json);
+        }
+
+        let mut scene_nodes = self
+            .graph
+            .roots()
+            .iter()
+            .map(|r| gltf_index(r.to_usize()))
+            .collect::<Result<Vec<_>, _>>()?;
+        if self.options.convert_axes {
+            let matrix = axis_conversion(self.doc);
+            if matrix != Mat4::IDENTITY {
+                let mut columns = [0.0; 16];
+                for (dest, &v) in columns.iter_mut().zip(matrix.0.iter().flatten()) {
+                    *dest = v;
+                }
+                nodes.push(scene::Node {
+                    camera: None,
+                    children: Some(scene_nodes),
+                    extensions: None,
+                    extras: Default::default(),
+                    matrix: Some(finite(columns).context("Invalid axis conversion matrix")?),
+                    mesh: None,
+                    name: Some("AxisConversion".to_owned()),
+                    rotation: None,
+                    scale: None,
+                    translation: None,
+                    skin: None,
+                    weights: None,
+                });
+                scene_nodes = vec![gltf_index(nodes.len() - 1)?];
+            }
+        }
+
+        self.animations()?;
+
+        let root = Root {
+            asset: gltf_json::Asset {
+                generator: Some(concat!("fbxcel-dom ", env!("CARGO_PKG_VERSION")).to_owned()),
+                ..Default::default()
+            },
+            scene: Some(Index::new(0)),
+            scenes: vec![scene::Scene {
+                extensions: None,
+                extras: Default::default(),
+                name: None,
+                nodes: scene_nodes,
+            }],
+            nodes,
+            meshes: self.meshes,
+            skins: self.skins,
+            materials: self.materials,
+            textures: self.textures,
+            images: self.images,
+            samplers: self.samplers,
+            animations: self.animations,
+            accessors: self.accessors,
+            buffer_views: self.buffer_views,
+            ..Default::default()
+        };
+
+        Ok((root, self.buffer))
+    }
+
+    /// Appends the data to the buffer and adds a buffer view.
+    ///
+    /// Returns the buffer view index.
+    pub(super) fn push_view(
+        &mut self,
+        data: &[u8],
+        target: Option<buffer::Target>,
+    ) -> Result<Index<buffer::View>, Error> {
+        let offset = super::padded_len(self.buffer.len());
+        to_u32(offset + data.len()).context("Too large glTF buffer")?;
+        let view = buffer::View {
+            buffer: Index::new(0),
+            byte_length: to_u32(data.len())?,
+            byte_offset: Some(to_u32(offset)?),
+            byte_stride: None,
+            name: None,
+            target: target.map(Checked::Valid),
+            extensions: None,
+            extras: Default::default(),
+        };
+        self.buffer.resize(offset, 0);
+        self.buffer.extend_from_slice(data);
+        self.buffer_views.push(view);
+        gltf_index(self.buffer_views.len() - 1)
+    }
+
+    /// Adds an `f32` accessor with the given type (such as `VEC3`).
+    ///
+    /// `min` and `max` are written if `with_bounds` is true, and an error is
+    /// returned if the values are not finite.
+    ///
+    /// Returns the accessor index.
+    pub(super) fn push_f32(
+        &mut self,
+        values: &[f32],
+        type_: accessor::Type,
+        target: Option<buffer::Target>,
+        with_bounds: bool,
+    ) -> Result<Index<Accessor>, Error> {
+        let components = type_.multiplicity();
+        let bounds = if with_bounds {
+            if let Some(v) = values.iter().find(|v| !v.is_finite()) {
+                bail!("Non-finite value in {:?} accessor: {}", type_, v);
+            }
+            if values.is_empty() {
+                bail!("Bounds of an empty {:?} accessor are undefined", type_);
+            }
+            let mut min = vec![f32::INFINITY; components];
+            let mut max = vec![f32::NEG_INFINITY; components];
+            for element in values.chunks_exact(components) {
+                for (i, &v) in element.iter().enumerate() {
+                    min[i] = min[i].min(v);
+                    max[i] = max[i].max(v);
+                }
+            }
+            Some((min, max))
+        } else {
+            None
+        };
+        let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let view = self.push_view(&bytes, target)?;
+        let (min, max) = match bounds {
+            Some((min, max)) => (Some(min.into()), Some(max.into())),
+            None => (None, None),
+        };
+        self.push_accessor(
+            view,
+            ComponentType::F32,
+            values.len() / components,
+            type_,
+            min,
+            max,
+        )
+    }
+
+    /// Adds a `u32` scalar accessor for vertex indices.
+    ///
+    /// Returns the accessor index.
+    pub(super) fn push_indices(&mut self, values: &[u32]) -> Result<Index<Accessor>, Error> {
+        let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let view = self.push_view(&bytes, Some(buffer::Target::ElementArrayBuffer))?;
+        self.push_accessor(
+            view,
+            ComponentType::U32,
+            values.len(),
+            accessor::Type::Scalar,
+            None,
+            None,
+        )
+    }
+
+    /// Adds a `u16` `VEC4` accessor for joint indices.
+    ///
+    /// Returns the accessor index.
+    pub(super) fn push_joints(&mut self, values: &[[u16; 4]]) -> Result<Index<Accessor>, Error> {
+        let bytes: Vec<u8> = values
+            .iter()
+            .flatten()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let view = self.push_view(&bytes, Some(buffer::Target::ArrayBuffer))?;
+        self.push_accessor(
+            view,
+            ComponentType::U16,
+            values.len(),
+            accessor::Type::Vec4,
+            None,
+            None,
+        )
+    }
+
+    /// Adds an accessor for the whole buffer view.
+    ///
+    /// Returns the accessor index.
+    fn push_accessor(
+        &mut self,
+        view: Index<buffer::View>,
+        component_type: ComponentType,
+        count: usize,
+        type_: accessor::Type,
+        min: Option<gltf_json::Value>,
+        max: Option<gltf_json::Value>,
+    ) -> Result<Index<Accessor>, Error> {
+        self.accessors.push(Accessor {
+            buffer_view: Some(view),
+            byte_offset: 0,
+            count: to_u32(count)?,
+            component_type: Checked::Valid(GenericComponentType(component_type)),
+            extensions: None,
+            extras: Default::default(),
+            type_: Checked::Valid(type_),
+            min,
+            max,
+            name: None,
+            normalized: false,
+            sparse: None,
+        });
+        gltf_index(self.accessors.len() - 1)
+    }
+}
+
+/// Converts the array index into a glTF index.
+pub(super) fn gltf_index<T>(index: usize) -> Result<Index<T>, Error> {
+    to_u32(index).map(Index::new)
+}
+
+/// Converts the index or the length into `u32` used by glTF.
+pub(super) fn to_u32(v: usize) -> Result<u32, Error> {
+    u32::try_from(v).map_err(|_| format_err!("Too large index or length for glTF: {}", v))
+}
+
+/// Converts the values into `f32`, and returns an error if any of them is
+/// not finite.
+pub(super) fn finite<const N: usize>(values: [f64; N]) -> Result<[f32; N], Error> {
+    let mut result = [0.0; N];
+    for (dest, &v) in result.iter_mut().zip(&values) {
+        *dest = v as f32;
+        if !dest.is_finite() {
+            bail!("Non-finite value: {:?}", values);
+        }
+    }
+    Ok(result)
+}
+
+/// Returns the matrix converting the axis system and unit of the document
+/// into the glTF ones (Y-up, right-handed, meters).
+fn axis_conversion(doc: &Document) -> Mat4 {
+    let axes = AxisSystem::from_tree(doc.tree());
+    // `UnitScaleFactor` is centimeters per unit.
+    let scale = if axes.unit_scale > 0.0 {
+        axes.unit_scale / 100.0
+    } else {
+        0.01
+    };
+    let rotation = axes.matrix().unwrap_or_else(|| {
+        warn!("Invalid axis system in `GlobalSettings`");
+        Mat4::IDENTITY
+    });
+    let m = rotation.transpose() * Mat4::from_scaling(Vector3::from([scale; 3]));
+    if m.determinant() < 0.0 {
+        warn!("Left-handed axis system is exported with a mirroring root node");
+    }
+
+    m
+}
+
+/// Decomposes the affine transform into translation, rotation, and scale.
+///
+/// Shear is not supported, and negative scaling is assigned to the X axis.
+pub(super) fn decompose(m: Mat4) -> (Vector3<f64>, Quaternion<f64>, Vector3<f64>) {
+    (
+        m.translation(),
+        rotation_quaternion(&m.rotation_part()),
+        m.scaling(),
+    )
+}
+
+/// Returns the unit quaternion of the pure rotation matrix.
+fn rotation_quaternion(m: &Mat4) -> Quaternion<f64> {
+    // Row-major accessor.
+    let r = |row: usize, col: usize| m.0[col][row];
+    let trace = r(0, 0) + r(1, 1) + r(2, 2);
+    let (x, y, z, w) = if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+        (
+            (r(2, 1) - r(1, 2)) / s,
+            (r(0, 2) - r(2, 0)) / s,
+            (r(1, 0) - r(0, 1)) / s,
+            0.25 * s,
+        )
+    } else if r(0, 0) > r(1, 1) && r(0, 0) > r(2, 2) {
+        let s = (1.0 + r(0, 0) - r(1, 1) - r(2, 2)).sqrt() * 2.0;
+        (
+            0.25 * s,
+            (r(0, 1) + r(1, 0)) / s,
+            (r(0, 2) + r(2, 0)) / s,
+            (r(2, 1) - r(1, 2)) / s,
+        )
+    } else if r(1, 1) > r(2, 2) {
+        let s = (1.0 + r(1, 1) - r(0, 0) - r(2, 2)).sqrt() * 2.0;
+        (
+            (r(0, 1) + r(1, 0)) / s,
+            0.25 * s,
+            (r(1, 2) + r(2, 1)) / s,
+            (r(0, 2) - r(2, 0)) / s,
+        )
+    } else {
+        let s = (1.0 + r(2, 2) - r(0, 0) - r(1, 1)).sqrt() * 2.0;
+        (
+            (r(0, 2) + r(2, 0)) / s,
+            (r(1, 2) + r(2, 1)) / s,
+            0.25 * s,
+            (r(1, 0) - r(0, 1)) / s,
+        )
+    };
+    let len = (x * x + y * y + z * z + w * w).sqrt();
+
+    Quaternion {
+        v: Vector3::from([x / len, y / len, z / len]),
+        s: w / len,
+    }
+}
+
+/// Converts the vector into a glTF array.
+///
+/// Returns an error if the vector is not finite.
+fn vec3(v: Vector3<f64>) -> Result<[f32; 3], Error> {
+    finite([v.x, v.y, v.z])
+}
+
+/// Converts the quaternion into a glTF array in glTF order (`x, y, z, w`).
+///
+/// Returns an error if the quaternion is not finite.
+fn quat(q: Quaternion<f64>) -> Result<[f32; 4], Error> {
+    finite([q.v.x, q.v.y, q.v.z, q.s])
+}
@@ -0,0 +1,177 @@
+//! Material and texture export.
+
+use anyhow::{Context, Error};
+use gltf_json::{
+    image::MimeType,
+    material::{
+        AlphaMode, EmissiveFactor, PbrBaseColorFactor, PbrMetallicRoughness, StrengthFactor,
+    },
+    texture::{self, WrappingMode},
+    validation::Checked,
+    Image, Index, Material, Texture,
+};
+use log::warn;
+
+use crate::v7400::{
+    data::{material::pbr::PbrMaterial, texture::WrapMode},
+    gltf::builder::{finite, gltf_index, Builder},
+    object::{texture::TextureHandle, TypedObjectHandle},
+    Warning,
+};
+
+impl<'a> Builder<'a> {
+    /// Returns the material with the given scene graph material index.
+    ///
+    /// Lambert / Phong parameters are converted by [`PbrMaterial`].
+    ///
+    /// Returns an error if the converted values are not finite.
+    pub(super) fn material(&mut self, index: usize) -> Result<Material, Error> {
+        let obj = self.graph.materials()[index].to_object_handle(self.doc);
+        let material = match obj.map(|obj| obj.get_typed()) {
+            Some(TypedObjectHandle::Material(o)) => o,
+            _ => {
+                warn!("Material object not found: index={}", index);
+                return Ok(Material::default());
+            }
+        };
+        let pbr = PbrMaterial::from_material(&material);
+
+        let invalid = || format!("Invalid material {:?}", material.name().unwrap_or_default());
+        let base_color = finite(pbr.base_color).with_context(invalid)?;
+        let [metallic, roughness] = finite([pbr.metallic, pbr.roughness]).with_context(invalid)?;
+        let emissive = finite(pbr.emissive).with_context(invalid)?;
+        let base_color_texture = match material.diffuse_texture() {
+            Some(texture) => self.texture(&texture)?.map(|index| texture::Info {
+                index,
+                tex_coord: 0,
+                extensions: None,
+                extras: Default::default(),
+            }),
+            None => None,
+        };
+        let alpha_mode = if base_color[3] < 1.0 || material.transparent_texture().is_some() {
+            AlphaMode::Blend
+        } else {
+            AlphaMode::Opaque
+        };
+
+        Ok(Material {
+            alpha_mode: Checked::Valid(alpha_mode),
+            name: material.name().map(ToOwned::to_owned),
+            pbr_metallic_roughness: PbrMetallicRoughness {
+                base_color_factor: PbrBaseColorFactor(base_color),
+                base_color_texture,
+                metallic_factor: StrengthFactor(metallic),
+                roughness_factor: StrengthFactor(roughness),
+                ..Default::default()
+            },
+            emissive_factor: EmissiveFactor(emissive),
+            ..Default::default()
+        })
+    }
+
+    /// Adds the texture (and the image and the sampler).
+    ///
+    /// Embedded content is stored in the buffer, and external files are
+    /// referred by the relative file name.
+    ///
+    /// Returns `Ok(None)` if the texture has no usable image.
+    fn texture(&mut self, texture: &TextureHandle<'_>) -> Result<Option<Index<Texture>>, Error> {
+        if let Some(&index) = self.texture_indices.get(&texture.object_id()) {
+            return Ok(index);
+        }
+        let index = self.texture_impl(texture)?;
+        self.texture_indices.insert(texture.object_id(), index);
+        Ok(index)
+    }
+
+    /// Adds the texture without caching.
+    fn texture_impl(
+        &mut self,
+        texture: &TextureHandle<'_>,
+    ) -> Result<Option<Index<Texture>>, Error> {
+        let clip = match texture.video_clip() {
+            Some(v) => v,
+            None => {
+                self.doc.warn(Warning::ExtractionFailed {
+                    object: Some(texture.object_id()),
+                    message: "Texture has no video clip".to_owned(),
+                });
+                return Ok(None);
+            }
+        };
+        let filename = clip.relative_filename().ok().filter(|v| !v.is_empty());
+        let content = clip.content().filter(|v| !v.is_empty());
+
+        let mut image = Image {
+            buffer_view: None,
+            mime_type: None,
+            name: clip.name().map(ToOwned::to_owned),
+            uri: None,
+            extensions: None,
+            extras: Default::default(),
+        };
+        match (content, content.and_then(mime_type)) {
+            (Some(content), Some(mime_type)) => {
+                image.buffer_view = Some(self.push_view(content, None)?);
+                image.mime_type = Some(MimeType(mime_type.to_owned()));
+            }
+            _ => match filename {
+                Some(filename) => image.uri = Some(encode_uri(&filename.replace('\\', "/"))),
+                None => {
+                    self.doc.warn(Warning::ExtractionFailed {
+                        object: Some(texture.object_id()),
+                        message: "Texture has no usable image".to_owned(),
+                    });
+                    return Ok(None);
+                }
+            },
+        }
+        self.images.push(image);
+
+        let props = texture.properties();
+        let wrap = |mode: Result<WrapMode, _>| match mode {
+            Ok(WrapMode::Clamp) => Checked::Valid(WrappingMode::ClampToEdge),
+            _ => Checked::Valid(WrappingMode::Repeat),
+        };
+        self.samplers.push(texture::Sampler {
+            wrap_s: wrap(props.wrap_mode_u_or_default()),
+            wrap_t: wrap(props.wrap_mode_v_or_default()),
+            ..Default::default()
+        });
+
+        self.textures.push(Texture {
+            name: None,
+            sampler: Some(gltf_index(self.samplers.len() - 1)?),
+            source: gltf_index(self.images.len() - 1)?,
+            extensions: None,
+            extras: Default::default(),
+        });
+        gltf_index(self.textures.len() - 1).map(Some)
+    }
+}
+
+/// Returns the MIME type of the image data supported by glTF.
+fn mime_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if data.starts_with(b"\xff\xd8\xff") {
+        Some("image/jpeg")
+    } else {
+        None
+    }
+}
+
+/// Percent-encodes the characters not allowed in URI paths.
+fn encode_uri(path: &str) -> String {
+    let mut uri = String::with_capacity(path.len());
+    for b in path.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(char::from(b))
+            }
+            b => uri.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    uri
+}
@@ -0,0 +1,375 @@
+//! Mesh and skin export.
+
+use std::{collections::HashMap, convert::TryFrom};
+
+use anyhow::{bail, format_err, Error};
+use gltf_json::{
+    accessor::Type,
+    buffer::Target,
+    mesh::{self, Semantic},
+    validation::Checked,
+    Index, Material, Mesh,
+};
+use log::warn;
+use mint::Vector3;
+
+use crate::v7400::{
+    data::{
+        mesh::{layer::TypedLayerElementHandle, PolygonVertexIndex, PolygonVertices},
+        transform::math::Mat4,
+    },
+    gltf::builder::{gltf_index, Builder},
+    object::{geometry, model::TypedModelHandle, TypedObjectHandle},
+    scene::{strongest_influences, NodeIndex},
+};
+
+/// Vertex attributes of a primitive.
+#[derive(Default)]
+struct Primitive {
+    /// Positions.
+    positions: Vec<f32>,
+    /// Normals.
+    normals: Vec<f32>,
+    /// Texture coordinates.
+    uvs: Vec<f32>,
+    /// Joint indices.
+    joints: Vec<[u16; 4]>,
+    /// Joint weights.
+    weights: Vec<f32>,
+    /// Vertex indices.
+    indices: Vec<u32>,
+    /// Vertex indices by the control point index and the attribute bits.
+    welded: HashMap<(u32, [u32; 5]), u32>,
+}
+
+/// Skin data of a mesh.
+struct Skin {
+    /// glTF skin index.
+    index: Index<gltf_json::Skin>,
+    /// Influences (joint and weight) for each control point.
+    influences: Vec<Vec<(u16, f32)>>,
+}
+
+impl<'a> Builder<'a> {
+    /// Adds the mesh (and the skin) of the given mesh node.
+    ///
+    /// Returns the mesh index and the skin index, or `Ok(None)` if the mesh
+    /// has no triangles.
+    pub(super) fn mesh(
+        &mut self,
+        node: usize,
+    ) -> Result<Option<(Index<Mesh>, Option<Index<gltf_json::Skin>>)>, Error> {
+        let scene_node = &self.graph.nodes()[node];
+        let name = scene_node.name.clone();
+        let material_indices = scene_node
+            .materials
+            .iter()
+            .map(|i| gltf_index::<Material>(i.to_usize()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let geometric = Mat4::from(scene_node.geometric_transform);
+        let normal_matrix = geometric
+            .inverse()
+            .ok_or_else(|| format_err!("Geometric transform is not invertible"))?
+            .transpose();
+
+        let mesh = match scene_node.object_id.to_object_handle(self.doc) {
+            Some(obj) => match obj.get_typed() {
+                TypedObjectHandle::Model(TypedModelHandle::Mesh(o)) => o.geometry()?,
+                _ => bail!("Mesh node is not a mesh model"),
+            },
+            None => bail!("Mesh model not found: {:?}", scene_node.object_id),
+        };
+        let skin = self.skin(&mesh, &geometric)?;
+        let polygon_vertices = mesh.polygon_vertices()?;
+        let tris = polygon_vertices.triangulate_each(triangulate)?;
+
+        // Meshes shared by many models are resolved only once.
+        let resolved = self.layer_elements.resolved(&mesh)?;
+        let mut materials = None;
+        for entry in mesh
+            .layers()
+            .flat_map(|layer| layer.layer_element_entries())
+        {
+            if let Ok(TypedLayerElementHandle::Material(h)) = entry.typed_layer_element() {
+                materials = Some(h.materials()?);
+                break;
+            }
+        }
+
+        let mut primitives: Vec<Primitive> = Vec::new();
+        let mut out_of_range = None;
+        for tri_vi in tris.triangle_vertex_indices() {
+            let cpi = tris
+                .control_point_index(tri_vi)
+                .ok_or_else(|| format_err!("Control point index not found for {:?}", tri_vi))?
+                .to_u32();
+            let position = tris
+                .control_point(tri_vi)
+                .ok_or_else(|| format_err!("Control point not found: index={}", cpi))?;
+            let position =
+                geometric.transform_point(Vector3::from([position.x, position.y, position.z]));
+            let pvi = tris
+                .polygon_vertex_index(tri_vi)
+                .ok_or_else(|| format_err!("Polygon vertex index not found for {:?}", tri_vi))?;
+            let normal = resolved
+                .normal(pvi)
+                .map(|n| normalize(transform_vector(&normal_matrix, n)));
+            // glTF texture coordinates have the origin at the top left.
+            let uv = resolved.uv(pvi).map(|uv| [uv.x, 1.0 - uv.y]);
+            let material = match &materials {
+                Some(materials) => materials.material_index(&tris, tri_vi)?.to_u32() as usize,
+                None => 0,
+            };
+            // Out-of-range material indices share a primitive without a
+            // material, so that they cannot cause huge allocations.
+            let material = if material > material_indices.len() {
+                out_of_range.get_or_insert(material);
+                material_indices.len()
+            } else {
+                material
+            };
+
+            if primitives.len() <= material {
+                primitives.resize_with(material + 1, Primitive::default);
+            }
+            let primitive = &mut primitives[material];
+            let n = normal.map_or([0.0; 3], |n| [n.x as f32, n.y as f32, n.z as f32]);
+            let uv = uv.map_or([0.0; 2], |uv| [uv[0] as f32, uv[1] as f32]);
+            let key = (
+                cpi,
+                [
+                    n[0].to_bits(),
+                    n[1].to_bits(),
+                    n[2].to_bits(),
+                    uv[0].to_bits(),
+                    uv[1].to_bits(),
+                ],
+            );
+            let next = (primitive.positions.len() / 3) as u32;
+            let index = *primitive.welded.entry(key).or_insert(next);
+            if index == next {
+                primitive.positions.extend_from_slice(&[
+                    position.x as f32,
+                    position.y as f32,
+                    position.z as f32,
+                ]);
+                primitive.normals.extend_from_slice(&n);
+                primitive.uvs.extend_from_slice(&uv);
+                if let Some(skin) = &skin {
+                    let (joints, weights) = vertex_influences(skin, cpi as usize);
+                    primitive.joints.push(joints);
+                    primitive.weights.extend_from_slice(&weights);
+                }
+            }
+            primitive.indices.push(index);
+        }
+
+        let has_normals = resolved.normals().is_some();
+        let has_uvs = resolved.uvs().is_some();
+        let mut json_primitives = Vec::new();
+        for (material, primitive) in primitives.iter().enumerate() {
+            if primitive.indices.is_empty() {
+                continue;
+            }
+            let mut attributes = HashMap::new();
+            let position = self.push_f32(
+                &primitive.positions,
+                Type::Vec3,
+                Some(Target::ArrayBuffer),
+                true,
+            )?;
+            attributes.insert(Checked::Valid(Semantic::Positions), position);
+            if has_normals {
+                let accessor = self.push_f32(
+                    &primitive.normals,
+                    Type::Vec3,
+                    Some(Target::ArrayBuffer),
+                    false,
+                )?;
+                attributes.insert(Checked::Valid(Semantic::Normals), accessor);
+            }
+            if has_uvs {
+                let accessor =
+                    self.push_f32(&primitive.uvs, Type::Vec2, Some(Target::ArrayBuffer), false)?;
+                attributes.insert(Checked::Valid(Semantic::TexCoords(0)), accessor);
+            }
+            if skin.is_some() {
+                let accessor = self.push_joints(&primitive.joints)?;
+                attributes.insert(Checked::Valid(Semantic::Joints(0)), accessor);
+                let accessor = self.push_f32(
+                    &primitive.weights,
+                    Type::Vec4,
+                    Some(Target::ArrayBuffer),
+                    false,
+                )?;
+                attributes.insert(Checked::Valid(Semantic::Weights(0)), accessor);
+            }
+            let material = match material_indices.get(material) {
+                Some(&index) => Some(index),
+                None => {
+                    if materials.is_some() {
+                        warn!(
+                            "Material index {} is out of range for node {:?}",
+                            out_of_range.unwrap_or(material),
+                            name
+                        );
+                    }
+                    None
+                }
+            };
+            json_primitives.push(mesh::Primitive {
+                attributes,
+                extensions: None,
+                extras: Default::default(),
+                indices: Some(self.push_indices(&primitive.indices)?),
+                material,
+                mode: Checked::Valid(mesh::Mode::Triangles),
+                targets: None,
+            });
+        }
+        if json_primitives.is_empty() {
+            return Ok(None);
+        }
+
+        self.meshes.push(Mesh {
+            extensions: None,
+            extras: Default::default(),
+            name: Some(name),
+            primitives: json_primitives,
+            weights: None,
+        });
+
+        Ok(Some((
+            gltf_index(self.meshes.len() - 1)?,
+            skin.map(|skin| skin.index),
+        )))
+    }
+
+    /// Adds the skin of the given geometry mesh.
+    ///
+    /// Returns `Ok(None)` if the mesh has no skin deformers.
+    fn skin(
+        &mut self,
+        mesh: &geometry::MeshHandle<'_>,
+        geometric: &Mat4,
+    ) -> Result<Option<Skin>, Error> {
+        let clusters: Vec<_> = mesh.skins().flat_map(|skin| skin.clusters()).collect();
+        if clusters.is_empty() {
+            return Ok(None);
+        }
+        let num_control_points = mesh.control_points()?.len();
+        let geometric_inverse = geometric
+            .inverse()
+            .ok_or_else(|| format_err!("Geometric transform is not invertible"))?;
+
+        let mut joints: Vec<NodeIndex> = Vec::new();
+        let mut inverse_binds: Vec<f32> = Vec::new();
+        let mut influences: Vec<Vec<(u16, f32)>> = Vec::new();
+        for cluster in clusters {
+            let joint = match cluster
+                .link()
+                .and_then(|model| self.graph.node_index(model.object_id()))
+            {
+                Some(v) => v,
+                None => {
+                    warn!("Skipping cluster without a bone: {:?}", cluster.object_id());
+                    continue;
+                }
+            };
+            // Joint-space matrix of the (geometric transformed) mesh at the
+            // bind time.
+            let link = Mat4::from(
+                cluster
+                    .transform_link()
+                    .unwrap_or_else(|_| self.graph.node(joint).global_transform),
+            );
+            let transform = cluster.transform().map_or(Mat4::IDENTITY, Mat4::from);
+            let inverse_bind = match link.inverse() {
+                Some(v) => v * transform * geometric_inverse,
+                None => {
+                    warn!(
+                        "Bone transform is not invertible: {:?}",
+                        cluster.object_id()
+                    );
+                    Mat4::IDENTITY
+                }
+            };
+
+            let joint_index = joints.len() as u16;
+            joints.push(joint);
+            inverse_binds.extend(inverse_bind.0.iter().flatten().map(|&v| v as f32));
+            for (&cpi, &weight) in cluster.indices()?.iter().zip(cluster.weights()?) {
+                let cpi = match usize::try_from(cpi) {
+                    Ok(v) if v < num_control_points => v,
+                    _ => bail!(
+                        "Control point index out of range in cluster: index={}, len={}",
+                        cpi,
+                        num_control_points
+                    ),
+                };
+                if influences.len() <= cpi {
+                    influences.resize_with(cpi + 1, Vec::new);
+                }
+                influences[cpi].push((joint_index, weight as f32));
+            }
+        }
+        if joints.is_empty() {
+            return Ok(None);
+        }
+
+        let inverse_binds = self.push_f32(&inverse_binds, Type::Mat4, None, false)?;
+        let joints = joints
+            .iter()
+            .map(|joint| gltf_index(joint.to_usize()))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.skins.push(gltf_json::Skin {
+            extensions: None,
+            extras: Default::default(),
+            inverse_bind_matrices: Some(inverse_binds),
+            joints,
+            name: None,
+            skeleton: None,
+        });
+
+        Ok(Some(Skin {
+            index: gltf_index(self.skins.len() - 1)?,
+            influences,
+        }))
+    }
+}
+
+/// Triangulates the polygon as a fan.
+///
+/// Degenerate polygons with less than three vertices are skipped.
+fn triangulate(
+    _: &PolygonVertices<'_>,
+    poly: &[PolygonVertexIndex],
+    results: &mut Vec<[PolygonVertexIndex; 3]>,
+) -> Result<(), Error> {
+    for i in 2..poly.len() {
+        results.push([poly[0], poly[i - 1], poly[i]]);
+    }
+
+    Ok(())
+}
+
+/// Returns the joints and the normalized weights of the strongest influences
+/// of the given control point.
+fn vertex_influences(skin: &Skin, cpi: usize) -> ([u16; 4], [f32; 4]) {
+    strongest_influences(skin.influences.get(cpi).map_or(&[], Vec::as_slice))
+}
+
+/// Transforms the direction vector by the upper 3x3 part of the matrix.
+fn transform_vector(m: &Mat4, v: Vector3<f64>) -> Vector3<f64> {
+    m.without_translation().transform_point(v)
+}
+
+/// Returns the normalized vector, or the vector itself if it is zero.
+fn normalize(v: Vector3<f64>) -> Vector3<f64> {
+    let len = (v.x * v.x + v.y * v.y + v.z * v.z).sqrt();
+    if len > 0.0 {
+        Vector3::from([v.x / len, v.y / len, v.z / len])
+    } else {
+        v
+    }
+}
@@ -0,0 +1,194 @@
+//! Owned object connection graph.
+//!
+//! [`ConnectionGraph`] is a snapshot of all objects and connections in a
+//! document, intended for debugging (for example, to find out why a texture
+//! or a deformer is not picked up).
+//!
+//! It does not borrow the document, and it can be written as Graphviz DOT
+//! by [`ConnectionGraph::write_dot`].
+//!
+//! [`ConnectionGraph`]: struct.ConnectionGraph.html
+//! [`ConnectionGraph::write_dot`]: struct.ConnectionGraph.html#method.write_dot
+
+use std::{collections::HashSet, fmt::Write as _, io};
+
+use crate::v7400::{
+    object::{ConnectionKind, ObjectId},
+    Document,
+};
+
+/// Object in a connection graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphNode {
+    /// Object ID.
+    pub id: ObjectId,
+    /// Object name.
+    pub name: Option<String>,
+    /// Object class.
+    pub class: String,
+    /// Object subclass.
+    pub subclass: String,
+}
+
+/// Connection in a connection graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphEdge {
+    /// Connection kind.
+    pub kind: ConnectionKind,
+    /// Source object ID.
+    pub source: ObjectId,
+    /// Destination object ID.
+    pub destination: ObjectId,
+    /// Connection label.
+    ///
+    /// For `OP`, `PO` and `PP` connections, this is the property name.
+    pub label: Option<String>,
+}
+
+/// Owned graph of objects and connections.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ConnectionGraph {
+    /// Objects, sorted by object ID.
+    pub nodes: Vec<GraphNode>,
+    /// Connections, in the order of the nodes in the tree.
+    pub edges: Vec<GraphEdge>,
+}
+
+impl ConnectionGraph {
+    /// Creates a new `ConnectionGraph` from the document.
+    pub fn from_document(doc: &Document) -> Self {
+        let mut nodes = doc
+            .objects()
+            .map(|obj| GraphNode {
+                id: obj.object_id(),
+                name: obj.name().map(ToOwned::to_owned),
+                class: obj.class().to_owned(),
+                subclass: obj.subclass().to_owned(),
+            })
+            .collect::<Vec<_>>();
+        nodes.sort_by_key(|node| node.id);
+        let edges = doc
+            .connections()
+            .map(|conn| GraphEdge {
+                kind: conn.kind(),
+                source: conn.source_id(),
+                destination: conn.destination_id(),
+                label: conn.label().map(ToOwned::to_owned),
+            })
+            .collect();
+
+        Self { nodes, edges }
+    }
+
+    /// Returns the node with the given object ID.
+    pub fn node(&self, id: ObjectId) -> Option<&GraphNode> {
+        self.nodes
+            .binary_search_by_key(&id, |node| node.id)
+            .ok()
+            .map(|i| &self.nodes[i])
+    }
+
+    /// Returns an iterator of outgoing connections of the given object.
+    pub fn outgoing(&self, source: ObjectId) -> impl Iterator<Item = &GraphEdge> {
+        self.edges.iter().filter(move |edge| edge.source == source)
+    }
+
+    /// Returns an iterator of incoming connections of the given object.
+    pub fn incoming(&self, destination: ObjectId) -> impl Iterator<Item = &GraphEdge> {
+        self.edges
+            .iter()
+            .filter(move |edge| edge.destination == destination)
+    }
+
+    /// Writes the graph in Graphviz DOT format.
+    ///
+    /// Nodes are labelled with the object name, class and subclass, and
+    /// edges are labelled with the connection kind and the property name.
+    /// Object IDs referenced by connections but not present as objects (such
+    /// as the root object `0`) are written as dashed nodes.
+    pub fn write_dot<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_dot().as_bytes())
+    }
+
+    /// Returns the graph in Graphviz DOT format.
+    ///
+    /// See [`write_dot`](#method.write_dot).
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        self.fmt_dot(&mut out)
+            .expect("Should never fail: writing to `String`");
+        out
+    }
+
+    /// Writes the graph in Graphviz DOT format to the formatter.
+    fn fmt_dot(&self, out: &mut String) -> std::fmt::Result {
+        writeln!(out, "digraph fbx {{")?;
+        writeln!(out, "    node [shape=box];")?;
+        let mut known = HashSet::new();
+        for node in &self.nodes {
+            known.insert(node.id);
+            writeln!(
+                out,
+                "    \"{}\" [label=\"{}\\n{}::{}\\n{}\"];",
+                node.id.raw(),
+                escape_dot(node.name.as_deref().unwrap_or("")),
+                escape_dot(&node.class),
+                escape_dot(&node.subclass),
+                node.id.raw(),
+            )?;
+        }
+        for edge in &self.edges {
+            for &id in &[edge.source, edge.destination] {
+                if known.insert(id) {
+                    let label = if id.raw() == 0 { "(root)" } else { "(missing)" };
+                    writeln!(
+                        out,
+                        "    \"{}\" [label=\"{}\\n{}\", style=dashed];",
+                        id.raw(),
+                        label,
+                        id.raw()
+                    )?;
+                }
+            }
+            let kind = match edge.kind {
+                ConnectionKind::ObjectToObject => "OO",
+                ConnectionKind::ObjectToProperty => "OP",
+                ConnectionKind::PropertyToObject => "PO",
+                ConnectionKind::PropertyToProperty => "PP",
+            };
+            match &edge.label {
+                Some(label) => writeln!(
+                    out,
+                    "    \"{}\" -> \"{}\" [label=\"{} {}\"];",
+                    edge.source.raw(),
+                    edge.destination.raw(),
+                    kind,
+                    escape_dot(label)
+                )?,
+                None => writeln!(
+                    out,
+                    "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                    edge.source.raw(),
+                    edge.destination.raw(),
+                    kind
+                )?,
+            }
+        }
+        writeln!(out, "}}")
+    }
+}
+
+/// Escapes a string to be used in a quoted DOT string.
+fn escape_dot(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
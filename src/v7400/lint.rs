@@ -0,0 +1,458 @@
+//! Importer compatibility lints.
+//!
+//! Some valid FBX constructs are not supported (or are silently dropped) by
+//! popular downstream importers.
+//! [`Linter`] checks a document for such constructs and reports them as
+//! [`LintIssue`]s with the object IDs and suggested remediations.
+//!
+//! Rules can be selected one by one, or by [`LintProfile`]s targeting
+//! specific importers.
+//!
+//! ```
+//! # #[cfg(feature = "edit")]
+//! # fn main() -> Result<(), anyhow::Error> {
+//! use fbxcel_dom::v7400::{
+//!     edit::{DocumentBuilder, ModelKind},
+//!     lint::{LintProfile, Linter, Rule},
+//!     object::property::OwnedPropertyValue,
+//! };
+//!
+//! let mut builder = DocumentBuilder::new();
+//! let parent = builder.add_model("Parent", ModelKind::Null, None);
+//! // `InheritType` 0 is `RrSs`.
+//! let value = OwnedPropertyValue::I32(0);
+//! builder
+//!     .edit_mut()
+//!     .insert_property(parent, "InheritType", "enum", "", "", value)?;
+//! let child = builder.add_model("Child", ModelKind::Null, Some(parent));
+//! let value = OwnedPropertyValue::F64x3([0.0, 1.0, 0.0]);
+//! builder
+//!     .edit_mut()
+//!     .insert_property(child, "GeometricTranslation", "Vector3D", "Vector", "", value)?;
+//! let doc = builder.build()?;
+//!
+//! let issues = Linter::new().profile(LintProfile::UnitySafe).lint(&doc);
+//! let found: Vec<_> = issues.iter().map(|issue| (issue.rule, issue.object)).collect();
+//! assert_eq!(found, [(Rule::InheritType, parent)]);
+//! assert_eq!(issues[0].remediation, Rule::InheritType.remediation());
+//!
+//! // Geometric transforms are baked by Unity, but not by glTF converters.
+//! let issues = Linter::new().profile(LintProfile::GltfConvertible).lint(&doc);
+//! let found: Vec<_> = issues.iter().map(|issue| (issue.rule, issue.object)).collect();
+//! assert_eq!(
+//!     found,
+//!     [(Rule::InheritType, parent), (Rule::GeometricTransform, child)]
+//! );
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "edit"))]
+//! # fn main() {}
+//! ```
+
+use std::collections::{BTreeSet, HashMap};
+
+use log::warn;
+use mint::Vector3;
+
+use crate::v7400::{
+    data::transform::InheritType,
+    object::{
+        deformer::{SkinHandle, TypedDeformerHandle},
+        model::ModelHandle,
+        plugin::PluginObjectHandle,
+        ObjectHandle, ObjectId, TypedObjectHandle,
+    },
+    Document,
+};
+
+/// Tolerance for transform property comparison.
+const EPSILON: f64 = 1e-6;
+
+/// Lint rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Rule {
+    /// Model with rotation pivot different from scaling pivot, or with
+    /// non-zero rotation or scaling offset.
+    ///
+    /// Such transforms cannot be represented by a plain TRS node without
+    /// baking.
+    NonUniformPivot,
+    /// Model with transform inheritance type other than `RSrs`.
+    InheritType,
+    /// Model with non-identity geometric transform.
+    GeometricTransform,
+    /// Control point influenced by more bones than the limit (see
+    /// [`Linter::max_influences`]).
+    ExcessBoneInfluences,
+    /// NURBS curve or surface geometry.
+    NurbsGeometry,
+    /// Layered texture.
+    LayeredTexture,
+    /// Object with a plugin-defined class (see
+    /// [`PluginObjectHandle`](../object/plugin/struct.PluginObjectHandle.html)).
+    ///
+    /// Such objects are dropped by importers without the plugin.
+    PluginObject,
+}
+
+impl Rule {
+    /// Returns the suggested remediation.
+    pub fn remediation(self) -> &'static str {
+        match self {
+            Rule::NonUniformPivot => {
+                "bake pivots into the transform (see `data::transform::bake_pivots`) or reset \
+                 pivots in the authoring tool"
+            }
+            Rule::InheritType => {
+                "set the inherit type to `RSrs`, or avoid non-uniform scaling on the parent"
+            }
+            Rule::GeometricTransform => {
+                "apply the geometric transform to the geometry vertices, or insert an \
+                 intermediate node"
+            }
+            Rule::ExcessBoneInfluences => {
+                "limit the bone influences per vertex and renormalize the weights"
+            }
+            Rule::NurbsGeometry => "convert the NURBS geometry to polygon mesh before export",
+            Rule::LayeredTexture => {
+                "flatten the texture layers into a single texture, or connect textures to \
+                 the material directly"
+            }
+            Rule::PluginObject => {
+                "install the plugin in the importing tool, or convert the object into \
+                 standard objects (such as a polygon mesh) before export"
+            }
+        }
+    }
+}
+
+/// Lint profile, a set of rules targeting a specific importer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum LintProfile {
+    /// Unity FBX importer.
+    UnitySafe,
+    /// Blender FBX importer.
+    BlenderSafe,
+    /// Conversion to glTF 2.0.
+    GltfConvertible,
+}
+
+impl LintProfile {
+    /// Returns the rules of the profile.
+    pub fn rules(self) -> &'static [Rule] {
+        match self {
+            LintProfile::UnitySafe => &[
+                Rule::NonUniformPivot,
+                Rule::InheritType,
+                Rule::ExcessBoneInfluences,
+                Rule::NurbsGeometry,
+                Rule::LayeredTexture,
+                Rule::PluginObject,
+            ],
+            LintProfile::BlenderSafe => &[
+                Rule::NonUniformPivot,
+                Rule::NurbsGeometry,
+                Rule::LayeredTexture,
+                Rule::PluginObject,
+            ],
+            LintProfile::GltfConvertible => &[
+                Rule::NonUniformPivot,
+                Rule::InheritType,
+                Rule::GeometricTransform,
+                Rule::ExcessBoneInfluences,
+                Rule::NurbsGeometry,
+                Rule::LayeredTexture,
+                Rule::PluginObject,
+            ],
+        }
+    }
+}
+
+/// Lint issue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    /// Rule.
+    pub rule: Rule,
+    /// Object ID.
+    pub object: ObjectId,
+    /// Description of the problem.
+    pub message: String,
+    /// Suggested remediation.
+    pub remediation: &'static str,
+}
+
+/// Document linter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Linter {
+    /// Enabled rules.
+    rules: BTreeSet<Rule>,
+    /// Maximum number of bone influences per control point.
+    max_influences: usize,
+}
+
+impl Linter {
+    /// Creates a new linter with no rules enabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables the rules of the profile.
+    pub fn profile(mut self, profile: LintProfile) -> Self {
+        self.rules.extend(profile.rules());
+        self
+    }
+
+    /// Enables or disables the rule.
+    pub fn rule(mut self, rule: Rule, enabled: bool) -> Self {
+        if enabled {
+            self.rules.insert(rule);
+        } else {
+            self.rules.remove(&rule);
+        }
+        self
+    }
+
+    /// Sets the maximum number of bone influences per control point.
+    ///
+    /// Default is 4.
+    pub fn max_influences(mut self, max_influences: usize) -> Self {
+        self.max_influences = max_influences;
+        self
+    }
+
+    /// Checks the document and returns the issues.
+    ///
+    /// Issues are sorted by object ID, and then by rule.
+    /// Objects whose data cannot be read are skipped with warnings.
+    pub fn lint(&self, doc: &Document) -> Vec<LintIssue> {
+        let mut issues = Vec::new();
+        for obj in doc.objects() {
+            match obj.get_typed() {
+                TypedObjectHandle::Model(model) => self.lint_model(&mut issues, &model),
+                TypedObjectHandle::Deformer(TypedDeformerHandle::Skin(skin)) => {
+                    self.lint_skin(&mut issues, &skin)
+                }
+                _ => self.lint_object(&mut issues, &obj),
+            }
+        }
+        issues.sort_by_key(|issue| (issue.object, issue.rule));
+        issues
+    }
+
+    /// Returns true if the rule is enabled.
+    fn enabled(&self, rule: Rule) -> bool {
+        self.rules.contains(&rule)
+    }
+
+    /// Checks the model.
+    fn lint_model(&self, issues: &mut Vec<LintIssue>, model: &ModelHandle<'_>) {
+        let props = model.properties();
+        if self.enabled(Rule::NonUniformPivot) {
+            match model.local_transform() {
+                Ok(local) => {
+                    if !approx_eq(local.rotation_pivot, local.scaling_pivot)
+                        || !approx_eq(local.rotation_offset, ZERO)
+                        || !approx_eq(local.scaling_offset, ZERO)
+                    {
+                        issues.push(issue(
+                            Rule::NonUniformPivot,
+                            model.object_id(),
+                            format!(
+                                "Model {:?} has rotation pivot {:?}, scaling pivot {:?}, \
+                                 rotation offset {:?}, and scaling offset {:?}",
+                                model.name().unwrap_or_default(),
+                                local.rotation_pivot,
+                                local.scaling_pivot,
+                                local.rotation_offset,
+                                local.scaling_offset
+                            ),
+                        ));
+                    }
+                }
+                Err(e) => warn_unreadable(model.object_id(), &e),
+            }
+        }
+        if self.enabled(Rule::InheritType) {
+            match props.inherit_type_or_default() {
+                Ok(InheritType::RSrs) => {}
+                Ok(inherit_type) => issues.push(issue(
+                    Rule::InheritType,
+                    model.object_id(),
+                    format!(
+                        "Model {:?} has inherit type {:?}",
+                        model.name().unwrap_or_default(),
+                        inherit_type
+                    ),
+                )),
+                Err(e) => warn_unreadable(model.object_id(), &e),
+            }
+        }
+        if self.enabled(Rule::GeometricTransform) {
+            let geometric = (|| {
+                Ok::<_, anyhow::Error>((
+                    props.geometric_translation_or_default()?,
+                    props.geometric_rotation_or_default()?,
+                    props.geometric_scaling_or_default()?,
+                ))
+            })();
+            match geometric {
+                Ok((t, r, s)) => {
+                    if !approx_eq(t, ZERO) || !approx_eq(r, ZERO) || !approx_eq(s, ONE) {
+                        issues.push(issue(
+                            Rule::GeometricTransform,
+                            model.object_id(),
+                            format!(
+                                "Model {:?} has geometric translation {:?}, rotation {:?}, \
+                                 and scaling {:?}",
+                                model.name().unwrap_or_default(),
+                                t,
+                                r,
+                                s
+                            ),
+                        ));
+                    }
+                }
+                Err(e) => warn_unreadable(model.object_id(), &e),
+            }
+        }
+    }
+
+    /// Checks the skin.
+    fn lint_skin(&self, issues: &mut Vec<LintIssue>, skin: &SkinHandle<'_>) {
+        if !self.enabled(Rule::ExcessBoneInfluences) {
+            return;
+        }
+        let mut influences: HashMap<i32, usize> = HashMap::new();
+        for cluster in skin.clusters() {
+            let (indices, weights) = match cluster
+                .indices()
+                .and_then(|indices| cluster.weights().map(|weights| (indices, weights)))
+            {
+                Ok(v) => v,
+                Err(e) => {
+                    warn_unreadable(cluster.object_id(), &e);
+                    continue;
+                }
+            };
+            for (&index, &weight) in indices.iter().zip(weights) {
+                if weight != 0.0 {
+                    *influences.entry(index).or_default() += 1;
+                }
+            }
+        }
+        let exceeding = influences
+            .values()
+            .filter(|&&count| count > self.max_influences)
+            .count();
+        if exceeding > 0 {
+            let max = influences.values().copied().max().unwrap_or_default();
+            issues.push(issue(
+                Rule::ExcessBoneInfluences,
+                skin.object_id(),
+                format!(
+                    "Skin {:?} has {} control points with more than {} bone influences \
+                     (max {})",
+                    skin.name().unwrap_or_default(),
+                    exceeding,
+                    self.max_influences,
+                    max
+                ),
+            ));
+        }
+    }
+
+    /// Checks the object by class and subclass.
+    fn lint_object(&self, issues: &mut Vec<LintIssue>, obj: &ObjectHandle<'_>) {
+        match (obj.class(), obj.subclass()) {
+            ("Geometry", subclass)
+                if self.enabled(Rule::NurbsGeometry) && subclass.contains("Nurbs") =>
+            {
+                issues.push(issue(
+                    Rule::NurbsGeometry,
+                    obj.object_id(),
+                    format!(
+                        "Geometry {:?} is {}",
+                        obj.name().unwrap_or_default(),
+                        subclass
+                    ),
+                ));
+            }
+            ("LayeredTexture", _) if self.enabled(Rule::LayeredTexture) => {
+                issues.push(issue(
+                    Rule::LayeredTexture,
+                    obj.object_id(),
+                    format!(
+                        "Texture {:?} is a layered texture",
+                        obj.name().unwrap_or_default()
+                    ),
+                ));
+            }
+            _ => {}
+        }
+        if self.enabled(Rule::PluginObject) {
+            if let Some(obj) = PluginObjectHandle::from_object(*obj) {
+                issues.push(issue(
+                    Rule::PluginObject,
+                    obj.object_id(),
+                    format!(
+                        "Object {:?} has plugin-defined class `{}` (node `{}`, subclass {:?})",
+                        obj.name().unwrap_or_default(),
+                        obj.class(),
+                        obj.node_name(),
+                        obj.subclass()
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self {
+            rules: BTreeSet::new(),
+            max_influences: 4,
+        }
+    }
+}
+
+/// Zero vector.
+const ZERO: Vector3<f64> = Vector3 {
+    x: 0.0,
+    y: 0.0,
+    z: 0.0,
+};
+
+/// Vector with all components one.
+const ONE: Vector3<f64> = Vector3 {
+    x: 1.0,
+    y: 1.0,
+    z: 1.0,
+};
+
+/// Creates an issue.
+fn issue(rule: Rule, object: ObjectId, message: String) -> LintIssue {
+    LintIssue {
+        rule,
+        object,
+        message,
+        remediation: rule.remediation(),
+    }
+}
+
+/// Returns true if the vectors are equal within the tolerance.
+fn approx_eq(a: Vector3<f64>, b: Vector3<f64>) -> bool {
+    (a.x - b.x).abs() <= EPSILON && (a.y - b.y).abs() <= EPSILON && (a.z - b.z).abs() <= EPSILON
+}
+
+/// Warns about the object whose data cannot be read.
+fn warn_unreadable(object: ObjectId, e: &anyhow::Error) {
+    warn!(
+        "Skipping lint of object {:?} with unreadable data: {:#}",
+        object, e
+    );
+}
@@ -76,27 +76,46 @@ use std::fmt;
 
 use fbxcel::tree::v7400::{NodeHandle, NodeId};
 
+#[cfg(feature = "symbol")]
+use crate::v7400::symbol::Symbol;
 use crate::v7400::{connection::Connection, Document};
 
 use self::property::{ObjectProperties, PropertiesHandle};
-pub use self::typed::TypedObjectHandle;
 pub(crate) use self::{
     cache::ObjectsCache,
-    meta::{ObjectClassSym, ObjectMeta},
+    meta::{ObjectIndex, ObjectMetaStore},
+};
+pub use self::{
+    connection::{ConnectedNodeType, ConnectionKind, ObjectConnection},
+    name::{Name, NameMatchOptions},
+    typed::TypedObjectHandle,
 };
 
 #[macro_use]
 mod macros;
 
+pub mod animation;
+pub mod audio;
 mod cache;
+pub mod character;
+pub mod collection;
+mod connection;
+pub mod constraint;
 pub mod deformer;
+pub mod extension;
 pub mod geometry;
+pub mod geometrycache;
+pub mod id;
 pub mod material;
 mod meta;
 pub mod model;
+mod name;
 pub mod nodeattribute;
+pub mod plugin;
 pub mod property;
+pub mod reference;
 pub mod scene;
+pub mod selection;
 pub mod texture;
 mod typed;
 pub mod video;
@@ -112,9 +131,22 @@ impl ObjectNodeId {
     }
 
     /// Creates a new `ObjectHandle`.
+    ///
+    /// # Panics
+    ///
+    /// This may panic if the object node does not exist in the given
+    /// document.
+    /// Use [`get_object_handle`][`Self::get_object_handle`] for object node
+    /// IDs which may come from other documents.
     pub fn to_object_handle(self, doc: &Document) -> ObjectHandle<'_> {
         ObjectHandle::from_object_node_id(self, doc)
     }
+
+    /// Creates a new `ObjectHandle`, if the object node exists in the given
+    /// document.
+    pub fn get_object_handle(self, doc: &Document) -> Option<ObjectHandle<'_>> {
+        ObjectHandle::try_from_object_node_id(self, doc)
+    }
 }
 
 impl std::ops::Deref for ObjectNodeId {
@@ -133,6 +165,7 @@ impl From<ObjectNodeId> for NodeId {
 
 /// Object ID.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ObjectId(i64);
 
 impl ObjectId {
@@ -147,6 +180,8 @@ impl ObjectId {
     }
 
     /// Returns an iterator of destination objects and connection labels.
+    ///
+    /// Objects are returned in the order of the connection nodes in the tree.
     pub fn destination_objects(
         self,
         doc: &Document,
@@ -157,16 +192,69 @@ impl ObjectId {
     }
 
     /// Returns an iterator of source objects and connection labels.
+    ///
+    /// Objects are returned in the order of the connection nodes in the tree.
     pub fn source_objects(self, doc: &Document) -> impl Iterator<Item = ConnectedObjectHandle<'_>> {
         doc.connections_cache()
             .incoming_connections(self)
             .map(move |conn| ConnectedObjectHandle::new(conn.source_id(), conn, doc))
     }
 
+    /// Returns an iterator of outgoing connections (i.e. the object is the
+    /// source).
+    pub fn destination_connections(
+        self,
+        doc: &Document,
+    ) -> impl Iterator<Item = ObjectConnection<'_>> {
+        doc.connections_cache()
+            .outgoing_connections(self)
+            .map(move |conn| ObjectConnection::new(conn, doc))
+    }
+
+    /// Returns an iterator of incoming connections (i.e. the object is the
+    /// destination).
+    pub fn source_connections(self, doc: &Document) -> impl Iterator<Item = ObjectConnection<'_>> {
+        doc.connections_cache()
+            .incoming_connections(self)
+            .map(move |conn| ObjectConnection::new(conn, doc))
+    }
+
+    /// Returns an iterator of connections from the object to the given
+    /// destination.
+    pub fn connections_to(
+        self,
+        destination: ObjectId,
+        doc: &Document,
+    ) -> impl Iterator<Item = ObjectConnection<'_>> {
+        doc.connections_cache()
+            .connections_between(self, destination)
+            .map(move |conn| ObjectConnection::new(conn, doc))
+    }
+
     /// Returns the raw object ID.
     pub fn raw(self) -> i64 {
         self.0
     }
+
+    /// Creates a new `ObjectId` from the raw FBX object ID.
+    ///
+    /// Raw IDs are stored in the FBX file, so they are stable across reloads
+    /// of the same file and can be used as external references to objects.
+    pub fn from_raw(id: i64) -> Self {
+        Self(id)
+    }
+}
+
+impl From<ObjectId> for i64 {
+    fn from(id: ObjectId) -> Self {
+        id.raw()
+    }
+}
+
+impl fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
 }
 
 /// Object handle.
@@ -176,8 +264,8 @@ impl ObjectId {
 pub struct ObjectHandle<'a> {
     /// Node ID.
     node_id: ObjectNodeId,
-    /// Object metadata.
-    object_meta: &'a ObjectMeta,
+    /// Object index in the metadata store.
+    index: ObjectIndex,
     /// Document.
     doc: &'a Document,
 }
@@ -190,13 +278,33 @@ impl<'a> ObjectHandle<'a> {
     /// This may panic if the object node with the given ID does not exist in
     /// the given document.
     fn from_object_node_id(node_id: ObjectNodeId, doc: &'a Document) -> Self {
-        let object_meta = doc
-            .objects_cache()
-            .meta_from_node_id(node_id)
-            .unwrap_or_else(|| panic!("No corresponding object metadata: node_id={:?}", node_id));
-        Self {
+        Self::try_from_object_node_id(node_id, doc)
+            .unwrap_or_else(|| panic!("No corresponding object metadata: node_id={:?}", node_id))
+    }
+
+    /// Creates a new `ObjectHandle` from the given object node ID.
+    ///
+    /// Returns `None` if the object node with the given ID does not exist in
+    /// the given document.
+    fn try_from_object_node_id(node_id: ObjectNodeId, doc: &'a Document) -> Option<Self> {
+        let index = doc.objects_cache().index_from_node_id(node_id)?;
+        Some(Self {
             node_id,
-            object_meta,
+            index,
+            doc,
+        })
+    }
+
+    /// Creates a new `ObjectHandle` from the given object index.
+    ///
+    /// # Panics
+    ///
+    /// This may panic if the object with the given index does not exist in
+    /// the given document.
+    pub(crate) fn from_index(index: ObjectIndex, doc: &'a Document) -> Self {
+        Self {
+            node_id: doc.objects_cache().meta().node_id(index),
+            index,
             doc,
         }
     }
@@ -205,17 +313,10 @@ impl<'a> ObjectHandle<'a> {
     ///
     /// Returns `None` if the given object ID has no corresponding FBX node.
     fn from_object_id(obj_id: ObjectId, doc: &'a Document) -> Option<Self> {
-        let node_id = doc.objects_cache().node_id(obj_id)?;
-        let object_meta = doc
-            .objects_cache()
-            .meta_from_node_id(node_id)
-            .expect("Should never fail: object cache should be consistent");
-        assert_eq!(obj_id, object_meta.object_id(), "Object ID mismatch");
-        Some(Self {
-            node_id,
-            object_meta,
-            doc,
-        })
+        let index = doc.objects_cache().index_from_id(obj_id)?;
+        let handle = Self::from_index(index, doc);
+        assert_eq!(obj_id, handle.object_id(), "Object ID mismatch");
+        Some(handle)
     }
 
     /// Returns object node ID.
@@ -225,7 +326,21 @@ impl<'a> ObjectHandle<'a> {
 
     /// Returns object ID.
     pub fn object_id(&self) -> ObjectId {
-        self.object_meta.object_id()
+        self.doc.objects_cache().meta().object_id(self.index)
+    }
+
+    /// Returns object index in the metadata store.
+    pub(crate) fn index(&self) -> ObjectIndex {
+        self.index
+    }
+
+    /// Returns the raw FBX object ID.
+    ///
+    /// This is stable across reloads of the same file, so it can be stored
+    /// by external systems and later resolved by
+    /// [`Document::object_by_fbx_id`].
+    pub fn fbx_id(&self) -> i64 {
+        self.object_id().raw()
     }
 
     /// Returns a reference to the document.
@@ -243,23 +358,55 @@ impl<'a> ObjectHandle<'a> {
         TypedObjectHandle::new(*self)
     }
 
+    /// Returns the typed object handle, which can be matched on object kind.
+    ///
+    /// This is same as [`get_typed`](#method.get_typed).
+    pub fn kind(&self) -> TypedObjectHandle<'a> {
+        self.get_typed()
+    }
+
     /// Returns object name.
     pub fn name(&self) -> Option<&'a str> {
-        self.object_meta.name()
+        self.doc
+            .objects_cache()
+            .meta()
+            .name_sym(self.index)
+            .map(|sym| self.doc.resolve_registered(sym))
+    }
+
+    /// Returns the symbol of the object name.
+    #[cfg(feature = "symbol")]
+    pub fn name_symbol(&self) -> Option<Symbol> {
+        self.doc.objects_cache().meta().name_sym(self.index)
+    }
+
+    /// Returns namespace-aware object name.
+    pub fn name_parts(&self) -> Option<Name<'a>> {
+        self.name().map(|name| Name::from_parts(name, self.class()))
     }
 
     /// Returns object class.
     pub fn class(&self) -> &'a str {
         self.doc
-            .objects_cache()
-            .resolve_class_string(self.object_meta.class_sym())
+            .resolve_registered(self.doc.objects_cache().meta().class_sym(self.index))
+    }
+
+    /// Returns the symbol of the object class.
+    #[cfg(feature = "symbol")]
+    pub fn class_symbol(&self) -> Symbol {
+        self.doc.objects_cache().meta().class_sym(self.index)
     }
 
     /// Returns object subclass.
     pub fn subclass(&self) -> &'a str {
         self.doc
-            .objects_cache()
-            .resolve_class_string(self.object_meta.subclass_sym())
+            .resolve_registered(self.doc.objects_cache().meta().subclass_sym(self.index))
+    }
+
+    /// Returns the symbol of the object subclass.
+    #[cfg(feature = "symbol")]
+    pub fn subclass_symbol(&self) -> Symbol {
+        self.doc.objects_cache().meta().subclass_sym(self.index)
     }
 
     /// Returns an iterator of destination objects and connection labels.
@@ -272,6 +419,32 @@ impl<'a> ObjectHandle<'a> {
         self.object_id().source_objects(self.doc)
     }
 
+    /// Returns an iterator of outgoing connections (i.e. the object is the
+    /// source).
+    pub fn destination_connections(&self) -> impl Iterator<Item = ObjectConnection<'a>> {
+        self.object_id().destination_connections(self.doc)
+    }
+
+    /// Returns an iterator of incoming connections (i.e. the object is the
+    /// destination).
+    pub fn source_connections(&self) -> impl Iterator<Item = ObjectConnection<'a>> {
+        self.object_id().source_connections(self.doc)
+    }
+
+    /// Returns an iterator of connections from the object to the given
+    /// destination.
+    pub fn connections_to(
+        &self,
+        destination: ObjectId,
+    ) -> impl Iterator<Item = ObjectConnection<'a>> {
+        self.object_id().connections_to(destination, self.doc)
+    }
+
+    /// Returns true if the object is connected to the given destination.
+    pub fn is_connected_to(&self, destination: ObjectId) -> bool {
+        self.connections_to(destination).next().is_some()
+    }
+
     /// Returns a handle of the directly associated properties node.
     pub fn direct_properties(&self) -> Option<PropertiesHandle<'a>> {
         PropertiesHandle::from_object(self)
@@ -286,6 +459,35 @@ impl<'a> ObjectHandle<'a> {
     }
 }
 
+/// Prints the class, the name, and the ID, such as `Model("Hips", id=123)`.
+///
+/// Typed handles are printed in the same way.
+///
+/// ```
+/// # #[cfg(feature = "edit")]
+/// # fn main() -> Result<(), anyhow::Error> {
+/// use fbxcel_dom::v7400::edit::{DocumentBuilder, ModelKind};
+///
+/// let mut builder = DocumentBuilder::new();
+/// let id = builder.add_model("Hips", ModelKind::LimbNode, None);
+/// let doc = builder.build()?;
+///
+/// let model = doc.model_by_path("Hips").expect("Model should exist");
+/// assert_eq!(model.to_string(), format!("Model(\"Hips\", id={})", id.raw()));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "edit"))]
+/// # fn main() {}
+/// ```
+impl fmt::Display for ObjectHandle<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "{}({:?}, id={})", self.class(), name, self.object_id()),
+            None => write!(f, "{}(id={})", self.class(), self.object_id()),
+        }
+    }
+}
+
 impl fmt::Debug for ObjectHandle<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         /// Object metadata type for debug printing.
@@ -349,6 +551,11 @@ impl<'a> ConnectedObjectHandle<'a> {
     pub fn label(&self) -> Option<&'a str> {
         self.connection
             .label_sym()
-            .map(|sym| self.doc.connections_cache().resolve_label(sym))
+            .map(|sym| self.doc.resolve_registered(sym))
+    }
+
+    /// Returns the connection record.
+    pub fn connection(&self) -> ObjectConnection<'a> {
+        ObjectConnection::new(self.connection, self.doc)
     }
 }
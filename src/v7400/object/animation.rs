@@ -0,0 +1,278 @@
+//! Animation objects.
+//!
+//! Animation data is organized as below:
+//!
+//! * `AnimationStack` (take) contains `AnimationLayer`s,
+//! * `AnimationLayer` contains `AnimationCurveNode`s,
+//! * `AnimationCurveNode` is bound to a property of an object, and contains
+//!   `AnimationCurve`s for each channel (such as `d|X`).
+
+use std::convert::TryFrom;
+
+use anyhow::{bail, format_err, Error};
+use fbxcel::{
+    low::v7400::{AttributeType, AttributeValue},
+    tree::v7400::NodeHandle,
+};
+
+use crate::v7400::{
+    object::{audio, ObjectHandle, TypedObjectHandle},
+    Error as DomError, ErrorLocation,
+};
+
+/// FBX time units (`KTime` ticks) per second.
+pub const KTIME_PER_SECOND: i64 = 46_186_158_000;
+
+/// Key attribute flag for constant interpolation.
+pub(crate) const FLAG_CONSTANT: i32 = 0x0000_0002;
+/// Key attribute flag for linear interpolation.
+pub(crate) const FLAG_LINEAR: i32 = 0x0000_0004;
+
+/// Key interpolation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Interpolation {
+    /// Constant (step).
+    Constant,
+    /// Linear.
+    Linear,
+    /// Cubic with auto tangents.
+    Cubic,
+}
+
+impl Interpolation {
+    /// Creates the interpolation from the key attribute flags.
+    ///
+    /// Flags without any interpolation bit are treated as cubic.
+    pub(crate) fn from_flags(flags: i32) -> Self {
+        if flags & FLAG_CONSTANT != 0 {
+            Interpolation::Constant
+        } else if flags & FLAG_LINEAR != 0 {
+            Interpolation::Linear
+        } else {
+            Interpolation::Cubic
+        }
+    }
+}
+
+define_object_subtype! {
+    /// `AnimationStack` node handle.
+    AnimationStackHandle: ObjectHandle
+}
+
+impl<'a> AnimationStackHandle<'a> {
+    /// Returns an iterator of child animation layers.
+    pub fn layers(&self) -> impl Iterator<Item = AnimationLayerHandle<'a>> {
+        self.source_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::AnimationLayer(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns an iterator of audio layers played with the stack.
+    pub fn audio_layers(&self) -> impl Iterator<Item = audio::AudioLayerHandle<'a>> {
+        self.source_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::AudioLayer(o) => Some(o),
+                _ => None,
+            })
+    }
+}
+
+define_object_subtype! {
+    /// `AnimationLayer` node handle.
+    AnimationLayerHandle: ObjectHandle
+}
+
+impl<'a> AnimationLayerHandle<'a> {
+    /// Returns an iterator of child animation curve nodes.
+    pub fn curve_nodes(&self) -> impl Iterator<Item = AnimationCurveNodeHandle<'a>> {
+        self.source_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::AnimationCurveNode(o) => Some(o),
+                _ => None,
+            })
+    }
+}
+
+define_object_subtype! {
+    /// `AnimationCurveNode` node handle.
+    AnimationCurveNodeHandle: ObjectHandle
+}
+
+impl<'a> AnimationCurveNodeHandle<'a> {
+    /// Returns an iterator of child animation curves and their channel
+    /// names (such as `d|X`).
+    pub fn curves(&self) -> impl Iterator<Item = (AnimationCurveHandle<'a>, &'a str)> {
+        self.source_objects().filter_map(|obj| {
+            let label = obj.label()?;
+            match obj.object_handle()?.get_typed() {
+                TypedObjectHandle::AnimationCurve(o) => Some((o, label)),
+                _ => None,
+            }
+        })
+    }
+
+    /// Returns the animated object and the name of the animated property
+    /// (such as `Lcl Translation`).
+    pub fn target(&self) -> Option<(ObjectHandle<'a>, &'a str)> {
+        self.destination_objects().find_map(|obj| {
+            let label = obj.label()?;
+            Some((obj.object_handle()?, label))
+        })
+    }
+}
+
+define_object_subtype! {
+    /// `AnimationCurve` node handle.
+    AnimationCurveHandle: ObjectHandle
+}
+
+impl<'a> AnimationCurveHandle<'a> {
+    /// Returns the key times in `KTime` ticks.
+    ///
+    /// See [`KTIME_PER_SECOND`].
+    pub fn key_times(&self) -> Result<&'a [i64], Error> {
+        let (node, attr) = self.first_attribute("KeyTime")?;
+        attr.get_arr_i64_or_type()
+            .map_err(|ty| self.type_mismatch(&node, "`[i64]`", ty).into())
+    }
+
+    /// Returns the key times in seconds, converted to `f32`.
+    pub fn key_times_seconds_f32(&self) -> Result<Vec<f32>, Error> {
+        Ok(self
+            .key_times()?
+            .iter()
+            .map(|&t| (t as f64 / KTIME_PER_SECOND as f64) as f32)
+            .collect())
+    }
+
+    /// Returns the key values.
+    pub fn key_values(&self) -> Result<&'a [f32], Error> {
+        let (node, attr) = self.first_attribute("KeyValueFloat")?;
+        attr.get_arr_f32_or_type()
+            .map_err(|ty| self.type_mismatch(&node, "`[f32]`", ty).into())
+    }
+
+    /// Returns the interpolations of the keys.
+    ///
+    /// The run-length encoded `KeyAttrFlags` and `KeyAttrRefCount` are
+    /// expanded, so the result has an element for each key.
+    pub fn key_interpolations(&self) -> Result<Vec<Interpolation>, Error> {
+        let len = self.key_times()?.len();
+        let (node, attr) = self.first_attribute("KeyAttrFlags")?;
+        let flags = attr
+            .get_arr_i32_or_type()
+            .map_err(|ty| self.type_mismatch(&node, "`[i32]`", ty))?;
+        let (node, attr) = self.first_attribute("KeyAttrRefCount")?;
+        let ref_counts = attr
+            .get_arr_i32_or_type()
+            .map_err(|ty| self.type_mismatch(&node, "`[i32]`", ty))?;
+        if flags.len() != ref_counts.len() {
+            bail!(
+                "Key attribute flags and reference counts mismatch: flags={}, ref_counts={}",
+                flags.len(),
+                ref_counts.len()
+            );
+        }
+
+        let mut interpolations = Vec::with_capacity(len);
+        for (&flag, &count) in flags.iter().zip(ref_counts) {
+            let count = usize::try_from(count)
+                .map_err(|_| format_err!("Negative key attribute reference count: {}", count))?;
+            if count > len - interpolations.len() {
+                bail!(
+                    "Key attributes count mismatch: keys={}, attributes>{}",
+                    len,
+                    interpolations.len() + count - 1
+                );
+            }
+            interpolations.extend(std::iter::repeat(Interpolation::from_flags(flag)).take(count));
+        }
+        if interpolations.len() != len {
+            bail!(
+                "Key attributes count mismatch: keys={}, attributes={}",
+                len,
+                interpolations.len()
+            );
+        }
+
+        Ok(interpolations)
+    }
+
+    /// Evaluates the curve at the given time in `KTime` ticks.
+    ///
+    /// Values before the first key and after the last key are clamped.
+    /// Cubic segments are evaluated as linear, since the tangents are not
+    /// interpreted.
+    ///
+    /// Returns `Ok(None)` if the curve has no keys.
+    #[cfg(feature = "animation")]
+    pub fn evaluate(&self, time: i64) -> Result<Option<f32>, Error> {
+        let times = self.key_times()?;
+        let values = self.key_values()?;
+        if times.len() != values.len() {
+            bail!(
+                "Key times and values count mismatch: times={}, values={}",
+                times.len(),
+                values.len()
+            );
+        }
+        let next = times.partition_point(|&t| t <= time);
+        if next == 0 {
+            return Ok(values.first().copied());
+        }
+        if next == times.len() {
+            return Ok(values.last().copied());
+        }
+
+        let prev = next - 1;
+        // Missing key attributes are treated as linear.
+        let interpolation = self
+            .key_interpolations()
+            .ok()
+            .and_then(|v| v.get(prev).copied())
+            .unwrap_or(Interpolation::Linear);
+        if interpolation == Interpolation::Constant {
+            return Ok(Some(values[prev]));
+        }
+        // Differences are computed in `f64` since they can overflow `i64`.
+        let ratio = (time as f64 - times[prev] as f64) / (times[next] as f64 - times[prev] as f64);
+        let (v0, v1) = (f64::from(values[prev]), f64::from(values[next]));
+
+        Ok(Some((v0 + (v1 - v0) * ratio) as f32))
+    }
+
+    /// Returns the child node with the given name and its first attribute.
+    fn first_attribute(&self, name: &str) -> Result<(NodeHandle<'a>, &'a AttributeValue), Error> {
+        let node = self
+            .node()
+            .first_child_by_name(name)
+            .ok_or_else(|| DomError::missing_node(&self.node(), name))?;
+        let attr = self
+            .document()
+            .attribute(&node, 0)?
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?;
+        Ok((node, attr))
+    }
+
+    /// Creates a type mismatch error for the attribute of the child node.
+    fn type_mismatch(
+        &self,
+        node: &NodeHandle<'_>,
+        expected: &str,
+        actual: AttributeType,
+    ) -> DomError {
+        DomError::type_mismatch(
+            ErrorLocation::node(node).with_object(self.object_id()),
+            expected,
+            actual,
+        )
+    }
+}
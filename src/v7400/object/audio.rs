@@ -0,0 +1,315 @@
+//! `Audio` and `AudioLayer` objects.
+//!
+//! Audio clips are connected to audio layers, and audio layers are connected
+//! to animation stacks, so that the clips are played in sync with the
+//! animation.
+//!
+//! ```
+//! # #[cfg(feature = "edit")]
+//! # fn main() -> Result<(), anyhow::Error> {
+//! use fbxcel_dom::v7400::edit::DocumentBuilder;
+//!
+//! let mut builder = DocumentBuilder::new();
+//! let stack = builder.add_animation_stack("Take 001", 0, 46_186_158_000)?;
+//! let layer = builder.add_audio_layer("Dialogue", stack)?;
+//! builder.add_audio("Line01", "C:/sounds/line01.wav", None, 23_093_079_000, layer)?;
+//! let doc = builder.build()?;
+//!
+//! let stack = doc.anim_stacks().next().expect("Animation stack should exist");
+//! let layer = stack.audio_layers().next().expect("Audio layer should exist");
+//! let audio = layer.audio_clips().next().expect("Audio should exist");
+//! assert_eq!(audio.relative_filename()?, "line01.wav");
+//! assert_eq!(audio.properties().offset_or_default()?, 23_093_079_000);
+//! assert!(audio.content().is_none());
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "edit"))]
+//! # fn main() {}
+//! ```
+
+use anyhow::Error;
+
+use crate::v7400::{
+    object::{
+        animation,
+        property::{loaders::PrimitiveLoader, ObjectProperties},
+        ObjectHandle, TypedObjectHandle,
+    },
+    Error as DomError, ErrorLocation,
+};
+
+define_object_subtype! {
+    /// `AudioLayer` node handle.
+    AudioLayerHandle: ObjectHandle
+}
+
+impl<'a> AudioLayerHandle<'a> {
+    /// Returns an iterator of the audio clips in the layer.
+    pub fn audio_clips(&self) -> impl Iterator<Item = AudioHandle<'a>> {
+        self.source_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Audio(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns an iterator of the animation stacks using the layer.
+    pub fn animation_stacks(&self) -> impl Iterator<Item = animation::AnimationStackHandle<'a>> {
+        self.destination_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::AnimationStack(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns properties.
+    pub fn properties(&self) -> AudioLayerProperties<'a> {
+        AudioLayerProperties {
+            properties: self.properties_by_native_typename("FbxAudioLayer"),
+        }
+    }
+}
+
+/// Proxy type to audio layer properties.
+#[derive(Debug, Clone, Copy)]
+pub struct AudioLayerProperties<'a> {
+    /// Properties.
+    properties: ObjectProperties<'a>,
+}
+
+impl<'a> AudioLayerProperties<'a> {
+    impl_prop_proxy_getters! {
+        /// Returns whether the layer is muted.
+        mute -> bool {
+            name = "Mute",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "mute flag",
+            default: {
+                /// Returns whether the layer is muted.
+                ///
+                /// Returns default if the value is not set.
+                mute_or_default = false
+            }
+        }
+
+        /// Returns whether the layer is soloed.
+        solo -> bool {
+            name = "Solo",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "solo flag",
+            default: {
+                /// Returns whether the layer is soloed.
+                ///
+                /// Returns default if the value is not set.
+                solo_or_default = false
+            }
+        }
+
+        /// Returns the volume in percent.
+        volume -> f64 {
+            name = "Volume",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "layer volume",
+            default: {
+                /// Returns the volume in percent.
+                ///
+                /// Returns default if the value is not set.
+                volume_or_default = 100.0
+            }
+        }
+
+        /// Returns the balance, from `-100.0` (left) to `100.0` (right).
+        balance -> f64 {
+            name = "Balance",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "layer balance",
+            default: {
+                /// Returns the balance, from `-100.0` (left) to `100.0`
+                /// (right).
+                ///
+                /// Returns default if the value is not set.
+                balance_or_default = 0.0
+            }
+        }
+    }
+}
+
+define_object_subtype! {
+    /// `Audio` node handle.
+    AudioHandle: ObjectHandle
+}
+
+impl<'a> AudioHandle<'a> {
+    /// Returns an iterator of the audio layers using the clip.
+    pub fn audio_layers(&self) -> impl Iterator<Item = AudioLayerHandle<'a>> {
+        self.destination_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::AudioLayer(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns filename.
+    ///
+    /// Note that this returns raw value, and the path separator might be a
+    /// slash or a backslash.
+    pub fn filename(&self) -> Result<&'a str, Error> {
+        self.child_string("Filename")
+    }
+
+    /// Returns relative filename.
+    ///
+    /// Note that this returns raw value, and the path separator might be a
+    /// slash or a backslash.
+    pub fn relative_filename(&self) -> Result<&'a str, Error> {
+        self.child_string("RelativeFilename")
+    }
+
+    /// Returns content.
+    pub fn content(&self) -> Option<&'a [u8]> {
+        self.node()
+            .children_by_name("Content")
+            .next()?
+            .attributes()
+            .first()?
+            .get_binary()
+    }
+
+    /// Returns properties.
+    pub fn properties(&self) -> AudioProperties<'a> {
+        AudioProperties {
+            properties: self.properties_by_native_typename("FbxAudio"),
+        }
+    }
+
+    /// Returns the string value of the child node with the given name.
+    fn child_string(&self, name: &str) -> Result<&'a str, Error> {
+        let node = self
+            .node()
+            .children_by_name(name)
+            .next()
+            .ok_or_else(|| DomError::missing_node(&self.node(), name))?;
+        node.attributes()
+            .first()
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?
+            .get_string_or_type()
+            .map_err(|ty| {
+                DomError::type_mismatch(
+                    ErrorLocation::node(&node).with_object(self.object_id()),
+                    "string",
+                    ty,
+                )
+                .into()
+            })
+    }
+}
+
+/// Proxy type to audio properties.
+#[derive(Debug, Clone, Copy)]
+pub struct AudioProperties<'a> {
+    /// Properties.
+    properties: ObjectProperties<'a>,
+}
+
+impl<'a> AudioProperties<'a> {
+    impl_prop_proxy_getters! {
+        /// Returns the start offset of the clip on the timeline in `KTime`
+        /// ticks.
+        offset -> i64 {
+            name = "Offset",
+            loader = PrimitiveLoader::<i64>::new(),
+            description = "start offset",
+            default: {
+                /// Returns the start offset of the clip on the timeline in
+                /// `KTime` ticks.
+                ///
+                /// Returns default if the value is not set.
+                offset_or_default = 0
+            }
+        }
+
+        /// Returns the start time in the clip in `KTime` ticks.
+        clip_in -> i64 {
+            name = "ClipIn",
+            loader = PrimitiveLoader::<i64>::new(),
+            description = "clip in time",
+            default: {
+                /// Returns the start time in the clip in `KTime` ticks.
+                ///
+                /// Returns default if the value is not set.
+                clip_in_or_default = 0
+            }
+        }
+
+        /// Returns the end time in the clip in `KTime` ticks.
+        clip_out -> i64 {
+            name = "ClipOut",
+            loader = PrimitiveLoader::<i64>::new(),
+            description = "clip out time",
+            default: {
+                /// Returns the end time in the clip in `KTime` ticks.
+                ///
+                /// Returns default if the value is not set.
+                clip_out_or_default = 0
+            }
+        }
+
+        /// Returns the play speed.
+        play_speed -> f64 {
+            name = "PlaySpeed",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "play speed",
+            default: {
+                /// Returns the play speed.
+                ///
+                /// Returns default if the value is not set.
+                play_speed_or_default = 1.0
+            }
+        }
+
+        /// Returns the volume in percent.
+        volume -> f64 {
+            name = "Volume",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "audio volume",
+            default: {
+                /// Returns the volume in percent.
+                ///
+                /// Returns default if the value is not set.
+                volume_or_default = 100.0
+            }
+        }
+
+        /// Returns whether the clip is muted.
+        mute -> bool {
+            name = "Mute",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "mute flag",
+            default: {
+                /// Returns whether the clip is muted.
+                ///
+                /// Returns default if the value is not set.
+                mute_or_default = false
+            }
+        }
+
+        /// Returns whether the clip loops.
+        looping -> bool {
+            name = "Loop",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "loop flag",
+            default: {
+                /// Returns whether the clip loops.
+                ///
+                /// Returns default if the value is not set.
+                looping_or_default = false
+            }
+        }
+    }
+}
@@ -4,87 +4,146 @@ use std::collections::HashMap;
 
 use fbxcel::tree::v7400::{NodeHandle, Tree};
 use log::{debug, trace};
-use string_interner::{DefaultBackend, StringInterner};
 
 use crate::v7400::{
+    document::memory,
     error::{
         load::{LoadError, StructureError},
         object::ObjectMetaError,
     },
-    object::{ObjectClassSym, ObjectId, ObjectMeta, ObjectNodeId},
+    object::{ObjectId, ObjectIndex, ObjectMetaStore, ObjectNodeId},
+    symbol::{Symbol, SymbolTable},
 };
 
 /// Objects cache.
 #[derive(Debug, Clone)]
 pub(crate) struct ObjectsCache {
-    /// A map from object ID to node ID.
-    obj_id_to_node_id: HashMap<ObjectId, ObjectNodeId>,
     /// Object metadata store.
-    meta: HashMap<ObjectNodeId, ObjectMeta>,
-    /// Interned object classes and subclasses.
-    class_strings: StringInterner<DefaultBackend<ObjectClassSym>>,
+    meta: ObjectMetaStore,
+    /// A map from object ID to object index.
+    index_by_id: HashMap<ObjectId, ObjectIndex>,
+    /// A map from object node ID to object index.
+    index_by_node: HashMap<ObjectNodeId, ObjectIndex>,
     /// `Document` nodes.
-    document_nodes: Vec<ObjectNodeId>,
+    document_nodes: Vec<ObjectIndex>,
+    /// Objects by class.
+    by_class: HashMap<Symbol, Vec<ObjectIndex>>,
+    /// Objects by class and subclass.
+    by_class_subclass: HashMap<(Symbol, Symbol), Vec<ObjectIndex>>,
+    /// Objects by name.
+    by_name: HashMap<Symbol, Vec<ObjectIndex>>,
 }
 
 impl ObjectsCache {
-    /// Returns object node ID corresponding to the given object ID.
-    pub(crate) fn node_id(&self, obj_id: ObjectId) -> Option<ObjectNodeId> {
-        self.obj_id_to_node_id.get(&obj_id).cloned()
+    /// Returns object index corresponding to the given object ID.
+    pub(crate) fn index_from_id(&self, obj_id: ObjectId) -> Option<ObjectIndex> {
+        self.index_by_id.get(&obj_id).copied()
     }
 
-    /// Returns a reference to the object metadata.
-    pub(crate) fn meta_from_node_id(&self, node_id: ObjectNodeId) -> Option<&ObjectMeta> {
-        self.meta.get(&node_id)
+    /// Returns object index corresponding to the given object node ID.
+    pub(crate) fn index_from_node_id(&self, node_id: ObjectNodeId) -> Option<ObjectIndex> {
+        self.index_by_node.get(&node_id).copied()
     }
 
-    /// Creates a new `ObjectsCache` from the given FBX data tree.
-    pub(crate) fn from_tree(tree: &Tree) -> Result<Self, LoadError> {
+    /// Returns a reference to the object metadata store.
+    pub(crate) fn meta(&self) -> &ObjectMetaStore {
+        &self.meta
+    }
+
+    /// Creates a new `ObjectsCache` from the given FBX data tree, interning
+    /// the names and classes into the symbol table.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "objects_cache", skip_all)
+    )]
+    pub(crate) fn from_tree(tree: &Tree, symbols: &mut SymbolTable) -> Result<Self, LoadError> {
         debug!("Loading objects cache");
-        let objects_cache = ObjectsCacheBuilder::default().load(tree)?;
+        let objects_cache = ObjectsCacheBuilder::new(symbols).load(tree)?;
         debug!(
             "Loaded objects cache successfully: {} objects",
-            objects_cache.obj_id_to_node_id.len()
+            objects_cache.meta.len()
         );
         Ok(objects_cache)
     }
 
-    /// Resolves object class and subclass to string.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the given symbol is not registered in the internal table.
-    pub(crate) fn resolve_class_string(&self, sym: ObjectClassSym) -> &str {
-        self.class_strings
-            .resolve(sym)
-            .unwrap_or_else(|| panic!("Unresolvable class name symbol: sym={:?}", sym))
+    /// Returns document object indices.
+    pub(crate) fn document_nodes(&self) -> &[ObjectIndex] {
+        &self.document_nodes
+    }
+
+    /// Returns an iterator of object indices.
+    pub(crate) fn object_indices(&self) -> impl Iterator<Item = ObjectIndex> {
+        self.meta.indices()
     }
 
-    /// Returns document node IDs.
-    pub(crate) fn document_nodes(&self) -> &[ObjectNodeId] {
-        &self.document_nodes
+    /// Returns object indices with the given class (and subclass), in the
+    /// order of the nodes in the tree.
+    pub(crate) fn object_indices_by_class(
+        &self,
+        class: Symbol,
+        subclass: Option<Symbol>,
+    ) -> &[ObjectIndex] {
+        let ids = match subclass {
+            Some(subclass) => self.by_class_subclass.get(&(class, subclass)),
+            None => self.by_class.get(&class),
+        };
+        ids.map_or(&[], Vec::as_slice)
     }
 
-    /// Returns an iterator of object IDs.
-    pub(crate) fn object_node_ids(&self) -> impl Iterator<Item = ObjectNodeId> + '_ {
-        self.meta.keys().cloned()
+    /// Returns object indices with the given name, in the order of the nodes
+    /// in the tree.
+    pub(crate) fn object_indices_by_name(&self, name: Symbol) -> &[ObjectIndex] {
+        self.by_name.get(&name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the estimated heap size.
+    pub(crate) fn heap_size(&self) -> usize {
+        self.meta.heap_size()
+            + memory::map_heap_size(&self.index_by_id)
+            + memory::map_heap_size(&self.index_by_node)
+            + memory::vec_heap_size(&self.document_nodes)
+            + memory::multimap_heap_size(&self.by_class)
+            + memory::multimap_heap_size(&self.by_class_subclass)
+            + memory::multimap_heap_size(&self.by_name)
     }
 }
 
 /// Objects cache builder.
 #[derive(Debug)]
-struct ObjectsCacheBuilder {
-    /// A map from object ID to node ID.
-    obj_id_to_node_id: HashMap<ObjectId, ObjectNodeId>,
+struct ObjectsCacheBuilder<'a> {
     /// Object metadata store.
-    meta: HashMap<ObjectNodeId, ObjectMeta>,
-    /// Interned object classes and subclasses.
-    class_strings: StringInterner<DefaultBackend<ObjectClassSym>>,
+    meta: ObjectMetaStore,
+    /// A map from object ID to object index.
+    index_by_id: HashMap<ObjectId, ObjectIndex>,
+    /// A map from object node ID to object index.
+    index_by_node: HashMap<ObjectNodeId, ObjectIndex>,
+    /// Symbol table for names, classes and subclasses.
+    symbols: &'a mut SymbolTable,
     /// `Document` nodes.
-    document_nodes: Vec<ObjectNodeId>,
+    document_nodes: Vec<ObjectIndex>,
+    /// Objects by class.
+    by_class: HashMap<Symbol, Vec<ObjectIndex>>,
+    /// Objects by class and subclass.
+    by_class_subclass: HashMap<(Symbol, Symbol), Vec<ObjectIndex>>,
+    /// Objects by name.
+    by_name: HashMap<Symbol, Vec<ObjectIndex>>,
 }
 
-impl ObjectsCacheBuilder {
+impl<'a> ObjectsCacheBuilder<'a> {
+    /// Creates a new builder interning strings into the given symbol table.
+    fn new(symbols: &'a mut SymbolTable) -> Self {
+        Self {
+            meta: Default::default(),
+            index_by_id: Default::default(),
+            index_by_node: Default::default(),
+            symbols,
+            document_nodes: Default::default(),
+            by_class: Default::default(),
+            by_class_subclass: Default::default(),
+            by_name: Default::default(),
+        }
+    }
+
     /// Loads the data from the tree.
     fn load(mut self, tree: &Tree) -> Result<ObjectsCache, LoadError> {
         self.load_objects(tree)?;
@@ -93,12 +152,16 @@ impl ObjectsCacheBuilder {
     }
 
     /// Creates an `ObjectsCache` from the builder.
-    fn build(self) -> ObjectsCache {
+    fn build(mut self) -> ObjectsCache {
+        self.meta.shrink_to_fit();
         ObjectsCache {
-            obj_id_to_node_id: self.obj_id_to_node_id,
             meta: self.meta,
-            class_strings: self.class_strings,
+            index_by_id: self.index_by_id,
+            index_by_node: self.index_by_node,
             document_nodes: self.document_nodes,
+            by_class: self.by_class,
+            by_class_subclass: self.by_class_subclass,
+            by_name: self.by_name,
         }
     }
 
@@ -124,18 +187,20 @@ impl ObjectsCacheBuilder {
             .next()
             .ok_or(StructureError::MissingDocumentsNode)?;
         for object_node in documents_node.children_by_name("Document") {
-            let obj_node_id = self.load_object(object_node)?;
-            self.document_nodes.push(obj_node_id);
+            let index = self.load_object(object_node)?;
+            self.document_nodes.push(index);
         }
 
         Ok(())
     }
 
     /// Loads an object from the node handle and caches it.
-    fn load_object(&mut self, node: NodeHandle<'_>) -> Result<ObjectNodeId, ObjectMetaError> {
+    fn load_object(&mut self, node: NodeHandle<'_>) -> Result<ObjectIndex, ObjectMetaError> {
         trace!("Loading object metadata, node_id={:?}", node.node_id());
         assert!(
-            !self.meta.contains_key(&ObjectNodeId::new(node.node_id())),
+            !self
+                .index_by_node
+                .contains_key(&ObjectNodeId::new(node.node_id())),
             "The node is already loaded: node_id={:?}",
             node.node_id()
         );
@@ -145,17 +210,27 @@ impl ObjectsCacheBuilder {
         let subclass_sym = self.load_subclass(node, obj_id)?;
 
         let obj_node_id = ObjectNodeId::new(node.node_id());
-        let meta = ObjectMeta::new(obj_id, name, class_sym, subclass_sym);
+        let index = self
+            .meta
+            .push(obj_node_id, obj_id, name, class_sym, subclass_sym);
         trace!(
-            "Successfully loaded object metadata: node={:?}, metadata={:?}",
+            "Successfully loaded object metadata: node={:?}, index={:?}",
             obj_node_id,
-            meta,
+            index,
         );
 
-        self.obj_id_to_node_id.insert(obj_id, obj_node_id);
-        self.meta.insert(obj_node_id, meta);
+        self.index_by_id.insert(obj_id, index);
+        self.index_by_node.insert(obj_node_id, index);
+        if let Some(name) = name {
+            self.by_name.entry(name).or_default().push(index);
+        }
+        self.by_class.entry(class_sym).or_default().push(index);
+        self.by_class_subclass
+            .entry((class_sym, subclass_sym))
+            .or_default()
+            .push(index);
 
-        Ok(obj_node_id)
+        Ok(index)
     }
 
     /// Loads an object ID from the given object node.
@@ -168,11 +243,11 @@ impl ObjectsCacheBuilder {
             .map(ObjectId::new)
             .map_err(|ty| ObjectMetaError::InvalidIdType(node.node_id(), ty))?;
         trace!("Got object id: {:?}", obj_id);
-        if let Some(&alt_node_id) = self.obj_id_to_node_id.get(&obj_id) {
+        if let Some(&alt_index) = self.index_by_id.get(&obj_id) {
             return Err(ObjectMetaError::DuplicateObjectId(
                 obj_id,
                 node.node_id(),
-                alt_node_id.into(),
+                self.meta.node_id(alt_index).into(),
             ));
         }
         Ok(obj_id)
@@ -183,7 +258,7 @@ impl ObjectsCacheBuilder {
         &mut self,
         node: NodeHandle<'_>,
         obj_id: ObjectId,
-    ) -> Result<(Option<String>, ObjectClassSym), ObjectMetaError> {
+    ) -> Result<(Option<Symbol>, Symbol), ObjectMetaError> {
         let attrs = node.attributes();
         let (name, class) = attrs
             .get(1)
@@ -192,16 +267,11 @@ impl ObjectsCacheBuilder {
             .map(|name_class| {
                 name_class.find("\u{0}\u{1}").map_or_else(
                     || (None, ""),
-                    |sep_pos| {
-                        (
-                            Some(name_class[0..sep_pos].to_owned()),
-                            &name_class[sep_pos + 2..],
-                        )
-                    },
+                    |sep_pos| (Some(&name_class[0..sep_pos]), &name_class[sep_pos + 2..]),
                 )
             })
             .map_err(|ty| ObjectMetaError::InvalidNameClassType(node.node_id(), obj_id, ty))?;
-        let class_sym = self.class_strings.get_or_intern(class);
+        let class_sym = self.symbols.intern(class);
         trace!(
             "Got name and class: object_id={:?}, name={:?}, class={:?}, class_sym={:?}",
             obj_id,
@@ -210,7 +280,7 @@ impl ObjectsCacheBuilder {
             class_sym
         );
 
-        Ok((name, class_sym))
+        Ok((name.map(|name| self.symbols.intern(name)), class_sym))
     }
 
     /// Loads subclass from the given object node.
@@ -218,14 +288,14 @@ impl ObjectsCacheBuilder {
         &mut self,
         node: NodeHandle<'_>,
         obj_id: ObjectId,
-    ) -> Result<ObjectClassSym, ObjectMetaError> {
+    ) -> Result<Symbol, ObjectMetaError> {
         let attrs = node.attributes();
         let subclass = attrs
             .get(2)
             .ok_or_else(|| ObjectMetaError::MissingSubclass(node.node_id(), obj_id))?
             .get_string_or_type()
             .map_err(|ty| ObjectMetaError::InvalidSubclassType(node.node_id(), obj_id, ty))?;
-        let subclass_sym = self.class_strings.get_or_intern(subclass);
+        let subclass_sym = self.symbols.intern(subclass);
         trace!(
             "Got subclass: object_id={:?}, subclass={:?}, subclass_sym={:?}",
             obj_id,
@@ -236,14 +306,3 @@ impl ObjectsCacheBuilder {
         Ok(subclass_sym)
     }
 }
-
-impl Default for ObjectsCacheBuilder {
-    fn default() -> Self {
-        Self {
-            obj_id_to_node_id: Default::default(),
-            meta: Default::default(),
-            class_strings: StringInterner::new(),
-            document_nodes: Default::default(),
-        }
-    }
-}
@@ -0,0 +1,156 @@
+//! `Character` object.
+//!
+//! A character is a HumanIK character definition, which maps slots of the
+//! humanoid skeleton (such as `Hips` and `LeftArm`) to models.
+//!
+//! Each model is connected to the character with an object-property
+//! connection labelled `<slot>Link`, and the offsets of the link are stored
+//! in the `<slot>TOffset`, `<slot>ROffset`, and `<slot>SOffset` properties.
+//!
+//! ```
+//! # #[cfg(feature = "edit")]
+//! # fn main() -> Result<(), anyhow::Error> {
+//! use fbxcel_dom::v7400::edit::{DocumentBuilder, ModelKind};
+//!
+//! let mut builder = DocumentBuilder::new();
+//! let hips = builder.add_model("pelvis", ModelKind::LimbNode, None);
+//! let spine = builder.add_model("spine_01", ModelKind::LimbNode, Some(hips));
+//! let edit = builder.edit_mut();
+//! let character = edit.add_object("Character", "Hero", "Character", "");
+//! edit.connect_to_property(hips, character, "HipsLink");
+//! edit.connect_to_property(spine, character, "SpineLink");
+//! let doc = builder.build()?;
+//!
+//! let character = doc.characters().next().expect("Character should exist");
+//! let links: Vec<_> = character
+//!     .links()
+//!     .map(|link| (link.slot(), link.model().name()))
+//!     .collect();
+//! assert_eq!(links, [("Hips", Some("pelvis")), ("Spine", Some("spine_01"))]);
+//! assert_eq!(character.link("Spine").and_then(|link| link.model().name()), Some("spine_01"));
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "edit"))]
+//! # fn main() {}
+//! ```
+
+use anyhow::Error;
+use mint::Vector3;
+
+use crate::v7400::{
+    object::{
+        model,
+        property::{loaders::MintLoader, ObjectProperties},
+        ObjectHandle, TypedObjectHandle,
+    },
+    ErrorLocation,
+};
+
+define_object_subtype! {
+    /// `Character` node handle.
+    CharacterHandle: ObjectHandle
+}
+
+impl<'a> CharacterHandle<'a> {
+    /// Returns an iterator of the links from slots to models.
+    ///
+    /// Links are in the order of the connections.
+    pub fn links(&self) -> impl Iterator<Item = CharacterLink<'a>> {
+        let character = *self;
+        self.source_objects().filter_map(move |obj| {
+            let slot = obj.label()?.strip_suffix("Link")?;
+            let model = match obj.object_handle()?.get_typed() {
+                TypedObjectHandle::Model(o) => o,
+                _ => return None,
+            };
+            Some(CharacterLink {
+                character,
+                slot,
+                model,
+            })
+        })
+    }
+
+    /// Returns the link of the given slot (such as `Hips`), if available.
+    pub fn link(&self, slot: &str) -> Option<CharacterLink<'a>> {
+        self.links().find(|link| link.slot == slot)
+    }
+
+    /// Returns properties.
+    pub fn properties(&self) -> ObjectProperties<'a> {
+        self.properties_by_native_typename("FbxCharacter")
+    }
+}
+
+/// Link from a character slot to a model.
+#[derive(Debug, Clone, Copy)]
+pub struct CharacterLink<'a> {
+    /// Character.
+    character: CharacterHandle<'a>,
+    /// Slot name.
+    slot: &'a str,
+    /// Model.
+    model: model::TypedModelHandle<'a>,
+}
+
+impl<'a> CharacterLink<'a> {
+    /// Returns the character.
+    pub fn character(&self) -> CharacterHandle<'a> {
+        self.character
+    }
+
+    /// Returns the slot name, such as `Hips` and `LeftArm`.
+    pub fn slot(&self) -> &'a str {
+        self.slot
+    }
+
+    /// Returns the linked model.
+    pub fn model(&self) -> model::TypedModelHandle<'a> {
+        self.model
+    }
+
+    /// Returns the translation offset.
+    ///
+    /// Returns zero if the value is not set.
+    pub fn translation_offset(&self) -> Result<Vector3<f64>, Error> {
+        self.offset("TOffset", "translation offset", 0.0)
+    }
+
+    /// Returns the rotation offset in degrees.
+    ///
+    /// Returns zero if the value is not set.
+    pub fn rotation_offset(&self) -> Result<Vector3<f64>, Error> {
+        self.offset("ROffset", "rotation offset", 0.0)
+    }
+
+    /// Returns the scaling offset.
+    ///
+    /// Returns one if the value is not set.
+    pub fn scaling_offset(&self) -> Result<Vector3<f64>, Error> {
+        self.offset("SOffset", "scaling offset", 1.0)
+    }
+
+    /// Loads the `<slot><suffix>` property.
+    fn offset(&self, suffix: &str, description: &str, default: f64) -> Result<Vector3<f64>, Error> {
+        let name = format!("{}{}", self.slot, suffix);
+        let value = self
+            .character
+            .properties()
+            .get_property(&name)
+            .map(|p| p.load_value(MintLoader::<Vector3<f64>>::new()))
+            .transpose()
+            .map_err(|e| {
+                Error::from(crate::v7400::Error::PropertyLoad {
+                    location: ErrorLocation::object(self.character.object_id())
+                        .with_property(&name),
+                    description: format!("character link {}", description),
+                    source: e,
+                })
+            })?;
+        Ok(value.unwrap_or(Vector3 {
+            x: default,
+            y: default,
+            z: default,
+        }))
+    }
+}
@@ -0,0 +1,165 @@
+//! `Collection`, `CollectionExclusive`, and `Container` objects.
+//!
+//! These objects organize the scene: members are connected to the
+//! collection.
+//! Display layers are exclusive collections, and an object belongs to at most
+//! one display layer.
+//!
+//! ```
+//! # #[cfg(feature = "edit")]
+//! # fn main() -> Result<(), anyhow::Error> {
+//! use fbxcel_dom::v7400::edit::{DocumentBuilder, ModelKind};
+//!
+//! let mut builder = DocumentBuilder::new();
+//! let wall = builder.add_model("Wall", ModelKind::Null, None);
+//! let pipe = builder.add_model("Pipe", ModelKind::Null, None);
+//! builder.add_display_layer("Structure", [0.8, 0.2, 0.2], &[wall])?;
+//! builder.add_display_layer("Plumbing", [0.2, 0.2, 0.8], &[pipe])?;
+//! let doc = builder.build()?;
+//!
+//! let layer = doc
+//!     .display_layers()
+//!     .find(|layer| layer.name() == Some("Structure"))
+//!     .expect("Display layer should exist");
+//! assert!(layer.properties().show_or_default()?);
+//! let models: Vec<_> = layer.models().filter_map(|model| model.name()).collect();
+//! assert_eq!(models, ["Wall"]);
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "edit"))]
+//! # fn main() {}
+//! ```
+
+use rgb::RGB;
+
+use crate::v7400::object::{
+    model,
+    property::{
+        loaders::{PrimitiveLoader, RgbLoader},
+        ObjectProperties,
+    },
+    ObjectHandle, TypedObjectHandle,
+};
+
+/// Implements member getters for collection-like handles.
+macro_rules! impl_member_getters {
+    ($ty:ident) => {
+        impl<'a> $ty<'a> {
+            /// Returns an iterator of the member objects.
+            pub fn members(&self) -> impl Iterator<Item = ObjectHandle<'a>> {
+                self.source_objects()
+                    .filter(|obj| obj.label().is_none())
+                    .filter_map(|obj| obj.object_handle())
+            }
+
+            /// Returns an iterator of the member models.
+            pub fn models(&self) -> impl Iterator<Item = model::TypedModelHandle<'a>> {
+                self.members().filter_map(|obj| match obj.get_typed() {
+                    TypedObjectHandle::Model(o) => Some(o),
+                    _ => None,
+                })
+            }
+        }
+    };
+}
+
+define_object_subtype! {
+    /// `Collection` node handle.
+    CollectionHandle: ObjectHandle
+}
+
+impl_member_getters!(CollectionHandle);
+
+define_object_subtype! {
+    /// `CollectionExclusive` node handle.
+    ///
+    /// Display layers are objects of this type with `DisplayLayer` class.
+    CollectionExclusiveHandle: ObjectHandle
+}
+
+impl_member_getters!(CollectionExclusiveHandle);
+
+impl<'a> CollectionExclusiveHandle<'a> {
+    /// Returns whether the collection is a display layer.
+    pub fn is_display_layer(&self) -> bool {
+        self.class() == "DisplayLayer"
+    }
+
+    /// Returns properties.
+    pub fn properties(&self) -> DisplayLayerProperties<'a> {
+        DisplayLayerProperties {
+            properties: self.properties_by_native_typename("FbxDisplayLayer"),
+        }
+    }
+}
+
+/// Proxy type to display layer properties.
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayLayerProperties<'a> {
+    /// Properties.
+    properties: ObjectProperties<'a>,
+}
+
+impl<'a> DisplayLayerProperties<'a> {
+    impl_prop_proxy_getters! {
+        /// Returns layer color.
+        color -> RGB<f64> {
+            name = "Color",
+            loader = RgbLoader::<RGB<f64>>::new(),
+            description = "display layer color",
+            default: {
+                /// Returns layer color.
+                ///
+                /// Returns default if the value is not set.
+                color_or_default = RGB::from([0.8; 3])
+            }
+        }
+
+        /// Returns whether the members are shown.
+        show -> bool {
+            name = "Show",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "show flag",
+            default: {
+                /// Returns whether the members are shown.
+                ///
+                /// Returns default if the value is not set.
+                show_or_default = true
+            }
+        }
+
+        /// Returns whether the members are frozen (not selectable).
+        freeze -> bool {
+            name = "Freeze",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "freeze flag",
+            default: {
+                /// Returns whether the members are frozen (not selectable).
+                ///
+                /// Returns default if the value is not set.
+                freeze_or_default = false
+            }
+        }
+
+        /// Returns whether the members are displayed as bounding boxes.
+        lod_box -> bool {
+            name = "LODBox",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "LOD box flag",
+            default: {
+                /// Returns whether the members are displayed as bounding
+                /// boxes.
+                ///
+                /// Returns default if the value is not set.
+                lod_box_or_default = false
+            }
+        }
+    }
+}
+
+define_object_subtype! {
+    /// `Container` node handle.
+    ContainerHandle: ObjectHandle
+}
+
+impl_member_getters!(ContainerHandle);
@@ -0,0 +1,134 @@
+//! Object connections.
+
+use crate::v7400::{
+    connection::Connection,
+    object::{ObjectHandle, ObjectId},
+    Document,
+};
+
+pub use crate::v7400::connection::ConnectedNodeType;
+
+/// Kind of a connection.
+///
+/// The first part is the source, and the second part is the destination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectionKind {
+    /// Object to object (`OO`).
+    ObjectToObject,
+    /// Object to property of an object (`OP`).
+    ObjectToProperty,
+    /// Property of an object to object (`PO`).
+    PropertyToObject,
+    /// Property of an object to property of an object (`PP`).
+    PropertyToProperty,
+}
+
+impl ConnectionKind {
+    /// Creates a new `ConnectionKind` from the source and destination types.
+    fn new(source: ConnectedNodeType, destination: ConnectedNodeType) -> Self {
+        match (source, destination) {
+            (ConnectedNodeType::Object, ConnectedNodeType::Object) => {
+                ConnectionKind::ObjectToObject
+            }
+            (ConnectedNodeType::Object, ConnectedNodeType::Property) => {
+                ConnectionKind::ObjectToProperty
+            }
+            (ConnectedNodeType::Property, ConnectedNodeType::Object) => {
+                ConnectionKind::PropertyToObject
+            }
+            (ConnectedNodeType::Property, ConnectedNodeType::Property) => {
+                ConnectionKind::PropertyToProperty
+            }
+        }
+    }
+
+    /// Returns the type of the source node.
+    pub fn source_type(self) -> ConnectedNodeType {
+        match self {
+            ConnectionKind::ObjectToObject | ConnectionKind::ObjectToProperty => {
+                ConnectedNodeType::Object
+            }
+            ConnectionKind::PropertyToObject | ConnectionKind::PropertyToProperty => {
+                ConnectedNodeType::Property
+            }
+        }
+    }
+
+    /// Returns the type of the destination node.
+    pub fn destination_type(self) -> ConnectedNodeType {
+        match self {
+            ConnectionKind::ObjectToObject | ConnectionKind::PropertyToObject => {
+                ConnectedNodeType::Object
+            }
+            ConnectionKind::ObjectToProperty | ConnectionKind::PropertyToProperty => {
+                ConnectedNodeType::Property
+            }
+        }
+    }
+}
+
+/// Connection record (provided by `C` node).
+#[derive(Debug, Clone, Copy)]
+pub struct ObjectConnection<'a> {
+    /// Connection.
+    connection: &'a Connection,
+    /// Document.
+    doc: &'a Document,
+}
+
+impl<'a> ObjectConnection<'a> {
+    /// Creates a new `ObjectConnection`.
+    pub(crate) fn new(connection: &'a Connection, doc: &'a Document) -> Self {
+        Self { connection, doc }
+    }
+
+    /// Returns the connection kind.
+    pub fn kind(&self) -> ConnectionKind {
+        ConnectionKind::new(
+            self.connection.source_type(),
+            self.connection.destination_type(),
+        )
+    }
+
+    /// Returns the source object ID.
+    pub fn source_id(&self) -> ObjectId {
+        self.connection.source_id()
+    }
+
+    /// Returns the destination object ID.
+    pub fn destination_id(&self) -> ObjectId {
+        self.connection.destination_id()
+    }
+
+    /// Returns the source object handle if the corresponding object node is
+    /// available.
+    pub fn source_object(&self) -> Option<ObjectHandle<'a>> {
+        self.source_id().to_object_handle(self.doc)
+    }
+
+    /// Returns the destination object handle if the corresponding object
+    /// node is available.
+    pub fn destination_object(&self) -> Option<ObjectHandle<'a>> {
+        self.destination_id().to_object_handle(self.doc)
+    }
+
+    /// Returns the connection label if available.
+    pub fn label(&self) -> Option<&'a str> {
+        self.connection
+            .label_sym()
+            .map(|sym| self.doc.resolve_registered(sym))
+    }
+
+    /// Returns the property name of the connection if it is connected to or
+    /// from a property.
+    ///
+    /// For `OP` connections, this is the name of the destination property
+    /// (such as `DiffuseColor` of a material).
+    /// For `PO` and `PP` connections, this is the label of the connection.
+    pub fn property_name(&self) -> Option<&'a str> {
+        match self.kind() {
+            ConnectionKind::ObjectToObject => None,
+            _ => self.label(),
+        }
+    }
+}
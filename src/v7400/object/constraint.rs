@@ -0,0 +1,460 @@
+//! `Constraint` object.
+//!
+//! Constraints are exposed for inspection only, and this crate does not
+//! evaluate them.
+//!
+//! The constrained object and the source objects are connected to the
+//! constraint with object-property connections, and the weights and offsets
+//! of each source are stored in properties named after the source (such as
+//! `Target.Weight`).
+//!
+//! ```
+//! # #[cfg(feature = "edit")]
+//! # fn main() -> Result<(), anyhow::Error> {
+//! use fbxcel_dom::v7400::{
+//!     edit::{DocumentBuilder, ModelKind},
+//!     object::{constraint::TypedConstraintHandle, property::OwnedPropertyValue},
+//! };
+//!
+//! let mut builder = DocumentBuilder::new();
+//! let target = builder.add_model("Target", ModelKind::Null, None);
+//! let follower = builder.add_model("Follower", ModelKind::Null, None);
+//! let edit = builder.edit_mut();
+//! let constraint = edit.add_object("Constraint", "Follow", "Constraint", "Position");
+//! edit.insert_property(
+//!     constraint,
+//!     "Target.Weight",
+//!     "Number",
+//!     "",
+//!     "A+",
+//!     OwnedPropertyValue::F64(50.0),
+//! )?;
+//! edit.connect_to_property(follower, constraint, "Constrained Object");
+//! edit.connect_to_property(target, constraint, "Source (Objects)");
+//! let doc = builder.build()?;
+//!
+//! let constraint = doc.constraints().next().expect("Constraint should exist");
+//! assert!(matches!(constraint, TypedConstraintHandle::Position(_)));
+//! let constrained = constraint.constrained_object().and_then(|obj| obj.name());
+//! assert_eq!(constrained, Some("Follower"));
+//! let sources: Vec<_> = constraint
+//!     .source_weights()?
+//!     .into_iter()
+//!     .map(|(source, weight)| (source.name(), weight))
+//!     .collect();
+//! assert_eq!(sources, [(Some("Target"), 50.0)]);
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "edit"))]
+//! # fn main() {}
+//! ```
+
+use anyhow::Error;
+use mint::Vector3;
+
+use crate::v7400::{
+    object::{
+        property::{
+            loaders::{MintLoader, PrimitiveLoader},
+            LoadProperty, ObjectProperties,
+        },
+        ObjectHandle,
+    },
+    ErrorLocation,
+};
+
+/// Labels of the connections from constrained objects.
+const CONSTRAINED_LABELS: &[&str] = &[
+    "Constrained Object",
+    "Constrained object (Child)",
+    "First Joint",
+];
+
+/// Labels of the connections from source objects.
+const SOURCE_LABELS: &[&str] = &["Source (Objects)", "Source (Parent)", "Aim At Objects"];
+
+define_typed_handle! {
+    /// Typed constraint handle.
+    TypedConstraintHandle(ConstraintHandle) {
+        /// Aim.
+        ("Constraint", "Aim") => Aim(AimConstraintHandle),
+        /// Parent.
+        ("Constraint", "Parent-Child") => Parent(ParentConstraintHandle),
+        /// Position.
+        ("Constraint", "Position") => Position(PositionConstraintHandle),
+        /// Rotation.
+        ("Constraint", "Rotation") => Rotation(RotationConstraintHandle),
+        /// Scale.
+        ("Constraint", "Scale") => Scale(ScaleConstraintHandle),
+        /// Single chain IK.
+        ("Constraint", "Single Chain IK") => SingleChainIk(SingleChainIkConstraintHandle),
+    }
+}
+
+define_object_subtype! {
+    /// `Constraint` node handle.
+    ConstraintHandle: ObjectHandle
+}
+
+impl<'a> ConstraintHandle<'a> {
+    /// Returns the constrained object.
+    ///
+    /// For single chain IK constraints, this is the first joint.
+    pub fn constrained_object(&self) -> Option<ObjectHandle<'a>> {
+        self.objects_by_labels(CONSTRAINED_LABELS).next()
+    }
+
+    /// Returns an iterator of the source objects.
+    ///
+    /// These are the targets of aim constraints, and the parents of parent
+    /// constraints.
+    /// Single chain IK constraints have the effector instead, see
+    /// [`SingleChainIkConstraintHandle::effector`].
+    pub fn sources(&self) -> impl Iterator<Item = ObjectHandle<'a>> {
+        self.objects_by_labels(SOURCE_LABELS)
+    }
+
+    /// Returns the weight of the source in percent.
+    ///
+    /// Returns `100.0` if the value is not set.
+    pub fn source_weight(&self, source: &ObjectHandle<'a>) -> Result<f64, Error> {
+        self.source_property(source, "Weight", PrimitiveLoader::<f64>::new(), "weight")
+            .map(|v| v.unwrap_or(100.0))
+    }
+
+    /// Returns the source objects and their weights in percent.
+    pub fn source_weights(&self) -> Result<Vec<(ObjectHandle<'a>, f64)>, Error> {
+        self.sources()
+            .map(|source| Ok((source, self.source_weight(&source)?)))
+            .collect()
+    }
+
+    /// Returns properties.
+    pub fn properties(&self) -> ConstraintProperties<'a> {
+        ConstraintProperties {
+            properties: self.properties_by_native_typename("FbxConstraint"),
+        }
+    }
+
+    /// Returns an iterator of the objects connected with any of the given
+    /// labels.
+    fn objects_by_labels(
+        &self,
+        labels: &'static [&'static str],
+    ) -> impl Iterator<Item = ObjectHandle<'a>> {
+        self.source_objects()
+            .filter(move |obj| obj.label().map_or(false, |label| labels.contains(&label)))
+            .filter_map(|obj| obj.object_handle())
+    }
+
+    /// Loads the per-source property `<source name>.<suffix>`.
+    ///
+    /// Returns `Ok(None)` if the source has no name or the property is not
+    /// set.
+    fn source_property<V>(
+        &self,
+        source: &ObjectHandle<'a>,
+        suffix: &str,
+        loader: V,
+        description: &str,
+    ) -> Result<Option<V::Value>, Error>
+    where
+        V: LoadProperty<'a, Error = Error>,
+    {
+        let name = match source.name() {
+            Some(name) => format!("{}.{}", name, suffix),
+            None => return Ok(None),
+        };
+        self.properties_by_native_typename("FbxConstraint")
+            .get_property(&name)
+            .map(|p| p.load_value(loader))
+            .transpose()
+            .map_err(|e| {
+                Error::from(crate::v7400::Error::PropertyLoad {
+                    location: ErrorLocation::object(self.object_id()).with_property(&name),
+                    description: format!("source {}", description),
+                    source: e,
+                })
+            })
+    }
+}
+
+/// Proxy type to constraint properties.
+#[derive(Debug, Clone, Copy)]
+pub struct ConstraintProperties<'a> {
+    /// Properties.
+    properties: ObjectProperties<'a>,
+}
+
+impl<'a> ConstraintProperties<'a> {
+    impl_prop_proxy_getters! {
+        /// Returns whether the constraint is active.
+        active -> bool {
+            name = "Active",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "active flag",
+            default: {
+                /// Returns whether the constraint is active.
+                ///
+                /// Returns default if the value is not set.
+                active_or_default = true
+            }
+        }
+
+        /// Returns whether the constraint is locked.
+        lock -> bool {
+            name = "Lock",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "lock flag",
+            default: {
+                /// Returns whether the constraint is locked.
+                ///
+                /// Returns default if the value is not set.
+                lock_or_default = false
+            }
+        }
+
+        /// Returns the weight of the whole constraint in percent.
+        weight -> f64 {
+            name = "Weight",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "constraint weight",
+            default: {
+                /// Returns the weight of the whole constraint in percent.
+                ///
+                /// Returns default if the value is not set.
+                weight_or_default = 100.0
+            }
+        }
+
+        /// Returns the translation offset of position constraints.
+        translation -> Vector3<f64> {
+            name = "Translation",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "translation offset",
+            default: {
+                /// Returns the translation offset of position constraints.
+                ///
+                /// Returns default if the value is not set.
+                translation_or_default = Vector3 { x: 0.0, y: 0.0, z: 0.0 }
+            }
+        }
+
+        /// Returns the rotation offset of rotation constraints in degrees.
+        rotation -> Vector3<f64> {
+            name = "Rotation",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "rotation offset",
+            default: {
+                /// Returns the rotation offset of rotation constraints in
+                /// degrees.
+                ///
+                /// Returns default if the value is not set.
+                rotation_or_default = Vector3 { x: 0.0, y: 0.0, z: 0.0 }
+            }
+        }
+
+        /// Returns the scaling offset of scale constraints.
+        scaling -> Vector3<f64> {
+            name = "Scaling",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "scaling offset",
+            default: {
+                /// Returns the scaling offset of scale constraints.
+                ///
+                /// Returns default if the value is not set.
+                scaling_or_default = Vector3 { x: 1.0, y: 1.0, z: 1.0 }
+            }
+        }
+
+        /// Returns the rotation offset of aim constraints in degrees.
+        rotation_offset -> Vector3<f64> {
+            name = "Rotation Offset",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "aim rotation offset",
+            default: {
+                /// Returns the rotation offset of aim constraints in degrees.
+                ///
+                /// Returns default if the value is not set.
+                rotation_offset_or_default = Vector3 { x: 0.0, y: 0.0, z: 0.0 }
+            }
+        }
+
+        /// Returns the aim vector of aim constraints.
+        aim_vector -> Vector3<f64> {
+            name = "Aim Vector",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "aim vector",
+            default: {
+                /// Returns the aim vector of aim constraints.
+                ///
+                /// Returns default if the value is not set.
+                aim_vector_or_default = Vector3 { x: 1.0, y: 0.0, z: 0.0 }
+            }
+        }
+
+        /// Returns the up vector of aim constraints.
+        up_vector -> Vector3<f64> {
+            name = "Up Vector",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "up vector",
+            default: {
+                /// Returns the up vector of aim constraints.
+                ///
+                /// Returns default if the value is not set.
+                up_vector_or_default = Vector3 { x: 0.0, y: 1.0, z: 0.0 }
+            }
+        }
+
+        /// Returns the world up vector of aim constraints.
+        world_up_vector -> Vector3<f64> {
+            name = "World Up Vector",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "world up vector",
+            default: {
+                /// Returns the world up vector of aim constraints.
+                ///
+                /// Returns default if the value is not set.
+                world_up_vector_or_default = Vector3 { x: 0.0, y: 1.0, z: 0.0 }
+            }
+        }
+
+        /// Returns the raw world up type of aim constraints.
+        world_up_type -> i32 {
+            name = "World Up Type",
+            loader = PrimitiveLoader::<i32>::new(),
+            description = "world up type",
+            default: {
+                /// Returns the raw world up type of aim constraints.
+                ///
+                /// Returns default if the value is not set.
+                world_up_type_or_default = 0
+            }
+        }
+
+        /// Returns the twist of single chain IK constraints in degrees.
+        twist -> f64 {
+            name = "Twist",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "IK twist",
+            default: {
+                /// Returns the twist of single chain IK constraints in degrees.
+                ///
+                /// Returns default if the value is not set.
+                twist_or_default = 0.0
+            }
+        }
+
+        /// Returns the pole vector of single chain IK constraints.
+        pole_vector -> Vector3<f64> {
+            name = "Pole Vector",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "IK pole vector",
+            default: {
+                /// Returns the pole vector of single chain IK constraints.
+                ///
+                /// Returns default if the value is not set.
+                pole_vector_or_default = Vector3 { x: 0.0, y: 1.0, z: 0.0 }
+            }
+        }
+    }
+}
+
+define_object_subtype! {
+    /// `Constraint` node handle (aim).
+    AimConstraintHandle: ConstraintHandle
+}
+
+impl<'a> AimConstraintHandle<'a> {
+    /// Returns the world up object.
+    pub fn world_up_object(&self) -> Option<ObjectHandle<'a>> {
+        self.objects_by_labels(&["World Up Object"]).next()
+    }
+}
+
+define_object_subtype! {
+    /// `Constraint` node handle (parent).
+    ParentConstraintHandle: ConstraintHandle
+}
+
+impl<'a> ParentConstraintHandle<'a> {
+    /// Returns the translation offset from the source.
+    ///
+    /// Returns zero if the value is not set.
+    pub fn translation_offset(&self, source: &ObjectHandle<'a>) -> Result<Vector3<f64>, Error> {
+        self.source_property(
+            source,
+            "Offset T",
+            MintLoader::<Vector3<f64>>::new(),
+            "translation offset",
+        )
+        .map(|v| {
+            v.unwrap_or(Vector3 {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            })
+        })
+    }
+
+    /// Returns the rotation offset from the source in degrees.
+    ///
+    /// Returns zero if the value is not set.
+    pub fn rotation_offset(&self, source: &ObjectHandle<'a>) -> Result<Vector3<f64>, Error> {
+        self.source_property(
+            source,
+            "Offset R",
+            MintLoader::<Vector3<f64>>::new(),
+            "rotation offset",
+        )
+        .map(|v| {
+            v.unwrap_or(Vector3 {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            })
+        })
+    }
+}
+
+define_object_subtype! {
+    /// `Constraint` node handle (position).
+    PositionConstraintHandle: ConstraintHandle
+}
+
+define_object_subtype! {
+    /// `Constraint` node handle (rotation).
+    RotationConstraintHandle: ConstraintHandle
+}
+
+define_object_subtype! {
+    /// `Constraint` node handle (scale).
+    ScaleConstraintHandle: ConstraintHandle
+}
+
+define_object_subtype! {
+    /// `Constraint` node handle (single chain IK).
+    SingleChainIkConstraintHandle: ConstraintHandle
+}
+
+impl<'a> SingleChainIkConstraintHandle<'a> {
+    /// Returns the first joint of the chain.
+    pub fn first_joint(&self) -> Option<ObjectHandle<'a>> {
+        self.objects_by_labels(&["First Joint"]).next()
+    }
+
+    /// Returns the end joint of the chain.
+    pub fn end_joint(&self) -> Option<ObjectHandle<'a>> {
+        self.objects_by_labels(&["End Joint"]).next()
+    }
+
+    /// Returns the effector.
+    pub fn effector(&self) -> Option<ObjectHandle<'a>> {
+        self.objects_by_labels(&["Effector"]).next()
+    }
+
+    /// Returns an iterator of the pole vector objects.
+    pub fn pole_vector_objects(&self) -> impl Iterator<Item = ObjectHandle<'a>> {
+        self.objects_by_labels(&["Pole Vector Objects"])
+    }
+}
@@ -3,14 +3,18 @@
 use crate::v7400::object::ObjectHandle;
 
 pub use self::{
-    blendshape::BlendShapeHandle, blendshapechannel::BlendShapeChannelHandle,
-    cluster::ClusterHandle, skin::SkinHandle,
+    blendshape::BlendShapeHandle,
+    blendshapechannel::BlendShapeChannelHandle,
+    cluster::ClusterHandle,
+    skin::SkinHandle,
+    vertexcache::{VertexCacheDeformerHandle, VertexCacheDeformerProperties},
 };
 
 mod blendshape;
 mod blendshapechannel;
 mod cluster;
 mod skin;
+mod vertexcache;
 
 define_typed_handle! {
     /// Typed deformer handle.
@@ -19,6 +23,8 @@ define_typed_handle! {
         ("Deformer", "BlendShape") => BlendShape(BlendShapeHandle),
         /// Skin.
         ("Deformer", "Skin") => Skin(SkinHandle),
+        /// VertexCacheDeformer.
+        ("Deformer", "VertexCacheDeformer") => VertexCache(VertexCacheDeformerHandle),
     }
 }
 
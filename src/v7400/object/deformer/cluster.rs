@@ -1,12 +1,20 @@
 //! `SubDeformer` object (cluster).
 
 use anyhow::{format_err, Error};
+use mint::ColumnMatrix4;
 
-use crate::v7400::object::{
-    deformer::{self, SubDeformerHandle},
-    TypedObjectHandle,
+use crate::v7400::{
+    data::transform::math::Mat4,
+    object::{
+        deformer::{self, SubDeformerHandle},
+        model, TypedObjectHandle,
+    },
+    Error as DomError, ErrorLocation,
 };
 
+#[cfg(feature = "glam")]
+use crate::v7400::data::scalar;
+
 define_object_subtype! {
     /// `SubDeformer` node handle (cluster).
     ClusterHandle: SubDeformerHandle
@@ -30,4 +38,144 @@ impl<'a> ClusterHandle<'a> {
                 )
             })
     }
+
+    /// Returns an iterator of parent deformer skins.
+    ///
+    /// Usually a cluster has only one parent skin (see [`skin`](#method.skin)).
+    pub fn skins(&self) -> impl Iterator<Item = deformer::SkinHandle<'a>> {
+        self.destination_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Deformer(deformer::TypedDeformerHandle::Skin(o)) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns the child model used as a bone (link).
+    pub fn link(&self) -> Option<model::TypedModelHandle<'a>> {
+        self.source_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .find_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Model(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns the control point indices influenced by the bone.
+    ///
+    /// Returns an empty slice if the cluster has no `Indexes` node.
+    pub fn indices(&self) -> Result<&'a [i32], Error> {
+        match self.node().first_child_by_name("Indexes") {
+            Some(node) => self
+                .document()
+                .attribute(&node, 0)?
+                .ok_or_else(|| DomError::missing_attribute(&node, 0))?
+                .get_arr_i32_or_type()
+                .map_err(|ty| {
+                    DomError::type_mismatch(
+                        ErrorLocation::node(&node).with_object(self.object_id()),
+                        "`[i32]`",
+                        ty,
+                    )
+                    .into()
+                }),
+            None => Ok(&[]),
+        }
+    }
+
+    /// Returns the weights for the control points of [`indices`](#method.indices).
+    ///
+    /// Returns an empty slice if the cluster has no `Weights` node.
+    pub fn weights(&self) -> Result<&'a [f64], Error> {
+        match self.node().first_child_by_name("Weights") {
+            Some(node) => self
+                .document()
+                .attribute(&node, 0)?
+                .ok_or_else(|| DomError::missing_attribute(&node, 0))?
+                .get_arr_f64_or_type()
+                .map_err(|ty| {
+                    DomError::type_mismatch(
+                        ErrorLocation::node(&node).with_object(self.object_id()),
+                        "`[f64]`",
+                        ty,
+                    )
+                    .into()
+                }),
+            None => Ok(&[]),
+        }
+    }
+
+    /// Returns the global transform of the mesh at the bind time.
+    pub fn transform(&self) -> Result<ColumnMatrix4<f64>, Error> {
+        self.matrix("Transform")
+    }
+
+    /// Returns the global transform of the bone at the bind time.
+    pub fn transform_link(&self) -> Result<ColumnMatrix4<f64>, Error> {
+        self.matrix("TransformLink")
+    }
+
+    /// Returns the skinning (inverse bind) matrix.
+    ///
+    /// This is `transform_link^-1 * transform`, which transforms the mesh
+    /// into the bone space at the bind time.
+    pub fn inverse_bind_matrix(&self) -> Result<ColumnMatrix4<f64>, Error> {
+        let link = Mat4::from(self.transform_link()?)
+            .inverse()
+            .ok_or_else(|| format_err!("`TransformLink` of the cluster is not invertible"))?;
+        Ok((link * Mat4::from(self.transform()?)).into())
+    }
+
+    /// Returns the global transform of the mesh at the bind time as
+    /// `glam::Affine3A`.
+    #[cfg(feature = "glam")]
+    pub fn transform_glam(&self) -> Result<glam::Affine3A, Error> {
+        self.transform().map(scalar::glam_affine3a)
+    }
+
+    /// Returns the global transform of the bone at the bind time as
+    /// `glam::Affine3A`.
+    #[cfg(feature = "glam")]
+    pub fn transform_link_glam(&self) -> Result<glam::Affine3A, Error> {
+        self.transform_link().map(scalar::glam_affine3a)
+    }
+
+    /// Returns the skinning (inverse bind) matrix as `glam::Mat4`.
+    ///
+    /// See [`inverse_bind_matrix`](#method.inverse_bind_matrix).
+    #[cfg(feature = "glam")]
+    pub fn inverse_bind_matrix_glam(&self) -> Result<glam::Mat4, Error> {
+        self.inverse_bind_matrix().map(scalar::glam_mat4)
+    }
+
+    /// Loads the column-major matrix from the child node with the given name.
+    fn matrix(&self, name: &str) -> Result<ColumnMatrix4<f64>, Error> {
+        let node = self
+            .node()
+            .first_child_by_name(name)
+            .ok_or_else(|| DomError::missing_node(&self.node(), name))?;
+        let location = || ErrorLocation::node(&node).with_object(self.object_id());
+        let values = self
+            .document()
+            .attribute(&node, 0)?
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?
+            .get_arr_f64_or_type()
+            .map_err(|ty| DomError::type_mismatch(location(), "`[f64]`", ty))?;
+        if values.len() != 16 {
+            return Err(DomError::type_mismatch(
+                location(),
+                "`[f64; 16]`",
+                format_args!("`[f64; {}]`", values.len()),
+            )
+            .into());
+        }
+        let mut m = [[0.0; 4]; 4];
+        for (col, chunk) in m.iter_mut().zip(values.chunks_exact(4)) {
+            col.copy_from_slice(chunk);
+        }
+
+        Ok(ColumnMatrix4::from(m))
+    }
 }
@@ -0,0 +1,105 @@
+//! `Deformer` object (vertex cache).
+
+use anyhow::{format_err, Error};
+
+use crate::v7400::object::{
+    deformer::DeformerHandle,
+    geometry, geometrycache,
+    property::{
+        loaders::{BorrowedStringLoader, PrimitiveLoader},
+        ObjectProperties,
+    },
+    TypedObjectHandle,
+};
+
+define_object_subtype! {
+    /// `Deformer` node handle (vertex cache).
+    VertexCacheDeformerHandle: DeformerHandle
+}
+
+impl<'a> VertexCacheDeformerHandle<'a> {
+    /// Returns the parent geometry mesh.
+    pub fn mesh(&self) -> Result<geometry::MeshHandle<'a>, Error> {
+        self.destination_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .find_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Geometry(geometry::TypedGeometryHandle::Mesh(o)) => Some(o),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                format_err!(
+                    "Vertex cache deformer object should have a parent geometry mesh: object={:?}",
+                    self
+                )
+            })
+    }
+
+    /// Returns the child cache, if available.
+    pub fn cache(&self) -> Option<geometrycache::CacheHandle<'a>> {
+        self.source_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .find_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Cache(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns properties.
+    pub fn properties(&self) -> VertexCacheDeformerProperties<'a> {
+        VertexCacheDeformerProperties {
+            properties: self.properties_by_native_typename("FbxVertexCacheDeformer"),
+        }
+    }
+}
+
+/// Proxy type to vertex cache deformer properties.
+#[derive(Debug, Clone, Copy)]
+pub struct VertexCacheDeformerProperties<'a> {
+    /// Properties.
+    properties: ObjectProperties<'a>,
+}
+
+impl<'a> VertexCacheDeformerProperties<'a> {
+    impl_prop_proxy_getters! {
+        /// Returns whether the deformer is active.
+        active -> bool {
+            name = "Active",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "active flag",
+            default: {
+                /// Returns whether the deformer is active.
+                ///
+                /// Returns default if the value is not set.
+                active_or_default = true
+            }
+        }
+
+        /// Returns the name of the channel in the cache file.
+        channel -> &'a str {
+            name = "Channel",
+            loader = BorrowedStringLoader::new(),
+            description = "cache channel name",
+            default: {
+                /// Returns the name of the channel in the cache file.
+                ///
+                /// Returns default if the value is not set.
+                channel_or_default = ""
+            }
+        }
+
+        /// Returns the name of the cache set.
+        cache_set -> &'a str {
+            name = "CacheSet",
+            loader = BorrowedStringLoader::new(),
+            description = "cache set name",
+            default: {
+                /// Returns the name of the cache set.
+                ///
+                /// Returns default if the value is not set.
+                cache_set_or_default = ""
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! User-defined object types.
+//!
+//! Downstream crates can define their own typed handles for object classes
+//! which are not supported by `fbxcel_dom` (for example, objects created by
+//! proprietary plugins).
+//!
+//! To do this, implement [`ObjectSubtypeHandle`] for the handle type, and
+//! register an [`ExtensionType`] to the [`Loader`] by
+//! [`Loader::register_extension`].
+//! Objects matching the registered type and not supported by `fbxcel_dom`
+//! are returned as [`TypedObjectHandle::Extension`], and can be converted to
+//! the user type by [`ExtensionObjectHandle::downcast`].
+//!
+//! [`ExtensionObjectHandle::downcast`]:
+//!     struct.ExtensionObjectHandle.html#method.downcast
+//! [`ExtensionType`]: struct.ExtensionType.html
+//! [`Loader`]: ../../struct.Loader.html
+//! [`Loader::register_extension`]: ../../struct.Loader.html#method.register_extension
+//! [`ObjectSubtypeHandle`]: trait.ObjectSubtypeHandle.html
+//! [`TypedObjectHandle::Extension`]: ../enum.TypedObjectHandle.html#variant.Extension
+
+use crate::v7400::object::ObjectHandle;
+
+/// User-defined typed object handle.
+pub trait ObjectSubtypeHandle<'a>: Sized {
+    /// Name of the extension type.
+    ///
+    /// This should be the same as the name of the registered
+    /// [`ExtensionType`](struct.ExtensionType.html).
+    const TYPE_NAME: &'static str;
+
+    /// Creates a new handle from the object handle.
+    ///
+    /// The given object is guaranteed to match the registered extension
+    /// type.
+    fn from_object(obj: ObjectHandle<'a>) -> Self;
+
+    /// Returns the object handle.
+    fn object_handle(&self) -> &ObjectHandle<'a>;
+}
+
+/// Registered extension object type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionType {
+    /// Type name.
+    type_name: &'static str,
+    /// Object node name.
+    node_name: String,
+    /// Object class.
+    class: Option<String>,
+    /// Object subclass.
+    subclass: Option<String>,
+}
+
+impl ExtensionType {
+    /// Creates a new `ExtensionType` matching objects with the given node
+    /// name.
+    ///
+    /// `type_name` should be the same as
+    /// [`ObjectSubtypeHandle::TYPE_NAME`](trait.ObjectSubtypeHandle.html#associatedconstant.TYPE_NAME)
+    /// of the handle type.
+    pub fn new(type_name: &'static str, node_name: impl Into<String>) -> Self {
+        Self {
+            type_name,
+            node_name: node_name.into(),
+            class: None,
+            subclass: None,
+        }
+    }
+
+    /// Restricts the type to objects with the given class.
+    pub fn class(self, class: impl Into<String>) -> Self {
+        Self {
+            class: Some(class.into()),
+            ..self
+        }
+    }
+
+    /// Restricts the type to objects with the given subclass.
+    pub fn subclass(self, subclass: impl Into<String>) -> Self {
+        Self {
+            subclass: Some(subclass.into()),
+            ..self
+        }
+    }
+
+    /// Returns the type name.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns whether the given object matches the type.
+    pub fn matches(&self, obj: &ObjectHandle<'_>) -> bool {
+        obj.node().name() == self.node_name
+            && self
+                .class
+                .as_ref()
+                .map_or(true, |class| obj.class() == class)
+            && self
+                .subclass
+                .as_ref()
+                .map_or(true, |subclass| obj.subclass() == subclass)
+    }
+}
+
+/// Handle of an object with a registered extension type.
+#[derive(Debug, Clone, Copy)]
+pub struct ExtensionObjectHandle<'a> {
+    /// Object.
+    object: ObjectHandle<'a>,
+    /// Type name.
+    type_name: &'static str,
+}
+
+impl<'a> ExtensionObjectHandle<'a> {
+    /// Creates a new `ExtensionObjectHandle`.
+    pub(crate) fn new(object: ObjectHandle<'a>, type_name: &'static str) -> Self {
+        Self { object, type_name }
+    }
+
+    /// Returns the registered type name.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Converts the handle to the user-defined handle type, if the type name
+    /// matches.
+    pub fn downcast<T: ObjectSubtypeHandle<'a>>(&self) -> Option<T> {
+        if T::TYPE_NAME == self.type_name {
+            Some(T::from_object(self.object))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> std::ops::Deref for ExtensionObjectHandle<'a> {
+    type Target = ObjectHandle<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.object
+    }
+}
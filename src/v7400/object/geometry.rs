@@ -1,10 +1,17 @@
 //! `Geometry` object.
 
-use crate::v7400::object::ObjectHandle;
+use crate::v7400::object::{model, ObjectHandle, TypedObjectHandle};
 
-pub use self::{mesh::MeshHandle, shape::ShapeHandle};
+#[cfg(feature = "nurbs")]
+pub use self::nurbs::{NurbsCurveHandle, NurbsForm, NurbsSurfaceHandle};
+pub use self::{
+    mesh::{MeshHandle, MeshProperties},
+    shape::ShapeHandle,
+};
 
 mod mesh;
+#[cfg(feature = "nurbs")]
+mod nurbs;
 mod shape;
 
 define_typed_handle! {
@@ -21,3 +28,16 @@ define_object_subtype! {
     /// `Geometry` node handle.
     GeometryHandle: ObjectHandle
 }
+
+impl<'a> GeometryHandle<'a> {
+    /// Returns an iterator of model objects using the geometry.
+    pub fn models(&self) -> impl Iterator<Item = model::TypedModelHandle<'a>> {
+        self.destination_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Model(o) => Some(o),
+                _ => None,
+            })
+    }
+}
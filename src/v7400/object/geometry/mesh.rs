@@ -1,10 +1,18 @@
 //! `Geometry` object (mesh).
 
-use anyhow::{format_err, Error};
+use anyhow::Error;
+use mint::Point3;
 
 use crate::v7400::{
     data::mesh::{layer::LayerHandle, ControlPoints, PolygonVertices, RawPolygonVertices},
-    object::{deformer, geometry::GeometryHandle, model, TypedObjectHandle},
+    object::{
+        deformer,
+        geometry::GeometryHandle,
+        model,
+        property::{loaders::PrimitiveLoader, ObjectProperties},
+        TypedObjectHandle,
+    },
+    Error as DomError, ErrorLocation,
 };
 
 define_object_subtype! {
@@ -48,43 +56,60 @@ impl<'a> MeshHandle<'a> {
             })
     }
 
+    /// Returns an iterator of child vertex cache deformers.
+    pub fn vertex_caches(&self) -> impl Iterator<Item = deformer::VertexCacheDeformerHandle<'a>> {
+        self.source_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Deformer(deformer::TypedDeformerHandle::VertexCache(o)) => {
+                    Some(o)
+                }
+                _ => None,
+            })
+    }
+
     /// Returns control points.
     pub(crate) fn control_points(&self) -> Result<ControlPoints<'a>, Error> {
-        self.node()
+        let node = self
+            .node()
             .children_by_name("Vertices")
             .next()
-            .ok_or_else(|| format_err!("`Vertices` child node not found for geometry mesh"))?
-            .attributes()
-            .get(0)
-            .ok_or_else(|| format_err!("`Vertices` node has no attributes"))?
+            .ok_or_else(|| DomError::missing_node(&self.node(), "Vertices"))?;
+        self.document()
+            .attribute(&node, 0)?
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?
             .get_arr_f64_or_type()
             .map(ControlPoints::new)
             .map_err(|ty| {
-                format_err!(
-                    "`Vertices` has wrong type attribute: expected `[f64]` but got {:?}`",
-                    ty
+                DomError::type_mismatch(
+                    ErrorLocation::node(&node).with_object(self.object_id()),
+                    "`[f64]`",
+                    ty,
                 )
+                .into()
             })
     }
 
     /// Returns polygon vertices without control points.
     pub(crate) fn raw_polygon_vertices(&self) -> Result<RawPolygonVertices<'a>, Error> {
-        self.node()
+        let node = self
+            .node()
             .children_by_name("PolygonVertexIndex")
             .next()
-            .ok_or_else(|| {
-                format_err!("`PolygonVertexIndex` child node not found for geometry mesh")
-            })?
-            .attributes()
-            .get(0)
-            .ok_or_else(|| format_err!("`PolygonVertexIndex` node has no attributes"))?
+            .ok_or_else(|| DomError::missing_node(&self.node(), "PolygonVertexIndex"))?;
+        self.document()
+            .attribute(&node, 0)?
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?
             .get_arr_i32_or_type()
             .map(RawPolygonVertices::new)
             .map_err(|ty| {
-                format_err!(
-                    "`PolygonVertexIndex` has wrong type attribute: expected `[i32]` but got {:?}`",
-                    ty
+                DomError::type_mismatch(
+                    ErrorLocation::node(&node).with_object(self.object_id()),
+                    "`[i32]`",
+                    ty,
                 )
+                .into()
             })
     }
 
@@ -95,8 +120,115 @@ impl<'a> MeshHandle<'a> {
         Ok(PolygonVertices::new(control_points, raw_polygon_vertices))
     }
 
+    /// Returns the axis-aligned bounding box of the control points as
+    /// `(min, max)`.
+    ///
+    /// Returns `None` if the mesh has no control points.
+    pub fn bounding_box(&self) -> Result<Option<(Point3<f64>, Point3<f64>)>, Error> {
+        let mut points = self.control_points()?.iter()?;
+        let first = match points.next() {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+        Ok(Some(points.fold((first, first), |(min, max), p| {
+            (
+                Point3 {
+                    x: min.x.min(p.x),
+                    y: min.y.min(p.y),
+                    z: min.z.min(p.z),
+                },
+                Point3 {
+                    x: max.x.max(p.x),
+                    y: max.y.max(p.y),
+                    z: max.z.max(p.z),
+                },
+            )
+        })))
+    }
+
+    /// Returns the axis-aligned bounding box of the control points as
+    /// `glam` vectors.
+    ///
+    /// See [`bounding_box`](#method.bounding_box).
+    #[cfg(feature = "glam")]
+    pub fn bounding_box_glam(&self) -> Result<Option<(glam::Vec3, glam::Vec3)>, Error> {
+        use crate::v7400::data::scalar;
+
+        Ok(self
+            .bounding_box()?
+            .map(|(min, max)| (scalar::glam_vec3(min), scalar::glam_vec3(max))))
+    }
+
     /// Returns layers.
     pub fn layers(&self) -> impl Iterator<Item = LayerHandle<'a>> {
-        self.node().children_by_name("Layer").map(LayerHandle::new)
+        let doc = self.document();
+        self.node()
+            .children_by_name("Layer")
+            .map(move |node| LayerHandle::new(doc, node))
+    }
+
+    /// Returns properties.
+    pub fn properties(&self) -> MeshProperties<'a> {
+        MeshProperties {
+            properties: self.properties_by_native_typename("FbxMesh"),
+        }
+    }
+}
+
+/// Proxy type to geometry mesh properties.
+#[derive(Debug, Clone, Copy)]
+pub struct MeshProperties<'a> {
+    /// Properties.
+    properties: ObjectProperties<'a>,
+}
+
+impl<'a> MeshProperties<'a> {
+    impl_prop_proxy_getters! {
+        /// Returns whether the mesh is visible to primary (camera) rays.
+        primary_visibility -> bool {
+            name = "Primary Visibility",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "primary visibility flag",
+            default: {
+                /// Returns whether the mesh is visible to primary (camera) rays.
+                ///
+                /// Returns default if the value is not set.
+                primary_visibility_or_default = true
+            }
+        }
+
+        /// Returns whether the mesh casts shadows.
+        casts_shadows -> bool {
+            name = "Casts Shadows",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "casts shadows flag",
+            default: {
+                /// Returns whether the mesh casts shadows.
+                ///
+                /// Returns default if the value is not set.
+                casts_shadows_or_default = true
+            }
+        }
+
+        /// Returns whether the mesh receives shadows.
+        receive_shadows -> bool {
+            name = "Receive Shadows",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "receive shadows flag",
+            default: {
+                /// Returns whether the mesh receives shadows.
+                ///
+                /// Returns default if the value is not set.
+                receive_shadows_or_default = true
+            }
+        }
+    }
+}
+
+impl<'a> std::ops::Deref for MeshProperties<'a> {
+    type Target = ObjectProperties<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.properties
     }
 }
@@ -0,0 +1,204 @@
+//! `Geometry` objects (NURBS curve and surface).
+//!
+//! NURBS geometries are exposed as raw control points and knot vectors;
+//! they are not tessellated.
+
+use anyhow::Error;
+use fbxcel::low::v7400::AttributeValue;
+use mint::Vector4;
+
+use crate::v7400::{object::geometry::GeometryHandle, Error as DomError, ErrorLocation};
+
+/// Form of a NURBS curve, or of a direction of a NURBS surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum NurbsForm {
+    /// Open.
+    Open,
+    /// Closed (the first and the last control points coincide).
+    Closed,
+    /// Periodic (the control points wrap around).
+    Periodic,
+}
+
+define_object_subtype! {
+    /// `Geometry` node handle (NURBS curve).
+    NurbsCurveHandle: GeometryHandle
+}
+
+impl<'a> NurbsCurveHandle<'a> {
+    /// Creates a new handle if the geometry is a NURBS curve.
+    pub fn from_geometry(geometry: GeometryHandle<'a>) -> Option<Self> {
+        if geometry.subclass() == "NurbsCurve" {
+            Some(Self::new(geometry))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the order (degree + 1).
+    pub fn order(&self) -> Result<i32, Error> {
+        attribute_i32(self, "Order", 0)
+    }
+
+    /// Returns the dimension (2 or 3).
+    pub fn dimension(&self) -> Result<i32, Error> {
+        attribute_i32(self, "Dimension", 0)
+    }
+
+    /// Returns the form.
+    pub fn form(&self) -> Result<NurbsForm, Error> {
+        attribute_form(self, "Form", 0)
+    }
+
+    /// Returns whether the curve is rational.
+    pub fn rational(&self) -> Result<bool, Error> {
+        attribute_i32(self, "Rational", 0).map(|v| v != 0)
+    }
+
+    /// Returns the homogeneous control points (`x`, `y`, `z`, and weight).
+    pub fn control_points(&self) -> Result<Vec<Vector4<f64>>, Error> {
+        attribute_points(self, "Points")
+    }
+
+    /// Returns the knot vector.
+    pub fn knot_vector(&self) -> Result<&'a [f64], Error> {
+        attribute_arr_f64(self, "KnotVector")
+    }
+}
+
+define_object_subtype! {
+    /// `Geometry` node handle (NURBS surface).
+    NurbsSurfaceHandle: GeometryHandle
+}
+
+impl<'a> NurbsSurfaceHandle<'a> {
+    /// Creates a new handle if the geometry is a NURBS surface.
+    pub fn from_geometry(geometry: GeometryHandle<'a>) -> Option<Self> {
+        if geometry.subclass() == "NurbsSurface" {
+            Some(Self::new(geometry))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the orders (degree + 1) in the U and V directions.
+    pub fn order(&self) -> Result<(i32, i32), Error> {
+        Ok((
+            attribute_i32(self, "NurbsSurfaceOrder", 0)?,
+            attribute_i32(self, "NurbsSurfaceOrder", 1)?,
+        ))
+    }
+
+    /// Returns the numbers of control points in the U and V directions.
+    pub fn dimensions(&self) -> Result<(i32, i32), Error> {
+        Ok((
+            attribute_i32(self, "Dimensions", 0)?,
+            attribute_i32(self, "Dimensions", 1)?,
+        ))
+    }
+
+    /// Returns the forms in the U and V directions.
+    pub fn form(&self) -> Result<(NurbsForm, NurbsForm), Error> {
+        Ok((
+            attribute_form(self, "Form", 0)?,
+            attribute_form(self, "Form", 1)?,
+        ))
+    }
+
+    /// Returns the homogeneous control points (`x`, `y`, `z`, and weight).
+    ///
+    /// Control points are ordered with the U index varying fastest.
+    pub fn control_points(&self) -> Result<Vec<Vector4<f64>>, Error> {
+        attribute_points(self, "Points")
+    }
+
+    /// Returns the knot vector in the U direction.
+    pub fn knot_vector_u(&self) -> Result<&'a [f64], Error> {
+        attribute_arr_f64(self, "KnotVectorU")
+    }
+
+    /// Returns the knot vector in the V direction.
+    pub fn knot_vector_v(&self) -> Result<&'a [f64], Error> {
+        attribute_arr_f64(self, "KnotVectorV")
+    }
+}
+
+/// Returns the attribute of the first child node with the given name.
+fn attribute<'a>(
+    geometry: &GeometryHandle<'a>,
+    name: &str,
+    index: usize,
+) -> Result<(&'a AttributeValue, ErrorLocation), Error> {
+    let node = geometry
+        .node()
+        .first_child_by_name(name)
+        .ok_or_else(|| DomError::missing_node(&geometry.node(), name))?;
+    let attr = geometry
+        .document()
+        .attribute(&node, index)?
+        .ok_or_else(|| DomError::missing_attribute(&node, index))?;
+    Ok((
+        attr,
+        ErrorLocation::node(&node).with_object(geometry.object_id()),
+    ))
+}
+
+/// Returns the `i32` attribute of the first child node with the given name.
+fn attribute_i32(geometry: &GeometryHandle<'_>, name: &str, index: usize) -> Result<i32, Error> {
+    let (attr, location) = attribute(geometry, name, index)?;
+    attr.get_i32_or_type()
+        .map_err(|ty| DomError::type_mismatch(location, "`i32`", ty).into())
+}
+
+/// Returns the form of the first child node with the given name.
+fn attribute_form(
+    geometry: &GeometryHandle<'_>,
+    name: &str,
+    index: usize,
+) -> Result<NurbsForm, Error> {
+    let (attr, location) = attribute(geometry, name, index)?;
+    match attr.get_string_or_type() {
+        Ok("Open") => Ok(NurbsForm::Open),
+        Ok("Closed") => Ok(NurbsForm::Closed),
+        Ok("Periodic") => Ok(NurbsForm::Periodic),
+        Ok(s) => Err(DomError::parse_error(location, "NURBS form", s).into()),
+        Err(ty) => Err(DomError::type_mismatch(location, "string", ty).into()),
+    }
+}
+
+/// Returns the `[f64]` attribute of the first child node with the given
+/// name.
+fn attribute_arr_f64<'a>(geometry: &GeometryHandle<'a>, name: &str) -> Result<&'a [f64], Error> {
+    let (attr, location) = attribute(geometry, name, 0)?;
+    attr.get_arr_f64_or_type()
+        .map_err(|ty| DomError::type_mismatch(location, "`[f64]`", ty).into())
+}
+
+/// Returns the homogeneous points of the first child node with the given
+/// name.
+fn attribute_points(geometry: &GeometryHandle<'_>, name: &str) -> Result<Vec<Vector4<f64>>, Error> {
+    let (attr, location) = attribute(geometry, name, 0)?;
+    let points = attr
+        .get_arr_f64_or_type()
+        .map_err(|ty| DomError::type_mismatch(location.clone(), "`[f64]`", ty))?;
+    if points.len() % 4 != 0 {
+        return Err(DomError::invalid_value(
+            location,
+            format!(
+                "NURBS control points should have 4 components, but got {} values",
+                points.len()
+            ),
+        )
+        .into());
+    }
+    Ok(points
+        .chunks_exact(4)
+        .map(|p| Vector4 {
+            x: p[0],
+            y: p[1],
+            z: p[2],
+            w: p[3],
+        })
+        .collect())
+}
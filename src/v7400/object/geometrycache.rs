@@ -0,0 +1,135 @@
+//! `Cache` object (geometry cache).
+//!
+//! A cache refers to external files of baked geometry (such as point caches
+//! of simulations), and is used by vertex cache deformers.
+//! The cache files are not read by this crate.
+//!
+//! ```
+//! # #[cfg(feature = "edit")]
+//! # fn main() -> Result<(), anyhow::Error> {
+//! use fbxcel_dom::v7400::{
+//!     data::cache::CacheFileType,
+//!     edit::{DocumentBuilder, ModelKind},
+//! };
+//!
+//! let mut builder = DocumentBuilder::new();
+//! let model = builder.add_model("Cloth", ModelKind::Mesh, None);
+//! let positions = [[0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+//! let mesh = builder.add_mesh("Cloth", &positions, &[&[0, 1, 2]], Some(model));
+//! builder.add_vertex_cache(
+//!     "ClothCache",
+//!     mesh,
+//!     "C:/caches/cloth.xml",
+//!     CacheFileType::MayaCache,
+//!     "ClothShape",
+//! )?;
+//! let doc = builder.build()?;
+//!
+//! let mesh = doc.meshes().next().expect("Mesh should exist");
+//! let deformer = mesh.vertex_caches().next().expect("Deformer should exist");
+//! assert_eq!(deformer.properties().channel_or_default()?, "ClothShape");
+//! let cache = deformer.cache().expect("Cache should exist");
+//! let props = cache.properties();
+//! assert_eq!(props.cache_file_or_default()?, "cloth.xml");
+//! assert_eq!(props.cache_file_absolute_path_or_default()?, "C:/caches/cloth.xml");
+//! assert_eq!(props.cache_file_type_or_default()?, CacheFileType::MayaCache);
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "edit"))]
+//! # fn main() {}
+//! ```
+
+use crate::v7400::{
+    data::cache::{CacheFileType, CacheFileTypeLoader},
+    object::{
+        deformer,
+        property::{loaders::BorrowedStringLoader, ObjectProperties},
+        ObjectHandle, TypedObjectHandle,
+    },
+};
+
+define_object_subtype! {
+    /// `Cache` node handle.
+    CacheHandle: ObjectHandle
+}
+
+impl<'a> CacheHandle<'a> {
+    /// Returns an iterator of vertex cache deformers using the cache.
+    pub fn vertex_cache_deformers(
+        &self,
+    ) -> impl Iterator<Item = deformer::VertexCacheDeformerHandle<'a>> {
+        self.destination_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Deformer(deformer::TypedDeformerHandle::VertexCache(o)) => {
+                    Some(o)
+                }
+                _ => None,
+            })
+    }
+
+    /// Returns properties.
+    pub fn properties(&self) -> CacheProperties<'a> {
+        CacheProperties {
+            properties: self.properties_by_native_typename("FbxCache"),
+        }
+    }
+}
+
+/// Proxy type to cache properties.
+#[derive(Debug, Clone, Copy)]
+pub struct CacheProperties<'a> {
+    /// Properties.
+    properties: ObjectProperties<'a>,
+}
+
+impl<'a> CacheProperties<'a> {
+    impl_prop_proxy_getters! {
+        /// Returns the relative path of the cache file.
+        ///
+        /// For Maya caches, this is the XML description file.
+        /// Note that this returns raw value, and the path separator might be
+        /// a slash or a backslash.
+        cache_file -> &'a str {
+            name = "CacheFile",
+            loader = BorrowedStringLoader::new(),
+            description = "relative cache file path",
+            default: {
+                /// Returns the relative path of the cache file.
+                ///
+                /// Returns default if the value is not set.
+                cache_file_or_default = ""
+            }
+        }
+
+        /// Returns the absolute path of the cache file.
+        ///
+        /// Note that this returns raw value, and the path separator might be
+        /// a slash or a backslash.
+        cache_file_absolute_path -> &'a str {
+            name = "CacheFileAbsolutePath",
+            loader = BorrowedStringLoader::new(),
+            description = "absolute cache file path",
+            default: {
+                /// Returns the absolute path of the cache file.
+                ///
+                /// Returns default if the value is not set.
+                cache_file_absolute_path_or_default = ""
+            }
+        }
+
+        /// Returns the cache file format.
+        cache_file_type -> CacheFileType {
+            name = "CacheFileType",
+            loader = CacheFileTypeLoader,
+            description = "cache file type",
+            default: {
+                /// Returns the cache file format.
+                ///
+                /// Returns default if the value is not set.
+                cache_file_type_or_default = CacheFileType::Unknown
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Owned typed object IDs.
+//!
+//! Object handles borrow the document, so they cannot be stored in
+//! application state easily.
+//! Typed IDs (such as [`ModelId`]) are owned and copyable, and can be
+//! resolved to the handles by [`Document::resolve`] at any time.
+//!
+//! ```
+//! # #[cfg(feature = "edit")]
+//! # fn main() -> Result<(), anyhow::Error> {
+//! use fbxcel_dom::v7400::{
+//!     edit::{DocumentBuilder, ModelKind},
+//!     object::{id::ModelId, model::TypedModelHandle, TypedObjectHandle},
+//! };
+//!
+//! let mut builder = DocumentBuilder::new();
+//! builder.add_model("Player", ModelKind::Null, None);
+//! let doc = builder.build()?;
+//!
+//! // Keep the ID instead of the handle.
+//! let player: ModelId = doc
+//!     .model_by_path("Player")
+//!     .map(ModelId::from)
+//!     .expect("Model should exist");
+//!
+//! // Resolve it later.
+//! let model: TypedModelHandle<'_> = doc.resolve(player).expect("Model should exist");
+//! assert_eq!(model.name(), Some("Player"));
+//!
+//! // IDs of other kinds are not resolved.
+//! let material = fbxcel_dom::v7400::object::id::MaterialId::from_object_id(player.object_id());
+//! assert!(doc.resolve(material).is_none());
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "edit"))]
+//! # fn main() {}
+//! ```
+//!
+//! [`ModelId`]: struct.ModelId.html
+//! [`Document::resolve`]: ../../struct.Document.html#method.resolve
+
+use crate::v7400::{
+    object::{
+        animation, constraint, deformer, geometry, material, model, nodeattribute, texture, video,
+        ObjectHandle, ObjectId, TypedObjectHandle,
+    },
+    Document,
+};
+
+/// ID resolvable to a handle.
+pub trait Resolve<'a> {
+    /// Handle type.
+    type Handle;
+
+    /// Resolves the ID to the handle.
+    ///
+    /// Returns `None` if the object is not found or has a different type.
+    fn resolve(self, doc: &'a Document) -> Option<Self::Handle>;
+}
+
+impl<'a> Resolve<'a> for ObjectId {
+    type Handle = ObjectHandle<'a>;
+
+    fn resolve(self, doc: &'a Document) -> Option<Self::Handle> {
+        self.to_object_handle(doc)
+    }
+}
+
+/// Defines typed object IDs.
+macro_rules! define_typed_ids {
+    ($(
+        $(#[$meta:meta])*
+        $id:ident => $handle:ty, $variant:ident;
+    )*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+            pub struct $id(ObjectId);
+
+            impl $id {
+                /// Creates a new ID from the object ID.
+                ///
+                /// The type of the object is not checked until the ID is
+                /// resolved.
+                pub fn from_object_id(id: ObjectId) -> Self {
+                    Self(id)
+                }
+
+                /// Returns the object ID.
+                pub fn object_id(self) -> ObjectId {
+                    self.0
+                }
+            }
+
+            impl From<$id> for ObjectId {
+                fn from(id: $id) -> Self {
+                    id.0
+                }
+            }
+
+            impl<'a> From<$handle> for $id {
+                fn from(handle: $handle) -> Self {
+                    Self(handle.object_id())
+                }
+            }
+
+            impl<'a> Resolve<'a> for $id {
+                type Handle = $handle;
+
+                fn resolve(self, doc: &'a Document) -> Option<Self::Handle> {
+                    match self.0.to_object_handle(doc)?.get_typed() {
+                        TypedObjectHandle::$variant(o) => Some(o),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+define_typed_ids! {
+    /// ID of an `AnimationCurve` object.
+    AnimationCurveId => animation::AnimationCurveHandle<'a>, AnimationCurve;
+    /// ID of an `AnimationCurveNode` object.
+    AnimationCurveNodeId => animation::AnimationCurveNodeHandle<'a>, AnimationCurveNode;
+    /// ID of an `AnimationLayer` object.
+    AnimationLayerId => animation::AnimationLayerHandle<'a>, AnimationLayer;
+    /// ID of an `AnimationStack` object.
+    AnimationStackId => animation::AnimationStackHandle<'a>, AnimationStack;
+    /// ID of a `Constraint` object.
+    ConstraintId => constraint::TypedConstraintHandle<'a>, Constraint;
+    /// ID of a `Deformer` object.
+    DeformerId => deformer::TypedDeformerHandle<'a>, Deformer;
+    /// ID of a `Geometry` object.
+    GeometryId => geometry::TypedGeometryHandle<'a>, Geometry;
+    /// ID of a `Material` object.
+    MaterialId => material::MaterialHandle<'a>, Material;
+    /// ID of a `Model` object.
+    ModelId => model::TypedModelHandle<'a>, Model;
+    /// ID of a `NodeAttribute` object.
+    NodeAttributeId => nodeattribute::TypedNodeAttributeHandle<'a>, NodeAttribute;
+    /// ID of a `SubDeformer` object.
+    SubDeformerId => deformer::TypedSubDeformerHandle<'a>, SubDeformer;
+    /// ID of a `Texture` object.
+    TextureId => texture::TextureHandle<'a>, Texture;
+    /// ID of a `Video` object.
+    VideoId => video::TypedVideoHandle<'a>, Video;
+}
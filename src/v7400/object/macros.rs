@@ -27,6 +27,12 @@ macro_rules! define_object_subtype {
                 &self.object
             }
         }
+
+        impl std::fmt::Display for $ty_sub<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Display::fmt(&self.object, f)
+            }
+        }
     }
 }
 
@@ -80,6 +86,12 @@ macro_rules! define_typed_handle {
                 }
             }
         }
+
+        impl std::fmt::Display for $outer<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Display::fmt(&**self, f)
+            }
+        }
     };
 }
 
@@ -104,7 +116,16 @@ macro_rules! impl_prop_proxy_getters {
                     .get_property($name)
                     .map(|p| p.load_value($loader))
                     .transpose()
-                    .map_err(|e| anyhow::format_err!("Failed to load {}: {}", $description, e))
+                    .map_err(|e| {
+                        anyhow::Error::from(crate::v7400::Error::PropertyLoad {
+                            location: crate::v7400::ErrorLocation::object(
+                                self.properties.object_id(),
+                            )
+                            .with_property($name),
+                            description: ($description).to_owned(),
+                            source: e,
+                        })
+                    })
             }
 
             $(#[$meta_default])*
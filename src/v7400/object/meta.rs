@@ -1,71 +1,118 @@
 //! Object metadata.
 
-use string_interner::symbol::SymbolU32;
+use std::convert::TryFrom;
 
-use crate::v7400::object::ObjectId;
+use crate::v7400::{
+    document::memory,
+    object::{ObjectId, ObjectNodeId},
+    symbol::Symbol,
+};
 
-/// Symbol for interned object class and subclass.
-// This is an opaque-typedef pattern.
-// `string_interner::Sym` has efficient implementation, so use it internally.
+/// Index of an object in the metadata store.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub(crate) struct ObjectClassSym(SymbolU32);
-
-impl string_interner::Symbol for ObjectClassSym {
-    fn try_from_usize(v: usize) -> Option<Self> {
-        SymbolU32::try_from_usize(v).map(Self)
-    }
+pub(crate) struct ObjectIndex(u32);
 
+impl ObjectIndex {
+    /// Returns the index as `usize`.
     fn to_usize(self) -> usize {
-        self.0.to_usize()
+        self.0 as usize
     }
 }
 
-/// Metadata of object node.
-#[derive(Debug, Clone)]
-pub(crate) struct ObjectMeta {
-    /// Object ID.
-    id: ObjectId,
-    /// Name (if exists).
-    name: Option<String>,
-    /// Class.
-    class: ObjectClassSym,
-    /// Subclass.
-    subclass: ObjectClassSym,
+/// Metadata of object nodes.
+///
+/// This is stored as struct of arrays indexed by [`ObjectIndex`], to keep the
+/// per-object overhead small for documents with many objects.
+#[derive(Default, Debug, Clone)]
+pub(crate) struct ObjectMetaStore {
+    /// Object node IDs.
+    node_ids: Vec<ObjectNodeId>,
+    /// Object IDs.
+    ids: Vec<ObjectId>,
+    /// Names (if exists).
+    names: Vec<Option<Symbol>>,
+    /// Classes.
+    classes: Vec<Symbol>,
+    /// Subclasses.
+    subclasses: Vec<Symbol>,
 }
 
-impl ObjectMeta {
-    /// Creates a new `ObjectMeta`.
-    pub(crate) fn new(
+impl ObjectMetaStore {
+    /// Adds an object and returns its index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of objects exceeds `u32::MAX`.
+    pub(crate) fn push(
+        &mut self,
+        node_id: ObjectNodeId,
         id: ObjectId,
-        name: Option<String>,
-        class: ObjectClassSym,
-        subclass: ObjectClassSym,
-    ) -> Self {
-        Self {
-            id,
-            name,
-            class,
-            subclass,
-        }
+        name: Option<Symbol>,
+        class: Symbol,
+        subclass: Symbol,
+    ) -> ObjectIndex {
+        let index = u32::try_from(self.node_ids.len())
+            .map(ObjectIndex)
+            .expect("Too many objects");
+        self.node_ids.push(node_id);
+        self.ids.push(id);
+        self.names.push(name);
+        self.classes.push(class);
+        self.subclasses.push(subclass);
+        index
+    }
+
+    /// Shrinks the capacity of the arrays as much as possible.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.node_ids.shrink_to_fit();
+        self.ids.shrink_to_fit();
+        self.names.shrink_to_fit();
+        self.classes.shrink_to_fit();
+        self.subclasses.shrink_to_fit();
+    }
+
+    /// Returns the estimated heap size.
+    pub(crate) fn heap_size(&self) -> usize {
+        memory::vec_heap_size(&self.node_ids)
+            + memory::vec_heap_size(&self.ids)
+            + memory::vec_heap_size(&self.names)
+            + memory::vec_heap_size(&self.classes)
+            + memory::vec_heap_size(&self.subclasses)
+    }
+
+    /// Returns the number of objects.
+    pub(crate) fn len(&self) -> usize {
+        self.node_ids.len()
+    }
+
+    /// Returns an iterator of all object indices.
+    pub(crate) fn indices(&self) -> impl Iterator<Item = ObjectIndex> {
+        // `push` ensures the length fits in `u32`.
+        (0..self.len() as u32).map(ObjectIndex)
+    }
+
+    /// Returns object node ID.
+    pub(crate) fn node_id(&self, index: ObjectIndex) -> ObjectNodeId {
+        self.node_ids[index.to_usize()]
     }
 
     /// Returns object ID.
-    pub(crate) fn object_id(&self) -> ObjectId {
-        self.id
+    pub(crate) fn object_id(&self, index: ObjectIndex) -> ObjectId {
+        self.ids[index.to_usize()]
     }
 
-    /// Returns object name.
-    pub(crate) fn name(&self) -> Option<&str> {
-        self.name.as_deref()
+    /// Returns object name symbol.
+    pub(crate) fn name_sym(&self, index: ObjectIndex) -> Option<Symbol> {
+        self.names[index.to_usize()]
     }
 
     /// Returns object class symbol.
-    pub(crate) fn class_sym(&self) -> ObjectClassSym {
-        self.class
+    pub(crate) fn class_sym(&self, index: ObjectIndex) -> Symbol {
+        self.classes[index.to_usize()]
     }
 
     /// Returns object subclass symbol.
-    pub(crate) fn subclass_sym(&self) -> ObjectClassSym {
-        self.subclass
+    pub(crate) fn subclass_sym(&self, index: ObjectIndex) -> Symbol {
+        self.subclasses[index.to_usize()]
     }
 }
@@ -1,23 +1,51 @@
 //! `Model` object.
 
-use crate::v7400::object::{ObjectHandle, TypedObjectHandle};
+use std::collections::HashSet;
 
+use anyhow::{bail, Error};
+use mint::{ColumnMatrix4, Vector3};
+
+use crate::v7400::{
+    data::{
+        model::Culling,
+        scalar,
+        transform::{
+            bake_pivots, math::Mat4, BakedTransform, EulerRotation, InheritType, InheritTypeLoader,
+            LocalTransform, RotationOrder, RotationOrderLoader, TransformCache,
+        },
+    },
+    object::{
+        collection, deformer, nodeattribute,
+        property::{
+            loaders::{MintLoader, PrimitiveLoader},
+            ObjectProperties,
+        },
+        ObjectHandle, ObjectId, TypedObjectHandle,
+    },
+    Error as DomError, ErrorLocation,
+};
+
+pub(crate) use self::path::find_by_path;
 pub use self::{
-    camera::CameraHandle, light::LightHandle, limbnode::LimbNodeHandle, mesh::MeshHandle,
-    null::NullHandle,
+    camera::CameraHandle, camerastereo::CameraStereoHandle, light::LightHandle,
+    limbnode::LimbNodeHandle, mesh::MeshHandle, null::NullHandle, path::PathOptions,
 };
 
 mod camera;
+mod camerastereo;
 mod light;
 mod limbnode;
 mod mesh;
 mod null;
+mod path;
 
 define_typed_handle! {
     /// Typed model handle.
     TypedModelHandle(ModelHandle) {
         /// Camera.
         ("Model", "Camera") => Camera(CameraHandle),
+        /// CameraStereo.
+        ("Model", "CameraStereo") => CameraStereo(CameraStereoHandle),
         /// Light.
         ("Model", "Light") => Light(LightHandle),
         /// LimbNode.
@@ -35,6 +63,37 @@ define_object_subtype! {
 }
 
 impl<'a> ModelHandle<'a> {
+    /// Returns the parent model if available.
+    ///
+    /// This is same as [`parent_model`](#method.parent_model).
+    pub fn parent(&self) -> Option<TypedModelHandle<'a>> {
+        self.parent_model()
+    }
+
+    /// Returns an iterator of the child models.
+    ///
+    /// This is same as [`child_models`](#method.child_models).
+    pub fn children(&self) -> impl Iterator<Item = TypedModelHandle<'a>> {
+        self.child_models()
+    }
+
+    /// Returns an iterator of the descendant models (excluding the model
+    /// itself), in depth-first pre-order.
+    pub fn descendants(&self) -> Descendants<'a> {
+        Descendants::new(*self)
+    }
+
+    /// Returns the display layer of the model, if available.
+    pub fn display_layer(&self) -> Option<collection::CollectionExclusiveHandle<'a>> {
+        self.destination_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .find_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::CollectionExclusive(o) if o.is_display_layer() => Some(o),
+                _ => None,
+            })
+    }
+
     /// Returns the parent model if available.
     pub fn parent_model(&self) -> Option<TypedModelHandle<'a>> {
         self.destination_objects()
@@ -57,4 +116,619 @@ impl<'a> ModelHandle<'a> {
                 _ => None,
             })
     }
+
+    /// Returns the node attribute if available.
+    pub fn node_attribute(&self) -> Option<nodeattribute::TypedNodeAttributeHandle<'a>> {
+        self.source_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::NodeAttribute(o) => Some(o),
+                _ => None,
+            })
+            .next()
+    }
+
+    /// Returns an iterator of subdeformer clusters which use the model as a
+    /// bone (link).
+    pub fn clusters(&self) -> impl Iterator<Item = deformer::ClusterHandle<'a>> {
+        self.destination_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::SubDeformer(deformer::TypedSubDeformerHandle::Cluster(o)) => {
+                    Some(o)
+                }
+                _ => None,
+            })
+    }
+
+    /// Returns properties.
+    pub fn properties(&self) -> ModelProperties<'a> {
+        ModelProperties {
+            properties: self.properties_by_native_typename("FbxNode"),
+        }
+    }
+
+    /// Returns the decomposed local transform.
+    ///
+    /// Properties absent from the object are taken from the property
+    /// template, or from the FBX SDK defaults.
+    pub fn local_transform(&self) -> Result<LocalTransform, Error> {
+        let props = self.properties();
+        Ok(LocalTransform {
+            translation: props.translation_or_default()?,
+            rotation: props.rotation_or_default()?,
+            scaling: props.scaling_or_default()?,
+            rotation_order: props.rotation_order_or_default()?,
+            pre_rotation: props.pre_rotation_or_default()?,
+            post_rotation: props.post_rotation_or_default()?,
+            rotation_pivot: props.rotation_pivot_or_default()?,
+            rotation_offset: props.rotation_offset_or_default()?,
+            scaling_pivot: props.scaling_pivot_or_default()?,
+            scaling_offset: props.scaling_offset_or_default()?,
+            rotation_active: props.rotation_active_or_default()?,
+        })
+    }
+
+    /// Returns the local transform with pivots and offsets baked into a plain
+    /// TRS.
+    ///
+    /// See [`data::transform::bake_pivots`] for detail.
+    ///
+    /// [`data::transform::bake_pivots`]: ../../data/transform/fn.bake_pivots.html
+    pub fn baked_transform(&self) -> Result<BakedTransform, Error> {
+        self.local_transform().map(|local| bake_pivots(&local))
+    }
+
+    /// Returns the global (world) transform.
+    ///
+    /// This takes pivots, offsets, pre/post rotations, and `InheritType` of
+    /// the model and its ancestors into account.
+    /// Geometric transform is not included, since it is not inherited by
+    /// children; see [`geometric_transform`](#method.geometric_transform).
+    ///
+    /// To get global transforms of many models, use [`TransformCache`].
+    ///
+    /// The expected matrices in the examples below are the results of the
+    /// FBX SDK formula (`FbxNode::EvaluateGlobalTransform`, and
+    /// `CalculateGlobalTransform` of the SDK samples for `InheritType`).
+    ///
+    /// Pivots, offsets, pre-rotation, and post-rotation:
+    ///
+    /// ```
+    /// # #[cfg(feature = "edit")]
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// use fbxcel_dom::v7400::{
+    ///     edit::{DocumentBuilder, ModelKind},
+    ///     object::property::OwnedPropertyValue,
+    /// };
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let id = builder.add_model("Pivoted", ModelKind::Null, None);
+    /// builder.set_local_transform(id, [10.0, 0.0, 0.0], [90.0, 0.0, 0.0], [2.0; 3])?;
+    /// for &(name, value) in &[
+    ///     ("PreRotation", [0.0, 0.0, 90.0]),
+    ///     ("PostRotation", [0.0, 90.0, 0.0]),
+    ///     ("RotationOffset", [1.0, 0.0, 0.0]),
+    ///     ("RotationPivot", [0.0, 2.0, 0.0]),
+    ///     ("ScalingOffset", [0.0, 0.0, 3.0]),
+    ///     ("ScalingPivot", [1.0, 0.0, 0.0]),
+    /// ] {
+    ///     let value = OwnedPropertyValue::F64x3(value);
+    ///     builder
+    ///         .edit_mut()
+    ///         .insert_property(id, name, "Vector3D", "Vector", "", value)?;
+    /// }
+    /// let active = OwnedPropertyValue::Bool(true);
+    /// builder
+    ///     .edit_mut()
+    ///     .insert_property(id, "RotationActive", "bool", "", "", active)?;
+    /// let doc = builder.build()?;
+    ///
+    /// let model = doc.model_by_path("Pivoted").expect("Model should exist");
+    /// let mut global: [[f64; 4]; 4] = model.global_transform()?.into();
+    /// global.iter_mut().flatten().for_each(|v| *v = v.round());
+    /// // Column-major.
+    /// assert_eq!(
+    ///     global,
+    ///     [
+    ///         [2.0, 0.0, 0.0, 0.0],
+    ///         [0.0, 0.0, 2.0, 0.0],
+    ///         [0.0, -2.0, 0.0, 0.0],
+    ///         [10.0, -1.0, -2.0, 1.0],
+    ///     ]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "edit"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// `InheritType` with a non-uniformly scaled parent:
+    ///
+    /// ```
+    /// # #[cfg(feature = "edit")]
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// use fbxcel_dom::v7400::{
+    ///     edit::{DocumentBuilder, ModelKind},
+    ///     object::property::OwnedPropertyValue,
+    /// };
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let parent = builder.add_model("Parent", ModelKind::Null, None);
+    /// builder.set_local_transform(parent, [0.0, 5.0, 0.0], [0.0, 0.0, 90.0], [2.0, 1.0, 1.0])?;
+    /// // `InheritType` values: 0 = RrSs, 1 = RSrs, 2 = Rrs.
+    /// for &(name, inherit_type) in &[("RrSs", 0), ("RSrs", 1), ("Rrs", 2)] {
+    ///     let child = builder.add_model(name, ModelKind::Null, Some(parent));
+    ///     builder.set_local_transform(child, [1.0, 0.0, 0.0], [0.0, 0.0, 90.0], [1.0, 1.0, 3.0])?;
+    ///     let value = OwnedPropertyValue::I32(inherit_type);
+    ///     builder
+    ///         .edit_mut()
+    ///         .insert_property(child, "InheritType", "enum", "", "", value)?;
+    /// }
+    /// let doc = builder.build()?;
+    ///
+    /// let global = |path: &str| -> Result<[[f64; 4]; 4], anyhow::Error> {
+    ///     let model = doc.model_by_path(path).expect("Model should exist");
+    ///     let mut m: [[f64; 4]; 4] = model.global_transform()?.into();
+    ///     m.iter_mut().flatten().for_each(|v| *v = v.round());
+    ///     Ok(m)
+    /// };
+    /// // Column-major.
+    /// assert_eq!(
+    ///     global("Parent/RrSs")?,
+    ///     [
+    ///         [-2.0, 0.0, 0.0, 0.0],
+    ///         [0.0, -1.0, 0.0, 0.0],
+    ///         [0.0, 0.0, 3.0, 0.0],
+    ///         [0.0, 7.0, 0.0, 1.0],
+    ///     ]
+    /// );
+    /// assert_eq!(
+    ///     global("Parent/RSrs")?,
+    ///     [
+    ///         [-1.0, 0.0, 0.0, 0.0],
+    ///         [0.0, -2.0, 0.0, 0.0],
+    ///         [0.0, 0.0, 3.0, 0.0],
+    ///         [0.0, 7.0, 0.0, 1.0],
+    ///     ]
+    /// );
+    /// assert_eq!(
+    ///     global("Parent/Rrs")?,
+    ///     [
+    ///         [-1.0, 0.0, 0.0, 0.0],
+    ///         [0.0, -1.0, 0.0, 0.0],
+    ///         [0.0, 0.0, 3.0, 0.0],
+    ///         [0.0, 7.0, 0.0, 1.0],
+    ///     ]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "edit"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// [`TransformCache`]: ../../data/transform/struct.TransformCache.html
+    pub fn global_transform(&self) -> Result<ColumnMatrix4<f64>, Error> {
+        TransformCache::new().global_transform(self)
+    }
+
+    /// Returns the global (world) transform converted to `f32`.
+    ///
+    /// See [`global_transform`](#method.global_transform).
+    pub fn global_transform_f32(&self) -> Result<ColumnMatrix4<f32>, Error> {
+        self.global_transform().map(scalar::matrix4)
+    }
+
+    /// Returns the global (world) transform as `glam::Affine3A`.
+    ///
+    /// See [`global_transform`](#method.global_transform).
+    #[cfg(feature = "glam")]
+    pub fn global_transform_glam(&self) -> Result<glam::Affine3A, Error> {
+        self.global_transform().map(scalar::glam_affine3a)
+    }
+
+    /// Returns whether the model itself is visible, ignoring ancestors.
+    pub fn is_visible(&self) -> Result<bool, Error> {
+        let props = self.properties();
+        Ok(props.show_or_default()? && props.visibility_or_default()? != 0.0)
+    }
+
+    /// Returns whether the model is effectively visible.
+    ///
+    /// A model is effectively visible if it is visible itself, and its parent
+    /// is effectively visible or `Visibility Inheritance` is disabled.
+    pub fn effective_visibility(&self) -> Result<bool, Error> {
+        let mut visited = HashSet::new();
+        let mut current = *self;
+        loop {
+            if !current.is_visible()? {
+                return Ok(false);
+            }
+            if !current.properties().visibility_inheritance_or_default()? {
+                return Ok(true);
+            }
+            if !visited.insert(current.object_id()) {
+                bail!(
+                    "Cyclic model hierarchy detected: object_id={:?}",
+                    current.object_id()
+                );
+            }
+            match current.parent_model() {
+                Some(parent) => current = *parent,
+                None => return Ok(true),
+            }
+        }
+    }
+
+    /// Returns face culling mode.
+    ///
+    /// Returns default if the `Culling` child node is not found.
+    pub fn culling(&self) -> Result<Culling, Error> {
+        let node = match self.node().children_by_name("Culling").next() {
+            Some(v) => v,
+            None => return Ok(Culling::default()),
+        };
+        node.attributes()
+            .first()
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?
+            .get_string_or_type()
+            .map_err(|ty| {
+                DomError::type_mismatch(
+                    ErrorLocation::node(&node).with_object(self.object_id()),
+                    "string",
+                    ty,
+                )
+            })?
+            .parse()
+            .map_err(|e| {
+                DomError::invalid_value(ErrorLocation::node(&node).with_object(self.object_id()), e)
+                    .into()
+            })
+    }
+
+    /// Returns whether the model is mirrored, i.e. the transform of the
+    /// attached geometry has negative determinant.
+    ///
+    /// Importers should flip the winding order of the faces of mirrored
+    /// models.
+    pub fn is_mirrored(&self) -> Result<bool, Error> {
+        let global = Mat4::from(self.global_transform()?);
+        let geometric = Mat4::from(self.geometric_transform()?);
+        Ok((global * geometric).determinant() < 0.0)
+    }
+
+    /// Returns the geometric transform.
+    ///
+    /// Geometric transform is applied to the attached geometry only, and is
+    /// not inherited by child models.
+    /// The transform of the geometry is `global_transform * geometric_transform`.
+    pub fn geometric_transform(&self) -> Result<ColumnMatrix4<f64>, Error> {
+        let props = self.properties();
+        let t = Mat4::from_translation(props.geometric_translation_or_default()?);
+        let r = EulerRotation::new(
+            props.geometric_rotation_or_default()?,
+            RotationOrder::EulerXyz,
+        )
+        .matrix();
+        let s = Mat4::from_scaling(props.geometric_scaling_or_default()?);
+        Ok((t * r * s).into())
+    }
+
+    /// Returns the geometric transform converted to `f32`.
+    pub fn geometric_transform_f32(&self) -> Result<ColumnMatrix4<f32>, Error> {
+        self.geometric_transform().map(scalar::matrix4)
+    }
+
+    /// Returns the geometric transform as `glam::Affine3A`.
+    #[cfg(feature = "glam")]
+    pub fn geometric_transform_glam(&self) -> Result<glam::Affine3A, Error> {
+        self.geometric_transform().map(scalar::glam_affine3a)
+    }
+}
+
+/// Proxy type to model properties.
+#[derive(Debug, Clone, Copy)]
+pub struct ModelProperties<'a> {
+    /// Properties.
+    properties: ObjectProperties<'a>,
+}
+
+impl<'a> ModelProperties<'a> {
+    impl_prop_proxy_getters! {
+        /// Returns local translation.
+        translation -> Vector3<f64> {
+            name = "Lcl Translation",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "local translation",
+            default: {
+                /// Returns local translation.
+                ///
+                /// Returns default if the value is not set.
+                translation_or_default = Vector3 { x: 0.0, y: 0.0, z: 0.0 }
+            }
+        }
+
+        /// Returns local rotation (Euler angles in degrees).
+        rotation -> Vector3<f64> {
+            name = "Lcl Rotation",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "local rotation",
+            default: {
+                /// Returns local rotation (Euler angles in degrees).
+                ///
+                /// Returns default if the value is not set.
+                rotation_or_default = Vector3 { x: 0.0, y: 0.0, z: 0.0 }
+            }
+        }
+
+        /// Returns local scaling.
+        scaling -> Vector3<f64> {
+            name = "Lcl Scaling",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "local scaling",
+            default: {
+                /// Returns local scaling.
+                ///
+                /// Returns default if the value is not set.
+                scaling_or_default = Vector3 { x: 1.0, y: 1.0, z: 1.0 }
+            }
+        }
+
+        /// Returns rotation order.
+        rotation_order -> RotationOrder {
+            name = "RotationOrder",
+            loader = RotationOrderLoader,
+            description = "rotation order",
+            default: {
+                /// Returns rotation order.
+                ///
+                /// Returns default if the value is not set.
+                rotation_order_or_default = RotationOrder::EulerXyz
+            }
+        }
+
+        /// Returns pre-rotation (Euler angles in degrees).
+        pre_rotation -> Vector3<f64> {
+            name = "PreRotation",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "pre-rotation",
+            default: {
+                /// Returns pre-rotation (Euler angles in degrees).
+                ///
+                /// Returns default if the value is not set.
+                pre_rotation_or_default = Vector3 { x: 0.0, y: 0.0, z: 0.0 }
+            }
+        }
+
+        /// Returns post-rotation (Euler angles in degrees).
+        post_rotation -> Vector3<f64> {
+            name = "PostRotation",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "post-rotation",
+            default: {
+                /// Returns post-rotation (Euler angles in degrees).
+                ///
+                /// Returns default if the value is not set.
+                post_rotation_or_default = Vector3 { x: 0.0, y: 0.0, z: 0.0 }
+            }
+        }
+
+        /// Returns rotation pivot.
+        rotation_pivot -> Vector3<f64> {
+            name = "RotationPivot",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "rotation pivot",
+            default: {
+                /// Returns rotation pivot.
+                ///
+                /// Returns default if the value is not set.
+                rotation_pivot_or_default = Vector3 { x: 0.0, y: 0.0, z: 0.0 }
+            }
+        }
+
+        /// Returns rotation offset.
+        rotation_offset -> Vector3<f64> {
+            name = "RotationOffset",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "rotation offset",
+            default: {
+                /// Returns rotation offset.
+                ///
+                /// Returns default if the value is not set.
+                rotation_offset_or_default = Vector3 { x: 0.0, y: 0.0, z: 0.0 }
+            }
+        }
+
+        /// Returns scaling pivot.
+        scaling_pivot -> Vector3<f64> {
+            name = "ScalingPivot",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "scaling pivot",
+            default: {
+                /// Returns scaling pivot.
+                ///
+                /// Returns default if the value is not set.
+                scaling_pivot_or_default = Vector3 { x: 0.0, y: 0.0, z: 0.0 }
+            }
+        }
+
+        /// Returns scaling offset.
+        scaling_offset -> Vector3<f64> {
+            name = "ScalingOffset",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "scaling offset",
+            default: {
+                /// Returns scaling offset.
+                ///
+                /// Returns default if the value is not set.
+                scaling_offset_or_default = Vector3 { x: 0.0, y: 0.0, z: 0.0 }
+            }
+        }
+
+        /// Returns whether the rotation order, pre-rotation, and post-rotation are active.
+        rotation_active -> bool {
+            name = "RotationActive",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "rotation active flag",
+            default: {
+                /// Returns whether the rotation order, pre-rotation, and post-rotation are active.
+                ///
+                /// Returns default if the value is not set.
+                rotation_active_or_default = false
+            }
+        }
+
+        /// Returns transform inheritance type.
+        inherit_type -> InheritType {
+            name = "InheritType",
+            loader = InheritTypeLoader,
+            description = "inherit type",
+            default: {
+                /// Returns transform inheritance type.
+                ///
+                /// Returns default if the value is not set.
+                inherit_type_or_default = InheritType::RSrs
+            }
+        }
+
+        /// Returns geometric translation.
+        geometric_translation -> Vector3<f64> {
+            name = "GeometricTranslation",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "geometric translation",
+            default: {
+                /// Returns geometric translation.
+                ///
+                /// Returns default if the value is not set.
+                geometric_translation_or_default = Vector3 { x: 0.0, y: 0.0, z: 0.0 }
+            }
+        }
+
+        /// Returns geometric rotation (Euler angles in degrees).
+        geometric_rotation -> Vector3<f64> {
+            name = "GeometricRotation",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "geometric rotation",
+            default: {
+                /// Returns geometric rotation (Euler angles in degrees).
+                ///
+                /// Returns default if the value is not set.
+                geometric_rotation_or_default = Vector3 { x: 0.0, y: 0.0, z: 0.0 }
+            }
+        }
+
+        /// Returns geometric scaling.
+        geometric_scaling -> Vector3<f64> {
+            name = "GeometricScaling",
+            loader = MintLoader::<Vector3<f64>>::new(),
+            description = "geometric scaling",
+            default: {
+                /// Returns geometric scaling.
+                ///
+                /// Returns default if the value is not set.
+                geometric_scaling_or_default = Vector3 { x: 1.0, y: 1.0, z: 1.0 }
+            }
+        }
+
+        /// Returns whether the model is shown.
+        show -> bool {
+            name = "Show",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "show flag",
+            default: {
+                /// Returns whether the model is shown.
+                ///
+                /// Returns default if the value is not set.
+                show_or_default = true
+            }
+        }
+
+        /// Returns visibility.
+        ///
+        /// `0.0` means hidden; other values mean visible.
+        visibility -> f64 {
+            name = "Visibility",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "visibility",
+            default: {
+                /// Returns visibility.
+                ///
+                /// Returns default if the value is not set.
+                visibility_or_default = 1.0
+            }
+        }
+
+        /// Returns whether the visibility is inherited from the parent.
+        visibility_inheritance -> bool {
+            name = "Visibility Inheritance",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "visibility inheritance flag",
+            default: {
+                /// Returns whether the visibility is inherited from the parent.
+                ///
+                /// Returns default if the value is not set.
+                visibility_inheritance_or_default = true
+            }
+        }
+    }
+
+    /// Returns whether the model is visible, i.e. the visibility is not zero.
+    ///
+    /// Returns default (visible) if the value is not set.
+    pub fn is_visible_or_default(&self) -> Result<bool, Error> {
+        self.visibility_or_default().map(|v| v != 0.0)
+    }
+}
+
+impl<'a> std::ops::Deref for ModelProperties<'a> {
+    type Target = ObjectProperties<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.properties
+    }
+}
+
+/// Iterator of descendant models.
+///
+/// This is created by [`ModelHandle::descendants`].
+///
+/// [`ModelHandle::descendants`]: struct.ModelHandle.html#method.descendants
+#[derive(Debug, Clone)]
+pub struct Descendants<'a> {
+    /// Models to be visited.
+    stack: Vec<TypedModelHandle<'a>>,
+    /// Models already visited.
+    ///
+    /// This prevents infinite loop for broken (cyclic) hierarchies.
+    visited: HashSet<ObjectId>,
+}
+
+impl<'a> Descendants<'a> {
+    /// Creates a new `Descendants` iterator.
+    fn new(root: ModelHandle<'a>) -> Self {
+        let mut visited = HashSet::new();
+        visited.insert(root.object_id());
+        let mut stack: Vec<_> = root.child_models().collect();
+        stack.reverse();
+        Self { stack, visited }
+    }
+}
+
+impl<'a> Iterator for Descendants<'a> {
+    type Item = TypedModelHandle<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let model = self.stack.pop()?;
+            if !self.visited.insert(model.object_id()) {
+                continue;
+            }
+            let len = self.stack.len();
+            self.stack.extend(model.child_models());
+            self.stack[len..].reverse();
+            return Some(model);
+        }
+    }
 }
@@ -0,0 +1,24 @@
+//! `Model` object (camerastereo).
+
+use crate::v7400::object::{model::ModelHandle, nodeattribute, TypedObjectHandle};
+
+define_object_subtype! {
+    /// `Model` node handle (camerastereo).
+    CameraStereoHandle: ModelHandle
+}
+
+impl<'a> CameraStereoHandle<'a> {
+    /// Returns the stereo camera node attribute if available.
+    pub fn stereo_attribute(&self) -> Option<nodeattribute::CameraStereoHandle<'a>> {
+        self.source_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::NodeAttribute(
+                    nodeattribute::TypedNodeAttributeHandle::CameraStereo(o),
+                ) => Some(o),
+                _ => None,
+            })
+            .next()
+    }
+}
@@ -1,8 +1,150 @@
 //! `Model` object (limbnode).
+//!
+//! ```
+//! # #[cfg(feature = "edit")]
+//! # fn main() -> Result<(), anyhow::Error> {
+//! use fbxcel_dom::v7400::edit::{DocumentBuilder, ModelKind};
+//!
+//! let mut builder = DocumentBuilder::new();
+//! let hips = builder.add_model("hips", ModelKind::LimbNode, None);
+//! let spine = builder.add_model("spine", ModelKind::LimbNode, Some(hips));
+//! builder.set_local_transform(hips, [0.0, 100.0, 0.0], [0.0; 3], [1.0; 3])?;
+//! builder.set_local_transform(spine, [0.0, 20.0, 0.0], [0.0; 3], [1.0; 3])?;
+//! let doc = builder.build()?;
+//!
+//! let segments: Vec<_> = doc
+//!     .bone_segments()
+//!     .into_iter()
+//!     .map(|(bone, segment)| (bone.name(), segment.tail.y, segment.length()))
+//!     .collect();
+//! // The leaf bone is extended along the parent bone.
+//! assert_eq!(
+//!     segments,
+//!     [(Some("hips"), 120.0, 20.0), (Some("spine"), 140.0, 20.0)]
+//! );
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "edit"))]
+//! # fn main() {}
+//! ```
 
-use crate::v7400::object::model::ModelHandle;
+use anyhow::Error;
+use mint::{Point3, Vector3};
+
+use crate::v7400::{
+    data::{model::BoneSegment, transform::TransformCache},
+    object::{
+        model::{ModelHandle, TypedModelHandle},
+        nodeattribute,
+    },
+};
 
 define_object_subtype! {
     /// `Model` node handle (limbnode).
     LimbNodeHandle: ModelHandle
 }
+
+impl<'a> LimbNodeHandle<'a> {
+    /// Returns the skeleton node attribute, if available.
+    pub fn skeleton(&self) -> Option<nodeattribute::LimbNodeHandle<'a>> {
+        match self.node_attribute()? {
+            nodeattribute::TypedNodeAttributeHandle::LimbNode(o) => Some(o),
+            _ => None,
+        }
+    }
+
+    /// Returns the joint orientation in degrees.
+    ///
+    /// Joint orientations (such as `jointOrient` of Maya) are exported as
+    /// `PreRotation`, so this is same as the pre-rotation.
+    pub fn joint_orientation(&self) -> Result<Vector3<f64>, Error> {
+        self.properties().pre_rotation_or_default()
+    }
+
+    /// Returns the parent limb node, if the parent model is a limb node.
+    pub fn parent_limb_node(&self) -> Option<LimbNodeHandle<'a>> {
+        match self.parent_model()? {
+            TypedModelHandle::LimbNode(o) => Some(o),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator of the child limb nodes.
+    pub fn child_limb_nodes(&self) -> impl Iterator<Item = LimbNodeHandle<'a>> {
+        self.child_models().filter_map(|model| match model {
+            TypedModelHandle::LimbNode(o) => Some(o),
+            _ => None,
+        })
+    }
+
+    /// Returns the rest-pose segment of the bone.
+    ///
+    /// The head is the global position of the limb node, and the tail is the
+    /// mean of the global positions of the child limb nodes.
+    /// For leaf bones, the tail is extended from the head in the direction
+    /// of the parent segment, by the length of the parent segment scaled by
+    /// `LimbLength` of the skeleton; bones without parent nor children have
+    /// zero length.
+    ///
+    /// The rest pose is the transform in the model properties, not the bind
+    /// pose.
+    /// To compute segments of many bones, use
+    /// [`segment_with`](#method.segment_with) or
+    /// [`Document::bone_segments`](../../struct.Document.html#method.bone_segments).
+    pub fn segment(&self) -> Result<BoneSegment, Error> {
+        self.segment_with(&mut TransformCache::new())
+    }
+
+    /// Returns the rest-pose segment of the bone, using the given transform
+    /// cache.
+    ///
+    /// See [`segment`](#method.segment) for detail.
+    pub fn segment_with(&self, cache: &mut TransformCache) -> Result<BoneSegment, Error> {
+        let head = position(cache, self)?;
+        let mut sum = [0.0; 3];
+        let mut count = 0;
+        for child in self.child_limb_nodes() {
+            let p = position(cache, &child)?;
+            sum[0] += p.x;
+            sum[1] += p.y;
+            sum[2] += p.z;
+            count += 1;
+        }
+        if count != 0 {
+            let n = f64::from(count);
+            let tail = Point3 {
+                x: sum[0] / n,
+                y: sum[1] / n,
+                z: sum[2] / n,
+            };
+            return Ok(BoneSegment { head, tail });
+        }
+
+        let parent = match self.parent_limb_node() {
+            Some(v) => v,
+            None => return Ok(BoneSegment { head, tail: head }),
+        };
+        let parent_head = position(cache, &parent)?;
+        let scale = self
+            .skeleton()
+            .map(|skeleton| skeleton.properties().limb_length_or_default())
+            .transpose()?
+            .unwrap_or(1.0);
+        let tail = Point3 {
+            x: head.x + (head.x - parent_head.x) * scale,
+            y: head.y + (head.y - parent_head.y) * scale,
+            z: head.z + (head.z - parent_head.z) * scale,
+        };
+        Ok(BoneSegment { head, tail })
+    }
+}
+
+/// Returns the global position of the model.
+fn position(cache: &mut TransformCache, model: &ModelHandle<'_>) -> Result<Point3<f64>, Error> {
+    let m = cache.global_transform(model)?;
+    Ok(Point3 {
+        x: m.w.x,
+        y: m.w.y,
+        z: m.w.z,
+    })
+}
@@ -0,0 +1,122 @@
+//! Model paths.
+
+use std::collections::HashSet;
+
+use crate::v7400::{
+    object::{
+        model::{ModelHandle, TypedModelHandle},
+        Name, NameMatchOptions,
+    },
+    Document,
+};
+
+/// Separator of path components.
+const SEPARATOR: char = '/';
+
+/// Options for model paths.
+///
+/// A model path is a slash-separated list of model names from a root model
+/// (a model without parent model) to the model, such as `"Root/Hips/Spine"`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PathOptions {
+    /// Whether to strip namespaces from names.
+    strip_namespace: bool,
+}
+
+impl PathOptions {
+    /// Creates a new default `PathOptions`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether to strip namespaces (such as `"Namespace:"` of
+    /// `"Namespace:JointName"`) from names.
+    ///
+    /// When looking up models by path, this also makes path components match
+    /// names ignoring namespaces on both sides.
+    ///
+    /// Default is `false`.
+    pub fn strip_namespace(self, strip_namespace: bool) -> Self {
+        Self { strip_namespace }
+    }
+
+    /// Returns the name of the model used for path components.
+    fn component<'a>(&self, model: &ModelHandle<'a>) -> &'a str {
+        let name = Name::new(model.name().unwrap_or_default());
+        if self.strip_namespace {
+            name.clean_name()
+        } else {
+            name.name()
+        }
+    }
+
+    /// Returns whether the model matches the given path component.
+    fn matches(&self, model: &ModelHandle<'_>, component: &str) -> bool {
+        Name::new(model.name().unwrap_or_default()).matches(
+            component,
+            NameMatchOptions::new().ignore_namespace(self.strip_namespace),
+        )
+    }
+}
+
+impl<'a> ModelHandle<'a> {
+    /// Returns the slash-separated path of the model from the root model.
+    pub fn path(&self) -> String {
+        self.path_with_options(PathOptions::default())
+    }
+
+    /// Returns the slash-separated path of the model from the root model
+    /// using the given options.
+    pub fn path_with_options(&self, options: PathOptions) -> String {
+        let mut components = vec![options.component(self)];
+        let mut visited = HashSet::new();
+        visited.insert(self.object_id());
+        let mut current = *self;
+        while let Some(parent) = current.parent_model() {
+            // Stop at broken (cyclic) hierarchies.
+            if !visited.insert(parent.object_id()) {
+                break;
+            }
+            components.push(options.component(&parent));
+            current = *parent;
+        }
+        components.reverse();
+        components.join(&SEPARATOR.to_string())
+    }
+}
+
+/// Finds a model by the slash-separated path.
+///
+/// If multiple models match, the first one found by depth-first search is
+/// returned.
+pub(crate) fn find_by_path<'a>(
+    doc: &'a Document,
+    path: &str,
+    options: PathOptions,
+) -> Option<TypedModelHandle<'a>> {
+    let components: Vec<_> = path.split(SEPARATOR).collect();
+    let mut roots: Vec<_> = doc.model_roots().collect();
+    roots.sort_by_key(|model| model.object_id());
+    roots
+        .into_iter()
+        .find_map(|root| find_from(root, &components, options))
+}
+
+/// Finds a model matching the given path components, starting from the
+/// given model.
+fn find_from<'a>(
+    model: TypedModelHandle<'a>,
+    components: &[&str],
+    options: PathOptions,
+) -> Option<TypedModelHandle<'a>> {
+    let (first, rest) = components.split_first()?;
+    if !options.matches(&model, first) {
+        return None;
+    }
+    if rest.is_empty() {
+        return Some(model);
+    }
+    model
+        .child_models()
+        .find_map(|child| find_from(child, rest, options))
+}
@@ -0,0 +1,147 @@
+//! Object names.
+
+/// Separator between the name and the class in raw object names.
+const CLASS_SEPARATOR: &str = "\u{0}\u{1}";
+
+/// Separator between namespaces and the name.
+const NAMESPACE_SEPARATOR: char = ':';
+
+/// Namespace-aware object name.
+///
+/// Raw FBX object names often look like `"Namespace:JointName\u{0}\u{1}Model"`.
+/// This type provides accessors to each part of such names:
+///
+/// * [`name`](#method.name): `"Namespace:JointName"`,
+/// * [`namespace`](#method.namespace): `Some("Namespace")`,
+/// * [`clean_name`](#method.clean_name): `"JointName"`, and
+/// * [`class_suffix`](#method.class_suffix): `Some("Model")`.
+///
+/// Namespaces can be nested (such as `"Outer:Inner:JointName"`); in that
+/// case the namespace is `"Outer:Inner"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Name<'a> {
+    /// Name without class suffix.
+    name: &'a str,
+    /// Class suffix.
+    class_suffix: Option<&'a str>,
+}
+
+impl<'a> Name<'a> {
+    /// Creates a new `Name` from the given raw name.
+    ///
+    /// The raw name may contain class suffix.
+    pub fn new(raw: &'a str) -> Self {
+        match raw.find(CLASS_SEPARATOR) {
+            Some(pos) => Self {
+                name: &raw[..pos],
+                class_suffix: Some(&raw[pos + CLASS_SEPARATOR.len()..]),
+            },
+            None => Self {
+                name: raw,
+                class_suffix: None,
+            },
+        }
+    }
+
+    /// Creates a new `Name` from the name and class suffix.
+    pub(crate) fn from_parts(name: &'a str, class_suffix: &'a str) -> Self {
+        Self {
+            name,
+            class_suffix: Some(class_suffix),
+        }
+    }
+
+    /// Returns the name without class suffix, including namespaces.
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Returns the namespace if available.
+    pub fn namespace(&self) -> Option<&'a str> {
+        self.name
+            .rfind(NAMESPACE_SEPARATOR)
+            .map(|pos| &self.name[..pos])
+    }
+
+    /// Returns the name without namespaces and class suffix.
+    pub fn clean_name(&self) -> &'a str {
+        match self.name.rfind(NAMESPACE_SEPARATOR) {
+            Some(pos) => &self.name[pos + NAMESPACE_SEPARATOR.len_utf8()..],
+            None => self.name,
+        }
+    }
+
+    /// Returns the class suffix if available.
+    pub fn class_suffix(&self) -> Option<&'a str> {
+        self.class_suffix
+    }
+
+    /// Returns whether the name matches the given string.
+    ///
+    /// The given string is compared with [`clean_name`](#method.clean_name)
+    /// if namespaces are ignored, or with [`name`](#method.name) otherwise.
+    pub fn matches(&self, other: &str, options: NameMatchOptions) -> bool {
+        if options.ignore_namespace {
+            self.clean_name() == Name::new(other).clean_name()
+        } else {
+            self.name == other
+        }
+    }
+}
+
+/// Options for name matching.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NameMatchOptions {
+    /// Whether to ignore namespaces.
+    ignore_namespace: bool,
+}
+
+impl NameMatchOptions {
+    /// Creates a new default `NameMatchOptions`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether to ignore namespaces.
+    ///
+    /// If namespaces are ignored, both sides are compared without namespaces,
+    /// i.e. `"JointName"`, `"NS:JointName"`, and `"Other:JointName"` match
+    /// each other.
+    ///
+    /// Default is `false`.
+    pub fn ignore_namespace(self, ignore_namespace: bool) -> Self {
+        Self { ignore_namespace }
+    }
+
+    /// Returns whether namespaces are ignored.
+    pub fn ignores_namespace(&self) -> bool {
+        self.ignore_namespace
+    }
+
+    /// Returns whether the names match under the options.
+    ///
+    /// This is same as `Name::new(name).matches(other, options)`.
+    ///
+    /// ```
+    /// use fbxcel_dom::v7400::object::NameMatchOptions;
+    ///
+    /// let options = NameMatchOptions::new();
+    /// assert!(!options.matches("NS:Hips", "Hips"));
+    /// assert!(options.ignore_namespace(true).matches("NS:Hips", "Other:Hips"));
+    /// ```
+    pub fn matches(&self, name: &str, other: &str) -> bool {
+        Name::new(name).matches(other, *self)
+    }
+
+    /// Returns the part of the name to be compared under the options.
+    ///
+    /// This is the name without namespaces if namespaces are ignored, and
+    /// the name as is otherwise.
+    pub(crate) fn comparable<'s>(&self, name: &'s str) -> &'s str {
+        if self.ignore_namespace {
+            Name::new(name).clean_name()
+        } else {
+            name
+        }
+    }
+}
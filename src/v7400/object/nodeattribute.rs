@@ -1,12 +1,17 @@
 //! `NodeAttribute` object.
 
-use crate::v7400::object::ObjectHandle;
+use crate::v7400::object::{model, ObjectHandle, TypedObjectHandle};
 
 pub use self::{
-    camera::CameraHandle, light::LightHandle, limbnode::LimbNodeHandle, null::NullHandle,
+    camera::{CameraHandle, CameraProperties},
+    camerastereo::{CameraStereoHandle, CameraStereoProperties},
+    light::{LightHandle, LightProperties},
+    limbnode::{LimbNodeHandle, LimbNodeProperties},
+    null::NullHandle,
 };
 
 mod camera;
+mod camerastereo;
 mod light;
 mod limbnode;
 mod null;
@@ -16,6 +21,8 @@ define_typed_handle! {
     TypedNodeAttributeHandle(NodeAttributeHandle) {
         /// Mesh.
         ("NodeAttribute", "Camera") => Camera(CameraHandle),
+        /// CameraStereo.
+        ("NodeAttribute", "CameraStereo") => CameraStereo(CameraStereoHandle),
         /// Light.
         ("NodeAttribute", "Light") => Light(LightHandle),
         /// LimbNode.
@@ -29,3 +36,16 @@ define_object_subtype! {
     /// `NodeAttribute` node handle.
     NodeAttributeHandle: ObjectHandle
 }
+
+impl<'a> NodeAttributeHandle<'a> {
+    /// Returns an iterator of parent model objects.
+    pub fn models(&self) -> impl Iterator<Item = model::TypedModelHandle<'a>> {
+        self.destination_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Model(o) => Some(o),
+                _ => None,
+            })
+    }
+}
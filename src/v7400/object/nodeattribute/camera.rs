@@ -1,8 +1,150 @@
 //! `NodeAttribute` object (camera).
 
-use crate::v7400::object::nodeattribute::NodeAttributeHandle;
+use crate::v7400::{
+    data::camera::{distance_in_meters, film_aspect_ratio, film_size_mm, vertical_fov},
+    object::{
+        nodeattribute::NodeAttributeHandle,
+        property::{loaders::PrimitiveLoader, ObjectProperties},
+    },
+};
 
 define_object_subtype! {
     /// `NodeAttribute` node handle (camera).
     CameraHandle: NodeAttributeHandle
 }
+
+impl<'a> CameraHandle<'a> {
+    /// Returns properties.
+    pub fn properties(&self) -> CameraProperties<'a> {
+        CameraProperties {
+            properties: self.properties_by_native_typename("FbxCamera"),
+        }
+    }
+}
+
+/// Proxy type to camera properties.
+#[derive(Debug, Clone, Copy)]
+pub struct CameraProperties<'a> {
+    /// Properties.
+    properties: ObjectProperties<'a>,
+}
+
+impl<'a> CameraProperties<'a> {
+    impl_prop_proxy_getters! {
+        /// Returns focal length in millimeters.
+        focal_length -> f64 {
+            name = "FocalLength",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "focal length",
+            default: {
+                /// Returns focal length in millimeters.
+                ///
+                /// Returns default if the value is not set.
+                focal_length_or_default = 34.89327
+            }
+        }
+
+        /// Returns film width in inches.
+        film_width -> f64 {
+            name = "FilmWidth",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "film width",
+            default: {
+                /// Returns film width in inches.
+                ///
+                /// Returns default if the value is not set.
+                film_width_or_default = 0.816
+            }
+        }
+
+        /// Returns film height in inches.
+        film_height -> f64 {
+            name = "FilmHeight",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "film height",
+            default: {
+                /// Returns film height in inches.
+                ///
+                /// Returns default if the value is not set.
+                film_height_or_default = 0.612
+            }
+        }
+
+        /// Returns near clip plane distance in scene units.
+        near_plane -> f64 {
+            name = "NearPlane",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "near plane distance",
+            default: {
+                /// Returns near clip plane distance in scene units.
+                ///
+                /// Returns default if the value is not set.
+                near_plane_or_default = 10.0
+            }
+        }
+
+        /// Returns far clip plane distance in scene units.
+        far_plane -> f64 {
+            name = "FarPlane",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "far plane distance",
+            default: {
+                /// Returns far clip plane distance in scene units.
+                ///
+                /// Returns default if the value is not set.
+                far_plane_or_default = 4000.0
+            }
+        }
+    }
+
+    /// Returns the film size in millimeters.
+    pub fn film_size_mm_or_default(&self) -> Result<[f64; 2], anyhow::Error> {
+        Ok(film_size_mm(
+            self.film_width_or_default()?,
+            self.film_height_or_default()?,
+        ))
+    }
+
+    /// Returns the film aspect ratio (width / height).
+    ///
+    /// Returns `Ok(None)` if the film height is not positive.
+    pub fn aspect_ratio_or_default(&self) -> Result<Option<f64>, anyhow::Error> {
+        Ok(film_aspect_ratio(
+            self.film_width_or_default()?,
+            self.film_height_or_default()?,
+        ))
+    }
+
+    /// Returns the vertical field of view in radians, computed from the focal
+    /// length and the film height.
+    ///
+    /// Returns `Ok(None)` if the focal length is not positive.
+    pub fn vertical_fov_or_default(&self) -> Result<Option<f64>, anyhow::Error> {
+        Ok(vertical_fov(
+            self.focal_length_or_default()?,
+            self.film_height_or_default()?,
+        ))
+    }
+
+    /// Returns the near and far clip plane distances in meters.
+    ///
+    /// `unit_scale_factor` is the `UnitScaleFactor` of `GlobalSettings`
+    /// (centimeters per scene unit).
+    pub fn clip_planes_in_meters_or_default(
+        &self,
+        unit_scale_factor: f64,
+    ) -> Result<[f64; 2], anyhow::Error> {
+        Ok([
+            distance_in_meters(self.near_plane_or_default()?, unit_scale_factor),
+            distance_in_meters(self.far_plane_or_default()?, unit_scale_factor),
+        ])
+    }
+}
+
+impl<'a> std::ops::Deref for CameraProperties<'a> {
+    type Target = ObjectProperties<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.properties
+    }
+}
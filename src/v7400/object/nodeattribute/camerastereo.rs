@@ -0,0 +1,165 @@
+//! `NodeAttribute` object (camerastereo).
+
+use crate::v7400::{
+    data::camera::{StereoMode, StereoModeLoader},
+    object::{
+        nodeattribute::{self, NodeAttributeHandle},
+        property::{
+            loaders::{BorrowedStringLoader, PrimitiveLoader},
+            ObjectProperties,
+        },
+        TypedObjectHandle,
+    },
+};
+
+define_object_subtype! {
+    /// `NodeAttribute` node handle (camerastereo).
+    CameraStereoHandle: NodeAttributeHandle
+}
+
+impl<'a> CameraStereoHandle<'a> {
+    /// Returns the left camera attribute of the rig if available.
+    pub fn left_camera(&self) -> Option<nodeattribute::CameraHandle<'a>> {
+        get_camera_node(self, "LeftCamera")
+    }
+
+    /// Returns the right camera attribute of the rig if available.
+    pub fn right_camera(&self) -> Option<nodeattribute::CameraHandle<'a>> {
+        get_camera_node(self, "RightCamera")
+    }
+
+    /// Returns properties.
+    pub fn properties(&self) -> CameraStereoProperties<'a> {
+        CameraStereoProperties {
+            properties: self.properties_by_native_typename("FbxCameraStereo"),
+        }
+    }
+}
+
+/// Returns a camera attribute connected with the given label, if available.
+fn get_camera_node<'a>(
+    obj: &CameraStereoHandle<'a>,
+    label: &str,
+) -> Option<nodeattribute::CameraHandle<'a>> {
+    obj.source_objects()
+        .filter(|obj| obj.label() == Some(label))
+        .filter_map(|obj| obj.object_handle())
+        .filter_map(|obj| match obj.get_typed() {
+            TypedObjectHandle::NodeAttribute(nodeattribute::TypedNodeAttributeHandle::Camera(
+                o,
+            )) => Some(o),
+            _ => None,
+        })
+        .next()
+}
+
+/// Proxy type to stereo camera properties.
+#[derive(Debug, Clone, Copy)]
+pub struct CameraStereoProperties<'a> {
+    /// Properties.
+    properties: ObjectProperties<'a>,
+}
+
+impl<'a> CameraStereoProperties<'a> {
+    impl_prop_proxy_getters! {
+        /// Returns stereo mode.
+        stereo -> StereoMode {
+            name = "Stereo",
+            loader = StereoModeLoader,
+            description = "stereo mode",
+            default: {
+                /// Returns stereo mode.
+                ///
+                /// Returns default if the value is not set.
+                stereo_or_default = StereoMode::None
+            }
+        }
+
+        /// Returns interaxial separation, i.e. distance between the left and
+        /// right cameras.
+        interaxial_separation -> f64 {
+            name = "InteraxialSeparation",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "interaxial separation",
+            default: {
+                /// Returns interaxial separation.
+                ///
+                /// Returns default if the value is not set.
+                interaxial_separation_or_default = 0.635
+            }
+        }
+
+        /// Returns distance to the zero parallax plane.
+        zero_parallax -> f64 {
+            name = "ZeroParallax",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "zero parallax",
+            default: {
+                /// Returns distance to the zero parallax plane.
+                ///
+                /// Returns default if the value is not set.
+                zero_parallax_or_default = 100.0
+            }
+        }
+
+        /// Returns toe-in adjustment.
+        toe_in_adjust -> f64 {
+            name = "ToeInAdjust",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "toe-in adjustment",
+            default: {
+                /// Returns toe-in adjustment.
+                ///
+                /// Returns default if the value is not set.
+                toe_in_adjust_or_default = 0.0
+            }
+        }
+
+        /// Returns film offset of the right camera.
+        film_offset_right_cam -> f64 {
+            name = "FilmOffsetRightCam",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "film offset of the right camera",
+            default: {
+                /// Returns film offset of the right camera.
+                ///
+                /// Returns default if the value is not set.
+                film_offset_right_cam_or_default = 0.0
+            }
+        }
+
+        /// Returns film offset of the left camera.
+        film_offset_left_cam -> f64 {
+            name = "FilmOffsetLeftCam",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "film offset of the left camera",
+            default: {
+                /// Returns film offset of the left camera.
+                ///
+                /// Returns default if the value is not set.
+                film_offset_left_cam_or_default = 0.0
+            }
+        }
+
+        /// Returns precomputed stereo file name.
+        precomp_file_name -> &'a str {
+            name = "PrecompFileName",
+            loader = BorrowedStringLoader::new(),
+            description = "precomputed file name",
+            default: {
+                /// Returns precomputed stereo file name.
+                ///
+                /// Returns default if the value is not set.
+                precomp_file_name_or_default = ""
+            }
+        }
+    }
+}
+
+impl<'a> std::ops::Deref for CameraStereoProperties<'a> {
+    type Target = ObjectProperties<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.properties
+    }
+}
@@ -1,8 +1,193 @@
 //! `NodeAttribute` object (light).
 
-use crate::v7400::object::nodeattribute::NodeAttributeHandle;
+use rgb::RGB;
+
+use crate::v7400::{
+    data::light::{normalize_intensity, DecayType, DecayTypeLoader, LightType, LightTypeLoader},
+    object::{
+        nodeattribute::NodeAttributeHandle,
+        property::{
+            loaders::{PrimitiveLoader, RgbLoader},
+            ObjectProperties,
+        },
+    },
+};
 
 define_object_subtype! {
     /// `NodeAttribute` node handle (light).
     LightHandle: NodeAttributeHandle
 }
+
+impl<'a> LightHandle<'a> {
+    /// Returns properties.
+    pub fn properties(&self) -> LightProperties<'a> {
+        LightProperties {
+            properties: self.properties_by_native_typename("FbxLight"),
+        }
+    }
+}
+
+/// Proxy type to light properties.
+#[derive(Debug, Clone, Copy)]
+pub struct LightProperties<'a> {
+    /// Properties.
+    properties: ObjectProperties<'a>,
+}
+
+impl<'a> LightProperties<'a> {
+    impl_prop_proxy_getters! {
+        /// Returns light type.
+        light_type -> LightType {
+            name = "LightType",
+            loader = LightTypeLoader,
+            description = "light type",
+            default: {
+                /// Returns light type.
+                ///
+                /// Returns default if the value is not set.
+                light_type_or_default = LightType::Point
+            }
+        }
+
+        /// Returns whether the light is enabled.
+        cast_light -> bool {
+            name = "CastLight",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "cast light flag",
+            default: {
+                /// Returns whether the light is enabled.
+                ///
+                /// Returns default if the value is not set.
+                cast_light_or_default = true
+            }
+        }
+
+        /// Returns light color.
+        color -> RGB<f64> {
+            name = "Color",
+            loader = RgbLoader::<RGB<f64>>::new(),
+            description = "light color",
+            default: {
+                /// Returns light color.
+                ///
+                /// Returns default if the value is not set.
+                color_or_default = RGB::from([1.0; 3])
+            }
+        }
+
+        /// Returns raw light intensity.
+        ///
+        /// See [`normalized_intensity_or_default`] for engine-friendly value.
+        ///
+        /// [`normalized_intensity_or_default`]: #method.normalized_intensity_or_default
+        intensity -> f64 {
+            name = "Intensity",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "light intensity",
+            default: {
+                /// Returns raw light intensity.
+                ///
+                /// Returns default if the value is not set.
+                intensity_or_default = 100.0
+            }
+        }
+
+        /// Returns inner cone angle of spot light in degrees.
+        inner_angle -> f64 {
+            name = "InnerAngle",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "inner cone angle",
+            default: {
+                /// Returns inner cone angle of spot light in degrees.
+                ///
+                /// Returns default if the value is not set.
+                inner_angle_or_default = 0.0
+            }
+        }
+
+        /// Returns outer cone angle of spot light in degrees.
+        outer_angle -> f64 {
+            name = "OuterAngle",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "outer cone angle",
+            default: {
+                /// Returns outer cone angle of spot light in degrees.
+                ///
+                /// Returns default if the value is not set.
+                outer_angle_or_default = 45.0
+            }
+        }
+
+        /// Returns decay type.
+        decay_type -> DecayType {
+            name = "DecayType",
+            loader = DecayTypeLoader,
+            description = "decay type",
+            default: {
+                /// Returns decay type.
+                ///
+                /// Returns default if the value is not set.
+                decay_type_or_default = DecayType::None
+            }
+        }
+
+        /// Returns distance where the decay starts.
+        decay_start -> f64 {
+            name = "DecayStart",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "decay start distance",
+            default: {
+                /// Returns distance where the decay starts.
+                ///
+                /// Returns default if the value is not set.
+                decay_start_or_default = 0.0
+            }
+        }
+
+        /// Returns whether the light casts shadows.
+        cast_shadows -> bool {
+            name = "CastShadows",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "cast shadows flag",
+            default: {
+                /// Returns whether the light casts shadows.
+                ///
+                /// Returns default if the value is not set.
+                cast_shadows_or_default = false
+            }
+        }
+    }
+
+    /// Returns the intensity normalized so that `1.0` is "full intensity".
+    ///
+    /// See [`data::light`](../../data/light/index.html) for the heuristic.
+    pub fn normalized_intensity_or_default(&self) -> Result<f64, anyhow::Error> {
+        self.intensity_or_default().map(normalize_intensity)
+    }
+
+    /// Returns the estimated attenuation radius.
+    ///
+    /// `cutoff` is the normalized intensity under which the light is
+    /// considered to have no effect (`1.0 / 256.0` is a reasonable choice).
+    ///
+    /// Returns `Ok(None)` if the light does not decay.
+    /// See [`DecayType::attenuation_radius`] for the heuristic.
+    ///
+    /// [`DecayType::attenuation_radius`]:
+    ///     ../../data/light/enum.DecayType.html#method.attenuation_radius
+    pub fn attenuation_radius_estimate(&self, cutoff: f64) -> Result<Option<f64>, anyhow::Error> {
+        Ok(self.decay_type_or_default()?.attenuation_radius(
+            self.normalized_intensity_or_default()?,
+            self.decay_start_or_default()?,
+            cutoff,
+        ))
+    }
+}
+
+impl<'a> std::ops::Deref for LightProperties<'a> {
+    type Target = ObjectProperties<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.properties
+    }
+}
@@ -1,8 +1,76 @@
 //! `NodeAttribute` object (limbnode).
 
-use crate::v7400::object::nodeattribute::NodeAttributeHandle;
+use rgb::RGB;
+
+use crate::v7400::object::{
+    nodeattribute::NodeAttributeHandle,
+    property::{
+        loaders::{PrimitiveLoader, RgbLoader},
+        ObjectProperties,
+    },
+};
 
 define_object_subtype! {
     /// `NodeAttribute` node handle (limbnode).
     LimbNodeHandle: NodeAttributeHandle
 }
+
+impl<'a> LimbNodeHandle<'a> {
+    /// Returns properties.
+    pub fn properties(&self) -> LimbNodeProperties<'a> {
+        LimbNodeProperties {
+            properties: self.properties_by_native_typename("FbxSkeleton"),
+        }
+    }
+}
+
+/// Proxy type to limb node (skeleton) properties.
+#[derive(Debug, Clone, Copy)]
+pub struct LimbNodeProperties<'a> {
+    /// Properties.
+    properties: ObjectProperties<'a>,
+}
+
+impl<'a> LimbNodeProperties<'a> {
+    impl_prop_proxy_getters! {
+        /// Returns display size of the limb.
+        size -> f64 {
+            name = "Size",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "limb size",
+            default: {
+                /// Returns display size of the limb.
+                ///
+                /// Returns default if the value is not set.
+                size_or_default = 100.0
+            }
+        }
+
+        /// Returns limb length, relative to the distance to the parent.
+        limb_length -> f64 {
+            name = "LimbLength",
+            loader = PrimitiveLoader::<f64>::new(),
+            description = "limb length",
+            default: {
+                /// Returns limb length, relative to the distance to the
+                /// parent.
+                ///
+                /// Returns default if the value is not set.
+                limb_length_or_default = 1.0
+            }
+        }
+
+        /// Returns display color of the limb.
+        color -> RGB<f64> {
+            name = "Color",
+            loader = RgbLoader::<RGB<f64>>::new(),
+            description = "limb color",
+            default: {
+                /// Returns display color of the limb.
+                ///
+                /// Returns default if the value is not set.
+                color_or_default = RGB::from([0.8; 3])
+            }
+        }
+    }
+}
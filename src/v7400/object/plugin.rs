@@ -0,0 +1,163 @@
+//! Objects of plugin-defined classes.
+//!
+//! Plugins of authoring tools can write objects of their own classes (such
+//! as procedural geometries), which are not interpreted by this crate.
+//! [`PluginObjectHandle`] exposes such objects with raw child nodes and
+//! properties, so that pipelines can at least tell which plugin data a file
+//! depends on.
+//!
+//! ```
+//! # #[cfg(feature = "edit")]
+//! # fn main() -> Result<(), anyhow::Error> {
+//! use fbxcel_dom::v7400::{
+//!     edit::DocumentBuilder,
+//!     object::property::{loaders::PrimitiveLoader, OwnedPropertyValue},
+//! };
+//!
+//! let mut builder = DocumentBuilder::new();
+//! let edit = builder.edit_mut();
+//! let id = edit.add_object("FluidSolver", "Ocean", "FluidSolver", "Grid");
+//! edit.insert_property(id, "Resolution", "int", "", "", OwnedPropertyValue::I32(256))?;
+//! let doc = builder.build()?;
+//!
+//! let obj = doc.plugin_objects().next().expect("Plugin object should exist");
+//! assert_eq!(obj.node_name(), "FluidSolver");
+//! assert_eq!(obj.subclass(), "Grid");
+//! let resolution = obj.property("Resolution", PrimitiveLoader::<i32>::new())?;
+//! assert_eq!(resolution, Some(256));
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "edit"))]
+//! # fn main() {}
+//! ```
+
+use anyhow::Error;
+use fbxcel::{low::v7400::AttributeValue, tree::v7400::NodeHandle};
+
+use crate::v7400::{
+    object::{
+        geometry,
+        property::{LoadProperty, ObjectProperties},
+        ObjectHandle, TypedObjectHandle,
+    },
+    ErrorLocation,
+};
+
+/// Object node names defined by the FBX SDK but not supported by this
+/// crate.
+///
+/// Objects of these node names are not considered as plugin objects.
+const SDK_NODE_NAMES: &[&str] = &[
+    "BindingOperator",
+    "BindingTable",
+    "Implementation",
+    "LayeredTexture",
+    "ObjectMetaData",
+    "Pose",
+];
+
+/// Geometry subclasses defined by the FBX SDK.
+///
+/// Geometries with other subclasses are considered as plugin objects.
+const SDK_GEOMETRY_SUBCLASSES: &[&str] = &[
+    "Boundary",
+    "Line",
+    "Mesh",
+    "Nurbs",
+    "NurbsCurve",
+    "NurbsSurface",
+    "Patch",
+    "Shape",
+    "TrimNurbsSurface",
+];
+
+define_object_subtype! {
+    /// Handle of an object with a plugin-defined class.
+    PluginObjectHandle: ObjectHandle
+}
+
+impl<'a> PluginObjectHandle<'a> {
+    /// Creates a new handle if the object is of a plugin-defined class.
+    ///
+    /// Objects of classes not supported by this crate are considered as
+    /// plugin objects, except for the classes defined by the FBX SDK.
+    /// Objects of user-registered extension types are also plugin objects.
+    pub fn from_object(obj: ObjectHandle<'a>) -> Option<Self> {
+        let is_plugin = match obj.get_typed() {
+            TypedObjectHandle::Unknown(_) | TypedObjectHandle::Extension(_) => {
+                !SDK_NODE_NAMES.contains(&obj.node().name())
+            }
+            TypedObjectHandle::Geometry(geometry::TypedGeometryHandle::Unknown(_)) => {
+                !SDK_GEOMETRY_SUBCLASSES.contains(&obj.subclass())
+            }
+            _ => false,
+        };
+        if is_plugin {
+            Some(Self::new(obj))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the name of the object node, such as `Geometry`.
+    pub fn node_name(&self) -> &'a str {
+        self.node().name()
+    }
+
+    /// Returns an iterator of the raw child nodes, except for the properties
+    /// node.
+    pub fn children(&self) -> impl Iterator<Item = NodeHandle<'a>> {
+        self.node()
+            .children()
+            .filter(|child| child.name() != "Properties70")
+    }
+
+    /// Returns the first raw child node with the given name, if available.
+    pub fn child(&self, name: &str) -> Option<NodeHandle<'a>> {
+        self.node().first_child_by_name(name)
+    }
+
+    /// Returns the attributes of the first child node with the given name,
+    /// if available.
+    ///
+    /// Note that lazily loaded arrays are not decoded.
+    pub fn child_attributes(&self, name: &str) -> Option<&'a [AttributeValue]> {
+        self.child(name).map(|child| child.attributes())
+    }
+
+    /// Returns properties.
+    ///
+    /// Default properties are taken from the first property template of the
+    /// object node name in the definitions, since the native type name of
+    /// plugin classes is unknown.
+    pub fn properties(&self) -> ObjectProperties<'a> {
+        let native_type = self
+            .document()
+            .definitions()
+            .object_type(self.node_name())
+            .and_then(|def| def.property_templates().next())
+            .map_or("", |template| template.native_type());
+        self.properties_by_native_typename(native_type)
+    }
+
+    /// Loads the property with the given name and loader.
+    ///
+    /// Returns `Ok(None)` if the property is not found.
+    pub fn property<V>(&self, name: &str, loader: V) -> Result<Option<V::Value>, Error>
+    where
+        V: LoadProperty<'a, Error = Error>,
+    {
+        let description = loader.expecting();
+        self.properties()
+            .get_property(name)
+            .map(|p| p.load_value(loader))
+            .transpose()
+            .map_err(|e| {
+                Error::from(crate::v7400::Error::PropertyLoad {
+                    location: ErrorLocation::object(self.object_id()).with_property(name),
+                    description,
+                    source: e,
+                })
+            })
+    }
+}
@@ -1,14 +1,27 @@
 //! Object properties and related stuff.
 
+pub(crate) use self::index::PropertiesIndex;
 pub use self::{
+    compound::CompoundProperty,
+    edits::{EditedProperties, PropertyEdit, PropertyEditRecord, PropertyEdits},
+    entry::PropertyEntry,
+    flags::PropertyFlags,
     loader::LoadProperty,
     node::{PropertyHandle, PropertyNodeId},
-    object_props::ObjectProperties,
+    object_props::{ObjectProperties, PropertyOrigin},
     properties::{PropertiesHandle, PropertiesNodeId},
+    value::{OwnedPropertyValue, PropertyValue},
 };
 
+pub mod alias;
+mod compound;
+mod edits;
+mod entry;
+mod flags;
+mod index;
 mod loader;
 pub mod loaders;
 mod node;
 mod object_props;
 mod properties;
+mod value;
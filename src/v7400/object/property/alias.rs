@@ -0,0 +1,34 @@
+//! Property name aliases.
+//!
+//! Some exporters write legacy or abbreviated property names (such as
+//! `Shininess` instead of `ShininessExponent`, or `Color` instead of
+//! `DiffuseColor`).
+//! This module centralizes that compatibility knowledge.
+//!
+//! Aliases are only used by explicit lookups such as
+//! [`ObjectProperties::get_property_or_alias`]; typed property getters read
+//! the canonical names only.
+//!
+//! Legacy `Ambient`, `Diffuse`, `Emissive`, and `Specular` properties are not
+//! aliases of the `*Color` properties, since their values are premultiplied
+//! by the corresponding `*Factor`.
+//!
+//! [`ObjectProperties::get_property_or_alias`]:
+//! ../struct.ObjectProperties.html#method.get_property_or_alias
+
+/// Canonical property names and their known aliases, in priority order.
+const ALIASES: &[(&str, &[&str])] = &[
+    ("DiffuseColor", &["Color"]),
+    ("ReflectionFactor", &["Reflectivity"]),
+    ("ShininessExponent", &["Shininess"]),
+];
+
+/// Returns known aliases of the given canonical property name.
+///
+/// Returns an empty slice if the name has no known aliases.
+pub fn aliases(name: &str) -> &'static [&'static str] {
+    ALIASES
+        .iter()
+        .find(|(canonical, _)| *canonical == name)
+        .map_or(&[], |(_, aliases)| aliases)
+}
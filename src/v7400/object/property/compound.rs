@@ -0,0 +1,111 @@
+//! Compound property grouping.
+//!
+//! Property names such as `d|X` or `Maya|param|sub` encode hierarchy with
+//! `|`.
+//! [`CompoundProperty`] groups such properties into a tree.
+
+use log::warn;
+
+use crate::v7400::object::property::PropertyHandle;
+
+/// Separator of compound property name segments.
+const SEPARATOR: char = '|';
+
+/// A node of the compound property tree.
+///
+/// The root node has an empty name and no property.
+#[derive(Debug, Clone)]
+pub struct CompoundProperty<'a> {
+    /// Name segment.
+    name: &'a str,
+    /// Property node with the full path of this node, if any.
+    property: Option<PropertyHandle<'a>>,
+    /// Child nodes, in the order of first appearance.
+    children: Vec<CompoundProperty<'a>>,
+}
+
+impl<'a> CompoundProperty<'a> {
+    /// Creates an empty node.
+    fn new(name: &'a str) -> Self {
+        Self {
+            name,
+            property: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Groups the given properties into a tree, and returns the root node.
+    ///
+    /// Properties whose names cannot be read are skipped with a warning.
+    pub fn from_properties(props: impl IntoIterator<Item = PropertyHandle<'a>>) -> Self {
+        let mut root = Self::new("");
+        for prop in props {
+            let name = match prop.name() {
+                Ok(v) => v,
+                Err(e) => {
+                    warn!("Ignoring property with unreadable name: {}", e);
+                    continue;
+                }
+            };
+            let node = name
+                .split(SEPARATOR)
+                .fold(&mut root, |node, segment| node.child_or_insert(segment));
+            if node.property.is_some() {
+                warn!(
+                    "Duplicate property name {:?}, using the first one: node_id={:?}",
+                    name,
+                    prop.node_id()
+                );
+                continue;
+            }
+            node.property = Some(prop);
+        }
+
+        root
+    }
+
+    /// Returns the child with the given name segment, creating it if absent.
+    fn child_or_insert(&mut self, segment: &'a str) -> &mut Self {
+        let index = match self.children.iter().position(|c| c.name == segment) {
+            Some(i) => i,
+            None => {
+                self.children.push(Self::new(segment));
+                self.children.len() - 1
+            }
+        };
+        &mut self.children[index]
+    }
+
+    /// Returns the name segment.
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Returns the property node with the full path of this node, if any.
+    ///
+    /// Intermediate nodes may have no corresponding property node.
+    pub fn property(&self) -> Option<PropertyHandle<'a>> {
+        self.property
+    }
+
+    /// Returns the child nodes.
+    pub fn children(&self) -> &[CompoundProperty<'a>] {
+        &self.children
+    }
+
+    /// Returns the child node with the given name segment.
+    pub fn child(&self, segment: &str) -> Option<&CompoundProperty<'a>> {
+        self.children.iter().find(|c| c.name == segment)
+    }
+
+    /// Returns the descendant node with the given `|`-separated path.
+    pub fn get(&self, path: &str) -> Option<&CompoundProperty<'a>> {
+        path.split(SEPARATOR)
+            .try_fold(self, |node, segment| node.child(segment))
+    }
+
+    /// Returns whether the node has children.
+    pub fn is_compound(&self) -> bool {
+        !self.children.is_empty()
+    }
+}
@@ -0,0 +1,175 @@
+//! Property edit overlay.
+//!
+//! [`PropertyEdits`] records typed set, insert and delete operations against
+//! object properties without mutating the parsed tree.
+//! Read APIs through [`EditedProperties`] see the overlay on top of the
+//! document.
+
+use std::collections::HashMap;
+
+use anyhow::Error;
+
+use crate::v7400::object::{
+    property::{ObjectProperties, OwnedPropertyValue, PropertyValue},
+    ObjectId,
+};
+
+/// A property edit operation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyEdit {
+    /// Sets the value of a property, keeping its data type.
+    Set(OwnedPropertyValue),
+    /// Inserts a new property with the given data type.
+    Insert {
+        /// Data type name (such as `KString` or `Vector3D`).
+        data_type: String,
+        /// Value.
+        value: OwnedPropertyValue,
+    },
+    /// Deletes a property.
+    Delete,
+}
+
+/// A recorded property edit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyEditRecord {
+    /// Target object.
+    pub object: ObjectId,
+    /// Property name.
+    pub name: String,
+    /// Edit operation.
+    pub edit: PropertyEdit,
+}
+
+/// Journal of property edits.
+#[derive(Default, Debug, Clone)]
+pub struct PropertyEdits {
+    /// Edits in the recorded order.
+    journal: Vec<PropertyEditRecord>,
+    /// Index of the latest edit for each property.
+    latest: HashMap<(ObjectId, String), usize>,
+}
+
+impl PropertyEdits {
+    /// Creates a new empty journal.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an edit.
+    fn record(&mut self, object: ObjectId, name: &str, edit: PropertyEdit) {
+        self.latest
+            .insert((object, name.to_owned()), self.journal.len());
+        self.journal.push(PropertyEditRecord {
+            object,
+            name: name.to_owned(),
+            edit,
+        });
+    }
+
+    /// Records setting the value of a property.
+    pub fn set(&mut self, object: ObjectId, name: &str, value: impl Into<OwnedPropertyValue>) {
+        self.record(object, name, PropertyEdit::Set(value.into()));
+    }
+
+    /// Records inserting a new property.
+    pub fn insert(
+        &mut self,
+        object: ObjectId,
+        name: &str,
+        data_type: &str,
+        value: impl Into<OwnedPropertyValue>,
+    ) {
+        self.record(
+            object,
+            name,
+            PropertyEdit::Insert {
+                data_type: data_type.to_owned(),
+                value: value.into(),
+            },
+        );
+    }
+
+    /// Records deleting a property.
+    pub fn delete(&mut self, object: ObjectId, name: &str) {
+        self.record(object, name, PropertyEdit::Delete);
+    }
+
+    /// Returns the latest edit for the property, if any.
+    pub fn get(&self, object: ObjectId, name: &str) -> Option<&PropertyEdit> {
+        self.latest
+            .get(&(object, name.to_owned()))
+            .map(|&i| &self.journal[i].edit)
+    }
+
+    /// Returns the edits in the recorded order.
+    pub fn journal(&self) -> &[PropertyEditRecord] {
+        &self.journal
+    }
+
+    /// Returns whether no edits are recorded.
+    pub fn is_empty(&self) -> bool {
+        self.journal.is_empty()
+    }
+
+    /// Clears all edits.
+    pub fn clear(&mut self) {
+        self.journal.clear();
+        self.latest.clear();
+    }
+
+    /// Returns a view of the object properties with the edits applied.
+    pub fn view<'a>(&'a self, properties: ObjectProperties<'a>) -> EditedProperties<'a> {
+        EditedProperties {
+            properties,
+            edits: self,
+        }
+    }
+}
+
+/// Object properties with a [`PropertyEdits`] overlay applied.
+#[derive(Debug, Clone, Copy)]
+pub struct EditedProperties<'a> {
+    /// Underlying properties.
+    properties: ObjectProperties<'a>,
+    /// Edits.
+    edits: &'a PropertyEdits,
+}
+
+impl<'a> EditedProperties<'a> {
+    /// Returns the underlying properties.
+    pub fn properties(&self) -> ObjectProperties<'a> {
+        self.properties
+    }
+
+    /// Returns the latest edit for the property, if any.
+    pub fn edit(&self, name: &str) -> Option<&'a PropertyEdit> {
+        self.edits.get(self.properties.object_id(), name)
+    }
+
+    /// Returns whether the property exists after the edits.
+    pub fn contains(&self, name: &str) -> bool {
+        match self.edit(name) {
+            Some(PropertyEdit::Delete) => false,
+            Some(_) => true,
+            None => self.properties.get_property(name).is_some(),
+        }
+    }
+
+    /// Returns the property value after the edits.
+    ///
+    /// Returns `Ok(None)` if the property does not exist or is deleted.
+    pub fn get_value(&self, name: &str) -> Result<Option<PropertyValue<'a>>, Error> {
+        match self.edit(name) {
+            Some(PropertyEdit::Set(value)) | Some(PropertyEdit::Insert { value, .. }) => {
+                Ok(Some(value.as_value()))
+            }
+            Some(PropertyEdit::Delete) => Ok(None),
+            None => self
+                .properties
+                .get_property(name)
+                .map(|prop| prop.value())
+                .transpose(),
+        }
+    }
+}
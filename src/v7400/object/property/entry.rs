@@ -0,0 +1,26 @@
+//! Property with the owner object.
+
+use crate::v7400::object::{
+    property::{PropertyFlags, PropertyHandle, PropertyValue},
+    ObjectHandle,
+};
+
+/// Property with the owner object, read by
+/// [`Document::iter_all_properties`].
+///
+/// [`Document::iter_all_properties`]:
+/// ../../struct.Document.html#method.iter_all_properties
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct PropertyEntry<'a> {
+    /// Owner object.
+    pub object: ObjectHandle<'a>,
+    /// Property.
+    pub property: PropertyHandle<'a>,
+    /// Property name.
+    pub name: &'a str,
+    /// Property value.
+    pub value: PropertyValue<'a>,
+    /// Property flags.
+    pub flags: PropertyFlags,
+}
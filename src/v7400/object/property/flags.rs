@@ -0,0 +1,111 @@
+//! Property flags.
+
+use log::warn;
+
+/// Flags of a `P` node under `Properties70` node.
+///
+/// This is parsed from the flag string (the fourth attribute of a property
+/// node), such as `A`, `U`, `L` or `A+U`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PropertyFlags(u8);
+
+impl PropertyFlags {
+    /// No flags.
+    pub const NONE: Self = Self(0);
+    /// Animatable (`A`).
+    pub const ANIMATABLE: Self = Self(1 << 0);
+    /// Animated (`+`, usually appears as `A+`).
+    pub const ANIMATED: Self = Self(1 << 1);
+    /// User-defined custom property (`U`).
+    pub const USER_DEFINED: Self = Self(1 << 2);
+    /// Locked (`L`, optionally followed by a member mask).
+    pub const LOCKED: Self = Self(1 << 3);
+    /// Hidden (`H`).
+    pub const HIDDEN: Self = Self(1 << 4);
+
+    /// Parses the flag string.
+    ///
+    /// Unknown characters are ignored with a warning.
+    pub fn parse(s: &str) -> Self {
+        let mut flags = Self::NONE;
+        for c in s.chars() {
+            match c {
+                'A' => flags |= Self::ANIMATABLE,
+                '+' => flags |= Self::ANIMATED,
+                'U' => flags |= Self::USER_DEFINED,
+                'L' => flags |= Self::LOCKED,
+                'H' => flags |= Self::HIDDEN,
+                // Member mask for `L`.
+                '0'..='9' => {}
+                c => warn!(
+                    "Ignoring unknown property flag character {:?} in {:?}",
+                    c, s
+                ),
+            }
+        }
+
+        flags
+    }
+
+    /// Returns the raw bits.
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns true if no flags are set.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns true if all flags in `other` are set.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns true if the property is animatable.
+    pub fn is_animatable(self) -> bool {
+        self.contains(Self::ANIMATABLE)
+    }
+
+    /// Returns true if the property is animated.
+    pub fn is_animated(self) -> bool {
+        self.contains(Self::ANIMATED)
+    }
+
+    /// Returns true if the property is a user-defined custom property.
+    pub fn is_user_defined(self) -> bool {
+        self.contains(Self::USER_DEFINED)
+    }
+
+    /// Returns true if the property is locked.
+    pub fn is_locked(self) -> bool {
+        self.contains(Self::LOCKED)
+    }
+
+    /// Returns true if the property is hidden.
+    pub fn is_hidden(self) -> bool {
+        self.contains(Self::HIDDEN)
+    }
+}
+
+impl std::ops::BitOr for PropertyFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for PropertyFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl std::ops::BitAnd for PropertyFlags {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(self.0 & rhs.0)
+    }
+}
@@ -0,0 +1,76 @@
+//! Property lookup index.
+
+use std::collections::HashMap;
+
+use fbxcel::tree::v7400::{NodeId, Tree};
+use log::{debug, trace};
+
+use crate::v7400::{
+    document::memory,
+    object::property::{PropertiesNodeId, PropertyNodeId},
+    symbol::{Symbol, SymbolTable},
+};
+
+/// Index of property nodes by the properties node and the property name.
+#[derive(Default, Debug, Clone)]
+pub(crate) struct PropertiesIndex {
+    /// Property nodes by the properties node and the name.
+    ///
+    /// The first node is used if the name is duplicated.
+    properties: HashMap<(NodeId, Symbol), PropertyNodeId>,
+}
+
+impl PropertiesIndex {
+    /// Creates a new index for all `Properties70` nodes in the tree,
+    /// interning the property names and type names into the symbol table.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "properties_index", skip_all)
+    )]
+    pub(crate) fn from_tree(tree: &Tree, symbols: &mut SymbolTable) -> Self {
+        debug!("Loading properties index");
+        let mut properties = HashMap::new();
+        let mut stack = vec![tree.root()];
+        while let Some(node) = stack.pop() {
+            if node.name() != "Properties70" {
+                stack.extend(node.children());
+                continue;
+            }
+            for prop in node.children_by_name("P") {
+                let attrs = prop.attributes();
+                let name = match attrs.first().and_then(|v| v.get_string()) {
+                    Some(v) => v,
+                    None => {
+                        trace!(
+                            "Skipping `P` node without name: node_id={:?}",
+                            prop.node_id()
+                        );
+                        continue;
+                    }
+                };
+                if let Some(type_name) = attrs.get(1).and_then(|v| v.get_string()) {
+                    symbols.intern(type_name);
+                }
+                properties
+                    .entry((node.node_id(), symbols.intern(name)))
+                    .or_insert_with(|| PropertyNodeId::new(prop.node_id()));
+            }
+        }
+        debug!(
+            "Loaded properties index successfully: {} properties",
+            properties.len()
+        );
+
+        Self { properties }
+    }
+
+    /// Returns the property node with the given name in the properties node.
+    pub(crate) fn get(&self, node: PropertiesNodeId, name: Symbol) -> Option<PropertyNodeId> {
+        self.properties.get(&(*node, name)).copied()
+    }
+
+    /// Returns the estimated heap size.
+    pub(crate) fn heap_size(&self) -> usize {
+        memory::map_heap_size(&self.properties)
+    }
+}
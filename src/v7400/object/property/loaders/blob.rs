@@ -0,0 +1,70 @@
+//! Property loader for binary blobs.
+
+use std::convert::TryFrom;
+
+use anyhow::Error;
+use fbxcel::low::v7400::AttributeValue;
+use log::warn;
+
+use crate::v7400::{
+    object::property::{LoadProperty, PropertyHandle},
+    Error as DomError,
+};
+
+/// Binary blob property loader.
+///
+/// This loads `Blob` typed properties, whose content is stored in the
+/// `BinaryData` child node of the property node, and properties with a
+/// single binary attribute (such as `Content`).
+///
+/// The loaded slice borrows the underlying attribute storage, so no data
+/// is copied.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BlobLoader;
+
+impl BlobLoader {
+    /// Creates a new loader.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<'a> LoadProperty<'a> for BlobLoader {
+    type Value = &'a [u8];
+    type Error = Error;
+
+    fn expecting(&self) -> String {
+        "binary blob".into()
+    }
+
+    fn load(self, node: &PropertyHandle<'a>) -> Result<Self::Value, Self::Error> {
+        if let [AttributeValue::Binary(v)] = node.value_part() {
+            return Ok(v);
+        }
+
+        let data = node
+            .node()
+            .first_child_by_name("BinaryData")
+            .ok_or_else(|| DomError::missing_node(&node.node(), "BinaryData"))?;
+        let content = data
+            .attributes()
+            .first()
+            .ok_or_else(|| DomError::missing_attribute(&data, 0))?
+            .get_binary_or_type()
+            .map_err(|ty| prop_type_err!("binary blob", ty, node))?;
+
+        // The first value attribute of `Blob` properties is the blob length.
+        if let Some(len) = node.value_part().first().and_then(|v| v.get_i32()) {
+            if usize::try_from(len).ok() != Some(content.len()) {
+                warn!(
+                    "Blob length mismatch: declared {} but got {}, node_id={:?}",
+                    len,
+                    content.len(),
+                    node.node_id()
+                );
+            }
+        }
+
+        Ok(content)
+    }
+}
@@ -160,7 +160,7 @@ macro_rules! impl_loader {
                 "`mint::",
                 stringify!($base),
                 "<",
-                stringify!($ty_target),
+                stringify!($ty_elem),
                 ">`"
             )
         }
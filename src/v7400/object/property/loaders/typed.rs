@@ -0,0 +1,224 @@
+//! Property loaders which check the declared property type.
+
+use std::convert::TryFrom;
+
+use anyhow::Error;
+use fbxcel::low::v7400::AttributeValue;
+
+use crate::v7400::{
+    object::property::{
+        loaders::{check_attrs_len, MintLoader, PrimitiveLoader, RgbLoader},
+        LoadProperty, PropertyHandle,
+    },
+    Error as DomError,
+};
+
+/// Returns `Ok(())` if the property has one of the expected data types.
+fn check_data_type(
+    node: &PropertyHandle<'_>,
+    expected: &[&str],
+    target_name: &str,
+) -> Result<(), Error> {
+    let data_type = node.data_type()?;
+    if !expected.contains(&data_type) {
+        return Err(DomError::type_mismatch(
+            node.error_location(),
+            format_args!(
+                "one of {:?} data types for {} property",
+                expected, target_name
+            ),
+            data_type,
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Property loader for `ColorRGB` and `Color` properties.
+///
+/// This loader rejects properties with other data types, even if they have
+/// three `f64` values.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ColorRgbLoader;
+
+impl ColorRgbLoader {
+    /// Creates a new loader.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl LoadProperty<'_> for ColorRgbLoader {
+    type Value = rgb::RGB<f64>;
+    type Error = Error;
+
+    fn expecting(&self) -> String {
+        "`ColorRGB` as `rgb::RGB<f64>`".into()
+    }
+
+    fn load(self, node: &PropertyHandle<'_>) -> Result<Self::Value, Self::Error> {
+        check_data_type(node, &["ColorRGB", "Color"], "`ColorRGB`")?;
+        node.load_value(RgbLoader::<rgb::RGB<f64>>::new())
+    }
+}
+
+/// Property loader for `ColorAndAlpha` and `ColorRGBA` properties.
+///
+/// This loader rejects properties with other data types, even if they have
+/// four `f64` values.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ColorRgbaLoader;
+
+impl ColorRgbaLoader {
+    /// Creates a new loader.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl LoadProperty<'_> for ColorRgbaLoader {
+    type Value = rgb::RGBA<f64>;
+    type Error = Error;
+
+    fn expecting(&self) -> String {
+        "`ColorAndAlpha` as `rgb::RGBA<f64>`".into()
+    }
+
+    fn load(self, node: &PropertyHandle<'_>) -> Result<Self::Value, Self::Error> {
+        check_data_type(node, &["ColorAndAlpha", "ColorRGBA"], "`ColorAndAlpha`")?;
+        node.load_value(RgbLoader::<rgb::RGBA<f64>>::new())
+    }
+}
+
+/// Property loader for `Vector3D`-like triples.
+///
+/// This accepts `Vector3D`, `Vector`, `Lcl Translation`, `Lcl Rotation`
+/// and `Lcl Scaling` properties.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vector3Loader;
+
+impl Vector3Loader {
+    /// Creates a new loader.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl LoadProperty<'_> for Vector3Loader {
+    type Value = mint::Vector3<f64>;
+    type Error = Error;
+
+    fn expecting(&self) -> String {
+        "`Vector3D` as `mint::Vector3<f64>`".into()
+    }
+
+    fn load(self, node: &PropertyHandle<'_>) -> Result<Self::Value, Self::Error> {
+        check_data_type(
+            node,
+            &[
+                "Vector3D",
+                "Vector",
+                "Lcl Translation",
+                "Lcl Rotation",
+                "Lcl Scaling",
+            ],
+            "`Vector3D`",
+        )?;
+        node.load_value(MintLoader::<mint::Vector3<f64>>::new())
+    }
+}
+
+/// Property loader for `KTime` properties.
+///
+/// The value is loaded as raw FBX time units (ticks).
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KTimeLoader;
+
+impl KTimeLoader {
+    /// Creates a new loader.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl LoadProperty<'_> for KTimeLoader {
+    type Value = i64;
+    type Error = Error;
+
+    fn expecting(&self) -> String {
+        "`KTime` as `i64`".into()
+    }
+
+    fn load(self, node: &PropertyHandle<'_>) -> Result<Self::Value, Self::Error> {
+        check_data_type(node, &["KTime", "Time"], "`KTime`")?;
+        node.load_value(PrimitiveLoader::<i64>::new())
+    }
+}
+
+/// Value of an enum property.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnumValue<'a> {
+    /// Raw enum value.
+    pub value: i32,
+    /// Labels of the enum variants, if declared.
+    ///
+    /// This is the pipe-separated label list split into items.
+    /// Built-in enum properties usually have no labels.
+    pub labels: Vec<&'a str>,
+}
+
+impl<'a> EnumValue<'a> {
+    /// Returns the label of the current value, if available.
+    pub fn label(&self) -> Option<&'a str> {
+        usize::try_from(self.value)
+            .ok()
+            .and_then(|i| self.labels.get(i))
+            .copied()
+    }
+}
+
+/// Property loader for `enum` properties.
+///
+/// This loads the raw value together with the declared label list (if any).
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EnumLoader;
+
+impl EnumLoader {
+    /// Creates a new loader.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<'a> LoadProperty<'a> for EnumLoader {
+    type Value = EnumValue<'a>;
+    type Error = Error;
+
+    fn expecting(&self) -> String {
+        "`enum` as `EnumValue`".into()
+    }
+
+    fn load(self, node: &PropertyHandle<'a>) -> Result<Self::Value, Self::Error> {
+        /// Type name to use in error message.
+        const TARGET_NAME: &str = "`enum`";
+
+        check_data_type(node, &["enum", "Enum"], TARGET_NAME)?;
+        let value_part = node.value_part();
+        let labels = match value_part {
+            [_, AttributeValue::String(labels)] if labels.is_empty() => Vec::new(),
+            [_, AttributeValue::String(labels)] => labels.split('|').collect(),
+            _ => {
+                check_attrs_len(node, 1, TARGET_NAME)?;
+                Vec::new()
+            }
+        };
+        let value = match value_part[0] {
+            AttributeValue::I16(v) => i32::from(v),
+            AttributeValue::I32(v) => v,
+            ref v => return Err(prop_type_err!(TARGET_NAME, v.type_(), node)),
+        };
+
+        Ok(EnumValue { value, labels })
+    }
+}
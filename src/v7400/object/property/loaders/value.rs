@@ -0,0 +1,120 @@
+//! Dynamically typed property value loader.
+
+use anyhow::Error;
+use fbxcel::low::v7400::AttributeValue;
+
+use crate::v7400::{
+    object::property::{
+        loaders::{
+            BlobLoader, BorrowedStringLoader, F64Arr2Loader, F64Arr3Loader, F64Arr4Loader,
+            PrimitiveLoader,
+        },
+        LoadProperty, PropertyHandle, PropertyValue,
+    },
+    Error as DomError,
+};
+
+/// Property loader for [`PropertyValue`].
+///
+/// The value kind is chosen from the declared property type name (such as
+/// `bool`, `int`, `double`, `Vector3D`, `KString` or `KTime`).
+/// If the type name is unknown, the kind is inferred from the raw attribute
+/// values: a single attribute is loaded as is, two to four `f64` attributes
+/// are loaded as an array, and no attributes (as `object` and `Compound`
+/// typed properties have) are loaded as [`PropertyValue::Empty`].
+///
+/// `Visibility` is stored as `f64`, and is loaded as [`PropertyValue::F64`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PropertyValueLoader;
+
+impl PropertyValueLoader {
+    /// Creates a new loader.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<'a> LoadProperty<'a> for PropertyValueLoader {
+    type Value = PropertyValue<'a>;
+    type Error = Error;
+
+    fn expecting(&self) -> String {
+        "any property value".into()
+    }
+
+    fn load(self, node: &PropertyHandle<'a>) -> Result<Self::Value, Self::Error> {
+        let value = match node.data_type()? {
+            "bool" | "Bool" | "Visibility Inheritance" => {
+                PropertyValue::Bool(node.load_value(PrimitiveLoader::<bool>::new())?)
+            }
+            "int" | "Integer" | "enum" | "Enum" => {
+                PropertyValue::I32(node.load_value(PrimitiveLoader::<i32>::new())?)
+            }
+            "LongLong" | "ULongLong" => {
+                PropertyValue::I64(node.load_value(PrimitiveLoader::<i64>::new())?)
+            }
+            "float" | "Float" => {
+                PropertyValue::F32(node.load_value(PrimitiveLoader::<f32>::new())?)
+            }
+            "double" | "Double" | "Number" | "Real" | "Visibility" | "FieldOfView"
+            | "FieldOfViewX" | "FieldOfViewY" | "Roll" | "OpticalCenterX" | "OpticalCenterY" => {
+                PropertyValue::F64(node.load_value(PrimitiveLoader::<f64>::new())?)
+            }
+            "Vector2D" | "Vector2" => PropertyValue::F64x2(node.load_value(F64Arr2Loader)?),
+            "Vector3D" | "Vector" | "Color" | "ColorRGB" | "Lcl Translation" | "Lcl Rotation"
+            | "Lcl Scaling" => PropertyValue::F64x3(node.load_value(F64Arr3Loader)?),
+            "Vector4D" | "Vector4" | "ColorAndAlpha" | "ColorRGBA" => {
+                PropertyValue::F64x4(node.load_value(F64Arr4Loader)?)
+            }
+            "KString" | "string" | "DateTime" | "Url" | "XRefUrl" => {
+                PropertyValue::String(node.load_value(BorrowedStringLoader)?)
+            }
+            "Blob" => PropertyValue::Binary(node.load_value(BlobLoader)?),
+            "KTime" | "Time" => {
+                PropertyValue::Time(node.load_value(PrimitiveLoader::<i64>::new())?)
+            }
+            _ => infer_value(node)?,
+        };
+
+        Ok(value)
+    }
+}
+
+/// Infers the value kind from the raw attribute values.
+fn infer_value<'a>(node: &PropertyHandle<'a>) -> Result<PropertyValue<'a>, Error> {
+    let value_part = node.value_part();
+    let value = match value_part {
+        [] => PropertyValue::Empty,
+        [AttributeValue::Bool(v)] => PropertyValue::Bool(*v),
+        [AttributeValue::I16(v)] => PropertyValue::I32(i32::from(*v)),
+        [AttributeValue::I32(v)] => PropertyValue::I32(*v),
+        [AttributeValue::I64(v)] => PropertyValue::I64(*v),
+        [AttributeValue::F32(v)] => PropertyValue::F32(*v),
+        [AttributeValue::F64(v)] => PropertyValue::F64(*v),
+        [AttributeValue::String(v)] => PropertyValue::String(v),
+        [AttributeValue::Binary(v)] => PropertyValue::Binary(v),
+        [AttributeValue::F64(x), AttributeValue::F64(y)] => PropertyValue::F64x2([*x, *y]),
+        [AttributeValue::F64(x), AttributeValue::F64(y), AttributeValue::F64(z)] => {
+            PropertyValue::F64x3([*x, *y, *z])
+        }
+        [AttributeValue::F64(x), AttributeValue::F64(y), AttributeValue::F64(z), AttributeValue::F64(w)] => {
+            PropertyValue::F64x4([*x, *y, *z, *w])
+        }
+        _ => {
+            return Err(DomError::type_mismatch(
+                node.error_location(),
+                format_args!(
+                    "property value of inferable kind (data_type={:?})",
+                    node.data_type().ok()
+                ),
+                value_part
+                    .iter()
+                    .map(AttributeValue::type_)
+                    .collect::<Vec<_>>(),
+            )
+            .into())
+        }
+    };
+
+    Ok(value)
+}
@@ -1,13 +1,26 @@
 //! Property node.
 
-use anyhow::{format_err, Error};
+#[cfg(feature = "symbol")]
+use anyhow::format_err;
+use anyhow::Error;
 use fbxcel::{
     low::v7400::AttributeValue,
     tree::v7400::{NodeHandle, NodeId},
 };
 use log::warn;
 
-use crate::v7400::{object::property::LoadProperty, Document};
+#[cfg(feature = "symbol")]
+use crate::v7400::symbol::Symbol;
+use crate::v7400::{
+    object::{
+        property::{
+            loaders::{BlobLoader, PropertyValueLoader},
+            LoadProperty, PropertyFlags, PropertyValue,
+        },
+        ObjectNodeId,
+    },
+    Document, Error as DomError, ErrorLocation,
+};
 
 /// Node ID of a `P` node under `Properties70` node.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -35,7 +48,7 @@ impl From<PropertyNodeId> for NodeId {
 }
 
 /// Node handle of a `P` node under `Properties70` node.
-#[derive(Debug, Clone, Copy)]
+#[derive(Clone, Copy)]
 pub struct PropertyHandle<'a> {
     /// Node ID.
     node_id: PropertyNodeId,
@@ -69,22 +82,63 @@ impl<'a> PropertyHandle<'a> {
         loader.load(self)
     }
 
+    /// Reads a value whose kind is chosen from the declared property type.
+    ///
+    /// This is a shorthand for `load_value(PropertyValueLoader::new())`.
+    pub fn value(&self) -> Result<PropertyValue<'a>, Error> {
+        self.load_value(PropertyValueLoader::new())
+    }
+
+    /// Reads a binary blob without copying.
+    ///
+    /// This is a shorthand for `load_value(BlobLoader::new())`.
+    pub fn blob(&self) -> Result<&'a [u8], Error> {
+        self.load_value(BlobLoader::new())
+    }
+
     /// Returns proprety name.
     pub fn name(&self) -> Result<&'a str, Error> {
         self.get_string_attr(0)
-            .map_err(|e| format_err!("Failed to get property name: {}", e))
     }
 
     /// Returns proprety type name.
     pub fn data_type(&self) -> Result<&'a str, Error> {
         self.get_string_attr(1)
-            .map_err(|e| format_err!("Failed to get property data type: {}", e))
+    }
+
+    /// Returns proprety name symbol.
+    #[cfg(feature = "symbol")]
+    pub fn name_symbol(&self) -> Result<Symbol, Error> {
+        self.symbol_of(self.name()?)
+    }
+
+    /// Returns proprety type name symbol.
+    #[cfg(feature = "symbol")]
+    pub fn data_type_symbol(&self) -> Result<Symbol, Error> {
+        self.symbol_of(self.data_type()?)
+    }
+
+    /// Returns the symbol of the string interned in the document.
+    #[cfg(feature = "symbol")]
+    fn symbol_of(&self, s: &str) -> Result<Symbol, Error> {
+        self.doc
+            .symbol(s)
+            .ok_or_else(|| format_err!("String not interned: {:?}", s))
     }
 
     /// Returns proprety label.
     pub fn label(&self) -> Result<&'a str, Error> {
         self.get_string_attr(2)
-            .map_err(|e| format_err!("Failed to get property label: {}", e))
+    }
+
+    /// Returns property flag string.
+    pub fn flags_str(&self) -> Result<&'a str, Error> {
+        self.get_string_attr(3)
+    }
+
+    /// Returns property flags.
+    pub fn flags(&self) -> Result<PropertyFlags, Error> {
+        self.flags_str().map(PropertyFlags::parse)
     }
 
     /// Returns property value part of node attributes.
@@ -100,26 +154,51 @@ impl<'a> PropertyHandle<'a> {
         })
     }
 
+    /// Returns the location of the property for errors.
+    ///
+    /// The object ID is set if the property belongs to an object (rather
+    /// than a property template).
+    pub(crate) fn error_location(&self) -> ErrorLocation {
+        let node = self.node();
+        let mut location = ErrorLocation::node(&node);
+        if let Some(name) = node
+            .attributes()
+            .first()
+            .and_then(AttributeValue::get_string)
+        {
+            location = location.with_property(name);
+        }
+        let object_index = node
+            .parent()
+            .and_then(|props| props.parent())
+            .and_then(|obj| {
+                self.doc
+                    .objects_cache()
+                    .index_from_node_id(ObjectNodeId::new(obj.node_id()))
+            });
+        if let Some(index) = object_index {
+            location = location.with_object(self.doc.objects_cache().meta().object_id(index));
+        }
+        location
+    }
+
     /// For internal use: returns string attribute.
     fn get_string_attr(&self, index: usize) -> Result<&'a str, Error> {
         self.node()
             .attributes()
             .get(index)
-            .ok_or_else(|| {
-                format_err!(
-                    "No properties found: node_id={:?}, attr_index={:?}",
-                    self,
-                    index
-                )
-            })?
+            .ok_or_else(|| DomError::missing_attribute(&self.node(), index))?
             .get_string_or_type()
-            .map_err(|ty| {
-                format_err!(
-                    "Expected string but got {:?}: node_id={:?}, attr_index={:?}",
-                    ty,
-                    self,
-                    index
-                )
-            })
+            .map_err(|ty| DomError::type_mismatch(self.error_location(), "string", ty).into())
+    }
+}
+
+impl std::fmt::Debug for PropertyHandle<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PropertyHandle")
+            .field("node_id", &self.node_id)
+            .field("name", &self.name().ok())
+            .field("data_type", &self.data_type().ok())
+            .finish()
     }
 }
@@ -1,20 +1,38 @@
 //! Proxy to object properties.
 
+use log::warn;
+
 use crate::v7400::{
     object::{
-        property::{PropertiesHandle, PropertiesNodeId, PropertyHandle},
-        ObjectHandle,
+        property::{
+            alias, CompoundProperty, PropertiesHandle, PropertiesNodeId, PropertyHandle,
+            PropertyValue,
+        },
+        ObjectHandle, ObjectId,
     },
     Document,
 };
 
+/// Where a property was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PropertyOrigin {
+    /// Direct `Properties70` node of the object.
+    Direct,
+    /// `Properties70` node of the class template in definitions.
+    Template,
+}
+
 /// Proxy to object properties.
 #[derive(Debug, Clone, Copy)]
 pub struct ObjectProperties<'a> {
+    /// Object ID.
+    object_id: ObjectId,
     /// Direct properties node ID.
     direct_props: Option<PropertiesNodeId>,
     /// Default properties node ID.
     default_props: Option<PropertiesNodeId>,
+    /// Whether to ignore ASCII case of property names.
+    ignore_ascii_case: bool,
     /// Document.
     doc: &'a Document,
 }
@@ -34,30 +52,178 @@ impl<'a> ObjectProperties<'a> {
             .properties_node_id(object.node().name(), native_type);
 
         Self {
+            object_id: object.object_id(),
             direct_props,
             default_props,
+            ignore_ascii_case: false,
             doc: object.document(),
         }
     }
 
+    /// Returns the object ID of the properties owner.
+    pub fn object_id(&self) -> ObjectId {
+        self.object_id
+    }
+
+    /// Returns a proxy which compares property names ignoring ASCII case.
+    ///
+    /// This affects all lookups through the returned proxy.
+    pub fn ignore_ascii_case(self) -> Self {
+        Self {
+            ignore_ascii_case: true,
+            ..self
+        }
+    }
+
     /// Returns property handle if found.
     pub fn get_property(&self, name: &str) -> Option<PropertyHandle<'a>> {
         self.get_direct_property(name)
             .or_else(|| self.get_default_property(name))
     }
 
+    /// Returns the first property found among the given names.
+    ///
+    /// Direct properties with any of the names take precedence over default
+    /// properties, so that an override written under a legacy name is not
+    /// hidden by a template default under the canonical name.
+    pub fn get_any(&self, names: &[&str]) -> Option<PropertyHandle<'a>> {
+        names
+            .iter()
+            .find_map(|name| self.get_direct_property(name))
+            .or_else(|| {
+                names
+                    .iter()
+                    .find_map(|name| self.get_default_property(name))
+            })
+    }
+
+    /// Returns property handle with the given name or its known aliases.
+    ///
+    /// See [`alias::aliases`] for the known aliases.
+    /// Typed property getters never fall back to aliases.
+    ///
+    /// ```
+    /// # #[cfg(feature = "edit")]
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// use fbxcel_dom::v7400::{
+    ///     data::material::ShadingModel,
+    ///     edit::{DocumentBuilder, ModelKind},
+    ///     object::property::{OwnedPropertyValue, PropertyValue},
+    /// };
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// let model = builder.add_model("Cube", ModelKind::Null, None);
+    /// let material = builder.add_material("Shiny", ShadingModel::Phong, [1.0; 3], Some(model))?;
+    /// let shininess = OwnedPropertyValue::F64(42.0);
+    /// builder
+    ///     .edit_mut()
+    ///     .insert_property(material, "Shininess", "double", "", "A", shininess)?;
+    /// let doc = builder.build()?;
+    ///
+    /// let props = doc.material_by_name("Shiny").expect("should exist").properties();
+    /// assert_eq!(props.shininess()?, None);
+    /// let prop = props.get_property_or_alias("ShininessExponent").expect("should exist");
+    /// assert_eq!(prop.value()?, PropertyValue::F64(42.0));
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "edit"))]
+    /// # fn main() {}
+    /// ```
+    pub fn get_property_or_alias(&self, name: &str) -> Option<PropertyHandle<'a>> {
+        let aliases = alias::aliases(name);
+        if aliases.is_empty() {
+            return self.get_property(name);
+        }
+        let names: Vec<&str> = std::iter::once(name)
+            .chain(aliases.iter().copied())
+            .collect();
+        self.get_any(&names)
+    }
+
+    /// Returns property handle and where it was found.
+    ///
+    /// This is useful to tell overridden properties from template defaults.
+    pub fn get_property_with_origin(
+        &self,
+        name: &str,
+    ) -> Option<(PropertyHandle<'a>, PropertyOrigin)> {
+        self.get_direct_property(name)
+            .map(|prop| (prop, PropertyOrigin::Direct))
+            .or_else(|| {
+                self.get_default_property(name)
+                    .map(|prop| (prop, PropertyOrigin::Template))
+            })
+    }
+
     /// Returns property handle of the direct property if found.
     pub(crate) fn get_direct_property(&self, name: &str) -> Option<PropertyHandle<'a>> {
         self.direct_props
             .map(|id| PropertiesHandle::new(id, self.doc))
-            .and_then(|props| props.get_property(name))
+            .and_then(|props| self.lookup(props, name))
     }
 
     /// Returns property handle of the default property if found.
     pub(crate) fn get_default_property(&self, name: &str) -> Option<PropertyHandle<'a>> {
         self.default_props
             .map(|id| PropertiesHandle::new(id, self.doc))
-            .and_then(|props| props.get_property(name))
+            .and_then(|props| self.lookup(props, name))
+    }
+
+    /// Looks up the property in the given properties node, honoring the
+    /// case sensitivity setting.
+    fn lookup(&self, props: PropertiesHandle<'a>, name: &str) -> Option<PropertyHandle<'a>> {
+        if self.ignore_ascii_case {
+            props.get_property_ignore_ascii_case(name)
+        } else {
+            props.get_property(name)
+        }
+    }
+
+    /// Returns an iterator of user-defined (`U` flagged) properties with
+    /// their values.
+    ///
+    /// User-defined properties are never inherited from default properties,
+    /// so only direct properties are scanned.
+    /// Properties whose flags or values cannot be read are skipped with a
+    /// warning.
+    pub fn user_properties(&self) -> impl Iterator<Item = (PropertyHandle<'a>, PropertyValue<'a>)> {
+        self.direct_props
+            .map(|id| PropertiesHandle::new(id, self.doc))
+            .into_iter()
+            .flat_map(|props| props.properties())
+            .filter(|prop| match prop.flags() {
+                Ok(flags) => flags.is_user_defined(),
+                Err(e) => {
+                    warn!(
+                        "Ignoring error for property flags (node_id={:?}): {}",
+                        prop.node_id(),
+                        e
+                    );
+                    false
+                }
+            })
+            .filter_map(|prop| match prop.value() {
+                Ok(value) => Some((prop, value)),
+                Err(e) => {
+                    warn!(
+                        "Ignoring error for user property value (node_id={:?}): {}",
+                        prop.node_id(),
+                        e
+                    );
+                    None
+                }
+            })
+    }
+
+    /// Returns the direct properties grouped into a tree by `|`-separated
+    /// names.
+    pub fn compound_tree(&self) -> CompoundProperty<'a> {
+        CompoundProperty::from_properties(
+            self.direct_props
+                .map(|id| PropertiesHandle::new(id, self.doc))
+                .into_iter()
+                .flat_map(|props| props.properties()),
+        )
     }
 
     /// Returns whether the object has corresponding default properties node.
@@ -1,15 +1,15 @@
 //! Properties set object.
 
-use anyhow::format_err;
 use fbxcel::tree::v7400::{NodeHandle, NodeId};
 use log::warn;
 
 use crate::v7400::{
     object::{
-        property::{PropertyHandle, PropertyNodeId},
+        property::{CompoundProperty, PropertyHandle, PropertyNodeId},
         ObjectHandle,
     },
-    Document,
+    symbol::Symbol,
+    Document, Error as DomError, ErrorLocation,
 };
 
 /// Node ID of a `Properties70` node.
@@ -72,21 +72,60 @@ impl<'a> PropertiesHandle<'a> {
         self.node_id.to_handle(self.doc.tree())
     }
 
+    /// Returns an iterator of all property nodes, in the order of the nodes
+    /// in the tree.
+    pub fn properties(&self) -> impl Iterator<Item = PropertyHandle<'a>> {
+        let doc = self.doc;
+        self.node()
+            .children_by_name("P")
+            .map(move |node| PropertyHandle::new(PropertyNodeId::new(node.node_id()), doc))
+    }
+
+    /// Returns the properties grouped into a tree by `|`-separated names.
+    pub fn compound_tree(&self) -> CompoundProperty<'a> {
+        CompoundProperty::from_properties(self.properties())
+    }
+
     /// Returns a node handle of the property node with the given name.
     pub fn get_property(&self, name: &str) -> Option<PropertyHandle<'a>> {
+        self.lookup_property(self.doc.lookup_symbol(name)?)
+    }
+
+    /// Returns a node handle of the property node with the given name symbol.
+    ///
+    /// This does not search the children, but uses the index built on
+    /// loading.
+    #[cfg(feature = "symbol")]
+    pub fn get_property_by_symbol(&self, name: Symbol) -> Option<PropertyHandle<'a>> {
+        self.lookup_property(name)
+    }
+
+    /// Returns a node handle of the property node with the given name symbol,
+    /// using the index built on loading.
+    fn lookup_property(&self, name: Symbol) -> Option<PropertyHandle<'a>> {
+        self.doc
+            .properties_index()
+            .get(self.node_id, name)
+            .map(|node_id| PropertyHandle::new(node_id, self.doc))
+    }
+
+    /// Returns a node handle of the property node with the given name,
+    /// ignoring ASCII case.
+    pub fn get_property_ignore_ascii_case(&self, name: &str) -> Option<PropertyHandle<'a>> {
+        self.find_property(|v| v.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns a node handle of the first property node whose name matches
+    /// the predicate.
+    fn find_property(&self, mut pred: impl FnMut(&str) -> bool) -> Option<PropertyHandle<'a>> {
         self.node()
             .children_by_name("P")
             .map(|node| {
                 node.attributes()
                     .get(0)
-                    .ok_or_else(|| format_err!("No attributes found"))?
+                    .ok_or_else(|| DomError::missing_attribute(&node, 0))?
                     .get_string_or_type()
-                    .map_err(|ty| {
-                        format_err!(
-                            "Expected string as property name (first attribute), but got {:?}",
-                            ty
-                        )
-                    })
+                    .map_err(|ty| DomError::type_mismatch(ErrorLocation::node(&node), "string", ty))
                     .map(|attr| (PropertyNodeId::new(node.node_id()), attr))
             })
             .filter_map(|res| match res {
@@ -99,7 +138,7 @@ impl<'a> PropertiesHandle<'a> {
                     None
                 }
             })
-            .find(move |&(_node, v)| v == name)
+            .find(move |&(_node, v)| pred(v))
             .map(|(node_id, _v)| PropertyHandle::new(node_id, self.doc))
     }
 }
@@ -0,0 +1,166 @@
+//! Dynamically typed property value.
+
+/// Property value whose type is chosen from the declared property type.
+///
+/// This is useful for generic tooling (such as inspectors or diffs) which
+/// wants to read any property without choosing a loader by purpose.
+///
+/// See [`PropertyValueLoader`][`super::loaders::PropertyValueLoader`] for
+/// how the variant is chosen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PropertyValue<'a> {
+    /// Boolean.
+    Bool(bool),
+    /// `i32`.
+    I32(i32),
+    /// `i64`.
+    I64(i64),
+    /// `f32`.
+    F32(f32),
+    /// `f64`.
+    F64(f64),
+    /// `[f64; 2]`.
+    F64x2([f64; 2]),
+    /// `[f64; 3]`.
+    F64x3([f64; 3]),
+    /// `[f64; 4]`.
+    F64x4([f64; 4]),
+    /// String.
+    String(&'a str),
+    /// Binary.
+    Binary(&'a [u8]),
+    /// Time, in raw FBX time units (ticks).
+    Time(i64),
+    /// No value, such as `object` and `Compound` typed properties.
+    Empty,
+}
+
+impl<'a> PropertyValue<'a> {
+    /// Returns the value kind name, for use in messages.
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            PropertyValue::Bool(_) => "bool",
+            PropertyValue::I32(_) => "i32",
+            PropertyValue::I64(_) => "i64",
+            PropertyValue::F32(_) => "f32",
+            PropertyValue::F64(_) => "f64",
+            PropertyValue::F64x2(_) => "[f64; 2]",
+            PropertyValue::F64x3(_) => "[f64; 3]",
+            PropertyValue::F64x4(_) => "[f64; 4]",
+            PropertyValue::String(_) => "string",
+            PropertyValue::Binary(_) => "binary",
+            PropertyValue::Time(_) => "time",
+            PropertyValue::Empty => "empty",
+        }
+    }
+
+    /// Returns the boolean value if the value is a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            PropertyValue::Bool(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as `i64` if the value is an integer or time.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            PropertyValue::I32(v) => Some(i64::from(v)),
+            PropertyValue::I64(v) | PropertyValue::Time(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as `f64` if the value is a scalar float.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            PropertyValue::F32(v) => Some(f64::from(v)),
+            PropertyValue::F64(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the string if the value is a string.
+    pub fn as_str(&self) -> Option<&'a str> {
+        match *self {
+            PropertyValue::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the binary if the value is a binary.
+    pub fn as_binary(&self) -> Option<&'a [u8]> {
+        match *self {
+            PropertyValue::Binary(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// Owned version of [`PropertyValue`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum OwnedPropertyValue {
+    /// Boolean.
+    Bool(bool),
+    /// `i32`.
+    I32(i32),
+    /// `i64`.
+    I64(i64),
+    /// `f32`.
+    F32(f32),
+    /// `f64`.
+    F64(f64),
+    /// `[f64; 2]`.
+    F64x2([f64; 2]),
+    /// `[f64; 3]`.
+    F64x3([f64; 3]),
+    /// `[f64; 4]`.
+    F64x4([f64; 4]),
+    /// String.
+    String(String),
+    /// Binary.
+    Binary(Vec<u8>),
+    /// Time, in raw FBX time units (ticks).
+    Time(i64),
+    /// No value, such as `object` and `Compound` typed properties.
+    Empty,
+}
+
+impl OwnedPropertyValue {
+    /// Returns a borrowed value.
+    pub fn as_value(&self) -> PropertyValue<'_> {
+        match self {
+            OwnedPropertyValue::Bool(v) => PropertyValue::Bool(*v),
+            OwnedPropertyValue::I32(v) => PropertyValue::I32(*v),
+            OwnedPropertyValue::I64(v) => PropertyValue::I64(*v),
+            OwnedPropertyValue::F32(v) => PropertyValue::F32(*v),
+            OwnedPropertyValue::F64(v) => PropertyValue::F64(*v),
+            OwnedPropertyValue::F64x2(v) => PropertyValue::F64x2(*v),
+            OwnedPropertyValue::F64x3(v) => PropertyValue::F64x3(*v),
+            OwnedPropertyValue::F64x4(v) => PropertyValue::F64x4(*v),
+            OwnedPropertyValue::String(v) => PropertyValue::String(v),
+            OwnedPropertyValue::Binary(v) => PropertyValue::Binary(v),
+            OwnedPropertyValue::Time(v) => PropertyValue::Time(*v),
+            OwnedPropertyValue::Empty => PropertyValue::Empty,
+        }
+    }
+}
+
+impl From<PropertyValue<'_>> for OwnedPropertyValue {
+    fn from(v: PropertyValue<'_>) -> Self {
+        match v {
+            PropertyValue::Bool(v) => OwnedPropertyValue::Bool(v),
+            PropertyValue::I32(v) => OwnedPropertyValue::I32(v),
+            PropertyValue::I64(v) => OwnedPropertyValue::I64(v),
+            PropertyValue::F32(v) => OwnedPropertyValue::F32(v),
+            PropertyValue::F64(v) => OwnedPropertyValue::F64(v),
+            PropertyValue::F64x2(v) => OwnedPropertyValue::F64x2(v),
+            PropertyValue::F64x3(v) => OwnedPropertyValue::F64x3(v),
+            PropertyValue::F64x4(v) => OwnedPropertyValue::F64x4(v),
+            PropertyValue::String(v) => OwnedPropertyValue::String(v.to_owned()),
+            PropertyValue::Binary(v) => OwnedPropertyValue::Binary(v.to_owned()),
+            PropertyValue::Time(v) => OwnedPropertyValue::Time(v),
+            PropertyValue::Empty => OwnedPropertyValue::Empty,
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! `SceneReference` object.
+//!
+//! A scene reference points at an external FBX file whose scene is composed
+//! into the document, usually with a namespace prefixed to the names of the
+//! referenced objects.
+//! The referenced files are not loaded automatically: see
+//! [`DocumentMut::resolve_references`] to compose them.
+//!
+//! [`DocumentMut::resolve_references`]:
+//!     ../../edit/struct.DocumentMut.html#method.resolve_references
+
+use crate::v7400::object::{
+    model,
+    property::{loaders::BorrowedStringLoader, loaders::PrimitiveLoader, ObjectProperties},
+    ObjectHandle, TypedObjectHandle,
+};
+
+define_object_subtype! {
+    /// `SceneReference` node handle.
+    SceneReferenceHandle: ObjectHandle
+}
+
+impl<'a> SceneReferenceHandle<'a> {
+    /// Returns the model the referenced scene is attached to, if available.
+    pub fn parent_model(&self) -> Option<model::TypedModelHandle<'a>> {
+        self.destination_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .find_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Model(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns properties.
+    pub fn properties(&self) -> SceneReferenceProperties<'a> {
+        SceneReferenceProperties {
+            properties: self.properties_by_native_typename("FbxSceneReference"),
+        }
+    }
+}
+
+/// Proxy type to scene reference properties.
+#[derive(Debug, Clone, Copy)]
+pub struct SceneReferenceProperties<'a> {
+    /// Properties.
+    properties: ObjectProperties<'a>,
+}
+
+impl<'a> SceneReferenceProperties<'a> {
+    impl_prop_proxy_getters! {
+        /// Returns the path of the referenced file.
+        ///
+        /// Note that this returns raw value, and the path separator might be
+        /// a slash or a backslash.
+        file_path -> &'a str {
+            name = "ReferenceFilePath",
+            loader = BorrowedStringLoader::new(),
+            description = "referenced file path",
+            default: {
+                /// Returns the path of the referenced file.
+                ///
+                /// Returns default if the value is not set.
+                file_path_or_default = ""
+            }
+        }
+
+        /// Returns the namespace of the referenced objects.
+        namespace -> &'a str {
+            name = "ReferenceNameSpace",
+            loader = BorrowedStringLoader::new(),
+            description = "reference namespace",
+            default: {
+                /// Returns the namespace of the referenced objects.
+                ///
+                /// Returns default if the value is not set.
+                namespace_or_default = ""
+            }
+        }
+
+        /// Returns the namespace of the referenced objects when the
+        /// reference was created.
+        original_namespace -> &'a str {
+            name = "ReferenceOriginalNameSpace",
+            loader = BorrowedStringLoader::new(),
+            description = "original reference namespace",
+            default: {
+                /// Returns the namespace of the referenced objects when the
+                /// reference was created.
+                ///
+                /// Returns default if the value is not set.
+                original_namespace_or_default = ""
+            }
+        }
+
+        /// Returns the identifier of the reference in the authoring tool.
+        identifier -> &'a str {
+            name = "ReferenceIdentifier",
+            loader = BorrowedStringLoader::new(),
+            description = "reference identifier",
+            default: {
+                /// Returns the identifier of the reference in the authoring
+                /// tool.
+                ///
+                /// Returns default if the value is not set.
+                identifier_or_default = ""
+            }
+        }
+
+        /// Returns whether the reference is loaded in the authoring tool.
+        active -> bool {
+            name = "ReferenceActive",
+            loader = PrimitiveLoader::<bool>::new(),
+            description = "reference active flag",
+            default: {
+                /// Returns whether the reference is loaded in the authoring
+                /// tool.
+                ///
+                /// Returns default if the value is not set.
+                active_or_default = true
+            }
+        }
+    }
+}
@@ -1,8 +1,11 @@
 //! `Document` node.
 
-use anyhow::{format_err, Error};
+use anyhow::Error;
 
-use crate::v7400::object::{ObjectHandle, ObjectId};
+use crate::v7400::{
+    object::{ObjectHandle, ObjectId},
+    Error as DomError, ErrorLocation,
+};
 
 /// `Document` node (`Scene` object) handle.
 ///
@@ -20,7 +23,7 @@ impl<'a> SceneHandle<'a> {
             .document()
             .objects_cache()
             .document_nodes()
-            .contains(&object.object_node_id());
+            .contains(&object.index());
         if !is_document_node {
             return None;
         }
@@ -29,21 +32,24 @@ impl<'a> SceneHandle<'a> {
 
     /// Returns the root object ID of the scene.
     pub fn root_object_id(&self) -> Result<ObjectId, Error> {
-        self.object
+        let node = self
+            .object
             .node()
             .children_by_name("RootNode")
             .next()
-            .ok_or_else(|| format_err!("`RootNode` not found for scene object node"))?
-            .attributes()
+            .ok_or_else(|| DomError::missing_node(&self.object.node(), "RootNode"))?;
+        node.attributes()
             .get(0)
-            .ok_or_else(|| format_err!("Attributes not found for `RootNode`"))?
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?
             .get_i64_or_type()
             .map(ObjectId::new)
             .map_err(|ty| {
-                format_err!(
-                    "Unexpected attribute type for `RootNode`: expected `i64` but got {:?}",
-                    ty
+                DomError::type_mismatch(
+                    ErrorLocation::node(&node).with_object(self.object.object_id()),
+                    "`i64`",
+                    ty,
                 )
+                .into()
             })
     }
 
@@ -52,14 +58,10 @@ impl<'a> SceneHandle<'a> {
     /// Note that this returns `Err(_)` if the object has no corresponding node.
     /// This can happen for valid FBX data.
     pub fn root_object(&self) -> Result<ObjectHandle<'_>, Error> {
-        self.root_object_id()?
+        let root_id = self.root_object_id()?;
+        root_id
             .to_object_handle(self.object.document())
-            .ok_or_else(|| {
-                format_err!(
-                    "Root object of the scene has no corresponding node: object_id={:?}",
-                    self.object.object_id()
-                )
-            })
+            .ok_or_else(|| DomError::missing_object(root_id).into())
     }
 }
 
@@ -0,0 +1,193 @@
+//! `SelectionSet` and `SelectionNode` objects.
+//!
+//! A selection set is a named group of objects and components (vertices,
+//! edges, and polygons of meshes), often used to tag export groups.
+//!
+//! Objects entirely in a set are connected to the set directly, or through
+//! selection nodes with `IsTheNodeInSet` flag.
+//! Components are listed by selection nodes connected to the set, and each
+//! selection node refers to the object connected to it.
+//!
+//! ```
+//! # #[cfg(feature = "edit")]
+//! # fn main() -> Result<(), anyhow::Error> {
+//! use fbxcel_dom::v7400::edit::{DocumentBuilder, ModelKind};
+//!
+//! let mut builder = DocumentBuilder::new();
+//! let door = builder.add_model("Door", ModelKind::Null, None);
+//! let wall = builder.add_model("Wall", ModelKind::Mesh, None);
+//! let positions = [[0.0; 3], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+//! builder.add_mesh("Wall", &positions, &[&[0, 1, 2], &[0, 2, 3]], Some(wall));
+//! let set = builder.add_selection_set("Collision", &[door])?;
+//! builder.add_selection_node(set, wall, &[], &[], &[1])?;
+//! let doc = builder.build()?;
+//!
+//! let set = doc.selection_sets().next().expect("Selection set should exist");
+//! assert_eq!(set.name(), Some("Collision"));
+//! let models: Vec<_> = set.models().filter_map(|model| model.name()).collect();
+//! assert_eq!(models, ["Door"]);
+//! let node = set.selection_nodes().next().expect("Selection node should exist");
+//! assert_eq!(node.model().and_then(|model| model.name()), Some("Wall"));
+//! assert_eq!(node.polygon_indices()?, [1]);
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "edit"))]
+//! # fn main() {}
+//! ```
+
+use anyhow::Error;
+
+use crate::v7400::{
+    object::{model, ObjectHandle, TypedObjectHandle},
+    Error as DomError, ErrorLocation,
+};
+
+define_object_subtype! {
+    /// `SelectionSet` node handle.
+    SelectionSetHandle: ObjectHandle
+}
+
+impl<'a> SelectionSetHandle<'a> {
+    /// Returns an iterator of the selection nodes in the set.
+    pub fn selection_nodes(&self) -> impl Iterator<Item = SelectionNodeHandle<'a>> {
+        self.source_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::SelectionNode(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns an iterator of the objects connected to the set directly
+    /// (not through selection nodes).
+    pub fn direct_objects(&self) -> impl Iterator<Item = ObjectHandle<'a>> {
+        self.source_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter(|obj| !matches!(obj.get_typed(), TypedObjectHandle::SelectionNode(_)))
+    }
+
+    /// Returns an iterator of the models entirely in the set.
+    ///
+    /// This includes models connected directly, and models of selection
+    /// nodes with `IsTheNodeInSet` flag.
+    /// Models with only some components in the set are not included.
+    pub fn models(&self) -> impl Iterator<Item = model::TypedModelHandle<'a>> {
+        let direct = self
+            .direct_objects()
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Model(o) => Some(o),
+                _ => None,
+            });
+        let whole = self
+            .selection_nodes()
+            .filter(|node| node.is_whole_object().unwrap_or(false))
+            .filter_map(|node| node.model());
+        direct.chain(whole)
+    }
+}
+
+define_object_subtype! {
+    /// `SelectionNode` node handle.
+    SelectionNodeHandle: ObjectHandle
+}
+
+impl<'a> SelectionNodeHandle<'a> {
+    /// Returns an iterator of the selection sets the node belongs to.
+    pub fn selection_sets(&self) -> impl Iterator<Item = SelectionSetHandle<'a>> {
+        self.destination_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::SelectionSet(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns the object whose components are selected.
+    pub fn object(&self) -> Option<ObjectHandle<'a>> {
+        self.source_objects()
+            .filter(|obj| obj.label().is_none())
+            .find_map(|obj| obj.object_handle())
+    }
+
+    /// Returns the model whose components are selected.
+    pub fn model(&self) -> Option<model::TypedModelHandle<'a>> {
+        self.source_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .find_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Model(o) => Some(o),
+                _ => None,
+            })
+    }
+
+    /// Returns whether the object itself (rather than its components) is in
+    /// the set.
+    ///
+    /// Returns `false` if the node has no `IsTheNodeInSet` node.
+    pub fn is_whole_object(&self) -> Result<bool, Error> {
+        let node = match self.node().first_child_by_name("IsTheNodeInSet") {
+            Some(v) => v,
+            None => return Ok(false),
+        };
+        let value = node
+            .attributes()
+            .first()
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?;
+        value
+            .get_i32()
+            .map(|v| v != 0)
+            .or_else(|| value.get_bool())
+            .ok_or_else(|| {
+                DomError::type_mismatch(
+                    ErrorLocation::node(&node).with_object(self.object_id()),
+                    "`i32` or `bool`",
+                    value.type_(),
+                )
+                .into()
+            })
+    }
+
+    /// Returns the indices of the selected control points.
+    ///
+    /// Returns an empty slice if the node has no `VertexIndexArray` node.
+    pub fn vertex_indices(&self) -> Result<&'a [i32], Error> {
+        self.indices("VertexIndexArray")
+    }
+
+    /// Returns the indices of the selected edges.
+    ///
+    /// Returns an empty slice if the node has no `EdgeIndexArray` node.
+    pub fn edge_indices(&self) -> Result<&'a [i32], Error> {
+        self.indices("EdgeIndexArray")
+    }
+
+    /// Returns the indices of the selected polygons.
+    ///
+    /// Returns an empty slice if the node has no `PolygonIndexArray` node.
+    pub fn polygon_indices(&self) -> Result<&'a [i32], Error> {
+        self.indices("PolygonIndexArray")
+    }
+
+    /// Returns the indices array of the child node with the given name.
+    fn indices(&self, name: &str) -> Result<&'a [i32], Error> {
+        match self.node().first_child_by_name(name) {
+            Some(node) => self
+                .document()
+                .attribute(&node, 0)?
+                .ok_or_else(|| DomError::missing_attribute(&node, 0))?
+                .get_arr_i32_or_type()
+                .map_err(|ty| {
+                    DomError::type_mismatch(
+                        ErrorLocation::node(&node).with_object(self.object_id()),
+                        "`[i32]`",
+                        ty,
+                    )
+                    .into()
+                }),
+            None => Ok(&[]),
+        }
+    }
+}
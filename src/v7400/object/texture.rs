@@ -1,10 +1,13 @@
 //! `Texture` object.
 
+use std::collections::HashSet;
+
 use mint::{Point3, Vector3};
 
 use crate::v7400::{
     data::texture::{BlendMode, BlendModeLoader, WrapMode, WrapModeLoader},
     object::{
+        material,
         property::{
             loaders::{BorrowedStringLoader, F64Arr3Loader, MintLoader, PrimitiveLoader},
             ObjectProperties,
@@ -31,6 +34,29 @@ impl<'a> TextureHandle<'a> {
             .next()
     }
 
+    /// Returns an iterator of material objects using the texture.
+    ///
+    /// Each material is returned once, even if the texture is connected to
+    /// multiple properties of the material.
+    pub fn materials(&self) -> impl Iterator<Item = material::MaterialHandle<'a>> {
+        let mut visited = HashSet::new();
+        self.material_slots()
+            .map(|(material, _)| material)
+            .filter(move |material| visited.insert(material.object_id()))
+    }
+
+    /// Returns an iterator of material objects using the texture, and the
+    /// names of the material properties (such as `DiffuseColor`) the texture
+    /// is connected to.
+    pub fn material_slots(&self) -> impl Iterator<Item = (material::MaterialHandle<'a>, &'a str)> {
+        self.destination_objects()
+            .filter_map(|obj| Some((obj.object_handle()?, obj.label()?)))
+            .filter_map(|(obj, label)| match obj.get_typed() {
+                TypedObjectHandle::Material(o) => Some((o, label)),
+                _ => None,
+            })
+    }
+
     /// Returns properties.
     pub fn properties(&self) -> TextureProperties<'a> {
         TextureProperties {
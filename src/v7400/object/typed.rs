@@ -1,13 +1,39 @@
 //! Node types.
 
 use crate::v7400::object::{
-    deformer, geometry, material, model, nodeattribute, texture, video, ObjectHandle,
+    animation, audio, character, collection, constraint, deformer, extension, geometry,
+    geometrycache, material, model, nodeattribute, reference, selection, texture, video,
+    ObjectHandle,
 };
 
 /// Typed object handle.
 #[derive(Debug, Clone, Copy)]
 #[non_exhaustive]
 pub enum TypedObjectHandle<'a> {
+    /// AnimationCurve.
+    AnimationCurve(animation::AnimationCurveHandle<'a>),
+    /// AnimationCurveNode.
+    AnimationCurveNode(animation::AnimationCurveNodeHandle<'a>),
+    /// AnimationLayer.
+    AnimationLayer(animation::AnimationLayerHandle<'a>),
+    /// AnimationStack.
+    AnimationStack(animation::AnimationStackHandle<'a>),
+    /// Audio.
+    Audio(audio::AudioHandle<'a>),
+    /// AudioLayer.
+    AudioLayer(audio::AudioLayerHandle<'a>),
+    /// Cache.
+    Cache(geometrycache::CacheHandle<'a>),
+    /// Character.
+    Character(character::CharacterHandle<'a>),
+    /// Collection.
+    Collection(collection::CollectionHandle<'a>),
+    /// CollectionExclusive.
+    CollectionExclusive(collection::CollectionExclusiveHandle<'a>),
+    /// Constraint.
+    Constraint(constraint::TypedConstraintHandle<'a>),
+    /// Container.
+    Container(collection::ContainerHandle<'a>),
     /// Deformer.
     Deformer(deformer::TypedDeformerHandle<'a>),
     /// Geometry.
@@ -18,12 +44,22 @@ pub enum TypedObjectHandle<'a> {
     Model(model::TypedModelHandle<'a>),
     /// NodeAttribute.
     NodeAttribute(nodeattribute::TypedNodeAttributeHandle<'a>),
+    /// SceneReference.
+    SceneReference(reference::SceneReferenceHandle<'a>),
+    /// SelectionNode.
+    SelectionNode(selection::SelectionNodeHandle<'a>),
+    /// SelectionSet.
+    SelectionSet(selection::SelectionSetHandle<'a>),
     /// SubDeformer.
     SubDeformer(deformer::TypedSubDeformerHandle<'a>),
     /// Texture.
     Texture(texture::TextureHandle<'a>),
     /// Model.
     Video(video::TypedVideoHandle<'a>),
+    /// Object with a user-registered extension type.
+    ///
+    /// See [`extension`](extension/index.html) module.
+    Extension(extension::ExtensionObjectHandle<'a>),
     /// Unknown.
     Unknown(ObjectHandle<'a>),
 }
@@ -31,7 +67,49 @@ pub enum TypedObjectHandle<'a> {
 impl<'a> TypedObjectHandle<'a> {
     /// Creates a new handle from the given object handle.
     pub(crate) fn new(obj: ObjectHandle<'a>) -> Self {
+        match Self::new_builtin(obj) {
+            TypedObjectHandle::Unknown(obj) => obj
+                .document()
+                .extension_types()
+                .iter()
+                .find(|ty| ty.matches(&obj))
+                .map_or(TypedObjectHandle::Unknown(obj), |ty| {
+                    TypedObjectHandle::Extension(extension::ExtensionObjectHandle::new(
+                        obj,
+                        ty.type_name(),
+                    ))
+                }),
+            typed => typed,
+        }
+    }
+
+    /// Creates a new handle for the types supported by this crate.
+    fn new_builtin(obj: ObjectHandle<'a>) -> Self {
         match obj.node().name() {
+            "AnimationCurve" => {
+                TypedObjectHandle::AnimationCurve(animation::AnimationCurveHandle::new(obj))
+            }
+            "AnimationCurveNode" => {
+                TypedObjectHandle::AnimationCurveNode(animation::AnimationCurveNodeHandle::new(obj))
+            }
+            "AnimationLayer" => {
+                TypedObjectHandle::AnimationLayer(animation::AnimationLayerHandle::new(obj))
+            }
+            "AnimationStack" => {
+                TypedObjectHandle::AnimationStack(animation::AnimationStackHandle::new(obj))
+            }
+            "Audio" => TypedObjectHandle::Audio(audio::AudioHandle::new(obj)),
+            "AudioLayer" => TypedObjectHandle::AudioLayer(audio::AudioLayerHandle::new(obj)),
+            "Cache" => TypedObjectHandle::Cache(geometrycache::CacheHandle::new(obj)),
+            "Character" => TypedObjectHandle::Character(character::CharacterHandle::new(obj)),
+            "Collection" => TypedObjectHandle::Collection(collection::CollectionHandle::new(obj)),
+            "CollectionExclusive" => TypedObjectHandle::CollectionExclusive(
+                collection::CollectionExclusiveHandle::new(obj),
+            ),
+            "Constraint" => TypedObjectHandle::Constraint(constraint::TypedConstraintHandle::new(
+                constraint::ConstraintHandle::new(obj),
+            )),
+            "Container" => TypedObjectHandle::Container(collection::ContainerHandle::new(obj)),
             "Deformer" => match obj.class() {
                 "Deformer" => TypedObjectHandle::Deformer(deformer::TypedDeformerHandle::new(
                     deformer::DeformerHandle::new(obj),
@@ -53,6 +131,15 @@ impl<'a> TypedObjectHandle<'a> {
                     nodeattribute::NodeAttributeHandle::new(obj),
                 ))
             }
+            "SceneReference" => {
+                TypedObjectHandle::SceneReference(reference::SceneReferenceHandle::new(obj))
+            }
+            "SelectionNode" => {
+                TypedObjectHandle::SelectionNode(selection::SelectionNodeHandle::new(obj))
+            }
+            "SelectionSet" => {
+                TypedObjectHandle::SelectionSet(selection::SelectionSetHandle::new(obj))
+            }
             "Texture" => TypedObjectHandle::Texture(texture::TextureHandle::new(obj)),
             "Video" => {
                 TypedObjectHandle::Video(video::TypedVideoHandle::new(video::VideoHandle::new(obj)))
@@ -67,15 +154,37 @@ impl<'a> std::ops::Deref for TypedObjectHandle<'a> {
 
     fn deref(&self) -> &Self::Target {
         match self {
+            TypedObjectHandle::AnimationCurve(o) => o,
+            TypedObjectHandle::AnimationCurveNode(o) => o,
+            TypedObjectHandle::AnimationLayer(o) => o,
+            TypedObjectHandle::AnimationStack(o) => o,
+            TypedObjectHandle::Audio(o) => o,
+            TypedObjectHandle::AudioLayer(o) => o,
+            TypedObjectHandle::Cache(o) => o,
+            TypedObjectHandle::Character(o) => o,
+            TypedObjectHandle::Collection(o) => o,
+            TypedObjectHandle::CollectionExclusive(o) => o,
+            TypedObjectHandle::Constraint(o) => &**o,
+            TypedObjectHandle::Container(o) => o,
             TypedObjectHandle::Deformer(o) => &**o,
             TypedObjectHandle::Geometry(o) => &**o,
             TypedObjectHandle::Material(o) => &**o,
             TypedObjectHandle::Model(o) => &**o,
             TypedObjectHandle::NodeAttribute(o) => &**o,
+            TypedObjectHandle::SceneReference(o) => o,
+            TypedObjectHandle::SelectionNode(o) => o,
+            TypedObjectHandle::SelectionSet(o) => o,
             TypedObjectHandle::SubDeformer(o) => &**o,
             TypedObjectHandle::Texture(o) => &**o,
             TypedObjectHandle::Video(o) => &**o,
+            TypedObjectHandle::Extension(o) => o,
             TypedObjectHandle::Unknown(o) => o,
         }
     }
 }
+
+impl std::fmt::Display for TypedObjectHandle<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&**self, f)
+    }
+}
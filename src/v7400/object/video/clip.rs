@@ -1,8 +1,11 @@
 //! `Video` object (clip).
 
-use anyhow::{format_err, Error};
+use anyhow::Error;
 
-use crate::v7400::object::video::VideoHandle;
+use crate::v7400::{
+    object::{texture, video::VideoHandle, TypedObjectHandle},
+    Error as DomError, ErrorLocation,
+};
 
 define_object_subtype! {
     /// `Video` node handle (clip).
@@ -10,27 +13,39 @@ define_object_subtype! {
 }
 
 impl<'a> ClipHandle<'a> {
+    /// Returns an iterator of texture objects using the video clip.
+    pub fn textures(&self) -> impl Iterator<Item = texture::TextureHandle<'a>> {
+        self.destination_objects()
+            .filter(|obj| obj.label().is_none())
+            .filter_map(|obj| obj.object_handle())
+            .filter_map(|obj| match obj.get_typed() {
+                TypedObjectHandle::Texture(o) => Some(o),
+                _ => None,
+            })
+    }
+
     /// Returns relative filename.
     ///
     /// Note that this returns raw value, and the path separator might be a
     /// slash or a backslash.
     pub fn relative_filename(&self) -> Result<&'a str, Error> {
         // "n" of "Filename" is lower.
-        self.node()
+        let node = self
+            .node()
             .children_by_name("RelativeFilename")
             .next()
-            .ok_or_else(|| {
-                format_err!("`RelativeFilename` child node not found for video clip object")
-            })?
-            .attributes()
+            .ok_or_else(|| DomError::missing_node(&self.node(), "RelativeFilename"))?;
+        node.attributes()
             .get(0)
-            .ok_or_else(|| format_err!("`RelativeFilename` node has no attributes"))?
+            .ok_or_else(|| DomError::missing_attribute(&node, 0))?
             .get_string_or_type()
             .map_err(|ty| {
-                format_err!(
-                    "Expected string as `RelativeFilename` value, but got {:?}",
-                    ty
+                DomError::type_mismatch(
+                    ErrorLocation::node(&node).with_object(self.object_id()),
+                    "string",
+                    ty,
                 )
+                .into()
             })
     }
     /// Returns content.
@@ -0,0 +1,268 @@
+//! Object query.
+//!
+//! [`Query`] filters the objects of a document by class, subclass, and name,
+//! and returns the matched objects as typed handles.
+//!
+//! ```
+//! # #[cfg(feature = "edit")]
+//! # fn main() -> Result<(), anyhow::Error> {
+//! use fbxcel_dom::v7400::{
+//!     edit::{DocumentBuilder, ModelKind},
+//!     object::model::TypedModelHandle,
+//! };
+//!
+//! let mut builder = DocumentBuilder::new();
+//! builder.add_model("Body_LOD0", ModelKind::Mesh, None);
+//! builder.add_model("Body_LOD1", ModelKind::Mesh, None);
+//! builder.add_model("Body_Root", ModelKind::Null, None);
+//! builder.add_model("Eye_LOD0", ModelKind::Mesh, None);
+//! let doc = builder.build()?;
+//!
+//! let names: Vec<_> = doc
+//!     .query()
+//!     .class("Model")
+//!     .subclass("Mesh")
+//!     .name_starts_with("Body")
+//!     .name_contains("_LOD")
+//!     .models()
+//!     .filter_map(|model| match model {
+//!         TypedModelHandle::Mesh(mesh) => mesh.name(),
+//!         _ => None,
+//!     })
+//!     .collect();
+//! assert_eq!(names, ["Body_LOD0", "Body_LOD1"]);
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "edit"))]
+//! # fn main() {}
+//! ```
+//!
+//! [`Query`]: struct.Query.html
+
+use std::fmt;
+
+use crate::v7400::{
+    object::{model::TypedModelHandle, NameMatchOptions, ObjectHandle, TypedObjectHandle},
+    Document,
+};
+
+/// Custom condition.
+type Predicate<'a> = Box<dyn Fn(&ObjectHandle<'a>) -> bool + 'a>;
+
+/// Name condition.
+#[derive(Debug, Clone)]
+enum NameFilter {
+    /// Name equals to the string.
+    Equals(String),
+    /// Name contains the string.
+    Contains(String),
+    /// Name starts with the string.
+    StartsWith(String),
+    /// Name ends with the string.
+    EndsWith(String),
+}
+
+impl NameFilter {
+    /// Returns whether the name matches under the options.
+    fn matches(&self, name: &str, options: NameMatchOptions) -> bool {
+        let name = options.comparable(name);
+        match self {
+            NameFilter::Equals(s) => name == options.comparable(s),
+            NameFilter::Contains(s) => name.contains(options.comparable(s)),
+            NameFilter::StartsWith(s) => name.starts_with(options.comparable(s)),
+            NameFilter::EndsWith(s) => name.ends_with(options.comparable(s)),
+        }
+    }
+}
+
+/// Object query.
+///
+/// All conditions must be satisfied.
+/// Objects are returned in the order of the objects in the document.
+///
+/// This is created by [`Document::query`].
+///
+/// [`Document::query`]: ../struct.Document.html#method.query
+pub struct Query<'a> {
+    /// Document.
+    doc: &'a Document,
+    /// Class.
+    class: Option<String>,
+    /// Subclass.
+    subclass: Option<String>,
+    /// Name conditions.
+    names: Vec<NameFilter>,
+    /// Name matching options.
+    name_options: NameMatchOptions,
+    /// Custom conditions.
+    filters: Vec<Predicate<'a>>,
+}
+
+impl<'a> Query<'a> {
+    /// Creates a new query matching all objects of the document.
+    pub(crate) fn new(doc: &'a Document) -> Self {
+        Self {
+            doc,
+            class: None,
+            subclass: None,
+            names: Vec::new(),
+            name_options: NameMatchOptions::new(),
+            filters: Vec::new(),
+        }
+    }
+
+    /// Matches objects of the given class, such as `"Model"`.
+    pub fn class(mut self, class: &str) -> Self {
+        self.class = Some(class.to_owned());
+        self
+    }
+
+    /// Matches objects of the given subclass, such as `"Mesh"`.
+    pub fn subclass(mut self, subclass: &str) -> Self {
+        self.subclass = Some(subclass.to_owned());
+        self
+    }
+
+    /// Matches objects with the given name.
+    pub fn name(mut self, name: &str) -> Self {
+        self.names.push(NameFilter::Equals(name.to_owned()));
+        self
+    }
+
+    /// Matches objects whose names contain the given string.
+    pub fn name_contains(mut self, s: &str) -> Self {
+        self.names.push(NameFilter::Contains(s.to_owned()));
+        self
+    }
+
+    /// Matches objects whose names start with the given string.
+    pub fn name_starts_with(mut self, prefix: &str) -> Self {
+        self.names.push(NameFilter::StartsWith(prefix.to_owned()));
+        self
+    }
+
+    /// Matches objects whose names end with the given string.
+    pub fn name_ends_with(mut self, suffix: &str) -> Self {
+        self.names.push(NameFilter::EndsWith(suffix.to_owned()));
+        self
+    }
+
+    /// Sets the options for the name conditions.
+    ///
+    /// If namespaces are ignored, the conditions are checked against the
+    /// object names without namespaces, and namespaces in the given strings
+    /// are also removed.
+    ///
+    /// ```
+    /// # #[cfg(feature = "edit")]
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// use fbxcel_dom::v7400::{
+    ///     edit::{DocumentBuilder, ModelKind},
+    ///     object::NameMatchOptions,
+    /// };
+    ///
+    /// let mut builder = DocumentBuilder::new();
+    /// builder.add_model("Rig:Hips", ModelKind::Null, None);
+    /// builder.add_model("Rig:Spine", ModelKind::Null, None);
+    /// let doc = builder.build()?;
+    ///
+    /// assert_eq!(doc.query().name_starts_with("Hi").count(), 0);
+    /// let options = NameMatchOptions::new().ignore_namespace(true);
+    /// let hips = doc.query().name_options(options).name_starts_with("Hi").first();
+    /// assert_eq!(hips.and_then(|obj| obj.name()), Some("Rig:Hips"));
+    /// assert_eq!(doc.query().name_options(options).name("Other:Spine").count(), 1);
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "edit"))]
+    /// # fn main() {}
+    /// ```
+    pub fn name_options(mut self, options: NameMatchOptions) -> Self {
+        self.name_options = options;
+        self
+    }
+
+    /// Matches objects satisfying the given predicate.
+    pub fn filter(mut self, f: impl Fn(&ObjectHandle<'a>) -> bool + 'a) -> Self {
+        self.filters.push(Box::new(f));
+        self
+    }
+
+    /// Returns whether the object matches the query.
+    pub fn matches(&self, obj: &ObjectHandle<'a>) -> bool {
+        if self
+            .class
+            .as_deref()
+            .map_or(false, |class| obj.class() != class)
+        {
+            return false;
+        }
+        if self
+            .subclass
+            .as_deref()
+            .map_or(false, |subclass| obj.subclass() != subclass)
+        {
+            return false;
+        }
+        if !self.names.is_empty() {
+            let name = match obj.name() {
+                Some(v) => v,
+                None => return false,
+            };
+            if !self
+                .names
+                .iter()
+                .all(|filter| filter.matches(name, self.name_options))
+            {
+                return false;
+            }
+        }
+        self.filters.iter().all(|f| f(obj))
+    }
+
+    /// Returns an iterator of the matched objects.
+    pub fn objects(self) -> impl Iterator<Item = ObjectHandle<'a>> {
+        let doc = self.doc;
+        let candidates: Box<dyn Iterator<Item = ObjectHandle<'a>>> = match &self.class {
+            Some(class) => Box::new(doc.objects_by_class(class, self.subclass.as_deref())),
+            None => Box::new(doc.objects()),
+        };
+        candidates.filter(move |obj| self.matches(obj))
+    }
+
+    /// Returns an iterator of the matched objects as typed handles.
+    pub fn typed(self) -> impl Iterator<Item = TypedObjectHandle<'a>> {
+        self.objects().map(|obj| obj.get_typed())
+    }
+
+    /// Returns an iterator of the matched model objects.
+    ///
+    /// Matched objects which are not models are skipped.
+    pub fn models(self) -> impl Iterator<Item = TypedModelHandle<'a>> {
+        self.typed().filter_map(|obj| match obj {
+            TypedObjectHandle::Model(o) => Some(o),
+            _ => None,
+        })
+    }
+
+    /// Returns the first matched object, if available.
+    pub fn first(self) -> Option<ObjectHandle<'a>> {
+        self.objects().next()
+    }
+
+    /// Returns the number of the matched objects.
+    pub fn count(self) -> usize {
+        self.objects().count()
+    }
+}
+
+impl fmt::Debug for Query<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Query")
+            .field("class", &self.class)
+            .field("subclass", &self.subclass)
+            .field("names", &self.names)
+            .field("name_options", &self.name_options)
+            .field("filters", &self.filters.len())
+            .finish()
+    }
+}
@@ -0,0 +1,442 @@
+//! Owned scene graph description.
+//!
+//! [`SceneGraph`] is a flattened digest of the model hierarchy of a document:
+//! nodes are stored in a vector with stable indices, and attached geometries,
+//! materials, cameras, and lights are referenced by index.
+//!
+//! It does not borrow the document, so it can be kept after the document is
+//! dropped.
+//!
+//! [`Extractor`] (and [`extract_all()`]) additionally extracts triangulated
+//! mesh buffers and baked animations of the scene, processing geometries and
+//! animation stacks on multiple threads.
+//! The extraction is configured by [`ExtractOptions`].
+//!
+//! With the `usd` feature, [`usd::Stage`] organizes the scene the way USD
+//! expects (prim paths, `xformOp`s, mesh schema attributes, and material
+//! bindings).
+//!
+//! [`SceneGraph`]: struct.SceneGraph.html
+//! [`ExtractOptions`]: struct.ExtractOptions.html
+
+use std::collections::HashMap;
+
+use anyhow::Error;
+use mint::ColumnMatrix4;
+
+use crate::v7400::{
+    data::transform::{LocalTransform, TransformCache},
+    object::{model::TypedModelHandle, nodeattribute::TypedNodeAttributeHandle, ObjectId},
+    Document,
+};
+
+#[cfg(feature = "animation")]
+pub use self::bake::{BakedAnimation, BakedNodeAnimation};
+#[cfg(feature = "gltf-export")]
+pub(crate) use self::{bake::sample_times, extract::strongest_influences};
+pub use self::{
+    extract::{extract_all, ExtractedMesh, ExtractedSkin, Extractor, SceneExtract},
+    mesh_data::MeshData,
+    options::{ExtractOptions, Precision, TargetAxes},
+    simple::{SimpleMaterial, SimpleMesh, SimpleScene, SimpleTexture},
+};
+
+#[cfg(feature = "animation")]
+mod bake;
+mod extract;
+mod mesh_data;
+mod options;
+mod simple;
+#[cfg(feature = "usd")]
+pub mod usd;
+
+/// Defines an index type.
+macro_rules! define_index {
+    ($(#[$meta:meta])* $ty:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+        #[repr(transparent)]
+        pub struct $ty(usize);
+
+        impl $ty {
+            /// Returns the index.
+            pub fn to_usize(self) -> usize {
+                self.0
+            }
+        }
+    };
+}
+
+define_index! {
+    /// Index of a node in a scene graph.
+    NodeIndex
+}
+
+define_index! {
+    /// Index of a geometry in a scene graph.
+    GeometryIndex
+}
+
+define_index! {
+    /// Index of a material in a scene graph.
+    MaterialIndex
+}
+
+define_index! {
+    /// Index of a camera in a scene graph.
+    CameraIndex
+}
+
+define_index! {
+    /// Index of a light in a scene graph.
+    LightIndex
+}
+
+/// Kind of a scene node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum SceneNodeKind {
+    /// Camera.
+    Camera,
+    /// Stereo camera rig.
+    CameraStereo,
+    /// Light.
+    Light,
+    /// Skeleton bone.
+    LimbNode,
+    /// Mesh.
+    Mesh,
+    /// Null (transform only).
+    Null,
+    /// Unknown.
+    Unknown,
+}
+
+impl SceneNodeKind {
+    /// Returns the kind of the given model.
+    fn from_model(model: &TypedModelHandle<'_>) -> Self {
+        match model {
+            TypedModelHandle::Camera(_) => SceneNodeKind::Camera,
+            TypedModelHandle::CameraStereo(_) => SceneNodeKind::CameraStereo,
+            TypedModelHandle::Light(_) => SceneNodeKind::Light,
+            TypedModelHandle::LimbNode(_) => SceneNodeKind::LimbNode,
+            TypedModelHandle::Mesh(_) => SceneNodeKind::Mesh,
+            TypedModelHandle::Null(_) => SceneNodeKind::Null,
+            _ => SceneNodeKind::Unknown,
+        }
+    }
+}
+
+/// Node of a scene graph.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct SceneNode {
+    /// Object ID of the model.
+    pub object_id: ObjectId,
+    /// Name of the model.
+    pub name: String,
+    /// Kind of the model.
+    pub kind: SceneNodeKind,
+    /// Parent node.
+    pub parent: Option<NodeIndex>,
+    /// Child nodes.
+    pub children: Vec<NodeIndex>,
+    /// Decomposed local transform.
+    pub local_transform: LocalTransform,
+    /// Global (world) transform.
+    pub global_transform: ColumnMatrix4<f64>,
+    /// Geometric transform (applied to the attached geometry only).
+    pub geometric_transform: ColumnMatrix4<f64>,
+    /// Attached geometry.
+    pub geometry: Option<GeometryIndex>,
+    /// Attached materials.
+    pub materials: Vec<MaterialIndex>,
+    /// Attached camera.
+    pub camera: Option<CameraIndex>,
+    /// Attached light.
+    pub light: Option<LightIndex>,
+}
+
+/// Owned, flattened scene graph.
+///
+/// See the [module-level documentation](index.html).
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct SceneGraph {
+    /// Nodes.
+    nodes: Vec<SceneNode>,
+    /// Root nodes.
+    roots: Vec<NodeIndex>,
+    /// Object IDs of geometries.
+    geometries: Vec<ObjectId>,
+    /// Object IDs of materials.
+    materials: Vec<ObjectId>,
+    /// Object IDs of camera node attributes.
+    cameras: Vec<ObjectId>,
+    /// Object IDs of light node attributes.
+    lights: Vec<ObjectId>,
+    /// Node indices by object ID.
+    node_indices: HashMap<ObjectId, NodeIndex>,
+}
+
+impl SceneGraph {
+    /// Creates a scene graph from the models in the given document.
+    ///
+    /// Nodes are stored in depth-first pre-order.
+    /// Roots are sorted by object ID, and children are in connection order,
+    /// so the indices are stable for the same input.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "scene_graph", skip_all)
+    )]
+    pub fn from_document(doc: &Document) -> Result<Self, Error> {
+        SceneGraphBuilder::default().build(doc)
+    }
+
+    /// Returns the nodes.
+    pub fn nodes(&self) -> &[SceneNode] {
+        &self.nodes
+    }
+
+    /// Returns the node at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of range, which can happen only for an
+    /// index from another scene graph.
+    pub fn node(&self, index: NodeIndex) -> &SceneNode {
+        &self.nodes[index.0]
+    }
+
+    /// Returns the node at the given index, or `None` if out of range.
+    pub fn get_node(&self, index: NodeIndex) -> Option<&SceneNode> {
+        self.nodes.get(index.0)
+    }
+
+    /// Returns the root nodes.
+    pub fn roots(&self) -> &[NodeIndex] {
+        &self.roots
+    }
+
+    /// Returns the object ID of the geometry at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of range, which can happen only for an
+    /// index from another scene graph.
+    pub fn geometry(&self, index: GeometryIndex) -> ObjectId {
+        self.geometries[index.0]
+    }
+
+    /// Returns the object ID of the geometry at the given index, or `None`
+    /// if out of range.
+    pub fn get_geometry(&self, index: GeometryIndex) -> Option<ObjectId> {
+        self.geometries.get(index.0).copied()
+    }
+
+    /// Returns the object IDs of the geometries.
+    pub fn geometries(&self) -> &[ObjectId] {
+        &self.geometries
+    }
+
+    /// Returns the object ID of the material at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of range, which can happen only for an
+    /// index from another scene graph.
+    pub fn material(&self, index: MaterialIndex) -> ObjectId {
+        self.materials[index.0]
+    }
+
+    /// Returns the object ID of the material at the given index, or `None`
+    /// if out of range.
+    pub fn get_material(&self, index: MaterialIndex) -> Option<ObjectId> {
+        self.materials.get(index.0).copied()
+    }
+
+    /// Returns the object IDs of the materials.
+    pub fn materials(&self) -> &[ObjectId] {
+        &self.materials
+    }
+
+    /// Returns the object ID of the camera node attribute at the given
+    /// index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of range, which can happen only for an
+    /// index from another scene graph.
+    pub fn camera(&self, index: CameraIndex) -> ObjectId {
+        self.cameras[index.0]
+    }
+
+    /// Returns the object ID of the camera node attribute at the given index, or
+    /// `None` if out of range.
+    pub fn get_camera(&self, index: CameraIndex) -> Option<ObjectId> {
+        self.cameras.get(index.0).copied()
+    }
+
+    /// Returns the object IDs of the camera node attributes.
+    pub fn cameras(&self) -> &[ObjectId] {
+        &self.cameras
+    }
+
+    /// Returns the object ID of the light node attribute at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of range, which can happen only for an
+    /// index from another scene graph.
+    pub fn light(&self, index: LightIndex) -> ObjectId {
+        self.lights[index.0]
+    }
+
+    /// Returns the object ID of the light node attribute at the given index, or
+    /// `None` if out of range.
+    pub fn get_light(&self, index: LightIndex) -> Option<ObjectId> {
+        self.lights.get(index.0).copied()
+    }
+
+    /// Returns the object IDs of the light node attributes.
+    pub fn lights(&self) -> &[ObjectId] {
+        &self.lights
+    }
+
+    /// Returns the index of the node for the given model object ID.
+    pub fn node_index(&self, object_id: ObjectId) -> Option<NodeIndex> {
+        self.node_indices.get(&object_id).copied()
+    }
+}
+
+/// Scene graph builder.
+#[derive(Debug, Default)]
+struct SceneGraphBuilder {
+    /// Scene graph being built.
+    graph: SceneGraph,
+    /// Geometry indices by object ID.
+    geometry_indices: HashMap<ObjectId, GeometryIndex>,
+    /// Material indices by object ID.
+    material_indices: HashMap<ObjectId, MaterialIndex>,
+    /// Camera indices by object ID.
+    camera_indices: HashMap<ObjectId, CameraIndex>,
+    /// Light indices by object ID.
+    light_indices: HashMap<ObjectId, LightIndex>,
+    /// Transform cache.
+    transforms: TransformCache,
+}
+
+impl SceneGraphBuilder {
+    /// Builds the scene graph.
+    fn build(mut self, doc: &Document) -> Result<SceneGraph, Error> {
+        let mut roots: Vec<_> = doc.model_roots().collect();
+        roots.sort_by_key(|model| model.object_id());
+
+        // Depth-first pre-order, using an explicit stack.
+        let mut stack: Vec<_> = roots.into_iter().rev().map(|m| (m, None)).collect();
+        while let Some((model, parent)) = stack.pop() {
+            if self.graph.node_indices.contains_key(&model.object_id()) {
+                continue;
+            }
+            let index = self.add_node(&model, parent)?;
+            match parent {
+                Some(parent) => self.graph.nodes[parent.0].children.push(index),
+                None => self.graph.roots.push(index),
+            }
+            let len = stack.len();
+            stack.extend(model.child_models().map(|child| (child, Some(index))));
+            stack[len..].reverse();
+        }
+
+        Ok(self.graph)
+    }
+
+    /// Adds a node for the given model.
+    fn add_node(
+        &mut self,
+        model: &TypedModelHandle<'_>,
+        parent: Option<NodeIndex>,
+    ) -> Result<NodeIndex, Error> {
+        let index = NodeIndex(self.graph.nodes.len());
+        self.graph.node_indices.insert(model.object_id(), index);
+
+        let mut geometry = None;
+        let mut materials = Vec::new();
+        if let TypedModelHandle::Mesh(mesh) = model {
+            if let Ok(geom) = mesh.geometry() {
+                geometry = Some(intern(
+                    &mut self.geometry_indices,
+                    &mut self.graph.geometries,
+                    geom.object_id(),
+                    GeometryIndex,
+                ));
+            }
+            materials = mesh
+                .materials()
+                .map(|mat| {
+                    intern(
+                        &mut self.material_indices,
+                        &mut self.graph.materials,
+                        mat.object_id(),
+                        MaterialIndex,
+                    )
+                })
+                .collect();
+        }
+
+        let mut camera = None;
+        let mut light = None;
+        match model.node_attribute() {
+            Some(attr @ TypedNodeAttributeHandle::Camera(_))
+            | Some(attr @ TypedNodeAttributeHandle::CameraStereo(_)) => {
+                camera = Some(intern(
+                    &mut self.camera_indices,
+                    &mut self.graph.cameras,
+                    attr.object_id(),
+                    CameraIndex,
+                ));
+            }
+            Some(attr @ TypedNodeAttributeHandle::Light(_)) => {
+                light = Some(intern(
+                    &mut self.light_indices,
+                    &mut self.graph.lights,
+                    attr.object_id(),
+                    LightIndex,
+                ));
+            }
+            _ => {}
+        }
+
+        self.graph.nodes.push(SceneNode {
+            object_id: model.object_id(),
+            name: model.name().unwrap_or_default().to_owned(),
+            kind: SceneNodeKind::from_model(model),
+            parent,
+            children: Vec::new(),
+            local_transform: model.local_transform()?,
+            global_transform: self.transforms.global_transform(model)?,
+            geometric_transform: model.geometric_transform()?,
+            geometry,
+            materials,
+            camera,
+            light,
+        });
+
+        Ok(index)
+    }
+}
+
+/// Returns the index for the given object ID, registering it if necessary.
+fn intern<I: Copy>(
+    indices: &mut HashMap<ObjectId, I>,
+    ids: &mut Vec<ObjectId>,
+    id: ObjectId,
+    new_index: impl FnOnce(usize) -> I,
+) -> I {
+    *indices.entry(id).or_insert_with(|| {
+        ids.push(id);
+        new_index(ids.len() - 1)
+    })
+}
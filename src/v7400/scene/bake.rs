@@ -0,0 +1,204 @@
+//! Animation baking.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Error};
+use log::debug;
+use mint::{ColumnMatrix4, Vector3};
+
+use crate::v7400::{
+    object::{
+        animation::{AnimationCurveHandle, KTIME_PER_SECOND},
+        ObjectId, TypedObjectHandle,
+    },
+    scene::{NodeIndex, SceneGraph},
+    Document,
+};
+
+/// Animation curves of a node, indexed by `[property][axis]`.
+///
+/// Properties are translation, rotation, and scaling.
+type NodeCurves<'a> = [[Option<AnimationCurveHandle<'a>>; 3]; 3];
+
+/// Animation stack baked into sampled local transforms.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct BakedAnimation {
+    /// Object ID of the animation stack.
+    pub stack: ObjectId,
+    /// Name of the animation stack.
+    pub name: String,
+    /// Sample times in seconds, relative to the first key.
+    pub times: Vec<f32>,
+    /// Animated nodes.
+    pub nodes: Vec<BakedNodeAnimation>,
+}
+
+/// Sampled local transforms of a node.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct BakedNodeAnimation {
+    /// Node in the scene graph.
+    pub node: NodeIndex,
+    /// Local transform matrices at the sample times.
+    pub local_matrices: Vec<ColumnMatrix4<f32>>,
+}
+
+/// Maximum number of samples of a baked animation.
+const MAX_ANIMATION_SAMPLES: f64 = 1e6;
+
+/// Returns the sample times in `KTime` ticks from `start` to `end` (including
+/// the end) at the sample rate.
+///
+/// Fails if the sample rate is not positive, or if the range needs more than
+/// a million samples (which means broken key times).
+pub(crate) fn sample_times(start: i64, end: i64, sample_rate: f64) -> Result<Vec<i64>, Error> {
+    if !(sample_rate > 0.0 && sample_rate.is_finite()) {
+        bail!("Invalid animation sample rate: {}", sample_rate);
+    }
+    let step = KTIME_PER_SECOND as f64 / sample_rate;
+    // Differences are computed in `f64` since they can overflow `i64`.
+    let count = ((end as f64 - start as f64) / step).ceil();
+    if count > MAX_ANIMATION_SAMPLES {
+        bail!(
+            "Too many animation samples: start={}, end={}, samples={}",
+            start,
+            end,
+            count
+        );
+    }
+    let mut times: Vec<i64> = (0..count.max(0.0) as u32)
+        .map(|i| start.saturating_add((f64::from(i) * step).round() as i64))
+        .take_while(|&t| t < end)
+        .collect();
+    times.push(end);
+    Ok(times)
+}
+
+/// Bakes the animation stack.
+///
+/// Only the first (base) animation layer of the stack is baked, and the local
+/// transforms of the animated nodes are sampled at the sample rate over the
+/// range of the keys.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "bake_animation",
+        skip_all,
+        fields(stack = ?stack_id)
+    )
+)]
+pub(crate) fn bake_animation(
+    doc: &Document,
+    graph: &SceneGraph,
+    stack_id: ObjectId,
+    sample_rate: f64,
+) -> Result<BakedAnimation, Error> {
+    let stack = match stack_id.to_object_handle(doc).map(|obj| obj.get_typed()) {
+        Some(TypedObjectHandle::AnimationStack(o)) => o,
+        _ => bail!("Animation stack not found: {:?}", stack_id),
+    };
+    let mut baked = BakedAnimation {
+        stack: stack_id,
+        name: stack.name().unwrap_or_default().to_owned(),
+        times: Vec::new(),
+        nodes: Vec::new(),
+    };
+    let layer = match stack.layers().next() {
+        Some(v) => v,
+        None => return Ok(baked),
+    };
+
+    let mut node_curves: BTreeMap<NodeIndex, NodeCurves<'_>> = BTreeMap::new();
+    for curve_node in layer.curve_nodes() {
+        let (target, property) = match curve_node.target() {
+            Some(v) => v,
+            None => continue,
+        };
+        let property = match property {
+            "Lcl Translation" => 0,
+            "Lcl Rotation" => 1,
+            "Lcl Scaling" => 2,
+            _ => {
+                debug!("Skipping animated property {:?}", property);
+                continue;
+            }
+        };
+        let node = match graph.node_index(target.object_id()) {
+            Some(v) => v,
+            None => continue,
+        };
+        for (curve, channel) in curve_node.curves() {
+            let axis = match channel {
+                "d|X" => 0,
+                "d|Y" => 1,
+                "d|Z" => 2,
+                _ => continue,
+            };
+            node_curves.entry(node).or_default()[property][axis] = Some(curve);
+        }
+    }
+
+    let mut range: Option<(i64, i64)> = None;
+    for curve in node_curves.values().flatten().flatten().flatten() {
+        let times = curve.key_times()?;
+        if let (Some(&first), Some(&last)) = (times.first(), times.last()) {
+            range = Some(match range {
+                Some((start, end)) => (start.min(first), end.max(last)),
+                None => (first, last),
+            });
+        }
+    }
+    let (start, end) = match range {
+        Some(v) => v,
+        None => return Ok(baked),
+    };
+
+    let times = sample_times(start, end, sample_rate)?;
+    baked.times = times
+        .iter()
+        .map(|&t| ((t as f64 - start as f64) / KTIME_PER_SECOND as f64) as f32)
+        .collect();
+    #[cfg(feature = "tracing")]
+    tracing::debug!(nodes = node_curves.len(), samples = times.len(), "Sampling");
+
+    for (&node, curves) in &node_curves {
+        let base = graph.node(node).local_transform;
+        let mut local_matrices = Vec::with_capacity(times.len());
+        for &time in &times {
+            let mut local = base;
+            for (property, axes) in curves.iter().enumerate() {
+                let value = match property {
+                    0 => &mut local.translation,
+                    1 => &mut local.rotation,
+                    _ => &mut local.scaling,
+                };
+                for (axis, curve) in axes.iter().enumerate() {
+                    let curve = match curve {
+                        Some(v) => v,
+                        None => continue,
+                    };
+                    if let Some(v) = curve.evaluate(time)? {
+                        set_axis(value, axis, f64::from(v));
+                    }
+                }
+            }
+            local_matrices.push(local.to_matrix_f32());
+        }
+        baked.nodes.push(BakedNodeAnimation {
+            node,
+            local_matrices,
+        });
+    }
+
+    Ok(baked)
+}
+
+/// Sets the component of the vector.
+fn set_axis(v: &mut Vector3<f64>, axis: usize, value: f64) {
+    match axis {
+        0 => v.x = value,
+        1 => v.y = value,
+        _ => v.z = value,
+    }
+}
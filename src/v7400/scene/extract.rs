@@ -0,0 +1,644 @@
+//! Parallel whole-scene extraction.
+
+use std::{collections::HashMap, convert::TryFrom};
+
+use anyhow::{bail, format_err, Error};
+use log::debug;
+use mint::ColumnMatrix4;
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+#[cfg(feature = "animation")]
+use crate::v7400::scene::bake::{bake_animation, BakedAnimation};
+use crate::v7400::{
+    data::{
+        mesh::{PolygonVertex, PolygonVertexIndex, PolygonVertices, ResolvedLayerElements},
+        scalar,
+        transform::math::Mat4,
+    },
+    object::{
+        geometry::{MeshHandle, TypedGeometryHandle},
+        ObjectId, TypedObjectHandle,
+    },
+    scene::{ExtractOptions, GeometryIndex, NodeIndex, Precision, SceneGraph},
+    Document, Warning,
+};
+
+/// Maximum number of joint influences per vertex.
+const MAX_INFLUENCES: usize = 4;
+
+/// Influences (joint index and weight) for each control point.
+type Influences = Vec<Vec<(u16, f32)>>;
+
+/// Extracts the scene of the document with the default options.
+///
+/// This is same as `Extractor::new().extract(doc)`.
+pub fn extract_all(doc: &Document) -> Result<SceneExtract, Error> {
+    Extractor::new().extract(doc)
+}
+
+/// Whole-scene extractor.
+///
+/// Geometry meshes are triangulated, welded into indexed vertex buffers with
+/// tangents, and animation stacks are baked into sampled local transforms
+/// (if the `animation` feature is enabled).
+/// Each geometry and each animation stack is processed as a separate job,
+/// and the jobs are processed in parallel with `rayon` if the `rayon` feature
+/// is enabled.
+///
+/// Meshes and animations which fail to be extracted are skipped with
+/// warnings.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct Extractor {
+    /// Options.
+    options: ExtractOptions,
+}
+
+impl Extractor {
+    /// Creates a new extractor with the default options.
+    ///
+    /// See [`ExtractOptions::new`](struct.ExtractOptions.html#method.new)
+    /// for the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new extractor with the given options.
+    pub fn with_options(options: ExtractOptions) -> Self {
+        Self { options }
+    }
+
+    /// Returns the options.
+    pub fn options(&self) -> &ExtractOptions {
+        &self.options
+    }
+
+    /// Sets the number of worker threads.
+    ///
+    /// See [`ExtractOptions::threads`](struct.ExtractOptions.html#method.threads).
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.options = self.options.threads(threads);
+        self
+    }
+
+    /// Sets the frames per second for baking animations.
+    pub fn sample_rate(mut self, fps: f64) -> Self {
+        self.options = self.options.sample_rate(fps);
+        self
+    }
+
+    /// Extracts the scene of the given document.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "extract_scene",
+            skip_all,
+            fields(threads = self.options.threads)
+        )
+    )]
+    pub fn extract(&self, doc: &Document) -> Result<SceneExtract, Error> {
+        let options = self.options;
+        if !(options.sample_rate.is_finite() && options.sample_rate > 0.0) {
+            bail!("Invalid animation sample rate: {}", options.sample_rate);
+        }
+        let graph = SceneGraph::from_document(doc)?;
+
+        let jobs: Vec<Job> = (0..graph.geometries().len())
+            .map(|i| Job::Mesh(GeometryIndex(i)))
+            .chain(Job::animations(doc))
+            .collect();
+        debug!(
+            "Extracting {} jobs with {} threads",
+            jobs.len(),
+            options.threads
+        );
+
+        let run = |job: Job| doc.capture_warnings(|| job.run(doc, &graph, &options));
+        #[cfg(feature = "rayon")]
+        let outputs: Vec<_> = if options.threads <= 1 {
+            jobs.into_iter().map(run).collect()
+        } else {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(options.threads)
+                .build()?
+                .install(|| jobs.into_par_iter().map(run).collect())
+        };
+        #[cfg(not(feature = "rayon"))]
+        let outputs: Vec<_> = jobs.into_iter().map(run).collect();
+
+        let mut meshes = Vec::new();
+        #[cfg(feature = "animation")]
+        let mut animations = Vec::new();
+        for (output, warnings) in outputs {
+            doc.extend_warnings(warnings);
+            match output {
+                JobOutput::Mesh(_, Ok(mesh)) => meshes.push(mesh),
+                #[cfg(feature = "animation")]
+                JobOutput::Animation(_, Ok(animation)) => animations.push(animation),
+                JobOutput::Mesh(id, Err(e)) => doc.warn(Warning::ExtractionFailed {
+                    object: Some(id),
+                    message: format!("Failed to extract mesh: {:#}", e),
+                }),
+                #[cfg(feature = "animation")]
+                JobOutput::Animation(id, Err(e)) => doc.warn(Warning::ExtractionFailed {
+                    object: Some(id),
+                    message: format!("Failed to bake animation stack: {:#}", e),
+                }),
+            }
+        }
+        Ok(SceneExtract {
+            graph,
+            meshes,
+            #[cfg(feature = "animation")]
+            animations,
+            root_transform: options.root_transform(doc).into(),
+        })
+    }
+}
+
+/// Extracted scene.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct SceneExtract {
+    /// Scene graph.
+    pub graph: SceneGraph,
+    /// Meshes, in the order of the geometries in the scene graph.
+    pub meshes: Vec<ExtractedMesh>,
+    /// Baked animations, in the order of the animation stacks.
+    #[cfg(feature = "animation")]
+    pub animations: Vec<BakedAnimation>,
+    /// Transform converting the scene into the target axis system and unit.
+    ///
+    /// Global transforms, meshes, and animations are in the axis system and
+    /// the unit of the document, and this should be applied on top of the
+    /// scene (for example, as the transform of an additional root node).
+    /// This is identity if no targets are set in [`ExtractOptions`].
+    ///
+    /// [`ExtractOptions`]: struct.ExtractOptions.html
+    pub root_transform: ColumnMatrix4<f64>,
+}
+
+/// Triangulated and welded mesh.
+///
+/// Values are in the local space of the geometry, and the geometric
+/// transforms of the models are not applied.
+/// Vertex attributes are plain arrays, and can be converted into `mint`
+/// types with `From` (such as `mint::Point3::from`).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExtractedMesh {
+    /// Geometry in the scene graph.
+    pub geometry: GeometryIndex,
+    /// Vertex positions.
+    pub positions: Vec<[f32; 3]>,
+    /// Vertex positions in `f64`, if [`Precision::F64`] is requested.
+    ///
+    /// [`Precision::F64`]: enum.Precision.html#variant.F64
+    pub positions_f64: Option<Vec<[f64; 3]>>,
+    /// Vertex normals.
+    pub normals: Option<Vec<[f32; 3]>>,
+    /// Vertex texture coordinates of the first UV set.
+    pub uvs: Option<Vec<[f32; 2]>>,
+    /// Vertex tangents, with the handedness of the bitangent as `w`.
+    ///
+    /// Generated if the mesh has both normals and texture coordinates.
+    pub tangents: Option<Vec<[f32; 4]>>,
+    /// Vertex joint indices into [`ExtractedSkin::joints`].
+    pub joints: Option<Vec<[u16; 4]>>,
+    /// Vertex joint weights, normalized to sum to one.
+    pub weights: Option<Vec<[f32; 4]>>,
+    /// Triangle vertex indices, or polygon vertex indices if triangulation
+    /// is disabled.
+    pub indices: Vec<u32>,
+    /// Number of vertices of each polygon, if triangulation is disabled.
+    ///
+    /// Polygons are stored in `indices` consecutively.
+    pub polygon_sizes: Option<Vec<u32>>,
+    /// Skin, if the mesh has skin deformers.
+    pub skin: Option<ExtractedSkin>,
+}
+
+/// Skin of an extracted mesh.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExtractedSkin {
+    /// Joint nodes.
+    pub joints: Vec<NodeIndex>,
+    /// Inverse bind matrices of the joints.
+    ///
+    /// These transform the vertices of the mesh into the joint space at the
+    /// bind time.
+    pub inverse_bind_matrices: Vec<ColumnMatrix4<f32>>,
+}
+
+/// Extraction job.
+#[derive(Debug, Clone, Copy)]
+enum Job {
+    /// Mesh extraction.
+    Mesh(GeometryIndex),
+    /// Animation stack baking.
+    #[cfg(feature = "animation")]
+    Animation(ObjectId),
+}
+
+impl Job {
+    /// Returns the animation stack baking jobs.
+    #[cfg(feature = "animation")]
+    fn animations(doc: &Document) -> impl Iterator<Item = Job> + '_ {
+        doc.anim_stacks()
+            .map(|stack| Job::Animation(stack.object_id()))
+    }
+
+    /// Returns no jobs, since animation baking is disabled.
+    #[cfg(not(feature = "animation"))]
+    fn animations(_doc: &Document) -> std::iter::Empty<Job> {
+        std::iter::empty()
+    }
+
+    /// Runs the job.
+    fn run(self, doc: &Document, graph: &SceneGraph, options: &ExtractOptions) -> JobOutput {
+        match self {
+            Job::Mesh(geometry) => JobOutput::Mesh(
+                graph.geometry(geometry),
+                extract_mesh(doc, graph, geometry, options),
+            ),
+            #[cfg(feature = "animation")]
+            Job::Animation(stack) => JobOutput::Animation(
+                stack,
+                bake_animation(doc, graph, stack, options.sample_rate),
+            ),
+        }
+    }
+}
+
+/// Output of an extraction job.
+enum JobOutput {
+    /// Mesh extraction, with the geometry object ID.
+    Mesh(ObjectId, Result<ExtractedMesh, Error>),
+    /// Animation stack baking, with the animation stack object ID.
+    #[cfg(feature = "animation")]
+    Animation(ObjectId, Result<BakedAnimation, Error>),
+}
+
+/// Extracts the mesh of the geometry.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "extract_mesh", skip_all)
+)]
+fn extract_mesh(
+    doc: &Document,
+    graph: &SceneGraph,
+    geometry: GeometryIndex,
+    options: &ExtractOptions,
+) -> Result<ExtractedMesh, Error> {
+    let id = graph.geometry(geometry);
+    let mesh = match id.to_object_handle(doc).map(|obj| obj.get_typed()) {
+        Some(TypedObjectHandle::Geometry(TypedGeometryHandle::Mesh(o))) => o,
+        Some(_) => bail!("Geometry is not a mesh: {:?}", id),
+        None => bail!("Geometry not found: {:?}", id),
+    };
+    let pvs = mesh.polygon_vertices()?;
+    let tris = pvs.triangulate_each(triangulate)?;
+    let resolved = ResolvedLayerElements::<f32>::from_mesh(&mesh)?;
+    let skin = extract_skin(graph, &mesh, options.max_influences)?;
+    let has_normals = resolved.normals().is_some();
+    let has_uvs = resolved.uvs().is_some();
+
+    let mut positions = Vec::new();
+    let mut positions_f64 = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut joints = Vec::new();
+    let mut weights = Vec::new();
+    let mut indices = Vec::new();
+    let mut welded: HashMap<(u32, [u32; 5]), u32> = HashMap::new();
+    let mut pv_indices: HashMap<PolygonVertexIndex, u32> = HashMap::new();
+    for tri_vi in tris.triangle_vertex_indices() {
+        let cpi = tris
+            .control_point_index(tri_vi)
+            .ok_or_else(|| format_err!("Control point index not found for {:?}", tri_vi))?
+            .to_u32();
+        let position = tris
+            .control_point(tri_vi)
+            .ok_or_else(|| format_err!("Control point not found: index={}", cpi))?;
+        let pvi = tris
+            .polygon_vertex_index(tri_vi)
+            .ok_or_else(|| format_err!("Polygon vertex index not found for {:?}", tri_vi))?;
+        let n = resolved.normal(pvi).map_or([0.0; 3], |n| [n.x, n.y, n.z]);
+        let uv = resolved.uv(pvi).map_or([0.0; 2], |uv| [uv.x, uv.y]);
+        let key = (
+            cpi,
+            [
+                n[0].to_bits(),
+                n[1].to_bits(),
+                n[2].to_bits(),
+                uv[0].to_bits(),
+                uv[1].to_bits(),
+            ],
+        );
+        let next = positions.len() as u32;
+        let index = *welded.entry(key).or_insert(next);
+        if index == next {
+            positions.push([position.x as f32, position.y as f32, position.z as f32]);
+            if options.precision == Precision::F64 {
+                positions_f64.push(position.into());
+            }
+            normals.push(n);
+            uvs.push(uv);
+            if let Some((_, influences)) = &skin {
+                let (j, w) = strongest_influences_n(
+                    influences.get(cpi as usize).map_or(&[], Vec::as_slice),
+                    options.max_influences,
+                );
+                joints.push(j);
+                weights.push(w);
+            }
+        }
+        indices.push(index);
+        pv_indices.insert(pvi, index);
+    }
+
+    let tangents = if has_normals && has_uvs {
+        Some(tangents(&positions, &normals, &uvs, &indices))
+    } else {
+        None
+    };
+    let polygon_sizes = if options.triangulate {
+        None
+    } else {
+        indices.clear();
+        let mut sizes = Vec::new();
+        let mut poly_start = 0;
+        for (i, &pv) in pvs.raw_polygon_vertices().iter().enumerate() {
+            if !PolygonVertex::new(pv).is_end() {
+                continue;
+            }
+            // Degenerate polygons are not triangulated.
+            if i + 1 - poly_start >= 3 {
+                for pvi in (poly_start..=i).map(PolygonVertexIndex::new) {
+                    let index = pv_indices
+                        .get(&pvi)
+                        .ok_or_else(|| format_err!("Polygon vertex not triangulated: {:?}", pvi))?;
+                    indices.push(*index);
+                }
+                sizes.push((i + 1 - poly_start) as u32);
+            }
+            poly_start = i + 1;
+        }
+        Some(sizes)
+    };
+
+    Ok(ExtractedMesh {
+        geometry,
+        positions,
+        positions_f64: match options.precision {
+            Precision::F32 => None,
+            Precision::F64 => Some(positions_f64),
+        },
+        normals: if has_normals { Some(normals) } else { None },
+        uvs: if has_uvs { Some(uvs) } else { None },
+        tangents,
+        joints: skin.as_ref().map(|_| joints),
+        weights: skin.as_ref().map(|_| weights),
+        indices,
+        polygon_sizes,
+        skin: skin.map(|(skin, _)| skin),
+    })
+}
+
+/// Extracts the skin of the mesh, with the influences (joint index and
+/// weight) for each control point.
+///
+/// Returns `Ok(None)` if the mesh has no skin deformers.
+/// Recoverable problems (such as clusters without bones) are recorded as
+/// warnings in the document.
+fn extract_skin(
+    graph: &SceneGraph,
+    mesh: &MeshHandle<'_>,
+    max_influences: usize,
+) -> Result<Option<(ExtractedSkin, Influences)>, Error> {
+    let doc = mesh.document();
+    let clusters: Vec<_> = mesh.skins().flat_map(|skin| skin.clusters()).collect();
+    if clusters.is_empty() {
+        return Ok(None);
+    }
+
+    let num_control_points = mesh.control_points()?.len();
+    let mut skin = ExtractedSkin {
+        joints: Vec::new(),
+        inverse_bind_matrices: Vec::new(),
+    };
+    let mut influences: Influences = Vec::new();
+    for cluster in clusters {
+        let joint = match cluster
+            .link()
+            .and_then(|model| graph.node_index(model.object_id()))
+        {
+            Some(v) => v,
+            None => {
+                doc.warn(Warning::MissingBone {
+                    cluster: cluster.object_id(),
+                });
+                continue;
+            }
+        };
+        let link = Mat4::from(cluster.transform_link().unwrap_or_else(|e| {
+            doc.warn(Warning::DefaultedValue {
+                object: cluster.object_id(),
+                name: "TransformLink".to_owned(),
+                cause: Some(format!("{:#}", e)),
+            });
+            graph.node(joint).global_transform
+        }));
+        let transform = cluster.transform().map_or_else(
+            |e| {
+                doc.warn(Warning::DefaultedValue {
+                    object: cluster.object_id(),
+                    name: "Transform".to_owned(),
+                    cause: Some(format!("{:#}", e)),
+                });
+                Mat4::IDENTITY
+            },
+            Mat4::from,
+        );
+        let inverse_bind = match link.inverse() {
+            Some(v) => v * transform,
+            None => {
+                doc.warn(Warning::SingularBindMatrix {
+                    cluster: cluster.object_id(),
+                });
+                Mat4::IDENTITY
+            }
+        };
+
+        let joint_index = u16::try_from(skin.joints.len())
+            .map_err(|_| format_err!("Too many joints in the skin of {:?}", mesh.object_id()))?;
+        skin.joints.push(joint);
+        skin.inverse_bind_matrices
+            .push(scalar::matrix4(inverse_bind.into()));
+        for (&cpi, &weight) in cluster.indices()?.iter().zip(cluster.weights()?) {
+            let cpi = match usize::try_from(cpi) {
+                Ok(v) if v < num_control_points => v,
+                _ => bail!(
+                    "Control point index out of range in cluster: index={}, len={}",
+                    cpi,
+                    num_control_points
+                ),
+            };
+            if influences.len() <= cpi {
+                influences.resize_with(cpi + 1, Vec::new);
+            }
+            influences[cpi].push((joint_index, weight as f32));
+        }
+    }
+    if skin.joints.is_empty() {
+        return Ok(None);
+    }
+    let clamped = influences
+        .iter()
+        .filter(|v| v.len() > max_influences)
+        .count();
+    if clamped > 0 {
+        doc.warn(Warning::ClampedInfluences {
+            geometry: mesh.object_id(),
+            control_points: clamped,
+            max_influences,
+        });
+    }
+
+    Ok(Some((skin, influences)))
+}
+
+/// Returns the joints and the normalized weights of the strongest influences.
+#[cfg(feature = "gltf-export")]
+pub(crate) fn strongest_influences(influences: &[(u16, f32)]) -> ([u16; 4], [f32; 4]) {
+    strongest_influences_n(influences, MAX_INFLUENCES)
+}
+
+/// Returns the joints and the normalized weights of at most `max` (up to
+/// four) strongest influences.
+fn strongest_influences_n(influences: &[(u16, f32)], max: usize) -> ([u16; 4], [f32; 4]) {
+    let mut influences = influences.to_vec();
+    influences.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    influences.truncate(max.min(MAX_INFLUENCES));
+
+    let mut joints = [0; 4];
+    let mut weights = [0.0; 4];
+    let total: f32 = influences.iter().map(|v| v.1).sum();
+    if total > 0.0 {
+        for (i, (joint, weight)) in influences.into_iter().enumerate() {
+            joints[i] = joint;
+            weights[i] = weight / total;
+        }
+    }
+
+    (joints, weights)
+}
+
+/// Triangulates the polygon as a fan.
+///
+/// Degenerate polygons with less than three vertices are skipped.
+fn triangulate(
+    _: &PolygonVertices<'_>,
+    poly: &[PolygonVertexIndex],
+    results: &mut Vec<[PolygonVertexIndex; 3]>,
+) -> Result<(), Error> {
+    for i in 2..poly.len() {
+        results.push([poly[0], poly[i - 1], poly[i]]);
+    }
+
+    Ok(())
+}
+
+/// Generates the vertex tangents from the texture coordinates.
+///
+/// Tangents and bitangents of the triangles are accumulated to the vertices,
+/// and the tangents are orthogonalized against the normals.
+fn tangents(
+    positions: &[[f32; 3]],
+    normals: &[[f32; 3]],
+    uvs: &[[f32; 2]],
+    indices: &[u32],
+) -> Vec<[f32; 4]> {
+    let mut tangents = vec![[0.0; 3]; positions.len()];
+    let mut bitangents = vec![[0.0; 3]; positions.len()];
+    for tri in indices.chunks_exact(3) {
+        let [i0, i1, i2] = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+        let e1 = sub(positions[i1], positions[i0]);
+        let e2 = sub(positions[i2], positions[i0]);
+        let (du1, dv1) = (uvs[i1][0] - uvs[i0][0], uvs[i1][1] - uvs[i0][1]);
+        let (du2, dv2) = (uvs[i2][0] - uvs[i0][0], uvs[i2][1] - uvs[i0][1]);
+        let det = du1 * dv2 - du2 * dv1;
+        if det.abs() <= f32::EPSILON {
+            continue;
+        }
+        let r = 1.0 / det;
+        let t = scale(sub(scale(e1, dv2), scale(e2, dv1)), r);
+        let b = scale(sub(scale(e2, du1), scale(e1, du2)), r);
+        for &i in &[i0, i1, i2] {
+            tangents[i] = add(tangents[i], t);
+            bitangents[i] = add(bitangents[i], b);
+        }
+    }
+
+    normals
+        .iter()
+        .zip(tangents)
+        .zip(bitangents)
+        .map(|((&n, t), b)| {
+            let t = sub(t, scale(n, dot(n, t)));
+            let t = normalize(t).unwrap_or_else(|| perpendicular(n));
+            let w = if dot(cross(n, t), b) < 0.0 { -1.0 } else { 1.0 };
+            [t[0], t[1], t[2], w]
+        })
+        .collect()
+}
+
+/// Returns `a + b`.
+fn add(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+/// Returns `a - b`.
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// Returns `v * s`.
+fn scale(v: [f32; 3], s: f32) -> [f32; 3] {
+    [v[0] * s, v[1] * s, v[2] * s]
+}
+
+/// Returns the dot product.
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Returns the cross product.
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Returns the normalized vector, or `None` if the vector is (nearly) zero.
+fn normalize(v: [f32; 3]) -> Option<[f32; 3]> {
+    let len = dot(v, v).sqrt();
+    if len > f32::EPSILON {
+        Some(scale(v, 1.0 / len))
+    } else {
+        None
+    }
+}
+
+/// Returns a unit vector perpendicular to the given vector.
+fn perpendicular(n: [f32; 3]) -> [f32; 3] {
+    let axis = if n[0].abs() < 0.9 {
+        [1.0, 0.0, 0.0]
+    } else {
+        [0.0, 1.0, 0.0]
+    };
+    normalize(cross(n, axis)).unwrap_or(axis)
+}
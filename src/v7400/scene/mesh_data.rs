@@ -0,0 +1,95 @@
+//! Mesh data for Bevy meshes.
+
+use crate::v7400::scene::ExtractedMesh;
+
+/// Vertex attributes and indices in the layout of Bevy `Mesh`es.
+///
+/// This uses only plain arrays, so it can be inserted into a Bevy `Mesh`
+/// (with `PrimitiveTopology::TriangleList`) without this crate depending on
+/// Bevy:
+///
+/// * `positions`: `Mesh::ATTRIBUTE_POSITION` (`Float32x3`).
+/// * `normals`: `Mesh::ATTRIBUTE_NORMAL` (`Float32x3`).
+/// * `uvs`: `Mesh::ATTRIBUTE_UV_0` (`Float32x2`).
+/// * `tangents`: `Mesh::ATTRIBUTE_TANGENT` (`Float32x4`).
+/// * `joint_indices`: `Mesh::ATTRIBUTE_JOINT_INDEX` (`Uint16x4`).
+/// * `joint_weights`: `Mesh::ATTRIBUTE_JOINT_WEIGHT` (`Float32x4`).
+/// * `indices`: `Indices::U32`.
+///
+/// ```ignore
+/// let data = extracted_mesh.to_mesh_data();
+/// let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+/// mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, data.positions);
+/// if let Some(normals) = data.normals {
+///     mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+/// }
+/// // ...
+/// mesh.set_indices(Some(Indices::U32(data.indices)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeshData {
+    /// Vertex positions.
+    pub positions: Vec<[f32; 3]>,
+    /// Vertex normals.
+    pub normals: Option<Vec<[f32; 3]>>,
+    /// Vertex texture coordinates, with the origin at the top left.
+    pub uvs: Option<Vec<[f32; 2]>>,
+    /// Vertex tangents, with the handedness of the bitangent as `w`.
+    pub tangents: Option<Vec<[f32; 4]>>,
+    /// Vertex joint indices.
+    pub joint_indices: Option<Vec<[u16; 4]>>,
+    /// Vertex joint weights.
+    pub joint_weights: Option<Vec<[f32; 4]>>,
+    /// Triangle vertex indices.
+    pub indices: Vec<u32>,
+}
+
+impl ExtractedMesh {
+    /// Returns the triangle vertex indices.
+    ///
+    /// If the mesh is not triangulated (see [`polygon_sizes`]), the polygons
+    /// are triangulated as fans.
+    ///
+    /// [`polygon_sizes`]: #structfield.polygon_sizes
+    pub fn triangle_indices(&self) -> Vec<u32> {
+        let sizes = match &self.polygon_sizes {
+            Some(v) => v,
+            None => return self.indices.clone(),
+        };
+        let mut triangles = Vec::new();
+        let mut rest = self.indices.as_slice();
+        for &size in sizes {
+            let (poly, next) = rest.split_at((size as usize).min(rest.len()));
+            for i in 2..poly.len() {
+                triangles.extend_from_slice(&[poly[0], poly[i - 1], poly[i]]);
+            }
+            rest = next;
+        }
+        triangles
+    }
+
+    /// Returns the mesh data in the layout of Bevy meshes.
+    ///
+    /// Texture coordinates are flipped vertically, since Bevy has the origin
+    /// of textures at the top left while FBX has it at the bottom left, and
+    /// the handedness of the tangents is negated accordingly.
+    /// The axis system is not converted; Bevy uses Y-up right-handed
+    /// coordinates, which is the FBX default.
+    pub fn to_mesh_data(&self) -> MeshData {
+        MeshData {
+            positions: self.positions.clone(),
+            normals: self.normals.clone(),
+            uvs: self
+                .uvs
+                .as_ref()
+                .map(|v| v.iter().map(|v| [v[0], 1.0 - v[1]]).collect()),
+            tangents: self
+                .tangents
+                .as_ref()
+                .map(|v| v.iter().map(|v| [v[0], v[1], v[2], -v[3]]).collect()),
+            joint_indices: self.joints.clone(),
+            joint_weights: self.weights.clone(),
+            indices: self.triangle_indices(),
+        }
+    }
+}
@@ -0,0 +1,242 @@
+//! Scene extraction options.
+
+use log::warn;
+use mint::Vector3;
+
+use crate::v7400::{
+    available_threads,
+    data::transform::{math::Mat4, AxisSystem},
+    Document,
+};
+
+/// Target axis system of the extracted scene.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TargetAxes {
+    /// Y-up, right-handed, with Z front (Maya, glTF, and OpenGL).
+    YUp,
+    /// Z-up, right-handed, with -Y front (3ds Max and Blender).
+    ZUp,
+}
+
+impl TargetAxes {
+    /// Returns the axis system with the given unit scale.
+    fn axis_system(self, unit_scale: f64) -> AxisSystem {
+        match self {
+            TargetAxes::YUp => AxisSystem::new((1, 1), (2, 1), (0, 1), unit_scale),
+            TargetAxes::ZUp => AxisSystem::new((2, 1), (1, -1), (0, 1), unit_scale),
+        }
+    }
+}
+
+/// Floating point precision of the extracted vertex positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Precision {
+    /// `f32` only.
+    F32,
+    /// `f64` in addition to `f32`.
+    ///
+    /// This is useful for large scenes (such as CAD and GIS data) where
+    /// `f32` positions lose precision.
+    F64,
+}
+
+/// Options of scene, mesh, and animation extraction.
+///
+/// ```
+/// # #[cfg(feature = "edit")]
+/// # fn main() -> Result<(), anyhow::Error> {
+/// use fbxcel_dom::v7400::{
+///     edit::{DocumentBuilder, ModelKind},
+///     scene::{ExtractOptions, Extractor, Precision, TargetAxes},
+/// };
+///
+/// let mut builder = DocumentBuilder::new();
+/// let model = builder.add_model("Quad", ModelKind::Mesh, None);
+/// let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+/// builder.add_mesh("Quad", &positions, &[&[0, 1, 2, 3]], Some(model));
+/// let doc = builder.build()?;
+///
+/// let options = ExtractOptions::new()
+///     .target_axes(Some(TargetAxes::ZUp))
+///     .target_unit_scale(Some(100.0))
+///     .triangulate(false)
+///     .max_influences(2)
+///     .sample_rate(60.0)
+///     .precision(Precision::F64);
+/// let extract = Extractor::with_options(options).extract(&doc)?;
+///
+/// let mesh = &extract.meshes[0];
+/// assert_eq!(mesh.polygon_sizes.as_deref(), Some(&[4][..]));
+/// assert_eq!(mesh.indices.len(), 4);
+/// assert_eq!(mesh.triangle_indices().len(), 6);
+/// assert_eq!(mesh.positions_f64.as_ref().map(Vec::len), Some(4));
+/// // Centimeters to meters, and Y-up to Z-up.
+/// let up = extract.root_transform.y;
+/// assert_eq!([up.x, up.y, up.z], [0.0, 0.0, 0.01]);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "edit"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExtractOptions {
+    /// Number of worker threads.
+    pub(crate) threads: usize,
+    /// Frames per second for baking animations.
+    pub(crate) sample_rate: f64,
+    /// Target axis system.
+    pub(crate) target_axes: Option<TargetAxes>,
+    /// Target unit scale in centimeters per unit.
+    pub(crate) target_unit_scale: Option<f64>,
+    /// Whether to triangulate polygons.
+    pub(crate) triangulate: bool,
+    /// Maximum number of joint influences per vertex.
+    pub(crate) max_influences: usize,
+    /// Precision of vertex positions.
+    pub(crate) precision: Precision,
+}
+
+impl ExtractOptions {
+    /// Creates a new options with the default values.
+    ///
+    /// By default:
+    ///
+    /// * four worker threads (one on `wasm32` targets) are used,
+    /// * animations are sampled at 30 frames per second,
+    /// * the axis system and the unit of the document are kept,
+    /// * polygons are triangulated,
+    /// * up to four joint influences are kept per vertex, and
+    /// * positions are extracted as `f32` only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of worker threads.
+    ///
+    /// The jobs are processed in parallel on a `rayon` thread pool only if the
+    /// `rayon` feature is enabled.
+    /// With 0 or 1, without the `rayon` feature, or on `wasm32` targets, the
+    /// jobs are processed on the calling thread.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = available_threads(threads);
+        self
+    }
+
+    /// Sets the frames per second for baking animations.
+    pub fn sample_rate(mut self, fps: f64) -> Self {
+        self.sample_rate = fps;
+        self
+    }
+
+    /// Sets the target axis system.
+    ///
+    /// The conversion is not applied to the extracted data, but returned as
+    /// [`SceneExtract::root_transform`] to be applied on top of the scene.
+    /// `None` keeps the axis system of the document.
+    ///
+    /// [`SceneExtract::root_transform`]:
+    /// struct.SceneExtract.html#structfield.root_transform
+    pub fn target_axes(mut self, axes: Option<TargetAxes>) -> Self {
+        self.target_axes = axes;
+        self
+    }
+
+    /// Sets the target unit scale in centimeters per unit (same as
+    /// `UnitScaleFactor` of `GlobalSettings`), such as `100.0` for meters.
+    ///
+    /// The conversion is returned as a part of
+    /// [`SceneExtract::root_transform`].
+    /// `None` keeps the unit of the document.
+    ///
+    /// [`SceneExtract::root_transform`]:
+    /// struct.SceneExtract.html#structfield.root_transform
+    pub fn target_unit_scale(mut self, unit_scale: Option<f64>) -> Self {
+        self.target_unit_scale = unit_scale;
+        self
+    }
+
+    /// Sets whether to triangulate polygons.
+    ///
+    /// If disabled, indices of meshes are polygon vertices, and the number
+    /// of vertices of each polygon is returned as
+    /// [`ExtractedMesh::polygon_sizes`].
+    ///
+    /// [`ExtractedMesh::polygon_sizes`]:
+    /// struct.ExtractedMesh.html#structfield.polygon_sizes
+    pub fn triangulate(mut self, triangulate: bool) -> Self {
+        self.triangulate = triangulate;
+        self
+    }
+
+    /// Sets the maximum number of joint influences per vertex, clamped to
+    /// `1..=4`.
+    ///
+    /// The strongest influences are kept and the weights are renormalized.
+    pub fn max_influences(mut self, max: usize) -> Self {
+        self.max_influences = max.clamp(1, 4);
+        self
+    }
+
+    /// Sets the precision of vertex positions.
+    pub fn precision(mut self, precision: Precision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Returns the transform converting the axis system and the unit of the
+    /// document into the targets.
+    pub(crate) fn root_transform(&self, doc: &Document) -> Mat4 {
+        if self.target_axes.is_none() && self.target_unit_scale.is_none() {
+            return Mat4::IDENTITY;
+        }
+        let src = AxisSystem::from_tree(doc.tree());
+        let scale = match self.target_unit_scale {
+            Some(target) if target > 0.0 && src.unit_scale > 0.0 => src.unit_scale / target,
+            Some(target) => {
+                warn!(
+                    "Invalid unit scale: document={}, target={}",
+                    src.unit_scale, target
+                );
+                1.0
+            }
+            None => 1.0,
+        };
+        let rotation = match self.target_axes {
+            Some(target) => match src.matrix() {
+                Some(src_m) => {
+                    let dest_m = target
+                        .axis_system(1.0)
+                        .matrix()
+                        .expect("Should never fail: built-in axis systems are valid");
+                    dest_m * src_m.transpose()
+                }
+                None => {
+                    warn!("Invalid axis system in `GlobalSettings`");
+                    Mat4::IDENTITY
+                }
+            },
+            None => Mat4::IDENTITY,
+        };
+        let m = rotation * Mat4::from_scaling(Vector3::from([scale; 3]));
+        if m.determinant() < 0.0 {
+            warn!("Axis system is converted between left-handed and right-handed with mirroring");
+        }
+
+        m
+    }
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        Self {
+            threads: available_threads(4),
+            sample_rate: 30.0,
+            target_axes: None,
+            target_unit_scale: None,
+            triangulate: true,
+            max_influences: 4,
+            precision: Precision::F32,
+        }
+    }
+}
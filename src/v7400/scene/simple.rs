@@ -0,0 +1,293 @@
+//! One-call scene loading.
+
+use std::{collections::HashMap, fs::File, io::BufReader, path::Path};
+
+use anyhow::{format_err, Error};
+use log::warn;
+
+#[cfg(feature = "animation")]
+use crate::v7400::scene::BakedAnimation;
+use crate::{
+    any::AnyDocument,
+    v7400::{
+        object::{material::MaterialHandle, texture::TextureHandle, ObjectId, TypedObjectHandle},
+        scene::{ExtractOptions, ExtractedMesh, Extractor, NodeIndex, SceneGraph, SceneNodeKind},
+        Document,
+    },
+};
+
+/// Scene with owned mesh buffers, materials, textures, skeleton, and baked
+/// animation clips.
+///
+/// This is for users who want to get something rendered without learning the
+/// handles and connections.
+/// Defaults of [`Extractor`] are used, and materials are simplified to
+/// base color and emissive factors.
+///
+/// ```
+/// # #[cfg(feature = "edit")]
+/// # fn main() -> Result<(), anyhow::Error> {
+/// use fbxcel_dom::{prelude::*, v7400::data::material::ShadingModel};
+///
+/// let mut builder = DocumentBuilder::new();
+/// let model = builder.add_model("Triangle", ModelKind::Mesh, None);
+/// let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+/// builder.add_mesh("Triangle", &positions, &[&[0, 1, 2]], Some(model));
+/// builder.add_material("Red", ShadingModel::Lambert, [1.0, 0.0, 0.0], Some(model))?;
+/// let doc = builder.build()?;
+///
+/// let scene = SimpleScene::from_document(&doc)?;
+/// assert_eq!(scene.meshes.len(), 1);
+/// assert_eq!(scene.meshes[0].mesh.indices, [0, 1, 2]);
+/// let material = &scene.materials[scene.meshes[0].materials[0]];
+/// assert_eq!(material.name, "Red");
+/// assert_eq!(material.base_color, [1.0, 0.0, 0.0, 1.0]);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "edit"))]
+/// # fn main() {}
+/// ```
+///
+/// [`Extractor`]: struct.Extractor.html
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct SimpleScene {
+    /// Scene graph.
+    pub graph: SceneGraph,
+    /// Meshes.
+    pub meshes: Vec<SimpleMesh>,
+    /// Materials.
+    pub materials: Vec<SimpleMaterial>,
+    /// Textures.
+    pub textures: Vec<SimpleTexture>,
+    /// Skeleton bone nodes, in the order of the scene graph.
+    pub skeleton: Vec<NodeIndex>,
+    /// Baked animation clips.
+    #[cfg(feature = "animation")]
+    pub clips: Vec<BakedAnimation>,
+}
+
+impl SimpleScene {
+    /// Loads the scene from the FBX file at the given path.
+    ///
+    /// ```no_run
+    /// use fbxcel_dom::prelude::*;
+    ///
+    /// let scene = SimpleScene::load("sample.fbx")?;
+    /// for mesh in &scene.meshes {
+    ///     println!("{} triangles", mesh.mesh.indices.len() / 3);
+    /// }
+    /// # Ok::<_, anyhow::Error>(())
+    /// ```
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .map_err(|e| format_err!("Failed to open {}: {}", path.display(), e))?;
+        let doc = match AnyDocument::from_seekable_reader(BufReader::new(file))? {
+            AnyDocument::V7400(_, doc) => doc,
+            #[cfg(feature = "v6100")]
+            AnyDocument::V6100(_, doc) => doc,
+        };
+        Self::from_document(&doc)
+    }
+
+    /// Creates the scene from the document.
+    pub fn from_document(doc: &Document) -> Result<Self, Error> {
+        Self::from_document_with(doc, ExtractOptions::new())
+    }
+
+    /// Creates the scene from the document with the given extraction
+    /// options.
+    pub fn from_document_with(doc: &Document, options: ExtractOptions) -> Result<Self, Error> {
+        let extract = Extractor::with_options(options).extract(doc)?;
+        let graph = extract.graph;
+
+        let mut textures = Vec::new();
+        let mut texture_indices = HashMap::new();
+        let materials = graph
+            .materials()
+            .iter()
+            .map(|&id| simple_material(doc, id, &mut textures, &mut texture_indices))
+            .collect();
+
+        let meshes = extract
+            .meshes
+            .into_iter()
+            .map(|mesh| {
+                let nodes: Vec<_> = graph
+                    .nodes()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, node)| node.geometry == Some(mesh.geometry))
+                    .map(|(i, _)| NodeIndex(i))
+                    .collect();
+                let materials = nodes
+                    .first()
+                    .map(|&node| {
+                        graph
+                            .node(node)
+                            .materials
+                            .iter()
+                            .map(|index| index.to_usize())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                SimpleMesh {
+                    mesh,
+                    nodes,
+                    materials,
+                }
+            })
+            .collect();
+
+        let skeleton = graph
+            .nodes()
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.kind == SceneNodeKind::LimbNode)
+            .map(|(i, _)| NodeIndex(i))
+            .collect();
+
+        Ok(Self {
+            graph,
+            meshes,
+            materials,
+            textures,
+            skeleton,
+            #[cfg(feature = "animation")]
+            clips: extract.animations,
+        })
+    }
+}
+
+/// Mesh of a simple scene.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct SimpleMesh {
+    /// Triangulated mesh buffers.
+    pub mesh: ExtractedMesh,
+    /// Nodes using the mesh.
+    pub nodes: Vec<NodeIndex>,
+    /// Indices into [`SimpleScene::materials`] of the materials of the first
+    /// node.
+    ///
+    /// [`SimpleScene::materials`]: struct.SimpleScene.html#structfield.materials
+    pub materials: Vec<usize>,
+}
+
+/// Material of a simple scene.
+///
+/// Lambert and Phong parameters are converted to factors in `[0, 1]`, as the
+/// glTF exporter does.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct SimpleMaterial {
+    /// Object ID of the material.
+    pub object_id: ObjectId,
+    /// Name.
+    pub name: String,
+    /// Base color (diffuse color multiplied by diffuse factor), with alpha
+    /// (`1 - transparency factor`).
+    pub base_color: [f32; 4],
+    /// Emissive color multiplied by emissive factor.
+    pub emissive: [f32; 3],
+    /// Index into [`SimpleScene::textures`] of the base color texture.
+    ///
+    /// [`SimpleScene::textures`]: struct.SimpleScene.html#structfield.textures
+    pub base_color_texture: Option<usize>,
+}
+
+/// Texture of a simple scene.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct SimpleTexture {
+    /// Object ID of the texture.
+    pub object_id: ObjectId,
+    /// Name.
+    pub name: String,
+    /// Relative file name of the image, if available.
+    pub filename: Option<String>,
+    /// Embedded image content, if available.
+    pub content: Option<Vec<u8>>,
+}
+
+/// Returns the simple material of the given object.
+fn simple_material(
+    doc: &Document,
+    id: ObjectId,
+    textures: &mut Vec<SimpleTexture>,
+    texture_indices: &mut HashMap<ObjectId, usize>,
+) -> SimpleMaterial {
+    let mut simple = SimpleMaterial {
+        object_id: id,
+        name: String::new(),
+        base_color: [0.8, 0.8, 0.8, 1.0],
+        emissive: [0.0; 3],
+        base_color_texture: None,
+    };
+    let material: MaterialHandle<'_> = match id.to_object_handle(doc).map(|obj| obj.get_typed()) {
+        Some(TypedObjectHandle::Material(o)) => o,
+        _ => {
+            warn!("Material object not found: object_id={:?}", id);
+            return simple;
+        }
+    };
+    let props = material.properties();
+    let diffuse = props
+        .diffuse_color_or_default()
+        .unwrap_or_else(|_| rgb::RGB::from([0.8; 3]));
+    let diffuse_factor = props.diffuse_factor_or_default().unwrap_or(1.0);
+    let alpha = 1.0 - props.transparency_factor_or_default().unwrap_or(0.0);
+    let emissive = props
+        .emissive_color_or_default()
+        .unwrap_or_else(|_| rgb::RGB::from([0.0; 3]));
+    let emissive_factor = props.emissive_factor_or_default().unwrap_or(1.0);
+
+    simple.name = material.name().unwrap_or_default().to_owned();
+    simple.base_color = [
+        clamp01(diffuse.r * diffuse_factor),
+        clamp01(diffuse.g * diffuse_factor),
+        clamp01(diffuse.b * diffuse_factor),
+        clamp01(alpha),
+    ];
+    simple.emissive = [
+        clamp01(emissive.r * emissive_factor),
+        clamp01(emissive.g * emissive_factor),
+        clamp01(emissive.b * emissive_factor),
+    ];
+    simple.base_color_texture = material.diffuse_texture().map(|texture| {
+        *texture_indices
+            .entry(texture.object_id())
+            .or_insert_with(|| {
+                textures.push(simple_texture(&texture));
+                textures.len() - 1
+            })
+    });
+    simple
+}
+
+/// Returns the simple texture of the given texture.
+fn simple_texture(texture: &TextureHandle<'_>) -> SimpleTexture {
+    let clip = texture.video_clip();
+    SimpleTexture {
+        object_id: texture.object_id(),
+        name: texture.name().unwrap_or_default().to_owned(),
+        filename: clip
+            .and_then(|clip| clip.relative_filename().ok())
+            .filter(|v| !v.is_empty())
+            .map(|v| v.replace('\\', "/")),
+        content: clip
+            .and_then(|clip| clip.content())
+            .filter(|v| !v.is_empty())
+            .map(ToOwned::to_owned),
+    }
+}
+
+/// Clamps the value into `[0, 1]` and converts it to `f32`.
+fn clamp01(v: f64) -> f32 {
+    v.clamp(0.0, 1.0) as f32
+}
@@ -0,0 +1,710 @@
+//! Scene data organized for USD.
+//!
+//! [`Stage::from_document()`] extracts the scene as plain structs following
+//! the conventions of USD (Universal Scene Description), so that a thin
+//! writer (such as one using `usd-rs` or a `.usda` text serializer) can be
+//! written on top of it:
+//!
+//! * each model becomes an `Xform` prim at a unique prim path, with the FBX
+//!   local transform decomposed into `xformOp`s and `xformOpOrder`,
+//! * each mesh becomes a `Mesh` prim (named `<model name>Shape`) under its
+//!   model prim, with the attributes of the `UsdGeomMesh` schema and
+//!   `GeomSubset`s for multiple materials,
+//! * each material becomes a `Material` prim under `/Materials` with the
+//!   inputs of `UsdPreviewSurface`, bound by `material:binding`
+//!   relationships.
+//!
+//! Values are in the units and axis system of the document, which are
+//! described by [`Stage::up_axis`] and [`Stage::meters_per_unit`].
+//! Cameras, lights, and skeletons are written as plain `Xform` prims.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, format_err, Error};
+use log::warn;
+use mint::{Point2, Point3, Vector3};
+
+use crate::v7400::{
+    data::{
+        material::pbr::roughness_from_shininess,
+        mesh::{
+            layer::TypedLayerElementHandle, PolygonIndex, PolygonVertex, PolygonVertexIndex,
+            ResolvedLayerElements,
+        },
+        transform::{math::Mat4, AxisSystem, LocalTransform, RotationOrder},
+    },
+    object::{geometry::TypedGeometryHandle, ObjectId, TypedObjectHandle},
+    scene::{NodeIndex, SceneGraph, SceneNode},
+    Document,
+};
+
+/// Path of the scope prim containing the materials.
+pub const MATERIALS_SCOPE: &str = "/Materials";
+
+/// Up axis of a stage (`upAxis` metadata).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum UpAxis {
+    /// Y axis.
+    Y,
+    /// Z axis.
+    Z,
+}
+
+impl UpAxis {
+    /// Returns the `upAxis` token.
+    pub fn token(self) -> &'static str {
+        match self {
+            UpAxis::Y => "Y",
+            UpAxis::Z => "Z",
+        }
+    }
+}
+
+/// Scene data organized as a USD stage.
+///
+/// See the [module-level documentation](index.html).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stage {
+    /// Up axis (`upAxis` metadata).
+    pub up_axis: UpAxis,
+    /// Meters per unit (`metersPerUnit` metadata).
+    pub meters_per_unit: f64,
+    /// Default prim (`defaultPrim` metadata), which is the first root prim.
+    pub default_prim: Option<String>,
+    /// Prims, with parents before their children.
+    pub prims: Vec<Prim>,
+    /// Materials, in the order of the scene graph materials.
+    pub materials: Vec<Material>,
+}
+
+impl Stage {
+    /// Extracts the stage from the given document.
+    ///
+    /// Meshes which fail to be extracted are skipped with warnings.
+    pub fn from_document(doc: &Document) -> Result<Self, Error> {
+        let graph = SceneGraph::from_document(doc)?;
+        let axes = AxisSystem::from_tree(doc.tree());
+        let up_axis = match axes.up_axis() {
+            (1, 1) => UpAxis::Y,
+            (2, 1) => UpAxis::Z,
+            up => {
+                warn!("Unsupported up axis {:?}, using Y axis", up);
+                UpAxis::Y
+            }
+        };
+        // `UnitScaleFactor` is centimeters per unit.
+        let meters_per_unit = if axes.unit_scale > 0.0 {
+            axes.unit_scale / 100.0
+        } else {
+            0.01
+        };
+
+        let materials = extract_materials(doc, &graph);
+
+        // The materials scope is a root prim.
+        let mut names: HashMap<Option<NodeIndex>, HashSet<String>> = HashMap::new();
+        names
+            .entry(None)
+            .or_default()
+            .insert(MATERIALS_SCOPE[1..].to_owned());
+        let mut paths = vec![String::new(); graph.nodes().len()];
+        let mut prims = Vec::new();
+        for (i, node) in graph.nodes().iter().enumerate() {
+            let index = NodeIndex(i);
+            let parent_path = node.parent.map_or("", |parent| &paths[parent.0]);
+            let siblings = names.entry(node.parent).or_default();
+            let path = format!("{}/{}", parent_path, unique_name(siblings, &node.name));
+            prims.push(Prim {
+                path: path.clone(),
+                node: index,
+                schema: PrimSchema::Xform(Xform::from_local_transform(&node.local_transform)),
+            });
+
+            if node.geometry.is_some() {
+                let children = names.entry(Some(index)).or_default();
+                let name = unique_name(children, &format!("{}Shape", node.name));
+                match extract_mesh(doc, &graph, node, &materials) {
+                    Ok(mesh) => prims.push(Prim {
+                        path: format!("{}/{}", path, name),
+                        node: index,
+                        schema: PrimSchema::Mesh(mesh),
+                    }),
+                    Err(e) => warn!("Failed to extract mesh {:?}: {:#}", node.name, e),
+                }
+            }
+            paths[i] = path;
+        }
+
+        let default_prim = graph
+            .roots()
+            .first()
+            .map(|root| paths[root.0][1..].to_owned());
+
+        Ok(Self {
+            up_axis,
+            meters_per_unit,
+            default_prim,
+            prims,
+            materials,
+        })
+    }
+}
+
+/// Prim.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Prim {
+    /// Absolute prim path.
+    pub path: String,
+    /// Scene graph node the prim is created for.
+    pub node: NodeIndex,
+    /// Schema and attributes.
+    pub schema: PrimSchema,
+}
+
+impl Prim {
+    /// Returns the prim type name.
+    pub fn type_name(&self) -> &'static str {
+        self.schema.type_name()
+    }
+}
+
+/// Schema and attributes of a prim.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum PrimSchema {
+    /// `Xform`.
+    Xform(Xform),
+    /// `Mesh`.
+    Mesh(Mesh),
+}
+
+impl PrimSchema {
+    /// Returns the prim type name.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            PrimSchema::Xform(_) => "Xform",
+            PrimSchema::Mesh(_) => "Mesh",
+        }
+    }
+}
+
+/// Transform of an `Xform` prim.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Xform {
+    /// Transform operations (`xformOp:*` attributes).
+    ///
+    /// Operations which have no effect are omitted.
+    pub ops: Vec<XformOp>,
+    /// Order of the operations (`xformOpOrder`).
+    ///
+    /// Entries are the operation names, prefixed with `!invert!` for the
+    /// inverse operations.
+    pub op_order: Vec<String>,
+}
+
+impl Xform {
+    /// Creates the transform operations from the FBX local transform.
+    ///
+    /// This is `T * Roff * Rp * Rpre * R * Rpost^-1 * Rp^-1 * Soff * Sp * S *
+    /// Sp^-1`, as [`LocalTransform::to_matrix()`].
+    pub fn from_local_transform(local: &LocalTransform) -> Self {
+        let mut xform = Self::default();
+        xform.push(XformOpKind::Translate, None, local.translation, false);
+        xform.push(
+            XformOpKind::Translate,
+            Some("rotationOffset"),
+            local.rotation_offset,
+            false,
+        );
+        xform.push(
+            XformOpKind::Translate,
+            Some("rotationPivot"),
+            local.rotation_pivot,
+            false,
+        );
+        let order = if local.rotation_active {
+            xform.push(
+                XformOpKind::RotateXyz,
+                Some("preRotation"),
+                local.pre_rotation,
+                false,
+            );
+            local.rotation_order
+        } else {
+            RotationOrder::EulerXyz
+        };
+        xform.push(XformOpKind::rotate(order), None, local.rotation, false);
+        if local.rotation_active {
+            xform.push(
+                XformOpKind::RotateXyz,
+                Some("postRotation"),
+                local.post_rotation,
+                true,
+            );
+        }
+        xform.push_inverse("rotationPivot");
+        xform.push(
+            XformOpKind::Translate,
+            Some("scalingOffset"),
+            local.scaling_offset,
+            false,
+        );
+        xform.push(
+            XformOpKind::Translate,
+            Some("scalingPivot"),
+            local.scaling_pivot,
+            false,
+        );
+        if local.scaling != Vector3::from([1.0; 3]) {
+            xform.push(XformOpKind::Scale, None, local.scaling, false);
+        }
+        xform.push_inverse("scalingPivot");
+
+        xform
+    }
+
+    /// Adds the operation if the value is not zero.
+    fn push(
+        &mut self,
+        kind: XformOpKind,
+        suffix: Option<&str>,
+        value: Vector3<f64>,
+        inverse: bool,
+    ) {
+        if kind != XformOpKind::Scale && value == Vector3::from([0.0; 3]) {
+            return;
+        }
+        let op = XformOp {
+            kind,
+            suffix: suffix.map(ToOwned::to_owned),
+            value,
+        };
+        let name = op.name();
+        self.op_order.push(if inverse {
+            format!("!invert!{}", name)
+        } else {
+            name
+        });
+        self.ops.push(op);
+    }
+
+    /// Adds the inverse of the translation with the given suffix, if it
+    /// exists.
+    fn push_inverse(&mut self, suffix: &str) {
+        let op = self
+            .ops
+            .iter()
+            .find(|op| op.kind == XformOpKind::Translate && op.suffix.as_deref() == Some(suffix));
+        if let Some(op) = op {
+            self.op_order.push(format!("!invert!{}", op.name()));
+        }
+    }
+}
+
+/// Transform operation of an `Xform` prim.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct XformOp {
+    /// Operation type.
+    pub kind: XformOpKind,
+    /// Operation name suffix (such as `rotationPivot`).
+    pub suffix: Option<String>,
+    /// Value (`double3`), with rotations in degrees.
+    pub value: Vector3<f64>,
+}
+
+impl XformOp {
+    /// Returns the operation name (such as `xformOp:translate:rotationPivot`).
+    pub fn name(&self) -> String {
+        match &self.suffix {
+            Some(suffix) => format!("xformOp:{}:{}", self.kind.token(), suffix),
+            None => format!("xformOp:{}", self.kind.token()),
+        }
+    }
+}
+
+/// Type of a transform operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum XformOpKind {
+    /// `translate`.
+    Translate,
+    /// `rotateXYZ`.
+    RotateXyz,
+    /// `rotateXZY`.
+    RotateXzy,
+    /// `rotateYXZ`.
+    RotateYxz,
+    /// `rotateYZX`.
+    RotateYzx,
+    /// `rotateZXY`.
+    RotateZxy,
+    /// `rotateZYX`.
+    RotateZyx,
+    /// `scale`.
+    Scale,
+}
+
+impl XformOpKind {
+    /// Returns the rotation operation type for the rotation order.
+    ///
+    /// Both FBX and USD name the Euler rotations in the order of
+    /// application, so the names match.
+    /// Spheric XYZ order is treated as Euler XYZ.
+    fn rotate(order: RotationOrder) -> Self {
+        match order {
+            RotationOrder::EulerXyz | RotationOrder::SphericXyz => XformOpKind::RotateXyz,
+            RotationOrder::EulerXzy => XformOpKind::RotateXzy,
+            RotationOrder::EulerYxz => XformOpKind::RotateYxz,
+            RotationOrder::EulerYzx => XformOpKind::RotateYzx,
+            RotationOrder::EulerZxy => XformOpKind::RotateZxy,
+            RotationOrder::EulerZyx => XformOpKind::RotateZyx,
+        }
+    }
+
+    /// Returns the operation type token.
+    pub fn token(self) -> &'static str {
+        match self {
+            XformOpKind::Translate => "translate",
+            XformOpKind::RotateXyz => "rotateXYZ",
+            XformOpKind::RotateXzy => "rotateXZY",
+            XformOpKind::RotateYxz => "rotateYXZ",
+            XformOpKind::RotateYzx => "rotateYZX",
+            XformOpKind::RotateZxy => "rotateZXY",
+            XformOpKind::RotateZyx => "rotateZYX",
+            XformOpKind::Scale => "scale",
+        }
+    }
+}
+
+/// Attributes of a `Mesh` prim.
+///
+/// Polygons are kept as they are, so writers should author
+/// `subdivisionScheme = "none"` (the USD default is Catmull-Clark).
+/// The geometric transform of the model is applied to the points and
+/// normals.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mesh {
+    /// Points (`points`).
+    pub points: Vec<Point3<f32>>,
+    /// Number of vertices of each face (`faceVertexCounts`).
+    pub face_vertex_counts: Vec<i32>,
+    /// Point indices of the face vertices (`faceVertexIndices`).
+    pub face_vertex_indices: Vec<i32>,
+    /// Normals with `faceVarying` interpolation (`normals`).
+    pub normals: Option<Vec<Vector3<f32>>>,
+    /// Texture coordinates of the first UV set with `faceVarying`
+    /// interpolation (`primvars:st`).
+    pub st: Option<Vec<Point2<f32>>>,
+    /// Bounding box (`extent`).
+    pub extent: [Point3<f32>; 2],
+    /// Path of the material bound to the whole mesh (`material:binding`).
+    pub material_binding: Option<String>,
+    /// Face subsets bound to different materials.
+    ///
+    /// This is empty if all faces use the same material.
+    pub subsets: Vec<GeomSubset>,
+}
+
+/// Face subset of a mesh, bound to a material.
+///
+/// This is a `GeomSubset` child prim of the mesh with `elementType = "face"`
+/// and `familyName = "materialBind"`.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeomSubset {
+    /// Prim name.
+    pub name: String,
+    /// Face indices (`indices`).
+    pub indices: Vec<i32>,
+    /// Path of the bound material (`material:binding`).
+    pub material_binding: String,
+}
+
+/// `Material` prim with a `UsdPreviewSurface` shader.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Material {
+    /// Absolute prim path.
+    pub path: String,
+    /// Object ID of the material.
+    pub object_id: ObjectId,
+    /// Diffuse color (`inputs:diffuseColor`).
+    pub diffuse_color: [f32; 3],
+    /// Emissive color (`inputs:emissiveColor`).
+    pub emissive_color: [f32; 3],
+    /// Specular color (`inputs:specularColor`), to be used with
+    /// `inputs:useSpecularWorkflow = 1`.
+    pub specular_color: [f32; 3],
+    /// Roughness (`inputs:roughness`), converted from the shininess.
+    pub roughness: f32,
+    /// Opacity (`inputs:opacity`).
+    pub opacity: f32,
+    /// File path of the diffuse texture, for a `UsdUVTexture` shader reading
+    /// `primvars:st`.
+    pub diffuse_texture: Option<String>,
+}
+
+/// Extracts the materials of the scene graph.
+fn extract_materials(doc: &Document, graph: &SceneGraph) -> Vec<Material> {
+    let mut names = HashSet::new();
+    graph
+        .materials()
+        .iter()
+        .map(|&id| {
+            let material = match id.to_object_handle(doc).map(|obj| obj.get_typed()) {
+                Some(TypedObjectHandle::Material(o)) => Some(o),
+                _ => {
+                    warn!("Material object not found: {:?}", id);
+                    None
+                }
+            };
+            let name = material.and_then(|o| o.name()).unwrap_or("Material");
+            let path = format!("{}/{}", MATERIALS_SCOPE, unique_name(&mut names, name));
+            let props = material.map(|o| o.properties());
+            let color = |c: Option<rgb::RGB<f64>>, factor: Option<f64>| {
+                let factor = factor.unwrap_or(1.0);
+                c.map_or([0.0; 3], |c| {
+                    [
+                        (c.r * factor) as f32,
+                        (c.g * factor) as f32,
+                        (c.b * factor) as f32,
+                    ]
+                })
+            };
+            let (diffuse_color, emissive_color, specular_color, shininess, transparency) =
+                match &props {
+                    Some(props) => (
+                        color(
+                            props.diffuse_color_or_default().ok(),
+                            props.diffuse_factor_or_default().ok(),
+                        ),
+                        color(
+                            props.emissive_color_or_default().ok(),
+                            props.emissive_factor_or_default().ok(),
+                        ),
+                        color(
+                            props.specular_or_default().ok(),
+                            props.specular_factor_or_default().ok(),
+                        ),
+                        props.shininess_or_default().unwrap_or(20.0),
+                        props.transparency_factor_or_default().unwrap_or(0.0),
+                    ),
+                    None => ([0.8; 3], [0.0; 3], [0.0; 3], 20.0, 0.0),
+                };
+            let diffuse_texture = material
+                .and_then(|o| o.diffuse_texture())
+                .and_then(|texture| texture.video_clip())
+                .and_then(|clip| clip.relative_filename().ok())
+                .filter(|v| !v.is_empty())
+                .map(|v| v.replace('\\', "/"));
+
+            Material {
+                path,
+                object_id: id,
+                diffuse_color,
+                emissive_color,
+                specular_color,
+                roughness: roughness_from_shininess(shininess) as f32,
+                opacity: (1.0 - transparency).clamp(0.0, 1.0) as f32,
+                diffuse_texture,
+            }
+        })
+        .collect()
+}
+
+/// Extracts the mesh of the given mesh node.
+fn extract_mesh(
+    doc: &Document,
+    graph: &SceneGraph,
+    node: &SceneNode,
+    materials: &[Material],
+) -> Result<Mesh, Error> {
+    let id = graph.geometry(
+        node.geometry
+            .expect("Should never fail: node has a geometry"),
+    );
+    let mesh = match id.to_object_handle(doc).map(|obj| obj.get_typed()) {
+        Some(TypedObjectHandle::Geometry(TypedGeometryHandle::Mesh(o))) => o,
+        Some(_) => bail!("Geometry is not a mesh: {:?}", id),
+        None => bail!("Geometry not found: {:?}", id),
+    };
+    let pvs = mesh.polygon_vertices()?;
+    let resolved = ResolvedLayerElements::<f32>::from_mesh(&mesh)?;
+    let material_layer = mesh
+        .layers()
+        .flat_map(|layer| layer.layer_element_entries())
+        .find_map(|entry| match entry.typed_layer_element() {
+            Ok(TypedLayerElementHandle::Material(h)) => Some(h),
+            _ => None,
+        })
+        .map(|h| h.materials())
+        .transpose()?;
+
+    let transform = Mat4::from(node.geometric_transform);
+    let has_transform = transform != Mat4::IDENTITY;
+    let normal_matrix = transform
+        .inverse()
+        .ok_or_else(|| format_err!("Geometric transform is not invertible"))?
+        .transpose()
+        .without_translation();
+
+    let points: Vec<Point3<f32>> = pvs
+        .raw_control_points()?
+        .map(|p| {
+            let p = if has_transform {
+                transform.transform_point(Vector3::from([p.x, p.y, p.z]))
+            } else {
+                Vector3::from([p.x, p.y, p.z])
+            };
+            Point3::from([p.x as f32, p.y as f32, p.z as f32])
+        })
+        .collect();
+    let mut extent = [Point3::from([0.0; 3]); 2];
+    if let Some(first) = points.first() {
+        extent = [*first; 2];
+        for p in &points[1..] {
+            extent[0] = Point3::from([
+                extent[0].x.min(p.x),
+                extent[0].y.min(p.y),
+                extent[0].z.min(p.z),
+            ]);
+            extent[1] = Point3::from([
+                extent[1].x.max(p.x),
+                extent[1].y.max(p.y),
+                extent[1].z.max(p.z),
+            ]);
+        }
+    }
+
+    let mut face_vertex_counts = Vec::new();
+    let mut face_vertex_indices = Vec::new();
+    // Faces by scene graph material index.
+    let mut faces_by_material: Vec<(Option<usize>, Vec<i32>)> = Vec::new();
+    let mut count = 0;
+    let mut poly_start = 0;
+    for (i, &raw) in pvs.raw_polygon_vertices().iter().enumerate() {
+        let pv = PolygonVertex::new(raw);
+        let cpi = pv.to_u32() as usize;
+        if cpi >= points.len() {
+            bail!("Control point index out of range: {}", cpi);
+        }
+        face_vertex_indices.push(cpi as i32);
+        count += 1;
+        if pv.is_end() {
+            let poly = PolygonIndex::new(face_vertex_counts.len());
+            let material = match &material_layer {
+                Some(layer) => {
+                    let pvi = PolygonVertexIndex::new(poly_start);
+                    let index = layer.material_index_by_polygon_vertex(&pvs, pvi, poly)?;
+                    node.materials.get(index.to_u32() as usize)
+                }
+                None => node.materials.first(),
+            }
+            .map(|m| m.to_usize());
+            match faces_by_material.iter_mut().find(|(m, _)| *m == material) {
+                Some((_, faces)) => faces.push(poly.to_usize() as i32),
+                None => faces_by_material.push((material, vec![poly.to_usize() as i32])),
+            }
+            face_vertex_counts.push(count);
+            count = 0;
+            poly_start = i + 1;
+        }
+    }
+    if count != 0 {
+        warn!(
+            "Skipping the last polygon without the end marker in {:?}",
+            node.name
+        );
+        face_vertex_indices.truncate(poly_start);
+    }
+    let len = face_vertex_indices.len();
+
+    let normals = resolved.normals().map(|normals| {
+        normals[..len]
+            .iter()
+            .map(|n| {
+                if !has_transform {
+                    return *n;
+                }
+                let n = normal_matrix.transform_point(Vector3::from([
+                    f64::from(n.x),
+                    f64::from(n.y),
+                    f64::from(n.z),
+                ]));
+                let norm = (n.x * n.x + n.y * n.y + n.z * n.z).sqrt();
+                let norm = if norm > 0.0 { norm } else { 1.0 };
+                Vector3::from([
+                    (n.x / norm) as f32,
+                    (n.y / norm) as f32,
+                    (n.z / norm) as f32,
+                ])
+            })
+            .collect()
+    });
+    let st = resolved.uvs().map(|uvs| uvs[..len].to_vec());
+
+    let binding = |material: Option<usize>| {
+        material
+            .and_then(|m| materials.get(m))
+            .map(|m| m.path.clone())
+    };
+    let (material_binding, subsets) = match faces_by_material.as_slice() {
+        [] => (None, Vec::new()),
+        [(material, _)] => (binding(*material), Vec::new()),
+        _ => {
+            let mut names = HashSet::new();
+            let subsets = faces_by_material
+                .into_iter()
+                .filter_map(|(material, indices)| {
+                    let material = material.and_then(|m| materials.get(m))?;
+                    let name = material.path.rsplit('/').next().unwrap_or_default();
+                    Some(GeomSubset {
+                        name: unique_name(&mut names, name),
+                        indices,
+                        material_binding: material.path.clone(),
+                    })
+                })
+                .collect();
+            (None, subsets)
+        }
+    };
+
+    Ok(Mesh {
+        points,
+        face_vertex_counts,
+        face_vertex_indices,
+        normals,
+        st,
+        extent,
+        material_binding,
+        subsets,
+    })
+}
+
+/// Returns the valid prim name for the given name, unique in the given
+/// names.
+///
+/// Characters other than ASCII alphanumerics and underscores are replaced by
+/// underscores, and a numeric suffix is added to duplicate names.
+fn unique_name(names: &mut HashSet<String>, name: &str) -> String {
+    let mut base: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !base.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        base.insert(0, '_');
+    }
+    let mut name = base.clone();
+    let mut suffix = 1;
+    while !names.insert(name.clone()) {
+        name = format!("{}_{}", base, suffix);
+        suffix += 1;
+    }
+    name
+}
@@ -0,0 +1,166 @@
+//! Interned strings.
+//!
+//! Object names, classes and subclasses, connection labels, and property
+//! names and type names are interned into a string table of the document on
+//! loading.
+//! A [`Symbol`] identifies an interned string, and is cheap to copy, compare
+//! and hash, so it can be used instead of the string to compare many names.
+//!
+//! Symbols are only meaningful for the document which created them.
+//!
+//! Symbols are public with the `symbol` feature, which interns the strings
+//! with the `string-interner` crate.
+//!
+//! ```
+//! # use fbxcel_dom::v7400::Document;
+//! # #[cfg(feature = "symbol")]
+//! # fn find_meshes(doc: &Document) {
+//! if let Some(mesh) = doc.symbol("Mesh") {
+//!     let count = doc
+//!         .objects()
+//!         .filter(|obj| obj.subclass_symbol() == mesh)
+//!         .count();
+//!     println!("{} meshes", count);
+//! }
+//! # }
+//! ```
+
+#[cfg(not(feature = "symbol"))]
+use std::{collections::HashMap, convert::TryFrom, sync::Arc};
+
+#[cfg(feature = "symbol")]
+use string_interner::{symbol::SymbolU32, DefaultBackend, StringInterner};
+
+/// Underlying representation of symbols.
+#[cfg(feature = "symbol")]
+type RawSymbol = SymbolU32;
+/// Underlying representation of symbols.
+#[cfg(not(feature = "symbol"))]
+type RawSymbol = u32;
+
+/// Symbol of a string interned in a document.
+// This is an opaque-typedef pattern.
+// `string_interner::Sym` has efficient implementation, so use it internally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(RawSymbol);
+
+#[cfg(feature = "symbol")]
+impl string_interner::Symbol for Symbol {
+    fn try_from_usize(v: usize) -> Option<Self> {
+        SymbolU32::try_from_usize(v).map(Self)
+    }
+
+    fn to_usize(self) -> usize {
+        self.0.to_usize()
+    }
+}
+
+/// Document-level string table.
+#[derive(Debug, Clone)]
+pub(crate) struct SymbolTable {
+    /// Interned strings.
+    #[cfg(feature = "symbol")]
+    strings: StringInterner<DefaultBackend<Symbol>>,
+    /// Interned strings, indexed by the symbols.
+    #[cfg(not(feature = "symbol"))]
+    strings: Vec<Arc<str>>,
+    /// Symbols of the interned strings.
+    #[cfg(not(feature = "symbol"))]
+    symbols: HashMap<Arc<str>, Symbol>,
+}
+
+#[cfg(feature = "symbol")]
+impl SymbolTable {
+    /// Interns the string and returns the symbol.
+    pub(crate) fn intern(&mut self, s: &str) -> Symbol {
+        self.strings.get_or_intern(s)
+    }
+
+    /// Returns the symbol of the string if it is interned.
+    pub(crate) fn get(&self, s: &str) -> Option<Symbol> {
+        self.strings.get(s)
+    }
+
+    /// Returns the interned string for the symbol.
+    pub(crate) fn resolve(&self, sym: Symbol) -> Option<&str> {
+        self.strings.resolve(sym)
+    }
+
+    /// Returns the estimated heap size.
+    pub(crate) fn heap_size(&self) -> usize {
+        // String contents, the end offset in the backend, and the hash table
+        // entry.
+        let per_string = std::mem::size_of::<usize>() + std::mem::size_of::<Symbol>() + 1;
+        (&self.strings)
+            .into_iter()
+            .map(|(_, s)| s.len() + per_string)
+            .sum()
+    }
+}
+
+#[cfg(not(feature = "symbol"))]
+impl SymbolTable {
+    /// Interns the string and returns the symbol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than `u32::MAX` strings are interned.
+    pub(crate) fn intern(&mut self, s: &str) -> Symbol {
+        if let Some(&sym) = self.symbols.get(s) {
+            return sym;
+        }
+        let sym = u32::try_from(self.strings.len())
+            .map(Symbol)
+            .expect("Too many interned strings");
+        let s: Arc<str> = Arc::from(s);
+        self.strings.push(s.clone());
+        self.symbols.insert(s, sym);
+        sym
+    }
+
+    /// Returns the symbol of the string if it is interned.
+    pub(crate) fn get(&self, s: &str) -> Option<Symbol> {
+        self.symbols.get(s).copied()
+    }
+
+    /// Returns the interned string for the symbol.
+    pub(crate) fn resolve(&self, sym: Symbol) -> Option<&str> {
+        self.strings.get(sym.0 as usize).map(|s| &**s)
+    }
+
+    /// Returns the estimated heap size.
+    pub(crate) fn heap_size(&self) -> usize {
+        // String contents with the reference counts, the pointers in the
+        // vector and the hash table, and the hash table value.
+        let per_string = 2 * std::mem::size_of::<usize>()
+            + 2 * std::mem::size_of::<Arc<str>>()
+            + std::mem::size_of::<Symbol>()
+            + 1;
+        self.strings.iter().map(|s| s.len() + per_string).sum()
+    }
+}
+
+impl SymbolTable {
+    /// Returns the interned string for the symbol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given symbol is not registered in the table.
+    pub(crate) fn resolve_registered(&self, sym: Symbol) -> &str {
+        self.resolve(sym)
+            .unwrap_or_else(|| panic!("Unresolvable symbol: sym={:?}", sym))
+    }
+}
+
+impl Default for SymbolTable {
+    fn default() -> Self {
+        Self {
+            #[cfg(feature = "symbol")]
+            strings: StringInterner::new(),
+            #[cfg(not(feature = "symbol"))]
+            strings: Vec::new(),
+            #[cfg(not(feature = "symbol"))]
+            symbols: HashMap::new(),
+        }
+    }
+}
@@ -0,0 +1,244 @@
+//! FBX version compatibility.
+//!
+//! FBX 7.5 and later use 64-bit node offsets, which are handled by the
+//! `fbxcel` parser and writer.
+//! Other structures of FBX 7.5, 7.6 and 7.7 are compatible with FBX 7.4 as
+//! far as this crate uses.
+//!
+//! FBX versions newer than the known versions are loaded in the same way,
+//! but warnings are emitted since they may be misread.
+//!
+//! Documents can be written in a version different from the source, and
+//! the changes made are reported as [`VersionReport`] (see
+//! `write::Writer::version_report()` with `writer` feature).
+
+use std::{convert::TryFrom, fmt};
+
+use fbxcel::{
+    low::{FbxHeader, FbxVersion},
+    tree::v7400::Tree,
+};
+
+/// Raw numbers of the known FBX 7.x versions.
+const KNOWN_V7_VERSIONS: &[u32] = &[7400, 7500, 7600, 7700];
+
+/// Raw number of the latest known FBX version.
+const LATEST_KNOWN_VERSION: u32 = 7700;
+
+/// Creates the FBX version value from the raw version number (such as
+/// `7700` for FBX 7.7).
+pub fn from_raw(raw: u32) -> FbxVersion {
+    let mut header = b"Kaydara FBX Binary  \0\x1a\0".to_vec();
+    header.extend_from_slice(&raw.to_le_bytes());
+    FbxHeader::load(&header[..])
+        .expect("Should never fail: valid FBX header is created")
+        .version()
+}
+
+/// Returns the raw version number (such as `7700` for FBX 7.7).
+pub fn to_raw(fbx_version: FbxVersion) -> u32 {
+    let (major, minor) = fbx_version.major_minor();
+    major * 1000 + minor * 100
+}
+
+/// Capabilities of an FBX version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    /// FBX version.
+    fbx_version: FbxVersion,
+}
+
+impl Capabilities {
+    /// Creates a new `Capabilities` for the given FBX version.
+    pub fn new(fbx_version: FbxVersion) -> Self {
+        Self { fbx_version }
+    }
+
+    /// Returns the FBX version.
+    pub fn fbx_version(self) -> FbxVersion {
+        self.fbx_version
+    }
+
+    /// Returns true if the version is one of FBX 7.4, 7.5, 7.6 and 7.7.
+    pub fn is_known(self) -> bool {
+        KNOWN_V7_VERSIONS.contains(&to_raw(self.fbx_version))
+    }
+
+    /// Returns true if the version is newer than the known versions.
+    ///
+    /// Files of such versions are loaded as FBX 7.x, but they may be
+    /// misread.
+    pub fn is_newer_than_known(self) -> bool {
+        to_raw(self.fbx_version) > LATEST_KNOWN_VERSION
+    }
+
+    /// Returns true if the version is loaded as FBX 7.x directly.
+    ///
+    /// FBX 6.x files are loaded through the conversion (see
+    /// [`v6100`][`crate::v6100`] module).
+    pub fn is_native(self) -> bool {
+        self.fbx_version.major() == 7
+    }
+
+    /// Returns true if binary files use 64-bit node offsets and lengths.
+    ///
+    /// This is true for FBX 7.5 and later, and allows files larger than
+    /// 4 GiB.
+    pub fn has_64bit_offsets(self) -> bool {
+        to_raw(self.fbx_version) >= 7500
+    }
+
+    /// Returns true if objects have numeric IDs and `Properties70`.
+    ///
+    /// This is false for FBX 6.x, which identifies objects by names and uses
+    /// `Properties60`.
+    pub fn has_object_ids(self) -> bool {
+        self.fbx_version.major() >= 7
+    }
+
+    /// Returns true if the binary writer can write this version.
+    pub fn is_writable(self) -> bool {
+        self.fbx_version.major() == 7
+    }
+}
+
+/// Returns the FBX version recorded in `FBXHeaderExtension`.
+pub(crate) fn header_version(tree: &Tree) -> Option<i32> {
+    tree.root()
+        .first_child_by_name("FBXHeaderExtension")?
+        .first_child_by_name("FBXVersion")?
+        .attributes()
+        .first()?
+        .get_i32()
+}
+
+/// Change made when writing a document in a different FBX version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum VersionChange {
+    /// The version in `FBXHeaderExtension/FBXVersion` is rewritten.
+    HeaderVersion {
+        /// Version in the source header.
+        from: Option<i32>,
+        /// Version written.
+        to: i32,
+    },
+    /// Node offsets and lengths are converted between 32-bit and 64-bit.
+    ///
+    /// Writing fails if the output does not fit in 32-bit offsets.
+    NodeOffsets {
+        /// Whether 64-bit offsets are written.
+        to_64bit: bool,
+    },
+    /// The source is newer than the target.
+    ///
+    /// Nodes and properties introduced after the target version are written
+    /// as is, and may be ignored by readers of the target version.
+    Downgrade,
+    /// The source is FBX 6.x, and written in the converted FBX 7.x
+    /// structure.
+    ConvertedFromV6,
+    /// The target version cannot be written, and writing fails.
+    UnsupportedTarget,
+}
+
+impl fmt::Display for VersionChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VersionChange::HeaderVersion {
+                from: Some(from),
+                to,
+            } => {
+                write!(f, "Header version is rewritten from {} to {}", from, to)
+            }
+            VersionChange::HeaderVersion { from: None, to } => {
+                write!(f, "Header version {} is written", to)
+            }
+            VersionChange::NodeOffsets { to_64bit: true } => {
+                f.write_str("Node offsets are converted from 32-bit to 64-bit")
+            }
+            VersionChange::NodeOffsets { to_64bit: false } => {
+                f.write_str("Node offsets are converted from 64-bit to 32-bit")
+            }
+            VersionChange::Downgrade => {
+                f.write_str("Nodes and properties newer than the target version are written as is")
+            }
+            VersionChange::ConvertedFromV6 => {
+                f.write_str("FBX 6.x source is written in the converted FBX 7.x structure")
+            }
+            VersionChange::UnsupportedTarget => f.write_str("Target version is not writable"),
+        }
+    }
+}
+
+/// Report of changes made when writing a document in a different FBX
+/// version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionReport {
+    /// FBX version of the source.
+    source: Option<FbxVersion>,
+    /// FBX version to write.
+    target: FbxVersion,
+    /// Changes.
+    changes: Vec<VersionChange>,
+}
+
+impl VersionReport {
+    /// Creates a new report for writing the tree in the target version.
+    ///
+    /// `source` is the FBX version of the source file, if available.
+    pub fn new(source: Option<FbxVersion>, tree: &Tree, target: FbxVersion) -> Self {
+        let header_version = header_version(tree);
+        let target_caps = Capabilities::new(target);
+        let target_raw = i32::try_from(to_raw(target)).unwrap_or(i32::MAX);
+        let mut changes = Vec::new();
+        if !target_caps.is_writable() {
+            changes.push(VersionChange::UnsupportedTarget);
+        }
+        if let Some(source) = source {
+            let source_caps = Capabilities::new(source);
+            if !source_caps.has_object_ids() {
+                changes.push(VersionChange::ConvertedFromV6);
+            } else if source_caps.has_64bit_offsets() != target_caps.has_64bit_offsets() {
+                changes.push(VersionChange::NodeOffsets {
+                    to_64bit: target_caps.has_64bit_offsets(),
+                });
+            }
+            if source_caps.has_object_ids() && to_raw(source) > to_raw(target) {
+                changes.push(VersionChange::Downgrade);
+            }
+        }
+        if header_version != Some(target_raw) {
+            changes.push(VersionChange::HeaderVersion {
+                from: header_version,
+                to: target_raw,
+            });
+        }
+
+        Self {
+            source,
+            target,
+            changes,
+        }
+    }
+
+    /// Returns the FBX version of the source, if available.
+    pub fn source(&self) -> Option<FbxVersion> {
+        self.source
+    }
+
+    /// Returns the FBX version to write.
+    pub fn target(&self) -> FbxVersion {
+        self.target
+    }
+
+    /// Returns the changes.
+    pub fn changes(&self) -> &[VersionChange] {
+        &self.changes
+    }
+
+    /// Returns true if the document is written without changes.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
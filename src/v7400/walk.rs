@@ -0,0 +1,278 @@
+//! Object graph walker.
+//!
+//! [`Document::walk`] visits the objects in depth-first order, calling
+//! [`Visitor::enter`] before and [`Visitor::leave`] after the connected
+//! objects.
+//!
+//! By default, the walk starts from the objects which are not connected to
+//! other objects (such as root models, which are connected only to the root
+//! object `0`), and follows the connections to the source objects (from a
+//! model to the child models, geometries, and materials).
+//! Each object is entered at most once, so the walk terminates even for
+//! cyclic graphs; objects shared by multiple destinations (such as
+//! materials) are entered from the first destination only.
+//!
+//! ```
+//! # #[cfg(feature = "edit")]
+//! # fn main() -> Result<(), anyhow::Error> {
+//! use fbxcel_dom::v7400::{
+//!     edit::{DocumentBuilder, ModelKind},
+//!     walk::{WalkControl, WalkStep},
+//! };
+//!
+//! let mut builder = DocumentBuilder::new();
+//! let root = builder.add_model("Root", ModelKind::Null, None);
+//! let child = builder.add_model("Child", ModelKind::Null, Some(root));
+//! builder.add_model("Grandchild", ModelKind::Null, Some(child));
+//! let doc = builder.build()?;
+//!
+//! let mut names = Vec::new();
+//! doc.walk(&mut |step: &WalkStep<'_>| {
+//!     if step.object.class() == "Model" {
+//!         names.push((step.depth, step.object.name().unwrap_or_default().to_owned()));
+//!     }
+//!     WalkControl::Continue
+//! });
+//! assert_eq!(
+//!     names,
+//!     [(0, "Root".to_owned()), (1, "Child".to_owned()), (2, "Grandchild".to_owned())]
+//! );
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "edit"))]
+//! # fn main() {}
+//! ```
+//!
+//! [`Document::walk`]: ../struct.Document.html#method.walk
+//! [`Visitor::enter`]: trait.Visitor.html#method.enter
+//! [`Visitor::leave`]: trait.Visitor.html#method.leave
+
+use std::collections::HashSet;
+
+use crate::v7400::{
+    object::{ObjectHandle, ObjectId},
+    Document,
+};
+
+/// Default maximum depth of a walk.
+pub const DEFAULT_MAX_DEPTH: usize = 64;
+
+/// Direction of the connections to follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WalkDirection {
+    /// From destination objects to source objects (such as from a parent
+    /// model to the child models).
+    Sources,
+    /// From source objects to destination objects (such as from a child
+    /// model to the parent model).
+    Destinations,
+}
+
+/// What to do after entering an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WalkControl {
+    /// Continue into the connected objects.
+    Continue,
+    /// Skip the connected objects, but continue the walk.
+    ///
+    /// `leave` is still called for the object.
+    SkipChildren,
+    /// Stop the walk immediately.
+    ///
+    /// `leave` is not called for the objects being walked.
+    Stop,
+}
+
+/// Object being walked.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct WalkStep<'a> {
+    /// Object.
+    pub object: ObjectHandle<'a>,
+    /// Object the walk came from, or `None` for start objects.
+    pub parent: Option<ObjectHandle<'a>>,
+    /// Label of the connection from the parent, if available.
+    ///
+    /// For object-property connections, this is the property name.
+    pub label: Option<&'a str>,
+    /// Depth, zero for start objects.
+    pub depth: usize,
+}
+
+/// Visitor of an object graph walk.
+///
+/// Closures taking `&WalkStep` and returning `WalkControl` can be used as
+/// visitors without `leave` callbacks.
+pub trait Visitor<'a> {
+    /// Called when the walk enters the object.
+    fn enter(&mut self, step: &WalkStep<'a>) -> WalkControl;
+
+    /// Called when the walk leaves the object, after the connected objects.
+    fn leave(&mut self, _step: &WalkStep<'a>) {}
+}
+
+impl<'a, F> Visitor<'a> for F
+where
+    F: FnMut(&WalkStep<'a>) -> WalkControl,
+{
+    fn enter(&mut self, step: &WalkStep<'a>) -> WalkControl {
+        self(step)
+    }
+}
+
+/// Options of an object graph walk.
+#[derive(Debug, Clone)]
+pub struct WalkOptions {
+    /// Direction of the connections to follow.
+    direction: WalkDirection,
+    /// Maximum depth.
+    max_depth: usize,
+    /// Start objects.
+    roots: Option<Vec<ObjectId>>,
+}
+
+impl WalkOptions {
+    /// Creates a new options with the default values.
+    ///
+    /// The walk follows the connections to the sources, up to
+    /// [`DEFAULT_MAX_DEPTH`](constant.DEFAULT_MAX_DEPTH.html).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the direction of the connections to follow.
+    ///
+    /// With `Destinations`, the default start objects are the objects which
+    /// have no source objects.
+    pub fn direction(mut self, direction: WalkDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Sets the maximum depth.
+    ///
+    /// Objects deeper than this are not entered.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets the start objects.
+    ///
+    /// If set, only the objects reachable from them are walked.
+    /// Object IDs not found in the document are ignored.
+    pub fn roots(mut self, roots: impl IntoIterator<Item = ObjectId>) -> Self {
+        self.roots = Some(roots.into_iter().collect());
+        self
+    }
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            direction: WalkDirection::Sources,
+            max_depth: DEFAULT_MAX_DEPTH,
+            roots: None,
+        }
+    }
+}
+
+/// Walks the document.
+pub(crate) fn walk<'a>(doc: &'a Document, options: &WalkOptions, visitor: &mut impl Visitor<'a>) {
+    let mut walker = Walker {
+        options,
+        visited: HashSet::new(),
+        found: HashSet::new(),
+    };
+
+    if let Some(roots) = &options.roots {
+        for obj in roots.iter().filter_map(|id| id.to_object_handle(doc)) {
+            if !walker.walk(obj, None, None, 0, visitor) {
+                return;
+            }
+        }
+        return;
+    }
+
+    let roots = doc.objects().filter(|obj| match options.direction {
+        WalkDirection::Sources => !obj
+            .destination_objects()
+            .any(|conn| conn.object_handle().is_some()),
+        WalkDirection::Destinations => !obj
+            .source_objects()
+            .any(|conn| conn.object_handle().is_some()),
+    });
+    for obj in roots {
+        if !walker.walk(obj, None, None, 0, visitor) {
+            return;
+        }
+    }
+    // Objects only reachable from cycles.
+    for obj in doc.objects() {
+        if walker.found.contains(&obj.object_id()) {
+            continue;
+        }
+        if !walker.walk(obj, None, None, 0, visitor) {
+            return;
+        }
+    }
+}
+
+/// Walk state.
+struct Walker<'o> {
+    /// Options.
+    options: &'o WalkOptions,
+    /// Entered objects.
+    visited: HashSet<ObjectId>,
+    /// Entered or encountered objects.
+    found: HashSet<ObjectId>,
+}
+
+impl Walker<'_> {
+    /// Walks the object, and returns `false` if the walk is stopped.
+    fn walk<'a>(
+        &mut self,
+        object: ObjectHandle<'a>,
+        parent: Option<ObjectHandle<'a>>,
+        label: Option<&'a str>,
+        depth: usize,
+        visitor: &mut impl Visitor<'a>,
+    ) -> bool {
+        self.found.insert(object.object_id());
+        if depth > self.options.max_depth || !self.visited.insert(object.object_id()) {
+            return true;
+        }
+
+        let step = WalkStep {
+            object,
+            parent,
+            label,
+            depth,
+        };
+        match visitor.enter(&step) {
+            WalkControl::Continue => {}
+            WalkControl::SkipChildren => {
+                visitor.leave(&step);
+                return true;
+            }
+            WalkControl::Stop => return false,
+        }
+
+        let children: Vec<_> = match self.options.direction {
+            WalkDirection::Sources => object.source_objects().collect(),
+            WalkDirection::Destinations => object.destination_objects().collect(),
+        };
+        for conn in children {
+            let child = match conn.object_handle() {
+                Some(v) => v,
+                None => continue,
+            };
+            if !self.walk(child, Some(object), conn.label(), depth + 1, visitor) {
+                return false;
+            }
+        }
+
+        visitor.leave(&step);
+        true
+    }
+}
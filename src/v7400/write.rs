@@ -0,0 +1,408 @@
+//! FBX binary writer for FBX 7.4 and 7.5.
+//!
+//! This serializes a [`Document`] (or a raw FBX data tree) back to binary
+//! FBX.
+//! Node end offsets, null-record terminators, the 64-bit offsets of FBX 7.5
+//! and the footer are handled by `fbxcel`'s low-level writer.
+//!
+//! To modify a document before saving, take the tree with
+//! [`Document::into_tree()`], edit it with `fbxcel::tree::v7400::Tree` APIs,
+//! and write it with [`Writer::write_tree()`] (or reload it with
+//! [`Loader::load_from_tree()`][`crate::v7400::Loader::load_from_tree`]).
+//!
+//! # Round-trip preservation
+//!
+//! The document keeps the whole FBX data tree including nodes, properties
+//! and connections not recognized by this crate, and
+//! [`edit::DocumentMut`][`crate::v7400::edit::DocumentMut`] edits only the
+//! nodes it is asked to.
+//! [`Writer::preserving()`] additionally reuses the FBX version and the
+//! footer of the source file, so untouched data is written back as is.
+//! Array attributes may be compressed differently from the source, because
+//! the tree does not record the original array encodings.
+//!
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use std::io::Cursor;
+//!
+//! use fbxcel_dom::{
+//!     fbxcel::low::FbxVersion,
+//!     v7400::{
+//!         data::material::ShadingModel,
+//!         edit::{DocumentBuilder, ModelKind},
+//!         object::{
+//!             property::{OwnedPropertyValue, PropertyValue},
+//!             ConnectionKind, ObjectId,
+//!         },
+//!         write::Writer,
+//!         Document,
+//!     },
+//! };
+//!
+//! fn objects(doc: &Document) -> Vec<(ObjectId, Option<&str>, &str, &str)> {
+//!     doc.objects()
+//!         .map(|obj| (obj.object_id(), obj.name(), obj.class(), obj.subclass()))
+//!         .collect()
+//! }
+//! fn connections(doc: &Document) -> Vec<(ObjectId, ObjectId, ConnectionKind, Option<&str>)> {
+//!     doc.connections()
+//!         .map(|conn| (conn.source_id(), conn.destination_id(), conn.kind(), conn.label()))
+//!         .collect()
+//! }
+//! fn properties(doc: &Document) -> Vec<(ObjectId, &str, PropertyValue<'_>)> {
+//!     doc.iter_all_properties()
+//!         .map(|entry| entry.expect("should be readable"))
+//!         .map(|entry| (entry.object.object_id(), entry.name, entry.value))
+//!         .collect()
+//! }
+//!
+//! let mut builder = DocumentBuilder::new();
+//! let root = builder.add_model("Root", ModelKind::Null, None);
+//! let quad = builder.add_model("Quad", ModelKind::Mesh, Some(root));
+//! builder.set_local_transform(quad, [1.0, 2.0, 3.0], [0.0, 90.0, 0.0], [1.0; 3])?;
+//! let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+//! builder.add_mesh("Quad", &positions, &[&[0, 1, 2, 3]], Some(quad));
+//! builder.add_material("Red", ShadingModel::Lambert, [1.0, 0.0, 0.0], Some(quad))?;
+//! let tag = OwnedPropertyValue::String("prop".to_owned());
+//! builder.edit_mut().insert_property(root, "Tag", "KString", "", "U", tag)?;
+//! let doc = builder.build()?;
+//!
+//! let data = Writer::new(FbxVersion::V7_4)
+//!     .write_document(&doc, Cursor::new(Vec::new()))?
+//!     .into_inner();
+//! let reloaded = Document::loader().load_from_slice(&data)?;
+//!
+//! assert_eq!(objects(&reloaded), objects(&doc));
+//! assert_eq!(connections(&reloaded), connections(&doc));
+//! assert_eq!(properties(&reloaded), properties(&doc));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Version conversion
+//!
+//! Documents are written in the FBX version of the [`Writer`], which can
+//! differ from the source (for example, FBX 7.7 source written as FBX 7.4).
+//! The version in `FBXHeaderExtension/FBXVersion` is rewritten, and the node
+//! offsets are written in the width of the target version.
+//! [`Writer::version_report()`] reports the changes made.
+//!
+//! # ASCII FBX
+//!
+//! With `ascii` feature, [`Writer`] can also write ASCII FBX (see
+//! `Writer::write_ascii_tree()`).
+
+use std::{
+    borrow::Cow,
+    convert::TryFrom,
+    io::{Seek, Write},
+};
+
+use fbxcel::{
+    low::{
+        v7400::{ArrayAttributeEncoding, AttributeValue, FbxFooter as SourceFooter},
+        FbxVersion,
+    },
+    tree::v7400::{NodeHandle, Tree},
+    writer::v7400::binary::{AttributesWriter, FbxFooter, Writer as LowWriter},
+};
+use log::trace;
+
+use crate::v7400::{
+    edit::{definitions::build_definitions, TemplateSource},
+    version::{to_raw, Capabilities, VersionReport},
+    Document,
+};
+
+pub use fbxcel::writer::v7400::binary::Error as WriteError;
+
+/// Default minimum number of array elements to compress.
+const DEFAULT_COMPRESSION_THRESHOLD: usize = 16;
+
+/// FBX binary writer.
+#[derive(Debug, Clone, Copy)]
+pub struct Writer {
+    /// FBX version to write.
+    fbx_version: FbxVersion,
+    /// Minimum number of array elements to compress, or `None` to disable
+    /// compression.
+    compression_threshold: Option<usize>,
+    /// Footer of the source file to reproduce.
+    footer: Option<SourceFooter>,
+    /// Whether to regenerate `Definitions` node on write.
+    regenerate_definitions: bool,
+}
+
+impl Writer {
+    /// Creates a new `Writer` for the given FBX version.
+    ///
+    /// Use `FbxVersion::V7_5` to write files larger than 4 GiB.
+    pub fn new(fbx_version: FbxVersion) -> Self {
+        Self {
+            fbx_version,
+            compression_threshold: Some(DEFAULT_COMPRESSION_THRESHOLD),
+            footer: None,
+            regenerate_definitions: false,
+        }
+    }
+
+    /// Creates a new `Writer` which reproduces the source file of the
+    /// document.
+    ///
+    /// The FBX version and the footer of the source file are used if
+    /// available (see [`Document::footer()`]), and FBX 7.4 with the default
+    /// footer otherwise.
+    /// FBX 7.4 is also used if the source version is not writable (such as
+    /// FBX 6.x).
+    pub fn preserving(doc: &Document) -> Self {
+        let fbx_version = doc
+            .capabilities()
+            .filter(|capabilities| capabilities.is_writable())
+            .map_or(FbxVersion::V7_4, Capabilities::fbx_version);
+        let writer = Self::new(fbx_version);
+        match doc.footer() {
+            Some(footer) => writer.footer(*footer),
+            None => writer,
+        }
+    }
+
+    /// Sets the footer fields to write.
+    ///
+    /// The padding length is always recomputed, since the layout of the
+    /// output can differ from the source.
+    pub fn footer(self, footer: SourceFooter) -> Self {
+        Self {
+            footer: Some(footer),
+            ..self
+        }
+    }
+
+    /// Sets the minimum number of array elements to compress with zlib.
+    ///
+    /// `None` disables array compression.
+    pub fn array_compression(self, threshold: Option<usize>) -> Self {
+        Self {
+            compression_threshold: threshold,
+            ..self
+        }
+    }
+
+    /// Sets whether to regenerate `Definitions` node on write.
+    ///
+    /// If enabled, object counts are recomputed from the objects in the tree,
+    /// and the existing property templates are kept for the object types in
+    /// use.
+    /// To copy templates from another document, use
+    /// [`DocumentMut::update_definitions()`][`crate::v7400::edit::DocumentMut::update_definitions`]
+    /// before writing.
+    pub fn regenerate_definitions(self, regenerate: bool) -> Self {
+        Self {
+            regenerate_definitions: regenerate,
+            ..self
+        }
+    }
+
+    /// Returns the report of changes made by writing the document in the
+    /// FBX version of the writer.
+    ///
+    /// The version in `FBXHeaderExtension/FBXVersion` is always rewritten to
+    /// the version of the writer.
+    pub fn version_report(&self, doc: &Document) -> VersionReport {
+        VersionReport::new(doc.fbx_version(), doc.tree(), self.fbx_version)
+    }
+
+    /// Writes the document to the given sink, and returns the sink.
+    ///
+    /// Compressed arrays not decoded yet (see
+    /// [`Loader::lazy_arrays()`][`crate::v7400::Loader::lazy_arrays`]) are
+    /// decoded for writing.
+    pub fn write_document<W: Write + Seek>(
+        &self,
+        doc: &Document,
+        sink: W,
+    ) -> Result<W, WriteError> {
+        let tree = doc
+            .preloaded_tree()
+            .map_err(|e| WriteError::UserDefined(e.into()))?;
+        self.write_tree(&tree, sink)
+    }
+
+    /// Writes the tree to the given sink, and returns the sink.
+    pub fn write_tree<W: Write + Seek>(&self, tree: &Tree, sink: W) -> Result<W, WriteError> {
+        trace!("Writing FBX binary: version={:?}", self.fbx_version);
+        let mut writer = LowWriter::new(sink, self.fbx_version)?;
+        let tree = &*self.target_tree(tree);
+        let definitions = self.generated_definitions(tree);
+        for node in self.toplevel_nodes(tree, definitions.as_ref()) {
+            self.write_node(&mut writer, node)?;
+        }
+        let footer = match &self.footer {
+            Some(footer) => FbxFooter {
+                unknown1: Some(&footer.unknown1),
+                padding_len: Default::default(),
+                unknown2: Some(footer.unknown2),
+                unknown3: Some(&footer.unknown3),
+            },
+            None => FbxFooter::default(),
+        };
+        let sink = writer.finalize_and_flush(&footer)?;
+        trace!("Wrote FBX binary successfully");
+
+        Ok(sink)
+    }
+
+    /// Writes the document to the given sink as ASCII FBX, and returns the
+    /// sink.
+    ///
+    /// Array compression and the footer settings are not used.
+    #[cfg(feature = "ascii")]
+    pub fn write_ascii_document<W: Write>(
+        &self,
+        doc: &Document,
+        sink: W,
+    ) -> Result<W, crate::v7400::ascii::Error> {
+        let tree = doc
+            .preloaded_tree()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+        self.write_ascii_tree(&tree, sink)
+    }
+
+    /// Writes the tree to the given sink as ASCII FBX, and returns the sink.
+    ///
+    /// Array compression and the footer settings are not used.
+    #[cfg(feature = "ascii")]
+    pub fn write_ascii_tree<W: Write>(
+        &self,
+        tree: &Tree,
+        sink: W,
+    ) -> Result<W, crate::v7400::ascii::Error> {
+        trace!("Writing FBX ASCII: version={:?}", self.fbx_version);
+        let tree = &*self.target_tree(tree);
+        let definitions = self.generated_definitions(tree);
+        let nodes = self.toplevel_nodes(tree, definitions.as_ref());
+        let sink = crate::v7400::ascii::write_nodes(nodes, self.fbx_version.major_minor(), sink)?;
+        trace!("Wrote FBX ASCII successfully");
+
+        Ok(sink)
+    }
+
+    /// Returns the tree with the header version of the writer.
+    ///
+    /// The tree is cloned only if the header version differs.
+    fn target_tree<'a>(&self, tree: &'a Tree) -> Cow<'a, Tree> {
+        let target = i32::try_from(to_raw(self.fbx_version)).unwrap_or(i32::MAX);
+        let node = tree
+            .root()
+            .first_child_by_name("FBXHeaderExtension")
+            .and_then(|header| header.first_child_by_name("FBXVersion"));
+        let node = match node {
+            Some(v) if v.attributes().first() != Some(&AttributeValue::I32(target)) => v.node_id(),
+            _ => return Cow::Borrowed(tree),
+        };
+        let mut tree = tree.clone();
+        tree.set_attributes_vec(node, vec![AttributeValue::I32(target)]);
+        Cow::Owned(tree)
+    }
+
+    /// Returns the tree with the regenerated `Definitions` node if enabled.
+    fn generated_definitions(&self, tree: &Tree) -> Option<Tree> {
+        if !self.regenerate_definitions {
+            return None;
+        }
+        let mut definitions = Tree::default();
+        let root = definitions.root().node_id();
+        build_definitions(tree, TemplateSource::Existing, &mut definitions, root);
+        Some(definitions)
+    }
+
+    /// Returns the toplevel nodes to write, substituting the regenerated
+    /// `Definitions` node.
+    fn toplevel_nodes<'a>(
+        &self,
+        tree: &'a Tree,
+        definitions: Option<&'a Tree>,
+    ) -> Vec<NodeHandle<'a>> {
+        let definitions =
+            definitions.and_then(|tree| tree.root().first_child_by_name("Definitions"));
+        let has_definitions = tree.root().first_child_by_name("Definitions").is_some();
+        let mut nodes = Vec::new();
+        for node in tree.root().children() {
+            match definitions {
+                Some(definitions) if node.name() == "Definitions" => {
+                    nodes.push(definitions);
+                    continue;
+                }
+                Some(definitions) if node.name() == "Objects" && !has_definitions => {
+                    nodes.push(definitions);
+                }
+                _ => {}
+            }
+            nodes.push(node);
+        }
+        nodes
+    }
+
+    /// Writes the node and its descendants.
+    fn write_node<W: Write + Seek>(
+        &self,
+        writer: &mut LowWriter<W>,
+        node: NodeHandle<'_>,
+    ) -> Result<(), WriteError> {
+        let mut attrs_writer = writer.new_node(node.name())?;
+        for attr in node.attributes() {
+            self.write_attribute(&mut attrs_writer, attr)?;
+        }
+        for child in node.children() {
+            self.write_node(writer, child)?;
+        }
+        writer.close_node()
+    }
+
+    /// Writes the attribute.
+    fn write_attribute<W: Write + Seek>(
+        &self,
+        writer: &mut AttributesWriter<'_, W>,
+        attr: &AttributeValue,
+    ) -> Result<(), WriteError> {
+        match attr {
+            AttributeValue::Bool(v) => writer.append_bool(*v),
+            AttributeValue::I16(v) => writer.append_i16(*v),
+            AttributeValue::I32(v) => writer.append_i32(*v),
+            AttributeValue::I64(v) => writer.append_i64(*v),
+            AttributeValue::F32(v) => writer.append_f32(*v),
+            AttributeValue::F64(v) => writer.append_f64(*v),
+            AttributeValue::ArrBool(v) => {
+                writer.append_arr_bool_from_iter(self.encoding(v.len()), v.iter().cloned())
+            }
+            AttributeValue::ArrI32(v) => {
+                writer.append_arr_i32_from_iter(self.encoding(v.len()), v.iter().cloned())
+            }
+            AttributeValue::ArrI64(v) => {
+                writer.append_arr_i64_from_iter(self.encoding(v.len()), v.iter().cloned())
+            }
+            AttributeValue::ArrF32(v) => {
+                writer.append_arr_f32_from_iter(self.encoding(v.len()), v.iter().cloned())
+            }
+            AttributeValue::ArrF64(v) => {
+                writer.append_arr_f64_from_iter(self.encoding(v.len()), v.iter().cloned())
+            }
+            AttributeValue::Binary(v) => writer.append_binary_direct(v),
+            AttributeValue::String(v) => writer.append_string_direct(v),
+        }
+    }
+
+    /// Returns the encoding for an array with the given length.
+    fn encoding(&self, len: usize) -> ArrayAttributeEncoding {
+        match self.compression_threshold {
+            Some(threshold) if len >= threshold => ArrayAttributeEncoding::Zlib,
+            _ => ArrayAttributeEncoding::Direct,
+        }
+    }
+}
+
+impl Default for Writer {
+    /// Creates a writer for FBX 7.4.
+    fn default() -> Self {
+        Self::new(FbxVersion::V7_4)
+    }
+}